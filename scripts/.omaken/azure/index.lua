@@ -1,25 +1,16 @@
 -- Omakure Azure widget (hello world example).
 -- This widget prints the logged-in user and active subscription via Azure CLI.
+-- It needs `commands = ["az"]` under [widgets] in omakure.toml.
 
-local function run(cmd)
-  local pipe = io.popen(cmd)
-  if not pipe then
-    return nil
-  end
-  local out = pipe:read("*a") or ""
-  pipe:close()
-  return out
+-- Runs an `az` command given as a list of arguments; returns its stdout and
+-- whether it succeeded.
+local function run_status(args)
+  local out = omakure.exec(args, 15)
+  return out or "", out ~= nil
 end
 
-local function run_status(cmd)
-  local pipe = io.popen(cmd .. " 2>/dev/null")
-  if not pipe then
-    return "", false
-  end
-  local out = pipe:read("*a") or ""
-  local ok, _, status = pipe:close()
-  local success = ok == true or status == 0
-  return out, success
+local function run(args)
+  return (omakure.exec(args, 15))
 end
 
 local function split_tsv(line)
@@ -48,8 +39,8 @@ local function extract_domain(user)
   return "<unknown>"
 end
 
-local function read_tsv(cmd)
-  local out, ok = run_status(cmd)
+local function read_tsv(args)
+  local out, ok = run_status(args)
   if not ok then
     return ""
   end
@@ -85,7 +76,7 @@ local function base64_decode(data)
 end
 
 local function extract_oid_from_token()
-  local token = read_tsv('az account get-access-token --query accessToken -o tsv')
+  local token = read_tsv({ "az", "account", "get-access-token", "--query", "accessToken", "-o", "tsv" })
   if token == "" then
     return ""
   end
@@ -108,13 +99,13 @@ end
 
 local function lookup_assignee_id(user, account_type)
   if account_type == "user" then
-    local assignee_id = read_tsv('az ad signed-in-user show --query id -o tsv')
+    local assignee_id = read_tsv({ "az", "ad", "signed-in-user", "show", "--query", "id", "-o", "tsv" })
     if assignee_id ~= "" then
       return assignee_id
     end
   end
   if account_type == "servicePrincipal" then
-    local assignee_id = read_tsv('az ad sp show --id "' .. user .. '" --query id -o tsv')
+    local assignee_id = read_tsv({ "az", "ad", "sp", "show", "--id", user, "--query", "id", "-o", "tsv" })
     if assignee_id ~= "" then
       return assignee_id
     end
@@ -144,8 +135,8 @@ end
 
 local function first_non_empty(cmds)
   local had_success = false
-  for _, cmd in ipairs(cmds) do
-    local out, ok = run_status(cmd)
+  for _, args in ipairs(cmds) do
+    local out, ok = run_status(args)
     if ok then
       had_success = true
       local trimmed = trim(out)
@@ -162,9 +153,11 @@ end
 
 local title = "Azure"
 
-local output = run(
-  'az account show --only-show-errors --query "[tenantId, user.name, user.type, name, id, state, environmentName]" -o tsv'
-)
+local output = run({
+  "az", "account", "show", "--only-show-errors",
+  "--query", "[tenantId, user.name, user.type, name, id, state, environmentName]",
+  "-o", "tsv",
+})
 if not output or output:gsub("%s+", "") == "" then
   return {
     title = title,
@@ -185,9 +178,9 @@ local cloud_env = parts[7] or "<unknown>"
 local domain = extract_domain(user)
 
 local default_location = "<not set>"
-local location_output = read_tsv(
-  'az configure -l --query "[?name==\'location\'].value | [0]" -o tsv'
-)
+local location_output = read_tsv({
+  "az", "configure", "-l", "--query", "[?name=='location'].value | [0]", "-o", "tsv",
+})
 if location_output ~= "" then
   default_location = location_output
 end
@@ -202,22 +195,32 @@ if user ~= "<unknown>" and sub_id ~= "<unknown>" then
     assignee_value = assignee_id
   end
 
-  local base = 'az role assignment list --only-show-errors '
-    .. assignee_flag .. ' "' .. assignee_value .. '" '
-    .. '--scope "/subscriptions/' .. sub_id .. '" '
-    .. '--all --query "[].roleDefinitionName" -o tsv'
+  local function role_list(extra)
+    local args = {
+      "az", "role", "assignment", "list", "--only-show-errors",
+      assignee_flag, assignee_value,
+      "--scope", "/subscriptions/" .. sub_id,
+      "--all", "--query", "[].roleDefinitionName", "-o", "tsv",
+    }
+    for _, arg in ipairs(extra) do
+      table.insert(args, arg)
+    end
+    return args
+  end
   local role_output, role_ok = first_non_empty({
-    base .. ' --include-groups --include-inherited',
-    base .. ' --include-inherited',
-    base
+    role_list({ "--include-groups", "--include-inherited" }),
+    role_list({ "--include-inherited" }),
+    role_list({})
   })
 
   if role_ok and role_output == "" and assignee_id ~= "" then
-    local fallback_cmd = "az role assignment list --only-show-errors "
-      .. '--scope "/subscriptions/' .. sub_id .. '" '
-      .. '--include-inherited --all '
-      .. '--query "[?principalId==\'' .. assignee_id .. '\'].roleDefinitionName" -o tsv'
-    local fallback_output = read_tsv(fallback_cmd)
+    local fallback_output = read_tsv({
+      "az", "role", "assignment", "list", "--only-show-errors",
+      "--scope", "/subscriptions/" .. sub_id,
+      "--include-inherited", "--all",
+      "--query", "[?principalId=='" .. assignee_id .. "'].roleDefinitionName",
+      "-o", "tsv",
+    })
     if fallback_output ~= "" then
       role_output = fallback_output
       role_ok = true
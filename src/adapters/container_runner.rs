@@ -0,0 +1,201 @@
+use crate::adapters::script_runner::execute;
+use crate::adapters::system_checks::container_engine;
+use crate::error::{AppError, AppResult, ScriptError};
+use crate::ports::{ContainerOptions, RunOptions, ScriptRunOutput, ScriptRunner};
+use crate::runtime::{custom_extension, script_args, script_kind, ScriptKind};
+use std::path::{self, Path};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Where the script's folder is mounted, read-only, in the container; also
+/// the working directory.
+const CONTAINER_DIR: &str = "/omakure";
+
+/// Runs scripts in the container their schema's `Runtime` names, with Docker
+/// or Podman, so the image supplies the interpreter and tools.
+pub struct ContainerScriptRunner;
+
+impl ContainerScriptRunner {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ScriptRunner for ContainerScriptRunner {
+    fn run(
+        &self,
+        script: &Path,
+        args: &[String],
+        options: &RunOptions,
+    ) -> AppResult<ScriptRunOutput> {
+        let container = container_options(options)?;
+        let engine = container_engine(container.engine.as_deref())?;
+        let dir = script.parent().unwrap_or(Path::new("."));
+        let name = container_name();
+        let mut command = container_command(&engine, container, &name, dir, &options.env)?;
+        command.args(script_command(script, args)?);
+        execute_and_clean_up(&engine, &name, command, options)
+    }
+
+    fn run_shell(
+        &self,
+        line: &str,
+        dir: &Path,
+        options: &RunOptions,
+    ) -> AppResult<ScriptRunOutput> {
+        let container = container_options(options)?;
+        let engine = container_engine(container.engine.as_deref())?;
+        let name = container_name();
+        let mut command = container_command(&engine, container, &name, dir, &options.env)?;
+        command.args(["sh", "-c", line]);
+        execute_and_clean_up(&engine, &name, command, options)
+    }
+}
+
+/// Runs `command`, then removes the container `name` when the engine did not
+/// exit on its own. Killing the engine's client, as a timeout does, leaves the
+/// container running, and `--rm` never fires.
+fn execute_and_clean_up(
+    engine: &str,
+    name: &str,
+    command: Command,
+    options: &RunOptions,
+) -> AppResult<ScriptRunOutput> {
+    let result = execute(command, options);
+    if !matches!(&result, Ok(output) if output.exit_code.is_some()) {
+        let _ = Command::new(engine)
+            .args(["rm", "-f", name])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+    result
+}
+
+/// Unique name for one run's container, so it can be removed by name.
+fn container_name() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    format!("omakure-{}-{}", std::process::id(), nanos)
+}
+
+fn container_options(options: &RunOptions) -> AppResult<&ContainerOptions> {
+    match &options.container {
+        Some(container) if !container.image.trim().is_empty() => Ok(container),
+        Some(_) => Err(AppError::General("Runtime needs an Image".to_string())),
+        None => Err(AppError::General(
+            "The script has no Runtime to run in".to_string(),
+        )),
+    }
+}
+
+/// `<engine> run` for `container`, up to and including the image, with `dir`
+/// mounted at `CONTAINER_DIR` and the container called `name`.
+///
+/// Only the names of `env` go on the command line; the engine reads the
+/// values from its own environment, where the runner sets them.
+fn container_command(
+    engine: &str,
+    container: &ContainerOptions,
+    name: &str,
+    dir: &Path,
+    env: &[(String, String)],
+) -> AppResult<Command> {
+    let dir = path::absolute(dir)?;
+    let mut command = Command::new(engine);
+    command
+        .args(["run", "--rm", "--init", "--name", name, "--mount"])
+        .arg(format!(
+            "type=bind,source={},target={},readonly",
+            dir.display(),
+            CONTAINER_DIR
+        ))
+        .args(["--workdir", CONTAINER_DIR]);
+    for (name, _) in env {
+        command.arg("--env").arg(name);
+    }
+    command.arg(&container.image);
+    Ok(command)
+}
+
+/// Interpreter, script path, and arguments as run inside the container. The
+/// image's own `bash`, `python3`, or `pwsh` is used; `[runner.interpreters]`
+/// name programs on this machine and do not apply.
+fn script_command(script: &Path, args: &[String]) -> Result<Vec<String>, ScriptError> {
+    let kind = script_kind(script).ok_or(ScriptError::UnsupportedType)?;
+    let mut command: Vec<String> = match kind {
+        ScriptKind::Bash => vec!["bash".to_string()],
+        ScriptKind::Python => vec!["python3".to_string()],
+        ScriptKind::PowerShell => ["pwsh", "-NoProfile", "-File"]
+            .iter()
+            .map(|part| part.to_string())
+            .collect(),
+        ScriptKind::Custom => {
+            custom_extension(script)
+                .ok_or(ScriptError::UnsupportedType)?
+                .command
+        }
+        _ => return Err(ScriptError::UnsupportedType),
+    };
+    let file_name = script
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or(ScriptError::UnsupportedType)?;
+    command.push(format!("{}/{}", CONTAINER_DIR, file_name));
+    command.extend(script_args(script, kind, args));
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_command() {
+        let container = ContainerOptions {
+            image: "mcr.microsoft.com/azure-cli".to_string(),
+            engine: None,
+        };
+        let env = vec![("TOKEN".to_string(), "secret".to_string())];
+        let mut command = container_command(
+            "podman",
+            &container,
+            "omakure-1",
+            Path::new("/ws/azure"),
+            &env,
+        )
+        .unwrap();
+        let args = ["--group".to_string(), "rg-prod".to_string()];
+        command.args(script_command(Path::new("/ws/azure/login.bash"), &args).unwrap());
+        let parts: Vec<String> = command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(command.get_program(), "podman");
+        assert_eq!(
+            parts,
+            vec![
+                "run",
+                "--rm",
+                "--init",
+                "--name",
+                "omakure-1",
+                "--mount",
+                "type=bind,source=/ws/azure,target=/omakure,readonly",
+                "--workdir",
+                "/omakure",
+                "--env",
+                "TOKEN",
+                "mcr.microsoft.com/azure-cli",
+                "bash",
+                "/omakure/login.bash",
+                "--group",
+                "rg-prod",
+            ]
+        );
+        assert!(script_command(Path::new("/ws/ops/Makefile#deploy"), &[]).is_err());
+    }
+}
@@ -2,9 +2,10 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::env_crypto::{is_encrypted, read_env_file};
 use crate::error::{AppResult, EnvironmentError};
 pub use crate::ports::{EnvFile, EnvironmentConfig};
-use crate::ports::{EnvPreview, EnvironmentRepository};
+use crate::ports::{EnvPreview, EnvVars, EnvironmentRepository};
 use crate::util::{read_dir_or_empty, read_file_if_exists};
 
 pub struct FsEnvironmentRepository {
@@ -19,14 +20,20 @@ impl FsEnvironmentRepository {
     }
 
     fn read_env_defaults(&self, path: &Path) -> AppResult<HashMap<String, String>> {
-        let contents = fs::read_to_string(path).map_err(|err| {
+        let contents = read_env_file(path).map_err(|err| {
             EnvironmentError::ReadFailed(format!(
                 "Failed to read environment file {}: {}",
                 path.display(),
                 err
             ))
         })?;
-        Ok(parse_env_defaults(&contents))
+        let mut defaults = parse_env_defaults(&contents);
+        // Secrets of an encrypted file only reach the script's environment,
+        // never the form, where they would end up in args and history.
+        if is_encrypted(path) {
+            defaults.retain(|key, _| !is_sensitive_key(key));
+        }
+        Ok(defaults)
     }
 }
 
@@ -126,7 +133,7 @@ impl EnvironmentRepository for FsEnvironmentRepository {
     }
 
     fn load_env_preview(&self, path: &Path) -> AppResult<EnvPreview> {
-        let contents = fs::read_to_string(path).map_err(|err| {
+        let contents = read_env_file(path).map_err(|err| {
             EnvironmentError::ReadFailed(format!(
                 "Failed to read environment file {}: {}",
                 path.display(),
@@ -135,6 +142,24 @@ impl EnvironmentRepository for FsEnvironmentRepository {
         })?;
         Ok(parse_env_preview(&contents))
     }
+
+    fn load_env_vars(&self, name: &str) -> AppResult<EnvVars> {
+        let path = self.envs_dir.join(name);
+        if !path.is_file() {
+            return Err(EnvironmentError::NotFound {
+                name: path.display().to_string(),
+            }
+            .into());
+        }
+        let contents = read_env_file(&path).map_err(|err| {
+            EnvironmentError::ReadFailed(format!(
+                "Failed to read environment file {}: {}",
+                path.display(),
+                err
+            ))
+        })?;
+        Ok(parse_env_vars(&contents))
+    }
 }
 
 fn load_active_env_name(envs_dir: &Path) -> AppResult<Option<String>> {
@@ -192,6 +217,30 @@ fn parse_env_preview(contents: &str) -> Vec<(String, String)> {
     entries
 }
 
+fn parse_env_vars(contents: &str) -> EnvVars {
+    let mut vars = Vec::new();
+
+    for line in contents.lines() {
+        let mut trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+            continue;
+        }
+        if let Some(stripped) = trimmed.strip_prefix("export ") {
+            trimmed = stripped.trim();
+        }
+
+        let mut parts = trimmed.splitn(2, '=');
+        let key = parts.next().unwrap_or("").trim();
+        let raw_value = parts.next().unwrap_or("").trim();
+        if key.is_empty() {
+            continue;
+        }
+        vars.push((key.to_string(), strip_quotes(raw_value).trim().to_string()));
+    }
+
+    vars
+}
+
 fn parse_env_defaults(contents: &str) -> HashMap<String, String> {
     let mut defaults = HashMap::new();
 
@@ -1,6 +1,11 @@
+pub mod container_runner;
 pub mod environments;
 pub(crate) mod omarchy;
+pub(crate) mod output_capture;
+pub(crate) mod process_limits;
+pub mod remote_repository;
 pub mod script_runner;
 pub(crate) mod system_checks;
+pub(crate) mod task_files;
 pub mod tui;
 pub mod workspace_repository;
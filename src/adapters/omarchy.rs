@@ -1,5 +1,6 @@
 use crate::adapters::tui::theme::{
-    BrandColors, HexColor, SemanticColors, StatusColors, Theme, ThemeMeta, ThemeVariant, UiColors,
+    BorderStyle, BrandColors, HexColor, SemanticColors, StatusColors, Theme, ThemeMeta,
+    ThemeVariant, UiColors,
 };
 use serde::Deserialize;
 use std::collections::BTreeSet;
@@ -118,6 +119,7 @@ pub(crate) fn map_to_theme(name: &str, colors: &OmarchyColors) -> Option<Theme>
             border_inactive: color8,
             selection_fg,
             selection_bg,
+            border: BorderStyle::default(),
         },
         status: StatusColors {
             ok: color2,
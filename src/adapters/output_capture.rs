@@ -0,0 +1,162 @@
+//! Captures a child output stream with a size cap.
+//!
+//! Everything read is also written to an optional log file, so the capped text
+//! kept in memory (head and tail) can be expanded later from disk.
+
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+
+/// Longest line handed to a `capture_lines` callback.
+const MAX_LINE: usize = 64 * 1024;
+
+/// Keeps the first and last `cap / 2` bytes of everything pushed into it.
+pub(crate) struct OutputBuffer {
+    cap: Option<usize>,
+    head: Vec<u8>,
+    tail: VecDeque<u8>,
+    total: u64,
+}
+
+impl OutputBuffer {
+    pub(crate) fn new(cap: Option<usize>) -> Self {
+        Self {
+            cap,
+            head: Vec::new(),
+            tail: VecDeque::new(),
+            total: 0,
+        }
+    }
+
+    pub(crate) fn push(&mut self, bytes: &[u8]) {
+        self.total += bytes.len() as u64;
+        let Some(cap) = self.cap else {
+            self.head.extend_from_slice(bytes);
+            return;
+        };
+        let head_cap = cap / 2;
+        let tail_cap = cap - head_cap;
+        let to_head = head_cap.saturating_sub(self.head.len()).min(bytes.len());
+        self.head.extend_from_slice(&bytes[..to_head]);
+        self.tail.extend(&bytes[to_head..]);
+        let excess = self.tail.len().saturating_sub(tail_cap);
+        self.tail.drain(..excess);
+    }
+
+    pub(crate) fn omitted(&self) -> u64 {
+        self.total - (self.head.len() + self.tail.len()) as u64
+    }
+
+    /// Renders the kept bytes, with a marker where bytes were dropped.
+    pub(crate) fn into_text(self, log_path: Option<&Path>) -> String {
+        let omitted = self.omitted();
+        let mut text = String::from_utf8_lossy(&self.head).into_owned();
+        if omitted > 0 {
+            let location = match log_path {
+                Some(path) => format!("; full output in {}", path.display()),
+                None => String::new(),
+            };
+            text.push_str(&format!(
+                "\n[... {} bytes truncated{} ...]\n",
+                omitted, location
+            ));
+        }
+        let (front, back) = self.tail.as_slices();
+        text.push_str(&String::from_utf8_lossy(&[front, back].concat()));
+        text
+    }
+}
+
+/// Reads `reader` to the end on a background thread and returns the kept text.
+///
+/// The log file is removed again when nothing was truncated.
+pub(crate) fn capture_stream<R: Read + Send + 'static>(
+    reader: R,
+    log_path: Option<PathBuf>,
+    cap: Option<usize>,
+) -> JoinHandle<io::Result<String>> {
+    capture_lines(reader, log_path, cap, |_| {})
+}
+
+/// Like `capture_stream`, also handing each complete line to `on_line` as it
+/// arrives, without its line ending. Lines are cut at `MAX_LINE` bytes.
+pub(crate) fn capture_lines<R, F>(
+    mut reader: R,
+    log_path: Option<PathBuf>,
+    cap: Option<usize>,
+    mut on_line: F,
+) -> JoinHandle<io::Result<String>>
+where
+    R: Read + Send + 'static,
+    F: FnMut(&str) + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut log = match &log_path {
+            Some(path) => Some(File::create(path)?),
+            None => None,
+        };
+        let mut buffer = OutputBuffer::new(cap);
+        let mut line = Vec::new();
+        let mut chunk = [0u8; 8192];
+        loop {
+            let read = match reader.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+            if let Some(log) = log.as_mut() {
+                log.write_all(&chunk[..read])?;
+            }
+            buffer.push(&chunk[..read]);
+            for &byte in &chunk[..read] {
+                if byte == b'\n' {
+                    let text = String::from_utf8_lossy(&line);
+                    on_line(text.trim_end_matches('\r'));
+                    line.clear();
+                } else if line.len() < MAX_LINE {
+                    line.push(byte);
+                }
+            }
+        }
+        drop(log);
+
+        let log_path = match log_path {
+            Some(path) if buffer.omitted() > 0 => Some(path),
+            Some(path) => {
+                let _ = fs::remove_file(&path);
+                None
+            }
+            None => None,
+        };
+        Ok(buffer.into_text(log_path.as_deref()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_buffer_keeps_everything_under_cap() {
+        let mut buffer = OutputBuffer::new(Some(16));
+        buffer.push(b"hello ");
+        buffer.push(b"world");
+        assert_eq!(buffer.omitted(), 0);
+        assert_eq!(buffer.into_text(None), "hello world");
+    }
+
+    #[test]
+    fn test_output_buffer_keeps_head_and_tail() {
+        let mut buffer = OutputBuffer::new(Some(8));
+        buffer.push(b"abcdef");
+        buffer.push(b"ghijklmnop");
+        assert_eq!(buffer.omitted(), 8);
+        assert_eq!(
+            buffer.into_text(Some(Path::new("out.log"))),
+            "abcd\n[... 8 bytes truncated; full output in out.log ...]\nmnop"
+        );
+    }
+}
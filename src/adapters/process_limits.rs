@@ -0,0 +1,282 @@
+//! Applies `RunLimits` to script processes.
+//!
+//! On Unix the limits are set in the child between fork and exec. On Windows
+//! niceness maps to a priority class and the memory cap to a job object; IO
+//! priority and open-file limits have no equivalent there and are ignored.
+//! Timed runs also get a process group of their own, so a timeout stops
+//! everything the script started.
+
+use crate::domain::RunLimits;
+use crate::error::ScriptError;
+use std::io;
+use std::process::{Child, Command};
+
+/// Configures `command` so the spawned process starts with `limits` applied.
+pub(crate) fn apply(command: &mut Command, limits: &RunLimits) -> Result<(), ScriptError> {
+    let io_priority = limits
+        .io_priority
+        .as_deref()
+        .map(parse_io_priority)
+        .transpose()?;
+    platform::apply(command, limits, io_priority);
+    Ok(())
+}
+
+/// Starts the process in a process group of its own, so `kill_tree` also
+/// reaches what it spawns. Windows has no groups; there only the child is
+/// killed.
+pub(crate) fn own_group(command: &mut Command) {
+    platform::own_group(command);
+}
+
+/// Kills the child started with `own_group` and every process left in its
+/// group.
+pub(crate) fn kill_tree(child: &mut Child) -> io::Result<()> {
+    platform::kill_tree(child)
+}
+
+/// Attaches limits that can only be set once the process exists.
+///
+/// The returned guard must be kept alive until the child has exited.
+pub(crate) fn attach(child: &Child, limits: &RunLimits) -> io::Result<Option<platform::Guard>> {
+    platform::attach(child, limits)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IoPriority {
+    Idle,
+    BestEffort(u8),
+}
+
+/// Whether `value` is an IO priority `apply` accepts.
+pub(crate) fn validate_io_priority(value: &str) -> Result<(), ScriptError> {
+    parse_io_priority(value).map(|_| ())
+}
+
+fn parse_io_priority(value: &str) -> Result<IoPriority, ScriptError> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("idle") {
+        return Ok(IoPriority::Idle);
+    }
+    match value.parse::<u8>() {
+        Ok(level) if level <= 7 => Ok(IoPriority::BestEffort(level)),
+        _ => Err(ScriptError::InvalidLimit(format!(
+            "IoPriority must be 'idle' or 0-7, got '{}'",
+            value
+        ))),
+    }
+}
+
+#[cfg(unix)]
+mod platform {
+    use super::IoPriority;
+    use crate::domain::RunLimits;
+    use std::io;
+    use std::os::unix::process::CommandExt;
+    use std::process::{Child, Command};
+
+    pub(crate) struct Guard;
+
+    pub(super) fn apply(
+        command: &mut Command,
+        limits: &RunLimits,
+        io_priority: Option<IoPriority>,
+    ) {
+        if limits.is_empty() {
+            return;
+        }
+        let nice = limits.nice;
+        let max_memory = limits
+            .max_memory_mb
+            .map(|mb| mb.saturating_mul(1024 * 1024));
+        let max_open_files = limits.max_open_files;
+        // SAFETY: the closure only makes async-signal-safe syscalls.
+        unsafe {
+            command.pre_exec(move || {
+                if let Some(nice) = nice {
+                    if libc::setpriority(libc::PRIO_PROCESS, 0, nice) != 0 {
+                        return Err(io::Error::last_os_error());
+                    }
+                }
+                if let Some(bytes) = max_memory {
+                    set_rlimit(libc::RLIMIT_AS, bytes)?;
+                }
+                if let Some(count) = max_open_files {
+                    set_rlimit(libc::RLIMIT_NOFILE, count)?;
+                }
+                if let Some(priority) = io_priority {
+                    set_io_priority(priority)?;
+                }
+                Ok(())
+            });
+        }
+    }
+
+    pub(super) fn attach(_child: &Child, _limits: &RunLimits) -> io::Result<Option<Guard>> {
+        Ok(None)
+    }
+
+    pub(super) fn own_group(command: &mut Command) {
+        command.process_group(0);
+    }
+
+    pub(super) fn kill_tree(child: &mut Child) -> io::Result<()> {
+        // The child leads its group, so the group id is its pid.
+        // SAFETY: killpg takes plain integers.
+        if unsafe { libc::killpg(child.id() as libc::pid_t, libc::SIGKILL) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    type Resource = libc::__rlimit_resource_t;
+    #[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+    type Resource = libc::c_int;
+
+    fn set_rlimit(resource: Resource, value: u64) -> io::Result<()> {
+        let limit = libc::rlimit {
+            rlim_cur: value as libc::rlim_t,
+            rlim_max: value as libc::rlim_t,
+        };
+        // SAFETY: setrlimit only reads the provided struct.
+        if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    #[cfg(target_os = "linux")]
+    fn set_io_priority(priority: IoPriority) -> io::Result<()> {
+        const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+        const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+        const IOPRIO_CLASS_BE: libc::c_int = 2;
+        const IOPRIO_CLASS_IDLE: libc::c_int = 3;
+
+        let value = match priority {
+            IoPriority::Idle => IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT,
+            IoPriority::BestEffort(level) => {
+                (IOPRIO_CLASS_BE << IOPRIO_CLASS_SHIFT) | libc::c_int::from(level)
+            }
+        };
+        // SAFETY: ioprio_set takes plain integers.
+        if unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, value) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn set_io_priority(_priority: IoPriority) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::IoPriority;
+    use crate::domain::RunLimits;
+    use std::io;
+    use std::os::windows::io::AsRawHandle;
+    use std::os::windows::process::CommandExt;
+    use std::process::{Child, Command};
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectExtendedLimitInformation,
+        SetInformationJobObject, JOBOBJECT_EXTENDED_LIMIT_INFORMATION,
+        JOB_OBJECT_LIMIT_PROCESS_MEMORY,
+    };
+
+    const IDLE_PRIORITY_CLASS: u32 = 0x0000_0040;
+    const BELOW_NORMAL_PRIORITY_CLASS: u32 = 0x0000_4000;
+    const ABOVE_NORMAL_PRIORITY_CLASS: u32 = 0x0000_8000;
+
+    /// Owns the job object holding the child; closing it releases the limits.
+    pub(crate) struct Guard(HANDLE);
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            // SAFETY: the handle came from CreateJobObjectW and is closed once.
+            unsafe {
+                CloseHandle(self.0);
+            }
+        }
+    }
+
+    pub(super) fn apply(
+        command: &mut Command,
+        limits: &RunLimits,
+        _io_priority: Option<IoPriority>,
+    ) {
+        let class = match limits.nice {
+            Some(nice) if nice >= 10 => IDLE_PRIORITY_CLASS,
+            Some(nice) if nice > 0 => BELOW_NORMAL_PRIORITY_CLASS,
+            Some(nice) if nice < 0 => ABOVE_NORMAL_PRIORITY_CLASS,
+            _ => return,
+        };
+        command.creation_flags(class);
+    }
+
+    pub(super) fn attach(child: &Child, limits: &RunLimits) -> io::Result<Option<Guard>> {
+        let Some(max_memory_mb) = limits.max_memory_mb else {
+            return Ok(None);
+        };
+        // SAFETY: all pointers passed below are valid for the duration of each call.
+        unsafe {
+            let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if job.is_null() {
+                return Err(io::Error::last_os_error());
+            }
+            let guard = Guard(job);
+            let mut info = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+            info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_PROCESS_MEMORY;
+            info.ProcessMemoryLimit = (max_memory_mb as usize).saturating_mul(1024 * 1024);
+            let set = SetInformationJobObject(
+                job,
+                JobObjectExtendedLimitInformation,
+                &info as *const _ as *const core::ffi::c_void,
+                std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+            );
+            if set == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if AssignProcessToJobObject(job, child.as_raw_handle() as HANDLE) == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(Some(guard))
+        }
+    }
+
+    pub(super) fn own_group(_command: &mut Command) {}
+
+    pub(super) fn kill_tree(child: &mut Child) -> io::Result<()> {
+        child.kill()
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform {
+    use super::IoPriority;
+    use crate::domain::RunLimits;
+    use std::io;
+    use std::process::{Child, Command};
+
+    pub(crate) struct Guard;
+
+    pub(super) fn apply(
+        _command: &mut Command,
+        _limits: &RunLimits,
+        _io_priority: Option<IoPriority>,
+    ) {
+    }
+
+    pub(super) fn attach(_child: &Child, _limits: &RunLimits) -> io::Result<Option<Guard>> {
+        Ok(None)
+    }
+
+    pub(super) fn own_group(_command: &mut Command) {}
+
+    pub(super) fn kill_tree(child: &mut Child) -> io::Result<()> {
+        child.kill()
+    }
+}
@@ -0,0 +1,253 @@
+use crate::domain::{parse_schema, Schema};
+use crate::error::{AppError, AppResult};
+use crate::http;
+use crate::ports::{ScriptRepository, WorkspaceEntry, WorkspaceEntryKind};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Index file name looked up under a catalog URL that names a folder.
+pub const INDEX_FILE: &str = "index.json";
+const INDEX_VERSION: u32 = 1;
+
+/// A published list of scripts, as written by `omakure scripts --index`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScriptIndex {
+    pub version: u32,
+    pub scripts: Vec<IndexedScript>,
+}
+
+/// One script of a `ScriptIndex`. The schema is inline, or fetched from
+/// `schema_url` (relative to the index) when first read.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IndexedScript {
+    /// Workspace-relative path with `/` separators.
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Schema>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_url: Option<String>,
+}
+
+impl ScriptIndex {
+    pub fn new(scripts: Vec<IndexedScript>) -> Self {
+        Self {
+            version: INDEX_VERSION,
+            scripts,
+        }
+    }
+}
+
+/// Read-only scripts from an index served over HTTP(S). Scripts are listed
+/// under `root`, a stand-in folder that only serves to build paths; nothing
+/// is read from or written to it.
+pub struct RemoteScriptRepository {
+    root: PathBuf,
+    /// URL the index was read from, with the file name dropped.
+    base_url: String,
+    scripts: BTreeMap<String, IndexedScript>,
+    fetched: Mutex<HashMap<String, Schema>>,
+}
+
+impl RemoteScriptRepository {
+    /// Reads the index at `url`, or at `url/index.json` when `url` does not
+    /// name a `.json` file.
+    pub fn connect(url: &str, root: PathBuf) -> AppResult<Self> {
+        let index_url = if url.ends_with(".json") {
+            url.to_string()
+        } else {
+            format!("{}/{}", url.trim_end_matches('/'), INDEX_FILE)
+        };
+        let body =
+            http::get_string(&index_url).map_err(|err| AppError::General(err.to_string()))?;
+        let index: ScriptIndex = serde_json::from_str(&body).map_err(|err| {
+            AppError::General(format!("Invalid script index at {}: {}", index_url, err))
+        })?;
+        if index.version > INDEX_VERSION {
+            return Err(AppError::General(format!(
+                "Script index at {} has version {}; this omakure reads version {}",
+                index_url, index.version, INDEX_VERSION
+            )));
+        }
+        let base_url = match index_url.rsplit_once('/') {
+            Some((base, _)) => format!("{}/", base),
+            None => String::new(),
+        };
+        Ok(Self::from_index(root, base_url, index))
+    }
+
+    fn from_index(root: PathBuf, base_url: String, index: ScriptIndex) -> Self {
+        Self {
+            root,
+            base_url,
+            scripts: index
+                .scripts
+                .into_iter()
+                .map(|script| (script.path.trim_matches('/').to_string(), script))
+                .collect(),
+            fetched: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The script named `name`: its path, or its path without the extension.
+    pub fn find(&self, name: &str) -> Option<PathBuf> {
+        let name = name.replace('\\', "/");
+        let name = name.trim_matches('/');
+        self.scripts
+            .keys()
+            .find(|key| key.as_str() == name)
+            .or_else(|| {
+                self.scripts.keys().find(|key| {
+                    key.strip_prefix(name)
+                        .is_some_and(|rest| rest.starts_with('.') && !rest[1..].contains('/'))
+                })
+            })
+            .map(|key| self.root.join(key))
+    }
+
+    /// Index key for a path under `root`.
+    fn key(&self, path: &Path) -> Option<String> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        Some(relative.to_string_lossy().replace('\\', "/"))
+    }
+
+    fn fetch_schema(&self, key: &str, schema_url: &str) -> AppResult<Schema> {
+        if let Some(schema) = self
+            .fetched
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get(key).cloned())
+        {
+            return Ok(schema);
+        }
+        let url = if schema_url.contains("://") {
+            schema_url.to_string()
+        } else {
+            format!("{}{}", self.base_url, schema_url.trim_start_matches('/'))
+        };
+        let body = http::get_string(&url).map_err(|err| AppError::General(err.to_string()))?;
+        let schema = parse_schema(&body)?;
+        if let Ok(mut cache) = self.fetched.lock() {
+            cache.insert(key.to_string(), schema.clone());
+        }
+        Ok(schema)
+    }
+}
+
+impl ScriptRepository for RemoteScriptRepository {
+    fn list_entries(&self, dir: &Path) -> io::Result<Vec<WorkspaceEntry>> {
+        let Some(prefix) = self.key(dir) else {
+            return Ok(Vec::new());
+        };
+        let mut dirs = BTreeSet::new();
+        let mut scripts = Vec::new();
+        for key in self.scripts.keys() {
+            let rest = if prefix.is_empty() {
+                key.as_str()
+            } else {
+                match key
+                    .strip_prefix(prefix.as_str())
+                    .and_then(|rest| rest.strip_prefix('/'))
+                {
+                    Some(rest) => rest,
+                    None => continue,
+                }
+            };
+            match rest.split_once('/') {
+                Some((name, _)) => {
+                    dirs.insert(name);
+                }
+                None => scripts.push(rest),
+            }
+        }
+        let entries = dirs
+            .into_iter()
+            .map(|name| WorkspaceEntry {
+                path: dir.join(name),
+                kind: WorkspaceEntryKind::Directory,
+            })
+            .chain(scripts.into_iter().map(|name| WorkspaceEntry {
+                path: dir.join(name),
+                kind: WorkspaceEntryKind::Script,
+            }))
+            .collect();
+        Ok(entries)
+    }
+
+    fn list_scripts_recursive(&self) -> io::Result<Vec<PathBuf>> {
+        Ok(self.scripts.keys().map(|key| self.root.join(key)).collect())
+    }
+
+    fn read_schema(&self, script: &Path) -> AppResult<Schema> {
+        let key = self.key(script).unwrap_or_default();
+        let Some(entry) = self.scripts.get(&key) else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{} is not in the script index", key),
+            )
+            .into());
+        };
+        match (&entry.schema, &entry.schema_url) {
+            (Some(schema), _) => Ok(schema.clone()),
+            (None, Some(schema_url)) => self.fetch_schema(&key, schema_url),
+            (None, None) => Err(AppError::General(format!(
+                "{} has no schema in the script index",
+                key
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lists_folders_and_reads_inline_schemas() {
+        let index: ScriptIndex = serde_json::from_str(
+            r#"{"version": 1, "scripts": [
+                {"path": "aws/deploy.bash", "schema": {"Name": "deploy", "Fields": []}},
+                {"path": "aws/s3/sync.py"},
+                {"path": "hello.ps1", "schema": {"Name": "hello", "Fields": []}}
+            ]}"#,
+        )
+        .unwrap();
+        let root = PathBuf::from("remote");
+        let repo =
+            RemoteScriptRepository::from_index(root.clone(), "https://example.com/".into(), index);
+
+        let top: Vec<_> = repo
+            .list_entries(&root)
+            .unwrap()
+            .into_iter()
+            .map(|entry| (entry.path, entry.kind))
+            .collect();
+        assert_eq!(
+            top,
+            vec![
+                (root.join("aws"), WorkspaceEntryKind::Directory),
+                (root.join("hello.ps1"), WorkspaceEntryKind::Script),
+            ]
+        );
+        let aws: Vec<_> = repo
+            .list_entries(&root.join("aws"))
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.path)
+            .collect();
+        assert_eq!(aws, vec![root.join("aws/s3"), root.join("aws/deploy.bash")]);
+
+        assert_eq!(repo.find("aws/deploy"), Some(root.join("aws/deploy.bash")));
+        assert_eq!(repo.find("deploy"), None);
+        let schema = repo.read_schema(&root.join("aws/deploy.bash")).unwrap();
+        assert_eq!(schema.name, "deploy");
+        assert!(repo.read_schema(&root.join("aws/s3/sync.py")).is_err());
+        assert!(repo.read_schema(&root.join("missing.bash")).is_err());
+    }
+}
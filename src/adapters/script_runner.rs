@@ -1,11 +1,34 @@
+use crate::adapters::container_runner::ContainerScriptRunner;
+use crate::adapters::output_capture::{capture_lines, capture_stream};
+use crate::adapters::process_limits;
 use crate::adapters::system_checks::{
-    ensure_bash_installed, ensure_git_installed, ensure_jq_installed, ensure_powershell_installed,
-    ensure_python_installed,
+    ensure_bash_installed, ensure_curl_installed, ensure_git_installed, ensure_jq_installed,
+    ensure_just_installed, ensure_kubectl_installed, ensure_make_installed,
+    ensure_powershell_installed, ensure_python_installed, ensure_runtime_installed,
+    ensure_ssh_installed,
 };
+use crate::domain::parse_prompt;
 use crate::error::{AppResult, ScriptError};
-use crate::ports::{ScriptRunOutput, ScriptRunner};
-use crate::runtime::{command_for_script, script_kind, ScriptKind};
+use crate::logging;
+use crate::ports::{Backend, Prompts, ResourceUsage, RunOptions, ScriptRunOutput, ScriptRunner};
+use crate::requirements;
+use crate::runtime::{
+    command_for_script, custom_extension, kubernetes_command, script_args, script_kind,
+    shell_command, ssh_command, ScriptKind,
+};
+use serde_json::json;
+use std::fs;
+use std::io::{self, Write};
 use std::path::Path;
+use std::process::{Child, ChildStdin, Command, ExitStatus, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How often a run with a timeout checks whether the script has exited.
+const TIMEOUT_POLL: Duration = Duration::from_millis(50);
+/// How long the output of a timed-out run may take to drain once it is
+/// killed: a process that escaped the kill can hold the pipes open.
+const DRAIN_GRACE: Duration = Duration::from_millis(500);
 
 pub struct MultiScriptRunner;
 
@@ -16,27 +39,318 @@ impl MultiScriptRunner {
 }
 
 impl ScriptRunner for MultiScriptRunner {
-    fn run(&self, script: &Path, args: &[String]) -> AppResult<ScriptRunOutput> {
-        match script_kind(script).ok_or(ScriptError::UnsupportedType)? {
+    fn run(
+        &self,
+        script: &Path,
+        args: &[String],
+        options: &RunOptions,
+    ) -> AppResult<ScriptRunOutput> {
+        if let Some(target) = &options.ssh {
+            ensure_ssh_installed()?;
+            let command = ssh_command(
+                script,
+                args,
+                target,
+                &options.env,
+                &options.remote_artifacts,
+                options.log_dir.as_deref(),
+            )?;
+            return execute(command, options);
+        }
+        if options.backend == Backend::Kubernetes {
+            ensure_kubectl_installed()?;
+            let command = kubernetes_command(script, args, &options.kubernetes, &options.env)?;
+            return execute(command, options);
+        }
+        if options.container.is_some() {
+            return ContainerScriptRunner::new().run(script, args, options);
+        }
+        let kind = script_kind(script).ok_or(ScriptError::UnsupportedType)?;
+        let interpreters = &options.interpreters;
+        match kind {
             ScriptKind::Bash => {
                 ensure_git_installed()?;
-                ensure_bash_installed()?;
+                match &interpreters.bash {
+                    Some(program) => ensure_runtime_installed(program)?,
+                    None => ensure_bash_installed()?,
+                }
                 ensure_jq_installed()?;
             }
-            ScriptKind::PowerShell => {
-                ensure_powershell_installed()?;
+            ScriptKind::PowerShell => match &interpreters.powershell {
+                Some(program) => ensure_runtime_installed(program)?,
+                None => ensure_powershell_installed()?,
+            },
+            ScriptKind::Python => match &interpreters.python {
+                Some(program) => ensure_runtime_installed(program)?,
+                None => ensure_python_installed()?,
+            },
+            ScriptKind::Workflow => {
+                ensure_curl_installed()?;
+            }
+            ScriptKind::Executable => {}
+            ScriptKind::Make => {
+                ensure_make_installed()?;
+            }
+            ScriptKind::Just => {
+                ensure_just_installed()?;
             }
-            ScriptKind::Python => {
-                ensure_python_installed()?;
+            ScriptKind::Custom => {
+                let custom = custom_extension(script).ok_or(ScriptError::UnsupportedType)?;
+                ensure_runtime_installed(&custom.command[0])?;
             }
         }
+        let missing = requirements::missing(&options.requires);
+        if !missing.is_empty() {
+            return Err(ScriptError::RequirementsNotMet(missing.join("; ")).into());
+        }
+
+        let mut command = command_for_script(script, interpreters)?;
+        command.args(script_args(script, kind, args));
+        execute(command, options)
+    }
 
-        let output = command_for_script(script)?.args(args).output()?;
-        Ok(ScriptRunOutput {
-            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-            exit_code: output.status.code(),
-            success: output.status.success(),
+    fn run_shell(
+        &self,
+        line: &str,
+        dir: &Path,
+        options: &RunOptions,
+    ) -> AppResult<ScriptRunOutput> {
+        let mut command = shell_command(line);
+        command.current_dir(dir);
+        execute(command, options)
+    }
+}
+
+/// Spawns `command` with `options` applied and collects its output.
+pub(crate) fn execute(mut command: Command, options: &RunOptions) -> AppResult<ScriptRunOutput> {
+    command
+        .envs(options.env.iter().map(|(key, value)| (key, value)))
+        .stdin(if options.prompts.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
         })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    process_limits::apply(&mut command, &options.limits)?;
+    if options.timeout.is_some() {
+        process_limits::own_group(&mut command);
+    }
+
+    let started = Instant::now();
+    let mut child = command.spawn()?;
+    let _job = match process_limits::attach(&child, &options.limits) {
+        Ok(job) => job,
+        Err(err) => {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(err.into());
+        }
+    };
+    if let Some(dir) = &options.log_dir {
+        fs::create_dir_all(dir)?;
+    }
+    let log_path = |name: &str| options.log_dir.as_ref().map(|dir| dir.join(name));
+    let stdin = child.stdin.take();
+    let stdout = child.stdout.take().map(|stream| {
+        let log_path = log_path("stdout.log");
+        match (&options.prompts, stdin) {
+            (Some(prompts), Some(stdin)) => capture_lines(
+                stream,
+                log_path,
+                options.max_output_bytes,
+                answer_prompts(prompts.clone(), stdin),
+            ),
+            _ => capture_stream(stream, log_path, options.max_output_bytes),
+        }
+    });
+    let stderr = child
+        .stderr
+        .take()
+        .map(|stream| capture_stream(stream, log_path("stderr.log"), options.max_output_bytes));
+    let (status, usage, timed_out) = match options.timeout {
+        Some(timeout) => wait_with_timeout(&mut child, started, timeout)?,
+        None => {
+            let (status, usage) = reap(&mut child)?;
+            (status, usage, false)
+        }
+    };
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let drain_by = timed_out.then(|| Instant::now() + DRAIN_GRACE);
+    let stdout = join_capture(stdout, drain_by)?;
+    let mut stderr = join_capture(stderr, drain_by)?;
+    if let Some(timeout) = options.timeout.filter(|_| timed_out) {
+        if !stderr.is_empty() && !stderr.ends_with('\n') {
+            stderr.push('\n');
+        }
+        stderr.push_str(&format!(
+            "omakure: stopped after the {}s timeout\n",
+            timeout.as_secs()
+        ));
+    }
+    if let Some(dir) = &options.log_dir {
+        // Only succeeds when no log had to be kept.
+        let _ = fs::remove_dir(dir);
+    }
+    Ok(ScriptRunOutput {
+        stdout,
+        stderr,
+        exit_code: status.code(),
+        success: status.success() && !timed_out,
+        usage,
+        duration_ms,
+        log_dir: options.log_dir.clone(),
+        retries: 0,
+        hooks: Vec::new(),
+        files: Vec::new(),
+    })
+}
+
+/// Line handler asking `prompts` each `OMAKURE_PROMPT` on stdout and writing
+/// the answer to the script's `stdin`. A prompt that does not parse gets an
+/// empty line, so the script is not left waiting.
+fn answer_prompts(prompts: Prompts, mut stdin: ChildStdin) -> impl FnMut(&str) + Send + 'static {
+    move |line| {
+        let answer = match parse_prompt(line) {
+            None => return,
+            Some(Ok(prompt)) => prompts.0.ask(&prompt).unwrap_or_else(|| prompt.fallback()),
+            Some(Err(err)) => {
+                logging::warn(
+                    "run",
+                    "Invalid prompt",
+                    json!({ "line": line, "error": err }),
+                );
+                String::new()
+            }
+        };
+        let _ = writeln!(stdin, "{}", answer).and_then(|_| stdin.flush());
+    }
+}
+
+/// Waits for `child`, killing its process group once `timeout` has passed
+/// since `started`. Returns whether it was killed.
+fn wait_with_timeout(
+    child: &mut Child,
+    started: Instant,
+    timeout: Duration,
+) -> io::Result<(ExitStatus, Option<ResourceUsage>, bool)> {
+    loop {
+        if let Some((status, usage)) = try_reap(child)? {
+            return Ok((status, usage, false));
+        }
+        if started.elapsed() >= timeout {
+            // Already gone if this fails; `reap` collects it either way.
+            let _ = process_limits::kill_tree(child);
+            let (status, usage) = reap(child)?;
+            return Ok((status, usage, true));
+        }
+        thread::sleep(TIMEOUT_POLL);
     }
 }
+
+type Reaped = (ExitStatus, Option<ResourceUsage>);
+
+/// Waits for `child` to exit and collects its status with its resource usage.
+fn reap(child: &mut Child) -> io::Result<Reaped> {
+    wait_child(child, true)?.ok_or_else(|| io::Error::other("child is still running"))
+}
+
+/// Collects `child` if it has exited, without blocking.
+fn try_reap(child: &mut Child) -> io::Result<Option<Reaped>> {
+    wait_child(child, false)
+}
+
+/// Reaps `child` with `wait4`, which reports the usage of that process (and
+/// the children it waited for) alone, not of every child omakure has run.
+///
+/// Once this returns a status the child is gone, so it must not be waited on
+/// or killed through `Child` again.
+#[cfg(unix)]
+fn wait_child(child: &mut Child, block: bool) -> io::Result<Option<Reaped>> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let pid = child.id() as libc::pid_t;
+    let flags = if block { 0 } else { libc::WNOHANG };
+    let mut status = 0;
+    let mut usage = std::mem::MaybeUninit::<libc::rusage>::zeroed();
+    loop {
+        // SAFETY: wait4 only writes into the provided status and struct.
+        let result = unsafe { libc::wait4(pid, &mut status, flags, usage.as_mut_ptr()) };
+        if result == 0 {
+            return Ok(None);
+        }
+        if result < 0 {
+            let err = io::Error::last_os_error();
+            if err.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(err);
+        }
+        // SAFETY: wait4 reaped the child, so the struct is initialized.
+        let usage = unsafe { usage.assume_init() };
+        return Ok(Some((
+            ExitStatus::from_raw(status),
+            Some(resource_usage(&usage)),
+        )));
+    }
+}
+
+/// Windows has no per-process equivalent that survives the reap, so runs
+/// there record no usage.
+#[cfg(not(unix))]
+fn wait_child(child: &mut Child, block: bool) -> io::Result<Option<Reaped>> {
+    let status = if block {
+        Some(child.wait()?)
+    } else {
+        child.try_wait()?
+    };
+    Ok(status.map(|status| (status, None)))
+}
+
+/// Joins an output capture thread. Past `deadline` the thread is left behind
+/// and the output dropped, so a process still holding the pipe cannot block
+/// the run.
+fn join_capture(
+    handle: Option<JoinHandle<io::Result<String>>>,
+    deadline: Option<Instant>,
+) -> io::Result<String> {
+    let Some(handle) = handle else {
+        return Ok(String::new());
+    };
+    if let Some(deadline) = deadline {
+        while !handle.is_finished() {
+            if Instant::now() >= deadline {
+                return Ok(String::new());
+            }
+            thread::sleep(TIMEOUT_POLL);
+        }
+    }
+    handle
+        .join()
+        .map_err(|_| io::Error::other("output capture thread panicked"))?
+}
+
+#[cfg(unix)]
+fn resource_usage(usage: &libc::rusage) -> ResourceUsage {
+    let max_rss = usage.ru_maxrss.max(0) as u64;
+    // macOS reports ru_maxrss in bytes, Linux in kilobytes.
+    let max_rss_kb = if cfg!(target_os = "macos") {
+        max_rss / 1024
+    } else {
+        max_rss
+    };
+    ResourceUsage {
+        user_cpu_ms: timeval_ms(usage.ru_utime),
+        system_cpu_ms: timeval_ms(usage.ru_stime),
+        max_rss_kb: if max_rss_kb == 0 {
+            None
+        } else {
+            Some(max_rss_kb)
+        },
+    }
+}
+
+#[cfg(unix)]
+fn timeval_ms(value: libc::timeval) -> u64 {
+    (value.tv_sec.max(0) as u64) * 1000 + (value.tv_usec.max(0) as u64) / 1000
+}
@@ -1,3 +1,4 @@
+use std::env;
 use std::process::Command;
 
 use crate::error::ScriptError;
@@ -69,6 +70,46 @@ pub(crate) fn ensure_jq_installed() -> Result<(), ScriptError> {
     ensure_command("jq", &["--version"], "Install jq and ensure it is in PATH")
 }
 
+pub(crate) fn ensure_make_installed() -> Result<(), ScriptError> {
+    ensure_command(
+        "make",
+        &["--version"],
+        "Install make and ensure it is in PATH",
+    )
+}
+
+pub(crate) fn ensure_just_installed() -> Result<(), ScriptError> {
+    ensure_command(
+        "just",
+        &["--version"],
+        "Install just and ensure it is in PATH",
+    )
+}
+
+pub(crate) fn ensure_kubectl_installed() -> Result<(), ScriptError> {
+    ensure_command(
+        "kubectl",
+        &["version", "--client"],
+        "Install kubectl and ensure it is in PATH",
+    )
+}
+
+pub(crate) fn ensure_ssh_installed() -> Result<(), ScriptError> {
+    ensure_command(
+        "ssh",
+        &["-V"],
+        "Install an OpenSSH client and ensure ssh is in PATH",
+    )
+}
+
+pub(crate) fn ensure_curl_installed() -> Result<(), ScriptError> {
+    ensure_command(
+        "curl",
+        &["--version"],
+        "Install curl and ensure it is in PATH",
+    )
+}
+
 pub(crate) fn ensure_powershell_installed() -> Result<(), ScriptError> {
     let program = powershell_program();
     ensure_command(
@@ -86,3 +127,59 @@ pub(crate) fn ensure_python_installed() -> Result<(), ScriptError> {
         &format!("Install Python and ensure {} is in PATH", program),
     )
 }
+
+/// The container engine to run with: `preferred` once checked, otherwise
+/// `docker`, then `podman`, whichever is installed.
+pub(crate) fn container_engine(preferred: Option<&str>) -> Result<String, ScriptError> {
+    if let Some(engine) = preferred {
+        ensure_command(
+            engine,
+            &["--version"],
+            &format!("Install {} and ensure it is in PATH", engine),
+        )?;
+        return Ok(engine.to_string());
+    }
+    ["docker", "podman"]
+        .into_iter()
+        .find(|engine| in_path(engine))
+        .map(str::to_string)
+        .ok_or_else(|| ScriptError::DependencyMissing {
+            name: "docker".to_string(),
+            hint: "Install Docker or Podman and ensure it is in PATH".to_string(),
+        })
+}
+
+/// Checks a runtime a flavor or script requires: known tools the way omakure
+/// checks them, any other command by finding it in PATH.
+pub(crate) fn ensure_runtime_installed(name: &str) -> Result<(), ScriptError> {
+    match name {
+        "bash" => ensure_bash_installed(),
+        "python" | "python3" => ensure_python_installed(),
+        "powershell" | "pwsh" => ensure_powershell_installed(),
+        "git" => ensure_git_installed(),
+        "jq" => ensure_jq_installed(),
+        "make" => ensure_make_installed(),
+        "just" => ensure_just_installed(),
+        "kubectl" => ensure_kubectl_installed(),
+        "ssh" => ensure_ssh_installed(),
+        "curl" => ensure_curl_installed(),
+        _ if in_path(name) => Ok(()),
+        _ => Err(ScriptError::DependencyMissing {
+            name: name.to_string(),
+            hint: format!("Install {} and ensure it is in PATH", name),
+        }),
+    }
+}
+
+fn in_path(program: &str) -> bool {
+    let extensions: &[&str] = if cfg!(windows) {
+        &["", ".exe", ".cmd", ".bat"]
+    } else {
+        &[""]
+    };
+    env::split_paths(&env::var_os("PATH").unwrap_or_default()).any(|dir| {
+        extensions
+            .iter()
+            .any(|extension| dir.join(format!("{}{}", program, extension)).is_file())
+    })
+}
@@ -0,0 +1,409 @@
+use crate::domain::Schema;
+use crate::error::{AppResult, SchemaError};
+use crate::runtime::{task_file_kind, task_target, ScriptKind, TASK_SEPARATOR};
+use serde_json::json;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A Makefile target or justfile recipe offered as a script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TaskTarget {
+    pub name: String,
+    /// The comment right above the target, or a Makefile `## help` comment.
+    pub description: Option<String>,
+    /// Recipe parameters; Makefile targets have none.
+    pub params: Vec<TaskParam>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TaskParam {
+    pub name: String,
+    pub required: bool,
+    pub default: Option<String>,
+}
+
+/// Script paths of the targets in the task file at `file`, e.g.
+/// `ops/Makefile#deploy`, or nothing for any other file.
+pub(crate) fn target_paths(file: &Path) -> io::Result<Vec<PathBuf>> {
+    let Some(name) = file.file_name().and_then(|name| name.to_str()) else {
+        return Ok(Vec::new());
+    };
+    if task_file_kind(file).is_none() {
+        return Ok(Vec::new());
+    }
+    Ok(list_targets(file)?
+        .into_iter()
+        .map(|target| file.with_file_name(format!("{}{}{}", name, TASK_SEPARATOR, target.name)))
+        .collect())
+}
+
+/// Schema for a target without a sidecar: its comment as the description and
+/// one string field per recipe parameter.
+pub(crate) fn target_schema(file: &Path, target: &str) -> AppResult<Schema> {
+    let task = list_targets(file)?
+        .into_iter()
+        .find(|task| task.name == target)
+        .ok_or(SchemaError::BlockNotFound)?;
+    let tag = match task_file_kind(file) {
+        Some(ScriptKind::Just) => "just",
+        _ => "make",
+    };
+    let fields: Vec<_> = task
+        .params
+        .iter()
+        .enumerate()
+        .map(|(index, param)| {
+            json!({
+                "Name": param.name,
+                "Type": "string",
+                "Order": index + 1,
+                "Required": param.required,
+                "Default": param.default,
+            })
+        })
+        .collect();
+    let schema = json!({
+        "Name": task.name,
+        "Description": task.description,
+        "Tags": [tag],
+        "Fields": fields,
+    });
+    Ok(serde_json::from_value(schema).map_err(SchemaError::from)?)
+}
+
+/// Positional arguments for the just recipe `script` from the `--name value`
+/// pairs a form builds; see `recipe_values`. Without readable parameters the
+/// values are passed in the order given.
+pub(crate) fn recipe_args(script: &Path, args: &[String]) -> Vec<String> {
+    let params = task_target(script)
+        .and_then(|(file, target)| {
+            list_targets(&file)
+                .ok()?
+                .into_iter()
+                .find(|task| task.name == target)
+        })
+        .map(|task| task.params)
+        .unwrap_or_default();
+    recipe_values(&params, args)
+}
+
+/// Values of `params` in declaration order, up to the last one `args` sets.
+/// Parameters left unset before it take their default, or an empty string
+/// when the default is not a literal. Pairs naming no parameter follow.
+fn recipe_values(params: &[TaskParam], args: &[String]) -> Vec<String> {
+    let mut values: Vec<Option<String>> = vec![None; params.len()];
+    let mut extra = Vec::new();
+    for pair in args.chunks(2) {
+        let [flag, value] = pair else {
+            extra.extend(pair.iter().cloned());
+            continue;
+        };
+        let name = flag.trim_start_matches('-');
+        match params.iter().position(|param| param.name == name) {
+            Some(index) => values[index] = Some(value.clone()),
+            None => extra.push(value.clone()),
+        }
+    }
+    let set = values
+        .iter()
+        .rposition(Option::is_some)
+        .map_or(0, |last| last + 1);
+    values
+        .into_iter()
+        .zip(params)
+        .take(set)
+        .map(|(value, param)| value.or_else(|| param.default.clone()).unwrap_or_default())
+        .chain(extra)
+        .collect()
+}
+
+fn list_targets(file: &Path) -> io::Result<Vec<TaskTarget>> {
+    let contents = fs::read_to_string(file)?;
+    Ok(match task_file_kind(file) {
+        Some(ScriptKind::Just) => parse_just_recipes(&contents),
+        Some(ScriptKind::Make) => parse_make_targets(&contents),
+        _ => Vec::new(),
+    })
+}
+
+/// Explicit targets of a Makefile, skipping special (`.PHONY`), pattern
+/// (`%.o`), and computed (`$(BIN)`) targets.
+pub(crate) fn parse_make_targets(contents: &str) -> Vec<TaskTarget> {
+    let mut targets: Vec<TaskTarget> = Vec::new();
+    let mut comment = None;
+    for line in contents.lines() {
+        if line.starts_with('\t') || line.trim().is_empty() {
+            comment = None;
+            continue;
+        }
+        if let Some(text) = line.strip_prefix('#') {
+            comment = Some(text.trim().to_string());
+            continue;
+        }
+        let Some((head, rest)) = line.split_once(':') else {
+            comment = None;
+            continue;
+        };
+        let description = rest
+            .split_once("##")
+            .map(|(_, help)| help.trim().to_string())
+            .or(comment.take());
+        if head.contains('=') || rest.starts_with('=') || rest.starts_with(":=") {
+            continue;
+        }
+        for name in head.split_whitespace() {
+            let valid = !name.starts_with('.')
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '/'));
+            if valid && !targets.iter().any(|target| target.name == name) {
+                targets.push(TaskTarget {
+                    name: name.to_string(),
+                    description: description.clone().filter(|text| !text.is_empty()),
+                    params: Vec::new(),
+                });
+            }
+        }
+    }
+    targets
+}
+
+/// Public recipes of a justfile with their parameters. Recipes starting with
+/// `_` or marked `[private]` are left out.
+pub(crate) fn parse_just_recipes(contents: &str) -> Vec<TaskTarget> {
+    let mut recipes = Vec::new();
+    let mut comment = None;
+    let mut private = false;
+    for line in contents.lines() {
+        if line.starts_with([' ', '\t']) || line.trim().is_empty() {
+            comment = None;
+            private = false;
+            continue;
+        }
+        if let Some(text) = line.strip_prefix('#') {
+            if !text.starts_with('!') {
+                comment = Some(text.trim().to_string());
+            }
+            continue;
+        }
+        if let Some(attributes) = line.strip_prefix('[') {
+            private |= attributes.contains("private");
+            continue;
+        }
+        let first_word = line.split_whitespace().next().unwrap_or("");
+        if matches!(first_word, "set" | "alias" | "export" | "import" | "mod") {
+            comment = None;
+            continue;
+        }
+        let Some(header) = recipe_header(line) else {
+            comment = None;
+            continue;
+        };
+        let mut words = split_words(header).into_iter();
+        let name = words.next().unwrap_or_default();
+        let name = name.trim_start_matches('@');
+        if !name.is_empty() && !name.starts_with('_') && !private {
+            recipes.push(TaskTarget {
+                name: name.to_string(),
+                description: comment.take().filter(|text| !text.is_empty()),
+                params: words.map(|word| parse_just_param(&word)).collect(),
+            });
+        }
+        comment = None;
+        private = false;
+    }
+    recipes
+}
+
+/// Text before the first unquoted `:` of a recipe line, or `None` for an
+/// assignment (`name := value`) or any other line.
+fn recipe_header(line: &str) -> Option<&str> {
+    let mut quote = None;
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            (Some(open), _) if c == open => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, ':') => {
+                return (!line[index + 1..].starts_with('=')).then_some(&line[..index]);
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits on whitespace outside quotes and parentheses.
+fn split_words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quote = None;
+    let mut depth = 0usize;
+    for c in text.chars() {
+        match (quote, c) {
+            (Some(open), _) if c == open => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth = depth.saturating_sub(1),
+            (None, _) if c.is_whitespace() && depth == 0 => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Reads `name`, `name='default'`, `$name`, `+names`, or `*names`. Defaults
+/// that are expressions rather than string literals make the parameter
+/// optional without a default.
+fn parse_just_param(word: &str) -> TaskParam {
+    let word = word.trim_start_matches('$');
+    let variadic_optional = word.starts_with('*');
+    let word = word.trim_start_matches(['+', '*']).trim_start_matches('$');
+    let (name, default) = match word.split_once('=') {
+        Some((name, default)) => (name, Some(default)),
+        None => (word, None),
+    };
+    let literal = default.and_then(|default| {
+        ['\'', '"']
+            .iter()
+            .find_map(|quote| default.strip_prefix(*quote)?.strip_suffix(*quote))
+            .map(str::to_string)
+    });
+    TaskParam {
+        name: name.to_string(),
+        required: default.is_none() && !variadic_optional,
+        default: literal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recipe_values_follow_parameter_order() {
+        let recipe = parse_just_recipes("deploy url target=\"x\" token region='eu':\n    echo\n");
+        let params = &recipe[0].params;
+        let args = |pairs: &[&str]| {
+            pairs
+                .iter()
+                .map(|part| part.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // An empty optional parameter in the middle keeps its default.
+        assert_eq!(
+            recipe_values(params, &args(&["--url", "u", "--token", "t"])),
+            vec!["u", "x", "t"]
+        );
+        assert_eq!(
+            recipe_values(params, &args(&["--region", "us", "--url", "u"])),
+            vec!["u", "x", "", "us"]
+        );
+        assert_eq!(
+            recipe_values(params, &args(&["--url", "u", "--other", "o"])),
+            vec!["u", "o"]
+        );
+        assert!(recipe_values(params, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_parse_make_targets() {
+        let contents = "\
+BIN := app
+VERSION ?= dev
+.PHONY: build test
+
+# Compile the binary
+build: deps
+\tgo build -o $(BIN)
+
+test: ## Run the unit tests
+\tgo test ./...
+
+%.o: %.c
+\tcc -c $<
+
+$(BIN): build
+lint fmt:
+\t./tools/check
+build: CFLAGS = -O2
+";
+        let targets = parse_make_targets(contents);
+        let names: Vec<&str> = targets.iter().map(|target| target.name.as_str()).collect();
+        assert_eq!(names, vec!["build", "test", "lint", "fmt"]);
+        assert_eq!(
+            targets[0].description.as_deref(),
+            Some("Compile the binary")
+        );
+        assert_eq!(
+            targets[1].description.as_deref(),
+            Some("Run the unit tests")
+        );
+        assert_eq!(targets[2].description, None);
+    }
+
+    #[test]
+    fn test_parse_just_recipes() {
+        let contents = "\
+set shell := [\"bash\", \"-c\"]
+registry := \"ghcr.io\"
+alias d := deploy
+
+# Ship a release
+deploy env region='eu-1' *flags: build
+    ./deploy.sh {{env}} {{region}} {{flags}}
+
+@build:
+    cargo build
+
+_helper:
+    echo hidden
+
+[private]
+setup:
+    echo hidden
+
+url target=(registry + \"/app\") $token:
+    echo {{target}}
+";
+        let recipes = parse_just_recipes(contents);
+        let names: Vec<&str> = recipes.iter().map(|recipe| recipe.name.as_str()).collect();
+        assert_eq!(names, vec!["deploy", "build", "url"]);
+        assert_eq!(recipes[0].description.as_deref(), Some("Ship a release"));
+        assert_eq!(
+            recipes[0].params,
+            vec![
+                TaskParam {
+                    name: "env".to_string(),
+                    required: true,
+                    default: None,
+                },
+                TaskParam {
+                    name: "region".to_string(),
+                    required: false,
+                    default: Some("eu-1".to_string()),
+                },
+                TaskParam {
+                    name: "flags".to_string(),
+                    required: false,
+                    default: None,
+                },
+            ]
+        );
+        assert_eq!(recipes[2].params[0].default, None);
+        assert!(!recipes[2].params[0].required);
+        assert_eq!(recipes[2].params[1].name, "token");
+    }
+}
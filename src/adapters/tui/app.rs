@@ -1,29 +1,55 @@
 use crate::adapters::environments::FsEnvironmentRepository;
+use crate::concurrency::{self, GroupTicket};
 use crate::domain::Schema;
-use crate::history::HistoryEntry;
-use crate::lua_widget::{self, WidgetData};
-use crate::ports::{WorkspaceEntry, WorkspaceEntryKind};
+use crate::error::AppResult;
+use crate::favorites;
+use crate::history::{self, HistoryEntry};
+use crate::inventory::{self, Inventory};
+use crate::keymap::Keymap;
+use crate::lua_widget::{self, ExecPolicy, WidgetData};
+use crate::ports::{Interpreters, WorkspaceEntry, WorkspaceEntryKind};
+use crate::requirements;
 use crate::search_index::SearchIndex;
+use crate::tutorial::{self, Step};
 use crate::use_cases::{EnvironmentService, ScriptService};
 use crate::workspace::Workspace;
+use std::io;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, TryRecvError};
 
-pub(crate) use super::state::HistoryFocus;
 use super::state::{
-    EnvironmentState, FieldInputState, HistoryState, NavigationState, SearchState, WidgetLoadResult,
+    AfterPreflight, ComparisonRequest, ComparisonState, ComparisonTarget, EnvironmentState,
+    FieldInputState, HistoryState, JobsState, NavigationState, PickerKind, PipelineEntry,
+    PipelinesState, PlanReview, PlanState, PreflightFailure, PreflightRequest, PreflightState,
+    PromptState, RecentState, RunResultState, RunningState, SearchState, WidgetLoadResult,
+    WorkspacesState,
 };
+pub(crate) use super::state::{HistoryFocus, RerunScope};
 use super::theme::Theme;
 
+/// Scripts in the `g r` quick list, one per digit key.
+const RECENT_LIMIT: usize = 9;
+/// Bytes of each output log `f` loads: the first and last half, so a huge
+/// log cannot stall the screen.
+const FULL_OUTPUT_LIMIT: u64 = 8 * 1024 * 1024;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub(crate) enum Screen {
     ScriptSelect,
     Search,
     Environments,
+    EnvPicker,
     FieldInput,
     History,
     Running,
     RunResult,
+    Comparison,
+    Jobs,
+    Recent,
+    PlanReview,
+    Preflight,
+    Workspaces,
+    Pipelines,
     Error,
 }
 
@@ -32,9 +58,18 @@ pub(crate) struct SchemaPreview {
     pub(crate) name: String,
     pub(crate) description: Option<String>,
     pub(crate) tags: Vec<String>,
+    pub(crate) owner: Option<String>,
+    pub(crate) contact: Option<String>,
     pub(crate) fields: Vec<SchemaFieldPreview>,
     pub(crate) outputs: Vec<SchemaOutputPreview>,
     pub(crate) queue: Option<QueuePreview>,
+    /// Recent runs with the same args both passed and failed.
+    pub(crate) flaky: bool,
+    pub(crate) requires: Vec<String>,
+    /// Why each missing or outdated tool of `requires` fails its check.
+    pub(crate) missing_tools: Vec<String>,
+    /// Image of the container the script runs in.
+    pub(crate) runtime: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -75,7 +110,7 @@ pub(crate) struct SchemaFieldPreview {
     pub(crate) required: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum ExecutionStatus {
     Success,
     Failed(Option<i32>),
@@ -86,16 +121,37 @@ pub(crate) struct App<'a> {
     service: &'a ScriptService,
     pub(crate) workspace: Workspace,
     pub(crate) theme: Theme,
+    pub(crate) keys: Keymap,
     pub(crate) screen: Screen,
     env_return: Option<Screen>,
+    picker_return: Option<Screen>,
     search_index: SearchIndex,
     pub(crate) navigation: NavigationState,
     pub(crate) environment: EnvironmentState,
     pub(crate) search: SearchState,
     pub(crate) history: HistoryState,
     pub(crate) field_input: FieldInputState,
+    pub(crate) run_result: RunResultState,
+    pub(crate) running: RunningState,
+    /// A question a running script is waiting on.
+    pub(crate) prompt: PromptState,
+    pub(crate) comparison: ComparisonState,
+    pub(crate) jobs: JobsState,
+    pub(crate) recent: RecentState,
+    pub(crate) plan: PlanState,
+    pub(crate) preflight: PreflightState,
+    pub(crate) workspaces: WorkspacesState,
+    pub(crate) pipelines: PipelinesState,
+    /// Untruncated output loaded from disk, keyed by history entry timestamp.
+    pub(crate) full_output: Option<(i64, String)>,
     pub(crate) result: Option<(PathBuf, Vec<String>)>,
+    /// Shell command from the `:` prompt and the folder to run it in.
+    pub(crate) shell: Option<(PathBuf, String)>,
     pub(crate) should_quit: bool,
+    /// Whether the key list for the current screen is shown over it.
+    pub(crate) help_open: bool,
+    /// `omakure tutorial`: index of the current step in `tutorial::STEPS`.
+    pub(crate) tutorial: Option<usize>,
     pub(crate) run_output_scroll: u16,
     pub(crate) error_message: Option<String>,
 }
@@ -111,7 +167,9 @@ impl<'a> App<'a> {
     ) -> Self {
         let current_dir = workspace.root().to_path_buf();
         let navigation = NavigationState::new(current_dir, entries);
-        let history = HistoryState::new(history);
+        let config = crate::config::load_workspace_config(workspace.config_path());
+        let time_zone = config.time_zone();
+        let history = HistoryState::new(history, time_zone);
         let search_status = search_index.status();
         let search = SearchState::new(search_status);
         let environment = EnvironmentState::new();
@@ -120,19 +178,38 @@ impl<'a> App<'a> {
             service,
             workspace,
             theme,
+            keys: config.keymap(),
             screen: Screen::ScriptSelect,
             env_return: None,
+            picker_return: None,
             search_index,
             navigation,
             environment,
             search,
             history,
             field_input,
+            run_result: RunResultState::new(),
+            running: RunningState::new(),
+            prompt: PromptState::new(),
+            comparison: ComparisonState::new(),
+            jobs: JobsState::new(),
+            recent: RecentState::new(),
+            plan: PlanState::new(),
+            preflight: PreflightState::new(),
+            workspaces: WorkspacesState::new(),
+            pipelines: PipelinesState::new(),
+            full_output: None,
             result: None,
+            shell: None,
             should_quit: false,
+            help_open: false,
+            tutorial: None,
             run_output_scroll: 0,
             error_message: None,
         };
+        app.load_favorites();
+        app.refresh_flaky();
+        app.select_first_entry();
         app.start_widget_load();
         app.load_env_config();
         app.update_schema_preview();
@@ -140,15 +217,84 @@ impl<'a> App<'a> {
         app
     }
 
+    /// Selection indexes the favorites first, then the current folder's entries.
     pub(crate) fn selected_entry(&self) -> Option<&WorkspaceEntry> {
-        self.navigation.entries.get(self.navigation.selection)
+        let favorites = self.navigation.favorites.len();
+        match self.navigation.selection.checked_sub(favorites) {
+            Some(index) => self.navigation.entries.get(index),
+            None => self.navigation.favorites.get(self.navigation.selection),
+        }
+    }
+
+    pub(crate) fn entry_count(&self) -> usize {
+        self.navigation.favorites.len() + self.navigation.entries.len()
+    }
+
+    /// Pins or unpins the selected script.
+    pub(crate) fn toggle_favorite(&mut self) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        if entry.kind != WorkspaceEntryKind::Script {
+            return;
+        }
+        let relative = entry
+            .path
+            .strip_prefix(self.workspace.root())
+            .unwrap_or(&entry.path)
+            .to_path_buf();
+        let mut favorites = favorites::load(&self.workspace);
+        let pinned = favorites::toggle(&mut favorites, &relative);
+        if let Err(err) = favorites::save(&self.workspace, &favorites) {
+            self.error_message = Some(format!("Failed to save favorites: {}", err));
+            self.screen = Screen::Error;
+            return;
+        }
+        let was_favorite = self.navigation.selection < self.navigation.favorites.len();
+        self.load_favorites();
+        // Keep the cursor on the same folder entry as the list above it grows or shrinks.
+        if !was_favorite {
+            self.navigation.selection = if pinned {
+                self.navigation.selection + 1
+            } else {
+                self.navigation.selection.saturating_sub(1)
+            };
+        }
+        self.navigation.selection = self
+            .navigation
+            .selection
+            .min(self.entry_count().saturating_sub(1));
+        self.navigation
+            .list_state
+            .select((self.entry_count() > 0).then_some(self.navigation.selection));
+        self.update_schema_preview();
+    }
+
+    fn load_favorites(&mut self) {
+        let root = self.workspace.root();
+        self.navigation.favorites = favorites::load(&self.workspace)
+            .into_iter()
+            .map(|path| root.join(path))
+            .filter(|path| crate::runtime::source_file(path).is_file())
+            .map(|path| WorkspaceEntry {
+                path,
+                kind: WorkspaceEntryKind::Script,
+            })
+            .collect();
+    }
+
+    fn select_first_entry(&mut self) {
+        self.navigation.selection = 0;
+        self.navigation
+            .list_state
+            .select((self.entry_count() > 0).then_some(0));
     }
 
     pub(crate) fn move_selection(&mut self, delta: isize) {
-        if self.navigation.entries.is_empty() {
+        if self.entry_count() == 0 {
             return;
         }
-        let len = self.navigation.entries.len() as isize;
+        let len = self.entry_count() as isize;
         let mut new_index = self.navigation.selection as isize + delta;
         if new_index < 0 {
             new_index = 0;
@@ -175,6 +321,332 @@ impl<'a> App<'a> {
         self.screen = Screen::Environments;
     }
 
+    pub(crate) fn open_jobs(&mut self) {
+        self.refresh_jobs();
+        self.screen = Screen::Jobs;
+    }
+
+    pub(crate) fn refresh_jobs(&mut self) {
+        match concurrency::list_jobs(&self.workspace) {
+            Ok(jobs) => {
+                self.jobs.jobs = jobs;
+                self.jobs.error = None;
+            }
+            Err(err) => self.jobs.error = Some(format!("Failed to read job queue: {}", err)),
+        }
+    }
+
+    /// Whether `script` runs in the Terraform plan-then-apply mode.
+    pub(crate) fn is_terraform(&self, script: &Path) -> bool {
+        match &self.navigation.schema_cache {
+            Some((path, schema)) if path == script => schema.is_terraform(),
+            _ => self
+                .service
+                .load_schema(script)
+                .map(|schema| schema.is_terraform())
+                .unwrap_or(false),
+        }
+    }
+
+    /// Shows a finished plan run for approval; failed or unreadable plans stay
+    /// on the run result.
+    pub(crate) fn open_plan_review(
+        &mut self,
+        script: PathBuf,
+        args: Vec<String>,
+        plan_file: PathBuf,
+        entry: &HistoryEntry,
+    ) {
+        if !entry.success {
+            return;
+        }
+        let Some(summary) = crate::domain::parse_plan_summary(&entry.stdout) else {
+            self.run_result.notice =
+                Some("No \"Plan:\" line in the output, so there is nothing to apply".to_string());
+            return;
+        };
+        self.plan.review = Some(PlanReview {
+            script,
+            args,
+            plan_file,
+            summary,
+            output: crate::domain::strip_ansi(&entry.stdout),
+        });
+        self.plan.scroll = 0;
+        self.screen = Screen::PlanReview;
+    }
+
+    /// Hands the reviewed plan to the main loop for its apply run.
+    pub(crate) fn approve_plan(&mut self) {
+        match self.plan.review.take() {
+            Some(review) if review.summary.has_changes() => self.plan.apply = Some(review),
+            _ => self.discard_plan(),
+        }
+    }
+
+    pub(crate) fn discard_plan(&mut self) {
+        if self.plan.review.take().is_some() {
+            self.run_result.notice = Some("Plan discarded; nothing was applied".to_string());
+        }
+        self.screen = Screen::RunResult;
+    }
+
+    pub(crate) fn scroll_plan(&mut self, delta: i32) {
+        self.plan.scroll = (self.plan.scroll as i32 + delta).clamp(0, u16::MAX as i32) as u16;
+    }
+
+    /// Queues the credential checks `schema` asks for, unless they just passed.
+    fn needs_preflight(&mut self, script: &Path, schema: &Schema, then: AfterPreflight) -> bool {
+        if self.preflight.cleared.take().as_deref() == Some(script) {
+            return false;
+        }
+        let config = crate::config::load_workspace_config(self.workspace.config_path());
+        match crate::preflight::checks_for(schema, &config.preflight, self.workspace.root()) {
+            Ok(checks) if checks.is_empty() => false,
+            Ok(checks) => {
+                self.preflight.pending = Some(PreflightRequest {
+                    script: script.to_path_buf(),
+                    checks,
+                    then,
+                    return_to: self.screen,
+                });
+                true
+            }
+            Err(err) => {
+                self.error_message = Some(err);
+                self.screen = Screen::Error;
+                true
+            }
+        }
+    }
+
+    /// Continues opening the script once its checks ran; a failed check is
+    /// shown with its login offer instead.
+    pub(crate) fn finish_preflight(
+        &mut self,
+        request: PreflightRequest,
+        failed: Option<(crate::preflight::Check, String)>,
+    ) {
+        if let Some((check, message)) = failed {
+            self.preflight.failure = Some(PreflightFailure {
+                request,
+                check,
+                message,
+            });
+            self.screen = Screen::Preflight;
+            return;
+        }
+        self.screen = request.return_to;
+        self.preflight.cleared = Some(request.script.clone());
+        match request.then {
+            AfterPreflight::Open => self.load_schema(request.script),
+            AfterPreflight::Prefill(args) => {
+                self.load_schema(request.script);
+                self.prefill_form(&args);
+            }
+            AfterPreflight::Compare => self.compare_script(request.script),
+        }
+    }
+
+    /// Hands the failed check's login to the main loop, which checks again after.
+    pub(crate) fn accept_preflight_login(&mut self) {
+        if let Some(failure) = self.preflight.failure.take() {
+            if failure.check.login.is_some() {
+                self.preflight.login = Some(failure);
+            } else {
+                self.preflight.failure = Some(failure);
+            }
+        }
+    }
+
+    /// Opens the script anyway, e.g. when the check is wrong about the credentials.
+    pub(crate) fn skip_preflight(&mut self) {
+        if let Some(failure) = self.preflight.failure.take() {
+            self.finish_preflight(failure.request, None);
+        }
+    }
+
+    pub(crate) fn cancel_preflight(&mut self) {
+        if let Some(failure) = self.preflight.failure.take() {
+            self.screen = failure.request.return_to;
+        }
+    }
+
+    /// Lists the last scripts run that still exist in the workspace.
+    pub(crate) fn open_recent(&mut self) {
+        let root = self.workspace.root();
+        self.recent.entries = history::recent_scripts(&self.history.entries, usize::MAX)
+            .into_iter()
+            .filter(|entry| crate::runtime::source_file(&root.join(&entry.script)).is_file())
+            .take(RECENT_LIMIT)
+            .cloned()
+            .collect();
+        self.recent.selection = 0;
+        self.recent
+            .table_state
+            .select((!self.recent.entries.is_empty()).then_some(0));
+        self.screen = Screen::Recent;
+    }
+
+    pub(crate) fn move_recent_selection(&mut self, delta: isize) {
+        if self.recent.entries.is_empty() {
+            return;
+        }
+        let last = self.recent.entries.len() as isize - 1;
+        self.recent.selection = (self.recent.selection as isize + delta).clamp(0, last) as usize;
+        self.recent.table_state.select(Some(self.recent.selection));
+    }
+
+    /// Opens the recent script at `index`, as if picked from the list.
+    pub(crate) fn open_recent_script(&mut self, index: usize) {
+        let Some(entry) = self.recent.entries.get(index) else {
+            return;
+        };
+        let script = self.workspace.root().join(&entry.script);
+        self.load_schema(script);
+    }
+
+    /// Lists the named workspaces, with the current one selected.
+    pub(crate) fn open_workspaces(&mut self) {
+        let profiles = crate::workspace_profiles::load();
+        self.workspaces.entries = profiles.workspaces.into_iter().collect();
+        let current = self
+            .workspaces
+            .entries
+            .iter()
+            .position(|(_, dir)| dir == self.workspace.root());
+        self.workspaces.selection = current.unwrap_or(0);
+        self.workspaces
+            .table_state
+            .select((!self.workspaces.entries.is_empty()).then_some(self.workspaces.selection));
+        self.screen = Screen::Workspaces;
+    }
+
+    pub(crate) fn move_workspace_selection(&mut self, delta: isize) {
+        if self.workspaces.entries.is_empty() {
+            return;
+        }
+        let last = self.workspaces.entries.len() as isize - 1;
+        self.workspaces.selection =
+            (self.workspaces.selection as isize + delta).clamp(0, last) as usize;
+        self.workspaces
+            .table_state
+            .select(Some(self.workspaces.selection));
+    }
+
+    /// Leaves the TUI to start it again in the selected workspace.
+    pub(crate) fn switch_workspace(&mut self) {
+        let Some((_, dir)) = self.workspaces.entries.get(self.workspaces.selection) else {
+            return;
+        };
+        if dir == self.workspace.root() {
+            self.screen = Screen::ScriptSelect;
+            return;
+        }
+        self.workspaces.switch_to = Some(dir.clone());
+        self.should_quit = true;
+    }
+
+    /// Lists the pipeline files, then the scripts with `DependsOn`.
+    pub(crate) fn open_pipelines(&mut self) {
+        let mut entries: Vec<PipelineEntry> =
+            crate::pipelines::list(&self.workspace.pipelines_dir())
+                .into_iter()
+                .map(|(name, pipeline)| PipelineEntry { name, pipeline })
+                .collect();
+        let root = self.workspace.root();
+        for script in self.service.scripts_with_dependencies().unwrap_or_default() {
+            entries.push(PipelineEntry {
+                name: self.display_path(&script),
+                pipeline: self
+                    .service
+                    .dependency_pipeline(root, &script)
+                    .map_err(|err| err.to_string()),
+            });
+        }
+        self.pipelines.entries = entries;
+        self.pipelines.selection = 0;
+        self.pipelines.error = None;
+        self.pipelines
+            .table_state
+            .select((!self.pipelines.entries.is_empty()).then_some(0));
+        self.screen = Screen::Pipelines;
+    }
+
+    pub(crate) fn move_pipeline_selection(&mut self, delta: isize) {
+        if self.pipelines.entries.is_empty() {
+            return;
+        }
+        let last = self.pipelines.entries.len() as isize - 1;
+        self.pipelines.selection =
+            (self.pipelines.selection as isize + delta).clamp(0, last) as usize;
+        self.pipelines
+            .table_state
+            .select(Some(self.pipelines.selection));
+    }
+
+    /// Asks the main loop to run the selected pipeline.
+    pub(crate) fn run_selected_pipeline(&mut self) {
+        let Some(entry) = self.pipelines.entries.get(self.pipelines.selection) else {
+            return;
+        };
+        match &entry.pipeline {
+            Ok(pipeline) => {
+                self.pipelines.error = None;
+                self.pipelines.request = Some(pipeline.clone());
+            }
+            Err(err) => self.pipelines.error = Some(err.clone()),
+        }
+    }
+
+    pub(crate) fn open_command_line(&mut self) {
+        self.navigation.command_line = Some(String::new());
+    }
+
+    /// Queues action `index` of the folder's widget, run like a script
+    /// from the list with the action's arguments.
+    pub(crate) fn run_widget_action(&mut self, index: usize) {
+        let Some(action) = self
+            .navigation
+            .widget
+            .as_ref()
+            .and_then(|widget| widget.actions.get(index))
+            .cloned()
+        else {
+            return;
+        };
+        match self
+            .service
+            .find_script(self.workspace.root(), &action.script)
+        {
+            Ok(script) => self.result = Some((script, action.args)),
+            Err(err) => {
+                self.error_message = Some(format!("Widget action {}: {}", action.label, err));
+                self.screen = Screen::Error;
+            }
+        }
+    }
+
+    pub(crate) fn submit_command_line(&mut self) {
+        let Some(line) = self.navigation.command_line.take() else {
+            return;
+        };
+        let line = line.trim();
+        if !line.is_empty() {
+            self.shell = Some((self.navigation.current_dir.clone(), line.to_string()));
+        }
+    }
+
+    /// Variables of the active env file, injected into ad-hoc shell commands;
+    /// an error when the active file cannot be read.
+    pub(crate) fn active_env_vars(&self) -> AppResult<Vec<(String, String)>> {
+        let service = self.environment_service();
+        match service.load_environment_config()?.active {
+            Some(name) => service.load_env_vars(&name),
+            None => Ok(Vec::new()),
+        }
+    }
+
     pub(crate) fn exit_envs(&mut self) {
         self.screen = self.env_return.unwrap_or(Screen::ScriptSelect);
         self.env_return = None;
@@ -283,6 +755,12 @@ impl<'a> App<'a> {
         };
 
         match entry.kind {
+            WorkspaceEntryKind::Directory if self.navigation.tree => {
+                if !self.navigation.expanded.remove(&entry.path) {
+                    self.navigation.expanded.insert(entry.path.clone());
+                }
+                self.reload_tree(&entry.path);
+            }
             WorkspaceEntryKind::Directory => {
                 self.navigation.current_dir = entry.path;
                 self.refresh_entries();
@@ -293,7 +771,135 @@ impl<'a> App<'a> {
         }
     }
 
+    /// Switches between the folder list and the tree, keeping the selected entry.
+    pub(crate) fn toggle_tree(&mut self) {
+        let root = self.workspace.root().to_path_buf();
+        let selected = self.selected_entry().map(|entry| entry.path.clone());
+        self.navigation.tree = !self.navigation.tree;
+        if self.navigation.tree {
+            // Opens the folders down to the selection so it stays in view.
+            let focus = selected
+                .clone()
+                .unwrap_or_else(|| self.navigation.current_dir.clone());
+            for folder in focus.ancestors().skip(1) {
+                if folder == root || !folder.starts_with(&root) {
+                    break;
+                }
+                self.navigation.expanded.insert(folder.to_path_buf());
+            }
+            self.navigation.current_dir = root;
+        } else if let Some(parent) = selected.as_deref().and_then(Path::parent) {
+            if parent.starts_with(&root) {
+                self.navigation.current_dir = parent.to_path_buf();
+            }
+        }
+        self.refresh_entries();
+        if let Some(path) = selected {
+            self.select_path(&path);
+        }
+    }
+
+    /// Tree mode: opens the selected folder.
+    pub(crate) fn expand_selected(&mut self) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        if !self.navigation.tree || entry.kind != WorkspaceEntryKind::Directory {
+            return;
+        }
+        let path = entry.path.clone();
+        if self.navigation.expanded.insert(path.clone()) {
+            self.reload_tree(&path);
+        }
+    }
+
+    /// Tree mode: closes the selected folder, or moves to the folder holding
+    /// the selection.
+    fn collapse_selected(&mut self) {
+        let Some(path) = self.selected_entry().map(|entry| entry.path.clone()) else {
+            return;
+        };
+        if self.navigation.expanded.remove(&path) {
+            self.reload_tree(&path);
+        } else if let Some(parent) = path.parent() {
+            if parent != self.workspace.root() {
+                self.select_path(parent);
+            }
+        }
+    }
+
+    /// Relists the tree after a folder opened or closed, keeping the cursor on `path`.
+    fn reload_tree(&mut self, path: &Path) {
+        match self.list_tree() {
+            Ok((entries, depths)) => {
+                self.navigation.entries = entries;
+                self.navigation.depths = depths;
+                self.select_path(path);
+            }
+            Err(err) => {
+                self.error_message = Some(err.to_string());
+                self.screen = Screen::Error;
+            }
+        }
+    }
+
+    /// The workspace root's entries, with each open folder's entries listed
+    /// under it.
+    fn list_tree(&self) -> io::Result<(Vec<WorkspaceEntry>, Vec<usize>)> {
+        let mut entries = Vec::new();
+        let mut depths = Vec::new();
+        self.list_tree_level(self.workspace.root(), 0, &mut entries, &mut depths)?;
+        Ok((entries, depths))
+    }
+
+    fn list_tree_level(
+        &self,
+        dir: &Path,
+        depth: usize,
+        entries: &mut Vec<WorkspaceEntry>,
+        depths: &mut Vec<usize>,
+    ) -> io::Result<()> {
+        for entry in self.service.list_entries(dir)? {
+            let open = entry.kind == WorkspaceEntryKind::Directory
+                && self.navigation.expanded.contains(&entry.path);
+            let path = entry.path.clone();
+            entries.push(entry);
+            depths.push(depth);
+            if open {
+                self.list_tree_level(&path, depth + 1, entries, depths)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves the cursor to the folder entry at `path`; the cursor stays put,
+    /// within bounds, when it is not listed.
+    fn select_path(&mut self, path: &Path) {
+        let favorites = self.navigation.favorites.len();
+        let index = match self
+            .navigation
+            .entries
+            .iter()
+            .position(|entry| entry.path == path)
+        {
+            Some(index) => favorites + index,
+            None => self
+                .navigation
+                .selection
+                .min(self.entry_count().saturating_sub(1)),
+        };
+        self.navigation.selection = index;
+        self.navigation
+            .list_state
+            .select((self.entry_count() > 0).then_some(index));
+        self.update_schema_preview();
+    }
+
     pub(crate) fn navigate_up(&mut self) {
+        if self.navigation.tree {
+            self.collapse_selected();
+            return;
+        }
         if self.navigation.current_dir == self.workspace.root() {
             return;
         }
@@ -325,13 +931,623 @@ impl<'a> App<'a> {
         self.history.entries.insert(0, entry);
         self.history.selection = 0;
         self.history.table_state.select(Some(0));
+        self.refresh_flaky();
+    }
+
+    /// Re-reads which scripts are flaky from history, for the list badges.
+    fn refresh_flaky(&mut self) {
+        let root = self.workspace.root();
+        self.navigation.flaky = history::flaky_scripts(&self.history.entries)
+            .into_iter()
+            .map(|script| root.join(script))
+            .collect();
+        // The preview shows the badge too; rebuild it on the next update.
+        self.navigation.preview_script = None;
+    }
+
+    /// Looks up what usually follows the last run, and who owns it, for the run
+    /// result screen.
+    pub(crate) fn refresh_suggestion(&mut self) {
+        let script = self
+            .history
+            .entries
+            .first()
+            .filter(|entry| entry.command.is_none())
+            .map(|entry| entry.script.clone());
+        self.run_result.suggestion = script
+            .as_ref()
+            .and_then(|script| history::suggest_next(&self.history.entries, script));
+        self.run_result.owner = script
+            .as_ref()
+            .and_then(|script| self.script_owner(&self.workspace.root().join(script)));
+    }
+
+    /// Opens the suggested next script, as if picked from the list.
+    pub(crate) fn accept_suggestion(&mut self) {
+        let Some(suggestion) = self.run_result.suggestion.take() else {
+            return;
+        };
+        let script = self.workspace.root().join(&suggestion);
+        if !crate::runtime::source_file(&script).is_file() {
+            self.run_result.notice = Some(format!("Script not found: {}", suggestion.display()));
+            return;
+        }
+        self.load_schema(script);
     }
 
     pub(crate) fn current_history_entry(&self) -> Option<&HistoryEntry> {
         self.history.entries.get(self.history.selection)
     }
 
+    /// Asks for confirmation before re-running the selected history entry.
+    pub(crate) fn request_history_rerun(&mut self, scope: RerunScope) {
+        let Some(entry) = self.current_history_entry() else {
+            return;
+        };
+        let checked = match scope {
+            RerunScope::All if entry.command.is_some() => Ok(()),
+            RerunScope::All => self.entry_target(entry).map(|_| ()),
+            RerunScope::Failed => failed_runs_check(entry),
+        };
+        match checked {
+            Ok(()) => {
+                self.history.confirm_rerun = Some(scope);
+                self.history.notice = None;
+            }
+            Err(message) => self.history.notice = Some(message),
+        }
+    }
+
+    pub(crate) fn confirm_history_rerun(&mut self) {
+        let Some(scope) = self.history.confirm_rerun.take() else {
+            return;
+        };
+        let Some(entry) = self.current_history_entry() else {
+            return;
+        };
+        let entry = entry.clone();
+        let result = match scope {
+            RerunScope::All => self.rerun_entry(&entry),
+            RerunScope::Failed => self.rerun_failed(&entry),
+        };
+        if let Err(message) = result {
+            self.history.notice = Some(message);
+        }
+    }
+
+    pub(crate) fn cancel_history_rerun(&mut self) {
+        self.history.confirm_rerun = None;
+    }
+
+    /// Re-runs the last run with the same args.
+    pub(crate) fn rerun_last(&mut self) {
+        let Some(entry) = self.history.entries.first().cloned() else {
+            return;
+        };
+        if let Err(message) = self.rerun_entry(&entry) {
+            self.run_result.notice = Some(message);
+        }
+    }
+
+    /// Queues `entry` to run again: a single run, or every environment of a comparison.
+    fn rerun_entry(&mut self, entry: &HistoryEntry) -> Result<(), String> {
+        if let Some(command) = &entry.command {
+            self.shell = Some((self.navigation.current_dir.clone(), command.clone()));
+            return Ok(());
+        }
+        let (script, args) = self.entry_target(entry)?;
+        if entry.runs.is_empty() {
+            self.result = Some((script, args));
+            return Ok(());
+        }
+        let targets = self.group_targets(&script, entry.runs.iter())?;
+        self.comparison.request = Some(ComparisonRequest {
+            script,
+            targets,
+            merge_into: None,
+        });
+        Ok(())
+    }
+
+    /// Queues the failed runs of a grouped entry, to be merged back into it.
+    fn rerun_failed(&mut self, entry: &HistoryEntry) -> Result<(), String> {
+        failed_runs_check(entry)?;
+        let (script, _) = self.entry_target(entry)?;
+        let targets = self.group_targets(&script, entry.runs.iter().filter(|run| !run.success))?;
+        self.comparison.request = Some(ComparisonRequest {
+            script,
+            targets,
+            merge_into: Some(entry.clone()),
+        });
+        Ok(())
+    }
+
+    /// Same args and env file as the original runs, with the env file read again.
+    fn group_targets<'r>(
+        &self,
+        script: &Path,
+        runs: impl Iterator<Item = &'r history::GroupRun>,
+    ) -> Result<Vec<ComparisonTarget>, String> {
+        let service = self.environment_service();
+        let mut inventory: Option<Inventory> = None;
+        let mut targets = Vec::new();
+        for run in runs {
+            if run.host {
+                // Host runs use the active env file, like the run that recorded them.
+                if inventory.is_none() {
+                    inventory = Some(self.load_inventory()?);
+                }
+                let host = inventory
+                    .as_ref()
+                    .and_then(|inventory| inventory.host(&run.label))
+                    .ok_or_else(|| format!("Unknown host: {}", run.label))?;
+                targets.push(ComparisonTarget {
+                    label: run.label.clone(),
+                    args: run.args.clone(),
+                    env: self
+                        .service
+                        .active_env(script)
+                        .map_err(|err| err.to_string())?,
+                    host: Some(host),
+                });
+                continue;
+            }
+            let env = service
+                .load_env_vars(&run.label)
+                .map_err(|err| err.to_string())?;
+            targets.push(ComparisonTarget {
+                label: run.label.clone(),
+                args: run.args.clone(),
+                env,
+                host: None,
+            });
+        }
+        Ok(targets)
+    }
+
+    /// Swaps in an updated entry, matched by timestamp.
+    pub(crate) fn replace_history_entry(&mut self, entry: HistoryEntry) {
+        if let Some(existing) = self
+            .history
+            .entries
+            .iter_mut()
+            .find(|existing| existing.timestamp == entry.timestamp)
+        {
+            *existing = entry;
+        }
+        self.full_output = None;
+    }
+
+    /// Opens the environment picker for the selected script.
+    pub(crate) fn compare_selected(&mut self) {
+        let script = match self.selected_entry() {
+            Some(entry) if entry.kind == WorkspaceEntryKind::Script => entry.path.clone(),
+            _ => return,
+        };
+        self.compare_script(script);
+    }
+
+    fn compare_script(&mut self, script: PathBuf) {
+        self.load_schema_then(script, AfterPreflight::Compare);
+        if self.screen == Screen::Error || self.preflight.pending.is_some() {
+            return;
+        }
+        // Scripts without fields would start running right away.
+        self.result = None;
+        self.open_env_picker(Screen::ScriptSelect);
+    }
+
+    /// Opens the environment picker for the form's script, all env files checked.
+    pub(crate) fn open_env_picker(&mut self, return_to: Screen) {
+        if self.field_input.selected_script.is_none() {
+            return;
+        }
+        let entries = match self.environment_service().list_env_files() {
+            Ok(entries) => entries,
+            Err(err) => {
+                self.field_input.error = Some(err.to_string());
+                return;
+            }
+        };
+        self.comparison.kind = PickerKind::Envs;
+        self.comparison.choices = entries
+            .into_iter()
+            .map(|entry| (entry.name, true))
+            .collect();
+        self.show_picker(return_to);
+    }
+
+    /// Opens the inventory host picker for the form's script, nothing checked.
+    pub(crate) fn open_target_picker(&mut self, return_to: Screen) {
+        if self.field_input.selected_script.is_none() {
+            return;
+        }
+        if !self.field_input.targets {
+            self.field_input.error = Some("This script does not set Targets".to_string());
+            return;
+        }
+        let inventory = match self.load_inventory() {
+            Ok(inventory) => inventory,
+            Err(err) => {
+                self.field_input.error = Some(err);
+                return;
+            }
+        };
+        self.comparison.kind = PickerKind::Hosts;
+        self.comparison.choices = inventory
+            .choices()
+            .into_iter()
+            .map(|name| (name, false))
+            .collect();
+        self.show_picker(return_to);
+    }
+
+    fn show_picker(&mut self, return_to: Screen) {
+        self.comparison.selection = 0;
+        self.comparison
+            .list_state
+            .select((!self.comparison.choices.is_empty()).then_some(0));
+        self.comparison.error = None;
+        self.picker_return = Some(return_to);
+        self.screen = Screen::EnvPicker;
+    }
+
+    fn load_inventory(&self) -> Result<Inventory, String> {
+        inventory::load(&self.workspace.inventory_path())
+    }
+
+    pub(crate) fn close_env_picker(&mut self) {
+        match self.picker_return.take() {
+            Some(Screen::FieldInput) => self.screen = Screen::FieldInput,
+            _ => self.back_to_script_select(),
+        }
+    }
+
+    pub(crate) fn move_env_choice(&mut self, delta: isize) {
+        if self.comparison.choices.is_empty() {
+            return;
+        }
+        let last = self.comparison.choices.len() as isize - 1;
+        let next = (self.comparison.selection as isize + delta).clamp(0, last);
+        self.comparison.selection = next as usize;
+        self.comparison
+            .list_state
+            .select(Some(self.comparison.selection));
+    }
+
+    pub(crate) fn toggle_env_choice(&mut self) {
+        if let Some((_, checked)) = self.comparison.choices.get_mut(self.comparison.selection) {
+            *checked = !*checked;
+        }
+        self.comparison.error = None;
+    }
+
+    pub(crate) fn toggle_all_env_choices(&mut self) {
+        let check = !self.comparison.choices.iter().all(|(_, checked)| *checked);
+        for (_, checked) in &mut self.comparison.choices {
+            *checked = check;
+        }
+        self.comparison.error = None;
+    }
+
+    /// Builds one run per checked env file and hands them to the main loop.
+    ///
+    /// Each env file fills the fields it names and is injected into the
+    /// script's environment; other fields keep the form's values.
+    pub(crate) fn start_comparison(&mut self) {
+        let Some(script) = self.field_input.selected_script.clone() else {
+            return;
+        };
+        if self.comparison.kind == PickerKind::Hosts {
+            self.start_host_runs(script);
+            return;
+        }
+        let names: Vec<String> = self
+            .comparison
+            .choices
+            .iter()
+            .filter(|(_, checked)| *checked)
+            .map(|(name, _)| name.clone())
+            .collect();
+        if names.is_empty() {
+            self.comparison.error = Some("Select at least one environment".to_string());
+            return;
+        }
+
+        let path_style = crate::runtime::path_style(&script);
+        let service = self.environment_service();
+        let mut targets = Vec::new();
+        for name in names {
+            let env = match service.load_env_vars(&name) {
+                Ok(env) => env,
+                Err(err) => {
+                    self.comparison.error = Some(err.to_string());
+                    return;
+                }
+            };
+            let inputs: Vec<String> = self
+                .field_input
+                .fields
+                .iter()
+                .zip(&self.field_input.field_inputs)
+                .map(|(field, input)| {
+                    env.iter()
+                        .find(|(key, value)| {
+                            key.eq_ignore_ascii_case(&field.name) && !value.is_empty()
+                        })
+                        .map(|(_, value)| value.clone())
+                        .unwrap_or_else(|| input.clone())
+                })
+                .collect();
+            let args =
+                match crate::domain::build_args(&self.field_input.fields, &inputs, path_style) {
+                    Ok(args) => args,
+                    Err(err) => {
+                        self.comparison.error = Some(format!("{}: {}", name, err));
+                        return;
+                    }
+                };
+            targets.push(ComparisonTarget {
+                label: name,
+                args,
+                env,
+                host: None,
+            });
+        }
+        self.picker_return = None;
+        self.comparison.request = Some(ComparisonRequest {
+            script,
+            targets,
+            merge_into: None,
+        });
+    }
+
+    /// Builds one run per selected host, groups expanded, with the form's
+    /// values and the active env file.
+    fn start_host_runs(&mut self, script: PathBuf) {
+        let selection: Vec<String> = self
+            .comparison
+            .choices
+            .iter()
+            .filter(|(_, checked)| *checked)
+            .map(|(name, _)| name.clone())
+            .collect();
+        if selection.is_empty() {
+            self.comparison.error = Some("Select at least one host or group".to_string());
+            return;
+        }
+        let hosts = match self
+            .load_inventory()
+            .and_then(|inventory| inventory.resolve(&selection))
+        {
+            Ok(hosts) => hosts,
+            Err(err) => {
+                self.comparison.error = Some(err);
+                return;
+            }
+        };
+        let path_style = crate::runtime::path_style(&script);
+        let args = match crate::domain::build_args(
+            &self.field_input.fields,
+            &self.field_input.field_inputs,
+            path_style,
+        ) {
+            Ok(args) => args,
+            Err(err) => {
+                self.comparison.error = Some(err.to_string());
+                return;
+            }
+        };
+        let env = match self.service.active_env(&script) {
+            Ok(env) => env,
+            Err(err) => {
+                self.comparison.error = Some(err.to_string());
+                return;
+            }
+        };
+        let targets = hosts
+            .into_iter()
+            .map(|(name, host)| ComparisonTarget {
+                label: name,
+                args: args.clone(),
+                env: env.clone(),
+                host: Some(host),
+            })
+            .collect();
+        self.picker_return = None;
+        self.comparison.request = Some(ComparisonRequest {
+            script,
+            targets,
+            merge_into: None,
+        });
+    }
+
+    /// Shows a grouped entry side by side.
+    pub(crate) fn open_comparison(&mut self, entry: HistoryEntry) {
+        self.comparison.entry = Some(entry);
+        self.comparison.column_offset = 0;
+        self.comparison.notice = None;
+        self.reset_run_output_scroll();
+        self.screen = Screen::Comparison;
+    }
+
+    pub(crate) fn open_history_comparison(&mut self) {
+        match self.current_history_entry() {
+            Some(entry) if !entry.runs.is_empty() => {
+                let entry = entry.clone();
+                self.open_comparison(entry);
+            }
+            Some(_) => self.history.notice = Some("Not a comparison run".to_string()),
+            None => {}
+        }
+    }
+
+    pub(crate) fn rerun_comparison(&mut self, scope: RerunScope) {
+        let Some(entry) = self.comparison.entry.clone() else {
+            return;
+        };
+        let result = match scope {
+            RerunScope::All => self.rerun_entry(&entry),
+            RerunScope::Failed => self.rerun_failed(&entry),
+        };
+        if let Err(message) = result {
+            self.comparison.notice = Some(message);
+        }
+    }
+
+    pub(crate) fn scroll_comparison_columns(&mut self, delta: isize) {
+        let count = self
+            .comparison
+            .entry
+            .as_ref()
+            .map(|entry| entry.runs.len())
+            .unwrap_or(0);
+        // The same bound the widget scrolls to, so scrolling back responds at once.
+        let last = count.saturating_sub(self.comparison.visible_columns);
+        let next = self.comparison.column_offset as isize + delta;
+        self.comparison.column_offset = next.clamp(0, last as isize) as usize;
+    }
+
+    /// Opens the field form for the last run, prefilled with its args.
+    pub(crate) fn edit_last_run(&mut self) {
+        let Some(entry) = self.history.entries.first() else {
+            return;
+        };
+        if let Some(command) = entry.command.clone() {
+            self.back_to_script_select();
+            self.navigation.command_line = Some(command);
+            return;
+        }
+        let (script, args) = match self.entry_target(entry) {
+            Ok(target) => target,
+            Err(message) => {
+                self.run_result.notice = Some(message);
+                return;
+            }
+        };
+        self.load_schema_then(script, AfterPreflight::Prefill(args.clone()));
+        self.prefill_form(&args);
+    }
+
+    fn prefill_form(&mut self, args: &[String]) {
+        if self.screen != Screen::FieldInput {
+            return;
+        }
+        let previous = crate::domain::inputs_from_args(&self.field_input.fields, args);
+        for (input, value) in self.field_input.field_inputs.iter_mut().zip(previous) {
+            if !value.is_empty() {
+                *input = value;
+            }
+        }
+    }
+
+    pub(crate) fn open_last_artifacts(&mut self) {
+        let Some(entry) = self.history.entries.first() else {
+            return;
+        };
+        self.run_result.notice = Some(self.open_artifacts(entry));
+    }
+
+    pub(crate) fn open_history_artifacts(&mut self) {
+        let Some(entry) = self.current_history_entry() else {
+            return;
+        };
+        self.history.notice = Some(self.open_artifacts(entry));
+    }
+
+    /// Opens the artifacts folder of `entry` in the file manager.
+    fn open_artifacts(&self, entry: &HistoryEntry) -> String {
+        let dir = history::run_artifacts_dir(&self.workspace, entry);
+        let result = std::fs::create_dir_all(&dir).and_then(|_| crate::util::open_path(&dir));
+        match result {
+            Ok(()) => format!("Opened {}", self.display_path(&dir)),
+            Err(err) => format!("Failed to open {}: {}", self.display_path(&dir), err),
+        }
+    }
+
+    pub(crate) fn copy_last_command(&mut self) {
+        let Some(entry) = self.history.entries.first() else {
+            return;
+        };
+        let script = self.workspace.root().join(&entry.script);
+        let line = match &entry.command {
+            Some(command) => Ok(command.clone()),
+            None => crate::runtime::resolved_command(&script, &entry.args, &self.interpreters())
+                .map(|command| crate::util::shell_join(&command))
+                .map_err(|err| err.to_string()),
+        };
+        let result = line.and_then(|line| {
+            crate::util::copy_to_clipboard(&line)
+                .map(|_| line)
+                .map_err(|err| err.to_string())
+        });
+        self.run_result.notice = Some(match result {
+            Ok(line) => format!("Copied: {}", line),
+            Err(err) => format!("Copy failed: {}", err),
+        });
+    }
+
+    pub(crate) fn start_note(&mut self) {
+        if !self.history.entries.is_empty() {
+            self.run_result.note = Some(String::new());
+            self.run_result.notice = None;
+        }
+    }
+
+    pub(crate) fn save_note(&mut self) {
+        let Some(note) = self.run_result.note.take() else {
+            return;
+        };
+        let Some(entry) = self.history.entries.first() else {
+            return;
+        };
+        if note.trim().is_empty() {
+            return;
+        }
+        self.run_result.notice = Some(match history::append_note(&self.workspace, entry, &note) {
+            Ok(path) => format!("Note saved to {}", self.display_path(&path)),
+            Err(err) => format!("Failed to save note: {}", err),
+        });
+    }
+
+    /// Loads the full output logs of `entry`, up to `FULL_OUTPUT_LIMIT` per
+    /// stream, returning a notice when there are none.
+    pub(crate) fn load_full_output(&mut self, entry: &HistoryEntry) -> Option<String> {
+        match history::load_full_output(&self.workspace, entry, Some(FULL_OUTPUT_LIMIT)) {
+            Ok(Some(output)) => {
+                self.full_output = Some((entry.timestamp, output));
+                self.reset_run_output_scroll();
+                None
+            }
+            Ok(None) => Some("Output was not truncated".to_string()),
+            Err(err) => Some(format!("Failed to load full output: {}", err)),
+        }
+    }
+
+    /// Output to display for `entry`: the loaded full output, or the stored one.
+    pub(crate) fn entry_output(&self, entry: &HistoryEntry) -> String {
+        match &self.full_output {
+            Some((timestamp, output)) if *timestamp == entry.timestamp => output.clone(),
+            _ => history::format_output(entry),
+        }
+    }
+
+    /// Absolute script path and args for an entry, or a message if the script is gone.
+    fn entry_target(&self, entry: &HistoryEntry) -> Result<(PathBuf, Vec<String>), String> {
+        let script = self.workspace.root().join(&entry.script);
+        if !crate::runtime::source_file(&script).is_file() {
+            return Err(format!("Script not found: {}", self.display_path(&script)));
+        }
+        Ok((script, entry.args.clone()))
+    }
+
     pub(crate) fn load_schema(&mut self, script: PathBuf) {
+        self.load_schema_then(script, AfterPreflight::Open);
+    }
+
+    /// Loads `script` into the form, unless its credential checks have to run
+    /// first; then the main loop runs them and `then` picks up afterwards.
+    fn load_schema_then(&mut self, script: PathBuf, then: AfterPreflight) {
         let schema_result = match self.navigation.schema_cache.as_ref() {
             Some((path, schema)) if path == &script => Ok(schema.clone()),
             _ => self.service.load_schema(&script),
@@ -339,11 +1555,37 @@ impl<'a> App<'a> {
 
         match schema_result {
             Ok(mut schema) => {
+                if self.needs_preflight(&script, &schema, then) {
+                    return;
+                }
                 self.load_env_config();
                 schema.fields.sort_by_key(|field| field.order);
                 let tags = schema.tags.clone();
                 let outputs = schema.outputs.clone();
                 let queue = schema.queue.clone();
+                let limits = schema.limits.clone();
+                let owner = schema.owner.clone();
+                let contact = schema.contact.clone();
+                let expected_duration_seconds = schema.expected_duration_seconds;
+                let concurrency_group = schema.concurrency_group.clone();
+                let kind = schema.kind.clone();
+                let workflow = schema.workflow.clone();
+                let backend = schema.backend.clone();
+                let targets = schema.targets;
+                let remote_artifacts = schema.remote_artifacts.clone();
+                let preflight = schema.preflight.clone();
+                let env = schema.env.clone();
+                let active_env = schema.active_env;
+                let requires_env = schema.requires_env.clone();
+                let requires = schema.requires.clone();
+                let runtime = schema.runtime.clone();
+                let depends_on = schema.depends_on.clone();
+                let success_codes = schema.success_codes.clone();
+                let fail_regex = schema.fail_regex.clone();
+                let retry = schema.retry.clone();
+                let hooks = schema.hooks.clone();
+                let prompts = schema.prompts;
+                self.field_input.owner = schema.owner_label();
                 self.field_input.schema_name = Some(schema.name);
                 self.field_input.schema_description = schema.description;
                 self.field_input.fields = schema.fields;
@@ -351,19 +1593,48 @@ impl<'a> App<'a> {
                 self.field_input.field_inputs = self.build_field_inputs();
                 self.field_input.args.clear();
                 self.field_input.error = None;
+                self.field_input.dry_run = None;
                 self.field_input.selected_script = Some(script.clone());
+                self.field_input.targets = targets == Some(true);
                 self.navigation.schema_cache = Some((
                     script.clone(),
                     Schema {
+                        kind,
                         name: self.field_input.schema_name.clone().unwrap_or_default(),
                         description: self.field_input.schema_description.clone(),
                         tags,
+                        owner,
+                        contact,
+                        expected_duration_seconds,
+                        concurrency_group,
                         fields: self.field_input.fields.clone(),
                         outputs,
                         queue,
+                        limits,
+                        backend,
+                        targets,
+                        preflight,
+                        remote_artifacts,
+                        workflow,
+                        env,
+                        active_env,
+                        requires_env,
+                        requires,
+                        runtime,
+                        depends_on,
+                        success_codes,
+                        fail_regex,
+                        retry,
+                        hooks,
+                        prompts,
                     },
                 ));
-                if self.field_input.fields.is_empty() {
+                // An unreadable env file fails the run itself, with its error.
+                let env = self.service.active_env(&script).unwrap_or_default();
+                self.field_input.missing_env = self.service.missing_env(&script, &[], &env);
+                if self.field_input.fields.is_empty() && self.field_input.targets {
+                    self.open_target_picker(Screen::ScriptSelect);
+                } else if self.field_input.fields.is_empty() {
                     self.result = Some((script, Vec::new()));
                 } else {
                     self.screen = Screen::FieldInput;
@@ -400,6 +1671,7 @@ impl<'a> App<'a> {
         {
             value.push(ch);
             self.field_input.error = None;
+            self.field_input.dry_run = None;
         }
     }
 
@@ -411,6 +1683,7 @@ impl<'a> App<'a> {
         {
             value.pop();
             self.field_input.error = None;
+            self.field_input.dry_run = None;
         }
     }
 
@@ -420,36 +1693,71 @@ impl<'a> App<'a> {
             return;
         }
 
-        let mut args = Vec::new();
-        for (idx, field) in self.field_input.fields.iter().enumerate() {
-            let input = self
-                .field_input
-                .field_inputs
-                .get(idx)
-                .map(String::as_str)
-                .unwrap_or("");
-            match crate::domain::normalize_input(field, input) {
-                Ok(value) => {
-                    if let Some(value) = value {
-                        let arg = field
-                            .arg
-                            .clone()
-                            .unwrap_or_else(|| format!("--{}", field.name));
-                        args.push(arg);
-                        args.push(value);
-                    }
-                }
-                Err(message) => {
-                    self.field_input.error = Some(format!("{}: {}", field.name, message));
-                    self.field_input.field_index = idx;
+        let Some(args) = self.build_form_args() else {
+            return;
+        };
+        self.field_input.args = args;
+        self.field_input.error = None;
+        self.finish();
+    }
+
+    fn interpreters(&self) -> Interpreters {
+        crate::config::load_workspace_config(self.workspace.config_path()).interpreters()
+    }
+
+    /// Shows the command the form would run without running it.
+    pub(crate) fn preview_command(&mut self) {
+        let Some(script) = self.field_input.selected_script.clone() else {
+            return;
+        };
+        let Some(args) = self.build_form_args() else {
+            return;
+        };
+        let line = match self.service.remote_backend(&script) {
+            Some(runs_on) => format!(
+                "runs on {}, not this machine: {}",
+                runs_on,
+                crate::util::shell_join(&args)
+            ),
+            None => match crate::runtime::resolved_command(&script, &args, &self.interpreters()) {
+                Ok(command) => crate::util::shell_join(&command),
+                Err(err) => {
+                    self.field_input.error = Some(err.to_string());
                     return;
                 }
+            },
+        };
+        self.field_input.error = None;
+        self.field_input.dry_run = Some(if self.field_input.targets {
+            format!(
+                "{} (Ctrl+T runs it over SSH on the picked hosts instead)",
+                line
+            )
+        } else {
+            line
+        });
+    }
+
+    fn build_form_args(&mut self) -> Option<Vec<String>> {
+        let path_style = self
+            .field_input
+            .selected_script
+            .as_deref()
+            .map(crate::runtime::path_style)
+            .unwrap_or(crate::domain::PathStyle::Unix);
+        match crate::domain::build_args(
+            &self.field_input.fields,
+            &self.field_input.field_inputs,
+            path_style,
+        ) {
+            Ok(args) => Some(args),
+            Err(err) => {
+                self.field_input.error = Some(err.to_string());
+                self.field_input.dry_run = None;
+                self.field_input.field_index = err.index;
+                None
             }
         }
-
-        self.field_input.args = args;
-        self.field_input.error = None;
-        self.finish();
     }
 
     fn finish(&mut self) {
@@ -461,15 +1769,21 @@ impl<'a> App<'a> {
     }
 
     pub(crate) fn refresh_entries(&mut self) {
-        match self.service.list_entries(&self.navigation.current_dir) {
-            Ok(entries) => {
+        self.navigation.tool_checks.clear();
+        self.navigation.preview_script = None;
+        let listed = if self.navigation.tree {
+            self.list_tree()
+        } else {
+            self.service
+                .list_entries(&self.navigation.current_dir)
+                .map(|entries| (entries, Vec::new()))
+        };
+        match listed {
+            Ok((entries, depths)) => {
                 self.navigation.entries = entries;
-                self.navigation.selection = 0;
-                if self.navigation.entries.is_empty() {
-                    self.navigation.list_state.select(None);
-                } else {
-                    self.navigation.list_state.select(Some(0));
-                }
+                self.navigation.depths = depths;
+                self.load_favorites();
+                self.select_first_entry();
                 self.error_message = None;
                 self.start_widget_load();
                 self.update_schema_preview();
@@ -491,6 +1805,7 @@ impl<'a> App<'a> {
         self.screen = Screen::ScriptSelect;
         self.field_input.schema_name = None;
         self.field_input.schema_description = None;
+        self.field_input.owner = None;
         self.field_input.fields.clear();
         self.field_input.field_index = 0;
         self.field_input.field_inputs.clear();
@@ -513,6 +1828,38 @@ impl<'a> App<'a> {
         }
     }
 
+    /// Owner and contact from the script's schema, for pointing at who to ask.
+    pub(crate) fn script_owner(&self, script: &Path) -> Option<String> {
+        self.script_schema(script)
+            .and_then(|schema| schema.owner_label())
+    }
+
+    /// Marks a run of `script` as started, queueing it first when its schema names a
+    /// concurrency group. The returned ticket holds the group until dropped.
+    pub(crate) fn start_running(&mut self, script: &Path) -> Option<GroupTicket> {
+        let schema = self.script_schema(script);
+        let ticket = schema
+            .as_ref()
+            .and_then(|schema| schema.concurrency_group())
+            .and_then(|group| GroupTicket::enqueue(&self.workspace, group, script).ok());
+        let overdue_factor =
+            crate::config::load_workspace_config(self.workspace.config_path()).overdue_factor();
+        self.running = match &ticket {
+            Some(ticket) => {
+                RunningState::queued(schema, ticket.path().to_path_buf(), overdue_factor)
+            }
+            None => RunningState::started(schema, overdue_factor),
+        };
+        ticket
+    }
+
+    fn script_schema(&self, script: &Path) -> Option<Schema> {
+        match self.navigation.schema_cache.as_ref() {
+            Some((path, schema)) if path == script => Some(schema.clone()),
+            _ => self.service.load_schema(script).ok(),
+        }
+    }
+
     pub(crate) fn display_path(&self, path: &Path) -> String {
         path.strip_prefix(self.workspace.root())
             .unwrap_or(path)
@@ -522,13 +1869,15 @@ impl<'a> App<'a> {
 
     fn start_widget_load(&mut self) {
         let dir = self.navigation.current_dir.clone();
+        let policy =
+            crate::config::load_workspace_config(self.workspace.config_path()).widget_exec();
         let (tx, rx) = mpsc::channel();
         self.navigation.widget_loading = true;
         self.navigation.widget = None;
         self.navigation.widget_error = None;
         self.navigation.widget_receiver = Some(rx);
         std::thread::spawn(move || {
-            let (widget, error) = load_widget_state(&dir);
+            let (widget, error) = load_widget_state(&dir, &policy);
             let _ = tx.send(WidgetLoadResult { widget, error });
         });
     }
@@ -688,7 +2037,77 @@ impl<'a> App<'a> {
         }
     }
 
+    /// Moves the tutorial on once the current step's task is done.
+    pub(crate) fn advance_tutorial(&mut self) {
+        let Some(step) = self.tutorial.and_then(|index| tutorial::STEPS.get(index)) else {
+            return;
+        };
+        let done =
+            match step {
+                Step::CreateScript => tutorial::has_new_script(self.workspace.root()),
+                Step::FillForm => self.history.entries.iter().any(|entry| {
+                    entry.success && entry.script == Path::new(tutorial::GREET_SCRIPT)
+                }),
+                Step::ViewHistory => self.screen == Screen::History,
+                Step::ActivateEnv => self
+                    .environment
+                    .config
+                    .as_ref()
+                    .is_some_and(|config| config.active.is_some()),
+            };
+        if done {
+            self.tutorial = self.tutorial.map(|index| index + 1);
+        }
+    }
+
+    /// Shows or hides the source pane of the selected script.
+    pub(crate) fn toggle_source(&mut self) {
+        self.navigation.source_open = !self.navigation.source_open;
+        self.update_source_preview();
+    }
+
+    pub(crate) fn scroll_source(&mut self, delta: i32) {
+        let lines = match &self.navigation.source {
+            Some((_, Ok(source))) => source.lines().count(),
+            _ => 0,
+        };
+        self.navigation.source_scroll = (self.navigation.source_scroll as i32 + delta)
+            .clamp(0, lines.saturating_sub(1).min(u16::MAX as usize) as i32)
+            as u16;
+    }
+
+    /// Reads the selected script's source while the source pane is open.
+    fn update_source_preview(&mut self) {
+        if !self.navigation.source_open {
+            return;
+        }
+        let path = match self.selected_entry() {
+            Some(entry) if entry.kind == WorkspaceEntryKind::Script => entry.path.clone(),
+            _ => return,
+        };
+        if matches!(&self.navigation.source, Some((cached, _)) if *cached == path) {
+            return;
+        }
+        // An executable's source is binary or beside the point; show its schema.
+        // A target shows the Makefile or justfile it comes from.
+        let file = match crate::runtime::script_kind(&path) {
+            Some(crate::runtime::ScriptKind::Executable) => {
+                crate::runtime::sidecar_schema_paths(&path)
+                    .into_iter()
+                    .find(|sidecar| sidecar.is_file())
+                    .unwrap_or_else(|| path.clone())
+            }
+            _ => crate::runtime::source_file(&path),
+        };
+        let source = std::fs::read(&file)
+            .map(|data| String::from_utf8_lossy(&data).into_owned())
+            .map_err(|err| err.to_string());
+        self.navigation.source = Some((path, source));
+        self.navigation.source_scroll = 0;
+    }
+
     fn update_schema_preview(&mut self) {
+        self.update_source_preview();
         let (entry_path, entry_kind) = match self.selected_entry() {
             Some(entry) => (entry.path.clone(), entry.kind),
             None => {
@@ -713,7 +2132,13 @@ impl<'a> App<'a> {
         match self.service.load_schema(&entry_path) {
             Ok(mut schema) => {
                 schema.fields.sort_by_key(|field| field.order);
-                self.navigation.schema_preview = Some(schema_to_preview(&schema));
+                let mut preview = schema_to_preview(&schema);
+                preview.flaky = self.navigation.flaky.contains(&entry_path);
+                // A container brings its own tools.
+                if preview.runtime.is_none() {
+                    preview.missing_tools = self.missing_tools(&preview.requires);
+                }
+                self.navigation.schema_preview = Some(preview);
                 self.navigation.schema_preview_error = None;
                 self.navigation.preview_script = Some(entry_path.clone());
                 self.navigation.schema_cache = Some((entry_path, schema));
@@ -726,6 +2151,21 @@ impl<'a> App<'a> {
         }
     }
 
+    /// Failed checks of the `requires` tools, each checked once a session.
+    fn missing_tools(&mut self, requires: &[String]) -> Vec<String> {
+        requires
+            .iter()
+            .filter_map(|spec| {
+                self.navigation
+                    .tool_checks
+                    .entry(spec.clone())
+                    .or_insert_with(|| requirements::check(spec))
+                    .clone()
+                    .err()
+            })
+            .collect()
+    }
+
     fn refresh_search_results(&mut self) {
         match self.search_index.query(&self.search.query) {
             Ok(results) => {
@@ -761,6 +2201,17 @@ impl<'a> App<'a> {
                 self.search.error = Some(err);
             }
         }
+        let neighbors = [
+            self.search.selection.checked_sub(1),
+            Some(self.search.selection + 1),
+        ];
+        let paths = neighbors
+            .into_iter()
+            .flatten()
+            .filter_map(|index| self.search.results.get(index))
+            .map(|entry| entry.script_path.clone())
+            .collect();
+        self.search_index.prefetch_details(paths);
     }
 }
 
@@ -776,8 +2227,18 @@ impl ExecutionStatus {
     }
 }
 
-fn load_widget_state(dir: &Path) -> (Option<WidgetData>, Option<String>) {
-    match lua_widget::load_widget(dir) {
+fn failed_runs_check(entry: &HistoryEntry) -> Result<(), String> {
+    if entry.runs.is_empty() {
+        return Err("Only grouped runs can re-run failed cases".to_string());
+    }
+    if entry.runs.iter().all(|run| run.success) {
+        return Err("No failed runs to re-run".to_string());
+    }
+    Ok(())
+}
+
+fn load_widget_state(dir: &Path, policy: &ExecPolicy) -> (Option<WidgetData>, Option<String>) {
+    match lua_widget::load_widget(dir, policy) {
         Ok(widget) => (widget, None),
         Err(err) => (None, Some(err)),
     }
@@ -847,8 +2308,14 @@ fn schema_to_preview(schema: &Schema) -> SchemaPreview {
         name: schema.name.clone(),
         description: schema.description.clone(),
         tags,
+        owner: schema.owner.clone(),
+        contact: schema.contact.clone(),
         fields,
         outputs,
         queue,
+        flaky: false,
+        requires: schema.requires.clone().unwrap_or_default(),
+        missing_tools: Vec::new(),
+        runtime: schema.runtime.as_ref().map(|runtime| runtime.image.clone()),
     }
 }
@@ -0,0 +1,118 @@
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::Terminal;
+use std::error::Error;
+use std::io;
+
+use super::theme::{self, Theme};
+use super::{resolve_theme, restore_terminal, setup_terminal};
+use crate::omaken_catalog::Catalog;
+use crate::workspace::Workspace;
+
+/// Lets the user pick one of the catalog entries at `choices`; returns the
+/// catalog position of the one picked, or `None` when they back out.
+/// `installed` entries are marked but can still be picked.
+pub fn pick_flavor(
+    workspace: &Workspace,
+    catalog: &Catalog,
+    choices: &[usize],
+    installed: &[String],
+) -> Result<Option<usize>, Box<dyn Error>> {
+    let theme = resolve_theme(workspace);
+    let mut terminal = setup_terminal()?;
+    let picked = pick_loop(&mut terminal, &theme, catalog, choices, installed);
+    restore_terminal(&mut terminal)?;
+    picked
+}
+
+fn pick_loop(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    theme: &Theme,
+    catalog: &Catalog,
+    choices: &[usize],
+    installed: &[String],
+) -> Result<Option<usize>, Box<dyn Error>> {
+    let mut list_state = ListState::default();
+    list_state.select((!choices.is_empty()).then_some(0));
+    loop {
+        terminal.draw(|frame| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Min(3),
+                    Constraint::Length(6),
+                    Constraint::Length(1),
+                ])
+                .split(frame.size());
+            let items: Vec<ListItem> = choices
+                .iter()
+                .map(|index| {
+                    let entry = &catalog.flavors[*index];
+                    let mut spans = vec![Span::raw(format!("{:>3}. {}", index + 1, entry.name))];
+                    if installed.contains(&entry.name) {
+                        spans.push(Span::styled(" (installed)", theme.text_muted()));
+                    }
+                    ListItem::new(Line::from(spans))
+                })
+                .collect();
+            let list = List::new(items)
+                .block(theme.block().title("Omaken catalog"))
+                .highlight_style(theme.selection_style())
+                .highlight_symbol(theme::selection_symbol_str());
+            frame.render_stateful_widget(list, chunks[0], &mut list_state);
+
+            let details = match list_state.selected().map(|selected| choices[selected]) {
+                Some(index) => {
+                    let entry = &catalog.flavors[index];
+                    let mut lines = vec![
+                        Line::from(entry.summary()),
+                        Line::from(Span::styled(entry.url.clone(), theme.text_secondary())),
+                    ];
+                    if let Some(reference) = &entry.reference {
+                        lines.push(Line::from(Span::styled(
+                            format!("ref {}", reference),
+                            theme.text_secondary(),
+                        )));
+                    }
+                    lines
+                }
+                None => vec![Line::from("No flavors in the catalog.")],
+            };
+            let details = Paragraph::new(details)
+                .block(theme.block().title("Details"))
+                .wrap(Wrap { trim: true });
+            frame.render_widget(details, chunks[1]);
+
+            let footer = Paragraph::new("Up/Down move, Enter install, Esc quit")
+                .style(theme.text_secondary());
+            frame.render_widget(footer, chunks[2]);
+        })?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let selected = list_state.selected();
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(None),
+            KeyCode::Enter => {
+                if let Some(selected) = selected {
+                    return Ok(Some(choices[selected]));
+                }
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                list_state.select(selected.map(|selected| selected.saturating_sub(1)));
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                list_state.select(selected.map(|selected| (selected + 1).min(choices.len() - 1)));
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,166 +1,728 @@
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
-use super::app::{App, HistoryFocus, Screen};
+use super::app::{App, HistoryFocus, RerunScope, Screen};
+use crate::domain::PromptKind;
+use crate::keymap::{Action, Chord, Keymap};
 
-pub(crate) fn handle_key_event(app: &mut App, key: KeyEvent) {
-    match app.screen {
-        Screen::ScriptSelect => handle_list_key(app, key),
-        Screen::Search => handle_search_key(app, key),
-        Screen::Environments => handle_envs_key(app, key),
-        Screen::FieldInput => handle_input_key(app, key),
-        Screen::History => handle_history_key(app, key),
-        Screen::Running => {}
-        Screen::RunResult => handle_run_result_key(app, key),
-        Screen::Error => handle_error_key(app, key),
-    }
+/// What triggers a binding.
+pub(crate) enum Keys {
+    /// An action from `[keys]` in `omakure.toml`.
+    Action(Action),
+    /// The back keys, then the quit keys.
+    Close,
+    /// Chords as `Chord::parse` reads them.
+    Fixed(&'static [&'static str]),
+    /// `1` to `9`.
+    Digits,
+    /// Any character typed without Ctrl or Alt.
+    Text,
+    Any,
 }
 
-fn handle_list_key(app: &mut App, key: KeyEvent) {
-    match key.code {
-        KeyCode::Char('s') | KeyCode::Char('S')
-            if key.modifiers.contains(KeyModifiers::CONTROL) =>
-        {
-            app.enter_search()
-        }
-        KeyCode::Char('e') | KeyCode::Char('E') if key.modifiers.contains(KeyModifiers::ALT) => {
-            app.enter_envs()
+impl Keys {
+    fn matches(&self, app: &App, key: &KeyEvent) -> bool {
+        match self {
+            Keys::Action(action) => app.keys.matches(*action, key),
+            Keys::Close => closes(app, key),
+            Keys::Fixed(chords) => chords.iter().any(|chord| {
+                Chord::parse(chord)
+                    .map(|chord| chord.matches(key))
+                    .unwrap_or(false)
+            }),
+            Keys::Digits => matches!(key.code, KeyCode::Char('1'..='9')),
+            Keys::Text => is_text(key),
+            Keys::Any => true,
         }
-        KeyCode::Char('q') => app.should_quit = true,
-        KeyCode::Esc => {
-            if app.navigation.current_dir == app.workspace.root() {
-                app.should_quit = true;
-            } else {
-                app.navigate_up();
+    }
+
+    /// How the help overlay shows the keys, e.g. `Down/j`.
+    pub(crate) fn label(&self, keys: &Keymap) -> String {
+        match self {
+            Keys::Action(action) => keys.labels(*action).join("/"),
+            Keys::Close => {
+                let mut labels = keys.labels(Action::Back);
+                labels.extend(keys.labels(Action::Quit));
+                labels.join("/")
             }
+            Keys::Fixed(chords) => chords
+                .iter()
+                .filter_map(|chord| Chord::parse(chord).ok())
+                .map(|chord| chord.label())
+                .collect::<Vec<_>>()
+                .join("/"),
+            Keys::Digits => "1-9".to_string(),
+            Keys::Text => "typing".to_string(),
+            Keys::Any => "any other key".to_string(),
         }
-        KeyCode::Char('r') | KeyCode::Char('R') | KeyCode::F(5) => app.refresh_entries(),
-        KeyCode::Char('i') | KeyCode::Char('I') | KeyCode::F(6) => app.refresh_status(),
-        KeyCode::Char('h') | KeyCode::Char('H') => {
-            app.screen = Screen::History;
-            app.history.focus = HistoryFocus::List;
-            app.reset_run_output_scroll();
-        }
-        KeyCode::Backspace | KeyCode::Left => app.navigate_up(),
-        _ if app.navigation.entries.is_empty() => {}
-        KeyCode::Down | KeyCode::Char('j') => app.move_selection(1),
-        KeyCode::Up | KeyCode::Char('k') => app.move_selection(-1),
-        KeyCode::Enter => app.enter_selected(),
-        _ => {}
     }
 }
 
-fn handle_search_key(app: &mut App, key: KeyEvent) {
-    match key.code {
-        KeyCode::Esc => app.screen = Screen::ScriptSelect,
-        KeyCode::Char('e') | KeyCode::Char('E') if key.modifiers.contains(KeyModifiers::ALT) => {
-            app.enter_envs()
-        }
-        KeyCode::Down | KeyCode::Char('j') => app.move_search_selection(1),
-        KeyCode::Up | KeyCode::Char('k') => app.move_search_selection(-1),
-        KeyCode::Enter => app.open_selected_search(),
-        KeyCode::Backspace => app.pop_search_char(),
-        KeyCode::Char(c)
-            if !key.modifiers.contains(KeyModifiers::CONTROL)
-                && !key.modifiers.contains(KeyModifiers::ALT) =>
-        {
-            app.append_search_char(c)
-        }
-        _ => {}
-    }
+/// A key binding: the keys, what the help overlay says, and what it does.
+///
+/// A screen's bindings are tried in order and the first match runs.
+pub(crate) struct Binding {
+    pub(crate) keys: Keys,
+    pub(crate) help: &'static str,
+    run: fn(&mut App, KeyEvent),
 }
 
-fn handle_input_key(app: &mut App, key: KeyEvent) {
-    match key.code {
-        KeyCode::Esc => app.back_to_script_select(),
-        KeyCode::Char('b') | KeyCode::Char('B')
-            if key.modifiers.contains(KeyModifiers::CONTROL) =>
-        {
-            app.back_to_script_select()
-        }
-        KeyCode::Enter => app.submit_form(),
-        KeyCode::Tab => app.move_field_selection(1),
-        KeyCode::BackTab => app.move_field_selection(-1),
-        KeyCode::Down => app.move_field_selection(1),
-        KeyCode::Up => app.move_field_selection(-1),
-        KeyCode::Backspace => app.pop_field_char(),
-        KeyCode::Char(c) => app.append_field_char(c),
-        _ => {}
-    }
+const fn bind(keys: Keys, help: &'static str, run: fn(&mut App, KeyEvent)) -> Binding {
+    Binding { keys, help, run }
 }
 
-fn handle_error_key(app: &mut App, key: KeyEvent) {
-    match key.code {
-        KeyCode::Char('q') | KeyCode::Esc => app.should_quit = true,
-        KeyCode::Enter => {
-            app.error_message = None;
-            app.screen = Screen::ScriptSelect;
-        }
+pub(crate) fn handle_key_event(app: &mut App, key: KeyEvent) {
+    if app.help_open {
+        app.help_open = false;
+        return;
+    }
+    let bindings = bindings(app);
+    let typing = bindings
+        .iter()
+        .any(|binding| matches!(binding.keys, Keys::Text))
+        && is_text(&key);
+    if !typing && is(app, Action::Help, &key) {
+        app.help_open = true;
+        return;
+    }
+    match app.screen {
+        Screen::History if app.history.confirm_rerun.is_none() => app.history.notice = None,
+        Screen::RunResult if app.run_result.note.is_none() => app.run_result.notice = None,
+        Screen::Comparison => app.comparison.notice = None,
         _ => {}
     }
+    if let Some(binding) = bindings
+        .iter()
+        .find(|binding| binding.keys.matches(app, &key))
+    {
+        (binding.run)(app, key);
+    }
 }
 
-fn handle_history_key(app: &mut App, key: KeyEvent) {
-    match app.history.focus {
-        HistoryFocus::List => match key.code {
-            KeyCode::Char('q') | KeyCode::Esc => app.screen = Screen::ScriptSelect,
-            KeyCode::Char('e') | KeyCode::Char('E')
-                if key.modifiers.contains(KeyModifiers::ALT) =>
-            {
-                app.enter_envs()
-            }
-            KeyCode::Down | KeyCode::Char('j') => app.move_history_selection(1),
-            KeyCode::Up | KeyCode::Char('k') => app.move_history_selection(-1),
-            KeyCode::Enter | KeyCode::Right => {
-                app.history.focus = HistoryFocus::Output;
-                app.reset_run_output_scroll();
-            }
-            _ => {}
+/// The bindings active on the current screen; the help overlay lists these.
+pub(crate) fn bindings(app: &App) -> &'static [Binding] {
+    match app.screen {
+        _ if app.prompt.is_open() => match app.prompt.open.as_ref().map(|open| open.prompt.kind) {
+            Some(PromptKind::Confirm) => PROMPT_CONFIRM_KEYS,
+            _ => PROMPT_VALUE_KEYS,
         },
-        HistoryFocus::Output => match key.code {
-            KeyCode::Char('q') => app.screen = Screen::ScriptSelect,
-            KeyCode::Esc | KeyCode::Left | KeyCode::Backspace => {
-                app.history.focus = HistoryFocus::List
-            }
-            KeyCode::Down | KeyCode::Char('j') => app.scroll_run_output(1),
-            KeyCode::Up | KeyCode::Char('k') => app.scroll_run_output(-1),
-            KeyCode::PageDown => app.scroll_run_output(10),
-            KeyCode::PageUp => app.scroll_run_output(-10),
-            KeyCode::Home => app.run_output_scroll = 0,
-            KeyCode::End => app.run_output_scroll = u16::MAX,
-            _ => {}
+        Screen::ScriptSelect if app.navigation.command_line.is_some() => COMMAND_LINE_KEYS,
+        Screen::ScriptSelect if app.navigation.pending_go => GO_KEYS,
+        Screen::ScriptSelect => LIST_KEYS,
+        Screen::Search => SEARCH_KEYS,
+        Screen::Environments => ENVS_KEYS,
+        Screen::EnvPicker => ENV_PICKER_KEYS,
+        Screen::FieldInput => INPUT_KEYS,
+        Screen::History if app.history.confirm_rerun.is_some() => HISTORY_CONFIRM_KEYS,
+        Screen::History => match app.history.focus {
+            HistoryFocus::List => HISTORY_LIST_KEYS,
+            HistoryFocus::Output => HISTORY_OUTPUT_KEYS,
         },
+        Screen::Running => &[],
+        Screen::RunResult if app.run_result.note.is_some() => NOTE_KEYS,
+        Screen::RunResult => RUN_RESULT_KEYS,
+        Screen::Comparison => COMPARISON_KEYS,
+        Screen::Jobs => JOBS_KEYS,
+        Screen::Recent => RECENT_KEYS,
+        Screen::Workspaces => WORKSPACES_KEYS,
+        Screen::Pipelines => PIPELINES_KEYS,
+        Screen::PlanReview => PLAN_REVIEW_KEYS,
+        Screen::Preflight => PREFLIGHT_KEYS,
+        Screen::Error => ERROR_KEYS,
     }
 }
 
-fn handle_run_result_key(app: &mut App, key: KeyEvent) {
-    match key.code {
-        KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => app.screen = Screen::ScriptSelect,
-        KeyCode::Char('h') | KeyCode::Char('H') => {
-            app.screen = Screen::History;
-            app.history.focus = HistoryFocus::List;
-            app.reset_run_output_scroll();
-        }
-        KeyCode::Down | KeyCode::Char('j') => app.scroll_run_output(1),
-        KeyCode::Up | KeyCode::Char('k') => app.scroll_run_output(-1),
-        KeyCode::PageDown => app.scroll_run_output(10),
-        KeyCode::PageUp => app.scroll_run_output(-10),
-        KeyCode::Home => app.run_output_scroll = 0,
-        _ => {}
-    }
+fn is(app: &App, action: Action, key: &KeyEvent) -> bool {
+    app.keys.matches(action, key)
+}
+
+/// Outside the script list, the quit keys close the screen like the back keys.
+fn closes(app: &App, key: &KeyEvent) -> bool {
+    is(app, Action::Back, key) || is(app, Action::Quit, key)
+}
+
+fn is_text(key: &KeyEvent) -> bool {
+    matches!(key.code, KeyCode::Char(_))
+        && !key.modifiers.contains(KeyModifiers::CONTROL)
+        && !key.modifiers.contains(KeyModifiers::ALT)
 }
 
-fn handle_envs_key(app: &mut App, key: KeyEvent) {
+fn typed(key: KeyEvent) -> char {
     match key.code {
-        KeyCode::Char('q') | KeyCode::Esc => app.exit_envs(),
-        KeyCode::Char('r') | KeyCode::Char('R') => app.refresh_status(),
-        KeyCode::Down | KeyCode::Char('j') => app.move_env_selection(1),
-        KeyCode::Up | KeyCode::Char('k') => app.move_env_selection(-1),
-        KeyCode::PageDown => app.scroll_env_preview(10),
-        KeyCode::PageUp => app.scroll_env_preview(-10),
-        KeyCode::Home => app.environment.preview_scroll = 0,
-        KeyCode::End => app.environment.preview_scroll = u16::MAX,
-        KeyCode::Enter => app.activate_selected_env(),
-        KeyCode::Char('d') | KeyCode::Char('D') => app.deactivate_env(),
-        _ => {}
+        KeyCode::Char(c) => c,
+        _ => '\0',
     }
 }
+
+fn open_history(app: &mut App) {
+    app.screen = Screen::History;
+    app.history.focus = HistoryFocus::List;
+    app.reset_run_output_scroll();
+}
+
+const LIST_KEYS: &[Binding] = &[
+    bind(Keys::Action(Action::Search), "search scripts", |app, _| {
+        app.enter_search()
+    }),
+    bind(Keys::Fixed(&["alt+e"]), "environments", |app, _| {
+        app.enter_envs()
+    }),
+    bind(Keys::Action(Action::Quit), "quit", |app, _| {
+        app.should_quit = true
+    }),
+    bind(
+        Keys::Action(Action::Back),
+        "up a folder, or quit at the root",
+        |app, _| {
+            if app.navigation.current_dir == app.workspace.root() {
+                app.should_quit = true;
+            } else {
+                app.navigate_up();
+            }
+        },
+    ),
+    bind(Keys::Action(Action::Refresh), "reload entries", |app, _| {
+        app.refresh_entries()
+    }),
+    bind(
+        Keys::Fixed(&["i", "I", "f6"]),
+        "reload status panel",
+        |app, _| app.refresh_status(),
+    ),
+    bind(Keys::Action(Action::History), "run history", |app, _| {
+        open_history(app)
+    }),
+    bind(Keys::Fixed(&["J"]), "queued jobs", |app, _| app.open_jobs()),
+    bind(
+        Keys::Fixed(&["g"]),
+        "then r: recent scripts, w: workspaces, p: pipelines",
+        |app, _| app.navigation.pending_go = true,
+    ),
+    bind(Keys::Digits, "run that widget action", |app, key| {
+        app.run_widget_action(typed(key) as usize - '1' as usize)
+    }),
+    bind(Keys::Fixed(&[":"]), "run a shell command here", |app, _| {
+        app.open_command_line()
+    }),
+    bind(
+        Keys::Fixed(&["backspace", "left"]),
+        "up a folder; in the tree, close it",
+        |app, _| app.navigate_up(),
+    ),
+    bind(Keys::Fixed(&["down", "j"]), "move down", |app, _| {
+        app.move_selection(1)
+    }),
+    bind(Keys::Fixed(&["up", "k"]), "move up", |app, _| {
+        app.move_selection(-1)
+    }),
+    bind(
+        Keys::Action(Action::Run),
+        "open folder or script",
+        |app, _| app.enter_selected(),
+    ),
+    bind(
+        Keys::Fixed(&["m"]),
+        "run in several environments",
+        |app, _| app.compare_selected(),
+    ),
+    bind(Keys::Fixed(&["*"]), "pin or unpin script", |app, _| {
+        app.toggle_favorite()
+    }),
+    bind(
+        Keys::Fixed(&["t"]),
+        "switch list and tree view",
+        |app, _| app.toggle_tree(),
+    ),
+    bind(
+        Keys::Fixed(&["right"]),
+        "open folder in the tree",
+        |app, _| app.expand_selected(),
+    ),
+    bind(
+        Keys::Fixed(&["v"]),
+        "show or hide the script source",
+        |app, _| app.toggle_source(),
+    ),
+    bind(
+        Keys::Fixed(&["pagedown"]),
+        "scroll the source down",
+        |app, _| app.scroll_source(10),
+    ),
+    bind(
+        Keys::Fixed(&["pageup"]),
+        "scroll the source up",
+        |app, _| app.scroll_source(-10),
+    ),
+];
+
+const COMMAND_LINE_KEYS: &[Binding] = &[
+    bind(
+        Keys::Fixed(&["enter"]),
+        "run in this folder with the active env",
+        |app, _| app.submit_command_line(),
+    ),
+    bind(Keys::Fixed(&["esc"]), "cancel", |app, _| {
+        app.navigation.command_line = None
+    }),
+    bind(Keys::Fixed(&["backspace"]), "delete character", |app, _| {
+        let Some(line) = app.navigation.command_line.as_mut() else {
+            return;
+        };
+        if line.pop().is_none() {
+            app.navigation.command_line = None;
+        }
+    }),
+    bind(Keys::Text, "edit the command", |app, key| {
+        if let Some(line) = app.navigation.command_line.as_mut() {
+            line.push(typed(key));
+        }
+    }),
+];
+
+const GO_KEYS: &[Binding] = &[
+    bind(Keys::Fixed(&["r"]), "recent scripts", |app, _| {
+        app.navigation.pending_go = false;
+        app.open_recent();
+    }),
+    bind(Keys::Fixed(&["w"]), "workspaces", |app, _| {
+        app.navigation.pending_go = false;
+        app.open_workspaces();
+    }),
+    bind(Keys::Fixed(&["p"]), "pipelines", |app, _| {
+        app.navigation.pending_go = false;
+        app.open_pipelines();
+    }),
+    bind(Keys::Any, "cancel", |app, _| {
+        app.navigation.pending_go = false
+    }),
+];
+
+const SEARCH_KEYS: &[Binding] = &[
+    bind(Keys::Action(Action::Back), "back", |app, _| {
+        app.screen = Screen::ScriptSelect
+    }),
+    bind(Keys::Fixed(&["alt+e"]), "environments", |app, _| {
+        app.enter_envs()
+    }),
+    bind(Keys::Fixed(&["down", "j"]), "move down", |app, _| {
+        app.move_search_selection(1)
+    }),
+    bind(Keys::Fixed(&["up", "k"]), "move up", |app, _| {
+        app.move_search_selection(-1)
+    }),
+    bind(Keys::Action(Action::Run), "open result", |app, _| {
+        app.open_selected_search()
+    }),
+    bind(Keys::Fixed(&["backspace"]), "delete character", |app, _| {
+        app.pop_search_char()
+    }),
+    bind(Keys::Text, "edit the query", |app, key| {
+        app.append_search_char(typed(key))
+    }),
+];
+
+const INPUT_KEYS: &[Binding] = &[
+    bind(Keys::Action(Action::Back), "back to scripts", |app, _| {
+        app.back_to_script_select()
+    }),
+    bind(Keys::Fixed(&["ctrl+b"]), "back to scripts", |app, _| {
+        app.back_to_script_select()
+    }),
+    bind(Keys::Fixed(&["ctrl+d"]), "preview command", |app, _| {
+        app.preview_command()
+    }),
+    bind(Keys::Fixed(&["ctrl+e"]), "pick environment", |app, _| {
+        app.open_env_picker(Screen::FieldInput)
+    }),
+    bind(Keys::Fixed(&["ctrl+t"]), "pick targets", |app, _| {
+        app.open_target_picker(Screen::FieldInput)
+    }),
+    bind(Keys::Action(Action::Run), "run", |app, _| app.submit_form()),
+    bind(Keys::Fixed(&["tab", "down"]), "next field", |app, _| {
+        app.move_field_selection(1)
+    }),
+    bind(
+        Keys::Fixed(&["backtab", "up"]),
+        "previous field",
+        |app, _| app.move_field_selection(-1),
+    ),
+    bind(Keys::Fixed(&["backspace"]), "delete character", |app, _| {
+        app.pop_field_char()
+    }),
+    bind(Keys::Text, "edit the field", |app, key| {
+        app.append_field_char(typed(key))
+    }),
+];
+
+const ERROR_KEYS: &[Binding] = &[
+    bind(Keys::Close, "quit", |app, _| app.should_quit = true),
+    bind(Keys::Fixed(&["enter"]), "dismiss", |app, _| {
+        app.error_message = None;
+        app.screen = Screen::ScriptSelect;
+    }),
+];
+
+const PROMPT_CONFIRM_KEYS: &[Binding] = &[
+    bind(Keys::Fixed(&["y", "Y"]), "answer yes", |app, _| {
+        app.prompt.answer_with("yes")
+    }),
+    bind(Keys::Fixed(&["n", "N"]), "answer no", |app, _| {
+        app.prompt.answer_with("no")
+    }),
+    bind(
+        Keys::Fixed(&["enter"]),
+        "give the default answer",
+        |app, _| app.prompt.answer_with(""),
+    ),
+    bind(
+        Keys::Fixed(&["esc"]),
+        "dismiss with the default",
+        |app, _| app.prompt.dismiss(),
+    ),
+];
+
+const PROMPT_VALUE_KEYS: &[Binding] = &[
+    bind(Keys::Fixed(&["enter"]), "send the answer", |app, _| {
+        app.prompt.submit()
+    }),
+    bind(
+        Keys::Fixed(&["esc"]),
+        "dismiss with the default",
+        |app, _| app.prompt.dismiss(),
+    ),
+    bind(Keys::Fixed(&["backspace"]), "delete character", |app, _| {
+        if let Some(open) = app.prompt.open.as_mut() {
+            open.input.pop();
+        }
+    }),
+    bind(Keys::Text, "type the answer", |app, key| {
+        if let Some(open) = app.prompt.open.as_mut() {
+            open.input.push(typed(key));
+        }
+    }),
+];
+
+const HISTORY_CONFIRM_KEYS: &[Binding] = &[
+    bind(Keys::Fixed(&["y", "Y", "enter"]), "rerun", |app, _| {
+        app.confirm_history_rerun()
+    }),
+    bind(Keys::Any, "cancel", |app, _| app.cancel_history_rerun()),
+];
+
+const HISTORY_LIST_KEYS: &[Binding] = &[
+    bind(Keys::Close, "back", |app, _| {
+        app.screen = Screen::ScriptSelect
+    }),
+    bind(Keys::Fixed(&["alt+e"]), "environments", |app, _| {
+        app.enter_envs()
+    }),
+    bind(Keys::Fixed(&["R"]), "rerun all shown", |app, _| {
+        app.request_history_rerun(RerunScope::All)
+    }),
+    bind(Keys::Fixed(&["F"]), "rerun failed shown", |app, _| {
+        app.request_history_rerun(RerunScope::Failed)
+    }),
+    bind(Keys::Fixed(&["v"]), "compare runs", |app, _| {
+        app.open_history_comparison()
+    }),
+    bind(Keys::Fixed(&["o"]), "open artifacts folder", |app, _| {
+        app.open_history_artifacts()
+    }),
+    bind(Keys::Fixed(&["down", "j"]), "move down", |app, _| {
+        app.move_history_selection(1)
+    }),
+    bind(Keys::Fixed(&["up", "k"]), "move up", |app, _| {
+        app.move_history_selection(-1)
+    }),
+    bind(Keys::Fixed(&["enter", "right"]), "view output", |app, _| {
+        app.history.focus = HistoryFocus::Output;
+        app.reset_run_output_scroll();
+    }),
+];
+
+const HISTORY_OUTPUT_KEYS: &[Binding] = &[
+    bind(Keys::Action(Action::Quit), "back to scripts", |app, _| {
+        app.screen = Screen::ScriptSelect
+    }),
+    bind(
+        Keys::Fixed(&["left", "backspace"]),
+        "back to list",
+        |app, _| app.history.focus = HistoryFocus::List,
+    ),
+    bind(Keys::Action(Action::Back), "back to list", |app, _| {
+        app.history.focus = HistoryFocus::List
+    }),
+    bind(Keys::Fixed(&["f"]), "load full output", |app, _| {
+        if let Some(entry) = app.current_history_entry().cloned() {
+            app.history.notice = app.load_full_output(&entry);
+        }
+    }),
+    bind(Keys::Fixed(&["o"]), "open artifacts folder", |app, _| {
+        app.open_history_artifacts()
+    }),
+    bind(Keys::Fixed(&["down", "j"]), "scroll down", |app, _| {
+        app.scroll_run_output(1)
+    }),
+    bind(Keys::Fixed(&["up", "k"]), "scroll up", |app, _| {
+        app.scroll_run_output(-1)
+    }),
+    bind(Keys::Fixed(&["pagedown"]), "page down", |app, _| {
+        app.scroll_run_output(10)
+    }),
+    bind(Keys::Fixed(&["pageup"]), "page up", |app, _| {
+        app.scroll_run_output(-10)
+    }),
+    bind(Keys::Fixed(&["home"]), "top", |app, _| {
+        app.run_output_scroll = 0
+    }),
+    bind(Keys::Fixed(&["end"]), "bottom", |app, _| {
+        app.run_output_scroll = u16::MAX
+    }),
+];
+
+const NOTE_KEYS: &[Binding] = &[
+    bind(Keys::Fixed(&["enter"]), "save note", |app, _| {
+        app.save_note()
+    }),
+    bind(Keys::Fixed(&["esc"]), "discard note", |app, _| {
+        app.run_result.note = None
+    }),
+    bind(Keys::Fixed(&["backspace"]), "delete character", |app, _| {
+        if let Some(note) = app.run_result.note.as_mut() {
+            note.pop();
+        }
+    }),
+    bind(Keys::Text, "edit the note", |app, key| {
+        if let Some(note) = app.run_result.note.as_mut() {
+            note.push(typed(key));
+        }
+    }),
+];
+
+const RUN_RESULT_KEYS: &[Binding] = &[
+    bind(Keys::Fixed(&["r"]), "run again", |app, _| app.rerun_last()),
+    bind(Keys::Fixed(&["e"]), "edit and run again", |app, _| {
+        app.edit_last_run()
+    }),
+    bind(Keys::Fixed(&["o"]), "open artifacts folder", |app, _| {
+        app.open_last_artifacts()
+    }),
+    bind(Keys::Fixed(&["c"]), "copy command", |app, _| {
+        app.copy_last_command()
+    }),
+    bind(Keys::Fixed(&["n"]), "add a note", |app, _| app.start_note()),
+    bind(Keys::Fixed(&["tab"]), "run suggested script", |app, _| {
+        app.accept_suggestion()
+    }),
+    bind(Keys::Fixed(&["f"]), "load full output", |app, _| {
+        if let Some(entry) = app.history.entries.first().cloned() {
+            app.run_result.notice = app.load_full_output(&entry);
+        }
+    }),
+    bind(Keys::Fixed(&["enter"]), "back to scripts", |app, _| {
+        app.screen = Screen::ScriptSelect
+    }),
+    bind(Keys::Close, "back to scripts", |app, _| {
+        app.screen = Screen::ScriptSelect
+    }),
+    bind(Keys::Action(Action::History), "run history", |app, _| {
+        open_history(app)
+    }),
+    bind(Keys::Fixed(&["down", "j"]), "scroll down", |app, _| {
+        app.scroll_run_output(1)
+    }),
+    bind(Keys::Fixed(&["up", "k"]), "scroll up", |app, _| {
+        app.scroll_run_output(-1)
+    }),
+    bind(Keys::Fixed(&["pagedown"]), "page down", |app, _| {
+        app.scroll_run_output(10)
+    }),
+    bind(Keys::Fixed(&["pageup"]), "page up", |app, _| {
+        app.scroll_run_output(-10)
+    }),
+    bind(Keys::Fixed(&["home"]), "top", |app, _| {
+        app.run_output_scroll = 0
+    }),
+];
+
+const ENVS_KEYS: &[Binding] = &[
+    bind(Keys::Close, "back", |app, _| app.exit_envs()),
+    bind(Keys::Action(Action::Refresh), "reload", |app, _| {
+        app.refresh_status()
+    }),
+    bind(Keys::Fixed(&["down", "j"]), "move down", |app, _| {
+        app.move_env_selection(1)
+    }),
+    bind(Keys::Fixed(&["up", "k"]), "move up", |app, _| {
+        app.move_env_selection(-1)
+    }),
+    bind(
+        Keys::Fixed(&["pagedown"]),
+        "scroll preview down",
+        |app, _| app.scroll_env_preview(10),
+    ),
+    bind(Keys::Fixed(&["pageup"]), "scroll preview up", |app, _| {
+        app.scroll_env_preview(-10)
+    }),
+    bind(Keys::Fixed(&["home"]), "preview top", |app, _| {
+        app.environment.preview_scroll = 0
+    }),
+    bind(Keys::Fixed(&["end"]), "preview bottom", |app, _| {
+        app.environment.preview_scroll = u16::MAX
+    }),
+    bind(Keys::Fixed(&["enter"]), "activate", |app, _| {
+        app.activate_selected_env()
+    }),
+    bind(Keys::Fixed(&["d", "D"]), "deactivate", |app, _| {
+        app.deactivate_env()
+    }),
+];
+
+const ENV_PICKER_KEYS: &[Binding] = &[
+    bind(Keys::Close, "cancel", |app, _| app.close_env_picker()),
+    bind(Keys::Fixed(&["down", "j"]), "move down", |app, _| {
+        app.move_env_choice(1)
+    }),
+    bind(Keys::Fixed(&["up", "k"]), "move up", |app, _| {
+        app.move_env_choice(-1)
+    }),
+    bind(Keys::Fixed(&["space"]), "toggle", |app, _| {
+        app.toggle_env_choice()
+    }),
+    bind(Keys::Fixed(&["a"]), "toggle all", |app, _| {
+        app.toggle_all_env_choices()
+    }),
+    bind(Keys::Fixed(&["enter"]), "run", |app, _| {
+        app.start_comparison()
+    }),
+];
+
+const COMPARISON_KEYS: &[Binding] = &[
+    bind(Keys::Fixed(&["enter"]), "back to scripts", |app, _| {
+        app.screen = Screen::ScriptSelect
+    }),
+    bind(Keys::Close, "back to scripts", |app, _| {
+        app.screen = Screen::ScriptSelect
+    }),
+    bind(Keys::Fixed(&["r"]), "run all again", |app, _| {
+        app.rerun_comparison(RerunScope::All)
+    }),
+    bind(Keys::Fixed(&["F"]), "run failed again", |app, _| {
+        app.rerun_comparison(RerunScope::Failed)
+    }),
+    bind(Keys::Action(Action::History), "run history", |app, _| {
+        open_history(app)
+    }),
+    bind(Keys::Fixed(&["left"]), "previous columns", |app, _| {
+        app.scroll_comparison_columns(-1)
+    }),
+    bind(Keys::Fixed(&["right"]), "next columns", |app, _| {
+        app.scroll_comparison_columns(1)
+    }),
+    bind(Keys::Fixed(&["down", "j"]), "scroll down", |app, _| {
+        app.scroll_run_output(1)
+    }),
+    bind(Keys::Fixed(&["up", "k"]), "scroll up", |app, _| {
+        app.scroll_run_output(-1)
+    }),
+    bind(Keys::Fixed(&["pagedown"]), "page down", |app, _| {
+        app.scroll_run_output(10)
+    }),
+    bind(Keys::Fixed(&["pageup"]), "page up", |app, _| {
+        app.scroll_run_output(-10)
+    }),
+    bind(Keys::Fixed(&["home"]), "top", |app, _| {
+        app.run_output_scroll = 0
+    }),
+];
+
+const JOBS_KEYS: &[Binding] = &[
+    bind(Keys::Close, "back", |app, _| {
+        app.screen = Screen::ScriptSelect
+    }),
+    bind(Keys::Action(Action::Refresh), "reload", |app, _| {
+        app.refresh_jobs()
+    }),
+];
+
+const RECENT_KEYS: &[Binding] = &[
+    bind(Keys::Close, "back", |app, _| {
+        app.screen = Screen::ScriptSelect
+    }),
+    bind(Keys::Fixed(&["down", "j"]), "move down", |app, _| {
+        app.move_recent_selection(1)
+    }),
+    bind(Keys::Fixed(&["up", "k"]), "move up", |app, _| {
+        app.move_recent_selection(-1)
+    }),
+    bind(Keys::Fixed(&["enter"]), "open", |app, _| {
+        app.open_recent_script(app.recent.selection)
+    }),
+    bind(Keys::Digits, "open that script", |app, key| {
+        app.open_recent_script(typed(key) as usize - '1' as usize)
+    }),
+];
+
+const WORKSPACES_KEYS: &[Binding] = &[
+    bind(Keys::Close, "back", |app, _| {
+        app.screen = Screen::ScriptSelect
+    }),
+    bind(Keys::Fixed(&["down", "j"]), "move down", |app, _| {
+        app.move_workspace_selection(1)
+    }),
+    bind(Keys::Fixed(&["up", "k"]), "move up", |app, _| {
+        app.move_workspace_selection(-1)
+    }),
+    bind(Keys::Fixed(&["enter"]), "switch to it", |app, _| {
+        app.switch_workspace()
+    }),
+];
+
+const PIPELINES_KEYS: &[Binding] = &[
+    bind(Keys::Close, "back", |app, _| {
+        app.screen = Screen::ScriptSelect
+    }),
+    bind(Keys::Fixed(&["down", "j"]), "move down", |app, _| {
+        app.move_pipeline_selection(1)
+    }),
+    bind(Keys::Fixed(&["up", "k"]), "move up", |app, _| {
+        app.move_pipeline_selection(-1)
+    }),
+    bind(Keys::Fixed(&["enter"]), "run it", |app, _| {
+        app.run_selected_pipeline()
+    }),
+];
+
+const PREFLIGHT_KEYS: &[Binding] = &[
+    bind(Keys::Fixed(&["y", "Y", "enter"]), "log in", |app, _| {
+        app.accept_preflight_login()
+    }),
+    bind(Keys::Fixed(&["s", "S"]), "continue anyway", |app, _| {
+        app.skip_preflight()
+    }),
+    bind(Keys::Fixed(&["n", "N"]), "cancel", |app, _| {
+        app.cancel_preflight()
+    }),
+    bind(Keys::Close, "cancel", |app, _| app.cancel_preflight()),
+];
+
+const PLAN_REVIEW_KEYS: &[Binding] = &[
+    bind(Keys::Fixed(&["y", "Y"]), "apply", |app, _| {
+        app.approve_plan()
+    }),
+    bind(Keys::Fixed(&["n", "N"]), "discard", |app, _| {
+        app.discard_plan()
+    }),
+    bind(Keys::Close, "discard", |app, _| app.discard_plan()),
+    bind(Keys::Fixed(&["down", "j"]), "scroll down", |app, _| {
+        app.scroll_plan(1)
+    }),
+    bind(Keys::Fixed(&["up", "k"]), "scroll up", |app, _| {
+        app.scroll_plan(-1)
+    }),
+    bind(Keys::Fixed(&["pagedown"]), "page down", |app, _| {
+        app.scroll_plan(10)
+    }),
+    bind(Keys::Fixed(&["pageup"]), "page up", |app, _| {
+        app.scroll_plan(-10)
+    }),
+];
@@ -1,12 +1,18 @@
 mod app;
+mod catalog;
 mod events;
 mod state;
+mod sync_conflicts;
 pub(crate) mod theme;
 mod ui;
 mod widgets;
 
+use crate::domain::{self, Pipeline};
+use crate::logging;
+use crate::preflight;
+use crate::runtime::path_style;
 use crate::search_index::SearchIndex;
-use crate::use_cases::ScriptService;
+use crate::use_cases::{PipelineExecutor, ScriptService};
 use crate::workspace::Workspace;
 use crossterm::event::{self, Event, KeyEventKind};
 use crossterm::terminal::{
@@ -15,15 +21,25 @@ use crossterm::terminal::{
 use crossterm::ExecutableCommand;
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
+use serde_json::json;
 use std::error::Error;
+use std::fs;
 use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus};
+use std::thread;
 use std::time::Duration;
 
-use crate::history;
+use crate::concurrency::GroupTicket;
+use crate::history::{self, HistoryEntry};
 use crate::theme_config;
 use app::{App, Screen};
+pub use catalog::pick_flavor;
 use events::handle_key_event;
-use theme::load_theme;
+pub(crate) use state::prompt_channel;
+use state::{PromptState, RunningState};
+pub use sync_conflicts::resolve_conflicts;
+use theme::{load_theme, Theme};
 use ui::{render_loading, render_ui};
 
 pub fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>, Box<dyn Error>> {
@@ -44,11 +60,8 @@ pub fn restore_terminal(
     Ok(())
 }
 
-pub fn run_app(
-    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
-    service: &ScriptService,
-    workspace: Workspace,
-) -> Result<(), Box<dyn Error>> {
+/// The workspace's theme, else the global one, else the default.
+fn resolve_theme(workspace: &Workspace) -> Theme {
     let theme_layout = theme_config::ensure_theme_layout().ok();
     let theme_dir = theme_layout
         .as_ref()
@@ -58,19 +71,60 @@ pub fn run_app(
         .and_then(|layout| theme_config::load_theme_name(&layout.config_path));
     let workspace_theme = theme_config::load_theme_name(workspace.config_path());
     let theme_name = workspace_theme.or(global_theme);
-    let theme = load_theme(theme_name.as_deref(), theme_dir);
+    load_theme(theme_name.as_deref(), theme_dir)
+}
+
+/// How a TUI session ended.
+pub struct Session {
+    /// History entries of the runs made, oldest first.
+    pub runs: Vec<HistoryEntry>,
+    /// Workspace picked in the switcher, to start the TUI again in.
+    pub switch_to: Option<PathBuf>,
+}
+
+/// Runs the TUI until it quits or switches workspace. `tutorial` shows the
+/// `omakure tutorial` steps; `prompt` receives the questions of the scripts
+/// `service` runs.
+pub(crate) fn run_app(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    service: &ScriptService,
+    workspace: Workspace,
+    tutorial: bool,
+    prompt: PromptState,
+) -> Result<Session, Box<dyn Error>> {
+    let started = history::timestamp_ms();
+    let theme = resolve_theme(&workspace);
     terminal.draw(|frame| render_loading(frame, &theme))?;
     let entries = service.list_entries(workspace.root())?;
     let history = history::load_entries(&workspace).unwrap_or_default();
     let search_index = SearchIndex::new(workspace.search_db_path());
-    search_index.start_background_rebuild(workspace.root().to_path_buf());
+    search_index
+        .start_background_rebuild(workspace.root().to_path_buf(), workspace.history_db_path());
     let mut app = App::new(service, workspace, entries, history, search_index, theme);
+    app.tutorial = tutorial.then_some(0);
+    app.prompt = prompt;
 
+    let mut shown_error: Option<String> = None;
     loop {
         if app.screen == Screen::Search {
             app.refresh_search_status();
         }
+        if app.screen == Screen::Jobs {
+            app.refresh_jobs();
+        }
         app.poll_widget_load();
+        app.advance_tutorial();
+        // Each error the TUI shows is logged once, so it can be traced later.
+        if app.error_message != shown_error {
+            if let Some(message) = &app.error_message {
+                logging::error(
+                    "tui",
+                    "Error shown",
+                    json!({ "message": message, "screen": format!("{:?}", app.screen) }),
+                );
+            }
+            shown_error = app.error_message.clone();
+        }
         let theme = app.theme.clone();
         terminal.draw(|frame| render_ui(frame, &mut app, &theme))?;
 
@@ -84,22 +138,289 @@ pub fn run_app(
         }
 
         if app.should_quit {
-            return Ok(());
+            let mut session: Vec<HistoryEntry> = app
+                .history
+                .entries
+                .iter()
+                .filter(|entry| entry.timestamp >= started)
+                .cloned()
+                .collect();
+            session.reverse();
+            return Ok(Session {
+                runs: session,
+                switch_to: app.workspaces.switch_to.take(),
+            });
+        }
+        if let Some(request) = app.preflight.pending.take() {
+            // An unreadable env file fails the run after the checks.
+            let env = app.active_env_vars().unwrap_or_default();
+            let dir = app.workspace.root().to_path_buf();
+            let names: Vec<&str> = request
+                .checks
+                .iter()
+                .map(|check| check.name.as_str())
+                .collect();
+            app.running = RunningState::shell(&format!("credential checks: {}", names.join(", ")));
+            let failed = run_while_drawing(terminal, &mut app, None, || {
+                request.checks.iter().find_map(|check| {
+                    preflight::run(check, &dir, &env)
+                        .err()
+                        .map(|message| (check.clone(), message))
+                })
+            })?;
+            app.finish_preflight(request, failed);
+        }
+        if let Some(mut failure) = app.preflight.login.take() {
+            let login = failure.check.login.as_ref().map(|login| login.command());
+            let status = match login {
+                Some(Ok(mut command)) => {
+                    command
+                        .current_dir(app.workspace.root())
+                        .envs(app.active_env_vars().unwrap_or_default());
+                    run_in_terminal(terminal, command)?.map_err(|err| err.to_string())
+                }
+                Some(Err(err)) => Err(err),
+                None => Err("no login configured".to_string()),
+            };
+            match status {
+                // Check again even after a failed login; the check has the last word.
+                Ok(_) => app.preflight.pending = Some(failure.request),
+                Err(err) => {
+                    failure.message = format!("login failed: {}", err);
+                    app.preflight.failure = Some(failure);
+                }
+            }
         }
         if let Some((script, args)) = app.result.take() {
-            app.screen = Screen::Running;
-            let theme = app.theme.clone();
-            terminal.draw(|frame| render_ui(frame, &mut app, &theme))?;
-            let run_result = service.run_script(&script, &args);
-            let entry = match run_result {
-                Ok(output) => history::success_entry(&app.workspace, &script, &args, output),
-                Err(err) => history::error_entry(&app.workspace, &script, &args, err.to_string()),
+            let log_dir = history::new_run_dir(&app.workspace, &script);
+            // Terraform scripts only plan here; applying waits for approval.
+            let plan_file = app
+                .is_terraform(&script)
+                .then(|| log_dir.join(domain::PLAN_FILE_NAME));
+            let mut env = Vec::new();
+            if let Some(plan_file) = &plan_file {
+                let _ = fs::create_dir_all(&log_dir);
+                env.extend(domain::phase_env("plan", plan_file));
+            }
+            let entry = run_single(terminal, &mut app, service, &script, &args, &log_dir, &env)?;
+            if let Some(plan_file) = plan_file {
+                app.open_plan_review(script, args, plan_file, &entry);
+            }
+        }
+        if let Some(review) = app.plan.apply.take() {
+            let log_dir = history::new_run_dir(&app.workspace, &review.script);
+            let env = domain::phase_env("apply", &review.plan_file);
+            run_single(
+                terminal,
+                &mut app,
+                service,
+                &review.script,
+                &review.args,
+                &log_dir,
+                &env,
+            )?;
+        }
+        if let Some((dir, command)) = app.shell.take() {
+            let log_dir = history::new_run_dir(&app.workspace, &history::shell_label(&command));
+            app.running = RunningState::shell(&command);
+            let run_result = match app.active_env_vars() {
+                Ok(env) => run_while_drawing(terminal, &mut app, None, || {
+                    service.run_shell(&command, &dir, Some(&log_dir), &env)
+                })?,
+                Err(err) => Err(err),
             };
+            let entry = history::shell_entry(&app.workspace, &command, run_result);
             let _ = history::record_entry(&app.workspace, &entry);
             app.add_history_entry(entry);
-            app.back_to_script_select();
+            app.refresh_suggestion();
+            app.run_result.notice = None;
             app.reset_run_output_scroll();
             app.screen = Screen::RunResult;
         }
+        if let Some(request) = app.comparison.request.take() {
+            let group_dir = match &request.merge_into {
+                Some(entry) => history::run_artifacts_dir(&app.workspace, entry),
+                None => history::new_run_dir(&app.workspace, &request.script),
+            };
+            let total = request.targets.len();
+            let mut runs = Vec::new();
+            for (index, target) in request.targets.iter().enumerate() {
+                app.comparison.progress =
+                    Some(format!("{} ({}/{})", target.label, index + 1, total));
+                let log_dir = history::group_run_dir(&group_dir, &target.label);
+                let ticket = app.start_running(&request.script);
+                let result = run_while_drawing(terminal, &mut app, ticket, || {
+                    service.run_script_with_env(
+                        &request.script,
+                        &target.args,
+                        Some(&log_dir),
+                        &target.env,
+                        target.host.as_ref(),
+                    )
+                })?;
+                let mut run = history::group_run(&target.label, &target.args, result);
+                run.host = target.host.is_some();
+                runs.push(run);
+            }
+            app.comparison.progress = None;
+            let entry = match &request.merge_into {
+                Some(base) => {
+                    let entry = history::merge_group_runs(&app.workspace, base, runs, &group_dir);
+                    let _ = history::update_entry(&app.workspace, &entry);
+                    app.replace_history_entry(entry.clone());
+                    entry
+                }
+                None => {
+                    let entry =
+                        history::group_entry(&app.workspace, &request.script, runs, &group_dir);
+                    let _ = history::record_entry(&app.workspace, &entry);
+                    app.add_history_entry(entry.clone());
+                    entry
+                }
+            };
+            app.back_to_script_select();
+            app.open_comparison(entry);
+        }
+        if let Some(pipeline) = app.pipelines.request.take() {
+            run_pipeline(terminal, &mut app, service, pipeline)?;
+        }
     }
 }
+
+/// Hands the terminal to `command` until it exits, e.g. for an interactive login.
+fn run_in_terminal(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    mut command: Command,
+) -> Result<io::Result<ExitStatus>, Box<dyn Error>> {
+    restore_terminal(terminal)?;
+    let status = command.status();
+    enable_raw_mode()?;
+    io::stdout().execute(EnterAlternateScreen)?;
+    terminal.clear()?;
+    Ok(status)
+}
+
+/// Runs one script with the active env file and `env` after it, records it
+/// in history, and shows its result.
+fn run_single(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    service: &ScriptService,
+    script: &Path,
+    args: &[String],
+    log_dir: &Path,
+    env: &[(String, String)],
+) -> Result<HistoryEntry, Box<dyn Error>> {
+    let ticket = app.start_running(script);
+    let run_result = run_while_drawing(terminal, app, ticket, || {
+        service.run_script(script, args, Some(log_dir), env)
+    })?;
+    let entry = match run_result {
+        Ok(output) => history::success_entry(&app.workspace, script, args, output),
+        Err(err) => history::error_entry(&app.workspace, script, args, err.to_string()),
+    };
+    let _ = history::record_entry(&app.workspace, &entry);
+    app.add_history_entry(entry.clone());
+    app.refresh_suggestion();
+    app.run_result.notice = None;
+    app.back_to_script_select();
+    app.reset_run_output_scroll();
+    app.screen = Screen::RunResult;
+    Ok(entry)
+}
+
+/// Runs the stages of `pipeline` in turn, each recorded in history, while
+/// the Pipelines screen shows where every stage stands.
+fn run_pipeline(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    service: &ScriptService,
+    pipeline: Pipeline,
+) -> Result<(), Box<dyn Error>> {
+    let root = app.workspace.root().to_path_buf();
+    let mut executor = match PipelineExecutor::new(service, &root, pipeline, path_style) {
+        Ok(executor) => executor,
+        Err(err) => {
+            app.pipelines.error = Some(err.to_string());
+            return Ok(());
+        }
+    };
+    let name = executor.pipeline().name.clone();
+    while let Some(index) = executor.next_stage() {
+        let script = executor.stages()[index].script.clone();
+        let started = executor.start_stage(index);
+        app.pipelines.run = Some((name.clone(), executor.stages().to_vec()));
+        let entry = match started {
+            Ok(args) => {
+                let log_dir = history::new_run_dir(&app.workspace, &script);
+                let ticket = app.start_running(&script);
+                let result = draw_while(terminal, app, ticket, || {
+                    service.run_script(&script, &args, Some(&log_dir), &[])
+                })?;
+                executor.finish_stage(index, &result);
+                match result {
+                    Ok(output) => history::success_entry(&app.workspace, &script, &args, output),
+                    Err(err) => {
+                        history::error_entry(&app.workspace, &script, &args, err.to_string())
+                    }
+                }
+            }
+            Err(err) => history::error_entry(&app.workspace, &script, &[], err),
+        };
+        let _ = history::record_entry(&app.workspace, &entry);
+        app.add_history_entry(entry);
+    }
+    app.pipelines.run = Some((name, executor.stages().to_vec()));
+    app.refresh_suggestion();
+    Ok(())
+}
+
+/// Runs `job` on a worker thread, redrawing the Running screen until it finishes.
+/// With a concurrency group `ticket`, the job waits for its turn first.
+fn run_while_drawing<T: Send>(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    ticket: Option<GroupTicket>,
+    job: impl FnOnce() -> T + Send,
+) -> Result<T, Box<dyn Error>> {
+    app.screen = Screen::Running;
+    draw_while(terminal, app, ticket, job)
+}
+
+/// Runs `job` on a worker thread, redrawing the current screen until it finishes.
+fn draw_while<T: Send>(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    ticket: Option<GroupTicket>,
+    job: impl FnOnce() -> T + Send,
+) -> Result<T, Box<dyn Error>> {
+    let theme = app.theme.clone();
+    let ticket = ticket.as_ref();
+    thread::scope(|scope| {
+        let handle = scope.spawn(move || {
+            if let Some(ticket) = ticket {
+                ticket.wait();
+            }
+            job()
+        });
+        while !handle.is_finished() {
+            app.running.poll_queue();
+            app.prompt.poll();
+            terminal.draw(|frame| render_ui(frame, app, &theme))?;
+            // Keys are only read while the script waits on an answer.
+            if !app.prompt.is_open() {
+                thread::sleep(Duration::from_millis(100));
+            } else if event::poll(Duration::from_millis(100))? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        handle_key_event(app, key);
+                    }
+                }
+            }
+        }
+        match handle.join() {
+            Ok(value) => Ok(value),
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    })
+}
@@ -0,0 +1,66 @@
+use crate::history::HistoryEntry;
+use crate::ports::SshTarget;
+use ratatui::widgets::ListState;
+use std::path::PathBuf;
+
+/// One environment (or inventory host) of a comparison run.
+pub(crate) struct ComparisonTarget {
+    pub(crate) label: String,
+    pub(crate) args: Vec<String>,
+    pub(crate) env: Vec<(String, String)>,
+    /// Host the run goes to over SSH; local when unset.
+    pub(crate) host: Option<SshTarget>,
+}
+
+/// What the picker offers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PickerKind {
+    /// Env files under `.omaken/envs`.
+    Envs,
+    /// Hosts and `@groups` from `.omaken/inventory.toml`.
+    Hosts,
+}
+
+/// Runs of one script across env files, waiting for the main loop to execute them.
+pub(crate) struct ComparisonRequest {
+    pub(crate) script: PathBuf,
+    pub(crate) targets: Vec<ComparisonTarget>,
+    /// Grouped entry the results replace runs in, instead of a new entry.
+    pub(crate) merge_into: Option<HistoryEntry>,
+}
+
+pub(crate) struct ComparisonState {
+    pub(crate) kind: PickerKind,
+    /// Env file names (or hosts) offered by the picker, with their checked state.
+    pub(crate) choices: Vec<(String, bool)>,
+    pub(crate) list_state: ListState,
+    pub(crate) selection: usize,
+    pub(crate) error: Option<String>,
+    pub(crate) request: Option<ComparisonRequest>,
+    /// Environment being run, shown on the running screen.
+    pub(crate) progress: Option<String>,
+    /// Grouped entry shown side by side.
+    pub(crate) entry: Option<HistoryEntry>,
+    pub(crate) column_offset: usize,
+    /// Columns that fit on screen, as of the last render.
+    pub(crate) visible_columns: usize,
+    pub(crate) notice: Option<String>,
+}
+
+impl ComparisonState {
+    pub(crate) fn new() -> Self {
+        Self {
+            kind: PickerKind::Envs,
+            choices: Vec::new(),
+            list_state: ListState::default(),
+            selection: 0,
+            error: None,
+            request: None,
+            progress: None,
+            entry: None,
+            column_offset: 0,
+            visible_columns: 1,
+            notice: None,
+        }
+    }
+}
@@ -4,12 +4,19 @@ use std::path::PathBuf;
 pub(crate) struct FieldInputState {
     pub(crate) schema_name: Option<String>,
     pub(crate) schema_description: Option<String>,
+    /// Owner label from the schema, read when the form opens.
+    pub(crate) owner: Option<String>,
     pub(crate) fields: Vec<Field>,
     pub(crate) field_index: usize,
     pub(crate) field_inputs: Vec<String>,
     pub(crate) args: Vec<String>,
     pub(crate) error: Option<String>,
+    pub(crate) dry_run: Option<String>,
     pub(crate) selected_script: Option<PathBuf>,
+    /// The schema sets `Targets`, so the form offers the host picker.
+    pub(crate) targets: bool,
+    /// `RequiresEnv` variables the active env file and the shell leave unset.
+    pub(crate) missing_env: Vec<String>,
 }
 
 impl FieldInputState {
@@ -17,12 +24,16 @@ impl FieldInputState {
         Self {
             schema_name: None,
             schema_description: None,
+            owner: None,
             fields: Vec::new(),
             field_index: 0,
             field_inputs: Vec::new(),
             args: Vec::new(),
             error: None,
+            dry_run: None,
             selected_script: None,
+            targets: false,
+            missing_env: Vec::new(),
         }
     }
 }
@@ -1,6 +1,14 @@
-use crate::history::HistoryEntry;
+use crate::history::{HistoryEntry, TimeZone};
 use ratatui::widgets::TableState;
 
+/// Which runs a confirmed history re-run covers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum RerunScope {
+    All,
+    /// Only the failed runs of a grouped entry.
+    Failed,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub(crate) enum HistoryFocus {
     List,
@@ -12,10 +20,13 @@ pub(crate) struct HistoryState {
     pub(crate) table_state: TableState,
     pub(crate) selection: usize,
     pub(crate) focus: HistoryFocus,
+    pub(crate) time_zone: TimeZone,
+    pub(crate) confirm_rerun: Option<RerunScope>,
+    pub(crate) notice: Option<String>,
 }
 
 impl HistoryState {
-    pub(crate) fn new(entries: Vec<HistoryEntry>) -> Self {
+    pub(crate) fn new(entries: Vec<HistoryEntry>, time_zone: TimeZone) -> Self {
         let mut table_state = TableState::default();
         if !entries.is_empty() {
             table_state.select(Some(0));
@@ -25,6 +36,9 @@ impl HistoryState {
             table_state,
             selection: 0,
             focus: HistoryFocus::List,
+            time_zone,
+            confirm_rerun: None,
+            notice: None,
         }
     }
 }
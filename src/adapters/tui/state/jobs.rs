@@ -0,0 +1,16 @@
+use crate::concurrency::QueuedJob;
+
+/// Runs queued or holding a concurrency group, across all omakure processes.
+pub(crate) struct JobsState {
+    pub(crate) jobs: Vec<QueuedJob>,
+    pub(crate) error: Option<String>,
+}
+
+impl JobsState {
+    pub(crate) fn new() -> Self {
+        Self {
+            jobs: Vec::new(),
+            error: None,
+        }
+    }
+}
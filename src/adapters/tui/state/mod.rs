@@ -1,11 +1,31 @@
+mod comparison;
 mod environment;
 mod field_input;
 mod history;
+mod jobs;
 mod navigation;
+mod pipelines;
+mod plan;
+mod preflight;
+mod prompt;
+mod recent;
+mod run_result;
+mod running;
 mod search;
+mod workspaces;
 
+pub(crate) use comparison::{ComparisonRequest, ComparisonState, ComparisonTarget, PickerKind};
 pub(crate) use environment::EnvironmentState;
 pub(crate) use field_input::FieldInputState;
-pub(crate) use history::{HistoryFocus, HistoryState};
+pub(crate) use history::{HistoryFocus, HistoryState, RerunScope};
+pub(crate) use jobs::JobsState;
 pub(crate) use navigation::{NavigationState, WidgetLoadResult};
+pub(crate) use pipelines::{PipelineEntry, PipelinesState};
+pub(crate) use plan::{PlanReview, PlanState};
+pub(crate) use preflight::{AfterPreflight, PreflightFailure, PreflightRequest, PreflightState};
+pub(crate) use prompt::{prompt_channel, PromptState};
+pub(crate) use recent::RecentState;
+pub(crate) use run_result::RunResultState;
+pub(crate) use running::RunningState;
 pub(crate) use search::SearchState;
+pub(crate) use workspaces::WorkspacesState;
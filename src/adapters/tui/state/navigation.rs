@@ -2,6 +2,7 @@ use crate::domain::Schema;
 use crate::lua_widget::WidgetData;
 use crate::ports::WorkspaceEntry;
 use ratatui::widgets::ListState;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::mpsc::Receiver;
 
@@ -15,7 +16,16 @@ pub(crate) struct WidgetLoadResult {
 
 pub(crate) struct NavigationState {
     pub(crate) current_dir: PathBuf,
+    /// Pinned scripts, listed above `entries` in every folder.
+    pub(crate) favorites: Vec<WorkspaceEntry>,
     pub(crate) entries: Vec<WorkspaceEntry>,
+    /// Tree mode: `entries` lists the whole workspace from the root, with the
+    /// contents of open folders under them.
+    pub(crate) tree: bool,
+    /// Nesting level of each of `entries` in tree mode; empty otherwise.
+    pub(crate) depths: Vec<usize>,
+    /// Folders open in tree mode, listed only once opened.
+    pub(crate) expanded: HashSet<PathBuf>,
     pub(crate) list_state: ListState,
     pub(crate) selection: usize,
     pub(crate) widget: Option<WidgetData>,
@@ -26,6 +36,20 @@ pub(crate) struct NavigationState {
     pub(crate) schema_preview_error: Option<String>,
     pub(crate) preview_script: Option<PathBuf>,
     pub(crate) schema_cache: Option<(PathBuf, Schema)>,
+    /// The source pane (`v`) is shown under the schema preview.
+    pub(crate) source_open: bool,
+    /// Source of the previewed script, or why it could not be read.
+    pub(crate) source: Option<(PathBuf, Result<String, String>)>,
+    pub(crate) source_scroll: u16,
+    /// Shell command being typed at the `:` prompt, if it is open.
+    pub(crate) command_line: Option<String>,
+    /// `g` was pressed and the next key picks where to go.
+    pub(crate) pending_go: bool,
+    /// Scripts whose recent runs with the same args both passed and failed.
+    pub(crate) flaky: HashSet<PathBuf>,
+    /// Outcome of each `Requires` entry checked this session, by spec; refreshing
+    /// checks again.
+    pub(crate) tool_checks: HashMap<String, Result<(), String>>,
 }
 
 impl NavigationState {
@@ -36,7 +60,11 @@ impl NavigationState {
         }
         Self {
             current_dir,
+            favorites: Vec::new(),
             entries,
+            tree: false,
+            depths: Vec::new(),
+            expanded: HashSet::new(),
             list_state,
             selection: 0,
             widget: None,
@@ -47,6 +75,13 @@ impl NavigationState {
             schema_preview_error: None,
             preview_script: None,
             schema_cache: None,
+            source_open: false,
+            source: None,
+            source_scroll: 0,
+            command_line: None,
+            pending_go: false,
+            flaky: HashSet::new(),
+            tool_checks: HashMap::new(),
         }
     }
 }
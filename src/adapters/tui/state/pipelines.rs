@@ -0,0 +1,34 @@
+use crate::domain::Pipeline;
+use crate::use_cases::StageState;
+use ratatui::widgets::TableState;
+
+/// A pipeline file, or a script with `DependsOn`, in the `g p` list.
+pub(crate) struct PipelineEntry {
+    pub(crate) name: String,
+    /// The stages, or why the pipeline cannot run.
+    pub(crate) pipeline: Result<Pipeline, String>,
+}
+
+pub(crate) struct PipelinesState {
+    pub(crate) entries: Vec<PipelineEntry>,
+    pub(crate) table_state: TableState,
+    pub(crate) selection: usize,
+    /// Pipeline picked to run; the main loop takes it and runs the stages.
+    pub(crate) request: Option<Pipeline>,
+    /// Name of the pipeline running or last run, and where its stages stand.
+    pub(crate) run: Option<(String, Vec<StageState>)>,
+    pub(crate) error: Option<String>,
+}
+
+impl PipelinesState {
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            table_state: TableState::default(),
+            selection: 0,
+            request: None,
+            run: None,
+            error: None,
+        }
+    }
+}
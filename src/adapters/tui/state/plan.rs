@@ -0,0 +1,30 @@
+use crate::domain::PlanSummary;
+use std::path::PathBuf;
+
+/// A Terraform plan shown for approval before its apply run.
+pub(crate) struct PlanReview {
+    pub(crate) script: PathBuf,
+    pub(crate) args: Vec<String>,
+    /// Plan file written by the plan phase, handed to the apply phase.
+    pub(crate) plan_file: PathBuf,
+    pub(crate) summary: PlanSummary,
+    /// Plan output with colors stripped.
+    pub(crate) output: String,
+}
+
+pub(crate) struct PlanState {
+    pub(crate) review: Option<PlanReview>,
+    pub(crate) scroll: u16,
+    /// Approved plan waiting for the main loop to run its apply phase.
+    pub(crate) apply: Option<PlanReview>,
+}
+
+impl PlanState {
+    pub(crate) fn new() -> Self {
+        Self {
+            review: None,
+            scroll: 0,
+            apply: None,
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use super::super::app::Screen;
+use crate::preflight::Check;
+use std::path::PathBuf;
+
+/// What opening a script was for, resumed once its checks pass.
+pub(crate) enum AfterPreflight {
+    Open,
+    /// Open the form prefilled with these args.
+    Prefill(Vec<String>),
+    /// Open the environment picker.
+    Compare,
+}
+
+/// A script waiting on its credential checks.
+pub(crate) struct PreflightRequest {
+    pub(crate) script: PathBuf,
+    pub(crate) checks: Vec<Check>,
+    pub(crate) then: AfterPreflight,
+    /// Screen to go back to when the user gives up.
+    pub(crate) return_to: Screen,
+}
+
+/// A failed check shown with its login offer.
+pub(crate) struct PreflightFailure {
+    pub(crate) request: PreflightRequest,
+    pub(crate) check: Check,
+    pub(crate) message: String,
+}
+
+pub(crate) struct PreflightState {
+    /// Checks for the main loop to run next.
+    pub(crate) pending: Option<PreflightRequest>,
+    pub(crate) failure: Option<PreflightFailure>,
+    /// Accepted login, waiting for the main loop to hand it the terminal.
+    pub(crate) login: Option<PreflightFailure>,
+    /// Script whose checks just passed, so loading it opens the form.
+    pub(crate) cleared: Option<PathBuf>,
+}
+
+impl PreflightState {
+    pub(crate) fn new() -> Self {
+        Self {
+            pending: None,
+            failure: None,
+            login: None,
+            cleared: None,
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use crate::domain::ScriptPrompt;
+use crate::ports::{PromptHandler, Prompts};
+
+/// A question from a running script and where its answer goes.
+pub(crate) struct PromptRequest {
+    prompt: ScriptPrompt,
+    reply: Sender<Option<String>>,
+}
+
+/// Passes the questions of scripts, asked on runner threads, to the TUI loop.
+struct PromptBridge {
+    requests: Sender<PromptRequest>,
+}
+
+impl PromptHandler for PromptBridge {
+    fn ask(&self, prompt: &ScriptPrompt) -> Option<String> {
+        let (reply, answer) = mpsc::channel();
+        self.requests
+            .send(PromptRequest {
+                prompt: prompt.clone(),
+                reply,
+            })
+            .ok()?;
+        answer.recv().ok().flatten()
+    }
+}
+
+/// The question being answered in the prompt dialog.
+pub(crate) struct OpenPrompt {
+    pub(crate) prompt: ScriptPrompt,
+    pub(crate) input: String,
+    reply: Sender<Option<String>>,
+}
+
+/// Questions of running scripts, answered one at a time.
+pub(crate) struct PromptState {
+    requests: Option<Receiver<PromptRequest>>,
+    pub(crate) open: Option<OpenPrompt>,
+}
+
+impl PromptState {
+    pub(crate) fn new() -> Self {
+        Self {
+            requests: None,
+            open: None,
+        }
+    }
+
+    pub(crate) fn is_open(&self) -> bool {
+        self.open.is_some()
+    }
+
+    /// Opens the next question waiting, unless one is open already.
+    pub(crate) fn poll(&mut self) {
+        if self.open.is_some() {
+            return;
+        }
+        let Some(request) = self
+            .requests
+            .as_ref()
+            .and_then(|requests| requests.try_recv().ok())
+        else {
+            return;
+        };
+        self.open = Some(OpenPrompt {
+            prompt: request.prompt,
+            input: String::new(),
+            reply: request.reply,
+        });
+    }
+
+    /// Answers with `input` read as `ScriptPrompt::answer` does.
+    pub(crate) fn answer_with(&mut self, input: &str) {
+        if let Some(open) = self.open.take() {
+            let _ = open.reply.send(Some(open.prompt.answer(input)));
+        }
+    }
+
+    /// Answers with what was typed into the dialog.
+    pub(crate) fn submit(&mut self) {
+        let input = self
+            .open
+            .as_ref()
+            .map(|open| open.input.clone())
+            .unwrap_or_default();
+        self.answer_with(&input);
+    }
+
+    /// Closes the question; the script gets its default answer.
+    pub(crate) fn dismiss(&mut self) {
+        if let Some(open) = self.open.take() {
+            let _ = open.reply.send(None);
+        }
+    }
+}
+
+/// A handler for `ScriptService::with_prompts`, and the state its questions
+/// arrive in.
+pub(crate) fn prompt_channel() -> (Prompts, PromptState) {
+    let (requests, received) = mpsc::channel();
+    let bridge = PromptBridge { requests };
+    let state = PromptState {
+        requests: Some(received),
+        open: None,
+    };
+    (Prompts(std::sync::Arc::new(bridge)), state)
+}
@@ -0,0 +1,19 @@
+use crate::history::HistoryEntry;
+use ratatui::widgets::TableState;
+
+/// Last run of each recently used script, for the `g r` quick list.
+pub(crate) struct RecentState {
+    pub(crate) entries: Vec<HistoryEntry>,
+    pub(crate) table_state: TableState,
+    pub(crate) selection: usize,
+}
+
+impl RecentState {
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            table_state: TableState::default(),
+            selection: 0,
+        }
+    }
+}
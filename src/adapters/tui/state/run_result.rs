@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+
+pub(crate) struct RunResultState {
+    /// Follow-up note being typed, if the note prompt is open.
+    pub(crate) note: Option<String>,
+    pub(crate) notice: Option<String>,
+    /// Script usually run after this one, relative to the workspace root.
+    pub(crate) suggestion: Option<PathBuf>,
+    /// Owner label from the last run's schema, shown when the run failed.
+    pub(crate) owner: Option<String>,
+}
+
+impl RunResultState {
+    pub(crate) fn new() -> Self {
+        Self {
+            note: None,
+            notice: None,
+            suggestion: None,
+            owner: None,
+        }
+    }
+}
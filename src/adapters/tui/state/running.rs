@@ -0,0 +1,105 @@
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::concurrency;
+use crate::config::DEFAULT_OVERDUE_FACTOR;
+use crate::domain::Schema;
+
+/// Elapsed time of the script currently running.
+pub(crate) struct RunningState {
+    pub(crate) started: Option<Instant>,
+    pub(crate) schema: Option<Schema>,
+    /// Ticket held while waiting for the script's concurrency group.
+    pub(crate) ticket: Option<PathBuf>,
+    /// Runs ahead of this one in its concurrency group.
+    pub(crate) queue_position: usize,
+    /// Ad-hoc shell command being run, shown instead of the script.
+    pub(crate) command: Option<String>,
+    /// Multiple of `ExpectedDurationSeconds` after which the run is overdue,
+    /// from `[runner] overdue_factor`.
+    pub(crate) overdue_factor: u64,
+}
+
+impl RunningState {
+    pub(crate) fn new() -> Self {
+        Self {
+            started: None,
+            schema: None,
+            ticket: None,
+            queue_position: 0,
+            command: None,
+            overdue_factor: DEFAULT_OVERDUE_FACTOR,
+        }
+    }
+
+    pub(crate) fn started(schema: Option<Schema>, overdue_factor: u64) -> Self {
+        Self {
+            started: Some(Instant::now()),
+            schema,
+            overdue_factor,
+            ..Self::new()
+        }
+    }
+
+    pub(crate) fn queued(schema: Option<Schema>, ticket: PathBuf, overdue_factor: u64) -> Self {
+        let mut state = Self {
+            schema,
+            ticket: Some(ticket),
+            overdue_factor,
+            ..Self::new()
+        };
+        state.poll_queue();
+        state
+    }
+
+    pub(crate) fn shell(command: &str) -> Self {
+        Self {
+            started: Some(Instant::now()),
+            command: Some(command.to_string()),
+            ..Self::new()
+        }
+    }
+
+    /// Starts the clock once the queued run reaches the front of its group.
+    pub(crate) fn poll_queue(&mut self) {
+        let Some(ticket) = &self.ticket else {
+            return;
+        };
+        match concurrency::queue_position(ticket) {
+            Some(position) if position > 0 => self.queue_position = position,
+            _ => {
+                self.ticket = None;
+                self.queue_position = 0;
+                self.started = Some(Instant::now());
+            }
+        }
+    }
+
+    pub(crate) fn concurrency_group(&self) -> Option<&str> {
+        self.schema
+            .as_ref()
+            .and_then(|schema| schema.concurrency_group())
+    }
+
+    pub(crate) fn is_queued(&self) -> bool {
+        self.ticket.is_some()
+    }
+
+    pub(crate) fn elapsed_ms(&self) -> u64 {
+        self.started
+            .map(|started| started.elapsed().as_millis() as u64)
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn expected_seconds(&self) -> Option<u64> {
+        self.schema
+            .as_ref()
+            .and_then(|schema| schema.expected_duration_seconds)
+    }
+
+    pub(crate) fn is_overdue(&self) -> bool {
+        self.schema
+            .as_ref()
+            .is_some_and(|schema| schema.is_overdue(self.elapsed_ms(), self.overdue_factor))
+    }
+}
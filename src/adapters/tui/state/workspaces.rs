@@ -0,0 +1,22 @@
+use ratatui::widgets::TableState;
+use std::path::PathBuf;
+
+/// Named workspaces from the global config, for the `g w` switcher.
+pub(crate) struct WorkspacesState {
+    pub(crate) entries: Vec<(String, PathBuf)>,
+    pub(crate) table_state: TableState,
+    pub(crate) selection: usize,
+    /// Workspace picked in the switcher; the TUI restarts there.
+    pub(crate) switch_to: Option<PathBuf>,
+}
+
+impl WorkspacesState {
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            table_state: TableState::default(),
+            selection: 0,
+            switch_to: None,
+        }
+    }
+}
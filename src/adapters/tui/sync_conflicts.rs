@@ -0,0 +1,164 @@
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{List, ListItem, ListState, Paragraph};
+use ratatui::Terminal;
+use std::error::Error;
+use std::io;
+
+use super::theme::{self, Theme};
+use super::{resolve_theme, restore_terminal, setup_terminal};
+use crate::script_sync::{line_diff, Conflict, DiffLine, Resolution};
+use crate::workspace::Workspace;
+
+/// Lets the user settle each conflict; returns a resolution per conflict,
+/// `None` for the ones left alone. Cancelling leaves all of them alone.
+pub fn resolve_conflicts(
+    workspace: &Workspace,
+    conflicts: &[Conflict],
+) -> Result<Vec<Option<Resolution>>, Box<dyn Error>> {
+    let theme = resolve_theme(workspace);
+    let mut terminal = setup_terminal()?;
+    let resolved = resolve_loop(&mut terminal, &theme, conflicts);
+    restore_terminal(&mut terminal)?;
+    resolved
+}
+
+fn resolution_label(resolution: Option<Resolution>) -> &'static str {
+    match resolution {
+        None => "unresolved",
+        Some(Resolution::KeepLocal) => "keep local",
+        Some(Resolution::TakeUpstream) => "take upstream",
+        Some(Resolution::BackupAndTake) => "backup and take",
+    }
+}
+
+fn resolve_loop(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    theme: &Theme,
+    conflicts: &[Conflict],
+) -> Result<Vec<Option<Resolution>>, Box<dyn Error>> {
+    let mut resolutions: Vec<Option<Resolution>> = vec![None; conflicts.len()];
+    let mut list_state = ListState::default();
+    list_state.select(Some(0));
+    // Scroll offset of the diff, or `None` while the list has focus.
+    let mut diff_scroll: Option<u16> = None;
+    loop {
+        let selected = list_state.selected().unwrap_or(0);
+        let conflict = &conflicts[selected];
+        let diff = line_diff(&conflict.local, &conflict.upstream);
+        terminal.draw(|frame| {
+            let list_height = (conflicts.len() as u16 + 2).min(10);
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(list_height),
+                    Constraint::Min(3),
+                    Constraint::Length(1),
+                ])
+                .split(frame.size());
+
+            let items: Vec<ListItem> = conflicts
+                .iter()
+                .zip(&resolutions)
+                .map(|(conflict, resolution)| {
+                    let style = match resolution {
+                        None => theme.status_fail_style(),
+                        Some(_) => theme.text_secondary(),
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::raw(format!("{} ", conflict.path.display())),
+                        Span::styled(format!("[{}]", resolution_label(*resolution)), style),
+                    ]))
+                })
+                .collect();
+            let list = List::new(items)
+                .block(theme.block().title("Changed both locally and upstream"))
+                .highlight_style(theme.selection_style())
+                .highlight_symbol(theme::selection_symbol_str());
+            frame.render_stateful_widget(list, chunks[0], &mut list_state);
+
+            let lines: Vec<Line> = diff
+                .iter()
+                .map(|line| match line {
+                    DiffLine::Same(text) => Line::from(format!("  {}", text)),
+                    DiffLine::Removed(text) => {
+                        Line::from(Span::styled(format!("- {}", text), theme.status_fail_style()))
+                    }
+                    DiffLine::Added(text) => {
+                        Line::from(Span::styled(format!("+ {}", text), theme.status_ok_style()))
+                    }
+                })
+                .collect();
+            let mut block = theme.block().title("Diff: - local, + upstream");
+            if diff_scroll.is_some() {
+                block = block.border_style(theme.selection_border_style());
+            }
+            let diff_view = Paragraph::new(lines)
+                .block(block)
+                .scroll((diff_scroll.unwrap_or(0), 0));
+            frame.render_widget(diff_view, chunks[1]);
+
+            let hint = if diff_scroll.is_some() {
+                "Up/Down scroll, d or Esc back to the list"
+            } else {
+                "l keep local, u take upstream, b backup and take, d view diff, Enter apply, Esc cancel"
+            };
+            frame.render_widget(
+                Paragraph::new(hint).style(theme.text_secondary()),
+                chunks[2],
+            );
+        })?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Ok(vec![None; conflicts.len()]);
+        }
+        if let Some(scroll) = diff_scroll {
+            let max_scroll = diff.len().saturating_sub(1) as u16;
+            diff_scroll = match key.code {
+                KeyCode::Esc | KeyCode::Char('d') | KeyCode::Char('q') => None,
+                KeyCode::Up | KeyCode::Char('k') => Some(scroll.saturating_sub(1)),
+                KeyCode::Down | KeyCode::Char('j') => Some((scroll + 1).min(max_scroll)),
+                KeyCode::PageUp => Some(scroll.saturating_sub(20)),
+                KeyCode::PageDown => Some((scroll + 20).min(max_scroll)),
+                _ => Some(scroll),
+            };
+            continue;
+        }
+        let resolution = match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return Ok(vec![None; conflicts.len()]),
+            KeyCode::Enter => return Ok(resolutions),
+            KeyCode::Char('d') => {
+                diff_scroll = Some(0);
+                continue;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                list_state.select(Some(selected.saturating_sub(1)));
+                continue;
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                list_state.select(Some((selected + 1).min(conflicts.len() - 1)));
+                continue;
+            }
+            KeyCode::Char('l') => Resolution::KeepLocal,
+            KeyCode::Char('u') => Resolution::TakeUpstream,
+            KeyCode::Char('b') => Resolution::BackupAndTake,
+            _ => continue,
+        };
+        resolutions[selected] = Some(resolution);
+        // Move on to the next file still unresolved, if any.
+        if let Some(next) = (selected + 1..conflicts.len())
+            .chain(0..selected)
+            .find(|index| resolutions[*index].is_none())
+        {
+            list_state.select(Some(next));
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::Span;
+use ratatui::widgets::{Block, BorderType, Borders};
 use serde::Deserialize;
 use std::error::Error;
 use std::fmt;
@@ -23,10 +24,28 @@ pub(crate) const BUILTIN_THEMES: &[BuiltinTheme] = &[
         name: "default",
         contents: DEFAULT_THEME_TOML,
     },
+    BuiltinTheme {
+        name: "light",
+        contents: include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/themes/light.toml")),
+    },
+    BuiltinTheme {
+        name: "high-contrast",
+        contents: include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/themes/high-contrast.toml"
+        )),
+    },
     BuiltinTheme {
         name: "dracula",
         contents: include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/themes/dracula.toml")),
     },
+    BuiltinTheme {
+        name: "colorblind",
+        contents: include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/themes/colorblind.toml"
+        )),
+    },
     BuiltinTheme {
         name: "catppuccin-mocha",
         contents: include_str!(concat!(
@@ -94,6 +113,39 @@ pub(crate) struct UiColors {
     pub border_inactive: HexColor,
     pub selection_fg: HexColor,
     pub selection_bg: Option<HexColor>,
+    #[serde(default)]
+    pub border: BorderStyle,
+}
+
+/// Line style of panel borders.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum BorderStyle {
+    #[default]
+    Plain,
+    Rounded,
+    Double,
+    Thick,
+}
+
+impl BorderStyle {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            BorderStyle::Plain => "plain",
+            BorderStyle::Rounded => "rounded",
+            BorderStyle::Double => "double",
+            BorderStyle::Thick => "thick",
+        }
+    }
+
+    fn border_type(&self) -> BorderType {
+        match self {
+            BorderStyle::Plain => BorderType::Plain,
+            BorderStyle::Rounded => BorderType::Rounded,
+            BorderStyle::Double => BorderType::Double,
+            BorderStyle::Thick => BorderType::Thick,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -180,6 +232,13 @@ impl Default for Theme {
 }
 
 impl Theme {
+    /// A bordered panel drawn with the theme's border style.
+    pub(crate) fn block(&self) -> Block<'static> {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(self.ui.border.border_type())
+    }
+
     pub(crate) fn selection_style(&self) -> Style {
         let mut style = Style::default()
             .fg(self.ui.selection_fg.color())
@@ -249,6 +308,7 @@ fn fallback_default_theme() -> Theme {
             border_inactive: HexColor::new(Color::Gray),
             selection_fg: HexColor::new(color_from_tuple(BRAND_GRADIENT_START)),
             selection_bg: None,
+            border: BorderStyle::Plain,
         },
         status: StatusColors {
             ok: HexColor::new(Color::Green),
@@ -349,6 +409,10 @@ fn parse_hex_color(value: &str) -> Result<Color, ThemeParseError> {
     Ok(Color::Rgb(red, green, blue))
 }
 
+pub(crate) fn selection_symbol_str() -> &'static str {
+    "> "
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -389,6 +453,20 @@ error = "#ffff00"
         assert_eq!(theme.brand.accent.color(), Color::Rgb(255, 0, 0));
     }
 
+    #[test]
+    fn builtin_themes_parse() {
+        for builtin in BUILTIN_THEMES {
+            assert!(
+                load_theme_from_str(builtin.contents).is_ok(),
+                "{} should parse",
+                builtin.name
+            );
+        }
+        let theme = load_theme_from_builtin("high-contrast").unwrap();
+        assert_eq!(theme.ui.border, BorderStyle::Thick);
+        assert_eq!(default_theme().ui.border, BorderStyle::Plain);
+    }
+
     #[test]
     fn load_theme_falls_back_to_default() {
         let theme = load_theme(Some("missing-theme"), None);
@@ -428,7 +506,3 @@ error = "#ffff00"
         assert!(load_theme_from_str(toml).is_err());
     }
 }
-
-pub(crate) fn selection_symbol_str() -> &'static str {
-    "> "
-}
@@ -1,32 +1,51 @@
 use ratatui::layout::{Constraint, Direction, Layout};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::widgets::Paragraph;
 use ratatui::Frame;
 
 use super::app::{App, Screen};
 use super::theme::Theme;
 use super::widgets::{
-    environment, envs, error as error_widget, field_input, history, loading as loading_widget,
-    run_result, running, schema, scripts, search,
+    comparison, environment, envs, error as error_widget, field_input, help, history, jobs,
+    loading as loading_widget, pipelines, plan, preflight, prompt, recent, run_result, running,
+    schema, scripts, search, source, tutorial, workspaces,
 };
+use crate::keymap::Action;
 
 pub(crate) fn render_ui(frame: &mut Frame, app: &mut App, theme: &Theme) {
     match app.screen {
         Screen::ScriptSelect => render_script_select(frame, app, theme),
         Screen::Search => search::render_search(frame, frame.size(), app, theme),
         Screen::Environments => envs::render_envs(frame, frame.size(), app, theme),
+        Screen::EnvPicker => comparison::render_env_picker(frame, frame.size(), app, theme),
         Screen::FieldInput => field_input::render_field_input(frame, frame.size(), app, theme),
         Screen::History => history::render_history(frame, frame.size(), app, theme),
-        Screen::Running => running::render_running(frame, frame.size(), app),
+        Screen::Running => running::render_running(frame, frame.size(), app, theme),
         Screen::RunResult => run_result::render_run_result(frame, frame.size(), app, theme),
+        Screen::Comparison => comparison::render_comparison(frame, frame.size(), app, theme),
+        Screen::Jobs => jobs::render_jobs(frame, frame.size(), app, theme),
+        Screen::Recent => recent::render_recent(frame, frame.size(), app, theme),
+        Screen::PlanReview => plan::render_plan_review(frame, frame.size(), app, theme),
+        Screen::Preflight => preflight::render_preflight(frame, frame.size(), app, theme),
+        Screen::Workspaces => workspaces::render_workspaces(frame, frame.size(), app, theme),
+        Screen::Pipelines => pipelines::render_pipelines(frame, frame.size(), app, theme),
         Screen::Error => render_error(frame, app, theme),
     }
+    if app.prompt.is_open() {
+        prompt::render_prompt(frame, app, theme);
+    }
+    if app.tutorial.is_some() {
+        tutorial::render_tutorial(frame, app, theme);
+    }
+    if app.help_open {
+        help::render_help(frame, app, theme);
+    }
 }
 
 pub(crate) fn render_loading(frame: &mut Frame, theme: &Theme) {
     let _ = theme;
-    loading_widget::render_loading(frame, frame.size());
+    loading_widget::render_loading(frame, frame.size(), theme);
 }
 
 fn render_script_select(frame: &mut Frame, app: &mut App, theme: &Theme) {
@@ -38,9 +57,7 @@ fn render_script_select(frame: &mut Frame, app: &mut App, theme: &Theme) {
     );
     let info_height = info_lines.len() as u16 + 2;
 
-    let outer = Block::default()
-        .borders(Borders::ALL)
-        .title(omakure_title_line(theme));
+    let outer = theme.block().title(omakure_title_line(theme));
     let inner = outer.inner(frame.size());
     frame.render_widget(outer, frame.size());
 
@@ -53,10 +70,8 @@ fn render_script_select(frame: &mut Frame, app: &mut App, theme: &Theme) {
         ])
         .split(inner);
 
-    environment::render_environment(frame, chunks[0], &info_title, info_lines);
-    let entries_block = Block::default()
-        .borders(Borders::ALL)
-        .title("Workspace Entries");
+    environment::render_environment(frame, chunks[0], &info_title, info_lines, theme);
+    let entries_block = theme.block().title("Workspace Entries");
     let entries_area = entries_block.inner(chunks[1]);
     frame.render_widget(entries_block, chunks[1]);
 
@@ -66,59 +81,101 @@ fn render_script_select(frame: &mut Frame, app: &mut App, theme: &Theme) {
     );
 
     if show_schema {
+        let list_width = if app.navigation.source_open { 40 } else { 55 };
         let body_chunks = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .constraints([
+                Constraint::Percentage(list_width),
+                Constraint::Percentage(100 - list_width),
+            ])
             .split(entries_area);
+        let preview_chunks = if app.navigation.source_open {
+            Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(35), Constraint::Min(3)])
+                .split(body_chunks[1])
+        } else {
+            Layout::default()
+                .constraints([Constraint::Min(0)])
+                .split(body_chunks[1])
+        };
 
         scripts::render_scripts(
             frame,
             body_chunks[0],
             &app.workspace,
-            &app.navigation.current_dir,
-            &app.navigation.entries,
-            &mut app.navigation.list_state,
+            &mut app.navigation,
             theme,
         );
         let schema_title = schema_title(app);
         schema::render_schema_preview(
             frame,
-            body_chunks[1],
+            preview_chunks[0],
             &schema_title,
             app.navigation.schema_preview.as_ref(),
             app.navigation.schema_preview_error.as_deref(),
             theme,
         );
+        if app.navigation.source_open {
+            source::render_source(
+                frame,
+                preview_chunks[1],
+                app.navigation.source.as_ref(),
+                app.navigation.source_scroll,
+                theme,
+            );
+        }
     } else {
         scripts::render_scripts(
             frame,
             entries_area,
             &app.workspace,
-            &app.navigation.current_dir,
-            &app.navigation.entries,
-            &mut app.navigation.list_state,
+            &mut app.navigation,
             theme,
         );
     }
 
-    let mut footer_text = if app.navigation.entries.is_empty() {
-        "Folder is empty. r refresh, h history, Ctrl+S search, Alt+E envs, q quit".to_string()
-    } else {
-        "Up/Down move, Enter open/run, r refresh, h history, Ctrl+S search, Alt+E envs, q quit"
-            .to_string()
-    };
-    if app.navigation.current_dir != app.workspace.root() {
-        if app.navigation.entries.is_empty() {
-            footer_text =
-                "Folder is empty. Backspace up, r refresh, h history, Ctrl+S search, Alt+E envs, q quit"
-                    .to_string();
-        } else {
-            footer_text =
-                "Up/Down move, Enter open/run, Backspace up, r refresh, h history, Ctrl+S search, Alt+E envs, q quit"
-                    .to_string();
-        }
+    let keys = &app.keys;
+    let mut footer_text = format!(
+        "{}{} refresh, {} history, g r recent, g w workspaces, g p pipelines, {} search, Alt+E envs, J jobs, : command, {} help, {} quit",
+        match (
+            app.navigation.entries.is_empty(),
+            app.navigation.current_dir != app.workspace.root(),
+        ) {
+            (true, false) => "Folder is empty. ".to_string(),
+            (true, true) => "Folder is empty. Backspace up, ".to_string(),
+            (false, _) if app.navigation.tree => format!(
+                "Up/Down move, {} open/run, Left/Right fold, m run in envs, t list, v source, ",
+                keys.label(Action::Run)
+            ),
+            (false, false) => format!(
+                "Up/Down move, {} open/run, m run in envs, t tree, v source, ",
+                keys.label(Action::Run)
+            ),
+            (false, true) => format!(
+                "Up/Down move, {} open/run, m run in envs, Backspace up, t tree, v source, ",
+                keys.label(Action::Run)
+            ),
+        },
+        keys.label(Action::Refresh),
+        keys.label(Action::History),
+        keys.label(Action::Search),
+        keys.label(Action::Help),
+        keys.label(Action::Quit),
+    );
+    if app.navigation.pending_go {
+        footer_text = "g: r recent, w workspaces, p pipelines, any other key to cancel".to_string();
     }
-    let footer = Paragraph::new(footer_text).style(theme.text_secondary());
+    let footer = match &app.navigation.command_line {
+        Some(line) => Paragraph::new(vec![
+            Line::from(format!(":{}", line)),
+            Line::from(Span::styled(
+                "Enter to run in this folder with the active env, Esc to cancel",
+                theme.text_secondary(),
+            )),
+        ]),
+        None => Paragraph::new(footer_text).style(theme.text_secondary()),
+    };
     frame.render_widget(footer, chunks[2]);
 }
 
@@ -3,15 +3,19 @@ use ratatui::style::Style;
 
 use super::super::app::ExecutionStatus;
 use super::super::theme::Theme;
+use crate::status::{FAIL_SYMBOL, OK_SYMBOL, WARN_SYMBOL};
 
 pub(crate) fn status_label_and_style(status: &ExecutionStatus, theme: &Theme) -> (String, Style) {
     match status {
-        ExecutionStatus::Success => ("OK".to_string(), theme.status_ok_style()),
+        ExecutionStatus::Success => (format!("{} OK", OK_SYMBOL), theme.status_ok_style()),
         ExecutionStatus::Failed(code) => match code {
-            Some(code) => (format!("FAIL ({})", code), theme.status_fail_style()),
-            None => ("FAIL".to_string(), theme.status_fail_style()),
+            Some(code) => (
+                format!("{} FAIL ({})", FAIL_SYMBOL, code),
+                theme.status_fail_style(),
+            ),
+            None => (format!("{} FAIL", FAIL_SYMBOL), theme.status_fail_style()),
         },
-        ExecutionStatus::Error => ("ERROR".to_string(), theme.status_error_style()),
+        ExecutionStatus::Error => (format!("{} ERROR", WARN_SYMBOL), theme.status_error_style()),
     }
 }
 
@@ -43,3 +47,15 @@ pub(crate) fn horizontal_split(area: Rect, left_percent: u16) -> [Rect; 2] {
 
     [chunks[0], chunks[1]]
 }
+
+/// A `width` by `height` box in the middle of `area`, shrunk to fit it.
+pub(crate) fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
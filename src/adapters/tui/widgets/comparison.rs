@@ -0,0 +1,196 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{List, ListItem, Paragraph, Wrap};
+use ratatui::Frame;
+
+use super::super::app::{App, ExecutionStatus};
+use super::super::state::PickerKind;
+use super::super::theme::{self, Theme};
+use super::common::{standard_screen_layout, status_label_and_style};
+use crate::history;
+
+/// Narrowest column before fewer environments are shown at once.
+const MIN_COLUMN_WIDTH: u16 = 30;
+
+pub(crate) fn render_env_picker(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
+    let script = app
+        .field_input
+        .selected_script
+        .as_deref()
+        .map(|path| app.display_path(path))
+        .unwrap_or_default();
+    let hosts = app.comparison.kind == PickerKind::Hosts;
+    let (title, hint, list_title, empty) = if hosts {
+        (
+            "Run on hosts",
+            "Each host runs the script over SSH with the form's values; @groups expand to their hosts.",
+            "Hosts",
+            "No hosts found in .omaken/inventory.toml.",
+        )
+    } else {
+        (
+            "Run in environments",
+            "Each env file fills matching fields and is injected into the script's environment.",
+            "Environments",
+            "No environment files found in .omaken/envs.",
+        )
+    };
+    let mut info_lines = vec![
+        Line::from(format!("Script: {}", script)),
+        Line::from(Span::styled(hint, theme.text_secondary())),
+    ];
+    if let Some(err) = &app.comparison.error {
+        info_lines.push(Line::from(vec![
+            Span::styled("Error: ", Style::default().fg(theme.semantic.error.color())),
+            Span::raw(err.clone()),
+        ]));
+    }
+    let chunks = standard_screen_layout(area, info_lines.len() as u16 + 2, 1);
+    let info = Paragraph::new(info_lines)
+        .block(theme.block().title(title))
+        .wrap(Wrap { trim: true });
+    frame.render_widget(info, chunks[0]);
+
+    if app.comparison.choices.is_empty() {
+        let empty = Paragraph::new(empty)
+            .block(theme.block().title(list_title))
+            .wrap(Wrap { trim: true });
+        frame.render_widget(empty, chunks[1]);
+    } else {
+        let items: Vec<ListItem> = app
+            .comparison
+            .choices
+            .iter()
+            .map(|(name, checked)| {
+                let mark = if *checked { "[x]" } else { "[ ]" };
+                ListItem::new(Line::from(format!("{} {}", mark, name)))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(theme.block().title(list_title))
+            .highlight_style(theme.selection_style())
+            .highlight_symbol(theme::selection_symbol_str());
+        frame.render_stateful_widget(list, chunks[1], &mut app.comparison.list_state);
+    }
+
+    let footer = Paragraph::new("Up/Down move, Space toggle, a toggle all, Enter run, Esc back")
+        .style(theme.text_secondary());
+    frame.render_widget(footer, chunks[2]);
+}
+
+pub(crate) fn render_comparison(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
+    let Some(entry) = app.comparison.entry.clone() else {
+        return;
+    };
+    let status = ExecutionStatus::from_history(&entry);
+    let (status_label, status_style) = status_label_and_style(&status, theme);
+    let offset = app.history.time_zone.offset_at(entry.timestamp);
+    let header_lines = vec![
+        Line::from(format!("Script: {}", app.display_path(&entry.script))),
+        Line::from(format!(
+            "Date: {}",
+            history::format_iso8601(entry.timestamp, offset)
+        )),
+        Line::from(vec![
+            Span::raw("Status: "),
+            Span::styled(status_label, status_style),
+            Span::raw(
+                entry
+                    .duration_ms
+                    .map(|ms| format!(" in {}", history::format_duration(ms)))
+                    .unwrap_or_default(),
+            ),
+        ]),
+    ];
+    let chunks = standard_screen_layout(area, header_lines.len() as u16 + 2, 1);
+    let header = Paragraph::new(header_lines).block(theme.block().title("Comparison"));
+    frame.render_widget(header, chunks[0]);
+
+    let visible = (chunks[1].width / MIN_COLUMN_WIDTH).max(1) as usize;
+    let start = app
+        .comparison
+        .column_offset
+        .min(entry.runs.len().saturating_sub(visible));
+    app.comparison.visible_columns = visible;
+    app.comparison.column_offset = start;
+    let runs = &entry.runs[start..entry.runs.len().min(start + visible)];
+    let baseline: Vec<String> = entry
+        .runs
+        .first()
+        .map(|run| {
+            history::format_run_output(run)
+                .lines()
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![
+            Constraint::Ratio(1, runs.len().max(1) as u32);
+            runs.len()
+        ])
+        .split(chunks[1]);
+    for (run, column) in runs.iter().zip(columns.iter()) {
+        let output = history::format_run_output(run);
+        let lines: Vec<Line> = if output.trim().is_empty() {
+            vec![Line::from("(no output)")]
+        } else {
+            // Lines that differ from the first environment stand out.
+            output
+                .lines()
+                .enumerate()
+                .map(|(index, line)| {
+                    if baseline.get(index).map(String::as_str) == Some(line) {
+                        Line::from(line.to_string())
+                    } else {
+                        Line::from(Span::styled(
+                            line.to_string(),
+                            Style::default().fg(theme.semantic.warning.color()),
+                        ))
+                    }
+                })
+                .collect()
+        };
+        let style = if run.error.is_some() {
+            theme.status_error_style()
+        } else if run.success {
+            theme.status_ok_style()
+        } else {
+            theme.status_fail_style()
+        };
+        let mut title = history::run_heading(run);
+        if let Some(ms) = run.duration_ms {
+            title.push_str(&format!(" · {}", history::format_duration(ms)));
+        }
+        let block = theme.block().title(Span::styled(title, style));
+        let paragraph = Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .scroll((app.run_output_scroll, 0));
+        frame.render_widget(paragraph, *column);
+    }
+
+    let footer = if let Some(notice) = &app.comparison.notice {
+        Paragraph::new(notice.as_str()).style(Style::default().fg(theme.semantic.error.color()))
+    } else {
+        let shown = if runs.len() < entry.runs.len() {
+            format!(
+                "{}-{} of {} envs, Left/Right to shift, ",
+                start + 1,
+                start + runs.len(),
+                entry.runs.len()
+            )
+        } else {
+            String::new()
+        };
+        Paragraph::new(format!(
+            "{}Up/Down scroll, r re-run all, F re-run failed, h history, Enter/Esc back",
+            shown
+        ))
+        .style(theme.text_secondary())
+    };
+    frame.render_widget(footer, chunks[2]);
+}
@@ -1,19 +1,23 @@
 use crate::app_meta;
-use crate::lua_widget::WidgetData;
+use crate::lua_widget::{WidgetData, WidgetLine};
 use crate::workspace::Workspace;
 use ratatui::layout::Rect;
-use ratatui::text::Line;
-use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, Wrap};
 use ratatui::Frame;
 
+use super::super::theme::Theme;
+
 pub(crate) fn render_environment(
     frame: &mut Frame,
     area: Rect,
     title: &str,
     lines: Vec<Line<'static>>,
+    theme: &Theme,
 ) {
     let info_block = Paragraph::new(lines)
-        .block(Block::default().borders(Borders::ALL).title(title))
+        .block(theme.block().title(title))
         .wrap(Wrap { trim: true });
     frame.render_widget(info_block, area);
 }
@@ -35,11 +39,16 @@ pub(crate) fn status_info(
     }
 
     if let Some(widget) = widget {
-        let lines = widget
-            .lines
-            .iter()
-            .map(|line| Line::from(line.clone()))
-            .collect();
+        let mut lines: Vec<Line<'static>> = widget.lines.iter().map(widget_line).collect();
+        for (index, action) in widget.actions.iter().take(9).enumerate() {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!("[{}]", index + 1),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(format!(" {}", action.label)),
+            ]));
+        }
         return (widget.title.clone(), lines);
     }
 
@@ -64,3 +73,20 @@ pub(crate) fn status_info(
     lines.push(Line::from(format!("Repo: {}", repo)));
     ("Workspace".to_string(), lines)
 }
+
+/// A widget line as a span with its colors; colors that do not parse are
+/// left out.
+fn widget_line(line: &WidgetLine) -> Line<'static> {
+    let color = |name: &Option<String>| name.as_deref().and_then(|name| name.parse::<Color>().ok());
+    let mut style = Style::default();
+    if let Some(fg) = color(&line.fg) {
+        style = style.fg(fg);
+    }
+    if let Some(bg) = color(&line.bg) {
+        style = style.bg(bg);
+    }
+    if line.bold {
+        style = style.add_modifier(Modifier::BOLD);
+    }
+    Line::from(Span::styled(line.text.clone(), style))
+}
@@ -1,7 +1,7 @@
 use ratatui::layout::Rect;
 use ratatui::style::Style;
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Wrap};
+use ratatui::widgets::{List, ListItem, Paragraph, Wrap};
 use ratatui::Frame;
 
 use super::super::app::App;
@@ -37,7 +37,7 @@ fn build_preview_lines(app: &App, theme: &Theme) -> Vec<Line<'static>> {
 }
 
 pub(crate) fn render_envs(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
-    let outer = Block::default().borders(Borders::ALL).title("Environments");
+    let outer = theme.block().title("Environments");
     let inner = outer.inner(area);
     frame.render_widget(outer, area);
 
@@ -76,7 +76,7 @@ pub(crate) fn render_envs(frame: &mut Frame, area: Rect, app: &mut App, theme: &
     let chunks = standard_screen_layout(inner, info_height, 2);
 
     let info = Paragraph::new(info_lines)
-        .block(Block::default().borders(Borders::ALL).title("Status"))
+        .block(theme.block().title("Status"))
         .wrap(Wrap { trim: true });
     frame.render_widget(info, chunks[0]);
 
@@ -84,7 +84,7 @@ pub(crate) fn render_envs(frame: &mut Frame, area: Rect, app: &mut App, theme: &
 
     if app.environment.entries.is_empty() {
         let empty = Paragraph::new("No environment files found.")
-            .block(Block::default().borders(Borders::ALL).title("Files"))
+            .block(theme.block().title("Files"))
             .wrap(Wrap { trim: true });
         frame.render_widget(empty, files_chunks[0]);
     } else {
@@ -110,7 +110,7 @@ pub(crate) fn render_envs(frame: &mut Frame, area: Rect, app: &mut App, theme: &
             .collect();
 
         let list = List::new(items)
-            .block(Block::default().borders(Borders::ALL).title("Files"))
+            .block(theme.block().title("Files"))
             .highlight_style(theme.selection_style())
             .highlight_symbol(theme::selection_symbol_str());
         frame.render_stateful_widget(list, files_chunks[0], &mut app.environment.list_state);
@@ -118,7 +118,7 @@ pub(crate) fn render_envs(frame: &mut Frame, area: Rect, app: &mut App, theme: &
 
     let preview_lines = build_preview_lines(app, theme);
     let preview = Paragraph::new(preview_lines)
-        .block(Block::default().borders(Borders::ALL).title("Preview"))
+        .block(theme.block().title("Preview"))
         .wrap(Wrap { trim: false })
         .scroll((app.environment.preview_scroll, 0));
     frame.render_widget(preview, files_chunks[1]);
@@ -1,7 +1,7 @@
 use ratatui::layout::Rect;
 use ratatui::style::Style;
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::widgets::{Paragraph, Wrap};
 use ratatui::Frame;
 
 use super::super::theme::Theme;
@@ -16,7 +16,7 @@ pub(crate) fn render_error(frame: &mut Frame, area: Rect, message: &str, theme:
         Line::from("Press Enter to return, Esc to quit"),
     ];
     let block = Paragraph::new(lines)
-        .block(Block::default().borders(Borders::ALL).title("Error"))
+        .block(theme.block().title("Error"))
         .wrap(Wrap { trim: true });
     frame.render_widget(block, area);
 }
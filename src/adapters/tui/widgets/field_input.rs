@@ -1,7 +1,7 @@
 use ratatui::layout::Rect;
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::widgets::{Paragraph, Wrap};
 use ratatui::Frame;
 
 use super::super::app::App;
@@ -36,19 +36,47 @@ pub(crate) fn render_field_input(frame: &mut Frame, area: Rect, app: &mut App, t
             Span::raw(app.field_input.schema_description.as_deref().unwrap_or("-")),
         ]),
     ];
+    if let Some(owner) = app.field_input.owner.as_deref() {
+        header_lines.push(Line::from(vec![
+            Span::styled("Owner: ", label_style),
+            Span::raw(owner),
+        ]));
+    }
+    if !app.field_input.missing_env.is_empty() {
+        header_lines.push(Line::from(Span::styled(
+            format!(
+                "Missing env: {} (runs are refused until an env file sets them)",
+                app.field_input.missing_env.join(", ")
+            ),
+            Style::default().fg(theme.semantic.warning.color()),
+        )));
+    }
     if let Some(message) = &app.field_input.error {
         header_lines.push(Line::from(Span::styled(
             format!("Error: {}", message),
             Style::default().fg(theme.semantic.error.color()),
         )));
     }
+    if let Some(command) = &app.field_input.dry_run {
+        header_lines.push(Line::from(vec![
+            Span::styled("Dry run: ", label_style),
+            Span::styled(
+                command.as_str(),
+                Style::default().fg(theme.semantic.info.color()),
+            ),
+        ]));
+    }
     let header_height = header_lines.len() as u16 + 2;
     let header = Paragraph::new(header_lines)
-        .block(Block::default().borders(Borders::ALL).title("Schema"))
+        .block(theme.block().title("Schema"))
         .wrap(Wrap { trim: true });
 
-    let footer = Paragraph::new("Tab/Shift+Tab to move, Enter to run, Ctrl+B back, Esc quit")
-        .style(theme.text_secondary());
+    let footer = Paragraph::new(if app.field_input.targets {
+        "Tab/Shift+Tab to move, Enter to run, Ctrl+D dry run, Ctrl+E run in envs, Ctrl+T run on hosts, Ctrl+B back, Esc quit"
+    } else {
+        "Tab/Shift+Tab to move, Enter to run, Ctrl+D dry run, Ctrl+E run in envs, Ctrl+B back, Esc quit"
+    })
+    .style(theme.text_secondary());
 
     let footer_height = 1u16;
     let chunks = standard_screen_layout(area, header_height, footer_height);
@@ -59,7 +87,7 @@ pub(crate) fn render_field_input(frame: &mut Frame, area: Rect, app: &mut App, t
 }
 
 fn render_field_boxes(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
-    let outer = Block::default().borders(Borders::ALL).title("Fields");
+    let outer = theme.block().title("Fields");
     let inner = outer.inner(area);
     frame.render_widget(outer, area);
 
@@ -128,10 +156,7 @@ fn render_field_boxes(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
                 Span::styled(value_text, value_style),
             ]),
         ];
-        let block = Block::default()
-            .borders(Borders::ALL)
-            .title(title)
-            .border_style(border_style);
+        let block = theme.block().title(title).border_style(border_style);
         let rect = Rect {
             x: inner.x,
             y,
@@ -0,0 +1,56 @@
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Clear, Paragraph};
+use ratatui::Frame;
+
+use super::super::app::App;
+use super::super::events;
+use super::super::theme::Theme;
+use super::common::centered;
+use crate::keymap::Action;
+
+/// Lists the keys of the current screen in a box over it.
+pub(crate) fn render_help(frame: &mut Frame, app: &App, theme: &Theme) {
+    let rows: Vec<(String, &str)> = events::bindings(app)
+        .iter()
+        .map(|binding| (binding.keys.label(&app.keys), binding.help))
+        .collect();
+    let key_width = rows
+        .iter()
+        .map(|(keys, _)| keys.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let key_style = Style::default()
+        .fg(theme.brand.accent.color())
+        .add_modifier(Modifier::BOLD);
+    let mut lines: Vec<Line> = rows
+        .iter()
+        .map(|(keys, help)| {
+            Line::from(vec![
+                Span::styled(format!("{:<width$}  ", keys, width = key_width), key_style),
+                Span::raw(help.to_string()),
+            ])
+        })
+        .collect();
+    if lines.is_empty() {
+        lines.push(Line::from("No keys on this screen."));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!(
+            "{} shows this list. Press any key to close it.",
+            app.keys.labels(Action::Help).join("/")
+        ),
+        theme.text_muted(),
+    )));
+
+    let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 4;
+    let height = lines.len() as u16 + 2;
+    let area = centered(frame.size(), width, height);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(theme.block().title("Keys")),
+        area,
+    );
+}
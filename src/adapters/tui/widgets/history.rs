@@ -1,10 +1,10 @@
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::Style;
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table, Wrap};
+use ratatui::widgets::{Cell, Paragraph, Row, Table, Wrap};
 use ratatui::Frame;
 
-use super::super::app::{App, ExecutionStatus, HistoryFocus};
+use super::super::app::{App, ExecutionStatus, HistoryFocus, RerunScope};
 use super::super::theme::Theme;
 use super::common::status_label_and_style;
 use crate::history;
@@ -24,37 +24,68 @@ pub(crate) fn render_history(frame: &mut Frame, area: Rect, app: &mut App, theme
     render_history_list(frame, body_chunks[0], app, theme);
     render_history_output(frame, body_chunks[1], app, theme);
 
-    let footer_text = match app.history.focus {
-        HistoryFocus::List => {
-            "Up/Down to select, Enter to view output, Alt+E envs, Esc/q to go back"
-        }
-        HistoryFocus::Output => "Up/Down to scroll, PgUp/PgDn, Esc to return, q to go back",
+    let footer = if let Some(scope) = app.history.confirm_rerun {
+        let target = app
+            .current_history_entry()
+            .map(|entry| app.display_path(&entry.script))
+            .unwrap_or_default();
+        let what = match scope {
+            RerunScope::All => "with the same args",
+            RerunScope::Failed => "for its failed runs only",
+        };
+        Paragraph::new(format!(
+            "Re-run {} {}? y/Enter to confirm, any other key to cancel",
+            target, what
+        ))
+        .style(Style::default().fg(theme.semantic.warning.color()))
+    } else if let Some(notice) = &app.history.notice {
+        Paragraph::new(notice.as_str()).style(Style::default().fg(theme.semantic.error.color()))
+    } else {
+        let footer_text = match app.history.focus {
+            HistoryFocus::List => {
+                "Up/Down to select, Enter to view output, R re-run, F re-run failed, v compare envs, o artifacts, Alt+E envs, Esc/q to go back"
+            }
+            HistoryFocus::Output => {
+                "Up/Down to scroll, PgUp/PgDn, f full output, o artifacts, Esc to return, q to go back"
+            }
+        };
+        Paragraph::new(footer_text).style(theme.text_secondary())
     };
-    let footer = Paragraph::new(footer_text).style(theme.text_secondary());
     frame.render_widget(footer, chunks[1]);
 }
 
 fn render_history_list(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
     if app.history.entries.is_empty() {
         let empty = Paragraph::new("No executions yet.")
-            .block(Block::default().borders(Borders::ALL).title("History"))
+            .block(theme.block().title("History"))
             .wrap(Wrap { trim: true });
         frame.render_widget(empty, area);
         return;
     }
 
+    let now = history::timestamp_ms();
     let rows: Vec<Row> = app
         .history
         .entries
         .iter()
         .map(|entry| {
             let name = app.display_path(&entry.script);
-            let date = history::format_timestamp(entry.timestamp);
+            let date = if now - entry.timestamp < RELATIVE_DATE_WINDOW_MS {
+                history::format_relative(entry.timestamp, now)
+            } else {
+                let offset = app.history.time_zone.offset_at(entry.timestamp);
+                history::format_timestamp(entry.timestamp, offset)
+            };
             let status = ExecutionStatus::from_history(entry);
             let (status_label, status_style) = status_label_and_style(&status, theme);
+            let duration = entry
+                .duration_ms
+                .map(history::format_duration)
+                .unwrap_or_else(|| "-".to_string());
             Row::new(vec![
                 Cell::from(Span::styled(status_label, status_style)),
                 Cell::from(Span::raw(date)),
+                Cell::from(Span::raw(duration)),
                 Cell::from(Span::raw(name)),
             ])
         })
@@ -63,6 +94,7 @@ fn render_history_list(frame: &mut Frame, area: Rect, app: &mut App, theme: &The
     let header = Row::new(vec![
         Cell::from(Span::styled("Status", theme.text_secondary())),
         Cell::from(Span::styled("Date", theme.text_secondary())),
+        Cell::from(Span::styled("Duration", theme.text_secondary())),
         Cell::from(Span::styled("Script", theme.text_secondary())),
     ]);
     let highlight_style = match app.history.focus {
@@ -79,11 +111,12 @@ fn render_history_list(frame: &mut Frame, area: Rect, app: &mut App, theme: &The
         [
             Constraint::Length(HISTORY_STATUS_WIDTH),
             Constraint::Length(HISTORY_DATE_WIDTH),
+            Constraint::Length(HISTORY_DURATION_WIDTH),
             Constraint::Min(HISTORY_MIN_SCRIPT_WIDTH),
         ],
     )
     .header(header)
-    .block(Block::default().borders(Borders::ALL).title("History"))
+    .block(theme.block().title("History"))
     .highlight_style(highlight_style)
     .highlight_symbol(highlight_symbol);
 
@@ -101,14 +134,51 @@ fn render_history_output(frame: &mut Frame, area: Rect, app: &mut App, theme: &T
         };
         let status = ExecutionStatus::from_history(entry);
         let (status_label, status_style) = status_label_and_style(&status, theme);
+        let offset = app.history.time_zone.offset_at(entry.timestamp);
         lines.push(Line::from(format!("Script: {}", name)));
-        lines.push(Line::from(format!("Args: {}", args)));
+        lines.push(Line::from(format!(
+            "Date: {}",
+            history::format_iso8601(entry.timestamp, offset)
+        )));
+        if entry.runs.is_empty() {
+            lines.push(Line::from(format!("Args: {}", args)));
+        } else {
+            let labels: Vec<&str> = entry.runs.iter().map(|run| run.label.as_str()).collect();
+            lines.push(Line::from(format!("Environments: {}", labels.join(", "))));
+        }
+        if !entry.env_overrides.is_empty() {
+            lines.push(Line::from(format!(
+                "Env overrides: {}",
+                entry.env_overrides.join(", ")
+            )));
+        }
         lines.push(Line::from(vec![
             Span::raw("Status: "),
             Span::styled(status_label, status_style),
         ]));
+        if let Some(duration_ms) = entry.duration_ms {
+            lines.push(Line::from(format!(
+                "Duration: {}",
+                history::format_duration(duration_ms)
+            )));
+        }
+        if entry.retries > 0 {
+            lines.push(Line::from(format!("Retries: {}", entry.retries)));
+        }
+        if !entry.files.is_empty() {
+            lines.push(Line::from(format!(
+                "Files: {}",
+                history::format_files(entry)
+            )));
+        }
+        if let Some(usage) = &entry.usage {
+            lines.push(Line::from(format!(
+                "Usage: {}",
+                history::format_usage(usage)
+            )));
+        }
         lines.push(Line::from(""));
-        let output = history::format_output(entry);
+        let output = app.entry_output(entry);
         if output.trim().is_empty() {
             lines.push(Line::from("(no output)"));
         } else {
@@ -126,7 +196,7 @@ fn render_history_output(frame: &mut Frame, area: Rect, app: &mut App, theme: &T
         app.run_output_scroll = max_scroll.min(u16::MAX as usize) as u16;
     }
 
-    let mut block = Block::default().borders(Borders::ALL).title("Output");
+    let mut block = theme.block().title("Output");
     if app.history.focus == HistoryFocus::Output {
         let border_style = theme.selection_border_style();
         block = block.border_style(border_style).title_style(border_style);
@@ -140,8 +210,11 @@ fn render_history_output(frame: &mut Frame, area: Rect, app: &mut App, theme: &T
     frame.render_widget(output, area);
 }
 
-const HISTORY_STATUS_WIDTH: u16 = 10;
+/// Entries newer than this show a relative age instead of a date.
+const RELATIVE_DATE_WINDOW_MS: i64 = 7 * 86_400_000;
+const HISTORY_STATUS_WIDTH: u16 = 12;
 const HISTORY_DATE_WIDTH: u16 = 16;
+const HISTORY_DURATION_WIDTH: u16 = 8;
 const HISTORY_MIN_SCRIPT_WIDTH: u16 = 10;
 const HISTORY_COLUMN_SPACING: u16 = 1;
 const HISTORY_HIGHLIGHT_WIDTH: u16 = 2;
@@ -158,8 +231,11 @@ fn history_list_width(total_width: u16, app: &App) -> u16 {
         .unwrap_or(0)
         .max(HISTORY_MIN_SCRIPT_WIDTH);
 
-    let content_width =
-        HISTORY_STATUS_WIDTH + HISTORY_DATE_WIDTH + max_script + HISTORY_COLUMN_SPACING * 2;
+    let content_width = HISTORY_STATUS_WIDTH
+        + HISTORY_DATE_WIDTH
+        + HISTORY_DURATION_WIDTH
+        + max_script
+        + HISTORY_COLUMN_SPACING * 3;
     let desired = content_width + HISTORY_BORDER_WIDTH + HISTORY_HIGHLIGHT_WIDTH;
     let min_output = HISTORY_MIN_OUTPUT_WIDTH.min(total_width.saturating_sub(10).max(1));
     let max_list = total_width.saturating_sub(min_output);
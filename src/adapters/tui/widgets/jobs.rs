@@ -0,0 +1,77 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::Span;
+use ratatui::widgets::{Cell, Paragraph, Row, Table, Wrap};
+use ratatui::Frame;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::super::app::App;
+use super::super::theme::Theme;
+use crate::history;
+
+pub(crate) fn render_jobs(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(2)])
+        .split(area);
+
+    let block = theme.block().title("Jobs");
+    if let Some(error) = &app.jobs.error {
+        let message = Paragraph::new(error.as_str())
+            .style(Style::default().fg(theme.semantic.error.color()))
+            .block(block)
+            .wrap(Wrap { trim: true });
+        frame.render_widget(message, chunks[0]);
+    } else if app.jobs.jobs.is_empty() {
+        let empty = Paragraph::new("No runs are holding or waiting for a concurrency group.")
+            .block(block)
+            .wrap(Wrap { trim: true });
+        frame.render_widget(empty, chunks[0]);
+    } else {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_millis())
+            .unwrap_or_default();
+        let rows = app.jobs.jobs.iter().map(|job| {
+            let (state, style) = if job.position == 0 {
+                (
+                    "running".to_string(),
+                    Style::default().fg(theme.semantic.info.color()),
+                )
+            } else {
+                (format!("#{}", job.position), theme.text_secondary())
+            };
+            let waiting = now_ms.saturating_sub(job.queued_at_ms) as u64;
+            Row::new(vec![
+                Cell::from(job.group.clone()),
+                Cell::from(Span::styled(state, style)),
+                Cell::from(app.display_path(&job.script)),
+                Cell::from(job.pid.to_string()),
+                Cell::from(history::format_duration(waiting)),
+            ])
+        });
+        let header = Row::new(vec![
+            Cell::from(Span::styled("Group", theme.text_secondary())),
+            Cell::from(Span::styled("Position", theme.text_secondary())),
+            Cell::from(Span::styled("Script", theme.text_secondary())),
+            Cell::from(Span::styled("PID", theme.text_secondary())),
+            Cell::from(Span::styled("Since", theme.text_secondary())),
+        ]);
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(20),
+                Constraint::Length(10),
+                Constraint::Min(20),
+                Constraint::Length(8),
+                Constraint::Length(8),
+            ],
+        )
+        .header(header)
+        .block(block);
+        frame.render_widget(table, chunks[0]);
+    }
+
+    let footer = Paragraph::new("r refresh, Esc to return").style(theme.text_secondary());
+    frame.render_widget(footer, chunks[1]);
+}
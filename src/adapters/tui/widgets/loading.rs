@@ -1,15 +1,17 @@
 use ratatui::layout::{Alignment, Rect};
 use ratatui::text::Line;
-use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::widgets::{Paragraph, Wrap};
 use ratatui::Frame;
 
-pub(crate) fn render_loading(frame: &mut Frame, area: Rect) {
+use super::super::theme::Theme;
+
+pub(crate) fn render_loading(frame: &mut Frame, area: Rect, theme: &Theme) {
     let lines = vec![
         Line::from("Loading environment..."),
         Line::from("Please wait."),
     ];
     let block = Paragraph::new(lines)
-        .block(Block::default().borders(Borders::ALL).title("Loading"))
+        .block(theme.block().title("Loading"))
         .alignment(Alignment::Center)
         .wrap(Wrap { trim: true });
     frame.render_widget(block, area);
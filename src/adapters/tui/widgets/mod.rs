@@ -1,12 +1,23 @@
 pub(crate) mod common;
+pub(crate) mod comparison;
 pub(crate) mod environment;
 pub(crate) mod envs;
 pub(crate) mod error;
 pub(crate) mod field_input;
+pub(crate) mod help;
 pub(crate) mod history;
+pub(crate) mod jobs;
 pub(crate) mod loading;
+pub(crate) mod pipelines;
+pub(crate) mod plan;
+pub(crate) mod preflight;
+pub(crate) mod prompt;
+pub(crate) mod recent;
 pub(crate) mod run_result;
 pub(crate) mod running;
 pub(crate) mod schema;
 pub(crate) mod scripts;
 pub(crate) mod search;
+pub(crate) mod source;
+pub(crate) mod tutorial;
+pub(crate) mod workspaces;
@@ -0,0 +1,127 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::Span;
+use ratatui::widgets::{Cell, Paragraph, Row, Table, Wrap};
+use ratatui::Frame;
+
+use super::super::app::App;
+use super::super::theme::Theme;
+use crate::history;
+use crate::status::{FAIL_SYMBOL, OK_SYMBOL, WARN_SYMBOL};
+use crate::use_cases::{StageState, StageStatus};
+
+pub(crate) fn render_pipelines(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
+    let stage_count = app
+        .pipelines
+        .run
+        .as_ref()
+        .map_or(0, |(_, stages)| stages.len() as u16);
+    let error_height = if app.pipelines.error.is_some() { 3 } else { 0 };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(3),
+            Constraint::Length(if stage_count > 0 { stage_count + 2 } else { 0 }),
+            Constraint::Length(error_height),
+            Constraint::Length(2),
+        ])
+        .split(area);
+
+    let block = theme.block().title("Pipelines");
+    if app.pipelines.entries.is_empty() {
+        let empty = Paragraph::new(
+            "No pipelines. Add one under .omaken/pipelines/, or give a script a DependsOn.",
+        )
+        .block(block)
+        .wrap(Wrap { trim: true });
+        frame.render_widget(empty, chunks[0]);
+    } else {
+        let rows = app.pipelines.entries.iter().map(|entry| {
+            let stages = match &entry.pipeline {
+                Ok(pipeline) => Span::styled(
+                    pipeline
+                        .stages
+                        .iter()
+                        .map(|stage| stage.id())
+                        .collect::<Vec<_>>()
+                        .join(" -> "),
+                    theme.text_secondary(),
+                ),
+                Err(err) => Span::styled(
+                    format!("{} {}", WARN_SYMBOL, err),
+                    theme.status_error_style(),
+                ),
+            };
+            Row::new(vec![Cell::from(entry.name.clone()), Cell::from(stages)])
+        });
+        let table = Table::new(rows, [Constraint::Length(28), Constraint::Min(20)])
+            .block(block)
+            .highlight_style(theme.selection_style())
+            .highlight_symbol(super::super::theme::selection_symbol_str());
+        frame.render_stateful_widget(table, chunks[0], &mut app.pipelines.table_state);
+    }
+
+    if let Some((name, stages)) = &app.pipelines.run {
+        let rows = stages.iter().map(|stage| stage_row(app, stage, theme));
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(3),
+                Constraint::Length(20),
+                Constraint::Length(10),
+                Constraint::Min(20),
+            ],
+        )
+        .block(theme.block().title(format!("Stages: {}", name)));
+        frame.render_widget(table, chunks[1]);
+    }
+
+    if let Some(error) = &app.pipelines.error {
+        let error = Paragraph::new(error.as_str())
+            .style(theme.status_error_style())
+            .wrap(Wrap { trim: true });
+        frame.render_widget(error, chunks[2]);
+    }
+
+    let footer = Paragraph::new("Up/Down move, Enter run the pipeline, Esc to return")
+        .style(theme.text_secondary());
+    frame.render_widget(footer, chunks[3]);
+}
+
+/// Status, stage, duration, then the stage's outputs or error.
+fn stage_row<'a>(app: &App, stage: &'a StageState, theme: &Theme) -> Row<'a> {
+    let (symbol, style) = match stage.status {
+        StageStatus::Pending => ("·", theme.text_muted()),
+        StageStatus::Running => ("…", Style::default().fg(theme.semantic.warning.color())),
+        StageStatus::Passed => (OK_SYMBOL, theme.status_ok_style()),
+        StageStatus::Failed => (FAIL_SYMBOL, theme.status_fail_style()),
+        StageStatus::Skipped => ("-", theme.text_muted()),
+    };
+    let detail = match (&stage.error, stage.status) {
+        (Some(error), _) => Span::styled(error.clone(), theme.status_error_style()),
+        (None, StageStatus::Pending | StageStatus::Running | StageStatus::Skipped) => {
+            Span::styled(app.display_path(&stage.script), theme.text_secondary())
+        }
+        (None, _) => Span::styled(
+            stage
+                .outputs
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect::<Vec<_>>()
+                .join(", "),
+            theme.text_secondary(),
+        ),
+    };
+    Row::new(vec![
+        Cell::from(Span::styled(symbol, style)),
+        Cell::from(stage.id.clone()),
+        Cell::from(Span::styled(
+            stage
+                .duration_ms
+                .map(history::format_duration)
+                .unwrap_or_else(|| stage.status.label().to_string()),
+            theme.text_secondary(),
+        )),
+        Cell::from(detail),
+    ])
+}
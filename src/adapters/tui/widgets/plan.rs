@@ -0,0 +1,75 @@
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, Wrap};
+use ratatui::Frame;
+
+use super::super::app::App;
+use super::super::theme::Theme;
+use super::common::standard_screen_layout;
+
+pub(crate) fn render_plan_review(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
+    let Some(review) = &app.plan.review else {
+        return;
+    };
+    let summary = review.summary;
+    let count_style = |count: u64, color: ratatui::style::Color| {
+        if count == 0 {
+            theme.text_secondary()
+        } else {
+            Style::default().fg(color).add_modifier(Modifier::BOLD)
+        }
+    };
+    let changes = if summary.has_changes() {
+        Line::from(vec![
+            Span::raw("Changes: "),
+            Span::styled(
+                format!("+{} to add", summary.add),
+                count_style(summary.add, theme.semantic.success.color()),
+            ),
+            Span::raw("  "),
+            Span::styled(
+                format!("~{} to change", summary.change),
+                count_style(summary.change, theme.semantic.warning.color()),
+            ),
+            Span::raw("  "),
+            Span::styled(
+                format!("-{} to destroy", summary.destroy),
+                count_style(summary.destroy, theme.semantic.error.color()),
+            ),
+        ])
+    } else {
+        Line::from(vec![
+            Span::raw("Changes: "),
+            Span::styled(
+                "none, infrastructure matches the configuration",
+                Style::default().fg(theme.semantic.success.color()),
+            ),
+        ])
+    };
+    let header_lines = vec![
+        Line::from(format!("Script: {}", app.display_path(&review.script))),
+        changes,
+    ];
+    let chunks = standard_screen_layout(area, header_lines.len() as u16 + 2, 1);
+    let header = Paragraph::new(header_lines)
+        .block(theme.block().title("Terraform plan"))
+        .wrap(Wrap { trim: true });
+    frame.render_widget(header, chunks[0]);
+
+    let output = Paragraph::new(review.output.as_str())
+        .block(theme.block().title("Plan output"))
+        .wrap(Wrap { trim: false })
+        .scroll((app.plan.scroll, 0));
+    frame.render_widget(output, chunks[1]);
+
+    let footer = if summary.has_changes() {
+        "y apply this plan, n/Esc discard, Up/Down/PgUp/PgDn scroll"
+    } else {
+        "Nothing to apply. Esc back, Up/Down/PgUp/PgDn scroll"
+    };
+    frame.render_widget(
+        Paragraph::new(footer).style(theme.text_secondary()),
+        chunks[2],
+    );
+}
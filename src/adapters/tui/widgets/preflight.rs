@@ -0,0 +1,57 @@
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, Wrap};
+use ratatui::Frame;
+
+use super::super::app::App;
+use super::super::theme::Theme;
+
+pub(crate) fn render_preflight(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
+    let Some(failure) = &app.preflight.failure else {
+        return;
+    };
+    let mut lines = vec![
+        Line::from(format!(
+            "Script: {}",
+            app.display_path(&failure.request.script)
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("{} credentials are missing or expired", failure.check.name),
+            Style::default()
+                .fg(theme.semantic.error.color())
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            format!("{}: {}", failure.check.command.label(), failure.message),
+            theme.text_secondary(),
+        )),
+        Line::from(""),
+    ];
+    match &failure.check.login {
+        Some(login) => {
+            lines.push(Line::from(vec![
+                Span::raw("Run login "),
+                Span::styled(login.label(), Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw("?"),
+            ]));
+            lines.push(Line::from(""));
+            lines.push(Line::from(
+                "y/Enter log in and check again, s open the script anyway, n/Esc cancel",
+            ));
+        }
+        None => {
+            lines.push(Line::from(format!(
+                "No login is configured for {}; set one under [preflight.{}] in omakure.toml.",
+                failure.check.name, failure.check.name
+            )));
+            lines.push(Line::from(""));
+            lines.push(Line::from("s open the script anyway, n/Esc cancel"));
+        }
+    }
+    let block = Paragraph::new(lines)
+        .block(theme.block().title("Credential check failed"))
+        .wrap(Wrap { trim: true });
+    frame.render_widget(block, area);
+}
@@ -0,0 +1,64 @@
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Clear, Paragraph, Wrap};
+use ratatui::Frame;
+
+use super::super::app::App;
+use super::super::theme::Theme;
+use super::common::centered;
+use crate::domain::PromptKind;
+
+/// Shows the question a running script waits on in a box over the screen.
+pub(crate) fn render_prompt(frame: &mut Frame, app: &App, theme: &Theme) {
+    let Some(open) = &app.prompt.open else {
+        return;
+    };
+    let prompt = &open.prompt;
+    let key_style = Style::default()
+        .fg(theme.brand.accent.color())
+        .add_modifier(Modifier::BOLD);
+    let default = prompt.default.as_deref().unwrap_or("");
+    let mut lines = vec![Line::from(prompt.message.clone()), Line::from("")];
+    match prompt.kind {
+        PromptKind::Confirm => {
+            lines.push(Line::from(vec![
+                Span::styled("y", key_style),
+                Span::raw(" yes  "),
+                Span::styled("n", key_style),
+                Span::raw(" no  "),
+                Span::styled("Enter/Esc", key_style),
+                Span::raw(format!(" {}", prompt.fallback())),
+            ]));
+        }
+        PromptKind::Value => {
+            let input = if prompt.secret {
+                "*".repeat(open.input.chars().count())
+            } else {
+                open.input.clone()
+            };
+            lines.push(Line::from(vec![
+                Span::styled("> ", key_style),
+                Span::raw(input),
+                Span::styled("_", theme.text_muted()),
+            ]));
+            lines.push(Line::from(""));
+            let hint = if default.is_empty() {
+                "Enter to send, Esc to send nothing".to_string()
+            } else {
+                format!("Enter to send, Esc for {}", default)
+            };
+            lines.push(Line::from(Span::styled(hint, theme.text_muted())));
+        }
+    }
+
+    let width = (prompt.message.chars().count() as u16 + 4).clamp(40, 80);
+    let height = lines.len() as u16 + 2 + prompt.message.chars().count() as u16 / 76;
+    let area = centered(frame.size(), width, height);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(theme.block().title("Script asks"))
+            .wrap(Wrap { trim: false }),
+        area,
+    );
+}
@@ -0,0 +1,77 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::Span;
+use ratatui::widgets::{Cell, Paragraph, Row, Table, Wrap};
+use ratatui::Frame;
+
+use super::super::app::App;
+use super::super::theme::Theme;
+use crate::history;
+use crate::status::{FAIL_SYMBOL, OK_SYMBOL};
+
+pub(crate) fn render_recent(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(2)])
+        .split(area);
+
+    let block = theme.block().title("Recent");
+    if app.recent.entries.is_empty() {
+        let empty = Paragraph::new("No scripts run yet. Scripts you run show up here.")
+            .block(block)
+            .wrap(Wrap { trim: true });
+        frame.render_widget(empty, chunks[0]);
+    } else {
+        let time_zone = app.history.time_zone;
+        let now = history::timestamp_ms();
+        let rows = app.recent.entries.iter().enumerate().map(|(index, entry)| {
+            let (symbol, color) = if entry.success {
+                (OK_SYMBOL, theme.semantic.success.color())
+            } else {
+                (FAIL_SYMBOL, theme.semantic.error.color())
+            };
+            Row::new(vec![
+                Cell::from(Span::styled(
+                    (index + 1).to_string(),
+                    theme.text_secondary(),
+                )),
+                Cell::from(entry.script.to_string_lossy().to_string()),
+                Cell::from(Span::styled(symbol, Style::default().fg(color))),
+                Cell::from(history::format_timestamp(
+                    entry.timestamp,
+                    time_zone.offset_at(entry.timestamp),
+                )),
+                Cell::from(Span::styled(
+                    history::format_relative(entry.timestamp, now),
+                    theme.text_secondary(),
+                )),
+            ])
+        });
+        let header = Row::new(vec![
+            Cell::from(""),
+            Cell::from(Span::styled("Script", theme.text_secondary())),
+            Cell::from(""),
+            Cell::from(Span::styled("Last run", theme.text_secondary())),
+            Cell::from(""),
+        ]);
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(2),
+                Constraint::Min(20),
+                Constraint::Length(2),
+                Constraint::Length(17),
+                Constraint::Length(12),
+            ],
+        )
+        .header(header)
+        .block(block)
+        .highlight_style(theme.selection_style())
+        .highlight_symbol(super::super::theme::selection_symbol_str());
+        frame.render_stateful_widget(table, chunks[0], &mut app.recent.table_state);
+    }
+
+    let footer = Paragraph::new("Up/Down move, Enter or 1-9 open, Esc to return")
+        .style(theme.text_secondary());
+    frame.render_widget(footer, chunks[1]);
+}
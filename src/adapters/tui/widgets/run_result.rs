@@ -1,6 +1,7 @@
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::widgets::{Paragraph, Wrap};
 use ratatui::Frame;
 
 use super::super::app::{App, ExecutionStatus};
@@ -24,17 +25,33 @@ pub(crate) fn render_run_result(frame: &mut Frame, area: Rect, app: &mut App, th
     }
 
     let output = Paragraph::new(lines)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Last run output"),
-        )
+        .block(theme.block().title("Last run output"))
         .wrap(Wrap { trim: false })
         .scroll((app.run_output_scroll, 0));
     frame.render_widget(output, chunks[0]);
 
-    let footer = Paragraph::new("Up/Down to scroll, PgUp/PgDn, Enter/Esc to return, h for history")
-        .style(theme.text_secondary());
+    let footer = if let Some(note) = &app.run_result.note {
+        Paragraph::new(vec![
+            Line::from(vec![
+                Span::styled("Note: ", theme.text_secondary()),
+                Span::raw(note.clone()),
+            ]),
+            Line::from(Span::styled(
+                "Enter to save, Esc to cancel",
+                theme.text_secondary(),
+            )),
+        ])
+    } else if let Some(notice) = &app.run_result.notice {
+        Paragraph::new(notice.as_str()).style(Style::default().fg(theme.semantic.info.color()))
+    } else {
+        Paragraph::new(vec![
+            Line::from(
+                "r re-run, e edit & re-run, o artifacts, c copy command, n note, f full output",
+            ),
+            Line::from("Up/Down to scroll, PgUp/PgDn, Enter/Esc to return, h for history"),
+        ])
+        .style(theme.text_secondary())
+    };
     frame.render_widget(footer, chunks[1]);
 }
 
@@ -58,12 +75,48 @@ fn render_lines(app: &App, theme: &Theme) -> Vec<Line<'static>> {
     let (status_label, status_style) = status_label_and_style(&status, theme);
     lines.push(Line::from(format!("Script: {}", name)));
     lines.push(Line::from(format!("Args: {}", args)));
-    lines.push(Line::from(vec![
+    let mut status_spans = vec![
         Span::raw("Status: "),
         Span::styled(status_label, status_style),
-    ]));
+    ];
+    if let Some(duration_ms) = entry.duration_ms {
+        status_spans.push(Span::raw(format!(
+            " in {}",
+            history::format_duration(duration_ms)
+        )));
+    }
+    lines.push(Line::from(status_spans));
+    if status != ExecutionStatus::Success {
+        if let Some(owner) = &app.run_result.owner {
+            lines.push(Line::from(vec![
+                Span::styled("Owner: ", theme.text_secondary()),
+                Span::raw(owner.clone()),
+            ]));
+        }
+    }
+    if !entry.files.is_empty() {
+        lines.push(Line::from(format!(
+            "Files: {} (o to open)",
+            history::format_files(entry)
+        )));
+    }
+    if let Some(usage) = &entry.usage {
+        lines.push(Line::from(format!(
+            "Usage: {}",
+            history::format_usage(usage)
+        )));
+    }
+    if let Some(suggestion) = &app.run_result.suggestion {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "Next: you usually run {} after this (Tab to open)",
+                suggestion.display()
+            ),
+            Style::default().fg(theme.semantic.info.color()),
+        )));
+    }
     lines.push(Line::from(""));
-    let output = history::format_output(entry);
+    let output = app.entry_output(entry);
     if output.trim().is_empty() {
         lines.push(Line::from("(no output)"));
     } else {
@@ -1,11 +1,14 @@
 use ratatui::layout::{Alignment, Rect};
-use ratatui::text::Line;
-use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Paragraph, Wrap};
 use ratatui::Frame;
 
 use super::super::app::App;
+use super::super::theme::Theme;
+use crate::history;
 
-pub(crate) fn render_running(frame: &mut Frame, area: Rect, app: &mut App) {
+pub(crate) fn render_running(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
     let script_name = app
         .field_input
         .selected_script
@@ -19,16 +22,61 @@ pub(crate) fn render_running(frame: &mut Frame, area: Rect, app: &mut App) {
         app.field_input.args.join(" ")
     };
 
-    let lines = vec![
-        Line::from("Running script..."),
-        Line::from(""),
-        Line::from(format!("Script: {}", script_name)),
-        Line::from(format!("Args: {}", args)),
-        Line::from(""),
-        Line::from("Please wait."),
-    ];
+    let heading = if app.running.is_queued() {
+        "Waiting for concurrency group..."
+    } else {
+        "Running script..."
+    };
+    let mut lines = vec![Line::from(heading), Line::from("")];
+    match &app.running.command {
+        Some(command) => lines.push(Line::from(format!("Command: {}", command))),
+        None => {
+            lines.push(Line::from(format!("Script: {}", script_name)));
+            lines.push(Line::from(format!("Args: {}", args)));
+        }
+    }
+    if let Some(progress) = &app.comparison.progress {
+        lines.push(Line::from(format!("Environment: {}", progress)));
+    }
+    if app.running.is_queued() {
+        lines.push(Line::from(format!(
+            "Queued in {} at position {}",
+            app.running.concurrency_group().unwrap_or("-"),
+            app.running.queue_position
+        )));
+        lines.push(Line::from(""));
+        lines.push(Line::from("Waiting for earlier runs in the group."));
+        let block = Paragraph::new(lines)
+            .block(theme.block().title("Queued"))
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+        frame.render_widget(block, area);
+        return;
+    }
+    let mut elapsed = format!(
+        "Elapsed: {}",
+        history::format_duration(app.running.elapsed_ms())
+    );
+    if let Some(expected) = app.running.expected_seconds() {
+        elapsed.push_str(&format!(
+            " (expected ~{})",
+            history::format_duration(expected.saturating_mul(1000))
+        ));
+    }
+    lines.push(Line::from(elapsed));
+    lines.push(Line::from(""));
+    if app.running.is_overdue() {
+        lines.push(Line::from(Span::styled(
+            "Over expected time. The run may be stuck.",
+            Style::default()
+                .fg(theme.semantic.warning.color())
+                .add_modifier(Modifier::BOLD),
+        )));
+    } else {
+        lines.push(Line::from("Please wait."));
+    }
     let block = Paragraph::new(lines)
-        .block(Block::default().borders(Borders::ALL).title("Executing"))
+        .block(theme.block().title("Executing"))
         .alignment(Alignment::Center)
         .wrap(Wrap { trim: true });
     frame.render_widget(block, area);
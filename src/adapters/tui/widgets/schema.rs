@@ -1,7 +1,7 @@
 use ratatui::layout::Rect;
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::widgets::{Paragraph, Wrap};
 use ratatui::Frame;
 
 use super::super::app::{QueuePreview, SchemaPreview};
@@ -17,7 +17,7 @@ pub(crate) fn render_schema_preview(
 ) {
     let lines = build_lines(preview, error, theme);
     let panel = Paragraph::new(lines)
-        .block(Block::default().borders(Borders::ALL).title(title))
+        .block(theme.block().title(title))
         .wrap(Wrap { trim: false });
     frame.render_widget(panel, area);
 }
@@ -59,6 +59,33 @@ fn build_lines(
     if !preview.tags.is_empty() {
         lines.push(Line::from(format!("Tags: {}", preview.tags.join(", "))));
     }
+    if let Some(owner) = preview.owner.as_deref() {
+        lines.push(Line::from(format!("Owner: {}", owner)));
+    }
+    if let Some(contact) = preview.contact.as_deref() {
+        lines.push(Line::from(format!("Contact: {}", contact)));
+    }
+    if preview.flaky {
+        lines.push(Line::from(Span::styled(
+            "Flaky: recent runs with the same args both passed and failed",
+            Style::default().fg(theme.semantic.warning.color()),
+        )));
+    }
+    if let Some(image) = preview.runtime.as_deref() {
+        lines.push(Line::from(format!("Runtime: {}", image)));
+    }
+    if !preview.requires.is_empty() {
+        lines.push(Line::from(format!(
+            "Requires: {}",
+            preview.requires.join(", ")
+        )));
+    }
+    for missing in &preview.missing_tools {
+        lines.push(Line::from(Span::styled(
+            format!("Missing: {}", missing),
+            Style::default().fg(theme.semantic.error.color()),
+        )));
+    }
     lines.push(Line::from(""));
     if preview.fields.is_empty() {
         lines.push(Line::from(Span::styled("(no fields)", theme.text_muted())));
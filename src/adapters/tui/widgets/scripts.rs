@@ -1,23 +1,72 @@
-use ratatui::layout::Rect;
-
-use ratatui::text::Line;
-use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{List, ListItem, ListState, Paragraph, Wrap};
 use ratatui::Frame;
-use std::path::Path;
 
+use super::super::state::NavigationState;
 use super::super::theme::Theme;
-use crate::ports::{WorkspaceEntry, WorkspaceEntryKind};
+use crate::ports::WorkspaceEntryKind;
 use crate::workspace::Workspace;
 
+/// Shown after scripts whose recent runs with the same args both passed and failed.
+const FLAKY_BADGE: &str = "[flaky]";
+
+/// Renders the favorites above the folder entries, or the workspace tree in tree
+/// mode; the list selection indexes both, favorites first.
 pub(crate) fn render_scripts(
     frame: &mut Frame,
     area: Rect,
     workspace: &Workspace,
-    current_dir: &Path,
-    entries: &[WorkspaceEntry],
-    list_state: &mut ListState,
+    navigation: &mut NavigationState,
     theme: &Theme,
 ) {
+    let current_dir = navigation.current_dir.as_path();
+    let favorites = navigation.favorites.as_slice();
+    let entries = navigation.entries.as_slice();
+    let title = if navigation.tree { "Tree" } else { "Entries" };
+    let list_state = &mut navigation.list_state;
+    let selected = list_state.selected();
+    let area = if favorites.is_empty() {
+        area
+    } else {
+        let height = (favorites.len() as u16 + 2).min(area.height / 2);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(height), Constraint::Min(3)])
+            .split(area);
+        let items: Vec<ListItem> = favorites
+            .iter()
+            .map(|entry| {
+                let relative = entry
+                    .path
+                    .strip_prefix(workspace.root())
+                    .unwrap_or(&entry.path)
+                    .to_string_lossy()
+                    .to_string();
+                let mut spans = vec![Span::raw(format!("★ {}", relative))];
+                if navigation.flaky.contains(&entry.path) {
+                    spans.push(Span::styled(
+                        format!(" {}", FLAKY_BADGE),
+                        Style::default().fg(theme.semantic.warning.color()),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(theme.block().title("Favorites"))
+            .highlight_style(theme.selection_style())
+            .highlight_symbol(super::super::theme::selection_symbol_str());
+        let mut favorites_state =
+            ListState::default().with_selected(selected.filter(|index| *index < favorites.len()));
+        frame.render_stateful_widget(list, chunks[0], &mut favorites_state);
+        chunks[1]
+    };
+    let mut entries_state = ListState::default()
+        .with_offset(list_state.offset())
+        .with_selected(selected.and_then(|index| index.checked_sub(favorites.len())));
+
     if entries.is_empty() {
         let relative = current_dir
             .strip_prefix(workspace.root())
@@ -30,13 +79,14 @@ pub(crate) fn render_scripts(
             Line::from("Add scripts or folders and press r to refresh."),
         ];
         let empty = Paragraph::new(empty_lines)
-            .block(Block::default().borders(Borders::ALL).title("Entries"))
+            .block(theme.block().title(title))
             .wrap(Wrap { trim: true });
         frame.render_widget(empty, area);
     } else {
         let items: Vec<ListItem> = entries
             .iter()
-            .map(|entry| {
+            .enumerate()
+            .map(|(index, entry)| {
                 let name = entry
                     .path
                     .file_name()
@@ -46,15 +96,38 @@ pub(crate) fn render_scripts(
                     WorkspaceEntryKind::Directory => format!("{}/", name),
                     WorkspaceEntryKind::Script => name.to_string(),
                 };
-                ListItem::new(label)
+                let badge = if navigation.flaky.contains(&entry.path) {
+                    Span::styled(
+                        format!(" {}", FLAKY_BADGE),
+                        Style::default().fg(theme.semantic.warning.color()),
+                    )
+                } else {
+                    Span::raw("")
+                };
+                if !navigation.tree {
+                    return ListItem::new(Line::from(vec![Span::raw(label), badge]));
+                }
+                let marker = match entry.kind {
+                    WorkspaceEntryKind::Directory if navigation.expanded.contains(&entry.path) => {
+                        "▾ "
+                    }
+                    WorkspaceEntryKind::Directory => "▸ ",
+                    WorkspaceEntryKind::Script => "  ",
+                };
+                let depth = navigation.depths.get(index).copied().unwrap_or(0);
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{}{}{}", "  ".repeat(depth), marker, label)),
+                    badge,
+                ]))
             })
             .collect();
 
         let list = List::new(items)
-            .block(Block::default().borders(Borders::ALL).title("Entries"))
+            .block(theme.block().title(title))
             .highlight_style(theme.selection_style())
             .highlight_symbol(super::super::theme::selection_symbol_str());
 
-        frame.render_stateful_widget(list, area, list_state);
+        frame.render_stateful_widget(list, area, &mut entries_state);
+        *list_state.offset_mut() = entries_state.offset();
     }
 }
@@ -1,6 +1,6 @@
 use ratatui::layout::Rect;
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Wrap};
+use ratatui::widgets::{List, ListItem, Paragraph, Wrap};
 use ratatui::Frame;
 
 use super::super::app::{App, SchemaFieldPreview, SchemaPreview};
@@ -8,9 +8,10 @@ use super::super::theme::{self, Theme};
 use super::common::{horizontal_split, standard_screen_layout};
 use super::schema;
 use crate::search_index::{SearchDetails, SearchResult, SearchStatus};
+use crate::status::WARN_SYMBOL;
 
 pub(crate) fn render_search(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
-    let outer = Block::default().borders(Borders::ALL).title("Search");
+    let outer = theme.block().title("Search");
     let inner = outer.inner(area);
     frame.render_widget(outer, area);
 
@@ -24,7 +25,7 @@ pub(crate) fn render_search(frame: &mut Frame, area: Rect, app: &mut App, theme:
 fn render_search_input(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     let title = match &app.search.status {
         SearchStatus::Indexing => "Search (indexing...)".to_string(),
-        SearchStatus::Ready { script_count } => format!("Search ({} scripts)", script_count),
+        SearchStatus::Ready { script_count, .. } => format!("Search ({} scripts)", script_count),
         SearchStatus::Error(_) => "Search (index error)".to_string(),
         SearchStatus::Idle => "Search".to_string(),
     };
@@ -34,7 +35,7 @@ fn render_search_input(frame: &mut Frame, area: Rect, app: &App, theme: &Theme)
         Line::from(app.search.query.clone())
     };
     let input = Paragraph::new(vec![query_line])
-        .block(Block::default().borders(Borders::ALL).title(title))
+        .block(theme.block().title(title))
         .wrap(Wrap { trim: true });
     frame.render_widget(input, area);
 }
@@ -49,7 +50,7 @@ fn render_search_body(frame: &mut Frame, area: Rect, app: &mut App, theme: &Them
             "No scripts found for this search.".to_string()
         };
         let empty = Paragraph::new(message)
-            .block(Block::default().borders(Borders::ALL).title("Results"))
+            .block(theme.block().title("Results"))
             .wrap(Wrap { trim: true });
         frame.render_widget(empty, area);
         return;
@@ -66,11 +67,20 @@ fn render_search_results(frame: &mut Frame, area: Rect, app: &mut App, theme: &T
         .search
         .results
         .iter()
-        .map(|result| ListItem::new(result_label(result)))
+        .map(|result| {
+            if result.schema_error.is_some() {
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{} ", WARN_SYMBOL), theme.status_error_style()),
+                    Span::raw(result_label(result)),
+                ]))
+            } else {
+                ListItem::new(result_label(result))
+            }
+        })
         .collect();
 
     let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("Results"))
+        .block(theme.block().title("Results"))
         .highlight_style(theme.selection_style())
         .highlight_symbol(theme::selection_symbol_str());
 
@@ -145,9 +155,15 @@ fn build_schema_preview_from_details(details: &SearchDetails) -> SchemaPreview {
         name: details.display_name.clone(),
         description: details.description.clone(),
         tags: details.tags.clone(),
+        owner: details.owner.clone(),
+        contact: details.contact.clone(),
         fields,
         outputs: Vec::new(),
         queue: None,
+        flaky: false,
+        requires: Vec::new(),
+        missing_tools: Vec::new(),
+        runtime: None,
     }
 }
 
@@ -156,8 +172,14 @@ fn build_schema_preview_from_result(result: &SearchResult) -> SchemaPreview {
         name: result.display_name.clone(),
         description: result.description.clone(),
         tags: result.tags.clone(),
+        owner: None,
+        contact: None,
         fields: Vec::new(),
         outputs: Vec::new(),
         queue: None,
+        flaky: false,
+        requires: Vec::new(),
+        missing_tools: Vec::new(),
+        runtime: None,
     }
 }
@@ -0,0 +1,77 @@
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+use std::path::{Path, PathBuf};
+
+use super::super::theme::Theme;
+use crate::runtime::script_kind;
+use crate::syntax::{highlight_line, TokenKind};
+
+/// Renders the script's source with line numbers, starting at line `scroll`.
+pub(crate) fn render_source(
+    frame: &mut Frame,
+    area: Rect,
+    source: Option<&(PathBuf, Result<String, String>)>,
+    scroll: u16,
+    theme: &Theme,
+) {
+    let lines = match source {
+        Some((path, Ok(text))) => source_lines(path, text, theme),
+        Some((_, Err(err))) => vec![
+            Line::from(Span::styled(
+                "Failed to read the script.",
+                Style::default()
+                    .fg(theme.semantic.error.color())
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(err.clone()),
+        ],
+        None => Vec::new(),
+    };
+    let title = match source {
+        Some((_, Ok(text))) => format!("Source ({} lines, PgUp/PgDn scroll)", text.lines().count()),
+        _ => "Source".to_string(),
+    };
+    let panel = Paragraph::new(lines)
+        .block(theme.block().title(title))
+        .scroll((scroll, 0));
+    frame.render_widget(panel, area);
+}
+
+fn source_lines(path: &Path, text: &str, theme: &Theme) -> Vec<Line<'static>> {
+    let kind = script_kind(path);
+    let width = text.lines().count().max(1).to_string().len();
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let line = line.replace('\t', "    ");
+            let mut spans = vec![Span::styled(
+                format!("{:>width$} ", index + 1),
+                theme.text_muted(),
+            )];
+            match kind {
+                Some(kind) => spans.extend(
+                    highlight_line(&line, kind)
+                        .into_iter()
+                        .map(|(token, text)| Span::styled(text.to_string(), style(token, theme))),
+                ),
+                None => spans.push(Span::raw(line.clone())),
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+fn style(token: TokenKind, theme: &Theme) -> Style {
+    match token {
+        TokenKind::Plain => Style::default(),
+        TokenKind::Keyword => Style::default()
+            .fg(theme.brand.accent.color())
+            .add_modifier(Modifier::BOLD),
+        TokenKind::String => Style::default().fg(theme.semantic.success.color()),
+        TokenKind::Variable => Style::default().fg(theme.semantic.info.color()),
+        TokenKind::Comment => theme.text_muted().add_modifier(Modifier::ITALIC),
+    }
+}
@@ -0,0 +1,81 @@
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Clear, Paragraph};
+use ratatui::Frame;
+
+use super::super::app::App;
+use super::super::theme::Theme;
+use crate::status::OK_SYMBOL;
+use crate::tutorial::STEPS;
+
+/// Footer rows the box stays above.
+const FOOTER_HEIGHT: u16 = 3;
+
+/// Draws the tutorial's steps and the current step's instructions in the
+/// bottom-right corner.
+pub(crate) fn render_tutorial(frame: &mut Frame, app: &App, theme: &Theme) {
+    let Some(current) = app.tutorial else {
+        return;
+    };
+    let accent = Style::default()
+        .fg(theme.brand.accent.color())
+        .add_modifier(Modifier::BOLD);
+    let mut lines: Vec<Line> = STEPS
+        .iter()
+        .enumerate()
+        .map(|(index, step)| {
+            let title = format!("{}. {}", index + 1, step.title());
+            if index < current {
+                Line::from(vec![
+                    Span::styled(format!("{} ", OK_SYMBOL), theme.status_ok_style()),
+                    Span::styled(title, theme.text_muted()),
+                ])
+            } else if index == current {
+                Line::from(Span::styled(format!("> {}", title), accent))
+            } else {
+                Line::from(format!("  {}", title))
+            }
+        })
+        .collect();
+    lines.push(Line::from(""));
+    match STEPS.get(current) {
+        Some(step) => lines.extend(
+            step.instructions()
+                .iter()
+                .map(|line| Line::from(line.to_string())),
+        ),
+        None => {
+            lines.push(Line::from(Span::styled(
+                "All done!",
+                theme.status_ok_style(),
+            )));
+            lines.push(Line::from("Press q to quit; the sandbox is deleted."));
+        }
+    }
+
+    let title = format!(
+        "Tutorial {}/{}",
+        (current + 1).min(STEPS.len()),
+        STEPS.len()
+    );
+    let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 4;
+    let height = lines.len() as u16 + 2;
+    let area = bottom_right(frame.size(), width, height);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(theme.block().title(title)),
+        area,
+    );
+}
+
+fn bottom_right(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height.saturating_sub(FOOTER_HEIGHT));
+    Rect {
+        x: area.x + area.width - width,
+        y: area.y + area.height.saturating_sub(FOOTER_HEIGHT + height),
+        width,
+        height,
+    }
+}
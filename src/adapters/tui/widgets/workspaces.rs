@@ -0,0 +1,53 @@
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::text::Span;
+use ratatui::widgets::{Cell, Paragraph, Row, Table, Wrap};
+use ratatui::Frame;
+
+use super::super::app::App;
+use super::super::theme::Theme;
+
+pub(crate) fn render_workspaces(frame: &mut Frame, area: Rect, app: &mut App, theme: &Theme) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(2)])
+        .split(area);
+
+    let block = theme.block().title("Workspaces");
+    if app.workspaces.entries.is_empty() {
+        let empty = Paragraph::new(
+            "No named workspaces. Add one with: omakure workspaces add <NAME> <PATH>",
+        )
+        .block(block)
+        .wrap(Wrap { trim: true });
+        frame.render_widget(empty, chunks[0]);
+    } else {
+        let root = app.workspace.root();
+        let rows = app.workspaces.entries.iter().map(|(name, dir)| {
+            let current = if dir == root { "current" } else { "" };
+            Row::new(vec![
+                Cell::from(name.clone()),
+                Cell::from(Span::styled(
+                    dir.display().to_string(),
+                    theme.text_secondary(),
+                )),
+                Cell::from(Span::styled(current, theme.text_secondary())),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(16),
+                Constraint::Min(20),
+                Constraint::Length(8),
+            ],
+        )
+        .block(block)
+        .highlight_style(theme.selection_style())
+        .highlight_symbol(super::super::theme::selection_symbol_str());
+        frame.render_stateful_widget(table, chunks[0], &mut app.workspaces.table_state);
+    }
+
+    let footer =
+        Paragraph::new("Up/Down move, Enter switch, Esc to return").style(theme.text_secondary());
+    frame.render_widget(footer, chunks[1]);
+}
@@ -1,7 +1,10 @@
+use crate::adapters::task_files::{target_paths, target_schema};
 use crate::domain::{extract_schema_block, parse_schema, Schema};
-use crate::error::{AppResult, ScriptError};
+use crate::error::{AppResult, SchemaError, ScriptError};
 use crate::ports::{ScriptRepository, WorkspaceEntry, WorkspaceEntryKind};
-use crate::runtime::{script_kind, ScriptKind};
+use crate::runtime::{
+    custom_extension, script_kind, sidecar_schema_paths, task_target, ScriptKind,
+};
 
 use std::fs;
 use std::io;
@@ -16,6 +19,28 @@ impl FsWorkspaceRepository {
     pub fn new<P: Into<PathBuf>>(root: P) -> Self {
         Self { root: root.into() }
     }
+
+    /// Text the schema of `script` is read from: its sidecar, the comment
+    /// block in the script, or a workflow file whole. `None` for schemas
+    /// derived from a Makefile target or justfile recipe, or when there is none.
+    pub fn schema_source(&self, script: &Path) -> Option<String> {
+        if let Some(sidecar) = sidecar_schema_paths(script)
+            .into_iter()
+            .find(|path| path.is_file())
+        {
+            return fs::read_to_string(sidecar).ok();
+        }
+        let kind = script_kind(script)?;
+        if kind == ScriptKind::Workflow {
+            return fs::read_to_string(script).ok();
+        }
+        let contents = fs::read_to_string(script).ok()?;
+        if kind == ScriptKind::Custom {
+            let custom = custom_extension(script)?;
+            return extract_schema_block(&contents, &[custom.comment.as_str()]).ok();
+        }
+        extract_schema_block(&contents, comment_prefixes(kind)?).ok()
+    }
 }
 
 impl ScriptRepository for FsWorkspaceRepository {
@@ -40,6 +65,11 @@ impl ScriptRepository for FsWorkspaceRepository {
                     path,
                     kind: WorkspaceEntryKind::Script,
                 });
+            } else if path.is_file() {
+                entries_out.extend(target_paths(&path)?.into_iter().map(|path| WorkspaceEntry {
+                    path,
+                    kind: WorkspaceEntryKind::Script,
+                }));
             }
         }
 
@@ -61,19 +91,56 @@ impl ScriptRepository for FsWorkspaceRepository {
     }
 
     fn read_schema(&self, script: &Path) -> AppResult<Schema> {
+        if let Some(sidecar) = sidecar_schema_paths(script)
+            .into_iter()
+            .find(|path| path.is_file())
+        {
+            return read_sidecar_schema(&sidecar);
+        }
         let prefixes = match script_kind(script) {
-            Some(ScriptKind::Bash) => vec!["#"],
-            Some(ScriptKind::PowerShell) => vec!["#", ";"],
-            Some(ScriptKind::Python) => vec!["#"],
+            // Workflow actions have no script body; the whole file is the schema.
+            Some(ScriptKind::Workflow) => return Ok(parse_schema(&fs::read_to_string(script)?)?),
+            // Only recognized with a sidecar, read above; reached if it was just removed.
+            Some(ScriptKind::Executable) => return Err(SchemaError::BlockNotFound.into()),
+            Some(ScriptKind::Make | ScriptKind::Just) => {
+                let (file, target) = task_target(script).ok_or(ScriptError::UnsupportedType)?;
+                return target_schema(&file, target);
+            }
+            Some(ScriptKind::Custom) => {
+                let custom = custom_extension(script).ok_or(ScriptError::UnsupportedType)?;
+                let contents = fs::read_to_string(script)?;
+                let block = extract_schema_block(&contents, &[custom.comment.as_str()])?;
+                return Ok(parse_schema(&block)?);
+            }
+            Some(kind) => comment_prefixes(kind).ok_or(ScriptError::UnsupportedType)?,
             None => return Err(ScriptError::UnsupportedType.into()),
         };
 
         let contents = fs::read_to_string(script)?;
-        let block = extract_schema_block(&contents, &prefixes)?;
+        let block = extract_schema_block(&contents, prefixes)?;
         Ok(parse_schema(&block)?)
     }
 }
 
+/// Comment prefixes of the schema block in scripts of `kind`.
+fn comment_prefixes(kind: ScriptKind) -> Option<&'static [&'static str]> {
+    match kind {
+        ScriptKind::Bash | ScriptKind::Python => Some(&["#"]),
+        ScriptKind::PowerShell => Some(&["#", ";"]),
+        _ => None,
+    }
+}
+
+fn read_sidecar_schema(path: &Path) -> AppResult<Schema> {
+    let contents = fs::read_to_string(path)?;
+    let schema = if path.extension().is_some_and(|ext| ext == "toml") {
+        toml::from_str(&contents).map_err(|err| SchemaError::InvalidToml(err.to_string()))?
+    } else {
+        serde_json::from_str(&contents).map_err(SchemaError::from)?
+    };
+    Ok(schema)
+}
+
 fn collect_scripts(dir: &Path, scripts: &mut Vec<PathBuf>) -> io::Result<()> {
     let entries = read_dir_or_empty(dir)?;
 
@@ -86,6 +153,8 @@ fn collect_scripts(dir: &Path, scripts: &mut Vec<PathBuf>) -> io::Result<()> {
             collect_scripts(&path, scripts)?;
         } else if path.is_file() && script_kind(&path).is_some() {
             scripts.push(path);
+        } else if path.is_file() {
+            scripts.extend(target_paths(&path)?);
         }
     }
 
@@ -113,3 +182,95 @@ fn entry_name(path: &Path) -> String {
         .unwrap_or("")
         .to_ascii_lowercase()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sidecar_schemas_win_over_embedded_block() {
+        let root = std::env::temp_dir().join(format!("omakure-sidecar-{}", std::process::id()));
+        let generated = root.join("generated");
+        fs::create_dir_all(&generated).unwrap();
+        let script = generated.join("deploy.bash");
+        fs::write(
+            &script,
+            "# OMAKURE_SCHEMA_START\n# {\"Name\": \"embedded\", \"Fields\": []}\n# OMAKURE_SCHEMA_END\n",
+        )
+        .unwrap();
+        let repo = FsWorkspaceRepository::new(&root);
+        assert_eq!(repo.read_schema(&script).unwrap().name, "embedded");
+
+        let toml = generated.join("deploy.omakure.toml");
+        fs::write(
+            &toml,
+            "Name = \"wrapped\"\n\n[[Fields]]\nName = \"target\"\nType = \"string\"\nOrder = 1\n",
+        )
+        .unwrap();
+        let schema = repo.read_schema(&script).unwrap();
+        assert_eq!(schema.name, "wrapped");
+        assert_eq!(schema.fields[0].name, "target");
+
+        let json = generated.join("deploy.omakure.json");
+        assert_eq!(sidecar_schema_paths(&script), vec![json.clone(), toml]);
+        fs::write(&json, r#"{"Name": "published", "Fields": []}"#).unwrap();
+        assert_eq!(repo.read_schema(&script).unwrap().name, "published");
+        fs::write(&json, "{").unwrap();
+        assert!(repo.read_schema(&script).is_err());
+        assert_eq!(repo.list_scripts_recursive().unwrap(), vec![script]);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_executables_need_a_sidecar() {
+        let root = std::env::temp_dir().join(format!("omakure-executable-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let tool = root.join("tf-plan");
+        fs::write(&tool, "#!/bin/sh\necho plan\n").unwrap();
+        crate::util::set_executable_permissions(&tool).unwrap();
+        fs::write(root.join("notes.txt"), "").unwrap();
+        let repo = FsWorkspaceRepository::new(&root);
+        assert_eq!(script_kind(&tool), None);
+        assert!(repo.list_scripts_recursive().unwrap().is_empty());
+
+        fs::write(
+            root.join("tf-plan.omakure.json"),
+            r#"{"Name": "plan", "Fields": []}"#,
+        )
+        .unwrap();
+        assert_eq!(script_kind(&tool), Some(ScriptKind::Executable));
+        assert_eq!(repo.list_scripts_recursive().unwrap(), vec![tool.clone()]);
+        assert_eq!(repo.read_schema(&tool).unwrap().name, "plan");
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_makefile_targets_are_scripts() {
+        let root = std::env::temp_dir().join(format!("omakure-make-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(
+            root.join("Makefile"),
+            "# Build it\nbuild:\n\tcc main.c\n\ntest: build\n\t./a.out\n",
+        )
+        .unwrap();
+        let repo = FsWorkspaceRepository::new(&root);
+        let build = root.join("Makefile#build");
+        assert_eq!(
+            repo.list_scripts_recursive().unwrap(),
+            vec![build.clone(), root.join("Makefile#test")]
+        );
+        let schema = repo.read_schema(&build).unwrap();
+        assert_eq!(schema.description.as_deref(), Some("Build it"));
+        assert!(schema.fields.is_empty());
+
+        fs::write(
+            root.join("Makefile.build.omakure.toml"),
+            "Name = \"build\"\n\n[[Fields]]\nName = \"CC\"\nType = \"string\"\nOrder = 1\n",
+        )
+        .unwrap();
+        assert_eq!(repo.read_schema(&build).unwrap().fields[0].name, "CC");
+        assert!(repo.read_schema(&root.join("Makefile#deploy")).is_err());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
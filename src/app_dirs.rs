@@ -0,0 +1,77 @@
+use std::path::{Path, PathBuf};
+
+const APP_NAME: &str = "omakure";
+/// Subfolder of the cache and state folders holding one folder per workspace.
+const WORKSPACES_DIR: &str = "workspaces";
+
+/// Per-user folders outside any workspace: XDG on Linux, Application Support
+/// and Caches on macOS, AppData on Windows.
+#[derive(Debug, Clone)]
+pub struct AppDirs {
+    /// Global config and themes.
+    pub config: PathBuf,
+    /// Data that can be rebuilt, such as search indexes.
+    pub cache: PathBuf,
+    /// Data worth keeping but not worth syncing, such as job queues.
+    pub state: PathBuf,
+}
+
+impl AppDirs {
+    /// The platform folders, or `None` when there is no home directory.
+    pub fn resolve() -> Option<Self> {
+        Some(Self {
+            config: dirs::config_dir()?.join(APP_NAME),
+            cache: dirs::cache_dir()?.join(APP_NAME),
+            // Only Linux has a state folder; local app data is the closest elsewhere.
+            state: dirs::state_dir()
+                .or_else(dirs::data_local_dir)?
+                .join(APP_NAME),
+        })
+    }
+
+    pub fn workspace_cache(&self, root: &Path) -> PathBuf {
+        self.cache.join(WORKSPACES_DIR).join(workspace_key(root))
+    }
+
+    pub fn workspace_state(&self, root: &Path) -> PathBuf {
+        self.state.join(WORKSPACES_DIR).join(workspace_key(root))
+    }
+}
+
+/// Folder name for a workspace: its folder name, for people browsing, and a
+/// hash of its full path, so workspaces with the same name stay apart.
+fn workspace_key(root: &Path) -> String {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let name: String = root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '-' })
+        .collect();
+    format!("{}-{:016x}", name, fnv1a(root.to_string_lossy().as_bytes()))
+}
+
+/// FNV-1a, which unlike `DefaultHasher` gives the same value in every build.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_key_is_stable_and_distinct() {
+        let key = workspace_key(Path::new("/nonexistent/team a/scripts"));
+        assert_eq!(key, workspace_key(Path::new("/nonexistent/team a/scripts")));
+        assert!(key.starts_with("scripts-"));
+        assert_eq!(key.len(), "scripts-".len() + 16);
+        assert_ne!(key, workspace_key(Path::new("/nonexistent/team b/scripts")));
+        assert!(workspace_key(Path::new("/nonexistent/my ws")).starts_with("my-ws-"));
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+    }
+}
@@ -0,0 +1,188 @@
+use flate2::read::GzDecoder;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Component, Path};
+
+/// Extracts a `.zip`, or a tarball (`.tar.gz`, `.tgz`, `.tar`), into `dest`.
+/// Entries that would land outside `dest` are refused.
+pub fn extract(archive: &Path, dest: &Path) -> Result<(), Box<dyn Error>> {
+    let name = archive
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let file = File::open(archive)
+        .map_err(|err| format!("Failed to open {}: {}", archive.display(), err))?;
+    fs::create_dir_all(dest)
+        .map_err(|err| format!("Failed to create {}: {}", dest.display(), err))?;
+    if name.ends_with(".zip") {
+        extract_zip(file, archive, dest)
+    } else if name.ends_with(".tar") {
+        extract_tar(file, archive, dest)
+    } else {
+        extract_tar(GzDecoder::new(file), archive, dest)
+    }
+}
+
+fn extract_tar(reader: impl Read, archive: &Path, dest: &Path) -> Result<(), Box<dyn Error>> {
+    let failed = |err: io::Error| format!("Failed to extract {}: {}", archive.display(), err);
+    let mut tar = tar::Archive::new(reader);
+    for entry in tar.entries().map_err(failed)? {
+        let mut entry = entry.map_err(failed)?;
+        let entry_path = entry.path().map_err(failed)?;
+        let path = entry_path.display().to_string();
+        // `unpack_in` would quietly extract an absolute path below `dest`.
+        let relative = entry_path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+        if !relative {
+            return Err(format!("{} has an unsafe path: {}", archive.display(), path).into());
+        }
+        // `unpack_in` also skips entries that would escape through a symlink.
+        let unpacked = entry.unpack_in(dest).map_err(|err| {
+            format!(
+                "Failed to extract {} from {}: {}",
+                path,
+                archive.display(),
+                err
+            )
+        })?;
+        if !unpacked {
+            return Err(format!("{} has an unsafe path: {}", archive.display(), path).into());
+        }
+    }
+    Ok(())
+}
+
+fn extract_zip(file: File, archive: &Path, dest: &Path) -> Result<(), Box<dyn Error>> {
+    let failed =
+        |err: zip::result::ZipError| format!("Failed to extract {}: {}", archive.display(), err);
+    let mut zip = zip::ZipArchive::new(file).map_err(failed)?;
+    for index in 0..zip.len() {
+        let mut entry = zip.by_index(index).map_err(failed)?;
+        let Some(relative) = entry.enclosed_name() else {
+            return Err(
+                format!("{} has an unsafe path: {}", archive.display(), entry.name()).into(),
+            );
+        };
+        let path = dest.join(&relative);
+        let entry_failed = |err: io::Error| {
+            format!(
+                "Failed to extract {} from {}: {}",
+                relative.display(),
+                archive.display(),
+                err
+            )
+        };
+        if entry.is_dir() {
+            fs::create_dir_all(&path).map_err(entry_failed)?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(entry_failed)?;
+        }
+        let mut out = File::create(&path).map_err(entry_failed)?;
+        io::copy(&mut entry, &mut out).map_err(entry_failed)?;
+        #[cfg(unix)]
+        if let Some(mode) = entry.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(mode)).map_err(entry_failed)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("omakure-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    /// Tarball with one file entry named `name`, written into the header as
+    /// is, since `tar::Builder` refuses unsafe paths.
+    fn write_tar(path: &Path, name: &str, data: &[u8]) {
+        let mut header = tar::Header::new_old();
+        header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_cksum();
+        let mut builder = tar::Builder::new(File::create(path).unwrap());
+        builder.append(&header, data).unwrap();
+        builder.finish().unwrap();
+    }
+
+    fn write_zip(path: &Path, files: &[(&str, &[u8])]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, data) in files {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_extract_refuses_unsafe_paths() {
+        let root = temp_root("archive-unsafe");
+        let dest = root.join("dest");
+        fs::create_dir_all(&dest).unwrap();
+
+        let archive = root.join("parent.tar");
+        write_tar(&archive, "../escaped", b"x");
+        assert!(extract(&archive, &dest).is_err());
+        assert!(!root.join("escaped").exists());
+
+        let absolute = root.join("absolute");
+        let archive = root.join("absolute.tar");
+        write_tar(&archive, &absolute.to_string_lossy(), b"x");
+        assert!(extract(&archive, &dest).is_err());
+        assert!(!absolute.exists());
+
+        let archive = root.join("parent.zip");
+        write_zip(&archive, &[("../escaped", b"x")]);
+        assert!(extract(&archive, &dest).is_err());
+        assert!(!root.join("escaped").exists());
+
+        assert_eq!(fs::read_dir(&dest).unwrap().count(), 0);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_extract_round_trip() {
+        let root = temp_root("archive-round-trip");
+
+        let archive = root.join("pack.zip");
+        write_zip(
+            &archive,
+            &[("index.lua", b"return {}"), ("lib/util.lua", b"-- util")],
+        );
+        let dest = root.join("zip");
+        extract(&archive, &dest).unwrap();
+        assert_eq!(
+            fs::read_to_string(dest.join("index.lua")).unwrap(),
+            "return {}"
+        );
+        assert_eq!(
+            fs::read_to_string(dest.join("lib/util.lua")).unwrap(),
+            "-- util"
+        );
+
+        let archive = root.join("pack.tar");
+        write_tar(&archive, "deploy.bash", b"echo ok");
+        let dest = root.join("tar");
+        extract(&archive, &dest).unwrap();
+        assert_eq!(
+            fs::read_to_string(dest.join("deploy.bash")).unwrap(),
+            "echo ok"
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
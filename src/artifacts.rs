@@ -0,0 +1,128 @@
+use crate::domain::OutputField;
+use crate::history::safe_slug;
+use crate::logging;
+use serde_json::json;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Copies the files matched by the `file` outputs into `dir`, one folder per
+/// output, and returns their paths relative to `dir`. Relative patterns are
+/// read from `base`, the script's folder. Inside its output's folder a file
+/// keeps its path below the pattern's first wildcard, so `logs/**/*.log`
+/// matches of the same name don't overwrite each other. A file that cannot
+/// be copied is logged and skipped.
+pub fn collect(outputs: &[OutputField], base: &Path, dir: &Path) -> Vec<PathBuf> {
+    let mut collected = Vec::new();
+    for output in outputs {
+        let Some(pattern) = output.file_pattern() else {
+            continue;
+        };
+        let (root, files) = matching_files(base, pattern);
+        for file in files {
+            let Ok(inner) = file.strip_prefix(&root) else {
+                continue;
+            };
+            let relative = PathBuf::from(safe_slug(&output.name)).join(inner);
+            match copy(&file, &dir.join(&relative)) {
+                Ok(()) => collected.push(relative),
+                Err(err) => logging::warn(
+                    "artifacts",
+                    "Output file not copied",
+                    json!({ "output": output.name, "file": file, "error": err.to_string() }),
+                ),
+            }
+        }
+    }
+    collected
+}
+
+/// Files matching `pattern`, in path order, with the folder they are all
+/// under: the pattern's leading components without wildcards. Folders are
+/// left out.
+fn matching_files(base: &Path, pattern: &str) -> (PathBuf, Vec<PathBuf>) {
+    let pattern = if Path::new(pattern).is_absolute() {
+        PathBuf::from(pattern)
+    } else {
+        base.join(pattern)
+    };
+    let mut components: Vec<_> = pattern.components().collect();
+    components.pop();
+    let root: PathBuf = components
+        .into_iter()
+        .take_while(|component| {
+            !component
+                .as_os_str()
+                .to_string_lossy()
+                .contains(['*', '?', '['])
+        })
+        .collect();
+    let Ok(paths) = glob::glob(&pattern.to_string_lossy()) else {
+        return (root, Vec::new());
+    };
+    let mut files: Vec<PathBuf> = paths.flatten().filter(|path| path.is_file()).collect();
+    files.sort();
+    (root, files)
+}
+
+fn copy(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(from, to).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect() {
+        let root = std::env::temp_dir().join(format!("omakure-artifacts-{}", std::process::id()));
+        let base = root.join("scripts");
+        fs::create_dir_all(base.join("out/nested")).unwrap();
+        fs::write(base.join("out/report.html"), "<p>ok</p>").unwrap();
+        fs::write(base.join("out/plan.txt"), "plan").unwrap();
+        fs::create_dir_all(base.join("logs/api")).unwrap();
+        fs::create_dir_all(base.join("logs/web")).unwrap();
+        fs::write(base.join("logs/api/run.log"), "api").unwrap();
+        fs::write(base.join("logs/web/run.log"), "web").unwrap();
+        let output = |name: &str, kind: &str, path: &str| OutputField {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            path: Some(path.to_string()),
+        };
+        let outputs = vec![
+            output("Report", "file", "out/*.html"),
+            output("plan", "string", "out/plan.txt"),
+            output("all", "file", "out/*"),
+            output("logs", "file", "logs/**/*.log"),
+        ];
+
+        let dir = root.join("artifacts");
+        let collected = collect(&outputs, &base, &dir);
+        assert_eq!(
+            collected,
+            vec![
+                PathBuf::from("report/report.html"),
+                PathBuf::from("all/plan.txt"),
+                PathBuf::from("all/report.html"),
+                PathBuf::from("logs/api/run.log"),
+                PathBuf::from("logs/web/run.log"),
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("report/report.html")).unwrap(),
+            "<p>ok</p>"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("logs/api/run.log")).unwrap(),
+            "api"
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("logs/web/run.log")).unwrap(),
+            "web"
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -11,6 +11,15 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub scripts_dir: Option<PathBuf>,
 
+    /// Use a named workspace from the global config
+    #[arg(
+        long,
+        global = true,
+        value_name = "NAME",
+        conflicts_with = "scripts_dir"
+    )]
+    pub workspace: Option<String>,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -20,9 +29,15 @@ pub enum Commands {
     /// Run a script without the TUI
     Run(RunArgs),
 
+    /// Run every script with a tag, or the scripts listed in a file
+    RunAll(RunAllArgs),
+
+    /// List or run pipelines: scripts run in order, passing outputs along
+    Pipeline(PipelineArgs),
+
     /// Check runtime dependencies and workspace
     #[command(visible_alias = "check")]
-    Doctor,
+    Doctor(DoctorArgs),
 
     /// List Omaken flavors
     List,
@@ -30,12 +45,24 @@ pub enum Commands {
     /// Install an Omaken flavor
     Install(OmakenInstallArgs),
 
+    /// Browse the Omaken catalog and install a community flavor
+    Browse(BrowseArgs),
+
     /// List available scripts
-    Scripts,
+    Scripts(ScriptsArgs),
 
     /// Create a new script template
     Init(InitArgs),
 
+    /// Create a new workspace from a template repository
+    InitWorkspace(InitWorkspaceArgs),
+
+    /// List and manage named workspaces
+    Workspaces(WorkspacesArgs),
+
+    /// Learn the TUI with guided tasks in a throwaway workspace
+    Tutorial,
+
     /// Show resolved paths and env
     #[command(visible_alias = "env")]
     Config,
@@ -43,6 +70,9 @@ pub enum Commands {
     /// Update omakure from GitHub releases
     Update(UpdateArgs),
 
+    /// Sync the repo's bundled scripts into the workspace
+    SyncScripts(SyncScriptsArgs),
+
     /// Remove the omakure binary
     Uninstall(UninstallArgs),
 
@@ -51,6 +81,69 @@ pub enum Commands {
 
     /// Manage themes
     Theme(ThemeArgs),
+
+    /// Work with run history
+    History(HistoryArgs),
+
+    /// List, activate, and show env files
+    Envs(EnvsArgs),
+
+    /// Write a static HTML report of scripts and recent runs
+    Report(ReportArgs),
+
+    /// Show run counts, failures, and flaky scripts from history
+    Stats(StatsArgs),
+
+    /// Send a summary of the runs since the last digest to the notification channels
+    Digest(DigestArgs),
+
+    /// Generate markdown docs from script schemas
+    Docs(DocsArgs),
+
+    /// Print a script's parsed schema
+    Schema(SchemaArgs),
+
+    /// Generate script code from schemas
+    Codegen(CodegenArgs),
+
+    /// Check a run receipt's signature and compare it with history
+    VerifyReceipt(VerifyReceiptArgs),
+
+    /// Record script digests, or report scripts changed since the last snapshot
+    Snapshot(SnapshotArgs),
+
+    /// Remove orphaned artifacts, stale queue tickets, and other leftovers
+    Gc(GcArgs),
+
+    /// Check every script's schema and report problems
+    Validate(ValidateArgs),
+
+    /// Trigger a workflow_dispatch action and wait for its run (used by the runner)
+    #[command(hide = true)]
+    Dispatch(DispatchArgs),
+
+    /// Run a script as a Kubernetes Job and stream its logs (used by the runner)
+    #[command(name = "kube-run", hide = true)]
+    KubeRun(KubeRunArgs),
+
+    /// Run a script on a remote host over SSH (used by the runner)
+    #[command(name = "ssh-run", hide = true)]
+    SshRun(SshRunArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ScriptsArgs {
+    /// One path per line relative to the workspace, for shell completion
+    #[arg(long)]
+    pub plain: bool,
+
+    /// Print an index.json of the scripts and their schemas, to publish for --remote
+    #[arg(long, conflicts_with_all = ["plain", "remote"])]
+    pub index: bool,
+
+    /// List the scripts of a published index instead (URL of index.json or its folder)
+    #[arg(long, value_name = "URL")]
+    pub remote: Option<String>,
 }
 
 #[derive(Args, Debug)]
@@ -59,11 +152,72 @@ pub struct RunArgs {
     #[arg(value_name = "SCRIPT")]
     pub script: String,
 
+    /// Print a JSON document with the run result instead of raw output
+    #[arg(long)]
+    pub json: bool,
+
+    /// Print the resolved command without running it
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Fill a schema field (repeatable)
+    #[arg(long = "field", value_name = "NAME=VALUE")]
+    pub fields: Vec<String>,
+
+    /// Set an environment variable for this run only (repeatable)
+    #[arg(short = 'e', long = "env", value_name = "KEY=VALUE")]
+    pub env: Vec<String>,
+
     /// Arguments forwarded to the script
     #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
     pub args: Vec<String>,
 }
 
+#[derive(Args, Debug)]
+pub struct RunAllArgs {
+    /// Run the scripts whose schema has this tag
+    #[arg(long, value_name = "TAG", required_unless_present = "list")]
+    pub tag: Option<String>,
+
+    /// Run the scripts named in this file, one per line (`#` starts a comment)
+    #[arg(long, value_name = "FILE", conflicts_with = "tag")]
+    pub list: Option<PathBuf>,
+
+    /// Run up to N scripts at a time
+    #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+    pub parallel: u16,
+
+    /// Print a JSON document with each script's result instead of the table
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct PipelineArgs {
+    #[command(subcommand)]
+    pub command: Option<PipelineCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PipelineCommand {
+    /// List the workspace's pipeline files and scripts with DependsOn (the default)
+    List,
+
+    /// Run a pipeline file, or a script after the scripts it DependsOn
+    Run(PipelineRunArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct PipelineRunArgs {
+    /// Pipeline name, or a script name or path
+    #[arg(value_name = "NAME")]
+    pub name: String,
+
+    /// Print a JSON document with each stage's result instead of the summary
+    #[arg(long)]
+    pub json: bool,
+}
+
 #[derive(Args, Debug)]
 pub struct InitArgs {
     /// Script path
@@ -73,6 +227,105 @@ pub struct InitArgs {
     /// Script path (legacy)
     #[arg(long, value_name = "SCRIPT")]
     pub name: Option<String>,
+
+    /// Generate a curl script from an OpenAPI spec (YAML or JSON)
+    #[arg(long, value_name = "SPEC", requires = "operation")]
+    pub from_openapi: Option<PathBuf>,
+
+    /// operationId to generate the script for
+    #[arg(long, value_name = "ID", requires = "from_openapi")]
+    pub operation: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct InitWorkspaceArgs {
+    /// Git URL, local folder, or name of a folder in the global templates dir
+    #[arg(value_name = "TEMPLATE")]
+    pub template: String,
+
+    /// Folder to create; defaults to the scripts directory
+    #[arg(long, value_name = "DIR")]
+    pub dir: Option<PathBuf>,
+
+    /// Set a template variable instead of being asked (repeatable)
+    #[arg(long = "var", value_name = "NAME=VALUE")]
+    pub vars: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct DispatchArgs {
+    /// `.workflow.json` action file
+    #[arg(value_name = "ACTION")]
+    pub action: PathBuf,
+
+    /// Field arguments, as built for scripts
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    pub args: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct KubeRunArgs {
+    /// kubectl context
+    #[arg(long)]
+    pub context: Option<String>,
+
+    /// Namespace for the Job
+    #[arg(long)]
+    pub namespace: Option<String>,
+
+    /// Container image; defaults to one matching the script type
+    #[arg(long)]
+    pub image: Option<String>,
+
+    /// Environment variable forwarded to the pod (repeatable)
+    #[arg(long = "env", value_name = "NAME")]
+    pub env: Vec<String>,
+
+    /// Script to run
+    #[arg(value_name = "SCRIPT")]
+    pub script: PathBuf,
+
+    /// Script arguments
+    #[arg(last = true)]
+    pub args: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct SshRunArgs {
+    /// `user@address` of the host
+    #[arg(long)]
+    pub destination: String,
+
+    /// SSH port
+    #[arg(long)]
+    pub port: Option<u16>,
+
+    /// Environment variable forwarded to the remote script (repeatable)
+    #[arg(long = "env", value_name = "NAME")]
+    pub env: Vec<String>,
+
+    /// Local folder receiving the collected files
+    #[arg(long)]
+    pub artifacts_dir: Option<PathBuf>,
+
+    /// Remote file or glob to fetch after the run (repeatable)
+    #[arg(long = "collect", value_name = "PATH", requires = "artifacts_dir")]
+    pub collect: Vec<String>,
+
+    /// Script to run
+    #[arg(value_name = "SCRIPT")]
+    pub script: PathBuf,
+
+    /// Script arguments
+    #[arg(last = true)]
+    pub args: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct DoctorArgs {
+    /// Print a JSON report of every check instead of the list
+    #[arg(long)]
+    pub json: bool,
 }
 
 #[derive(Args, Debug)]
@@ -84,6 +337,33 @@ pub struct UpdateArgs {
     /// Release tag (vX.Y.Z)
     #[arg(long)]
     pub version: Option<String>,
+
+    /// Install without checking the archive against the release's SHA256SUMS
+    #[arg(long)]
+    pub no_verify: bool,
+
+    /// Restore the binary the last update replaced (omakure.bak)
+    #[arg(long, conflicts_with_all = ["repo", "version", "no_verify"])]
+    pub rollback: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct SyncScriptsArgs {
+    /// Overwrite every script that differs from upstream, local edits included
+    #[arg(long, conflicts_with = "diff")]
+    pub force: bool,
+
+    /// Show how each script differs from upstream without changing anything
+    #[arg(long)]
+    pub diff: bool,
+
+    /// GitHub repository (owner/name)
+    #[arg(long)]
+    pub repo: Option<String>,
+
+    /// Release tag to sync from (default: the installed version)
+    #[arg(long)]
+    pub tag: Option<String>,
 }
 
 #[derive(Args, Debug)]
@@ -100,6 +380,36 @@ pub struct CompletionArgs {
     pub shell: Shell,
 }
 
+/// `omakure __complete`, which prints candidates for the completion scripts.
+/// It is parsed apart from `Cli` so the generated scripts do not offer it.
+#[derive(Parser, Debug)]
+#[command(name = "omakure __complete")]
+pub struct CompleteCli {
+    /// Scripts directory override
+    #[arg(long, global = true)]
+    pub scripts_dir: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: CompleteCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CompleteCommand {
+    /// `run --field` values for a script: field names, then their choices
+    Field(CompleteFieldArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct CompleteFieldArgs {
+    /// Script name or path
+    #[arg(value_name = "SCRIPT")]
+    pub script: String,
+
+    /// The word being completed
+    #[arg(default_value = "", allow_hyphen_values = true)]
+    pub word: String,
+}
+
 #[derive(Args, Debug)]
 pub struct ThemeArgs {
     #[command(subcommand)]
@@ -128,6 +438,261 @@ pub struct ThemeSetArgs {
     pub name: String,
 }
 
+#[derive(Args, Debug)]
+pub struct WorkspacesArgs {
+    #[command(subcommand)]
+    pub command: Option<WorkspacesCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum WorkspacesCommand {
+    /// List named workspaces (the default)
+    List,
+
+    /// Name a workspace folder, or point an existing name elsewhere
+    Add(WorkspacesAddArgs),
+
+    /// Forget a named workspace; its folder is left alone
+    Remove(WorkspacesNameArgs),
+
+    /// Use a named workspace when no other one is picked
+    Default(WorkspacesNameArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct WorkspacesAddArgs {
+    /// Workspace name
+    #[arg(value_name = "NAME")]
+    pub name: String,
+
+    /// Workspace folder
+    #[arg(value_name = "PATH")]
+    pub path: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct WorkspacesNameArgs {
+    /// Workspace name
+    #[arg(value_name = "NAME")]
+    pub name: String,
+}
+
+#[derive(Args, Debug)]
+pub struct VerifyReceiptArgs {
+    /// Receipt file, or the run's artifacts folder holding it
+    #[arg(value_name = "RECEIPT")]
+    pub receipt: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct SnapshotArgs {
+    /// Report added, removed, and modified scripts instead of writing a snapshot
+    #[arg(long)]
+    pub verify: bool,
+
+    /// Snapshot file to write or verify against (default: omakure.snapshot.json)
+    #[arg(long, value_name = "PATH")]
+    pub file: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct GcArgs {
+    /// List what would be removed without removing it
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct BrowseArgs {
+    /// Install the catalog entry with this number or name
+    #[arg(long, value_name = "INDEX|NAME")]
+    pub install: Option<String>,
+
+    /// Only show flavors with this tag
+    #[arg(long)]
+    pub tag: Option<String>,
+
+    /// Print the catalog instead of opening the picker
+    #[arg(long, conflicts_with = "install")]
+    pub list: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ValidateArgs {
+    /// Print a JSON document with the problems found
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct CodegenArgs {
+    #[command(subcommand)]
+    pub command: CodegenCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CodegenCommand {
+    /// Rewrite the argument parser between the OMAKURE_ARGS markers from the schema
+    Args(CodegenArgsArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct CodegenArgsArgs {
+    /// Script name or path
+    #[arg(value_name = "SCRIPT")]
+    pub script: String,
+
+    /// Fail if the parser is out of date instead of rewriting it
+    #[arg(long)]
+    pub check: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct HistoryArgs {
+    #[command(subcommand)]
+    pub command: HistoryCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HistoryCommand {
+    /// Export history entries
+    Export(HistoryExportArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct HistoryExportArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value = "json")]
+    pub format: ExportFormat,
+
+    /// Write to a file instead of stdout
+    #[arg(long, value_name = "FILE")]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct EnvsArgs {
+    #[command(subcommand)]
+    pub command: Option<EnvsCommand>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum EnvsCommand {
+    /// List env files, marking the active one (default)
+    List,
+
+    /// Print the active env file's name
+    Active,
+
+    /// Activate an env file
+    Use(EnvsNameArgs),
+
+    /// Deactivate the active env file
+    #[command(visible_alias = "deactivate")]
+    Off,
+
+    /// Print an env file's variables, sensitive values masked
+    Show(EnvsShowArgs),
+
+    /// Encrypt an env file into `<name>.age` and remove the plain file
+    Encrypt(EnvsNameArgs),
+
+    /// Turn an encrypted env file back into a plain one
+    Decrypt(EnvsNameArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct EnvsNameArgs {
+    /// Env file name; `.conf` may be left out
+    #[arg(value_name = "NAME")]
+    pub name: String,
+}
+
+#[derive(Args, Debug)]
+pub struct EnvsShowArgs {
+    /// Env file name; defaults to the active one
+    #[arg(value_name = "NAME")]
+    pub name: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct DocsArgs {
+    /// Script name or path
+    #[arg(
+        value_name = "SCRIPT",
+        required_unless_present = "all",
+        conflicts_with = "all"
+    )]
+    pub script: Option<String>,
+
+    /// Document every script in the workspace
+    #[arg(long)]
+    pub all: bool,
+
+    /// Write one markdown file per script into this folder instead of stdout
+    #[arg(long, value_name = "DIR")]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct SchemaArgs {
+    /// Script name or path
+    #[arg(value_name = "SCRIPT")]
+    pub script: String,
+
+    /// Indent the JSON
+    #[arg(long, conflicts_with = "table")]
+    pub pretty: bool,
+
+    /// Print a readable summary and field table instead of JSON
+    #[arg(long)]
+    pub table: bool,
+
+    /// Read the schema from a published index instead (URL of index.json or its folder)
+    #[arg(long, value_name = "URL")]
+    pub remote: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ReportArgs {
+    /// Folder receiving index.html
+    #[arg(long, value_name = "DIR")]
+    pub html: PathBuf,
+
+    /// Number of recent runs to include
+    #[arg(long, default_value_t = 50)]
+    pub runs: usize,
+}
+
+#[derive(Args, Debug)]
+pub struct StatsArgs {
+    /// Print a JSON array instead of a table
+    #[arg(long)]
+    pub json: bool,
+
+    /// Only list flaky scripts
+    #[arg(long)]
+    pub flaky: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct DigestArgs {
+    /// Print the digest without sending it or marking it sent
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Keep running and send a digest on the [digest] schedule
+    #[arg(long, conflicts_with = "dry_run")]
+    pub watch: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Ndjson,
+}
+
 #[derive(ValueEnum, Clone, Debug)]
 pub enum Shell {
     Bash,
@@ -139,10 +704,22 @@ pub enum Shell {
 #[derive(Args, Debug)]
 pub struct OmakenInstallArgs {
     /// Git URL of the flavor repository
-    #[arg(value_name = "GIT_URL")]
-    pub url: String,
+    #[arg(
+        value_name = "GIT_URL",
+        required_unless_present = "locked",
+        conflicts_with = "locked"
+    )]
+    pub url: Option<String>,
 
     /// Override the install folder name
-    #[arg(long)]
+    #[arg(long, conflicts_with = "locked")]
     pub name: Option<String>,
+
+    /// Tag or branch to install instead of the default branch
+    #[arg(long = "ref", value_name = "REF", conflicts_with = "locked")]
+    pub reference: Option<String>,
+
+    /// Install every flavor in omaken.lock at its pinned commit
+    #[arg(long)]
+    pub locked: bool,
 }
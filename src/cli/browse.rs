@@ -0,0 +1,83 @@
+use crate::adapters::tui;
+use crate::cli::args::BrowseArgs;
+use crate::cli::omaken::{flavor_names, install_omaken};
+use crate::config;
+use crate::http;
+use crate::omaken_catalog::{self, Catalog, CatalogEntry, DEFAULT_CATALOG_URL};
+use crate::workspace::Workspace;
+use std::error::Error;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+
+pub fn run(scripts_dir: PathBuf, args: BrowseArgs) -> Result<(), Box<dyn Error>> {
+    let workspace = Workspace::new(scripts_dir);
+    workspace.ensure_layout()?;
+    let catalog = load_catalog(&workspace)?;
+
+    if let Some(selector) = &args.install {
+        let entry = catalog
+            .find(selector)
+            .ok_or_else(|| format!("No catalog entry {}", selector))?;
+        return install_entry(&workspace, entry);
+    }
+
+    let choices = catalog.matching(args.tag.as_deref());
+    if choices.is_empty() {
+        match &args.tag {
+            Some(tag) => println!("No catalog flavors tagged {}.", tag),
+            None => println!("The catalog lists no flavors."),
+        }
+        return Ok(());
+    }
+    let installed = flavor_names(&workspace)?;
+    if !args.list && io::stdin().is_terminal() && io::stdout().is_terminal() {
+        if let Some(index) = tui::pick_flavor(&workspace, &catalog, &choices, &installed)? {
+            install_entry(&workspace, &catalog.flavors[index])?;
+        }
+        return Ok(());
+    }
+
+    println!("Omaken catalog:");
+    for index in choices {
+        let entry = &catalog.flavors[index];
+        let mut line = format!("{:>3}. {}", index + 1, entry.name);
+        let summary = entry.summary();
+        if !summary.is_empty() {
+            line.push_str(&format!(" - {}", summary));
+        }
+        if installed.contains(&entry.name) {
+            line.push_str(" (installed)");
+        }
+        println!("{}", line);
+    }
+    println!("Install one with: omakure browse --install <number|name>");
+    Ok(())
+}
+
+/// Reads the catalog from `[omaken] catalog`, a URL or a path relative to
+/// the workspace, or from the default URL.
+fn load_catalog(workspace: &Workspace) -> Result<Catalog, Box<dyn Error>> {
+    let config = config::read_workspace_config(workspace.config_path())?;
+    let source = config
+        .omaken
+        .catalog
+        .unwrap_or_else(|| DEFAULT_CATALOG_URL.to_string());
+    let contents = if source.contains("://") {
+        http::get_string(&source)?
+    } else {
+        let path = workspace.root().join(&source);
+        fs::read_to_string(&path)
+            .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?
+    };
+    Ok(omaken_catalog::parse(&contents)?)
+}
+
+fn install_entry(workspace: &Workspace, entry: &CatalogEntry) -> Result<(), Box<dyn Error>> {
+    install_omaken(
+        workspace,
+        &entry.url,
+        Some(&entry.name),
+        entry.reference.as_deref(),
+    )
+}
@@ -0,0 +1,42 @@
+use crate::adapters::workspace_repository::FsWorkspaceRepository;
+use crate::cli::args::{CodegenArgs, CodegenArgsArgs, CodegenCommand};
+use crate::cli::run::resolve_script_path;
+use crate::codegen;
+use crate::ports::ScriptRepository;
+use crate::runtime::script_kind;
+use crate::workspace::Workspace;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+pub fn run(scripts_dir: PathBuf, args: CodegenArgs) -> Result<(), Box<dyn Error>> {
+    match args.command {
+        CodegenCommand::Args(args) => run_args(scripts_dir, args),
+    }
+}
+
+fn run_args(scripts_dir: PathBuf, args: CodegenArgsArgs) -> Result<(), Box<dyn Error>> {
+    let workspace = Workspace::new(scripts_dir);
+    let script = resolve_script_path(&args.script, workspace.root())?;
+    let kind = script_kind(&script).ok_or("Unsupported script extension")?;
+    let repo = FsWorkspaceRepository::new(workspace.root().to_path_buf());
+    let schema = repo.read_schema(&script)?;
+
+    let parser = codegen::args_parser(&schema.fields, kind)?;
+    let contents = fs::read_to_string(&script)?;
+    let updated = codegen::replace_args_section(&contents, &parser)?;
+    if updated == contents {
+        println!("{} is up to date", script.display());
+    } else if args.check {
+        return Err(format!(
+            "The argument parser in {} does not match its schema; run `omakure codegen args {}`",
+            script.display(),
+            args.script
+        )
+        .into());
+    } else {
+        fs::write(&script, updated)?;
+        println!("Updated the argument parser in {}", script.display());
+    }
+    Ok(())
+}
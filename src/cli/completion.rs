@@ -0,0 +1,312 @@
+use crate::adapters::workspace_repository::FsWorkspaceRepository;
+use crate::cli::args::{Cli, CompleteCommand, CompletionArgs, Shell};
+use crate::cli::run::resolve_script_path;
+use crate::domain::Schema;
+use crate::ports::ScriptRepository;
+use clap::CommandFactory;
+use clap_complete::{generate, Shell as ClapShell};
+use std::error::Error;
+use std::path::PathBuf;
+
+/// Subcommands whose first positional argument is a script, as the words
+/// typed after `omakure`.
+const SCRIPT_COMMANDS: [&[&str]; 4] = [&["run"], &["schema"], &["docs"], &["codegen", "args"]];
+
+/// Completes `--field` for `run`: the script is the first word after `run`
+/// that is neither an option nor an option's value.
+const BASH_FIELDS: &str = r#"
+_omakure_fields() {
+    local line="${COMP_LINE:0:COMP_POINT}"
+    local -a words
+    read -ra words <<< "${line}"
+    [[ ${line} == *" " ]] && words+=("")
+    local count=${#words[@]}
+    [[ ${count} -ge 4 && ${words[1]} == run && ${words[count-2]} == --field ]] || return 1
+    local script="" i
+    for (( i = 2; i < count - 1; i++ )); do
+        case "${words[i]}" in
+            --field|--env|-e|--scripts-dir) (( i++ )) ;;
+            -*) ;;
+            *) script="${words[i]}"; break ;;
+        esac
+    done
+    [[ -n ${script} ]] || return 1
+    local word="${words[count-1]}"
+    local candidates
+    candidates="$(omakure __complete field "${script}" "${word}" 2>/dev/null)"
+    if [[ ${word} == *=* ]]; then
+        # `=` splits words in bash, so only the value is being completed.
+        COMPREPLY=( $(compgen -W "${candidates//${word%%=*}=/}" -- "${word#*=}") )
+    else
+        compopt -o nospace
+        COMPREPLY=( $(compgen -W "${candidates}" -- "${word}") )
+    fi
+}
+"#;
+
+const ZSH_HELPERS: &str = r#"
+_omakure_scripts() {
+    local -a scripts
+    scripts=(${(f)"$(omakure scripts --plain 2>/dev/null)"})
+    compadd -a scripts
+}
+
+_omakure_fields() {
+    [[ -n ${line[1]} ]] || return 1
+    local -a candidates
+    candidates=(${(f)"$(omakure __complete field "${line[1]}" "${PREFIX}" 2>/dev/null)"})
+    if [[ ${PREFIX} == *=* ]]; then
+        compset -P '*='
+        candidates=(${candidates#*=})
+        compadd -a candidates
+    else
+        compadd -S '' -a candidates
+    fi
+}
+"#;
+
+const FISH_FIELDS: &str = r#"
+function __omakure_fields
+    set -l script
+    set -l skip 0
+    for token in (commandline -opc)[3..-1]
+        if test $skip = 1
+            set skip 0
+            continue
+        end
+        switch $token
+            case --field --env -e --scripts-dir
+                set skip 1
+            case '-*'
+            case '*'
+                set script $token
+                break
+        end
+    end
+    test -n "$script"; or return
+    omakure __complete field $script (commandline -ct) 2>/dev/null
+end
+
+complete -c omakure -n "__fish_omakure_using_subcommand run" -l field -r -f -a "(__omakure_fields)"
+"#;
+
+const PWSH_FIELDS: &str = r#"
+function __OmakureFields($elements, $wordToComplete) {
+    $words = @($elements | Select-Object -Skip 2 | ForEach-Object { "$_" })
+    if ($wordToComplete) { $words = @($words | Select-Object -SkipLast 1) }
+    if ($words.Count -eq 0 -or $words[-1] -ne '--field') { return }
+    $script = $null
+    for ($i = 0; $i -lt $words.Count - 1; $i++) {
+        if ($words[$i] -in '--field', '--env', '-e', '--scripts-dir') { $i++; continue }
+        if (-not $words[$i].StartsWith('-')) { $script = $words[$i]; break }
+    }
+    if (-not $script) { return }
+    & omakure __complete field $script $wordToComplete 2>$null | ForEach-Object {
+        [CompletionResult]::new($_, $_, [CompletionResultType]::ParameterValue, $_)
+    }
+}
+"#;
+
+/// Prints clap's completion script for `shell`, extended so that script
+/// arguments complete the paths `omakure scripts --plain` lists, and
+/// `run --field` completes the script's field names and choices.
+pub fn run(args: CompletionArgs) -> Result<(), Box<dyn Error>> {
+    let mut cmd = Cli::command();
+    let clap_shell = match args.shell {
+        Shell::Bash => ClapShell::Bash,
+        Shell::Zsh => ClapShell::Zsh,
+        Shell::Fish => ClapShell::Fish,
+        Shell::Pwsh => ClapShell::PowerShell,
+    };
+    let mut generated = Vec::new();
+    generate(clap_shell, &mut cmd, "omakure", &mut generated);
+    let generated = String::from_utf8(generated)?;
+
+    let script = match args.shell {
+        Shell::Bash => format!("{}{}{}", generated, BASH_FIELDS, bash_scripts()),
+        Shell::Zsh => zsh_completion(&generated),
+        Shell::Fish => format!("{}{}{}", generated, fish_scripts(), FISH_FIELDS),
+        Shell::Pwsh => pwsh_completion(&generated),
+    };
+    print!("{}", script);
+    Ok(())
+}
+
+/// `omakure __complete`: candidates for the completion scripts, one per line.
+pub fn complete(scripts_dir: PathBuf, command: CompleteCommand) -> Result<(), Box<dyn Error>> {
+    match command {
+        CompleteCommand::Field(args) => {
+            let script = resolve_script_path(&args.script, &scripts_dir)?;
+            let schema = FsWorkspaceRepository::new(scripts_dir).read_schema(&script)?;
+            for candidate in field_candidates(&schema, &args.word) {
+                println!("{}", candidate);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `NAME=` for each field while `word` has no `=`, then `NAME=VALUE` for
+/// the field's choices (or `true` and `false` for a bool).
+fn field_candidates(schema: &Schema, word: &str) -> Vec<String> {
+    let Some((name, _)) = word.split_once('=') else {
+        let mut fields: Vec<_> = schema.fields.iter().collect();
+        fields.sort_by_key(|field| field.order);
+        return fields
+            .into_iter()
+            .map(|field| format!("{}=", field.name))
+            .filter(|candidate| candidate.starts_with(word))
+            .collect();
+    };
+    let Some(field) = schema
+        .fields
+        .iter()
+        .find(|field| field.name.eq_ignore_ascii_case(name))
+    else {
+        return Vec::new();
+    };
+    let values: Vec<String> = match &field.choices {
+        Some(choices) => choices.clone(),
+        None if matches!(field.kind.to_lowercase().as_str(), "bool" | "boolean") => {
+            vec!["true".to_string(), "false".to_string()]
+        }
+        None => Vec::new(),
+    };
+    values
+        .into_iter()
+        .map(|value| format!("{}={}", name, value))
+        .filter(|candidate| candidate.starts_with(word))
+        .collect()
+}
+
+/// Wraps clap's `_omakure` so the word after a script command completes
+/// script paths, and `run --field` completes fields.
+fn bash_scripts() -> String {
+    let patterns: Vec<String> = SCRIPT_COMMANDS
+        .iter()
+        .map(|words| match words {
+            [word] => word.to_string(),
+            words => format!("\"{}\"", words.join(" ")),
+        })
+        .collect();
+    format!(
+        r#"
+_omakure_scripts() {{
+    _omakure_fields && return 0
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    local words="${{COMP_WORDS[*]:1:COMP_CWORD-1}}"
+    if [[ ${{cur}} != -* ]]; then
+        case "${{words}}" in
+            {})
+                COMPREPLY=( $(compgen -W "$(omakure scripts --plain 2>/dev/null)" -- "${{cur}}") )
+                return 0
+                ;;
+        esac
+    fi
+    _omakure "$@"
+}}
+
+complete -F _omakure_scripts -o bashdefault -o default omakure
+"#,
+        patterns.join("|")
+    )
+}
+
+/// Points clap's `SCRIPT` and `--field` arguments at functions listing the
+/// scripts and fields. The functions go first: the file runs top to bottom
+/// as `_omakure` itself.
+fn zsh_completion(generated: &str) -> String {
+    let generated = generated
+        .replace(
+            "-- Script name or path:_default'",
+            "-- Script name or path:_omakure_scripts'",
+        )
+        .replace(
+            "--field=[Fill a schema field (repeatable)]:NAME=VALUE:_default'",
+            "--field=[Fill a schema field (repeatable)]:NAME=VALUE:_omakure_fields'",
+        );
+    match generated.split_once('\n') {
+        Some((compdef, rest)) => format!("{}\n{}{}", compdef, ZSH_HELPERS, rest),
+        None => generated,
+    }
+}
+
+fn fish_scripts() -> String {
+    let conditions: Vec<String> = SCRIPT_COMMANDS
+        .iter()
+        .map(|words| match words {
+            [word] => format!("__fish_omakure_using_subcommand {}", word),
+            [command, subcommand, ..] => format!(
+                "__fish_omakure_using_subcommand {}; and __fish_seen_subcommand_from {}",
+                command, subcommand
+            ),
+            [] => String::new(),
+        })
+        .map(|condition| {
+            format!(
+                "complete -c omakure -n \"{}\" -f -a \"(omakure scripts --plain 2>/dev/null)\"\n",
+                condition
+            )
+        })
+        .collect();
+    conditions.concat()
+}
+
+/// Adds the scripts to the completions clap lists for each script command,
+/// and answers `run --field` before clap's own cases.
+fn pwsh_completion(generated: &str) -> String {
+    let mut script = generated.to_string();
+    for words in SCRIPT_COMMANDS {
+        let case = format!("'omakure;{}' {{\n", words.join(";"));
+        script = script.replacen(
+            &case,
+            &format!(
+                "{}            & omakure scripts --plain 2>$null | ForEach-Object {{\n                [CompletionResult]::new($_, $_, [CompletionResultType]::ParameterValue, $_)\n            }}\n",
+                case
+            ),
+            1,
+        );
+    }
+    let switch = "    $completions = @(switch ($command) {";
+    script = script.replacen(
+        switch,
+        &format!(
+            "    if (\"$($commandElements[1])\" -eq 'run') {{\n        $fields = @(__OmakureFields $commandElements $wordToComplete)\n        if ($fields.Count -gt 0) {{ return $fields }}\n    }}\n\n{}",
+            switch
+        ),
+        1,
+    );
+    script.replacen(
+        "Register-ArgumentCompleter",
+        &format!("{}\nRegister-ArgumentCompleter", PWSH_FIELDS.trim_start()),
+        1,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_candidates() {
+        let schema: Schema = serde_json::from_str(
+            r#"{"Name": "deploy", "Fields": [
+                {"Name": "env", "Type": "string", "Order": 2, "Choices": ["staging", "production"]},
+                {"Name": "dry_run", "Type": "bool", "Order": 1},
+                {"Name": "tag", "Type": "string", "Order": 3}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            field_candidates(&schema, ""),
+            vec!["dry_run=", "env=", "tag="]
+        );
+        assert_eq!(field_candidates(&schema, "e"), vec!["env="]);
+        assert_eq!(field_candidates(&schema, "env=p"), vec!["env=production"]);
+        assert_eq!(
+            field_candidates(&schema, "dry_run="),
+            vec!["dry_run=true", "dry_run=false"]
+        );
+        assert!(field_candidates(&schema, "tag=").is_empty());
+    }
+}
@@ -1,5 +1,12 @@
 use crate::app_meta;
+use crate::config::{check_workspace_config, WorkspaceConfig};
+use crate::env_crypto;
+use crate::receipt;
+use crate::runtime::{powershell_program, python_program};
+use crate::status::WARN_SYMBOL;
+use crate::theme_config;
 use crate::workspace::Workspace;
+use crate::workspace_profiles;
 use std::env;
 use std::error::Error;
 use std::path::PathBuf;
@@ -10,6 +17,9 @@ pub fn run(scripts_dir: PathBuf) -> Result<(), Box<dyn Error>> {
     println!("Version: {}", app_meta::APP_VERSION);
     println!("Binary: {}", exe.display());
     println!("Workspace root: {}", workspace.root().display());
+    if let Some(name) = workspace_profiles::load().name_of(workspace.root()) {
+        println!("Named workspace: {}", name);
+    }
     println!("Omaken dir: {}", workspace.omaken_dir().display());
     println!("History dir: {}", workspace.history_dir().display());
     println!("Workspace config: {}", workspace.config_path().display());
@@ -18,6 +28,37 @@ pub fn run(scripts_dir: PathBuf) -> Result<(), Box<dyn Error>> {
         "Active environment file: {}",
         workspace.envs_active_path().display()
     );
+    println!("History db: {}", workspace.history_db_path().display());
+    println!("Artifacts dir: {}", workspace.artifacts_dir().display());
+    println!("Favorites: {}", workspace.favorites_path().display());
+    println!("Cache dir: {}", workspace.cache_dir().display());
+    println!("Search index: {}", workspace.search_db_path().display());
+    println!("State dir: {}", workspace.state_dir().display());
+    println!("Job queue: {}", workspace.queue_dir().display());
+    match theme_config::theme_layout() {
+        Some(layout) => {
+            println!("Global config dir: {}", layout.config_dir.display());
+            println!("Global config: {}", layout.config_path.display());
+            println!("Themes dir: {}", layout.themes_dir.display());
+        }
+        None => println!("Global config dir: (no home directory)"),
+    }
+    if let Some(path) = receipt::key_path() {
+        println!("Receipt key: {}", path.display());
+    }
+    if let Some(path) = env_crypto::identity_path() {
+        println!("Env file key: {}", path.display());
+    }
+
+    match check_workspace_config(workspace.config_path()) {
+        Ok((config, problems)) => {
+            print_settings(&config);
+            for problem in problems {
+                eprintln!("{} {}: {}", WARN_SYMBOL, problem.key, problem.message);
+            }
+        }
+        Err(err) => eprintln!("{} {}", WARN_SYMBOL, err),
+    }
 
     print_env_if_set("OMAKURE_SCRIPTS_DIR");
     print_env_if_set("OMAKURE_REPO");
@@ -31,6 +72,65 @@ pub fn run(scripts_dir: PathBuf) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// The effective `omakure.toml` values, defaults included.
+fn print_settings(config: &WorkspaceConfig) {
+    if let Some(name) = &config.workspace.name {
+        println!("Workspace name: {}", name);
+    }
+    let global_theme = theme_config::theme_layout()
+        .and_then(|layout| theme_config::load_theme_name(&layout.config_path));
+    println!(
+        "Theme: {}",
+        config
+            .theme
+            .name
+            .clone()
+            .or(global_theme)
+            .unwrap_or_else(|| "default".to_string())
+    );
+    println!(
+        "Runner backend: {}",
+        config.runner.backend.as_deref().unwrap_or("local")
+    );
+    match config.timeout() {
+        Some(timeout) => println!("Runner timeout: {}s", timeout.as_secs()),
+        None => println!("Runner timeout: none"),
+    }
+    let interpreters = config.interpreters();
+    println!(
+        "Interpreters: bash={}, powershell={}, python={}",
+        interpreters.bash.as_deref().unwrap_or("bash"),
+        interpreters
+            .powershell
+            .as_deref()
+            .unwrap_or(powershell_program()),
+        interpreters.python.as_deref().unwrap_or(python_program())
+    );
+    for custom in config.custom_extensions() {
+        println!(
+            "Extension .{}: {}",
+            custom.extension,
+            custom.command.join(" ")
+        );
+    }
+    println!(
+        "History timezone: {}",
+        config.history.timezone.as_deref().unwrap_or("local")
+    );
+    match config.retention() {
+        Some(retention) => println!(
+            "History retention: {} days",
+            retention.as_secs() / (24 * 60 * 60)
+        ),
+        None => println!("History retention: keep all"),
+    }
+    match config.max_entries() {
+        Some(max_entries) => println!("History max entries: {}", max_entries),
+        None => println!("History max entries: unlimited"),
+    }
+    println!("History max output: {} KB", config.max_output_kb());
+}
+
 fn print_env_if_set(name: &str) {
     if let Ok(value) = env::var(name) {
         println!("{}: {}", name, value);
@@ -0,0 +1,101 @@
+use crate::adapters::workspace_repository::FsWorkspaceRepository;
+use crate::cli::args::DigestArgs;
+use crate::config::{load_workspace_config, WorkspaceConfig};
+use crate::cron::Schedule;
+use crate::digest;
+use crate::history;
+use crate::notify;
+use crate::ports::ScriptRepository;
+use crate::workspace::Workspace;
+use std::error::Error;
+use std::fs;
+use std::thread;
+use std::time::Duration;
+
+/// Period of the first digest, when none was sent before.
+const FIRST_DIGEST_MS: i64 = 24 * 60 * 60 * 1000;
+
+pub fn run(scripts_dir: std::path::PathBuf, args: DigestArgs) -> Result<(), Box<dyn Error>> {
+    let workspace = Workspace::new(scripts_dir);
+    let config = load_workspace_config(workspace.config_path());
+    if args.watch {
+        return watch(&workspace);
+    }
+    if !args.dry_run && !notify::has_channels(&config.notifications) {
+        return Err(
+            "No [notifications] webhooks or command in omakure.toml; add --dry-run to print the digest"
+                .into(),
+        );
+    }
+    send_digest(&workspace, &config, args.dry_run)
+}
+
+/// Compiles the runs since the last digest and sends them, or only prints
+/// them on a dry run.
+fn send_digest(
+    workspace: &Workspace,
+    config: &WorkspaceConfig,
+    dry_run: bool,
+) -> Result<(), Box<dyn Error>> {
+    let now = history::timestamp_ms();
+    let since = last_digest(workspace).unwrap_or(now - FIRST_DIGEST_MS);
+    let entries = history::load_entries(workspace)?;
+    let repo = FsWorkspaceRepository::new(workspace.root().to_path_buf());
+    let digest = digest::compile(&entries, since, now, |script| {
+        repo.read_schema(&workspace.root().join(script))
+            .ok()
+            .and_then(|schema| schema.owner_label())
+    });
+    let text = digest.render(config.time_zone());
+    if dry_run {
+        print!("{}", text);
+        return Ok(());
+    }
+    notify::send(&config.notifications, &digest.headline(), &text)?;
+    if let Some(parent) = workspace.digest_state_path().parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(workspace.digest_state_path(), now.to_string())?;
+    println!("{}", digest.headline());
+    Ok(())
+}
+
+/// Sends a digest whenever the `[digest] schedule` fires. A failed delivery
+/// is reported and its runs roll into the next digest.
+fn watch(workspace: &Workspace) -> Result<(), Box<dyn Error>> {
+    let config = load_workspace_config(workspace.config_path());
+    let expression = config
+        .digest
+        .schedule
+        .as_deref()
+        .ok_or("Set schedule under [digest] in omakure.toml, e.g. \"0 8 * * 1-5\"")?;
+    let schedule = Schedule::parse(expression)?;
+    if !notify::has_channels(&config.notifications) {
+        return Err("No [notifications] webhooks or command in omakure.toml".into());
+    }
+    let time_zone = config.time_zone();
+    loop {
+        let now = history::timestamp_ms();
+        let next = schedule
+            .next_after(now, time_zone)
+            .ok_or("The [digest] schedule never fires")?;
+        println!(
+            "Next digest at {}",
+            history::format_timestamp(next, time_zone.offset_at(next))
+        );
+        thread::sleep(Duration::from_millis((next - now).max(0) as u64));
+        // Re-read the config so channel changes apply without a restart.
+        let config = load_workspace_config(workspace.config_path());
+        if let Err(err) = send_digest(workspace, &config, false) {
+            eprintln!("{}", err);
+        }
+    }
+}
+
+fn last_digest(workspace: &Workspace) -> Option<i64> {
+    fs::read_to_string(workspace.digest_state_path())
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
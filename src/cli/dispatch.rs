@@ -0,0 +1,281 @@
+use crate::adapters::workspace_repository::FsWorkspaceRepository;
+use crate::cli::args::DispatchArgs;
+use crate::domain::inputs_from_args;
+use crate::history::{self, UtcOffset};
+use crate::ports::ScriptRepository;
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::env;
+use std::error::Error;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const WORKFLOW_DISPATCH: &str = "workflow_dispatch";
+const DEFAULT_API_URL: &str = "https://api.github.com";
+const DEFAULT_REF: &str = "main";
+const DEFAULT_POLL_SECONDS: u64 = 10;
+const DEFAULT_TIMEOUT_MINUTES: u64 = 60;
+/// Slack for the local clock running ahead of GitHub's when matching the
+/// run to the dispatch by its creation time.
+const CLOCK_SKEW_MS: i64 = 60_000;
+/// How long to look for the run a dispatch created before giving up.
+const FIND_RUN_ATTEMPTS: u32 = 20;
+const FIND_RUN_INTERVAL: Duration = Duration::from_secs(3);
+
+pub fn run(args: DispatchArgs) -> Result<(), Box<dyn Error>> {
+    let root = args
+        .action
+        .parent()
+        .map(|parent| parent.to_path_buf())
+        .unwrap_or_default();
+    let schema = FsWorkspaceRepository::new(root).read_schema(&args.action)?;
+    if schema.kind.as_deref() != Some(WORKFLOW_DISPATCH) {
+        return Err(format!(
+            "{} is not a {} action (set \"Type\": \"{}\")",
+            args.action.display(),
+            WORKFLOW_DISPATCH,
+            WORKFLOW_DISPATCH
+        )
+        .into());
+    }
+    let target = schema
+        .workflow
+        .ok_or("workflow_dispatch action is missing its \"Workflow\" block")?;
+    let git_ref = target.git_ref.as_deref().unwrap_or(DEFAULT_REF);
+    let poll = Duration::from_secs(target.poll_seconds.unwrap_or(DEFAULT_POLL_SECONDS).max(1));
+    let timeout_minutes = target
+        .timeout_minutes
+        .unwrap_or(DEFAULT_TIMEOUT_MINUTES)
+        .max(1);
+
+    let mut inputs = Map::new();
+    let values = inputs_from_args(&schema.fields, &args.args);
+    for (field, value) in schema.fields.iter().zip(values) {
+        if !value.is_empty() {
+            inputs.insert(field.name.clone(), Value::String(value));
+        }
+    }
+
+    let github = GitHub::from_env()?;
+    let runs_path = format!(
+        "/repos/{}/actions/workflows/{}/runs?event={}&branch={}&per_page=20",
+        target.repo, target.workflow, WORKFLOW_DISPATCH, git_ref
+    );
+    let known: HashSet<u64> = run_ids(&github.request("GET", &runs_path, None)?);
+    // Runs started by someone else in the meantime are told apart by their
+    // actor, when the token can tell whose it is.
+    let actor = github.request("GET", "/user", None).ok().and_then(|user| {
+        user.get("login")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    });
+    let new_runs_path = format!(
+        "{}&created=%3E%3D{}",
+        runs_path,
+        utc_iso(history::timestamp_ms() - CLOCK_SKEW_MS)
+    );
+    github.request(
+        "POST",
+        &format!(
+            "/repos/{}/actions/workflows/{}/dispatches",
+            target.repo, target.workflow
+        ),
+        Some(&json!({ "ref": git_ref, "inputs": inputs })),
+    )?;
+    println!(
+        "Dispatched {} on {} at {}",
+        target.workflow, target.repo, git_ref
+    );
+
+    let mut run_id = None;
+    for _ in 0..FIND_RUN_ATTEMPTS {
+        thread::sleep(FIND_RUN_INTERVAL);
+        let runs = github.request("GET", &new_runs_path, None)?;
+        run_id = dispatched_run(&runs, &known, actor.as_deref());
+        if run_id.is_some() {
+            break;
+        }
+    }
+    let run_id = run_id.ok_or("Workflow was dispatched, but its run did not show up in time")?;
+
+    let run_path = format!("/repos/{}/actions/runs/{}", target.repo, run_id);
+    let mut run_url = format!("https://github.com/{}/actions/runs/{}", target.repo, run_id);
+    let mut last_status = String::new();
+    let started = Instant::now();
+    loop {
+        let run = github.request("GET", &run_path, None)?;
+        if last_status.is_empty() {
+            if let Some(url) = run.get("html_url").and_then(Value::as_str) {
+                run_url = url.to_string();
+            }
+            println!("Run: {}", run_url);
+        }
+        let status = run
+            .get("status")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown")
+            .to_string();
+        if status != last_status {
+            println!("Status: {}", status);
+            last_status = status;
+        }
+        if last_status == "completed" {
+            let conclusion = run
+                .get("conclusion")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown");
+            println!("Conclusion: {}", conclusion);
+            if conclusion != "success" {
+                eprintln!("Workflow run finished with {}", conclusion);
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        if started.elapsed() >= Duration::from_secs(timeout_minutes * 60) {
+            return Err(format!(
+                "Workflow run did not complete within {} minutes: {}",
+                timeout_minutes, run_url
+            )
+            .into());
+        }
+        thread::sleep(poll);
+    }
+}
+
+/// The earliest run in `runs` that is not `known` and, when `actor` is set,
+/// was started by them: the one the dispatch created.
+fn dispatched_run(runs: &Value, known: &HashSet<u64>, actor: Option<&str>) -> Option<u64> {
+    runs.get("workflow_runs")?
+        .as_array()?
+        .iter()
+        .filter(|run| {
+            actor.is_none_or(|actor| {
+                run.pointer("/actor/login").and_then(Value::as_str) == Some(actor)
+            })
+        })
+        .filter_map(|run| run.get("id").and_then(Value::as_u64))
+        .filter(|id| !known.contains(id))
+        .min()
+}
+
+/// `timestamp_ms` as `YYYY-MM-DDTHH:MM:SSZ`, as the GitHub API filters take it.
+fn utc_iso(timestamp_ms: i64) -> String {
+    let (year, month, day, hour, minute, second) =
+        history::civil_time(timestamp_ms, UtcOffset::UTC);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, hour, minute, second
+    )
+}
+
+fn run_ids(runs: &Value) -> HashSet<u64> {
+    runs.get("workflow_runs")
+        .and_then(Value::as_array)
+        .map(|runs| {
+            runs.iter()
+                .filter_map(|run| run.get("id").and_then(Value::as_u64))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Minimal GitHub REST client on top of `curl`, like `omakure update`.
+struct GitHub {
+    api: String,
+    token: String,
+}
+
+impl GitHub {
+    fn from_env() -> Result<Self, Box<dyn Error>> {
+        let token = ["GITHUB_TOKEN", "GH_TOKEN"]
+            .iter()
+            .find_map(|name| env::var(name).ok().filter(|value| !value.trim().is_empty()))
+            .ok_or("Set GITHUB_TOKEN (or GH_TOKEN), e.g. in the active env file")?;
+        let api = env::var("GITHUB_API_URL").unwrap_or_else(|_| DEFAULT_API_URL.to_string());
+        Ok(Self {
+            api: api.trim_end_matches('/').to_string(),
+            token,
+        })
+    }
+
+    fn request(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&Value>,
+    ) -> Result<Value, Box<dyn Error>> {
+        let mut command = Command::new("curl");
+        command
+            .args(["--silent", "--show-error", "--config", "-"])
+            .args(["--request", method, "--write-out", "\n%{http_code}"])
+            .arg(format!("{}{}", self.api, path))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(body) = body {
+            command
+                .args(["--header", "Content-Type: application/json"])
+                .arg("--data-binary")
+                .arg(body.to_string());
+        }
+        let mut child = command.spawn()?;
+        // Headers go through stdin so the token never shows up in the process list.
+        if let Some(mut stdin) = child.stdin.take() {
+            write!(
+                stdin,
+                "header = \"Authorization: Bearer {}\"\n\
+                 header = \"Accept: application/vnd.github+json\"\n\
+                 header = \"X-GitHub-Api-Version: 2022-11-28\"\n",
+                self.token
+            )?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(format!(
+                "curl failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+            .into());
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let (response, code) = stdout.rsplit_once('\n').unwrap_or(("", &stdout));
+        let code: u16 = code.trim().parse().unwrap_or(0);
+        let value = if response.trim().is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_str(response).unwrap_or(Value::Null)
+        };
+        if !(200..300).contains(&code) {
+            let message = value
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("request failed");
+            return Err(
+                format!("GitHub API {} {}: HTTP {}: {}", method, path, code, message).into(),
+            );
+        }
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dispatched_run_is_the_earliest_new_run_of_the_actor() {
+        let runs = json!({ "workflow_runs": [
+            { "id": 40, "actor": { "login": "me" } },
+            { "id": 31, "actor": { "login": "someone" } },
+            { "id": 33, "actor": { "login": "me" } },
+            { "id": 20, "actor": { "login": "me" } },
+        ]});
+        let known = HashSet::from([20]);
+        assert_eq!(dispatched_run(&runs, &known, Some("me")), Some(33));
+        assert_eq!(dispatched_run(&runs, &known, None), Some(31));
+        assert_eq!(dispatched_run(&runs, &known, Some("other")), None);
+        assert_eq!(utc_iso(1_705_321_800_000), "2024-01-15T12:30:00Z");
+    }
+}
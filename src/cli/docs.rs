@@ -0,0 +1,249 @@
+use crate::adapters::workspace_repository::FsWorkspaceRepository;
+use crate::cli::args::DocsArgs;
+use crate::cli::run::resolve_script_path;
+use crate::config::load_workspace_config;
+use crate::domain::{Field, Schema};
+use crate::history::{self, HistoryEntry, TimeZone};
+use crate::ports::ScriptRepository;
+use crate::util::shell_join;
+use crate::workspace::Workspace;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn run(scripts_dir: PathBuf, args: DocsArgs) -> Result<(), Box<dyn Error>> {
+    let workspace = Workspace::new(scripts_dir);
+    let time_zone = load_workspace_config(workspace.config_path()).time_zone();
+    let repo = FsWorkspaceRepository::new(workspace.root().to_path_buf());
+    let scripts = match &args.script {
+        Some(script) => vec![resolve_script_path(script, workspace.root())?],
+        None => {
+            let mut scripts = repo.list_scripts_recursive()?;
+            scripts.sort();
+            scripts
+        }
+    };
+    let entries = history::load_entries(&workspace).unwrap_or_default();
+
+    let mut written = 0;
+    for script in &scripts {
+        let relative = script
+            .strip_prefix(workspace.root())
+            .unwrap_or(script)
+            .to_path_buf();
+        let schema = match repo.read_schema(script) {
+            Ok(schema) => schema,
+            Err(err) if args.all => {
+                eprintln!("Skipping {}: {}", relative.display(), err);
+                continue;
+            }
+            Err(err) => return Err(Box::new(err)),
+        };
+        let runs: Vec<&HistoryEntry> = entries
+            .iter()
+            .filter(|entry| entry.script == relative)
+            .collect();
+        let markdown = render_markdown(&relative, &schema, &runs, time_zone);
+        match &args.out {
+            Some(dir) => {
+                fs::create_dir_all(dir)?;
+                fs::write(dir.join(doc_file_name(&relative)), markdown)?;
+            }
+            None => {
+                if written > 0 {
+                    println!();
+                }
+                print!("{}", markdown);
+            }
+        }
+        written += 1;
+    }
+
+    if let Some(dir) = &args.out {
+        eprintln!("Wrote {} docs to {}", written, dir.display());
+    }
+    Ok(())
+}
+
+fn render_markdown(
+    script: &Path,
+    schema: &Schema,
+    runs: &[&HistoryEntry],
+    time_zone: TimeZone,
+) -> String {
+    let mut md = String::new();
+    let _ = writeln!(md, "# {}\n", schema.name);
+    if let Some(description) = &schema.description {
+        let _ = writeln!(md, "{}\n", description);
+    }
+    let _ = writeln!(md, "- Script: `{}`", script.display());
+    if let Some(tags) = schema.tags.as_ref().filter(|tags| !tags.is_empty()) {
+        let _ = writeln!(md, "- Tags: {}", tags.join(", "));
+    }
+    if let Some(owner) = schema.owner_label() {
+        let _ = writeln!(md, "- Owner: {}", owner);
+    }
+    if let Some(seconds) = schema.expected_duration_seconds {
+        let _ = writeln!(
+            md,
+            "- Expected duration: {}",
+            history::format_duration(seconds.saturating_mul(1000))
+        );
+    }
+    if let Some(group) = schema.concurrency_group() {
+        let _ = writeln!(md, "- Concurrency group: `{}`", group);
+    }
+    md.push('\n');
+
+    let mut fields = schema.fields.clone();
+    fields.sort_by_key(|field| field.order);
+    md.push_str("## Fields\n\n");
+    if fields.is_empty() {
+        md.push_str("This script takes no fields.\n\n");
+    } else {
+        md.push_str("| Name | Type | Required | Arg | Default | Choices | Prompt |\n");
+        md.push_str("| --- | --- | --- | --- | --- | --- | --- |\n");
+        for field in &fields {
+            let _ = writeln!(
+                md,
+                "| {} | {} | {} | {} | {} | {} | {} |",
+                cell(&field.name),
+                cell(&field.kind),
+                if field.required.unwrap_or(false) {
+                    "yes"
+                } else {
+                    "no"
+                },
+                code_cell(field.arg.as_deref()),
+                code_cell(field.default.as_deref()),
+                cell(&field.choices.as_deref().unwrap_or_default().join(", ")),
+                cell(field.prompt.as_deref().unwrap_or("")),
+            );
+        }
+        md.push('\n');
+    }
+
+    md.push_str("## Example\n\n```bash\n");
+    let _ = writeln!(md, "{}", example_command(script, &fields));
+    md.push_str("```\n\n");
+
+    if let Some(outputs) = schema
+        .outputs
+        .as_ref()
+        .filter(|outputs| !outputs.is_empty())
+    {
+        md.push_str("## Outputs\n\n| Name | Type |\n| --- | --- |\n");
+        for output in outputs {
+            let _ = writeln!(md, "| {} | {} |", cell(&output.name), cell(&output.kind));
+        }
+        md.push('\n');
+    }
+
+    if let Some(queue) = &schema.queue {
+        if let Some(matrix) = &queue.matrix {
+            md.push_str("## Queue matrix\n\n| Field | Values |\n| --- | --- |\n");
+            for value in &matrix.values {
+                let _ = writeln!(
+                    md,
+                    "| {} | {} |",
+                    cell(&value.name),
+                    cell(&value.values.join(", "))
+                );
+            }
+            md.push('\n');
+        }
+        if let Some(cases) = queue.cases.as_ref().filter(|cases| !cases.is_empty()) {
+            md.push_str("## Queue cases\n\n| Case | Values |\n| --- | --- |\n");
+            for (index, case) in cases.iter().enumerate() {
+                let name = case
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("case {}", index + 1));
+                let values = case
+                    .values
+                    .iter()
+                    .map(|value| format!("{}={}", value.name, value.value))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let _ = writeln!(md, "| {} | {} |", cell(&name), cell(&values));
+            }
+            md.push('\n');
+        }
+    }
+
+    md.push_str("## Recent runs\n\n");
+    md.push_str(&run_statistics(runs, time_zone));
+    md
+}
+
+/// `omakure run` line filling each field with its default or a placeholder.
+fn example_command(script: &Path, fields: &[Field]) -> String {
+    let mut parts = vec![
+        "omakure".to_string(),
+        "run".to_string(),
+        script.to_string_lossy().to_string(),
+    ];
+    for field in fields {
+        let value = field
+            .default
+            .clone()
+            .or_else(|| field.choices.as_ref().and_then(|c| c.first().cloned()))
+            .unwrap_or_else(|| format!("<{}>", field.name));
+        parts.push("--field".to_string());
+        parts.push(format!("{}={}", field.name, value));
+    }
+    shell_join(&parts)
+}
+
+fn run_statistics(runs: &[&HistoryEntry], time_zone: TimeZone) -> String {
+    let Some(last) = runs.first() else {
+        return "No runs recorded yet.\n".to_string();
+    };
+    let succeeded = runs.iter().filter(|entry| entry.success).count();
+    let durations: Vec<u64> = runs.iter().filter_map(|entry| entry.duration_ms).collect();
+    let mut md = String::new();
+    let _ = writeln!(
+        md,
+        "- Runs: {} ({}% succeeded)",
+        runs.len(),
+        succeeded * 100 / runs.len()
+    );
+    if !durations.is_empty() {
+        let average = durations.iter().sum::<u64>() / durations.len() as u64;
+        let _ = writeln!(
+            md,
+            "- Average duration: {}",
+            history::format_duration(average)
+        );
+    }
+    let _ = writeln!(
+        md,
+        "- Last run: {} ({})",
+        history::format_timestamp(last.timestamp, time_zone.offset_at(last.timestamp)),
+        if last.success { "ok" } else { "failed" }
+    );
+    md
+}
+
+fn doc_file_name(script: &Path) -> String {
+    let stem = script.with_extension("");
+    let name: String = stem
+        .to_string_lossy()
+        .chars()
+        .map(|ch| if ch == '/' || ch == '\\' { '-' } else { ch })
+        .collect();
+    format!("{}.md", name)
+}
+
+/// Escapes a value for a markdown table cell.
+fn cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+fn code_cell(value: Option<&str>) -> String {
+    match value {
+        Some(value) if !value.is_empty() => format!("`{}`", cell(value)),
+        _ => String::new(),
+    }
+}
@@ -1,64 +1,252 @@
 use crate::adapters::system_checks::{
     ensure_bash_installed, ensure_git_installed, ensure_jq_installed, ensure_powershell_installed,
-    ensure_python_installed,
+    ensure_python_installed, ensure_runtime_installed,
 };
+use crate::adapters::workspace_repository::FsWorkspaceRepository;
+use crate::app_meta::APP_VERSION;
+use crate::cli::args::DoctorArgs;
+use crate::cli::omaken::flavor_names;
+use crate::config::check_workspace_config;
+use crate::omaken_manifest;
+use crate::ports::ScriptRepository;
+use crate::requirements;
+use crate::status::{FAIL_SYMBOL, OK_SYMBOL, WARN_SYMBOL};
 use crate::workspace::Workspace;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::error::Error;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-pub fn run(scripts_dir: PathBuf) -> Result<(), Box<dyn Error>> {
-    let mut ok = true;
+/// How a check came out: errors fail `doctor`, warnings do not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    Warn,
+    Error,
+}
+
+#[derive(Debug, Serialize)]
+struct Check {
+    /// `checks`, `config`, `flavors`, or `scripts`.
+    section: &'static str,
+    name: String,
+    status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct Report {
+    ok: bool,
+    version: &'static str,
+    checks: Vec<Check>,
+}
+
+pub fn run(scripts_dir: PathBuf, args: DoctorArgs) -> Result<(), Box<dyn Error>> {
     let workspace = Workspace::new(scripts_dir);
+    let mut checks = vec![
+        required("checks", "git", ensure_git_installed()),
+        required("checks", "bash", ensure_bash_installed()),
+        required("checks", "jq", ensure_jq_installed()),
+        optional("checks", "powershell", ensure_powershell_installed()),
+        optional("checks", "python", ensure_python_installed()),
+        workspace_path("workspace_root", workspace.root()),
+        workspace_path("omaken_dir", workspace.omaken_dir()),
+        workspace_path("history_dir", workspace.history_dir()),
+        workspace_path("workspace_config", workspace.config_path()),
+    ];
 
-    println!("Checks:");
-    ok &= print_required("git", ensure_git_installed());
-    ok &= print_required("bash", ensure_bash_installed());
-    ok &= print_required("jq", ensure_jq_installed());
-    print_optional("powershell", ensure_powershell_installed());
-    print_optional("python", ensure_python_installed());
+    match check_workspace_config(workspace.config_path()) {
+        Ok((_, problems)) if problems.is_empty() => {
+            checks.push(optional("config", "omakure.toml", Ok::<(), String>(())));
+        }
+        Ok((_, problems)) => {
+            // Problem values are ignored or fall back to defaults, so runs still work.
+            let mut by_key: Vec<(String, Vec<String>)> = Vec::new();
+            for problem in problems {
+                match by_key.iter_mut().find(|(key, _)| *key == problem.key) {
+                    Some((_, messages)) => messages.push(problem.message),
+                    None => by_key.push((problem.key, vec![problem.message])),
+                }
+            }
+            for (key, messages) in by_key {
+                checks.push(optional("config", &key, Err(messages.join("; "))));
+            }
+        }
+        Err(err) => checks.push(required("config", "omakure.toml", Err(err))),
+    }
 
-    print_workspace_path("workspace_root", workspace.root());
-    print_workspace_path("omaken_dir", workspace.omaken_dir());
-    print_workspace_path("history_dir", workspace.history_dir());
-    print_workspace_path("workspace_config", workspace.config_path());
+    check_flavors(&workspace, &mut checks);
+    check_scripts(&workspace, &mut checks);
 
+    let ok = checks.iter().all(|check| check.status != Status::Error);
+    if args.json {
+        let report = Report {
+            ok,
+            version: APP_VERSION,
+            checks,
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_checks(&checks);
+        println!(
+            "{}",
+            if ok {
+                "All checks passed."
+            } else {
+                "One or more checks failed."
+            }
+        );
+    }
     if !ok {
-        println!("One or more checks failed.");
         std::process::exit(1);
     }
-
-    println!("All checks passed.");
     Ok(())
 }
 
-fn print_required<E: std::fmt::Display>(label: &str, result: Result<(), E>) -> bool {
-    match result {
-        Ok(()) => {
-            println!("  {}: OK", label);
-            true
-        }
-        Err(err) => {
-            println!("  {}: ERROR - {}", label, err);
-            false
+/// Checks the omakure version and runtimes each flavor's `omaken.toml`
+/// asks for; flavors without one are skipped.
+fn check_flavors(workspace: &Workspace, checks: &mut Vec<Check>) {
+    let manifests: Vec<_> = flavor_names(workspace)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|name| {
+            let manifest = omaken_manifest::load(&workspace.omaken_dir().join(&name));
+            Some((name, manifest.transpose()?))
+        })
+        .collect();
+    for (name, manifest) in manifests {
+        let manifest = match manifest {
+            Ok(manifest) => manifest,
+            Err(err) => {
+                checks.push(required("flavors", &name, Err(err)));
+                continue;
+            }
+        };
+        checks.push(required(
+            "flavors",
+            &name,
+            manifest.check_version(APP_VERSION),
+        ));
+        for runtime in &manifest.runtimes {
+            checks.push(required(
+                "flavors",
+                &format!("{}: {}", name, runtime),
+                ensure_runtime_installed(runtime),
+            ));
         }
     }
 }
 
-fn print_optional<E: std::fmt::Display>(label: &str, result: Result<(), E>) {
-    match result {
-        Ok(()) => {
-            println!("  {}: OK", label);
-        }
-        Err(err) => {
-            println!("  {}: WARN - {}", label, err);
+/// Checks the tools each script lists in `Requires`, running each distinct
+/// check once.
+fn check_scripts(workspace: &Workspace, checks: &mut Vec<Check>) {
+    let repo = FsWorkspaceRepository::new(workspace.root().to_path_buf());
+    let mut scripts = repo.list_scripts_recursive().unwrap_or_default();
+    scripts.sort();
+    let mut results: HashMap<String, Result<(), String>> = HashMap::new();
+    for script in scripts {
+        let Some(requires) = repo
+            .read_schema(&script)
+            .ok()
+            .and_then(|schema| schema.requires)
+        else {
+            continue;
+        };
+        let relative = script
+            .strip_prefix(workspace.root())
+            .unwrap_or(&script)
+            .to_string_lossy()
+            .replace('\\', "/");
+        for spec in requires {
+            let result = results
+                .entry(spec.clone())
+                .or_insert_with(|| requirements::check(&spec))
+                .clone();
+            checks.push(required(
+                "scripts",
+                &format!("{}: {}", relative, spec),
+                result,
+            ));
         }
     }
 }
 
-fn print_workspace_path(label: &str, path: &std::path::Path) {
-    if path.exists() {
-        println!("  {}: OK - {}", label, path.display());
-    } else {
-        println!("  {}: WARN - {} (not created yet)", label, path.display());
+fn required<E: std::fmt::Display>(
+    section: &'static str,
+    name: &str,
+    result: Result<(), E>,
+) -> Check {
+    check(section, name, result, Status::Error)
+}
+
+fn optional<E: std::fmt::Display>(
+    section: &'static str,
+    name: &str,
+    result: Result<(), E>,
+) -> Check {
+    check(section, name, result, Status::Warn)
+}
+
+fn check<E: std::fmt::Display>(
+    section: &'static str,
+    name: &str,
+    result: Result<(), E>,
+    failure: Status,
+) -> Check {
+    let (status, message) = match result {
+        Ok(()) => (Status::Ok, None),
+        Err(err) => (failure, Some(err.to_string())),
+    };
+    Check {
+        section,
+        name: name.to_string(),
+        status,
+        message,
+        path: None,
+    }
+}
+
+fn workspace_path(name: &str, path: &Path) -> Check {
+    let exists = path.exists();
+    Check {
+        section: "checks",
+        name: name.to_string(),
+        status: if exists { Status::Ok } else { Status::Warn },
+        message: (!exists).then(|| "not created yet".to_string()),
+        path: Some(path.to_path_buf()),
+    }
+}
+
+fn print_checks(checks: &[Check]) {
+    let mut section = None;
+    for check in checks {
+        if section != Some(check.section) {
+            section = Some(check.section);
+            println!(
+                "{}:",
+                match check.section {
+                    "config" => "Config",
+                    "flavors" => "Flavors",
+                    "scripts" => "Scripts",
+                    _ => "Checks",
+                }
+            );
+        }
+        let (symbol, label) = match check.status {
+            Status::Ok => (OK_SYMBOL, "OK"),
+            Status::Warn => (WARN_SYMBOL, "WARN"),
+            Status::Error => (FAIL_SYMBOL, "ERROR"),
+        };
+        let detail = match (&check.path, &check.message) {
+            (Some(path), Some(message)) => format!(" - {} ({})", path.display(), message),
+            (Some(path), None) => format!(" - {}", path.display()),
+            (None, Some(message)) => format!(" - {}", message),
+            (None, None) => String::new(),
+        };
+        println!("  {}: {} {}{}", check.name, symbol, label, detail);
     }
 }
@@ -0,0 +1,131 @@
+use crate::adapters::environments::FsEnvironmentRepository;
+use crate::cli::args::{EnvsArgs, EnvsCommand};
+use crate::env_crypto;
+use crate::use_cases::EnvironmentService;
+use crate::workspace::Workspace;
+use std::error::Error;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub fn run(scripts_dir: PathBuf, args: EnvsArgs) -> Result<(), Box<dyn Error>> {
+    let workspace = Workspace::new(scripts_dir);
+    let service =
+        EnvironmentService::new(Box::new(FsEnvironmentRepository::new(workspace.envs_dir())));
+    match args.command.unwrap_or(EnvsCommand::List) {
+        EnvsCommand::List => list(&service),
+        EnvsCommand::Active => {
+            if let Some(name) = service.load_environment_config()?.active {
+                println!("{}", name);
+            }
+            Ok(())
+        }
+        EnvsCommand::Use(args) => {
+            let name = resolve_name(&service, &args.name)?;
+            service.set_active_env(Some(&name))?;
+            println!("Active env: {}", name);
+            Ok(())
+        }
+        EnvsCommand::Off => {
+            service.set_active_env(None)?;
+            println!("No active env");
+            Ok(())
+        }
+        EnvsCommand::Show(args) => {
+            let name = match args.name {
+                Some(name) => resolve_name(&service, &name)?,
+                None => service
+                    .load_environment_config()?
+                    .active
+                    .ok_or("No active env; name one to show")?,
+            };
+            let config = service.load_environment_config()?;
+            for (key, value) in service.load_env_preview(&config.envs_dir.join(&name))? {
+                println!("{}={}", key, value);
+            }
+            Ok(())
+        }
+        EnvsCommand::Encrypt(args) => {
+            let name = resolve_name(&service, &args.name)?;
+            if env_crypto::is_encrypted(Path::new(&name)) {
+                return Err(format!("{} is already encrypted", name).into());
+            }
+            convert(&service, &workspace, &name, env_crypto::encrypt_file)
+        }
+        EnvsCommand::Decrypt(args) => {
+            let name = resolve_name(&service, &args.name)?;
+            if !env_crypto::is_encrypted(Path::new(&name)) {
+                return Err(format!("{} is not encrypted", name).into());
+            }
+            convert(&service, &workspace, &name, env_crypto::decrypt_file)
+        }
+    }
+}
+
+/// Replaces env file `name` with the file `apply` writes, keeping it active
+/// if it was.
+fn convert(
+    service: &EnvironmentService,
+    workspace: &Workspace,
+    name: &str,
+    apply: fn(&Path) -> io::Result<PathBuf>,
+) -> Result<(), Box<dyn Error>> {
+    let was_active = service.load_environment_config()?.active.as_deref() == Some(name);
+    let written = apply(&workspace.envs_dir().join(name))?;
+    let written_name = written
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or("Env file name is not valid UTF-8")?;
+    if was_active {
+        service.set_active_env(Some(written_name))?;
+    }
+    println!("Wrote {}", written.display());
+    Ok(())
+}
+
+fn list(service: &EnvironmentService) -> Result<(), Box<dyn Error>> {
+    let files = service.list_env_files()?;
+    let active = match service.load_environment_config() {
+        Ok(config) => config.active,
+        Err(err) => {
+            eprintln!("{}", err);
+            None
+        }
+    };
+    if files.is_empty() {
+        println!("No env files.");
+        return Ok(());
+    }
+    for file in files {
+        let marker = if active.as_deref() == Some(file.name.as_str()) {
+            "*"
+        } else {
+            " "
+        };
+        println!("{} {}", marker, file.name);
+    }
+    Ok(())
+}
+
+/// The env file called `name`, or `name.conf`.
+fn resolve_name(service: &EnvironmentService, name: &str) -> Result<String, Box<dyn Error>> {
+    let files = service.list_env_files()?;
+    let with_conf = format!("{}.conf", name);
+    files
+        .iter()
+        .find(|file| file.name == name)
+        .or_else(|| files.iter().find(|file| file.name == with_conf))
+        .map(|file| file.name.clone())
+        .ok_or_else(|| {
+            let names: Vec<&str> = files.iter().map(|file| file.name.as_str()).collect();
+            format!(
+                "Unknown env file '{}'; available: {}",
+                name,
+                if names.is_empty() {
+                    "(none)".to_string()
+                } else {
+                    names.join(", ")
+                }
+            )
+            .into()
+        })
+}
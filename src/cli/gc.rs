@@ -0,0 +1,64 @@
+use crate::cli::args::GcArgs;
+use crate::gc;
+use crate::history;
+use crate::status::WARN_SYMBOL;
+use crate::util::format_bytes;
+use crate::workspace::Workspace;
+use std::error::Error;
+use std::path::PathBuf;
+
+pub fn run(scripts_dir: PathBuf, args: GcArgs) -> Result<(), Box<dyn Error>> {
+    let workspace = Workspace::new(scripts_dir);
+    // Artifacts are judged against history, so an unreadable history stops here.
+    let entries = history::load_entries(&workspace)?;
+    let garbage = gc::collect(&workspace, &entries)?;
+    if garbage.is_empty() {
+        println!("Nothing to clean up.");
+        return Ok(());
+    }
+
+    let mut reclaimed = 0;
+    let mut failed = 0;
+    for item in &garbage {
+        let path = item
+            .path
+            .strip_prefix(workspace.root())
+            .unwrap_or(&item.path)
+            .display();
+        if args.dry_run {
+            reclaimed += item.bytes;
+            println!(
+                "  {} ({}, {})",
+                path,
+                item.kind.label(),
+                format_bytes(item.bytes)
+            );
+            continue;
+        }
+        match gc::reclaim(item) {
+            Ok(bytes) => {
+                reclaimed += bytes;
+                println!(
+                    "  {} ({}, {})",
+                    path,
+                    item.kind.label(),
+                    format_bytes(bytes)
+                );
+            }
+            Err(err) => {
+                failed += 1;
+                println!("  {} {}", WARN_SYMBOL, err);
+            }
+        }
+    }
+    let verb = if args.dry_run {
+        "Would reclaim"
+    } else {
+        "Reclaimed"
+    };
+    println!("{} {}.", verb, format_bytes(reclaimed));
+    if failed > 0 {
+        return Err(format!("{} items could not be removed", failed).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use crate::cli::args::{ExportFormat, HistoryArgs, HistoryCommand, HistoryExportArgs};
+use crate::config::load_workspace_config;
+use crate::history::{self, HistoryEntry, TimeZone};
+use crate::util::shell_join;
+use crate::workspace::Workspace;
+use serde::Serialize;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+
+#[derive(Serialize)]
+struct ExportRecord {
+    timestamp: String,
+    timestamp_ms: i64,
+    script: String,
+    args: Vec<String>,
+    status: &'static str,
+    exit_code: Option<i32>,
+    duration_ms: Option<u64>,
+}
+
+impl ExportRecord {
+    fn new(entry: &HistoryEntry, time_zone: TimeZone) -> Self {
+        let status = if entry.error.is_some() {
+            "error"
+        } else if entry.success {
+            "ok"
+        } else {
+            "failed"
+        };
+        Self {
+            timestamp: history::format_iso8601(
+                entry.timestamp,
+                time_zone.offset_at(entry.timestamp),
+            ),
+            timestamp_ms: entry.timestamp,
+            script: entry.script.to_string_lossy().to_string(),
+            args: entry.args.clone(),
+            status,
+            exit_code: entry.exit_code,
+            duration_ms: entry.duration_ms,
+        }
+    }
+}
+
+pub fn run(scripts_dir: PathBuf, args: HistoryArgs) -> Result<(), Box<dyn Error>> {
+    match args.command {
+        HistoryCommand::Export(args) => export(scripts_dir, args),
+    }
+}
+
+fn export(scripts_dir: PathBuf, args: HistoryExportArgs) -> Result<(), Box<dyn Error>> {
+    let workspace = Workspace::new(scripts_dir);
+    let time_zone = load_workspace_config(workspace.config_path()).time_zone();
+    let records: Vec<ExportRecord> = history::load_entries(&workspace)?
+        .iter()
+        .map(|entry| ExportRecord::new(entry, time_zone))
+        .collect();
+
+    let mut out: Box<dyn Write> = match &args.out {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    match args.format {
+        ExportFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &records)?;
+            writeln!(out)?;
+        }
+        ExportFormat::Ndjson => {
+            for record in &records {
+                serde_json::to_writer(&mut out, record)?;
+                writeln!(out)?;
+            }
+        }
+        ExportFormat::Csv => write_csv(&mut out, &records)?,
+    }
+    out.flush()?;
+
+    if let Some(path) = &args.out {
+        eprintln!("Exported {} entries to {}", records.len(), path.display());
+    }
+    Ok(())
+}
+
+fn write_csv(out: &mut dyn Write, records: &[ExportRecord]) -> io::Result<()> {
+    writeln!(
+        out,
+        "timestamp,timestamp_ms,script,args,status,exit_code,duration_ms"
+    )?;
+    for record in records {
+        let exit_code = record
+            .exit_code
+            .map(|code| code.to_string())
+            .unwrap_or_default();
+        let duration_ms = record
+            .duration_ms
+            .map(|ms| ms.to_string())
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{}",
+            record.timestamp,
+            record.timestamp_ms,
+            csv_field(&record.script),
+            csv_field(&shell_join(&record.args)),
+            record.status,
+            exit_code,
+            duration_ms
+        )?;
+    }
+    Ok(())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -1,6 +1,9 @@
 use crate::cli::args::InitArgs;
-use crate::runtime::{script_extensions, script_kind, ScriptKind};
-use crate::util::set_executable_permissions;
+use crate::codegen;
+use crate::domain::Field;
+use crate::openapi::{self, Operation, ParamLocation};
+use crate::runtime::{custom_extension, script_extensions, script_kind, ScriptKind};
+use crate::util::{bash_quote, set_executable_permissions};
 use crate::workspace::Workspace;
 use std::error::Error;
 use std::fs;
@@ -31,10 +34,35 @@ pub fn run(scripts_dir: PathBuf, options: InitArgs) -> Result<(), Box<dyn Error>
     if script_id.is_empty() {
         return Err("Script name must contain letters or numbers".into());
     }
-    let kind = script_kind(&script_path).ok_or("Unsupported script extension")?;
-    let content = build_template(&script_id, kind);
+    let kind = script_kind(&script_path)
+        .filter(|kind| {
+            !matches!(
+                kind,
+                ScriptKind::Executable | ScriptKind::Make | ScriptKind::Just
+            )
+        })
+        .ok_or("Unsupported script extension")?;
+    let content = match (&options.from_openapi, &options.operation) {
+        (Some(spec), Some(operation_id)) => {
+            if kind != ScriptKind::Bash {
+                return Err(
+                    "--from-openapi generates bash scripts; use a .bash or .sh name".into(),
+                );
+            }
+            let spec = openapi::load_spec(spec)?;
+            let operation = openapi::find_operation(&spec, operation_id)?;
+            build_openapi_template(&script_id, &operation)
+        }
+        _ if kind == ScriptKind::Custom => {
+            let custom = custom_extension(&script_path).ok_or("Unsupported script extension")?;
+            build_custom_template(&script_id, &custom.comment)
+        }
+        _ => build_template(&script_id, kind),
+    };
     fs::write(&script_path, content)?;
-    set_executable_permissions(&script_path)?;
+    if kind != ScriptKind::Workflow {
+        set_executable_permissions(&script_path)?;
+    }
 
     println!("Created {}", script_path.display());
     Ok(())
@@ -88,9 +116,29 @@ fn build_template(script_id: &str, kind: ScriptKind) -> String {
         ScriptKind::Bash => build_bash_template(script_id),
         ScriptKind::PowerShell => build_powershell_template(script_id),
         ScriptKind::Python => build_python_template(script_id),
+        ScriptKind::Workflow => build_workflow_template(script_id.trim_end_matches("_workflow")),
+        ScriptKind::Executable | ScriptKind::Make | ScriptKind::Just => {
+            unreachable!("init does not write executables or task targets")
+        }
+        ScriptKind::Custom => unreachable!("custom scripts use build_custom_template"),
     }
 }
 
+/// The parser for the templates' `target` field, as `omakure codegen args` writes it.
+fn template_args(kind: ScriptKind) -> String {
+    let field = Field {
+        name: "target".to_string(),
+        prompt: Some("Target (optional)".to_string()),
+        kind: "string".to_string(),
+        order: 1,
+        required: Some(false),
+        default: None,
+        choices: None,
+        arg: Some("--target".to_string()),
+    };
+    codegen::args_parser(&[field], kind).unwrap_or_default()
+}
+
 fn build_bash_template(script_id: &str) -> String {
     format!(
         r#"#!/usr/bin/env bash
@@ -116,10 +164,11 @@ set -euo pipefail
 # OMAKURE_SCHEMA_END
 
 
-# 2) Defaults
-TARGET=""
+# 2) Args
+# OMAKURE_ARGS_START
+{args}# OMAKURE_ARGS_END
 
-# 3) Args + prompts
+# 3) Prompts
 prompt_if_empty() {{
   local var_name="$1"
   local label="$2"
@@ -130,25 +179,13 @@ prompt_if_empty() {{
   fi
 }}
 
-while [[ $# -gt 0 ]]; do
-  case "$1" in
-    --target)
-      TARGET="${{2:-}}"
-      shift 2
-      ;;
-    *)
-      echo "Unknown arg: $1" >&2
-      exit 1
-      ;;
-  esac
-done
-
 prompt_if_empty TARGET "Target (optional)"
 
 # 4) Main
 echo "TODO: implement {script_id}"
 "#,
-        script_id = script_id
+        script_id = script_id,
+        args = template_args(ScriptKind::Bash)
     )
 }
 
@@ -174,19 +211,8 @@ fn build_powershell_template(script_id: &str) -> String {
 # }}
 # OMAKURE_SCHEMA_END
 
-$Target = ""
-for ($i = 0; $i -lt $args.Length; $i++) {{
-  switch ($args[$i]) {{
-    "--target" {{
-      $Target = $args[$i + 1]
-      $i++
-    }}
-    default {{
-      Write-Error "Unknown arg: $($args[$i])"
-      exit 1
-    }}
-  }}
-}}
+# OMAKURE_ARGS_START
+{args}# OMAKURE_ARGS_END
 
 if (-not $Target) {{
   $Target = Read-Host "Target (optional)"
@@ -194,14 +220,14 @@ if (-not $Target) {{
 
 Write-Output "TODO: implement {script_id}"
 "#,
-        script_id = script_id
+        script_id = script_id,
+        args = template_args(ScriptKind::PowerShell)
     )
 }
 
 fn build_python_template(script_id: &str) -> String {
     format!(
         r#"#!/usr/bin/env python3
-import argparse
 
 # OMAKURE_SCHEMA_START
 # {{
@@ -221,13 +247,314 @@ import argparse
 # }}
 # OMAKURE_SCHEMA_END
 
-parser = argparse.ArgumentParser()
-parser.add_argument("--target", default="")
-args = parser.parse_args()
+# OMAKURE_ARGS_START
+{args}# OMAKURE_ARGS_END
+
 target = args.target or input("Target (optional): ")
 
 print(f"TODO: implement {script_id}")
+"#,
+        script_id = script_id,
+        args = template_args(ScriptKind::Python)
+    )
+}
+
+/// Only the schema block, commented with `comment`: omakure knows nothing
+/// else about the language of a `[runner.extensions]` script.
+fn build_custom_template(script_id: &str, comment: &str) -> String {
+    let block = format!(
+        r#"OMAKURE_SCHEMA_START
+{{
+  "Name": "{script_id}",
+  "Description": "Describe what this script does.",
+  "Tags": [],
+  "Fields": [
+    {{
+      "Name": "target",
+      "Prompt": "Target (optional)",
+      "Type": "string",
+      "Order": 1,
+      "Required": false,
+      "Arg": "--target"
+    }}
+  ]
+}}
+OMAKURE_SCHEMA_END"#,
+        script_id = script_id
+    );
+    let mut template: String = block
+        .lines()
+        .map(|line| format!("{} {}\n", comment, line))
+        .collect();
+    template.push_str(&format!("\n{} TODO: implement {}\n", comment, script_id));
+    template
+}
+
+fn build_workflow_template(script_id: &str) -> String {
+    format!(
+        r#"{{
+  "Type": "workflow_dispatch",
+  "Name": "{script_id}",
+  "Description": "Describe what this workflow does.",
+  "Tags": ["ci"],
+  "Workflow": {{
+    "Repo": "owner/repo",
+    "Workflow": "deploy.yml",
+    "Ref": "main"
+  }},
+  "Fields": [
+    {{
+      "Name": "target",
+      "Prompt": "Target (workflow input)",
+      "Type": "string",
+      "Order": 1,
+      "Required": false
+    }}
+  ]
+}}
 "#,
         script_id = script_id
     )
 }
+
+/// A schema field generated for an OpenAPI parameter, plus its bash variable.
+struct ApiField {
+    name: String,
+    variable: String,
+    prompt: String,
+    kind: String,
+    required: bool,
+    default: Option<String>,
+    choices: Option<Vec<String>>,
+    /// Parameter name and location in the request; `None` for the base URL.
+    param: Option<(String, ParamLocation)>,
+}
+
+impl ApiField {
+    fn arg(&self) -> String {
+        format!("--{}", self.name.replace('_', "-"))
+    }
+}
+
+fn api_fields(operation: &Operation) -> Vec<ApiField> {
+    let mut fields = vec![ApiField {
+        name: "base_url".to_string(),
+        variable: "BASE_URL".to_string(),
+        prompt: "Base URL".to_string(),
+        kind: "string".to_string(),
+        required: true,
+        default: operation.server.clone(),
+        choices: None,
+        param: None,
+    }];
+    for param in &operation.params {
+        let mut name = normalize_script_id(Path::new(&param.name));
+        if name.is_empty() || fields.iter().any(|field| field.name == name) {
+            let prefix = match param.location {
+                ParamLocation::Path => "path",
+                ParamLocation::Query => "query",
+                ParamLocation::Header => "header",
+                ParamLocation::Body => "body",
+            };
+            name = format!("{}_{}", prefix, name).trim_matches('_').to_string();
+        }
+        fields.push(ApiField {
+            variable: name.to_ascii_uppercase(),
+            prompt: param
+                .description
+                .clone()
+                .unwrap_or_else(|| param.name.clone()),
+            name,
+            kind: param.kind.clone(),
+            required: param.required,
+            default: param.default.clone(),
+            choices: param.choices.clone(),
+            param: Some((param.name.clone(), param.location)),
+        });
+    }
+    fields
+}
+
+fn schema_block(script_id: &str, description: &str, fields: &[ApiField]) -> String {
+    let quote = |value: &str| serde_json::to_string(value).unwrap_or_default();
+    let mut lines = vec![
+        "{".to_string(),
+        format!("  \"Name\": {},", quote(script_id)),
+        format!("  \"Description\": {},", quote(description)),
+        "  \"Tags\": [\"api\"],".to_string(),
+        "  \"Fields\": [".to_string(),
+    ];
+    for (index, field) in fields.iter().enumerate() {
+        let mut entries = vec![
+            format!("\"Name\": {}", quote(&field.name)),
+            format!("\"Prompt\": {}", quote(&field.prompt)),
+            format!("\"Type\": {}", quote(&field.kind)),
+            format!("\"Order\": {}", index + 1),
+            format!("\"Required\": {}", field.required),
+        ];
+        if let Some(default) = &field.default {
+            entries.push(format!("\"Default\": {}", quote(default)));
+        }
+        if let Some(choices) = &field.choices {
+            let choices: Vec<String> = choices.iter().map(|choice| quote(choice)).collect();
+            entries.push(format!("\"Choices\": [{}]", choices.join(", ")));
+        }
+        entries.push(format!("\"Arg\": {}", quote(&field.arg())));
+        lines.push("    {".to_string());
+        let count = entries.len();
+        for (position, entry) in entries.into_iter().enumerate() {
+            let comma = if position + 1 < count { "," } else { "" };
+            lines.push(format!("      {}{}", entry, comma));
+        }
+        let comma = if index + 1 < fields.len() { "," } else { "" };
+        lines.push(format!("    }}{}", comma));
+    }
+    lines.push("  ]".to_string());
+    lines.push("}".to_string());
+    lines.iter().map(|line| format!("# {}\n", line)).collect()
+}
+
+fn build_openapi_template(script_id: &str, operation: &Operation) -> String {
+    let fields = api_fields(operation);
+    let with_location = |location: ParamLocation| {
+        fields.iter().filter_map(move |field| match &field.param {
+            Some((name, param_location)) if *param_location == location => Some((name, field)),
+            _ => None,
+        })
+    };
+    let description = operation
+        .summary
+        .clone()
+        .unwrap_or_else(|| format!("Calls {} {}.", operation.method, operation.path));
+
+    let mut out = String::from("#!/usr/bin/env bash\nset -euo pipefail\n\n");
+    out.push_str(&format!(
+        "# Generated from OpenAPI operation {}: {} {}\n\n",
+        operation.id, operation.method, operation.path
+    ));
+    out.push_str("# 1) Schema for the TUI\n# OMAKURE_SCHEMA_START\n");
+    out.push_str(&schema_block(script_id, &description, &fields));
+    out.push_str("# OMAKURE_SCHEMA_END\n\n\n# 2) Defaults\n");
+    for field in &fields {
+        out.push_str(&format!(
+            "{}={}\n",
+            field.variable,
+            bash_quote(field.default.as_deref().unwrap_or(""))
+        ));
+    }
+
+    out.push_str(
+        r#"
+# 3) Args + prompts
+prompt_if_empty() {
+  local var_name="$1"
+  local label="$2"
+  local value="${!var_name:-}"
+  if [[ -z "${value}" ]]; then
+    read -r -p "${label}: " value
+    printf -v "${var_name}" '%s' "${value}"
+  fi
+}
+
+urlencode() {
+  local LC_ALL=C
+  local value="$1" out="" ch i
+  for ((i = 0; i < ${#value}; i++)); do
+    ch="${value:i:1}"
+    case "${ch}" in
+      [a-zA-Z0-9.~_-]) out+="${ch}" ;;
+      *) printf -v ch '%%%02X' "'${ch}"; out+="${ch}" ;;
+    esac
+  done
+  printf '%s' "${out}"
+}
+
+json_escape() {
+  local value="$1"
+  value="${value//\\/\\\\}"
+  value="${value//\"/\\\"}"
+  printf '%s' "${value}"
+}
+
+while [[ $# -gt 0 ]]; do
+  case "$1" in
+"#,
+    );
+    for field in &fields {
+        out.push_str(&format!(
+            "    {})\n      {}=\"${{2:-}}\"\n      shift 2\n      ;;\n",
+            field.arg(),
+            field.variable
+        ));
+    }
+    out.push_str(
+        r#"    *)
+      echo "Unknown arg: $1" >&2
+      exit 1
+      ;;
+  esac
+done
+
+"#,
+    );
+    for field in fields.iter().filter(|field| field.required) {
+        out.push_str(&format!(
+            "prompt_if_empty {} {}\n",
+            field.variable,
+            bash_quote(&field.prompt)
+        ));
+    }
+
+    out.push_str("\n# 4) Request\n");
+    let mut path = operation.path.clone();
+    for (name, field) in with_location(ParamLocation::Path) {
+        path = path.replace(
+            &format!("{{{}}}", name),
+            &format!("$(urlencode \"${{{}}}\")", field.variable),
+        );
+    }
+    out.push_str(&format!("URL=\"${{BASE_URL%/}}{}\"\n", path));
+    out.push_str("QUERY=\"\"\n");
+    for (name, field) in with_location(ParamLocation::Query) {
+        out.push_str(&format!(
+            "if [[ -n \"${{{var}}}\" ]]; then QUERY+=\"&{name}=$(urlencode \"${{{var}}}\")\"; fi\n",
+            var = field.variable,
+            name = name
+        ));
+    }
+    out.push_str("if [[ -n \"${QUERY}\" ]]; then URL+=\"?${QUERY#&}\"; fi\n\n");
+
+    out.push_str(&format!(
+        "CURL_ARGS=(--silent --show-error --fail -X {})\n",
+        operation.method
+    ));
+    for (name, field) in with_location(ParamLocation::Header) {
+        out.push_str(&format!(
+            "if [[ -n \"${{{var}}}\" ]]; then CURL_ARGS+=(-H \"{name}: ${{{var}}}\"); fi\n",
+            var = field.variable,
+            name = name
+        ));
+    }
+
+    let body: Vec<_> = with_location(ParamLocation::Body).collect();
+    if !body.is_empty() {
+        out.push_str("\nBODY=\"\"\n");
+        for (name, field) in body {
+            let value = if field.kind == "string" {
+                format!("\\\"$(json_escape \"${{{}}}\")\\\"", field.variable)
+            } else {
+                format!("${{{}}}", field.variable)
+            };
+            out.push_str(&format!(
+                "if [[ -n \"${{{var}}}\" ]]; then BODY+=\",\\\"{name}\\\":{value}\"; fi\n",
+                var = field.variable,
+                name = name,
+                value = value
+            ));
+        }
+        out.push_str("CURL_ARGS+=(-H \"Content-Type: application/json\" --data \"{${BODY#,}}\")\n");
+    }
+
+    out.push_str("\ncurl \"${CURL_ARGS[@]}\" \"${URL}\"\n");
+    out
+}
@@ -0,0 +1,127 @@
+use crate::adapters::system_checks::ensure_git_installed;
+use crate::app_dirs::AppDirs;
+use crate::cli::args::InitWorkspaceArgs;
+use crate::util::TempDirGuard;
+use crate::workspace::Workspace;
+use crate::workspace_template::{self, Variable, WORKSPACE_VARIABLE};
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Folder of the global config dir holding named templates.
+const TEMPLATES_DIR: &str = "templates";
+
+pub fn run(scripts_dir: PathBuf, args: InitWorkspaceArgs) -> Result<(), Box<dyn Error>> {
+    let target = args.dir.unwrap_or(scripts_dir);
+    if target.exists() && fs::read_dir(&target)?.next().is_some() {
+        return Err(format!("{} already exists and is not empty", target.display()).into());
+    }
+
+    let clone_dir = env::temp_dir().join(format!("omakure-template-{}", std::process::id()));
+    let _clone_guard = TempDirGuard::new(clone_dir.clone());
+    let template = match local_template(&args.template) {
+        Some(dir) => dir,
+        None => {
+            clone(&args.template, &clone_dir)?;
+            clone_dir
+        }
+    };
+
+    let manifest = workspace_template::load_manifest(&template)?;
+    if let Some(description) = &manifest.description {
+        println!("{}", description.trim());
+    }
+    let mut values = parse_vars(&args.vars)?;
+    let name = target
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    values.entry(WORKSPACE_VARIABLE.to_string()).or_insert(name);
+    for variable in &manifest.variables {
+        if !values.contains_key(&variable.name) {
+            let value = ask(variable)?;
+            values.insert(variable.name.clone(), value);
+        }
+    }
+
+    let written = workspace_template::instantiate(&template, &target, &values)?;
+    Workspace::new(target.clone()).ensure_layout()?;
+    println!(
+        "Created workspace {} ({} files)",
+        target.display(),
+        written.len()
+    );
+    Ok(())
+}
+
+/// A folder path, or the name of a folder in the global templates dir.
+fn local_template(template: &str) -> Option<PathBuf> {
+    let path = Path::new(template);
+    if path.is_dir() {
+        return Some(path.to_path_buf());
+    }
+    let named = AppDirs::resolve()?
+        .config
+        .join(TEMPLATES_DIR)
+        .join(template);
+    named.is_dir().then_some(named)
+}
+
+fn clone(url: &str, dir: &Path) -> Result<(), Box<dyn Error>> {
+    ensure_git_installed()?;
+    let status = Command::new("git")
+        .args(["clone", "--depth", "1", "--quiet"])
+        .arg(url)
+        .arg(dir)
+        .status()?;
+    if !status.success() {
+        return Err(format!("git clone {} failed", url).into());
+    }
+    Ok(())
+}
+
+fn parse_vars(vars: &[String]) -> Result<BTreeMap<String, String>, Box<dyn Error>> {
+    let mut values = BTreeMap::new();
+    for raw in vars {
+        let (name, value) = raw
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid --var '{}', expected NAME=VALUE", raw))?;
+        values.insert(name.trim().to_string(), value.to_string());
+    }
+    Ok(values)
+}
+
+/// Asks on the terminal; without one, the default is used.
+fn ask(variable: &Variable) -> Result<String, Box<dyn Error>> {
+    let missing = || {
+        format!(
+            "No value for {}; pass --var {}=VALUE",
+            variable.name, variable.name
+        )
+    };
+    if !io::stdin().is_terminal() {
+        return variable.default.clone().ok_or_else(|| missing().into());
+    }
+    let label = match &variable.default {
+        Some(default) => format!("{} [{}]", variable.label(), default),
+        None => variable.label().to_string(),
+    };
+    let stdin = io::stdin();
+    loop {
+        eprint!("{}: ", label);
+        io::stderr().flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            return Err(missing().into());
+        }
+        match (line.trim(), &variable.default) {
+            ("", Some(default)) => return Ok(default.clone()),
+            ("", None) => eprintln!("  A value is required"),
+            (value, _) => return Ok(value.to_string()),
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use crate::cli::args::KubeRunArgs;
+use crate::runtime::{powershell_program, script_kind, ScriptKind};
+use serde_json::{json, Map, Value};
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const MOUNT_DIR: &str = "/omakure";
+const MANAGED_BY_LABEL: &str = "app.kubernetes.io/managed-by";
+/// Finished Jobs left behind (e.g. after a failed or killed run) are removed
+/// by the cluster after this long; the ConfigMap and Secret they own go with them.
+const JOB_TTL_SECONDS: u64 = 3600;
+const POD_RUNNING_TIMEOUT: &str = "5m";
+const EXIT_CODE_ATTEMPTS: u32 = 30;
+const EXIT_CODE_INTERVAL: Duration = Duration::from_secs(1);
+
+pub fn run(args: KubeRunArgs) -> Result<(), Box<dyn Error>> {
+    let (interpreter, default_image): (Vec<&str>, &str) = match script_kind(&args.script) {
+        Some(ScriptKind::Bash) => (vec!["bash"], "bash:5"),
+        Some(ScriptKind::Python) => (vec!["python3"], "python:3-slim"),
+        Some(ScriptKind::PowerShell) => (
+            vec![powershell_program(), "-NoProfile", "-File"],
+            "mcr.microsoft.com/powershell:latest",
+        ),
+        _ => return Err("This script type cannot run on the kubernetes backend".into()),
+    };
+    let file_name = args
+        .script
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or("Script path has no file name")?
+        .to_string();
+    let contents = fs::read_to_string(&args.script)?;
+    let image = args
+        .image
+        .clone()
+        .unwrap_or_else(|| default_image.to_string());
+    let name = job_name(&args.script);
+    let env: Map<String, Value> = args
+        .env
+        .iter()
+        .filter_map(|key| {
+            env::var(key)
+                .ok()
+                .map(|value| (key.clone(), Value::String(value)))
+        })
+        .collect();
+
+    let mut command: Vec<String> = interpreter.iter().map(|part| part.to_string()).collect();
+    command.push(format!("{}/{}", MOUNT_DIR, file_name));
+
+    let kubectl = Kubectl {
+        context: args.context.clone(),
+        namespace: args.namespace.clone(),
+    };
+    // The Job comes first so the ConfigMap and Secret can name it as their
+    // owner; its pod waits for them to exist.
+    kubectl.apply(&job_manifest(&name, &image, command, &args.args))?;
+    let owned = kubectl
+        .uid(&format!("job/{}", name))
+        .and_then(|uid| kubectl.apply(&data_manifest(&name, &uid, &file_name, &contents, env)));
+    if let Err(err) = owned {
+        kubectl.delete(&name);
+        return Err(err);
+    }
+    eprintln!(
+        "Started Job {} in namespace {} with image {}",
+        name,
+        args.namespace.as_deref().unwrap_or("(current)"),
+        image
+    );
+
+    let code = match kubectl
+        .follow_logs(&name)
+        .and_then(|_| kubectl.exit_code(&name))
+    {
+        Ok(code) => code,
+        Err(err) => {
+            return Err(format!(
+                "{} (Job {} was left in place; inspect it with `kubectl describe job/{}`)",
+                err, name, name
+            )
+            .into())
+        }
+    };
+    kubectl.delete(&name);
+    if code != 0 {
+        std::process::exit(code);
+    }
+    Ok(())
+}
+
+/// `omakure-<script>-<suffix>`, a valid DNS-1123 name unique per run.
+fn job_name(script: &Path) -> String {
+    let stem = script
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("script");
+    let mut slug = String::new();
+    for ch in stem.chars().map(|ch| ch.to_ascii_lowercase()) {
+        if ch.is_ascii_alphanumeric() {
+            slug.push(ch);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug.trim_matches('-').chars().take(40).collect();
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_millis())
+        .unwrap_or_default();
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        format!("omakure-{:x}", millis)
+    } else {
+        format!("omakure-{}-{:x}", slug, millis)
+    }
+}
+
+fn metadata(name: &str) -> Value {
+    json!({
+        "name": name,
+        "labels": { MANAGED_BY_LABEL: "omakure" },
+    })
+}
+
+/// The Job running the script from the ConfigMap, with the Secret's env.
+fn job_manifest(name: &str, image: &str, command: Vec<String>, args: &[String]) -> Value {
+    json!({
+        "apiVersion": "batch/v1",
+        "kind": "Job",
+        "metadata": metadata(name),
+        "spec": {
+            "backoffLimit": 0,
+            "ttlSecondsAfterFinished": JOB_TTL_SECONDS,
+            "template": {
+                "metadata": { "labels": { MANAGED_BY_LABEL: "omakure" } },
+                "spec": {
+                    "restartPolicy": "Never",
+                    "volumes": [{
+                        "name": "script",
+                        "configMap": { "name": name, "defaultMode": 0o755 },
+                    }],
+                    "containers": [{
+                        "name": "script",
+                        "image": image,
+                        "command": command,
+                        "args": args,
+                        "envFrom": [{ "secretRef": { "name": name } }],
+                        "volumeMounts": [{
+                            "name": "script",
+                            "mountPath": MOUNT_DIR,
+                            "readOnly": true,
+                        }],
+                    }],
+                },
+            },
+        },
+    })
+}
+
+/// ConfigMap with the script and Secret with the forwarded env, both owned by
+/// the Job with `job_uid`, so the cluster deletes them along with it.
+fn data_manifest(
+    name: &str,
+    job_uid: &str,
+    file_name: &str,
+    contents: &str,
+    env: Map<String, Value>,
+) -> Value {
+    let mut metadata = metadata(name);
+    metadata["ownerReferences"] = json!([{
+        "apiVersion": "batch/v1",
+        "kind": "Job",
+        "name": name,
+        "uid": job_uid,
+    }]);
+    json!({
+        "apiVersion": "v1",
+        "kind": "List",
+        "items": [
+            {
+                "apiVersion": "v1",
+                "kind": "ConfigMap",
+                "metadata": metadata,
+                "data": { file_name: contents },
+            },
+            {
+                "apiVersion": "v1",
+                "kind": "Secret",
+                "metadata": metadata,
+                "type": "Opaque",
+                "stringData": env,
+            },
+        ],
+    })
+}
+
+struct Kubectl {
+    context: Option<String>,
+    namespace: Option<String>,
+}
+
+impl Kubectl {
+    fn command(&self) -> Command {
+        let mut command = Command::new("kubectl");
+        if let Some(context) = &self.context {
+            command.arg("--context").arg(context);
+        }
+        if let Some(namespace) = &self.namespace {
+            command.arg("--namespace").arg(namespace);
+        }
+        command
+    }
+
+    fn apply(&self, manifest: &Value) -> Result<(), Box<dyn Error>> {
+        let mut child = self
+            .command()
+            .args(["apply", "-f", "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(manifest.to_string().as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(format!(
+                "kubectl apply failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    /// The uid of `object`, e.g. `job/<name>`.
+    fn uid(&self, object: &str) -> Result<String, Box<dyn Error>> {
+        let output = self
+            .command()
+            .args(["get", object, "--output", "jsonpath={.metadata.uid}"])
+            .stderr(Stdio::piped())
+            .output()?;
+        let uid = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if !output.status.success() || uid.is_empty() {
+            return Err(format!(
+                "kubectl get {} failed: {}",
+                object,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+            .into());
+        }
+        Ok(uid)
+    }
+
+    /// Streams the pod's logs into our stdout until the container exits.
+    fn follow_logs(&self, name: &str) -> Result<(), Box<dyn Error>> {
+        let status = self
+            .command()
+            .args(["logs", "--follow"])
+            .arg(format!("--pod-running-timeout={}", POD_RUNNING_TIMEOUT))
+            .arg(format!("job/{}", name))
+            .status()?;
+        if !status.success() {
+            return Err("kubectl logs failed".into());
+        }
+        Ok(())
+    }
+
+    /// Exit code of the Job's container, waiting briefly for the pod status to settle.
+    fn exit_code(&self, name: &str) -> Result<i32, Box<dyn Error>> {
+        for _ in 0..EXIT_CODE_ATTEMPTS {
+            let output = self
+                .command()
+                .args(["get", "pods", "--selector"])
+                .arg(format!("job-name={}", name))
+                .args([
+                    "--output",
+                    "jsonpath={.items[0].status.containerStatuses[0].state.terminated.exitCode}",
+                ])
+                .output()?;
+            let text = String::from_utf8_lossy(&output.stdout);
+            if let Ok(code) = text.trim().parse() {
+                return Ok(code);
+            }
+            thread::sleep(EXIT_CODE_INTERVAL);
+        }
+        Err("The Job's pod did not report an exit code".into())
+    }
+
+    /// Deletes the Job and, in case the owner references did not take, the
+    /// ConfigMap and Secret.
+    fn delete(&self, name: &str) {
+        let _ = self
+            .command()
+            .args(["delete", "job,configmap,secret", name])
+            .args(["--ignore-not-found", "--wait=false"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+}
@@ -1,14 +1,41 @@
+use crate::adapters::remote_repository::{IndexedScript, RemoteScriptRepository, ScriptIndex};
 use crate::adapters::workspace_repository::FsWorkspaceRepository;
+use crate::cli::args::ScriptsArgs;
+use crate::cli::schema::strip_nulls;
 use crate::ports::ScriptRepository;
+use crate::status::WARN_SYMBOL;
 use std::error::Error;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-pub fn run(scripts_dir: PathBuf) -> Result<(), Box<dyn Error>> {
-    let repo = FsWorkspaceRepository::new(scripts_dir.clone());
+pub fn run(scripts_dir: PathBuf, args: ScriptsArgs) -> Result<(), Box<dyn Error>> {
+    let (repo, root, label): (Box<dyn ScriptRepository>, PathBuf, String) = match &args.remote {
+        Some(url) => {
+            let repo = RemoteScriptRepository::connect(url, PathBuf::from("remote"))
+                .map_err(|err| err.to_string())?;
+            let root = repo.root().to_path_buf();
+            (Box::new(repo), root, url.clone())
+        }
+        None => (
+            Box::new(FsWorkspaceRepository::new(scripts_dir.clone())),
+            scripts_dir.clone(),
+            scripts_dir.display().to_string(),
+        ),
+    };
     let mut scripts = repo.list_scripts_recursive()?;
     scripts.sort();
 
-    println!("Scripts folder: {}", scripts_dir.display());
+    if args.index {
+        return print_index(repo.as_ref(), &root, &scripts);
+    }
+
+    if args.plain {
+        for script in scripts {
+            println!("{}", relative_key(&script, &root));
+        }
+        return Ok(());
+    }
+
+    println!("Scripts folder: {}", label);
     if scripts.is_empty() {
         println!("(no scripts found)");
         return Ok(());
@@ -16,7 +43,7 @@ pub fn run(scripts_dir: PathBuf) -> Result<(), Box<dyn Error>> {
 
     for script in scripts {
         let display_path = script
-            .strip_prefix(&scripts_dir)
+            .strip_prefix(&root)
             .unwrap_or(&script)
             .to_string_lossy();
         println!(" - {}", display_path);
@@ -24,3 +51,40 @@ pub fn run(scripts_dir: PathBuf) -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+/// Writes every script with its schema as JSON. Scripts whose schema does not
+/// parse are listed without one, with a warning on stderr.
+fn print_index(
+    repo: &dyn ScriptRepository,
+    root: &Path,
+    scripts: &[PathBuf],
+) -> Result<(), Box<dyn Error>> {
+    let scripts = scripts
+        .iter()
+        .map(|script| {
+            let path = relative_key(script, root);
+            let schema = match repo.read_schema(script) {
+                Ok(schema) => Some(schema),
+                Err(err) => {
+                    eprintln!("{} {}: {}", WARN_SYMBOL, path, err);
+                    None
+                }
+            };
+            IndexedScript {
+                path,
+                schema,
+                schema_url: None,
+            }
+        })
+        .collect();
+    let mut value = serde_json::to_value(ScriptIndex::new(scripts))?;
+    strip_nulls(&mut value);
+    println!("{}", serde_json::to_string_pretty(&value)?);
+    Ok(())
+}
+
+/// Path relative to `root` with `/` separators on every platform.
+fn relative_key(script: &Path, root: &Path) -> String {
+    let relative = script.strip_prefix(root).unwrap_or(script);
+    relative.to_string_lossy().replace('\\', "/")
+}
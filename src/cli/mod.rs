@@ -1,10 +1,33 @@
 pub mod args;
+pub mod browse;
+pub mod codegen;
+pub mod completion;
 pub mod config;
+pub mod digest;
+pub mod dispatch;
+pub mod docs;
 pub mod doctor;
+pub mod envs;
+pub mod gc;
+pub mod history;
 pub mod init;
+pub mod init_workspace;
+pub mod kubernetes;
 pub mod list;
 pub mod omaken;
+pub mod pipeline;
+pub mod report;
 pub mod run;
+pub mod run_all;
+pub mod schema;
+pub mod snapshot;
+pub mod ssh;
+pub mod stats;
+pub mod sync_scripts;
 pub mod theme;
+pub mod tutorial;
 pub mod uninstall;
 pub mod update;
+pub mod validate;
+pub mod verify_receipt;
+pub mod workspaces;
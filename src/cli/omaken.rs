@@ -1,11 +1,23 @@
-use crate::adapters::system_checks::ensure_git_installed;
+use crate::adapters::system_checks::{ensure_git_installed, ensure_runtime_installed};
+use crate::app_meta::APP_VERSION;
+use crate::archive;
 use crate::cli::args::OmakenInstallArgs;
+use crate::http;
+use crate::omaken_lock::{self, FlavorSource, LockedFlavor};
+use crate::omaken_manifest::{self, MANIFEST_FILE};
+use crate::receipt::sha256_hex;
+use crate::status::WARN_SYMBOL;
+use crate::util::TempDirGuard;
 use crate::workspace::Workspace;
+use std::env;
 use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+/// Archive types `install` extracts.
+const ARCHIVE_EXTENSIONS: [&str; 4] = [".tar.gz", ".tgz", ".tar", ".zip"];
+
 pub fn run_list(workspace_root: PathBuf) -> Result<(), Box<dyn Error>> {
     let workspace = Workspace::new(workspace_root);
     workspace.ensure_layout()?;
@@ -18,10 +30,19 @@ pub fn run_install(
 ) -> Result<(), Box<dyn Error>> {
     let workspace = Workspace::new(workspace_root);
     workspace.ensure_layout()?;
-    install_omaken(&workspace, &options.url, options.name.as_deref())
+    match options.url {
+        Some(url) => install_omaken(
+            &workspace,
+            &url,
+            options.name.as_deref(),
+            options.reference.as_deref(),
+        ),
+        None => install_locked(&workspace),
+    }
 }
 
-fn list_omaken(workspace: &Workspace) -> Result<(), Box<dyn Error>> {
+/// Folder names under `.omaken/`, sorted.
+pub(crate) fn flavor_names(workspace: &Workspace) -> std::io::Result<Vec<String>> {
     let mut flavors = Vec::new();
     for entry in fs::read_dir(workspace.omaken_dir())? {
         let entry = entry?;
@@ -33,23 +54,46 @@ fn list_omaken(workspace: &Workspace) -> Result<(), Box<dyn Error>> {
         }
     }
     flavors.sort();
+    Ok(flavors)
+}
+
+fn list_omaken(workspace: &Workspace) -> Result<(), Box<dyn Error>> {
+    let flavors = flavor_names(workspace)?;
     if flavors.is_empty() {
         println!("No Omaken flavors installed.");
     } else {
+        let lockfile = omaken_lock::load(&workspace.omaken_lock_path())?;
         println!("Omaken flavors:");
         for name in flavors {
-            println!(" - {}", name);
+            let mut line = format!(" - {}", name);
+            if let Some(locked) = lockfile.get(&name) {
+                line.push_str(&format!(" ({})", pin_label(locked)));
+            }
+            match omaken_manifest::load(&workspace.omaken_dir().join(&name)) {
+                Ok(Some(manifest)) => {
+                    if let Some(summary) = manifest.summary() {
+                        line.push_str(&format!(" - {}", summary));
+                    }
+                }
+                Ok(None) => {}
+                Err(err) => line.push_str(&format!(" {} {}", WARN_SYMBOL, err)),
+            }
+            println!("{}", line);
         }
     }
     Ok(())
 }
 
-fn install_omaken(
+pub(crate) fn install_omaken(
     workspace: &Workspace,
     url: &str,
     override_name: Option<&str>,
+    reference: Option<&str>,
 ) -> Result<(), Box<dyn Error>> {
-    ensure_git_installed()?;
+    let source = source_kind(url);
+    if reference.is_some() && source != FlavorSource::Git {
+        return Err("--ref only applies to git repositories".into());
+    }
     let name = override_name
         .map(|name| name.to_string())
         .unwrap_or_else(|| infer_name_from_url(url));
@@ -61,23 +105,282 @@ fn install_omaken(
         return Err(format!("Omaken already exists: {}", target_dir.display()).into());
     }
 
-    let status = Command::new("git")
-        .arg("clone")
-        .arg("--depth")
-        .arg("1")
-        .arg(url)
-        .arg(&target_dir)
-        .status()?;
+    let mut locked = LockedFlavor {
+        name,
+        source,
+        url: url.to_string(),
+        reference: reference.map(str::to_string),
+        commit: None,
+        sha256: None,
+    };
+    let installed = match source {
+        FlavorSource::Git => clone_flavor(url, reference, &target_dir).and_then(|()| {
+            locked.commit = Some(git(&target_dir, &["rev-parse", "HEAD"])?);
+            Ok(())
+        }),
+        FlavorSource::Archive => {
+            install_archive(url, &target_dir).map(|sha256| locked.sha256 = Some(sha256))
+        }
+        FlavorSource::Path => {
+            // Recorded absolute, so the lockfile works from any folder.
+            locked.url = fs::canonicalize(url)?.display().to_string();
+            copy_flavor(Path::new(url), &target_dir)
+        }
+    };
+    if let Err(err) = installed.and_then(|()| validate_flavor(&target_dir)) {
+        // Leave nothing behind, so a fixed flavor installs under the same name.
+        let _ = fs::remove_dir_all(&target_dir);
+        return Err(err);
+    }
+
+    let lock_path = workspace.omaken_lock_path();
+    let mut lockfile = omaken_lock::load(&lock_path)?;
+    println!(
+        "Installed Omaken flavor to {} ({})",
+        target_dir.display(),
+        pin_label(&locked)
+    );
+    lockfile.pin(locked);
+    omaken_lock::save(&lock_path, &lockfile)?;
+    Ok(())
+}
+
+/// Brings every flavor in `omaken.lock` back: git flavors to their pinned
+/// commit, missing archives checked against their digest, missing folders
+/// copied again.
+fn install_locked(workspace: &Workspace) -> Result<(), Box<dyn Error>> {
+    let lock_path = workspace.omaken_lock_path();
+    let lockfile = omaken_lock::load(&lock_path)?;
+    if lockfile.flavors.is_empty() {
+        return Err(format!("No flavors pinned in {}", lock_path.display()).into());
+    }
+    for flavor in &lockfile.flavors {
+        let target_dir = workspace.omaken_dir().join(&flavor.name);
+        match flavor.source {
+            FlavorSource::Git => {
+                let commit = flavor
+                    .commit
+                    .as_deref()
+                    .ok_or_else(|| format!("{} has no commit in omaken.lock", flavor.name))?;
+                if !checkout_commit(workspace, flavor, commit, &target_dir)? {
+                    println!("{} is at {}", flavor.name, pin_label(flavor));
+                    continue;
+                }
+            }
+            _ if target_dir.exists() => {
+                println!("{} is installed ({})", flavor.name, pin_label(flavor));
+                continue;
+            }
+            FlavorSource::Archive => {
+                let sha256 = install_archive(&flavor.url, &target_dir)?;
+                if flavor
+                    .sha256
+                    .as_deref()
+                    .is_some_and(|pinned| pinned != sha256)
+                {
+                    let _ = fs::remove_dir_all(&target_dir);
+                    return Err(format!(
+                        "{} changed since it was locked (sha256 {})",
+                        flavor.url, sha256
+                    )
+                    .into());
+                }
+            }
+            FlavorSource::Path => copy_flavor(Path::new(&flavor.url), &target_dir)?,
+        }
+        validate_flavor(&target_dir)?;
+        println!("Installed {} at {}", flavor.name, pin_label(flavor));
+    }
+    Ok(())
+}
+
+/// Git repository, archive, or local folder, told apart by the path and
+/// extension.
+fn source_kind(url: &str) -> FlavorSource {
+    if Path::new(url).is_dir() {
+        FlavorSource::Path
+    } else if ARCHIVE_EXTENSIONS
+        .iter()
+        .any(|extension| url.to_ascii_lowercase().ends_with(extension))
+    {
+        FlavorSource::Archive
+    } else {
+        FlavorSource::Git
+    }
+}
+
+fn clone_flavor(
+    url: &str,
+    reference: Option<&str>,
+    target_dir: &Path,
+) -> Result<(), Box<dyn Error>> {
+    ensure_git_installed()?;
+    let mut command = Command::new("git");
+    // A tag checks out a detached HEAD; that is expected here.
+    command.args(["-c", "advice.detachedHead=false", "clone", "--depth", "1"]);
+    if let Some(reference) = reference {
+        command.arg("--branch").arg(reference);
+    }
+    let status = command.arg(url).arg(target_dir).status()?;
     if !status.success() {
         return Err("git clone failed".into());
     }
+    Ok(())
+}
+
+/// Checks out `commit` in `target_dir`, fetching the flavor if missing.
+/// Returns whether anything changed.
+fn checkout_commit(
+    workspace: &Workspace,
+    flavor: &LockedFlavor,
+    commit: &str,
+    target_dir: &Path,
+) -> Result<bool, Box<dyn Error>> {
+    ensure_git_installed()?;
+    if target_dir.exists() {
+        if git(target_dir, &["rev-parse", "HEAD"]).ok().as_deref() == Some(commit) {
+            return Ok(false);
+        }
+    } else {
+        git(workspace.omaken_dir(), &["init", "--quiet", &flavor.name])?;
+        git(target_dir, &["remote", "add", "origin", &flavor.url])?;
+    }
+    fetch_commit(target_dir, &flavor.url, commit)?;
+    git(target_dir, &["checkout", "--quiet", "--detach", commit])?;
+    Ok(true)
+}
+
+/// Downloads or reads the archive at `url`, extracts it into `target_dir`,
+/// and returns the archive's SHA-256.
+fn install_archive(url: &str, target_dir: &Path) -> Result<String, Box<dyn Error>> {
+    let temp_dir = env::temp_dir().join(format!("omakure-flavor-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&temp_dir);
+    fs::create_dir_all(&temp_dir)?;
+    let _temp_guard = TempDirGuard::new(temp_dir.clone());
 
-    println!("Installed Omaken flavor to {}", target_dir.display());
+    let file_name = url.rsplit(['/', '\\']).next().unwrap_or("flavor.tar.gz");
+    let archive = temp_dir.join(file_name);
+    if url.starts_with("http://") || url.starts_with("https://") {
+        http::download(url, &archive)?;
+    } else {
+        fs::copy(url, &archive)?;
+    }
+    let sha256 = sha256_hex(&fs::read(&archive)?);
+    let extract_dir = temp_dir.join("flavor");
+    fs::create_dir_all(&extract_dir)?;
+    archive::extract(&archive, &extract_dir)?;
+    copy_flavor(&archive_root(&extract_dir)?, target_dir)?;
+    Ok(sha256)
+}
+
+/// The single folder an archive wraps everything in, as GitHub's do, or the
+/// extraction folder itself.
+fn archive_root(dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let entries: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    match entries.as_slice() {
+        [only] if only.is_dir() => Ok(only.clone()),
+        _ => Ok(dir.to_path_buf()),
+    }
+}
+
+/// Copies a flavor folder, leaving out its `.git`.
+fn copy_flavor(source: &Path, target_dir: &Path) -> Result<(), Box<dyn Error>> {
+    if !source.is_dir() {
+        return Err(format!("Flavor folder not found: {}", source.display()).into());
+    }
+    let mut stack = vec![source.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let relative = dir.strip_prefix(source)?;
+        fs::create_dir_all(target_dir.join(relative))?;
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.file_name().is_some_and(|name| name == ".git") {
+                continue;
+            }
+            if path.is_dir() {
+                stack.push(path);
+            } else {
+                fs::copy(&path, target_dir.join(path.strip_prefix(source)?))?;
+            }
+        }
+    }
     Ok(())
 }
 
+/// Rejects a flavor whose `omaken.toml` is invalid or needs a newer omakure,
+/// and warns about runtimes missing from PATH.
+fn validate_flavor(dir: &Path) -> Result<(), Box<dyn Error>> {
+    let Some(manifest) = omaken_manifest::load(dir)? else {
+        return Ok(());
+    };
+    manifest
+        .check_version(APP_VERSION)
+        .map_err(|err| format!("{} in {}: {}", MANIFEST_FILE, dir.display(), err))?;
+    for runtime in &manifest.runtimes {
+        if let Err(err) = ensure_runtime_installed(runtime) {
+            println!("{} {}", WARN_SYMBOL, err);
+        }
+    }
+    Ok(())
+}
+
+fn fetch_commit(dir: &Path, url: &str, commit: &str) -> Result<(), Box<dyn Error>> {
+    let shallow = ["fetch", "--quiet", "--depth", "1", url, commit];
+    if git(dir, &shallow).is_ok() {
+        return Ok(());
+    }
+    // Some servers refuse to fetch a commit by its SHA; take the whole history.
+    let mut full = vec!["fetch", "--quiet", "--tags", url];
+    if dir.join(".git").join("shallow").exists() {
+        full.push("--unshallow");
+    }
+    git(dir, &full).map(|_| ())
+}
+
+/// Runs git in `dir` and returns its trimmed output.
+fn git(dir: &Path, args: &[&str]) -> Result<String, Box<dyn Error>> {
+    let output = Command::new("git").arg("-C").arg(dir).args(args).output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `v1.2 @ 1a2b3c4` or `1a2b3c4` for git, `archive 1a2b3c4` by digest, or
+/// `local folder`.
+fn pin_label(flavor: &LockedFlavor) -> String {
+    let short = |digest: &str| digest.get(..7).unwrap_or(digest).to_string();
+    match flavor.source {
+        FlavorSource::Git => {
+            let commit = short(flavor.commit.as_deref().unwrap_or_default());
+            match &flavor.reference {
+                Some(reference) => format!("{} @ {}", reference, commit),
+                None => commit,
+            }
+        }
+        FlavorSource::Archive => format!(
+            "archive {}",
+            short(flavor.sha256.as_deref().unwrap_or_default())
+        ),
+        FlavorSource::Path => "local folder".to_string(),
+    }
+}
+
 fn infer_name_from_url(url: &str) -> String {
-    let trimmed = url.trim_end_matches('/');
-    let last = trimmed.rsplit('/').next().unwrap_or(trimmed);
-    last.trim_end_matches(".git").to_string()
+    let trimmed = url.trim_end_matches(['/', '\\']);
+    let last = trimmed.rsplit(['/', '\\']).next().unwrap_or(trimmed);
+    let lower = last.to_ascii_lowercase();
+    [".git"]
+        .iter()
+        .chain(ARCHIVE_EXTENSIONS.iter())
+        .find(|extension| lower.ends_with(*extension))
+        .map_or(last, |extension| &last[..last.len() - extension.len()])
+        .to_string()
 }
@@ -0,0 +1,207 @@
+use crate::cli::args::{PipelineArgs, PipelineCommand, PipelineRunArgs};
+use crate::cli::run::{resolve_script_path, script_service, wait_for_group};
+use crate::config::load_workspace_config;
+use crate::domain::Pipeline;
+use crate::history;
+use crate::pipelines;
+use crate::runtime::path_style;
+use crate::status::{FAIL_SYMBOL, OK_SYMBOL, WARN_SYMBOL};
+use crate::use_cases::{PipelineExecutor, ScriptService, StageStatus};
+use crate::workspace::Workspace;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::PathBuf;
+use std::time::Instant;
+
+#[derive(Serialize)]
+struct PipelineReport {
+    pipeline: String,
+    success: bool,
+    duration_ms: u64,
+    stages: Vec<StageReport>,
+}
+
+#[derive(Serialize)]
+struct StageReport {
+    id: String,
+    /// Workspace-relative path.
+    script: PathBuf,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    outputs: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+pub fn run(scripts_dir: PathBuf, args: PipelineArgs) -> Result<(), Box<dyn Error>> {
+    let workspace = Workspace::new(scripts_dir);
+    workspace.ensure_layout()?;
+    let config = load_workspace_config(workspace.config_path());
+    let service = script_service(&workspace, &config);
+    match args.command.unwrap_or(PipelineCommand::List) {
+        PipelineCommand::List => list(&workspace, &service),
+        PipelineCommand::Run(args) => run_pipeline(&workspace, &service, args),
+    }
+}
+
+fn list(workspace: &Workspace, service: &ScriptService) -> Result<(), Box<dyn Error>> {
+    let files = pipelines::list(&workspace.pipelines_dir());
+    let scripts = service
+        .scripts_with_dependencies()
+        .map_err(|err| err.to_string())?;
+    if files.is_empty() && scripts.is_empty() {
+        println!(
+            "No pipelines. Add one under {}, or give a script a DependsOn.",
+            workspace.pipelines_dir().display()
+        );
+        return Ok(());
+    }
+    for (name, pipeline) in files {
+        match pipeline {
+            Ok(pipeline) => print_pipeline(&name, &pipeline),
+            Err(err) => println!("{} {}  {}", WARN_SYMBOL, name, err),
+        }
+    }
+    for script in scripts {
+        let name = history::script_path(workspace, &script);
+        match service.dependency_pipeline(workspace.root(), &script) {
+            Ok(pipeline) => print_pipeline(&name.display().to_string(), &pipeline),
+            Err(err) => println!("{} {}  {}", WARN_SYMBOL, name.display(), err),
+        }
+    }
+    Ok(())
+}
+
+fn print_pipeline(name: &str, pipeline: &Pipeline) {
+    let stages: Vec<String> = pipeline.stages.iter().map(|stage| stage.id()).collect();
+    println!("{}  {}", name, stages.join(" -> "));
+    if let Some(description) = &pipeline.description {
+        println!("    {}", description);
+    }
+}
+
+/// Runs each stage in turn, recording it in history, and stops at the first
+/// failure. Exits with 1 when a stage failed.
+fn run_pipeline(
+    workspace: &Workspace,
+    service: &ScriptService,
+    args: PipelineRunArgs,
+) -> Result<(), Box<dyn Error>> {
+    let pipeline = load_pipeline(workspace, service, &args.name)?;
+    let mut executor = PipelineExecutor::new(service, workspace.root(), pipeline, path_style)
+        .map_err(|err| err.to_string())?;
+    let started = Instant::now();
+    while let Some(index) = executor.next_stage() {
+        let script = executor.stages()[index].script.clone();
+        let stage_started = Instant::now();
+        let entry = match executor.start_stage(index) {
+            Ok(stage_args) => {
+                let group = service
+                    .load_schema(&script)
+                    .ok()
+                    .and_then(|schema| schema.concurrency_group().map(str::to_string));
+                let _ticket =
+                    group.and_then(|group| wait_for_group(workspace, &group, &script).ok());
+                let log_dir = history::new_run_dir(workspace, &script);
+                let result = service.run_script(&script, &stage_args, Some(&log_dir), &[]);
+                executor.finish_stage(index, &result);
+                match result {
+                    Ok(output) => history::success_entry(workspace, &script, &stage_args, output),
+                    Err(err) => {
+                        let mut entry =
+                            history::error_entry(workspace, &script, &stage_args, err.to_string());
+                        entry.duration_ms = Some(stage_started.elapsed().as_millis() as u64);
+                        entry
+                    }
+                }
+            }
+            Err(err) => history::error_entry(workspace, &script, &[], err),
+        };
+        let _ = history::record_entry(workspace, &entry);
+        let stage = &executor.stages()[index];
+        eprintln!(
+            "{} {} ({})",
+            if stage.status == StageStatus::Passed {
+                OK_SYMBOL
+            } else {
+                FAIL_SYMBOL
+            },
+            stage.id,
+            history::format_duration(entry.duration_ms.unwrap_or_default())
+        );
+        if !args.json {
+            for (name, value) in &stage.outputs {
+                eprintln!("    {} = {}", name, value);
+            }
+        }
+    }
+
+    let report = PipelineReport {
+        pipeline: executor.pipeline().name.clone(),
+        success: !executor.failed(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        stages: executor
+            .stages()
+            .iter()
+            .map(|stage| StageReport {
+                id: stage.id.clone(),
+                script: history::script_path(workspace, &stage.script),
+                status: stage.status.label(),
+                duration_ms: stage.duration_ms,
+                outputs: stage.outputs.clone(),
+                error: stage.error.clone(),
+            })
+            .collect(),
+    };
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_summary(&report);
+    }
+    if !report.success {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// The pipeline file `name`, or else the `DependsOn` pipeline of the script
+/// `name` resolves to.
+fn load_pipeline(
+    workspace: &Workspace,
+    service: &ScriptService,
+    name: &str,
+) -> Result<Pipeline, Box<dyn Error>> {
+    if let Some(pipeline) = pipelines::load(&workspace.pipelines_dir(), name)? {
+        return Ok(pipeline);
+    }
+    let script = resolve_script_path(name, workspace.root())
+        .map_err(|_| format!("No pipeline or script named {}", name))?;
+    Ok(service
+        .dependency_pipeline(workspace.root(), &script)
+        .map_err(|err| err.to_string())?)
+}
+
+fn print_summary(report: &PipelineReport) {
+    println!();
+    for stage in &report.stages {
+        if let Some(error) = &stage.error {
+            println!("{}: {}", stage.id, error);
+        }
+    }
+    let passed = report
+        .stages
+        .iter()
+        .filter(|stage| stage.status == StageStatus::Passed.label())
+        .count();
+    println!(
+        "Pipeline {} {}: {} of {} stages passed in {}",
+        report.pipeline,
+        if report.success { "passed" } else { "failed" },
+        passed,
+        report.stages.len(),
+        history::format_duration(report.duration_ms)
+    );
+}
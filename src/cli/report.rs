@@ -0,0 +1,197 @@
+use crate::adapters::workspace_repository::FsWorkspaceRepository;
+use crate::cli::args::ReportArgs;
+use crate::config::load_workspace_config;
+use crate::domain::Schema;
+use crate::history::{self, HistoryEntry, TimeZone};
+use crate::ports::ScriptRepository;
+use crate::status::{FAIL_SYMBOL, OK_SYMBOL, WARN_SYMBOL};
+use crate::workspace::Workspace;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;margin:2rem;color:#222}\
+table{border-collapse:collapse;width:100%;margin-bottom:2rem}\
+th,td{border:1px solid #ddd;padding:.4rem .6rem;text-align:left;vertical-align:top}\
+th{background:#f4f4f4}.tag{background:#eef;border-radius:3px;padding:0 .3rem;margin-right:.2rem}\
+.ok{color:#1a7f37}.failed{color:#cf222e}.error{color:#9a6700}.muted{color:#777}\
+code{font-size:.9em}";
+
+pub fn run(scripts_dir: PathBuf, args: ReportArgs) -> Result<(), Box<dyn Error>> {
+    let workspace = Workspace::new(scripts_dir);
+    let time_zone = load_workspace_config(workspace.config_path()).time_zone();
+    let repo = FsWorkspaceRepository::new(workspace.root().to_path_buf());
+    let mut scripts = repo.list_scripts_recursive()?;
+    scripts.sort();
+    let catalogue: Vec<(PathBuf, Result<Schema, String>)> = scripts
+        .into_iter()
+        .map(|script| {
+            let schema = repo.read_schema(&script).map_err(|err| err.to_string());
+            (script, schema)
+        })
+        .collect();
+    let mut entries = history::load_entries(&workspace)?;
+    entries.truncate(args.runs);
+
+    let html = render_report(&workspace, &catalogue, &entries, time_zone);
+    fs::create_dir_all(&args.html)?;
+    let path = args.html.join("index.html");
+    fs::write(&path, html)?;
+    println!("Report written to {}", path.display());
+    Ok(())
+}
+
+fn render_report(
+    workspace: &Workspace,
+    catalogue: &[(PathBuf, Result<Schema, String>)],
+    entries: &[HistoryEntry],
+    time_zone: TimeZone,
+) -> String {
+    let now = history::timestamp_ms();
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>omakure report</title>\n");
+    let _ = writeln!(html, "<style>{}</style>\n</head>\n<body>", STYLE);
+    let _ = writeln!(html, "<h1>omakure report</h1>");
+    let _ = writeln!(
+        html,
+        "<p class=\"muted\">Workspace <code>{}</code>, generated {}.</p>",
+        escape(&workspace.root().to_string_lossy()),
+        escape(&history::format_timestamp(now, time_zone.offset_at(now)))
+    );
+
+    let _ = writeln!(html, "<h2>Scripts ({})</h2>", catalogue.len());
+    if catalogue.is_empty() {
+        html.push_str("<p class=\"muted\">No scripts found.</p>\n");
+    } else {
+        html.push_str(
+            "<table>\n<tr><th>Script</th><th>Name</th><th>Description</th><th>Tags</th>\
+             <th>Owner</th><th>Fields</th></tr>\n",
+        );
+        for (script, schema) in catalogue {
+            render_script_row(&mut html, &relative(workspace, script), schema);
+        }
+        html.push_str("</table>\n");
+    }
+
+    let _ = writeln!(html, "<h2>Recent runs ({})</h2>", entries.len());
+    if entries.is_empty() {
+        html.push_str("<p class=\"muted\">No runs recorded yet.</p>\n");
+    } else {
+        html.push_str(
+            "<table>\n<tr><th>Date</th><th>Script</th><th>Args</th><th>Status</th>\
+             <th>Duration</th></tr>\n",
+        );
+        for entry in entries {
+            render_run_row(&mut html, entry, time_zone);
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn render_script_row(html: &mut String, script: &str, schema: &Result<Schema, String>) {
+    let schema = match schema {
+        Ok(schema) => schema,
+        Err(err) => {
+            let _ = writeln!(
+                html,
+                "<tr><td><code>{}</code></td><td colspan=\"5\" class=\"error\">{} {}</td></tr>",
+                escape(script),
+                WARN_SYMBOL,
+                escape(err)
+            );
+            return;
+        }
+    };
+    let tags: String = schema
+        .tags
+        .iter()
+        .flatten()
+        .map(|tag| format!("<span class=\"tag\">{}</span>", escape(tag)))
+        .collect();
+    let fields = schema
+        .fields
+        .iter()
+        .map(|field| {
+            let required = if field.required.unwrap_or(false) {
+                "*"
+            } else {
+                ""
+            };
+            format!(
+                "{}{} <span class=\"muted\">{}</span>",
+                escape(&field.name),
+                required,
+                escape(&field.kind)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("<br>");
+    let _ = writeln!(
+        html,
+        "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+        escape(script),
+        escape(&schema.name),
+        escape(schema.description.as_deref().unwrap_or("")),
+        tags,
+        escape(&schema.owner_label().unwrap_or_default()),
+        fields
+    );
+}
+
+fn render_run_row(html: &mut String, entry: &HistoryEntry, time_zone: TimeZone) {
+    let (class, label) = if entry.error.is_some() {
+        ("error", format!("{} error", WARN_SYMBOL))
+    } else if entry.success {
+        ("ok", format!("{} ok", OK_SYMBOL))
+    } else {
+        match entry.exit_code {
+            Some(code) => ("failed", format!("{} exit {}", FAIL_SYMBOL, code)),
+            None => ("failed", format!("{} failed", FAIL_SYMBOL)),
+        }
+    };
+    let _ = writeln!(
+        html,
+        "<tr><td>{}</td><td><code>{}</code></td><td><code>{}</code></td>\
+         <td class=\"{}\">{}</td><td>{}</td></tr>",
+        escape(&history::format_timestamp(
+            entry.timestamp,
+            time_zone.offset_at(entry.timestamp)
+        )),
+        escape(&entry.script.to_string_lossy()),
+        escape(&entry.args.join(" ")),
+        class,
+        label,
+        entry
+            .duration_ms
+            .map(history::format_duration)
+            .unwrap_or_default()
+    );
+}
+
+fn relative(workspace: &Workspace, script: &Path) -> String {
+    script
+        .strip_prefix(workspace.root())
+        .unwrap_or(script)
+        .to_string_lossy()
+        .to_string()
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
@@ -1,41 +1,184 @@
+use crate::adapters::environments::FsEnvironmentRepository;
 use crate::adapters::script_runner::MultiScriptRunner;
 use crate::adapters::workspace_repository::FsWorkspaceRepository;
 use crate::cli::args::RunArgs;
+use crate::concurrency::{queue_position, GroupTicket};
+use crate::config::{load_workspace_config, WorkspaceConfig};
+use crate::domain::{
+    build_args, normalize_input, parse_outputs, Field, PromptKind, Schema, ScriptPrompt,
+};
+use crate::error::SchemaError;
 use crate::history;
-use crate::ports::ScriptRunOutput;
-use crate::runtime::script_extensions;
-use crate::use_cases::ScriptService;
+use crate::ports::{HookRun, Interpreters, PromptHandler, Prompts, ResourceUsage, ScriptRunOutput};
+use crate::runtime::{path_style, resolved_command, script_extensions, source_file, task_target};
+use crate::use_cases::{EnvironmentService, ScriptService};
+use crate::util::shell_join;
 use crate::workspace::Workspace;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
 use std::error::Error;
+use std::io::{self, BufRead, IsTerminal, Write};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+#[derive(Serialize)]
+struct DryRunReport<'a> {
+    script: &'a Path,
+    args: &'a [String],
+    /// The local command; absent when the run goes to `runs_on`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    runs_on: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    env: Vec<&'a str>,
+}
+
+#[derive(Serialize)]
+struct RunReport {
+    script: PathBuf,
+    args: Vec<String>,
+    success: bool,
+    exit_code: Option<i32>,
+    duration_ms: u64,
+    stdout: String,
+    stderr: String,
+    outputs: BTreeMap<String, String>,
+    usage: Option<ResourceUsage>,
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    owner: Option<String>,
+    overdue: bool,
+    /// Failed attempts the schema's `Retry` ran again.
+    retries: u32,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    hooks: Vec<HookRun>,
+    /// Collected output files, relative to the run's artifacts folder.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    files: Vec<PathBuf>,
+}
 
 pub fn run(scripts_dir: PathBuf, options: RunArgs) -> Result<(), Box<dyn Error>> {
     let workspace = Workspace::new(scripts_dir);
     workspace.ensure_layout()?;
 
     let script_path = resolve_script_path(&options.script, workspace.root())?;
+    let overrides = parse_env_overrides(&options.env)?;
 
-    let repo = Box::new(FsWorkspaceRepository::new(workspace.root().to_path_buf()));
-    let runner = Box::new(MultiScriptRunner::new());
-    let service = ScriptService::new(repo, runner);
+    let config = load_workspace_config(workspace.config_path());
+    let service = script_service(&workspace, &config);
+
+    let args = resolve_args(&service, &workspace, &script_path, &options)?;
+    if options.dry_run {
+        return print_dry_run(
+            &script_path,
+            &args,
+            &overrides,
+            service.remote_backend(&script_path),
+            &config.interpreters(),
+            options.json,
+        );
+    }
 
-    let run_result = service.run_script(&script_path, &options.args);
+    let schema = service.load_schema(&script_path).ok();
+    let owner = schema.as_ref().and_then(|schema| schema.owner_label());
+    let overdue_factor = config.overdue_factor();
+    let is_overdue = |duration_ms: u64| {
+        schema
+            .as_ref()
+            .is_some_and(|schema| schema.is_overdue(duration_ms, overdue_factor))
+    };
+    let _ticket = match schema
+        .as_ref()
+        .and_then(|schema| schema.concurrency_group())
+    {
+        Some(group) => Some(wait_for_group(&workspace, group, &script_path)?),
+        None => None,
+    };
+    let started = Instant::now();
+    let log_dir = history::new_run_dir(&workspace, &script_path);
+    let run_result = service.run_script(&script_path, &args, Some(&log_dir), &overrides);
+    let override_names = || overrides.iter().map(|(key, _)| key.clone()).collect();
+    let duration_ms = started.elapsed().as_millis() as u64;
     match run_result {
         Ok(output) => {
             let success = output.success;
-            let exit_code = output.exit_code.unwrap_or(1);
-            print_output(&output);
-            let entry = history::success_entry(&workspace, &script_path, &options.args, output);
+            // A run failed by `FailRegex` can have exited with 0.
+            let exit_code = match output.exit_code {
+                Some(code) if code != 0 => code,
+                _ => 1,
+            };
+            let overdue = is_overdue(output.duration_ms);
+            if options.json {
+                let report = RunReport {
+                    script: script_path.clone(),
+                    args: args.clone(),
+                    success,
+                    exit_code: output.exit_code,
+                    duration_ms: output.duration_ms,
+                    stdout: output.stdout.clone(),
+                    stderr: output.stderr.clone(),
+                    outputs: parse_outputs(&output.stdout),
+                    usage: output.usage,
+                    error: None,
+                    owner: owner.clone().filter(|_| !success),
+                    overdue,
+                    retries: output.retries,
+                    hooks: output.hooks.clone(),
+                    files: output.files.clone(),
+                };
+                print_report(&report)?;
+            } else {
+                print_output(&output);
+                if output.retries > 0 {
+                    eprintln!("Retries: {}", output.retries);
+                }
+                print_failed_hooks(&output.hooks);
+                print_files(&output);
+                if overdue {
+                    print_overdue(output.duration_ms, schema.as_ref(), overdue_factor);
+                }
+                if !success {
+                    print_owner(owner.as_deref());
+                }
+            }
+            let mut entry = history::success_entry(&workspace, &script_path, &args, output);
+            entry.env_overrides = override_names();
             let _ = history::record_entry(&workspace, &entry);
             if !success {
                 std::process::exit(exit_code);
             }
         }
         Err(err) => {
-            eprintln!("{}", err);
-            let entry =
-                history::error_entry(&workspace, &script_path, &options.args, err.to_string());
+            let mut entry = history::error_entry(&workspace, &script_path, &args, err.to_string());
+            entry.env_overrides = override_names();
             let _ = history::record_entry(&workspace, &entry);
+            if options.json {
+                let report = RunReport {
+                    script: script_path,
+                    args,
+                    success: false,
+                    exit_code: None,
+                    duration_ms,
+                    stdout: String::new(),
+                    stderr: String::new(),
+                    outputs: BTreeMap::new(),
+                    usage: None,
+                    error: Some(err.to_string()),
+                    owner,
+                    overdue: is_overdue(duration_ms),
+                    retries: 0,
+                    hooks: Vec::new(),
+                    files: Vec::new(),
+                };
+                print_report(&report)?;
+                std::process::exit(1);
+            }
+            eprintln!("{}", err);
+            print_owner(owner.as_deref());
             return Err(Box::new(err));
         }
     }
@@ -43,7 +186,248 @@ pub fn run(scripts_dir: PathBuf, options: RunArgs) -> Result<(), Box<dyn Error>>
     Ok(())
 }
 
-fn resolve_script_path(script: &str, scripts_dir: &Path) -> Result<PathBuf, Box<dyn Error>> {
+fn print_dry_run(
+    script: &Path,
+    args: &[String],
+    env: &[(String, String)],
+    runs_on: Option<String>,
+    interpreters: &Interpreters,
+    json: bool,
+) -> Result<(), Box<dyn Error>> {
+    // A run going elsewhere does not use this machine's interpreters.
+    let command = match runs_on {
+        Some(_) => None,
+        None => Some(resolved_command(script, args, interpreters)?),
+    };
+    if json {
+        let report = DryRunReport {
+            script,
+            args,
+            command,
+            runs_on,
+            env: env.iter().map(|(key, _)| key.as_str()).collect(),
+        };
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+    // Like the JSON report and history, only the names: values may be secrets.
+    if !env.is_empty() {
+        let names: Vec<&str> = env.iter().map(|(key, _)| key.as_str()).collect();
+        println!("Env overrides: {}", names.join(", "));
+    }
+    let line = match runs_on {
+        Some(runs_on) => {
+            println!("Would run on {}, not this machine", runs_on);
+            args.to_vec()
+        }
+        None => command.unwrap_or_default(),
+    };
+    if !line.is_empty() {
+        println!("{}", shell_join(&line));
+    }
+    Ok(())
+}
+
+/// Reads `-e KEY=VALUE` options into variables for this run.
+fn parse_env_overrides(raw: &[String]) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    raw.iter()
+        .map(|raw| match raw.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                Ok((key.trim().to_string(), value.to_string()))
+            }
+            _ => Err(format!("Invalid --env '{}', expected KEY=VALUE", raw).into()),
+        })
+        .collect()
+}
+
+/// Service running the workspace's scripts with its `[runner]` defaults and,
+/// unless turned off, the active env file.
+pub(crate) fn script_service(workspace: &Workspace, config: &WorkspaceConfig) -> ScriptService {
+    let repo = Box::new(FsWorkspaceRepository::new(workspace.root().to_path_buf()));
+    let runner = Box::new(MultiScriptRunner::new());
+    let service = ScriptService::new(repo, runner)
+        .with_run_defaults(config.run_options())
+        .with_hooks(config.hooks());
+    let service = if io::stdin().is_terminal() {
+        service.with_prompts(Prompts(Arc::new(TerminalPrompts::default())))
+    } else {
+        service
+    };
+    if !config.pass_active_env() {
+        return service;
+    }
+    let environment = FsEnvironmentRepository::new(workspace.envs_dir());
+    service.with_environment(EnvironmentService::new(Box::new(environment)))
+}
+
+fn resolve_args(
+    service: &ScriptService,
+    workspace: &Workspace,
+    script_path: &Path,
+    options: &RunArgs,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let interactive = options.args.is_empty() && io::stdin().is_terminal();
+    if options.fields.is_empty() && !interactive {
+        return Ok(options.args.clone());
+    }
+
+    let mut schema = match service.load_schema(script_path) {
+        Ok(schema) => schema,
+        Err(_) if options.fields.is_empty() => return Ok(options.args.clone()),
+        Err(err) => return Err(err.into()),
+    };
+    schema.fields.sort_by_key(|field| field.order);
+
+    let defaults = env_defaults(workspace);
+    let mut inputs: Vec<String> = schema
+        .fields
+        .iter()
+        .map(|field| {
+            defaults
+                .get(&field.name.to_ascii_lowercase())
+                .cloned()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    for raw in &options.fields {
+        let (name, value) = raw
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid --field '{}', expected NAME=VALUE", raw))?;
+        let name = name.trim();
+        let index = schema
+            .fields
+            .iter()
+            .position(|field| field.name == name)
+            .or_else(|| {
+                schema
+                    .fields
+                    .iter()
+                    .position(|field| field.name.eq_ignore_ascii_case(name))
+            })
+            .ok_or_else(|| format!("Unknown field '{}' for {}", name, schema.name))?;
+        inputs[index] = value.to_string();
+    }
+
+    let missing: Vec<usize> = schema
+        .fields
+        .iter()
+        .enumerate()
+        .filter(|(index, field)| {
+            matches!(
+                normalize_input(field, &inputs[*index]),
+                Err(SchemaError::ValueRequired)
+            )
+        })
+        .map(|(index, _)| index)
+        .collect();
+    if missing.is_empty() && options.fields.is_empty() {
+        return Ok(options.args.clone());
+    }
+    if interactive {
+        for index in missing {
+            inputs[index] = prompt_field(&schema.fields[index])?;
+        }
+    }
+
+    let mut args = build_args(&schema.fields, &inputs, path_style(script_path))
+        .map_err(|err| err.to_string())?;
+    args.extend(options.args.iter().cloned());
+    Ok(args)
+}
+
+fn prompt_field(field: &Field) -> Result<String, Box<dyn Error>> {
+    let mut label = field.prompt.clone().unwrap_or_else(|| field.name.clone());
+    if let Some(choices) = &field.choices {
+        label.push_str(&format!(" [{}]", choices.join("/")));
+    }
+
+    let stdin = io::stdin();
+    loop {
+        eprint!("{}: ", label);
+        io::stderr().flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            return Err(format!("No value entered for {}", field.name).into());
+        }
+        match normalize_input(field, &line) {
+            Ok(Some(value)) => return Ok(value),
+            Ok(None) => return Ok(String::new()),
+            Err(err) => eprintln!("  {}", err),
+        }
+    }
+}
+
+/// Asks the `OMAKURE_PROMPT` questions of scripts on the terminal.
+#[derive(Default)]
+struct TerminalPrompts {
+    /// Keeps the questions of scripts run side by side from interleaving.
+    lock: Mutex<()>,
+}
+
+impl PromptHandler for TerminalPrompts {
+    fn ask(&self, prompt: &ScriptPrompt) -> Option<String> {
+        let _guard = self.lock.lock().ok()?;
+        let hint = match (prompt.kind, &prompt.default) {
+            (PromptKind::Confirm, _) if prompt.fallback() == "yes" => " [Y/n]".to_string(),
+            (PromptKind::Confirm, _) => " [y/N]".to_string(),
+            (PromptKind::Value, Some(default)) if !prompt.secret => format!(" [{}]", default),
+            (PromptKind::Value, _) => String::new(),
+        };
+        eprint!("{}{}: ", prompt.message, hint);
+        io::stderr().flush().ok()?;
+        let input = if prompt.secret {
+            read_hidden()?
+        } else {
+            let mut line = String::new();
+            match io::stdin().lock().read_line(&mut line) {
+                Ok(read) if read > 0 => line,
+                _ => return None,
+            }
+        };
+        Some(prompt.answer(&input))
+    }
+}
+
+/// A line typed without echo; `None` on Esc or Ctrl+C.
+fn read_hidden() -> Option<String> {
+    terminal::enable_raw_mode().ok()?;
+    let mut input = String::new();
+    let finished = loop {
+        let Ok(Event::Key(key)) = event::read() else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Enter => break true,
+            KeyCode::Esc => break false,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break false,
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(ch) => input.push(ch),
+            _ => {}
+        }
+    };
+    let _ = terminal::disable_raw_mode();
+    eprintln!();
+    finished.then_some(input)
+}
+
+fn env_defaults(workspace: &Workspace) -> HashMap<String, String> {
+    let repo = FsEnvironmentRepository::new(workspace.envs_dir());
+    EnvironmentService::new(Box::new(repo))
+        .load_environment_config()
+        .map(|config| config.defaults)
+        .unwrap_or_default()
+}
+
+pub(crate) fn resolve_script_path(
+    script: &str,
+    scripts_dir: &Path,
+) -> Result<PathBuf, Box<dyn Error>> {
     let has_separator = script.contains('/') || script.contains('\\');
     let path = PathBuf::from(script);
 
@@ -59,6 +443,12 @@ fn resolve_script_path(script: &str, scripts_dir: &Path) -> Result<PathBuf, Box<
 }
 
 fn resolve_with_extensions(path: PathBuf) -> Result<PathBuf, Box<dyn Error>> {
+    if task_target(&path).is_some() {
+        if source_file(&path).is_file() {
+            return Ok(path);
+        }
+        return Err(format!("Script not found: {}", path.display()).into());
+    }
     if path.exists() {
         if path.is_file() {
             return Ok(path);
@@ -92,3 +482,62 @@ fn print_output(output: &ScriptRunOutput) {
         }
     }
 }
+
+pub(crate) fn wait_for_group(
+    workspace: &Workspace,
+    group: &str,
+    script: &Path,
+) -> Result<GroupTicket, Box<dyn Error>> {
+    let ticket = GroupTicket::enqueue(workspace, group, script)?;
+    if let Some(position) = queue_position(ticket.path()).filter(|position| *position > 0) {
+        eprintln!(
+            "Waiting for concurrency group {} (position {})",
+            group, position
+        );
+        ticket.wait();
+    }
+    Ok(ticket)
+}
+
+fn print_overdue(duration_ms: u64, schema: Option<&Schema>, factor: u64) {
+    let expected = schema
+        .and_then(|schema| schema.expected_duration_seconds)
+        .unwrap_or_default();
+    eprintln!(
+        "Warning: run took {}, over {}x the expected {}",
+        history::format_duration(duration_ms),
+        factor,
+        history::format_duration(expected.saturating_mul(1000))
+    );
+}
+
+fn print_files(output: &ScriptRunOutput) {
+    let Some(dir) = output.log_dir.as_ref().filter(|_| !output.files.is_empty()) else {
+        return;
+    };
+    eprintln!("Files in {}:", dir.display());
+    for file in &output.files {
+        eprintln!("  {}", file.display());
+    }
+}
+
+fn print_failed_hooks(hooks: &[HookRun]) {
+    for hook in hooks.iter().filter(|hook| !hook.success) {
+        eprintln!(
+            "Warning: {} hook failed: {}",
+            hook.stage.label(),
+            hook.command
+        );
+    }
+}
+
+fn print_owner(owner: Option<&str>) {
+    if let Some(owner) = owner {
+        eprintln!("Owner: {}", owner);
+    }
+}
+
+fn print_report(report: &RunReport) -> Result<(), Box<dyn Error>> {
+    println!("{}", serde_json::to_string_pretty(report)?);
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use crate::adapters::workspace_repository::FsWorkspaceRepository;
+use crate::cli::args::RunAllArgs;
+use crate::cli::run::{resolve_script_path, script_service, wait_for_group};
+use crate::config::load_workspace_config;
+use crate::history;
+use crate::ports::ScriptRepository;
+use crate::status::{FAIL_SYMBOL, OK_SYMBOL};
+use crate::use_cases::ScriptService;
+use crate::workspace::Workspace;
+use serde::Serialize;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+
+/// How one script of the batch went.
+#[derive(Serialize)]
+struct BatchResult {
+    /// Workspace-relative path.
+    script: PathBuf,
+    success: bool,
+    exit_code: Option<i32>,
+    duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct BatchReport {
+    passed: usize,
+    failed: usize,
+    duration_ms: u64,
+    results: Vec<BatchResult>,
+}
+
+/// Runs the selected scripts with no arguments, `args.parallel` at a time,
+/// recording each run in history. Exits with 1 when any script failed.
+pub fn run(scripts_dir: PathBuf, args: RunAllArgs) -> Result<(), Box<dyn Error>> {
+    let workspace = Workspace::new(scripts_dir);
+    workspace.ensure_layout()?;
+    let config = load_workspace_config(workspace.config_path());
+    let service = script_service(&workspace, &config);
+
+    let scripts = match (&args.list, &args.tag) {
+        (Some(list), _) => listed_scripts(list, workspace.root())?,
+        (None, Some(tag)) => {
+            let scripts = tagged_scripts(&service, workspace.root(), tag)?;
+            if scripts.is_empty() {
+                return Err(format!("No scripts tagged '{}'", tag).into());
+            }
+            scripts
+        }
+        (None, None) => return Err("Pass --tag or --list".into()),
+    };
+
+    let started = Instant::now();
+    let next = AtomicUsize::new(0);
+    let finished: Mutex<Vec<Option<BatchResult>>> =
+        Mutex::new(scripts.iter().map(|_| None).collect());
+    // One history write at a time; SQLite would otherwise make the rest wait.
+    let recording = Mutex::new(());
+    let workers = usize::from(args.parallel).min(scripts.len());
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(script) = scripts.get(index) else {
+                    break;
+                };
+                let result = run_script(&service, &workspace, script, &recording);
+                eprintln!(
+                    "{} {} ({})",
+                    if result.success {
+                        OK_SYMBOL
+                    } else {
+                        FAIL_SYMBOL
+                    },
+                    result.script.display(),
+                    history::format_duration(result.duration_ms)
+                );
+                if let Ok(mut finished) = finished.lock() {
+                    finished[index] = Some(result);
+                }
+            });
+        }
+    });
+
+    let results: Vec<BatchResult> = finished
+        .into_inner()
+        .map_err(|_| "A script run panicked")?
+        .into_iter()
+        .flatten()
+        .collect();
+    let failed = results.iter().filter(|result| !result.success).count();
+    let report = BatchReport {
+        passed: results.len() - failed,
+        failed,
+        duration_ms: started.elapsed().as_millis() as u64,
+        results,
+    };
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_table(&report);
+    }
+    if failed > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Scripts whose schema has `tag`, compared without case, in path order.
+fn tagged_scripts(
+    service: &ScriptService,
+    root: &Path,
+    tag: &str,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut scripts = FsWorkspaceRepository::new(root.to_path_buf()).list_scripts_recursive()?;
+    scripts.sort();
+    scripts.retain(|script| {
+        service.load_schema(script).is_ok_and(|schema| {
+            schema
+                .tags
+                .unwrap_or_default()
+                .iter()
+                .any(|other| other.eq_ignore_ascii_case(tag))
+        })
+    });
+    Ok(scripts)
+}
+
+/// Scripts named in `list`, one per line as `omakure run` takes them. Blank
+/// lines and comments are skipped; a comment starts with `#` at the start of
+/// a line or after a space, so `ops/Makefile#clean` still names a target.
+fn listed_scripts(list: &Path, root: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let contents = fs::read_to_string(list)
+        .map_err(|err| format!("Failed to read {}: {}", list.display(), err))?;
+    let mut scripts = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let name = line.find(" #").map_or(line, |at| &line[..at]).trim();
+        if name.is_empty() || name.starts_with('#') {
+            continue;
+        }
+        let script = resolve_script_path(name, root)
+            .map_err(|err| format!("{} line {}: {}", list.display(), index + 1, err))?;
+        if !scripts.contains(&script) {
+            scripts.push(script);
+        }
+    }
+    if scripts.is_empty() {
+        return Err(format!("{} lists no scripts", list.display()).into());
+    }
+    Ok(scripts)
+}
+
+fn run_script(
+    service: &ScriptService,
+    workspace: &Workspace,
+    script: &Path,
+    recording: &Mutex<()>,
+) -> BatchResult {
+    let started = Instant::now();
+    let group = service
+        .load_schema(script)
+        .ok()
+        .and_then(|schema| schema.concurrency_group().map(str::to_string));
+    let _ticket = group.and_then(|group| wait_for_group(workspace, &group, script).ok());
+    let log_dir = history::new_run_dir(workspace, script);
+    let entry = match service.run_script(script, &[], Some(&log_dir), &[]) {
+        Ok(output) => history::success_entry(workspace, script, &[], output),
+        Err(err) => {
+            let mut entry = history::error_entry(workspace, script, &[], err.to_string());
+            entry.duration_ms = Some(started.elapsed().as_millis() as u64);
+            entry
+        }
+    };
+    if let Ok(_guard) = recording.lock() {
+        let _ = history::record_entry(workspace, &entry);
+    }
+    BatchResult {
+        script: entry.script,
+        success: entry.success,
+        exit_code: entry.exit_code,
+        duration_ms: entry.duration_ms.unwrap_or_default(),
+        error: entry.error,
+    }
+}
+
+fn print_table(report: &BatchReport) {
+    let rows: Vec<[String; 3]> = report
+        .results
+        .iter()
+        .map(|result| {
+            let status = match (result.success, result.exit_code) {
+                (true, _) => "pass".to_string(),
+                (false, Some(code)) => format!("fail ({})", code),
+                (false, None) => "error".to_string(),
+            };
+            [
+                result.script.display().to_string(),
+                status,
+                history::format_duration(result.duration_ms),
+            ]
+        })
+        .collect();
+    let header = ["SCRIPT", "STATUS", "DURATION"];
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    println!();
+    for row in std::iter::once(header.map(str::to_string)).chain(rows) {
+        println!(
+            "{:<w0$}  {:<w1$}  {:>w2$}",
+            row[0],
+            row[1],
+            row[2],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2]
+        );
+    }
+    for result in &report.results {
+        if let Some(error) = &result.error {
+            println!("{}: {}", result.script.display(), error);
+        }
+    }
+    println!();
+    println!(
+        "{} passed, {} failed in {}",
+        report.passed,
+        report.failed,
+        history::format_duration(report.duration_ms)
+    );
+}
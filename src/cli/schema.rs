@@ -0,0 +1,200 @@
+use crate::adapters::remote_repository::RemoteScriptRepository;
+use crate::adapters::workspace_repository::FsWorkspaceRepository;
+use crate::cli::args::SchemaArgs;
+use crate::cli::run::resolve_script_path;
+use crate::domain::{field_arg, Schema};
+use crate::history;
+use crate::ports::ScriptRepository;
+use serde_json::Value;
+use std::error::Error;
+use std::path::PathBuf;
+
+pub fn run(scripts_dir: PathBuf, args: SchemaArgs) -> Result<(), Box<dyn Error>> {
+    let mut schema = match &args.remote {
+        Some(url) => {
+            let repo = RemoteScriptRepository::connect(url, PathBuf::from("remote"))
+                .map_err(|err| err.to_string())?;
+            let script = repo
+                .find(&args.script)
+                .ok_or_else(|| format!("Script not found in {}: {}", url, args.script))?;
+            repo.read_schema(&script)?
+        }
+        None => {
+            let script = resolve_script_path(&args.script, &scripts_dir)?;
+            FsWorkspaceRepository::new(scripts_dir).read_schema(&script)?
+        }
+    };
+    // Stable sort: fields sharing an Order keep the order they were written in.
+    schema.fields.sort_by_key(|field| field.order);
+
+    if args.table {
+        print_table(&schema);
+        return Ok(());
+    }
+    let mut value = serde_json::to_value(&schema)?;
+    strip_nulls(&mut value);
+    if args.pretty {
+        println!("{}", serde_json::to_string_pretty(&value)?);
+    } else {
+        println!("{}", value);
+    }
+    Ok(())
+}
+
+/// Drops unset keys, leaving only what the schema sets.
+pub(crate) fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, value| !value.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_nulls),
+        _ => {}
+    }
+}
+
+fn print_table(schema: &Schema) {
+    println!("Name: {}", schema.name);
+    let mut details: Vec<(&str, String)> = Vec::new();
+    if let Some(description) = &schema.description {
+        details.push(("Description", description.clone()));
+    }
+    if let Some(kind) = &schema.kind {
+        details.push(("Type", kind.clone()));
+    }
+    if let Some(tags) = schema.tags.as_ref().filter(|tags| !tags.is_empty()) {
+        details.push(("Tags", tags.join(", ")));
+    }
+    if let Some(owner) = schema.owner_label() {
+        details.push(("Owner", owner));
+    }
+    if let Some(seconds) = schema.expected_duration_seconds {
+        details.push((
+            "Expected duration",
+            history::format_duration(seconds.saturating_mul(1000)),
+        ));
+    }
+    if let Some(group) = schema.concurrency_group() {
+        details.push(("Concurrency group", group.to_string()));
+    }
+    if let Some(backend) = &schema.backend {
+        details.push(("Backend", backend.clone()));
+    }
+    if let Some(checks) = schema
+        .preflight
+        .as_ref()
+        .filter(|checks| !checks.is_empty())
+    {
+        details.push(("Preflight", checks.join(", ")));
+    }
+    if let Some(names) = schema
+        .requires_env
+        .as_ref()
+        .filter(|names| !names.is_empty())
+    {
+        details.push(("Requires env", names.join(", ")));
+    }
+    if let Some(tools) = schema.requires.as_ref().filter(|tools| !tools.is_empty()) {
+        details.push(("Requires", tools.join(", ")));
+    }
+    for (label, value) in details {
+        println!("{}: {}", label, value);
+    }
+
+    println!();
+    if schema.fields.is_empty() {
+        println!("No fields.");
+    } else {
+        let rows: Vec<[String; 7]> = schema
+            .fields
+            .iter()
+            .map(|field| {
+                [
+                    field.order.to_string(),
+                    field.name.clone(),
+                    field.kind.clone(),
+                    if field.required.unwrap_or(false) {
+                        "yes"
+                    } else {
+                        "no"
+                    }
+                    .to_string(),
+                    field_arg(field),
+                    field.default.clone().unwrap_or_else(|| "-".to_string()),
+                    field
+                        .choices
+                        .as_ref()
+                        .map(|choices| choices.join(", "))
+                        .unwrap_or_else(|| "-".to_string()),
+                ]
+            })
+            .collect();
+        let header = [
+            "ORDER", "NAME", "TYPE", "REQUIRED", "ARG", "DEFAULT", "CHOICES",
+        ];
+        let mut widths = header.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        print_row(&header.map(str::to_string), &widths);
+        for row in &rows {
+            print_row(row, &widths);
+        }
+    }
+
+    if let Some(outputs) = schema
+        .outputs
+        .as_ref()
+        .filter(|outputs| !outputs.is_empty())
+    {
+        println!("\nOutputs:");
+        for output in outputs {
+            match output.file_pattern() {
+                Some(pattern) => println!("  {} ({}, {})", output.name, output.kind, pattern),
+                None => println!("  {} ({})", output.name, output.kind),
+            }
+        }
+    }
+    if let Some(queue) = &schema.queue {
+        println!("\nQueue:");
+        for matrix in queue.matrix.iter().flat_map(|matrix| &matrix.values) {
+            println!("  matrix {}: {}", matrix.name, matrix.values.join(", "));
+        }
+        for (index, case) in queue.cases.iter().flatten().enumerate() {
+            let values: Vec<String> = case
+                .values
+                .iter()
+                .map(|value| format!("{}={}", value.name, value.value))
+                .collect();
+            let name = case
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("#{}", index + 1));
+            println!("  case {}: {}", name, values.join(" "));
+        }
+    }
+    if let Some(env) = schema.env.as_ref().filter(|env| !env.is_empty()) {
+        println!("\nEnv:");
+        for (name, var) in env {
+            let mut line = format!("  {}", name);
+            if let Some(field) = &var.from_field {
+                line.push_str(&format!(" from field {}", field));
+            }
+            if let Some(default) = &var.default {
+                line.push_str(&format!(" (default {})", default));
+            }
+            println!("{}", line);
+        }
+    }
+}
+
+fn print_row(cells: &[String; 7], widths: &[usize; 7]) {
+    let line: Vec<String> = cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+        .collect();
+    println!("{}", line.join("  ").trim_end());
+}
@@ -0,0 +1,63 @@
+use crate::cli::args::SnapshotArgs;
+use crate::config::load_workspace_config;
+use crate::history;
+use crate::snapshot::{self, Drift};
+use crate::status::{FAIL_SYMBOL, OK_SYMBOL};
+use crate::workspace::Workspace;
+use std::error::Error;
+use std::path::PathBuf;
+
+pub fn run(scripts_dir: PathBuf, args: SnapshotArgs) -> Result<(), Box<dyn Error>> {
+    let workspace = Workspace::new(scripts_dir);
+    let path = args.file.unwrap_or_else(|| workspace.snapshot_path());
+    let current = snapshot::take(workspace.root(), history::timestamp_ms())?;
+    if !args.verify {
+        snapshot::write(&path, &current)?;
+        println!(
+            "Recorded {} scripts in {}",
+            current.scripts.len(),
+            path.display()
+        );
+        return Ok(());
+    }
+
+    let previous = snapshot::read(&path)?;
+    let time_zone = load_workspace_config(workspace.config_path()).time_zone();
+    println!(
+        "Snapshot: {} (taken {})",
+        path.display(),
+        history::format_timestamp(
+            previous.created_at,
+            time_zone.offset_at(previous.created_at)
+        )
+    );
+    let drift = previous.drift(&current);
+    if drift.is_empty() {
+        println!(
+            "  {} {} scripts unchanged",
+            OK_SYMBOL,
+            current.scripts.len()
+        );
+        return Ok(());
+    }
+    for change in &drift {
+        match change {
+            Drift::Added(path) => println!("  + {} (added)", path),
+            Drift::Removed(path) => println!("  - {} (removed)", path),
+            Drift::Modified { path, schema } => {
+                let detail = if *schema {
+                    "modified, schema changed"
+                } else {
+                    "modified"
+                };
+                println!("  ~ {} ({})", path, detail);
+            }
+        }
+    }
+    println!(
+        "{} {} scripts changed since the snapshot.",
+        FAIL_SYMBOL,
+        drift.len()
+    );
+    std::process::exit(1);
+}
@@ -0,0 +1,160 @@
+use crate::cli::args::SshRunArgs;
+use crate::runtime::{script_kind, ScriptKind};
+use crate::util::shell_join;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Subfolder of the run's artifacts folder receiving fetched files.
+const REMOTE_ARTIFACTS_DIR: &str = "remote";
+/// Runs a python script read from stdin after a first line holding its
+/// environment as a JSON object, so the values stay off both command lines.
+const PYTHON_BOOTSTRAP: &str = "import json, os, sys; \
+os.environ.update(json.loads(sys.stdin.readline())); \
+sys.argv[0] = '-'; \
+exec(compile(sys.stdin.read(), '<stdin>', 'exec'), {'__name__': '__main__'})";
+
+pub fn run(args: SshRunArgs) -> Result<(), Box<dyn Error>> {
+    // ssh would read a leading dash as one of its own options.
+    if args.destination.starts_with('-') {
+        return Err(format!("Invalid ssh destination: {}", args.destination).into());
+    }
+    let kind = script_kind(&args.script);
+    let interpreter: &[&str] = match kind {
+        Some(ScriptKind::Bash) => &["bash", "-s", "--"],
+        Some(ScriptKind::Python) => &["python3", "-c", PYTHON_BOOTSTRAP],
+        _ => return Err("Only bash and python scripts can run over SSH".into()),
+    };
+    let env: Vec<(String, String)> = args
+        .env
+        .iter()
+        .filter(|key| is_env_name(key))
+        .filter_map(|key| env::var(key).ok().map(|value| (key.clone(), value)))
+        .collect();
+
+    // The script arrives on stdin, so nothing has to be copied to the host
+    // first. The env values go ahead of it, never into ssh's argv, where `ps`
+    // would show them on both hosts.
+    let mut input = env_preamble(kind == Some(ScriptKind::Python), &env).into_bytes();
+    input.extend(fs::read(&args.script)?);
+    let mut remote: Vec<String> = interpreter.iter().map(|part| part.to_string()).collect();
+    remote.extend(args.args.iter().cloned());
+
+    let mut command = Command::new("ssh");
+    command.args(["-o", "BatchMode=yes"]);
+    if let Some(port) = args.port {
+        command.arg("-p").arg(port.to_string());
+    }
+    let mut child = command
+        .arg(&args.destination)
+        .arg("--")
+        .arg(shell_join(&remote))
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(&input)?;
+    }
+    let status = child.wait()?;
+    // 255 is ssh's own failure: connection, authentication, or host key.
+    if status.code() == Some(255) {
+        return Err(format!("ssh to {} failed", args.destination).into());
+    }
+    // Failed runs often leave the most useful logs, so collect either way.
+    if let Some(dir) = &args.artifacts_dir {
+        if let Err(err) = collect(&args, dir) {
+            eprintln!("Could not fetch remote artifacts: {}", err);
+        }
+    }
+    match status.code() {
+        Some(0) => Ok(()),
+        Some(code) => std::process::exit(code),
+        None => Err("ssh was terminated by a signal".into()),
+    }
+}
+
+/// Input sent ahead of the script to set `env` on the host: `export` lines for
+/// bash, or the JSON line `PYTHON_BOOTSTRAP` reads.
+fn env_preamble(python: bool, env: &[(String, String)]) -> String {
+    if python {
+        let map: serde_json::Map<String, serde_json::Value> = env
+            .iter()
+            .map(|(key, value)| (key.clone(), serde_json::Value::String(value.clone())))
+            .collect();
+        return format!("{}\n", serde_json::Value::Object(map));
+    }
+    env.iter()
+        .map(|(key, value)| {
+            format!(
+                "export {}={}\n",
+                key,
+                shell_join(std::slice::from_ref(value))
+            )
+        })
+        .collect()
+}
+
+/// Whether `key` can follow `export` without the shell reading anything else.
+fn is_env_name(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|first| first == '_' || first.is_ascii_alphabetic())
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+}
+
+/// Fetches `args.collect` from the host into `dir/remote` with one `sftp` batch.
+///
+/// Paths may be globs; ones that match nothing are skipped.
+fn collect(args: &SshRunArgs, dir: &Path) -> Result<(), Box<dyn Error>> {
+    let local = dir.join(REMOTE_ARTIFACTS_DIR);
+    fs::create_dir_all(&local)?;
+    let mut batch = format!("lcd {}\n", sftp_quote(&local.to_string_lossy()));
+    for path in &args.collect {
+        // A leading `-` keeps sftp going when one path fails.
+        batch.push_str(&format!("-get -pR {}\n", sftp_quote(path)));
+    }
+
+    let mut command = Command::new("sftp");
+    command.args(["-o", "BatchMode=yes", "-q", "-b", "-"]);
+    if let Some(port) = args.port {
+        command.arg("-P").arg(port.to_string());
+    }
+    let mut child = command
+        .arg(&args.destination)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(batch.as_bytes())?;
+    }
+    let status = child.wait()?;
+    // Nothing matched: leave no empty folder behind.
+    let _ = fs::remove_dir(&local);
+    if !status.success() {
+        return Err("sftp failed".into());
+    }
+    Ok(())
+}
+
+fn sftp_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_preamble_quotes_values() {
+        let env = vec![("TOKEN".to_string(), "it's $secret".to_string())];
+        assert_eq!(
+            env_preamble(false, &env),
+            "export TOKEN='it'\\''s $secret'\n"
+        );
+        assert_eq!(env_preamble(true, &env), "{\"TOKEN\":\"it's $secret\"}\n");
+        assert!(!is_env_name("A;rm"));
+    }
+}
@@ -0,0 +1,84 @@
+use crate::cli::args::StatsArgs;
+use crate::history::{self, ScriptStats};
+use crate::workspace::Workspace;
+use std::error::Error;
+use std::path::PathBuf;
+
+pub fn run(scripts_dir: PathBuf, args: StatsArgs) -> Result<(), Box<dyn Error>> {
+    let workspace = Workspace::new(scripts_dir);
+    let entries = history::load_entries(&workspace)?;
+    let mut stats = history::script_stats(&entries);
+    if args.flaky {
+        stats.retain(|stat| stat.flaky);
+    }
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+    if stats.is_empty() {
+        println!(
+            "{}",
+            if args.flaky {
+                "No flaky scripts."
+            } else {
+                "No runs recorded yet."
+            }
+        );
+        return Ok(());
+    }
+    print_table(&stats);
+    let flaky = stats.iter().filter(|stat| stat.flaky).count();
+    if flaky > 0 {
+        println!();
+        println!(
+            "{} flaky script(s): recent runs with the same args both passed and failed.",
+            flaky
+        );
+    }
+    Ok(())
+}
+
+fn print_table(stats: &[ScriptStats]) {
+    let now = history::timestamp_ms();
+    let rows: Vec<[String; 6]> = stats
+        .iter()
+        .map(|stat| {
+            let success = (stat.runs - stat.failed) * 100 / stat.runs.max(1);
+            [
+                stat.script.display().to_string(),
+                stat.runs.to_string(),
+                stat.failed.to_string(),
+                format!("{}%", success),
+                stat.average_duration_ms
+                    .map(history::format_duration)
+                    .unwrap_or_else(|| "-".to_string()),
+                history::format_relative(stat.last_run, now),
+            ]
+        })
+        .collect();
+    let header = [
+        "SCRIPT", "RUNS", "FAILED", "SUCCESS", "AVG TIME", "LAST RUN",
+    ];
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    print_row(&header.map(str::to_string), &widths, "");
+    for (row, stat) in rows.iter().zip(stats) {
+        print_row(row, &widths, if stat.flaky { "flaky" } else { "" });
+    }
+}
+
+fn print_row(cells: &[String; 6], widths: &[usize; 6], badge: &str) {
+    let mut line = format!("{:<width$}", cells[0], width = widths[0]);
+    for (cell, width) in cells.iter().zip(widths).skip(1) {
+        line.push_str(&format!("  {:>width$}", cell, width = width));
+    }
+    if !badge.is_empty() {
+        line.push_str("  ");
+        line.push_str(badge);
+    }
+    println!("{}", line.trim_end());
+}
@@ -0,0 +1,313 @@
+use crate::adapters::tui;
+use crate::app_meta::APP_VERSION;
+use crate::archive;
+use crate::cli::args::SyncScriptsArgs;
+use crate::cli::update::{normalize_version_tag, resolve_repo};
+use crate::http;
+use crate::script_sync::{self, Conflict, DiffLine, Resolution, SyncAction};
+use crate::status::WARN_SYMBOL;
+use crate::util::TempDirGuard;
+use crate::workspace::Workspace;
+use std::env;
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
+
+/// Unchanged lines shown around each change by `--diff`.
+const DIFF_CONTEXT: usize = 2;
+
+/// What to do with scripts that differ from upstream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncMode {
+    /// Update untouched copies and ask about the ones edited locally.
+    Resolve,
+    /// Make every script match upstream.
+    Force,
+    /// Report what would change, and change nothing.
+    Diff,
+}
+
+pub fn run(scripts_dir: PathBuf, args: SyncScriptsArgs) -> Result<(), Box<dyn Error>> {
+    let repo = resolve_repo(args.repo);
+    let version = normalize_version_tag(args.tag.as_deref().unwrap_or(APP_VERSION));
+    let mode = if args.force {
+        SyncMode::Force
+    } else if args.diff {
+        SyncMode::Diff
+    } else {
+        SyncMode::Resolve
+    };
+
+    fs::create_dir_all(&scripts_dir)?;
+    let temp_dir = env::temp_dir().join(format!("omakure-sync-{}", std::process::id()));
+    fs::create_dir_all(&temp_dir)?;
+    let _temp_guard = TempDirGuard::new(temp_dir.clone());
+    sync_repo_scripts(&repo, &version, &scripts_dir, &temp_dir, mode)
+}
+
+/// Syncs the `scripts` folder of `repo` at tag `version` into the workspace.
+pub(crate) fn sync_repo_scripts(
+    repo: &str,
+    version: &str,
+    scripts_dir: &Path,
+    work_dir: &Path,
+    mode: SyncMode,
+) -> Result<(), Box<dyn Error>> {
+    let source_url = if cfg!(windows) {
+        format!(
+            "https://github.com/{}/archive/refs/tags/{}.zip",
+            repo, version
+        )
+    } else {
+        format!(
+            "https://github.com/{}/archive/refs/tags/{}.tar.gz",
+            repo, version
+        )
+    };
+
+    let source_archive = if cfg!(windows) {
+        work_dir.join("omakure-source.zip")
+    } else {
+        work_dir.join("omakure-source.tar.gz")
+    };
+
+    http::download(&source_url, &source_archive)?;
+
+    let source_root = work_dir.join("source");
+    fs::create_dir_all(&source_root)?;
+    archive::extract(&source_archive, &source_root)?;
+
+    let scripts_src = find_dir_named(&source_root, "scripts")
+        .ok_or("scripts folder not found in source archive")?;
+    let workspace = Workspace::new(scripts_dir.to_path_buf());
+    if mode == SyncMode::Diff {
+        return diff_files(&scripts_src, &workspace);
+    }
+    let (copied, updated) = sync_files(&scripts_src, &workspace, mode)?;
+
+    if copied > 0 {
+        println!("Copied {} script(s) to {}", copied, scripts_dir.display());
+    }
+    if updated > 0 {
+        println!("Updated {} script(s) in {}", updated, scripts_dir.display());
+    }
+    if copied == 0 && updated == 0 {
+        println!("Scripts already up to date in {}", scripts_dir.display());
+    }
+
+    Ok(())
+}
+
+/// Copies the upstream scripts in `src_dir` into the workspace and updates
+/// the ones not edited locally. Scripts edited locally and upstream alike
+/// are offered for resolution in a terminal, and kept as they are otherwise;
+/// `SyncMode::Force` overwrites every edited copy instead.
+fn sync_files(
+    src_dir: &Path,
+    workspace: &Workspace,
+    mode: SyncMode,
+) -> Result<(usize, usize), Box<dyn Error>> {
+    let base_path = workspace.sync_base_path();
+    let mut base = script_sync::load(&base_path)?;
+    let mut copied = 0;
+    let mut updated = 0;
+    let mut conflicts = Vec::new();
+    for rel in upstream_files(src_dir)? {
+        let key = sync_key(&rel);
+        let source = src_dir.join(&rel);
+        let target = workspace.root().join(&rel);
+        let upstream = fs::read(&source)?;
+        let local = read_local(&target)?;
+        match script_sync::classify(local.as_deref(), &upstream, base.get(&key)) {
+            SyncAction::Copy => {
+                copy_upstream(&source, &target)?;
+                copied += 1;
+            }
+            SyncAction::Update => {
+                copy_upstream(&source, &target)?;
+                updated += 1;
+            }
+            SyncAction::KeepLocal | SyncAction::Conflict if mode == SyncMode::Force => {
+                copy_upstream(&source, &target)?;
+                updated += 1;
+            }
+            SyncAction::UpToDate => {}
+            SyncAction::KeepLocal => continue,
+            SyncAction::Conflict => {
+                conflicts.push(Conflict {
+                    path: rel,
+                    local: String::from_utf8_lossy(&local.unwrap_or_default()).to_string(),
+                    upstream: String::from_utf8_lossy(&upstream).to_string(),
+                });
+                continue;
+            }
+        }
+        base.record(&key, &upstream);
+    }
+
+    if !conflicts.is_empty() {
+        let resolutions = if io::stdin().is_terminal() && io::stdout().is_terminal() {
+            tui::resolve_conflicts(workspace, &conflicts)?
+        } else {
+            vec![None; conflicts.len()]
+        };
+        for (conflict, resolution) in conflicts.iter().zip(resolutions) {
+            let source = src_dir.join(&conflict.path);
+            let target = workspace.root().join(&conflict.path);
+            match resolution {
+                None => {
+                    eprintln!(
+                        "{} {} changed locally and upstream; kept the local copy (run omakure sync-scripts in a terminal to resolve)",
+                        WARN_SYMBOL,
+                        conflict.path.display()
+                    );
+                    continue;
+                }
+                Some(Resolution::KeepLocal) => {}
+                Some(Resolution::TakeUpstream) => {
+                    copy_upstream(&source, &target)?;
+                    updated += 1;
+                }
+                Some(Resolution::BackupAndTake) => {
+                    let backup = script_sync::backup_path(&target);
+                    fs::rename(&target, &backup)?;
+                    copy_upstream(&source, &target)?;
+                    println!(
+                        "Backed up {} to {}",
+                        conflict.path.display(),
+                        backup.display()
+                    );
+                    updated += 1;
+                }
+            }
+            base.record(&sync_key(&conflict.path), &fs::read(&source)?);
+        }
+    }
+    script_sync::save(&base_path, &base)?;
+    Ok((copied, updated))
+}
+
+/// Prints how each workspace script differs from upstream, with a diff of
+/// the local copy against upstream.
+fn diff_files(src_dir: &Path, workspace: &Workspace) -> Result<(), Box<dyn Error>> {
+    let base = script_sync::load(&workspace.sync_base_path())?;
+    let mut differing = 0;
+    for rel in upstream_files(src_dir)? {
+        let upstream = fs::read(src_dir.join(&rel))?;
+        let local = read_local(&workspace.root().join(&rel))?;
+        let label =
+            match script_sync::classify(local.as_deref(), &upstream, base.get(&sync_key(&rel))) {
+                SyncAction::UpToDate => continue,
+                SyncAction::Copy => "new upstream",
+                SyncAction::Update => "changed upstream",
+                SyncAction::KeepLocal => "edited locally",
+                SyncAction::Conflict => "changed locally and upstream",
+            };
+        differing += 1;
+        println!("{} ({})", rel.display(), label);
+        let local = String::from_utf8_lossy(local.as_deref().unwrap_or_default()).to_string();
+        print_diff(&local, &String::from_utf8_lossy(&upstream));
+        println!();
+    }
+    if differing == 0 {
+        println!("Scripts match upstream in {}", workspace.root().display());
+    } else {
+        println!("{} script(s) differ from upstream", differing);
+    }
+    Ok(())
+}
+
+/// Changed lines, `-` local and `+` upstream, with `DIFF_CONTEXT` unchanged
+/// lines around each change.
+fn print_diff(local: &str, upstream: &str) {
+    let diff = script_sync::line_diff(local, upstream);
+    let changed: Vec<usize> = diff
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, DiffLine::Same(_)))
+        .map(|(index, _)| index)
+        .collect();
+    let near_change = |index: usize| {
+        changed
+            .iter()
+            .any(|&change| change.abs_diff(index) <= DIFF_CONTEXT)
+    };
+    let mut skipped = false;
+    for (index, line) in diff.iter().enumerate() {
+        if !near_change(index) {
+            skipped = true;
+            continue;
+        }
+        if skipped {
+            println!("  ...");
+            skipped = false;
+        }
+        match line {
+            DiffLine::Same(text) => println!("    {}", text),
+            DiffLine::Removed(text) => println!("  - {}", text),
+            DiffLine::Added(text) => println!("  + {}", text),
+        }
+    }
+}
+
+fn read_local(target: &Path) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
+    match fs::read(target) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Files under `src_dir`, relative to it and sorted.
+fn upstream_files(src_dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut stack = vec![src_dir.to_path_buf()];
+    let mut files = Vec::new();
+    while let Some(dir) = stack.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                stack.push(path);
+                continue;
+            }
+            let rel = path
+                .strip_prefix(src_dir)
+                .map_err(|_| "Failed to compute script path")?;
+            files.push(rel.to_path_buf());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// A relative script path with `/` separators on every platform.
+fn sync_key(rel: &Path) -> String {
+    rel.to_string_lossy().replace('\\', "/")
+}
+
+fn copy_upstream(source: &Path, target: &Path) -> Result<(), Box<dyn Error>> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(source, target)?;
+    Ok(())
+}
+
+fn find_dir_named(root: &Path, name: &str) -> Option<PathBuf> {
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let entries = fs::read_dir(&dir).ok()?;
+        for entry in entries {
+            let entry = entry.ok()?;
+            let path = entry.path();
+            if path.is_dir() {
+                if path.file_name() == Some(OsStr::new(name)) {
+                    return Some(path);
+                }
+                stack.push(path);
+            }
+        }
+    }
+    None
+}
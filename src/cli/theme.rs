@@ -127,9 +127,10 @@ fn print_theme_preview(name: &str, theme: &Theme) {
         format_color(theme.ui.text_muted.color())
     );
     println!(
-        "UI borders: active {}, inactive {}",
+        "UI borders: active {}, inactive {}, style {}",
         format_color(theme.ui.border_active.color()),
-        format_color(theme.ui.border_inactive.color())
+        format_color(theme.ui.border_inactive.color()),
+        theme.ui.border.name()
     );
     println!("Selection: {}", format_color(theme.ui.selection_fg.color()));
     println!(
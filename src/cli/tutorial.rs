@@ -0,0 +1,24 @@
+use crate::tutorial;
+use crate::util::TempDirGuard;
+use std::env;
+use std::error::Error;
+use std::path::PathBuf;
+
+/// Creates the sandbox workspace for `omakure tutorial`; it is deleted when
+/// the guard drops.
+///
+/// Shell commands run from the TUI see the sandbox as their workspace and
+/// this binary as `omakure`, so the tutorial's `omakure init` lands there.
+pub fn prepare() -> Result<(PathBuf, TempDirGuard), Box<dyn Error>> {
+    let root = env::temp_dir().join(format!("omakure-tutorial-{}", std::process::id()));
+    let guard = TempDirGuard::new(root.clone());
+    tutorial::create_sandbox(&root)?;
+
+    env::set_var("OMAKURE_SCRIPTS_DIR", &root);
+    if let Some(bin_dir) = env::current_exe()?.parent() {
+        let mut paths = vec![bin_dir.to_path_buf()];
+        paths.extend(env::split_paths(&env::var_os("PATH").unwrap_or_default()));
+        env::set_var("PATH", env::join_paths(paths)?);
+    }
+    Ok((root, guard))
+}
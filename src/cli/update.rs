@@ -1,6 +1,12 @@
+use crate::archive;
 use crate::cli::args::UpdateArgs;
+use crate::cli::sync_scripts::{self, SyncMode};
+use crate::http;
+use crate::logging;
+use crate::receipt::sha256_hex;
+use crate::status::WARN_SYMBOL;
 use crate::util::{ps_quote, set_executable_permissions, TempDirGuard};
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::env;
 use std::error::Error;
 use std::ffi::OsStr;
@@ -9,8 +15,31 @@ use std::path::{Path, PathBuf};
 use std::process::Command;
 
 const DEFAULT_REPO: &str = "This-Is-NPC/omakure";
+/// Release asset listing the SHA-256 of every other asset, as `sha256sum` prints it.
+const CHECKSUMS_ASSET: &str = "SHA256SUMS";
 
 pub fn run(scripts_dir: PathBuf, args: UpdateArgs) -> Result<(), Box<dyn Error>> {
+    let rollback_requested = args.rollback;
+    let result = if rollback_requested {
+        rollback()
+    } else {
+        update(scripts_dir, args)
+    };
+    if let Err(err) = &result {
+        logging::error(
+            "update",
+            if rollback_requested {
+                "Rollback failed"
+            } else {
+                "Update failed"
+            },
+            json!({ "error": err.to_string() }),
+        );
+    }
+    result
+}
+
+fn update(scripts_dir: PathBuf, args: UpdateArgs) -> Result<(), Box<dyn Error>> {
     let repo = resolve_repo(args.repo);
     let version = match resolve_version(args.version) {
         Some(version) => normalize_version_tag(&version),
@@ -26,19 +55,34 @@ pub fn run(scripts_dir: PathBuf, args: UpdateArgs) -> Result<(), Box<dyn Error>>
     let current_version = env!("CARGO_PKG_VERSION");
     let target_version = version.trim_start_matches('v');
     let should_update = target_version != current_version;
+    logging::info(
+        "update",
+        "Update started",
+        json!({ "repo": repo, "from": current_version, "to": version }),
+    );
 
     if should_update {
         let asset = release_asset(&version)?;
-        let url = format!(
-            "https://github.com/{}/releases/download/{}/{}",
-            repo, version, asset
-        );
+        let release_url = format!("https://github.com/{}/releases/download/{}", repo, version);
         let archive_path = temp_dir.join(&asset);
-        download_to_path(&url, &archive_path)?;
+        http::download(&format!("{}/{}", release_url, asset), &archive_path)?;
+        if args.no_verify {
+            logging::warn(
+                "update",
+                "Checksum verification skipped",
+                json!({ "asset": asset }),
+            );
+            eprintln!(
+                "{} Skipping checksum verification of {}",
+                WARN_SYMBOL, asset
+            );
+        } else {
+            verify_checksum(&release_url, &asset, &archive_path)?;
+        }
 
         let extract_dir = temp_dir.join("release");
         fs::create_dir_all(&extract_dir)?;
-        extract_archive(&archive_path, &extract_dir)?;
+        archive::extract(&archive_path, &extract_dir)?;
 
         let bin_name = if cfg!(windows) {
             "omakure.exe"
@@ -47,19 +91,27 @@ pub fn run(scripts_dir: PathBuf, args: UpdateArgs) -> Result<(), Box<dyn Error>>
         };
         let new_bin = find_file(&extract_dir, bin_name)?;
         install_binary(&new_bin)?;
+        logging::info("update", "Binary updated", json!({ "version": version }));
         println!("Updated omakure to {}", version);
     } else {
         println!("omakure already on {}", version);
     }
 
-    if let Err(err) = sync_repo_scripts(&repo, &version, &scripts_dir, &temp_dir) {
+    if let Err(err) =
+        sync_scripts::sync_repo_scripts(&repo, &version, &scripts_dir, &temp_dir, SyncMode::Resolve)
+    {
+        logging::warn(
+            "update",
+            "Script sync failed",
+            json!({ "error": err.to_string() }),
+        );
         eprintln!("Warning: failed to sync scripts: {}", err);
     }
 
     Ok(())
 }
 
-fn resolve_repo(repo: Option<String>) -> String {
+pub(crate) fn resolve_repo(repo: Option<String>) -> String {
     repo.or_else(|| env::var("OMAKURE_REPO").ok())
         .or_else(|| env::var("OVERTURE_REPO").ok())
         .or_else(|| env::var("CLOUD_MGMT_REPO").ok())
@@ -71,7 +123,7 @@ fn resolve_version(version: Option<String>) -> Option<String> {
     version.or_else(|| env::var("VERSION").ok())
 }
 
-fn normalize_version_tag(version: &str) -> String {
+pub(crate) fn normalize_version_tag(version: &str) -> String {
     if version.starts_with('v') {
         version.to_string()
     } else {
@@ -81,7 +133,7 @@ fn normalize_version_tag(version: &str) -> String {
 
 fn fetch_latest_version(repo: &str) -> Result<String, Box<dyn Error>> {
     let url = format!("https://api.github.com/repos/{}/releases/latest", repo);
-    let json = download_string(&url)?;
+    let json = http::get_string(&url)?;
     let value: Value = serde_json::from_str(&json)?;
     let tag = value
         .get("tag_name")
@@ -113,97 +165,99 @@ fn release_asset(version: &str) -> Result<String, Box<dyn Error>> {
     Ok(format!("omakure-{}-{}-{}.{}", version, os, arch, ext))
 }
 
-fn download_string(url: &str) -> Result<String, Box<dyn Error>> {
-    if cfg!(windows) {
-        let script = format!("(Invoke-WebRequest -Uri {}).Content", ps_quote(url));
-        let output = Command::new("powershell")
-            .args(["-NoProfile", "-Command", &script])
-            .output()?;
-        if !output.status.success() {
-            return Err(format!("Failed to download {}", url).into());
-        }
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else if command_exists("curl") {
-        let output = Command::new("curl").args(["-fsSL", url]).output()?;
-        if !output.status.success() {
-            return Err(format!("Failed to download {}", url).into());
-        }
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else if command_exists("wget") {
-        let output = Command::new("wget").args(["-qO-", url]).output()?;
-        if !output.status.success() {
-            return Err(format!("Failed to download {}", url).into());
-        }
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err("Missing curl or wget for update".into())
+/// Checks `archive` against its line in the release's `SHA256SUMS`, so a
+/// corrupted or tampered download is never extracted.
+fn verify_checksum(release_url: &str, asset: &str, archive: &Path) -> Result<(), Box<dyn Error>> {
+    let sums = http::get_string(&format!("{}/{}", release_url, CHECKSUMS_ASSET)).map_err(|_| {
+        format!(
+            "Release has no {} to verify {} against; pass --no-verify to install anyway",
+            CHECKSUMS_ASSET, asset
+        )
+    })?;
+    let expected = expected_checksum(&sums, asset)
+        .ok_or_else(|| format!("{} does not list {}", CHECKSUMS_ASSET, asset))?;
+    let actual = sha256_hex(&fs::read(archive)?);
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(format!(
+            "Checksum mismatch for {}: expected {}, got {}. Not installing.",
+            asset, expected, actual
+        )
+        .into());
     }
+    Ok(())
 }
 
-fn download_to_path(url: &str, dest: &Path) -> Result<(), Box<dyn Error>> {
-    if cfg!(windows) {
-        let script = format!(
-            "Invoke-WebRequest -Uri {} -OutFile {}",
-            ps_quote(url),
-            ps_quote(&dest.display().to_string())
-        );
-        let status = Command::new("powershell")
-            .args(["-NoProfile", "-Command", &script])
-            .status()?;
-        if !status.success() {
-            return Err(format!("Failed to download {}", url).into());
-        }
-    } else if command_exists("curl") {
-        let status = Command::new("curl")
-            .args(["-fL", "-o", &dest.display().to_string(), url])
-            .status()?;
-        if !status.success() {
-            return Err(format!("Failed to download {}", url).into());
-        }
-    } else if command_exists("wget") {
-        let status = Command::new("wget")
-            .args(["-q", "-O", &dest.display().to_string(), url])
-            .status()?;
-        if !status.success() {
-            return Err(format!("Failed to download {}", url).into());
-        }
-    } else {
-        return Err("Missing curl or wget for update".into());
-    }
+/// The digest `sums` lists for `asset`, in `sha256sum` format: `<hex>  <name>`,
+/// or `<hex> *<name>` for binary mode.
+fn expected_checksum<'a>(sums: &'a str, asset: &str) -> Option<&'a str> {
+    sums.lines().find_map(|line| {
+        let (digest, name) = line.trim().split_once(char::is_whitespace)?;
+        let name = name.trim_start();
+        (name.strip_prefix('*').unwrap_or(name) == asset).then_some(digest)
+    })
+}
 
-    Ok(())
+/// Where an update keeps the binary it replaced: `omakure.bak`, or
+/// `omakure.bak.exe` on Windows so it still runs.
+fn backup_binary_path(target: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    let stem = target
+        .file_stem()
+        .and_then(OsStr::to_str)
+        .ok_or("Unable to determine binary name")?;
+    let name = match target.extension().and_then(OsStr::to_str) {
+        Some(ext) => format!("{}.bak.{}", stem, ext),
+        None => format!("{}.bak", stem),
+    };
+    Ok(target.with_file_name(name))
 }
 
-fn extract_archive(archive: &Path, dest: &Path) -> Result<(), Box<dyn Error>> {
+/// Swaps the running binary with the one the last update replaced, so a
+/// second rollback undoes the first.
+fn rollback() -> Result<(), Box<dyn Error>> {
+    let target = env::current_exe()?;
+    let backup = backup_binary_path(&target)?;
+    if !backup.is_file() {
+        return Err(format!("No previous binary to roll back to ({})", backup.display()).into());
+    }
+    let version = Command::new(&backup)
+        .arg("--version")
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|version| !version.is_empty())
+        .unwrap_or_else(|| "the previous version".to_string());
+    logging::info("update", "Rollback started", json!({ "to": version }));
+
     if cfg!(windows) {
+        let swap_path = target.with_extension("swap.exe");
         let script = format!(
-            "Expand-Archive -Path {} -DestinationPath {} -Force",
-            ps_quote(&archive.display().to_string()),
-            ps_quote(&dest.display().to_string())
+            "$processId = {pid}; \
+             try {{ $p = Get-Process -Id $processId -ErrorAction SilentlyContinue; if ($p) {{ $p.WaitForExit(); }} }} catch {{}}; \
+             Move-Item -Force {target} {swap}; \
+             Move-Item -Force {backup} {target}; \
+             Move-Item -Force {swap} {backup};",
+            pid = std::process::id(),
+            target = ps_quote(&target.display().to_string()),
+            swap = ps_quote(&swap_path.display().to_string()),
+            backup = ps_quote(&backup.display().to_string())
         );
-        let status = Command::new("powershell")
+        Command::new("powershell")
             .args(["-NoProfile", "-Command", &script])
-            .status()?;
-        if !status.success() {
-            return Err("Failed to extract update archive".into());
-        }
+            .spawn()?;
+        println!("Rolling back to {} after this process exits.", version);
     } else {
-        if !command_exists("tar") {
-            return Err("Missing tar for update".into());
-        }
-        let status = Command::new("tar")
-            .args([
-                "-xzf",
-                &archive.display().to_string(),
-                "-C",
-                &dest.display().to_string(),
-            ])
-            .status()?;
-        if !status.success() {
-            return Err("Failed to extract update archive".into());
+        let swap_path = target.with_file_name(format!(
+            "{}.swap",
+            target.file_name().unwrap_or_default().to_string_lossy()
+        ));
+        fs::rename(&target, &swap_path)?;
+        if let Err(err) = fs::rename(&backup, &target) {
+            let _ = fs::rename(&swap_path, &target);
+            return Err(err.into());
         }
+        fs::rename(&swap_path, &backup)?;
+        println!("Rolled back to {}", version);
     }
-
     Ok(())
 }
 
@@ -230,6 +284,10 @@ fn install_binary_unix(new_bin: &Path, target: &Path) -> Result<(), Box<dyn Erro
 
     fs::copy(new_bin, &temp_target)?;
     set_executable_permissions(&temp_target)?;
+    // Kept for `omakure update --rollback`.
+    let backup_path = backup_binary_path(target)?;
+    fs::copy(target, &backup_path)?;
+    set_executable_permissions(&backup_path)?;
 
     match fs::rename(&temp_target, target) {
         Ok(()) => Ok(()),
@@ -252,7 +310,7 @@ fn install_binary_windows(new_bin: &Path, target: &Path) -> Result<(), Box<dyn E
         .ok_or("Unable to determine binary name")?;
     let ext = target.extension().and_then(OsStr::to_str).unwrap_or("exe");
     let new_path = target_dir.join(format!("{}.new.{}", stem, ext));
-    let backup_path = target_dir.join(format!("{}.old.{}", stem, ext));
+    let backup_path = backup_binary_path(target)?;
 
     if new_path.exists() {
         let _ = fs::remove_file(&new_path);
@@ -263,8 +321,7 @@ fn install_binary_windows(new_bin: &Path, target: &Path) -> Result<(), Box<dyn E
         "$processId = {pid}; \
          try {{ $p = Get-Process -Id $processId -ErrorAction SilentlyContinue; if ($p) {{ $p.WaitForExit(); }} }} catch {{}}; \
          if (Test-Path {target}) {{ Move-Item -Force {target} {backup}; }} \
-         Move-Item -Force {new_path} {target}; \
-         if (Test-Path {backup}) {{ Remove-Item -Force {backup}; }}",
+         Move-Item -Force {new_path} {target};",
         pid = std::process::id(),
         target = ps_quote(&target.display().to_string()),
         new_path = ps_quote(&new_path.display().to_string()),
@@ -279,81 +336,6 @@ fn install_binary_windows(new_bin: &Path, target: &Path) -> Result<(), Box<dyn E
     Ok(())
 }
 
-fn sync_repo_scripts(
-    repo: &str,
-    version: &str,
-    scripts_dir: &Path,
-    work_dir: &Path,
-) -> Result<(), Box<dyn Error>> {
-    let source_url = if cfg!(windows) {
-        format!(
-            "https://github.com/{}/archive/refs/tags/{}.zip",
-            repo, version
-        )
-    } else {
-        format!(
-            "https://github.com/{}/archive/refs/tags/{}.tar.gz",
-            repo, version
-        )
-    };
-
-    let source_archive = if cfg!(windows) {
-        work_dir.join("omakure-source.zip")
-    } else {
-        work_dir.join("omakure-source.tar.gz")
-    };
-
-    download_to_path(&source_url, &source_archive)?;
-
-    let source_root = work_dir.join("source");
-    fs::create_dir_all(&source_root)?;
-    extract_archive(&source_archive, &source_root)?;
-
-    let scripts_src = find_dir_named(&source_root, "scripts")
-        .ok_or("scripts folder not found in source archive")?;
-    let (copied, skipped) = copy_missing_files(&scripts_src, scripts_dir)?;
-
-    if copied > 0 {
-        println!("Copied {} script(s) to {}", copied, scripts_dir.display());
-    } else if skipped > 0 {
-        println!("Scripts already up to date in {}", scripts_dir.display());
-    }
-
-    Ok(())
-}
-
-fn copy_missing_files(src_dir: &Path, dest_dir: &Path) -> Result<(usize, usize), Box<dyn Error>> {
-    let mut stack = vec![src_dir.to_path_buf()];
-    let mut copied = 0;
-    let mut skipped = 0;
-
-    while let Some(dir) = stack.pop() {
-        for entry in fs::read_dir(&dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_dir() {
-                stack.push(path);
-                continue;
-            }
-            let rel = path
-                .strip_prefix(src_dir)
-                .map_err(|_| "Failed to compute script path")?;
-            let target = dest_dir.join(rel);
-            if target.exists() {
-                skipped += 1;
-                continue;
-            }
-            if let Some(parent) = target.parent() {
-                fs::create_dir_all(parent)?;
-            }
-            fs::copy(&path, &target)?;
-            copied += 1;
-        }
-    }
-
-    Ok((copied, skipped))
-}
-
 fn find_file(root: &Path, name: &str) -> Result<PathBuf, Box<dyn Error>> {
     let mut stack = vec![root.to_path_buf()];
     while let Some(dir) = stack.pop() {
@@ -372,24 +354,25 @@ fn find_file(root: &Path, name: &str) -> Result<PathBuf, Box<dyn Error>> {
     Err(format!("{} not found in archive", name).into())
 }
 
-fn find_dir_named(root: &Path, name: &str) -> Option<PathBuf> {
-    let mut stack = vec![root.to_path_buf()];
-    while let Some(dir) = stack.pop() {
-        let entries = fs::read_dir(&dir).ok()?;
-        for entry in entries {
-            let entry = entry.ok()?;
-            let path = entry.path();
-            if path.is_dir() {
-                if path.file_name() == Some(OsStr::new(name)) {
-                    return Some(path);
-                }
-                stack.push(path);
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_checksum() {
+        let sums = "abc123  omakure-v1.0.0-linux-x86_64.tar.gz\n\
+                    def456 *omakure-v1.0.0-windows-x86_64.zip\n";
+        assert_eq!(
+            expected_checksum(sums, "omakure-v1.0.0-linux-x86_64.tar.gz"),
+            Some("abc123")
+        );
+        assert_eq!(
+            expected_checksum(sums, "omakure-v1.0.0-windows-x86_64.zip"),
+            Some("def456")
+        );
+        assert_eq!(
+            expected_checksum(sums, "omakure-v1.0.0-darwin-x86_64.tar.gz"),
+            None
+        );
     }
-    None
-}
-
-fn command_exists(cmd: &str) -> bool {
-    Command::new(cmd).arg("--version").output().is_ok()
 }
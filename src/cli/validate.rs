@@ -0,0 +1,128 @@
+use crate::adapters::workspace_repository::FsWorkspaceRepository;
+use crate::cli::args::ValidateArgs;
+use crate::domain::{lint_schema, SchemaProblem};
+use crate::ports::ScriptRepository;
+use crate::runtime::{script_kind, sidecar_schema_paths, ScriptKind};
+use crate::status::{FAIL_SYMBOL, OK_SYMBOL};
+use serde::Serialize;
+use serde_json::Value;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+#[derive(Serialize)]
+struct Report {
+    checked: usize,
+    problems: Vec<ScriptProblem>,
+}
+
+#[derive(Serialize)]
+struct ScriptProblem {
+    script: String,
+    #[serde(flatten)]
+    problem: SchemaProblem,
+}
+
+pub fn run(scripts_dir: PathBuf, args: ValidateArgs) -> Result<(), Box<dyn Error>> {
+    let repo = FsWorkspaceRepository::new(scripts_dir.clone());
+    let mut report = Report {
+        checked: 0,
+        problems: Vec::new(),
+    };
+    let mut scripts = repo.list_scripts_recursive()?;
+    scripts.sort();
+    for script in scripts {
+        // Makefile targets and justfile recipes get a schema derived from the file.
+        if matches!(
+            script_kind(&script),
+            Some(ScriptKind::Make | ScriptKind::Just)
+        ) {
+            continue;
+        }
+        report.checked += 1;
+        let relative = script
+            .strip_prefix(&scripts_dir)
+            .unwrap_or(&script)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let problems = match repo.schema_source(&script) {
+            Some(source) => match schema_value(&script, &source) {
+                Ok(value) => lint_schema(&value),
+                Err(message) => vec![SchemaProblem {
+                    field: None,
+                    message,
+                }],
+            },
+            None => vec![SchemaProblem {
+                field: None,
+                message: "No OMAKURE_SCHEMA_START/OMAKURE_SCHEMA_END block".to_string(),
+            }],
+        };
+        report
+            .problems
+            .extend(problems.into_iter().map(|problem| ScriptProblem {
+                script: relative.clone(),
+                problem,
+            }));
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+    if !report.problems.is_empty() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// The schema text of `script` as JSON: TOML sidecars are converted, and
+/// JSON is read from its first `{` as `parse_schema` does.
+fn schema_value(script: &Path, source: &str) -> Result<Value, String> {
+    let toml_sidecar = sidecar_schema_paths(script)
+        .into_iter()
+        .find(|path| path.is_file())
+        .is_some_and(|path| path.extension().is_some_and(|ext| ext == "toml"));
+    if toml_sidecar {
+        let value: toml::Value =
+            toml::from_str(source).map_err(|err| format!("Invalid TOML: {}", err))?;
+        return serde_json::to_value(value).map_err(|err| err.to_string());
+    }
+    let start = source.find('{').ok_or("No JSON object in the schema")?;
+    serde_json::Deserializer::from_str(&source[start..])
+        .into_iter::<Value>()
+        .next()
+        .unwrap_or(Ok(Value::Null))
+        .map_err(|err| format!("Invalid JSON: {}", err))
+}
+
+fn print_report(report: &Report) {
+    let mut current: Option<&str> = None;
+    for item in &report.problems {
+        if current != Some(item.script.as_str()) {
+            println!("{}", item.script);
+            current = Some(item.script.as_str());
+        }
+        match &item.problem.field {
+            Some(field) => println!("  {} {}: {}", FAIL_SYMBOL, field, item.problem.message),
+            None => println!("  {} {}", FAIL_SYMBOL, item.problem.message),
+        }
+    }
+    if report.problems.is_empty() {
+        println!("{} {} schemas valid", OK_SYMBOL, report.checked);
+    } else {
+        let mut scripts: Vec<&str> = report
+            .problems
+            .iter()
+            .map(|item| item.script.as_str())
+            .collect();
+        scripts.dedup();
+        println!(
+            "{} {} problems in {} of {} scripts",
+            FAIL_SYMBOL,
+            report.problems.len(),
+            scripts.len(),
+            report.checked
+        );
+    }
+}
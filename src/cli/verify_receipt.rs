@@ -0,0 +1,80 @@
+use crate::cli::args::VerifyReceiptArgs;
+use crate::config::load_workspace_config;
+use crate::history;
+use crate::receipt::{self, RECEIPT_FILE};
+use crate::status::{FAIL_SYMBOL, OK_SYMBOL, WARN_SYMBOL};
+use crate::workspace::Workspace;
+use std::error::Error;
+use std::path::PathBuf;
+
+pub fn run(scripts_dir: PathBuf, args: VerifyReceiptArgs) -> Result<(), Box<dyn Error>> {
+    let path = if args.receipt.is_dir() {
+        args.receipt.join(RECEIPT_FILE)
+    } else {
+        args.receipt
+    };
+    let receipt = receipt::read(&path)?;
+    let workspace = Workspace::new(scripts_dir);
+    let time_zone = load_workspace_config(workspace.config_path()).time_zone();
+
+    println!("Receipt: {}", path.display());
+    println!("  script: {}", receipt.script);
+    println!(
+        "  run at: {} (history id {})",
+        history::format_iso8601(receipt.timestamp, time_zone.offset_at(receipt.timestamp)),
+        receipt.timestamp
+    );
+    let exit = receipt
+        .exit_code
+        .map(|code| format!(", exit {}", code))
+        .unwrap_or_default();
+    let duration = receipt
+        .duration_ms
+        .map(|ms| format!(", {}", history::format_duration(ms)))
+        .unwrap_or_default();
+    println!("  status: {}{}{}", receipt.status, exit, duration);
+
+    let key = receipt::load_key()?.ok_or_else(|| {
+        format!(
+            "No receipt key at {}; copy the signer's key there to verify",
+            receipt::key_path()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default()
+        )
+    })?;
+    if !receipt.verify(&key) {
+        println!("  signature: {} invalid", FAIL_SYMBOL);
+        if receipt.key_id != receipt::key_id(&key) {
+            println!(
+                "    signed with key {}, this key is {}",
+                receipt.key_id,
+                receipt::key_id(&key)
+            );
+        }
+        return Err("The receipt was not signed with this key or was changed after signing".into());
+    }
+    println!("  signature: {} valid (key {})", OK_SYMBOL, receipt.key_id);
+
+    let entries = history::load_entries(&workspace).unwrap_or_default();
+    let Some(entry) = entries.iter().find(|entry| {
+        entry.timestamp == receipt.timestamp && entry.script.to_string_lossy() == receipt.script
+    }) else {
+        println!(
+            "  history: {} run not found in {}; output not checked",
+            WARN_SYMBOL,
+            workspace.root().display()
+        );
+        return Ok(());
+    };
+    let mismatches = receipt.mismatches(entry, &receipt::full_output(&workspace, entry));
+    if !mismatches.is_empty() {
+        println!(
+            "  history: {} differs in {}",
+            FAIL_SYMBOL,
+            mismatches.join(", ")
+        );
+        return Err("History no longer matches the receipt".into());
+    }
+    println!("  history: {} matches", OK_SYMBOL);
+    Ok(())
+}
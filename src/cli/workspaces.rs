@@ -0,0 +1,98 @@
+use crate::cli::args::{WorkspacesArgs, WorkspacesCommand};
+use crate::theme_config;
+use crate::workspace_profiles;
+use std::env;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+pub fn run(scripts_dir: PathBuf, args: WorkspacesArgs) -> Result<(), Box<dyn Error>> {
+    let config_path = theme_config::theme_layout()
+        .ok_or("Unable to resolve config directory")?
+        .config_path;
+    match args.command.unwrap_or(WorkspacesCommand::List) {
+        WorkspacesCommand::List => list(&config_path, &scripts_dir),
+        WorkspacesCommand::Add(args) => {
+            validate_name(&args.name)?;
+            let dir = if args.path.is_absolute() {
+                args.path
+            } else {
+                env::current_dir()?.join(args.path)
+            };
+            workspace_profiles::add(&config_path, &args.name, &dir)?;
+            println!("Added workspace '{}': {}", args.name, dir.display());
+            if !dir.is_dir() {
+                println!(
+                    "{} does not exist yet; omakure creates it on first start",
+                    dir.display()
+                );
+            }
+            Ok(())
+        }
+        WorkspacesCommand::Remove(args) => {
+            workspace_profiles::read(&config_path)?.resolve(&args.name)?;
+            workspace_profiles::remove(&config_path, &args.name)?;
+            println!("Removed workspace '{}'", args.name);
+            Ok(())
+        }
+        WorkspacesCommand::Default(args) => {
+            workspace_profiles::read(&config_path)?.resolve(&args.name)?;
+            workspace_profiles::set_default(&config_path, &args.name)?;
+            println!("Default workspace set to '{}'", args.name);
+            Ok(())
+        }
+    }
+}
+
+fn list(config_path: &Path, scripts_dir: &Path) -> Result<(), Box<dyn Error>> {
+    let profiles = workspace_profiles::read(config_path)?;
+    if profiles.workspaces.is_empty() {
+        println!("No named workspaces. Add one with: omakure workspaces add <NAME> <PATH>");
+        return Ok(());
+    }
+    let width = profiles
+        .workspaces
+        .keys()
+        .map(String::len)
+        .max()
+        .unwrap_or(0);
+    for (name, dir) in &profiles.workspaces {
+        let mut notes = Vec::new();
+        if profiles.default.as_deref() == Some(name) {
+            notes.push("default");
+        }
+        if dir == scripts_dir {
+            notes.push("current");
+        }
+        let marker = if dir == scripts_dir { "*" } else { " " };
+        let notes = if notes.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", notes.join(", "))
+        };
+        println!(
+            "{} {:width$}  {}{}",
+            marker,
+            name,
+            dir.display(),
+            notes,
+            width = width
+        );
+    }
+    Ok(())
+}
+
+/// Names go on the command line and in TOML keys, so keep them plain.
+fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid workspace name '{}'; use letters, digits, '-', '_', or '.'",
+            name
+        ))
+    }
+}
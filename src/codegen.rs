@@ -0,0 +1,210 @@
+use crate::domain::{field_arg, Field};
+use crate::runtime::ScriptKind;
+use crate::util::{bash_quote, ps_quote};
+
+/// Comment lines around the generated argument parser.
+pub const ARGS_START: &str = "OMAKURE_ARGS_START";
+pub const ARGS_END: &str = "OMAKURE_ARGS_END";
+
+const GENERATED_NOTE: &str =
+    "# Generated from the schema by `omakure codegen args`; edit the schema, not this block.";
+
+/// Argument parser for `fields` in the script's language, without the markers.
+///
+/// Every field becomes a variable holding its default, set by `<arg> <value>`;
+/// any other argument is an error.
+pub fn args_parser(fields: &[Field], kind: ScriptKind) -> Result<String, String> {
+    let mut fields: Vec<&Field> = fields.iter().collect();
+    fields.sort_by_key(|field| field.order);
+    match kind {
+        ScriptKind::Bash => Ok(bash_parser(&fields)),
+        ScriptKind::PowerShell => Ok(powershell_parser(&fields)),
+        ScriptKind::Python => Ok(python_parser(&fields)),
+        ScriptKind::Workflow => Err("Workflow actions have no argument parser".to_string()),
+        ScriptKind::Executable => Err("Executables have no argument parser".to_string()),
+        ScriptKind::Custom => {
+            Err("Scripts run through [runner.extensions] have no argument parser".to_string())
+        }
+        ScriptKind::Make | ScriptKind::Just => {
+            Err("Make and just targets have no argument parser".to_string())
+        }
+    }
+}
+
+/// Swaps the lines between the `OMAKURE_ARGS_START` and `OMAKURE_ARGS_END`
+/// comments for `parser`, keeping everything else as it is.
+pub fn replace_args_section(contents: &str, parser: &str) -> Result<String, String> {
+    let is_marker = |line: &str, marker: &str| line.trim().trim_start_matches('#').trim() == marker;
+    let lines: Vec<&str> = contents.split_inclusive('\n').collect();
+    let missing = || {
+        format!(
+            "No `# {}` / `# {}` lines found; add them where the argument parser goes",
+            ARGS_START, ARGS_END
+        )
+    };
+    let start = lines
+        .iter()
+        .position(|line| is_marker(line, ARGS_START))
+        .ok_or_else(missing)?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .position(|line| is_marker(line, ARGS_END))
+            .ok_or_else(missing)?;
+    let newline = if lines[start].ends_with("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+
+    let mut out: String = lines[..=start].concat();
+    for line in parser.lines() {
+        out.push_str(line);
+        out.push_str(newline);
+    }
+    out.push_str(&lines[end..].concat());
+    Ok(out)
+}
+
+fn bash_parser(fields: &[&Field]) -> String {
+    let mut out = format!("{}\n", GENERATED_NOTE);
+    for field in fields {
+        out.push_str(&format!(
+            "{}={}\n",
+            variable(&field.name, Case::Upper),
+            bash_quote(field.default.as_deref().unwrap_or(""))
+        ));
+    }
+    out.push_str("\nwhile [[ $# -gt 0 ]]; do\n  case \"$1\" in\n");
+    for field in fields {
+        out.push_str(&format!(
+            "    {})\n      {}=\"${{2:-}}\"\n      shift 2\n      ;;\n",
+            field_arg(field),
+            variable(&field.name, Case::Upper)
+        ));
+    }
+    out.push_str(
+        "    *)\n      echo \"Unknown arg: $1\" >&2\n      exit 1\n      ;;\n  esac\ndone\n",
+    );
+    out
+}
+
+fn powershell_parser(fields: &[&Field]) -> String {
+    let mut out = format!("{}\n", GENERATED_NOTE);
+    for field in fields {
+        out.push_str(&format!(
+            "${} = {}\n",
+            variable(&field.name, Case::Pascal),
+            ps_quote(field.default.as_deref().unwrap_or(""))
+        ));
+    }
+    out.push_str("for ($i = 0; $i -lt $args.Length; $i++) {\n  switch ($args[$i]) {\n");
+    for field in fields {
+        out.push_str(&format!(
+            "    {} {{\n      ${} = $args[$i + 1]\n      $i++\n    }}\n",
+            ps_quote(&field_arg(field)),
+            variable(&field.name, Case::Pascal)
+        ));
+    }
+    out.push_str(
+        "    default {\n      Write-Error \"Unknown arg: $($args[$i])\"\n      exit 1\n    }\n  }\n}\n",
+    );
+    out
+}
+
+fn python_parser(fields: &[&Field]) -> String {
+    let quote = |value: &str| serde_json::to_string(value).unwrap_or_default();
+    let mut out = format!(
+        "{}\nimport argparse\n\nparser = argparse.ArgumentParser()\n",
+        GENERATED_NOTE
+    );
+    for field in fields {
+        out.push_str(&format!(
+            "parser.add_argument({}, dest={}, default={})\n",
+            quote(&field_arg(field)),
+            quote(&variable(&field.name, Case::Lower)),
+            quote(field.default.as_deref().unwrap_or(""))
+        ));
+    }
+    out.push_str("args = parser.parse_args()\n");
+    out
+}
+
+enum Case {
+    Upper,
+    Lower,
+    Pascal,
+}
+
+/// A variable name for a field: `dry-run` gives `DRY_RUN`, `dry_run`, or `DryRun`.
+fn variable(name: &str, case: Case) -> String {
+    let words = name
+        .split(|ch: char| !ch.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty());
+    let mut out = match case {
+        Case::Upper => words
+            .map(str::to_ascii_uppercase)
+            .collect::<Vec<_>>()
+            .join("_"),
+        Case::Lower => words
+            .map(str::to_ascii_lowercase)
+            .collect::<Vec<_>>()
+            .join("_"),
+        Case::Pascal => words
+            .map(|word| {
+                let mut chars = word.chars();
+                let first = chars.next().map(|ch| ch.to_ascii_uppercase());
+                first.into_iter().chain(chars).collect::<String>()
+            })
+            .collect(),
+    };
+    if out.is_empty() || out.starts_with(|ch: char| ch.is_ascii_digit()) {
+        out.insert(0, '_');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, order: u32, default: Option<&str>, arg: Option<&str>) -> Field {
+        Field {
+            name: name.to_string(),
+            prompt: None,
+            kind: "string".to_string(),
+            order,
+            required: None,
+            default: default.map(str::to_string),
+            choices: None,
+            arg: arg.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_args_parser_replaces_marked_section() {
+        let fields = vec![
+            field("dry-run", 2, Some("false"), None),
+            field("target", 1, Some("$HOME"), Some("--target")),
+        ];
+        let parser = args_parser(&fields, ScriptKind::Bash).unwrap();
+        let script = "#!/usr/bin/env bash\n# OMAKURE_ARGS_START\nOLD=1\n# OMAKURE_ARGS_END\necho \"$TARGET\"\n";
+        let updated = replace_args_section(script, &parser).unwrap();
+        assert!(updated.starts_with("#!/usr/bin/env bash\n# OMAKURE_ARGS_START\n# Generated"));
+        assert!(updated.contains("TARGET=\"\\$HOME\"\nDRY_RUN=\"false\"\n"));
+        assert!(updated.contains("    --dry-run)\n      DRY_RUN=\"${2:-}\"\n"));
+        assert!(updated.ends_with("done\n# OMAKURE_ARGS_END\necho \"$TARGET\"\n"));
+        assert!(!updated.contains("OLD=1"));
+        assert_eq!(replace_args_section(&updated, &parser).unwrap(), updated);
+
+        let powershell = args_parser(&fields, ScriptKind::PowerShell).unwrap();
+        assert!(powershell.contains("$DryRun = 'false'\n"));
+        assert!(powershell.contains("    '--dry-run' {\n      $DryRun = $args[$i + 1]\n"));
+        let python = args_parser(&fields, ScriptKind::Python).unwrap();
+        assert!(python
+            .contains("parser.add_argument(\"--dry-run\", dest=\"dry_run\", default=\"false\")\n"));
+
+        assert!(replace_args_section("echo hi\n", &parser).is_err());
+        assert!(args_parser(&fields, ScriptKind::Workflow).is_err());
+    }
+}
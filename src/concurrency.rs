@@ -0,0 +1,264 @@
+use crate::util::read_dir_or_empty;
+use crate::workspace::Workspace;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const TICKET_EXTENSION: &str = "ticket";
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A run waiting for, or holding, its concurrency group.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedJob {
+    pub group: String,
+    pub script: PathBuf,
+    pub pid: u32,
+    pub queued_at_ms: u128,
+    /// `0` for the run holding the group, otherwise its place in line.
+    pub position: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TicketFile {
+    script: PathBuf,
+    pid: u32,
+    queued_at_ms: u128,
+}
+
+/// A place in a concurrency group's queue, released on drop.
+///
+/// Tickets are files under `.history/queue/<group>/`, so runs started from
+/// the TUI and the CLI line up in the same queue.
+#[derive(Debug)]
+pub struct GroupTicket {
+    path: PathBuf,
+}
+
+impl GroupTicket {
+    pub fn enqueue(workspace: &Workspace, group: &str, script: &Path) -> io::Result<Self> {
+        let dir = group_dir(workspace, group);
+        fs::create_dir_all(&dir)?;
+        let queued_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let pid = std::process::id();
+        let ticket = TicketFile {
+            script: script.to_path_buf(),
+            pid,
+            queued_at_ms: queued_at.as_millis(),
+        };
+        let path = dir.join(format!(
+            "{:020}-{}.{}",
+            queued_at.as_nanos(),
+            pid,
+            TICKET_EXTENSION
+        ));
+        let contents = serde_json::to_vec(&ticket).map_err(io::Error::other)?;
+        fs::write(&path, contents)?;
+        Ok(Self { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Blocks until every ticket queued ahead of this one is released.
+    pub fn wait(&self) {
+        while queue_position(&self.path).unwrap_or(0) > 0 {
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+impl Drop for GroupTicket {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Place of `ticket` in its group; `0` means it holds the group.
+///
+/// Returns `None` once the ticket file is gone.
+pub fn queue_position(ticket: &Path) -> Option<usize> {
+    let dir = ticket.parent()?;
+    let tickets = live_tickets(dir).ok()?;
+    tickets.iter().position(|(path, _)| path == ticket)
+}
+
+/// Every queued or running job, grouped by concurrency group in queue order.
+pub fn list_jobs(workspace: &Workspace) -> io::Result<Vec<QueuedJob>> {
+    let root = workspace.queue_dir();
+    let read_dir = match fs::read_dir(&root) {
+        Ok(read_dir) => read_dir,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut groups = Vec::new();
+    for entry in read_dir {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            groups.push(entry.path());
+        }
+    }
+    groups.sort();
+
+    let mut jobs = Vec::new();
+    for dir in groups {
+        let group = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        for (position, (_, ticket)) in live_tickets(&dir)?.into_iter().enumerate() {
+            jobs.push(QueuedJob {
+                group: group.clone(),
+                script: ticket.script,
+                pid: ticket.pid,
+                queued_at_ms: ticket.queued_at_ms,
+                position,
+            });
+        }
+    }
+    Ok(jobs)
+}
+
+/// Tickets left behind by processes that exited, then group folders with no
+/// tickets at all.
+pub fn stale_entries(workspace: &Workspace) -> io::Result<Vec<PathBuf>> {
+    let mut stale = Vec::new();
+    for group in read_dir_or_empty(&workspace.queue_dir())? {
+        let dir = group.path();
+        if !dir.is_dir() {
+            continue;
+        }
+        let mut live = 0;
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let ticket = fs::read(&path)
+                .ok()
+                .and_then(|bytes| serde_json::from_slice::<TicketFile>(&bytes).ok());
+            match ticket {
+                Some(ticket) if !process_alive(ticket.pid) => stale.push(path),
+                // Unreadable files may be tickets still being written.
+                _ => live += 1,
+            }
+        }
+        if live == 0 {
+            stale.push(dir);
+        }
+    }
+    Ok(stale)
+}
+
+fn group_dir(workspace: &Workspace, group: &str) -> PathBuf {
+    let name: String = group
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' || ch == '.' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    workspace.queue_dir().join(name)
+}
+
+/// Tickets in queue order, removing those left behind by exited processes.
+fn live_tickets(dir: &Path) -> io::Result<Vec<(PathBuf, TicketFile)>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) == Some(TICKET_EXTENSION) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut tickets = Vec::new();
+    for path in paths {
+        let ticket = match fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<TicketFile>(&bytes).ok())
+        {
+            Some(ticket) => ticket,
+            None => continue,
+        };
+        if process_alive(ticket.pid) {
+            tickets.push((path, ticket));
+        } else {
+            let _ = fs::remove_file(&path);
+        }
+    }
+    Ok(tickets)
+}
+
+#[cfg(unix)]
+pub(crate) fn process_alive(pid: u32) -> bool {
+    if pid == std::process::id() {
+        return true;
+    }
+    // SAFETY: signal 0 only checks that the process exists.
+    let result = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    result == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(windows)]
+pub(crate) fn process_alive(pid: u32) -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, STILL_ACTIVE};
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    if pid == std::process::id() {
+        return true;
+    }
+    // SAFETY: the handle is checked before use and closed once.
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return false;
+        }
+        let mut code = 0u32;
+        let ok = GetExitCodeProcess(handle, &mut code) != 0;
+        CloseHandle(handle);
+        ok && code == STILL_ACTIVE as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tickets_queue_in_order_and_release_on_drop() {
+        let root = std::env::temp_dir().join(format!("omakure-queue-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let workspace = Workspace::isolated(root.clone());
+
+        let first = GroupTicket::enqueue(&workspace, "dns-changes", Path::new("a.sh")).unwrap();
+        let second = GroupTicket::enqueue(&workspace, "dns-changes", Path::new("b.sh")).unwrap();
+        let other = GroupTicket::enqueue(&workspace, "backups", Path::new("c.sh")).unwrap();
+        assert_eq!(queue_position(first.path()), Some(0));
+        assert_eq!(queue_position(second.path()), Some(1));
+        assert_eq!(queue_position(other.path()), Some(0));
+
+        let jobs = list_jobs(&workspace).unwrap();
+        let summary: Vec<_> = jobs
+            .iter()
+            .map(|job| (job.group.as_str(), job.position))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("backups", 0), ("dns-changes", 0), ("dns-changes", 1)]
+        );
+
+        drop(first);
+        assert_eq!(queue_position(second.path()), Some(0));
+        second.wait();
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
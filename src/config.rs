@@ -0,0 +1,575 @@
+use crate::adapters::process_limits::validate_io_priority;
+use crate::adapters::system_checks::ensure_runtime_installed;
+use crate::adapters::tui::theme::builtin_theme_contents;
+use crate::app_meta::APP_VERSION;
+use crate::cron::Schedule;
+use crate::domain::{Hooks, RunLimits};
+use crate::history::TimeZone;
+use crate::keymap::{KeyList, Keymap};
+use crate::lua_widget::ExecPolicy;
+use crate::omaken_manifest::parse_version;
+use crate::ports::{Backend, Interpreters, KubernetesOptions, RunOptions};
+use crate::runtime::{is_builtin_extension, CustomExtension};
+use crate::theme_config;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// Workspace settings read from `omakure.toml`.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct WorkspaceConfig {
+    #[serde(default)]
+    pub workspace: WorkspaceSection,
+    #[serde(default)]
+    pub theme: ThemeSection,
+    #[serde(default)]
+    pub runner: RunnerConfig,
+    #[serde(default)]
+    pub history: HistoryConfig,
+    #[serde(default)]
+    pub kubernetes: KubernetesConfig,
+    /// `[preflight.<name>]` tables, keyed by check name.
+    #[serde(default)]
+    pub preflight: BTreeMap<String, PreflightConfig>,
+    /// `[keys]`: TUI action name to a key or list of keys.
+    #[serde(default)]
+    pub keys: BTreeMap<String, KeyList>,
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    #[serde(default)]
+    pub digest: DigestConfig,
+    #[serde(default)]
+    pub omaken: OmakenConfig,
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    #[serde(default)]
+    pub widgets: WidgetsConfig,
+}
+
+/// The `[workspace]` table.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct WorkspaceSection {
+    /// omakure version that created the workspace.
+    pub version: Option<String>,
+    /// Display name for the workspace.
+    pub name: Option<String>,
+}
+
+/// The `[theme]` table; overrides the global theme for this workspace.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct ThemeSection {
+    pub name: Option<String>,
+}
+
+/// The `[runner]` table: defaults applied to every script run.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct RunnerConfig {
+    pub nice: Option<i32>,
+    pub io_priority: Option<String>,
+    pub max_memory_mb: Option<u64>,
+    pub max_open_files: Option<u64>,
+    /// Warn when a run takes this many times its `ExpectedDurationSeconds`.
+    pub overdue_factor: Option<u64>,
+    /// `local` (default) or `kubernetes`; a script's `Backend` overrides it.
+    pub backend: Option<String>,
+    /// Set the active env file's variables for script runs; on by default.
+    pub pass_active_env: Option<bool>,
+    /// Stop runs that take longer than this many seconds.
+    pub timeout_seconds: Option<u64>,
+    #[serde(default)]
+    pub interpreters: InterpretersConfig,
+    /// `[runner.extensions]`: script extension to the command line running it.
+    #[serde(default)]
+    pub extensions: BTreeMap<String, ExtensionConfig>,
+}
+
+/// The `[runner.interpreters]` table: programs that run each script type,
+/// by name on `PATH` or by path.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct InterpretersConfig {
+    pub bash: Option<String>,
+    pub powershell: Option<String>,
+    pub python: Option<String>,
+}
+
+/// A `[runner.extensions]` entry: a command line such as `"deno run"`, or a
+/// table that also names the comment prefix of the schema block.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum ExtensionConfig {
+    Command(CommandLine),
+    Table {
+        command: CommandLine,
+        comment: Option<String>,
+    },
+}
+
+/// A command line split on whitespace, or a list of arguments taken as they
+/// are (for paths with spaces).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum CommandLine {
+    Line(String),
+    Args(Vec<String>),
+}
+
+impl ExtensionConfig {
+    fn command(&self) -> Vec<String> {
+        let line = match self {
+            ExtensionConfig::Command(line) | ExtensionConfig::Table { command: line, .. } => line,
+        };
+        match line {
+            CommandLine::Line(line) => line.split_whitespace().map(str::to_string).collect(),
+            CommandLine::Args(args) => args.clone(),
+        }
+    }
+
+    fn comment(&self) -> &str {
+        match self {
+            ExtensionConfig::Table {
+                comment: Some(comment),
+                ..
+            } => comment,
+            _ => "#",
+        }
+    }
+}
+
+/// The `[kubernetes]` table, used by runs on the `kubernetes` backend.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct KubernetesConfig {
+    pub context: Option<String>,
+    pub namespace: Option<String>,
+    pub image: Option<String>,
+}
+
+/// A `[preflight.<name>]` table: a custom credential check, or overrides for
+/// a built-in one.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct PreflightConfig {
+    /// Shell command that exits non-zero when credentials are missing or expired.
+    pub command: Option<String>,
+    /// Workspace script offered when the check fails.
+    pub login: Option<String>,
+}
+
+/// The `[notifications]` table: channels digests are delivered to.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct NotificationsConfig {
+    /// URLs receiving a `{"text": ...}` POST, like Slack incoming webhooks;
+    /// `$NAME` reads the URL from that environment variable.
+    #[serde(default)]
+    pub webhooks: Vec<String>,
+    /// Shell command receiving the message on stdin.
+    pub command: Option<String>,
+}
+
+/// The `[digest]` table.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct DigestConfig {
+    /// Cron schedule for `omakure digest --watch`, e.g. `0 8 * * 1-5`.
+    pub schedule: Option<String>,
+}
+
+/// The `[omaken]` table.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct OmakenConfig {
+    /// URL or workspace-relative path of the catalog `omakure browse` reads.
+    pub catalog: Option<String>,
+}
+
+/// The `[hooks]` table: shell commands run around every script run, before
+/// and after the script's own `Hooks`.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct HooksConfig {
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// The `[widgets]` table: what `omakure.exec` in Lua widgets may run.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct WidgetsConfig {
+    /// Programs widgets may start, by name on `PATH` or by path.
+    #[serde(default)]
+    pub commands: Vec<String>,
+    /// Environment variables passed to them, besides `PATH`, `HOME`, and the locale.
+    #[serde(default)]
+    pub env: Vec<String>,
+}
+
+/// The `[history]` table.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct HistoryConfig {
+    /// `local`, `utc`, or a fixed offset such as `+02:00`.
+    pub timezone: Option<String>,
+    /// Size cap for the stdout and stderr stored in history, in kilobytes.
+    pub max_output_kb: Option<usize>,
+    /// Print the runs of a TUI session to the terminal when it quits.
+    pub exit_summary: Option<bool>,
+    /// Write a signed `receipt.json` into each run's artifacts folder.
+    pub receipts: Option<bool>,
+    /// Drop runs older than this many days when a new run is recorded.
+    pub retention_days: Option<u64>,
+    /// Keep at most this many runs, dropping the oldest.
+    pub max_entries: Option<u64>,
+}
+
+/// An unknown key or invalid value in `omakure.toml`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ConfigProblem {
+    /// Dotted key, such as `runner.timeout_seconds`.
+    pub key: String,
+    pub message: String,
+}
+
+impl ConfigProblem {
+    fn new(key: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            message: message.into(),
+        }
+    }
+}
+
+const DEFAULT_MAX_OUTPUT_KB: usize = 1024;
+pub(crate) const DEFAULT_OVERDUE_FACTOR: u64 = 2;
+
+impl WorkspaceConfig {
+    pub fn run_options(&self) -> RunOptions {
+        RunOptions {
+            limits: RunLimits {
+                nice: self.runner.nice,
+                io_priority: self.runner.io_priority.clone(),
+                max_memory_mb: self.runner.max_memory_mb,
+                max_open_files: self.runner.max_open_files,
+            },
+            max_output_bytes: Some(self.max_output_kb().saturating_mul(1024)),
+            backend: self
+                .runner
+                .backend
+                .as_deref()
+                .and_then(Backend::parse)
+                .unwrap_or_default(),
+            kubernetes: KubernetesOptions {
+                context: self.kubernetes.context.clone(),
+                namespace: self.kubernetes.namespace.clone(),
+                image: self.kubernetes.image.clone(),
+            },
+            timeout: self.timeout(),
+            interpreters: self.interpreters(),
+            ..RunOptions::default()
+        }
+    }
+
+    pub fn hooks(&self) -> Hooks {
+        Hooks {
+            before: self.hooks.before.clone(),
+            after: self.hooks.after.clone(),
+        }
+    }
+
+    pub fn max_output_kb(&self) -> usize {
+        self.history.max_output_kb.unwrap_or(DEFAULT_MAX_OUTPUT_KB)
+    }
+
+    pub fn interpreters(&self) -> Interpreters {
+        Interpreters {
+            bash: self.runner.interpreters.bash.clone(),
+            powershell: self.runner.interpreters.powershell.clone(),
+            python: self.runner.interpreters.python.clone(),
+        }
+    }
+
+    /// The `[runner.extensions]` entries, for `runtime::set_custom_extensions`.
+    pub fn custom_extensions(&self) -> Vec<CustomExtension> {
+        self.runner
+            .extensions
+            .iter()
+            .map(|(extension, entry)| CustomExtension {
+                extension: extension.trim_start_matches('.').to_ascii_lowercase(),
+                command: entry.command(),
+                comment: entry.comment().to_string(),
+            })
+            .collect()
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.runner
+            .timeout_seconds
+            .filter(|seconds| *seconds > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// Runs older than this are dropped from history.
+    pub fn retention(&self) -> Option<Duration> {
+        self.history
+            .retention_days
+            .filter(|days| *days > 0)
+            .map(|days| Duration::from_secs(days.saturating_mul(24 * 60 * 60)))
+    }
+
+    pub fn max_entries(&self) -> Option<u64> {
+        self.history.max_entries.filter(|count| *count > 0)
+    }
+
+    pub fn overdue_factor(&self) -> u64 {
+        self.runner
+            .overdue_factor
+            .filter(|factor| *factor > 0)
+            .unwrap_or(DEFAULT_OVERDUE_FACTOR)
+    }
+
+    /// Display zone for history timestamps; unknown values fall back to local time.
+    pub fn time_zone(&self) -> TimeZone {
+        self.history
+            .timezone
+            .as_deref()
+            .and_then(TimeZone::parse)
+            .unwrap_or_default()
+    }
+
+    pub fn widget_exec(&self) -> ExecPolicy {
+        ExecPolicy {
+            commands: self.widgets.commands.clone(),
+            env: self.widgets.env.clone(),
+        }
+    }
+
+    pub fn pass_active_env(&self) -> bool {
+        self.runner.pass_active_env.unwrap_or(true)
+    }
+
+    pub fn exit_summary(&self) -> bool {
+        self.history.exit_summary.unwrap_or(false)
+    }
+
+    pub fn receipts(&self) -> bool {
+        self.history.receipts.unwrap_or(false)
+    }
+
+    /// TUI keys, with invalid `[keys]` entries left at their defaults.
+    pub fn keymap(&self) -> Keymap {
+        Keymap::from_config(&self.keys).0
+    }
+
+    /// Values that are ignored or fall back to a default. Interpreters and
+    /// themes are looked up, so this touches `PATH` and the themes folder.
+    pub fn problems(&self) -> Vec<ConfigProblem> {
+        let mut problems = Vec::new();
+        if let Some(version) = &self.workspace.version {
+            match (parse_version(version), parse_version(APP_VERSION)) {
+                (None, _) => problems.push(ConfigProblem::new(
+                    "workspace.version",
+                    format!("'{}' is not a version", version),
+                )),
+                (Some(wanted), Some(running)) if wanted > running => {
+                    problems.push(ConfigProblem::new(
+                        "workspace.version",
+                        format!(
+                            "written by omakure {}; this is {}, so newer settings may be ignored",
+                            version, APP_VERSION
+                        ),
+                    ))
+                }
+                _ => {}
+            }
+        }
+        if let Some(name) = &self.theme.name {
+            let installed = theme_config::theme_layout()
+                .is_some_and(|layout| layout.themes_dir.join(format!("{}.toml", name)).is_file());
+            if builtin_theme_contents(name).is_none() && !installed {
+                problems.push(ConfigProblem::new(
+                    "theme.name",
+                    format!("no theme named '{}'", name),
+                ));
+            }
+        }
+        if let Some(Err(err)) = self.runner.io_priority.as_deref().map(validate_io_priority) {
+            problems.push(ConfigProblem::new("runner.io_priority", err.to_string()));
+        }
+        if let Some(backend) = &self.runner.backend {
+            if Backend::parse(backend).is_none() {
+                problems.push(ConfigProblem::new(
+                    "runner.backend",
+                    format!("must be 'local' or 'kubernetes', got '{}'", backend),
+                ));
+            }
+        }
+        for (key, value) in [
+            ("runner.overdue_factor", self.runner.overdue_factor),
+            ("runner.timeout_seconds", self.runner.timeout_seconds),
+            ("history.retention_days", self.history.retention_days),
+            ("history.max_entries", self.history.max_entries),
+        ] {
+            if value == Some(0) {
+                problems.push(ConfigProblem::new(key, "must be greater than 0"));
+            }
+        }
+        for (key, program) in [
+            ("runner.interpreters.bash", &self.runner.interpreters.bash),
+            (
+                "runner.interpreters.powershell",
+                &self.runner.interpreters.powershell,
+            ),
+            (
+                "runner.interpreters.python",
+                &self.runner.interpreters.python,
+            ),
+        ] {
+            if let Some(Err(err)) = program.as_deref().map(ensure_runtime_installed) {
+                problems.push(ConfigProblem::new(key, err.to_string()));
+            }
+        }
+        for custom in self.custom_extensions() {
+            let key = format!("runner.extensions.{}", custom.extension);
+            if is_builtin_extension(&custom.extension) {
+                problems.push(ConfigProblem::new(
+                    key,
+                    format!(
+                        ".{} scripts are run by omakure itself; use [runner.interpreters]",
+                        custom.extension
+                    ),
+                ));
+            } else if custom.command.is_empty() {
+                problems.push(ConfigProblem::new(key, "command is empty"));
+            } else if let Err(err) = ensure_runtime_installed(&custom.command[0]) {
+                problems.push(ConfigProblem::new(key, err.to_string()));
+            }
+        }
+        if let Some(zone) = &self.history.timezone {
+            if TimeZone::parse(zone).is_none() {
+                problems.push(ConfigProblem::new(
+                    "history.timezone",
+                    format!(
+                        "must be 'local', 'utc', or an offset such as +02:00, got '{}'",
+                        zone
+                    ),
+                ));
+            }
+        }
+        if let Some(Err(err)) = self.digest.schedule.as_deref().map(Schedule::parse) {
+            problems.push(ConfigProblem::new("digest.schedule", err));
+        }
+        let (_, errors) = Keymap::from_config(&self.keys);
+        problems.extend(
+            errors
+                .into_iter()
+                .map(|err| ConfigProblem::new("keys", err)),
+        );
+        problems
+    }
+}
+
+/// Loads the workspace config, falling back to defaults when it is missing or invalid.
+pub(crate) fn load_workspace_config(path: &Path) -> WorkspaceConfig {
+    read_workspace_config(path).unwrap_or_default()
+}
+
+/// Reads the workspace config; a missing file is the default config.
+pub(crate) fn read_workspace_config(path: &Path) -> Result<WorkspaceConfig, String> {
+    check_workspace_config(path).map(|(config, _)| config)
+}
+
+/// Reads the workspace config along with its problems: unknown keys, then
+/// invalid values.
+pub(crate) fn check_workspace_config(
+    path: &Path,
+) -> Result<(WorkspaceConfig, Vec<ConfigProblem>), String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok((Default::default(), Vec::new()))
+        }
+        Err(err) => return Err(format!("Failed to read {}: {}", path.display(), err)),
+    };
+    parse_workspace_config(&contents)
+        .map_err(|err| format!("Failed to parse {}: {}", path.display(), err))
+}
+
+fn parse_workspace_config(
+    contents: &str,
+) -> Result<(WorkspaceConfig, Vec<ConfigProblem>), toml::de::Error> {
+    let mut problems = Vec::new();
+    let config: WorkspaceConfig =
+        serde_ignored::deserialize(toml::Deserializer::new(contents), |key| {
+            problems.push(ConfigProblem::new(key.to_string(), "unknown key"));
+        })?;
+    problems.extend(config.problems());
+    Ok((config, problems))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_problems() {
+        let (config, problems) = parse_workspace_config(
+            r#"
+            [workspace]
+            version = "999.0.0"
+
+            [runner]
+            timeout_seconds = 0
+            backend = "docker"
+            retries = 3
+
+            [history]
+            retention_days = 30
+            timezone = "+02:00"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.retention(), Some(Duration::from_secs(30 * 86400)));
+        assert_eq!(config.timeout(), None);
+        let keys: Vec<&str> = problems.iter().map(|p| p.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "runner.retries",
+                "workspace.version",
+                "runner.backend",
+                "runner.timeout_seconds"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_extensions() {
+        let (config, problems) = parse_workspace_config(
+            r##"
+            [runner.extensions]
+            ".TS" = "deno run --allow-read"
+            pl = { command = ["perl", "-w"], comment = "#" }
+            lua = { command = "lua", comment = "--" }
+            py = "pypy3"
+            "##,
+        )
+        .unwrap();
+        let extensions: Vec<(String, Vec<String>, String)> = config
+            .custom_extensions()
+            .into_iter()
+            .map(|custom| (custom.extension, custom.command, custom.comment))
+            .collect();
+        let words = |line: &str| line.split(' ').map(str::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            extensions,
+            vec![
+                (
+                    "ts".to_string(),
+                    words("deno run --allow-read"),
+                    "#".to_string()
+                ),
+                ("lua".to_string(), words("lua"), "--".to_string()),
+                ("pl".to_string(), words("perl -w"), "#".to_string()),
+                ("py".to_string(), words("pypy3"), "#".to_string()),
+            ]
+        );
+        assert!(problems
+            .iter()
+            .any(|problem| problem.key == "runner.extensions.py"
+                && problem.message.contains("[runner.interpreters]")));
+    }
+}
@@ -0,0 +1,162 @@
+use crate::history::{civil_time, TimeZone};
+
+/// Minutes searched for the next match; a year covers every valid schedule.
+const SEARCH_LIMIT_MINUTES: i64 = 366 * 24 * 60;
+const MINUTE_MS: i64 = 60_000;
+
+/// A five-field cron schedule: minute, hour, day of month, month, and day of
+/// week (0 or 7 is Sunday). Fields take `*`, values, ranges, lists, and
+/// steps such as `*/15` or `1-5`; `@hourly`, `@daily`, `@weekly`, and
+/// `@monthly` are shorthands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    /// Day of month and day of week were both restricted, so either matches,
+    /// as in classic cron.
+    either_day: bool,
+}
+
+impl Schedule {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let expression = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!(
+                "Cron schedule '{}' needs five fields: minute hour day month weekday",
+                expression
+            ));
+        };
+        let mut weekdays = parse_field(weekday, 0, 7)?;
+        // 7 is another name for Sunday.
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days: parse_field(day, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            weekdays,
+            either_day: day != "*" && weekday != "*",
+        })
+    }
+
+    /// Whether the schedule fires in the minute holding `timestamp_ms`.
+    pub fn matches(&self, timestamp_ms: i64, time_zone: TimeZone) -> bool {
+        let offset = time_zone.offset_at(timestamp_ms);
+        let (_, month, day, hour, minute, _) = civil_time(timestamp_ms, offset);
+        let days = (timestamp_ms / 1000 + i64::from(offset.0) * 60).div_euclid(86_400);
+        let weekday = (days + 4).rem_euclid(7);
+        let day_matches = has(self.days, day);
+        let weekday_matches = has(self.weekdays, weekday);
+        let day_ok = if self.either_day {
+            day_matches || weekday_matches
+        } else {
+            day_matches && weekday_matches
+        };
+        has(self.minutes, minute) && has(self.hours, hour) && has(self.months, month) && day_ok
+    }
+
+    /// Start of the first matching minute after `timestamp_ms`.
+    pub fn next_after(&self, timestamp_ms: i64, time_zone: TimeZone) -> Option<i64> {
+        let start = timestamp_ms.div_euclid(MINUTE_MS) * MINUTE_MS + MINUTE_MS;
+        (0..SEARCH_LIMIT_MINUTES)
+            .map(|step| start + step * MINUTE_MS)
+            .find(|candidate| self.matches(*candidate, time_zone))
+    }
+}
+
+fn has(set: u64, value: i64) -> bool {
+    (0..64).contains(&value) && set & (1 << value) != 0
+}
+
+/// Bit set of the values `field` allows between `min` and `max`.
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let invalid = || format!("Invalid cron field '{}'", field);
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().map_err(|_| invalid())?),
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                start.parse().map_err(|_| invalid())?,
+                end.parse().map_err(|_| invalid())?,
+            )
+        } else {
+            let value = range.parse().map_err(|_| invalid())?;
+            // `5/10` runs from 5 to the end of the range.
+            (value, if step > 1 { max } else { value })
+        };
+        if step == 0 || start < min || end > max || start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::UtcOffset;
+
+    const UTC: TimeZone = TimeZone::Fixed(UtcOffset::UTC);
+    /// 2024-01-15 08:00 UTC, a Monday.
+    const MONDAY_8AM: i64 = 1_705_305_600_000;
+
+    #[test]
+    fn test_parse_fields() {
+        let schedule = Schedule::parse("*/15 8-9 * * 1-5").unwrap();
+        assert!(schedule.matches(MONDAY_8AM, UTC));
+        assert!(schedule.matches(MONDAY_8AM + 45 * MINUTE_MS, UTC));
+        assert!(!schedule.matches(MONDAY_8AM + 5 * MINUTE_MS, UTC));
+        assert!(!schedule.matches(MONDAY_8AM - 2 * 86_400_000, UTC));
+        for invalid in [
+            "* * * *",
+            "60 * * * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+        ] {
+            assert!(Schedule::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_next_after() {
+        let daily = Schedule::parse("@daily").unwrap();
+        assert_eq!(
+            daily.next_after(MONDAY_8AM, UTC),
+            Some(MONDAY_8AM + 16 * 3_600_000)
+        );
+        // Day of month or Sunday (as 7): Sunday the 21st comes first.
+        let either = Schedule::parse("0 8 1 * 7").unwrap();
+        assert_eq!(
+            either.next_after(MONDAY_8AM, UTC),
+            Some(MONDAY_8AM + 6 * 86_400_000)
+        );
+        let plus_two = TimeZone::Fixed(UtcOffset(120));
+        assert_eq!(
+            Schedule::parse("0 10 * * *")
+                .unwrap()
+                .next_after(MONDAY_8AM - MINUTE_MS, plus_two),
+            Some(MONDAY_8AM)
+        );
+    }
+}
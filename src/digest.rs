@@ -0,0 +1,186 @@
+use crate::history::{self, HistoryEntry, TimeZone};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Slowest runs listed in a digest.
+const SLOWEST_RUNS: usize = 5;
+
+/// Summary of the runs in a period, sent as a heartbeat for scheduled work.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Digest {
+    pub since: i64,
+    pub until: i64,
+    pub runs: usize,
+    pub failed: usize,
+    /// Scripts with failed runs, most failures first.
+    pub failures: Vec<Failures>,
+    /// Longest runs with their duration in milliseconds, slowest first.
+    pub slowest: Vec<(PathBuf, u64)>,
+}
+
+/// Failed runs of one script in a digest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failures {
+    pub script: PathBuf,
+    pub count: usize,
+    /// Owner label from the script's schema, telling who to ask.
+    pub owner: Option<String>,
+}
+
+/// Digest of the script runs after `since` up to and including `until`.
+/// Shell commands from the `:` prompt are left out. `owner` gives the owner
+/// label of a script, by its history path.
+pub fn compile(
+    entries: &[HistoryEntry],
+    since: i64,
+    until: i64,
+    owner: impl Fn(&Path) -> Option<String>,
+) -> Digest {
+    let runs: Vec<&HistoryEntry> = entries
+        .iter()
+        .filter(|entry| {
+            entry.command.is_none() && entry.timestamp > since && entry.timestamp <= until
+        })
+        .collect();
+    let mut failures: Vec<Failures> = Vec::new();
+    for entry in runs.iter().filter(|entry| !entry.success) {
+        match failures
+            .iter_mut()
+            .find(|failure| failure.script == entry.script)
+        {
+            Some(failure) => failure.count += 1,
+            None => failures.push(Failures {
+                script: entry.script.clone(),
+                count: 1,
+                owner: owner(&entry.script),
+            }),
+        }
+    }
+    failures.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.script.cmp(&b.script)));
+    let mut slowest: Vec<(PathBuf, u64)> = runs
+        .iter()
+        .filter_map(|entry| Some((entry.script.clone(), entry.duration_ms?)))
+        .collect();
+    slowest.sort_by_key(|(_, duration_ms)| std::cmp::Reverse(*duration_ms));
+    slowest.truncate(SLOWEST_RUNS);
+    Digest {
+        since,
+        until,
+        failed: failures.iter().map(|failure| failure.count).sum(),
+        runs: runs.len(),
+        failures,
+        slowest,
+    }
+}
+
+impl Digest {
+    /// One line summing the digest up, used as the notification title.
+    pub fn headline(&self) -> String {
+        if self.runs == 0 {
+            "omakure digest: no runs".to_string()
+        } else if self.failed == 0 {
+            format!("omakure digest: {} runs, all passed", self.runs)
+        } else {
+            format!("omakure digest: {} runs, {} failed", self.runs, self.failed)
+        }
+    }
+
+    /// Plain-text digest: the headline, the period, failures, and slowest runs.
+    pub fn render(&self, time_zone: TimeZone) -> String {
+        let mut text = String::new();
+        let _ = writeln!(text, "{}", self.headline());
+        let _ = writeln!(
+            text,
+            "Period: {} to {}",
+            history::format_timestamp(self.since, time_zone.offset_at(self.since)),
+            history::format_timestamp(self.until, time_zone.offset_at(self.until))
+        );
+        if !self.failures.is_empty() {
+            let _ = writeln!(text, "\nFailures:");
+            for failure in &self.failures {
+                let _ = write!(text, "  {} x{}", failure.script.display(), failure.count);
+                if let Some(owner) = &failure.owner {
+                    let _ = write!(text, " (owner: {})", owner);
+                }
+                text.push('\n');
+            }
+        }
+        if !self.slowest.is_empty() {
+            let _ = writeln!(text, "\nSlowest runs:");
+            for (script, duration_ms) in &self.slowest {
+                let _ = writeln!(
+                    text,
+                    "  {} {}",
+                    script.display(),
+                    history::format_duration(*duration_ms)
+                );
+            }
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::UtcOffset;
+
+    fn run(timestamp: i64, script: &str, success: bool, duration_ms: u64) -> HistoryEntry {
+        HistoryEntry {
+            timestamp,
+            script: PathBuf::from(script),
+            args: Vec::new(),
+            success,
+            exit_code: Some(if success { 0 } else { 1 }),
+            stdout: String::new(),
+            stderr: String::new(),
+            error: None,
+            usage: None,
+            duration_ms: Some(duration_ms),
+            artifacts: None,
+            runs: Vec::new(),
+            command: None,
+            env_overrides: Vec::new(),
+            retries: 0,
+            hooks: Vec::new(),
+            files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_compile_counts_the_period() {
+        let entries = vec![
+            run(50, "backup.bash", true, 9_000),
+            run(40, "sync.bash", false, 100),
+            run(30, "sync.bash", false, 200),
+            run(20, "report.bash", false, 300),
+            run(10, "backup.bash", true, 60_000),
+        ];
+        let owner = |script: &Path| (script == Path::new("sync.bash")).then(|| "ops".to_string());
+        let digest = compile(&entries, 10, 40, owner);
+        assert_eq!((digest.runs, digest.failed), (3, 3));
+        assert_eq!(
+            digest.failures,
+            vec![
+                Failures {
+                    script: PathBuf::from("sync.bash"),
+                    count: 2,
+                    owner: Some("ops".to_string()),
+                },
+                Failures {
+                    script: PathBuf::from("report.bash"),
+                    count: 1,
+                    owner: None,
+                },
+            ]
+        );
+        assert_eq!(digest.slowest[0], (PathBuf::from("report.bash"), 300));
+        assert_eq!(digest.headline(), "omakure digest: 3 runs, 3 failed");
+        let text = digest.render(TimeZone::Fixed(UtcOffset::UTC));
+        assert!(text.contains("Failures:\n  sync.bash x2 (owner: ops)\n  report.bash x1\n"));
+        assert_eq!(
+            compile(&entries, 50, 60, owner).headline(),
+            "omakure digest: no runs"
+        );
+    }
+}
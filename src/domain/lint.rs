@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+use super::locale::{canonical_number, parse_bool};
+use super::schema::Schema;
+
+/// Field types the form knows how to read.
+const FIELD_TYPES: [&str; 5] = ["string", "number", "bool", "boolean", "path"];
+
+/// Something wrong with a schema, found by `lint_schema`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaProblem {
+    /// The field at fault, or `None` for the schema as a whole.
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl SchemaProblem {
+    fn schema(message: impl Into<String>) -> Self {
+        Self {
+            field: None,
+            message: message.into(),
+        }
+    }
+
+    fn field(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: Some(field.to_string()),
+            message: message.into(),
+        }
+    }
+}
+
+/// Problems in a schema read as plain JSON, so that each one is reported
+/// rather than only the first error deserializing would stop at.
+pub fn lint_schema(value: &Value) -> Vec<SchemaProblem> {
+    let Some(schema) = value.as_object() else {
+        return vec![SchemaProblem::schema("Schema is not a JSON object")];
+    };
+    let mut problems = Vec::new();
+    if !schema.get("Name").is_some_and(Value::is_string) {
+        problems.push(SchemaProblem::schema("Missing Name"));
+    }
+    match schema.get("Fields") {
+        None => problems.push(SchemaProblem::schema("Missing Fields")),
+        Some(Value::Array(fields)) => lint_fields(fields, &mut problems),
+        Some(_) => problems.push(SchemaProblem::schema("Fields is not a list")),
+    }
+    for output in schema
+        .get("Outputs")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let is_file = output
+            .get("Type")
+            .and_then(Value::as_str)
+            .is_some_and(|kind| kind.eq_ignore_ascii_case("file"));
+        if is_file && !output.get("Path").is_some_and(Value::is_string) {
+            let name = output.get("Name").and_then(Value::as_str).unwrap_or("?");
+            problems.push(SchemaProblem::schema(format!(
+                "Output {} is a file but has no Path",
+                name
+            )));
+        }
+    }
+    if let Some(pattern) = schema.get("FailRegex").and_then(Value::as_str) {
+        if let Err(err) = regex::Regex::new(pattern) {
+            problems.push(SchemaProblem::schema(format!(
+                "FailRegex does not compile: {}",
+                err
+            )));
+        }
+    }
+    if problems.is_empty() {
+        if let Err(err) = Schema::deserialize(value) {
+            problems.push(SchemaProblem::schema(format!(
+                "Schema does not parse: {}",
+                err
+            )));
+        }
+    }
+    problems
+}
+
+fn lint_fields(fields: &[Value], problems: &mut Vec<SchemaProblem>) {
+    let mut names: HashSet<&str> = HashSet::new();
+    let mut orders: HashMap<u64, String> = HashMap::new();
+    for (index, field) in fields.iter().enumerate() {
+        let Some(field) = field.as_object() else {
+            problems.push(SchemaProblem::schema(format!(
+                "Field {} is not an object",
+                index + 1
+            )));
+            continue;
+        };
+        let label = match field.get("Name").and_then(Value::as_str) {
+            Some(name) => {
+                if !names.insert(name) {
+                    problems.push(SchemaProblem::field(name, "Duplicate field name"));
+                }
+                name.to_string()
+            }
+            None => {
+                let label = format!("#{}", index + 1);
+                problems.push(SchemaProblem::field(&label, "Missing Name"));
+                label
+            }
+        };
+
+        match field.get("Order") {
+            None => problems.push(SchemaProblem::field(&label, "Missing Order")),
+            Some(order) => match order.as_u64() {
+                Some(order) => {
+                    if let Some(other) = orders.insert(order, label.clone()) {
+                        problems.push(SchemaProblem::field(
+                            &label,
+                            format!("Order {} is also used by {}", order, other),
+                        ));
+                    }
+                }
+                None => problems.push(SchemaProblem::field(&label, "Order must be a whole number")),
+            },
+        }
+
+        let kind = match field.get("Type").and_then(Value::as_str) {
+            Some(kind) if FIELD_TYPES.contains(&kind.to_lowercase().as_str()) => {
+                Some(kind.to_lowercase())
+            }
+            Some(kind) => {
+                problems.push(SchemaProblem::field(
+                    &label,
+                    format!(
+                        "Unknown Type \"{}\" (expected string, number, bool, or path)",
+                        kind
+                    ),
+                ));
+                None
+            }
+            None => {
+                problems.push(SchemaProblem::field(&label, "Missing Type"));
+                None
+            }
+        };
+
+        let choices: Option<Vec<&str>> = match field.get("Choices") {
+            None | Some(Value::Null) => None,
+            Some(Value::Array(choices)) if choices.iter().all(Value::is_string) => {
+                Some(choices.iter().filter_map(Value::as_str).collect())
+            }
+            Some(_) => {
+                problems.push(SchemaProblem::field(
+                    &label,
+                    "Choices must be a list of strings",
+                ));
+                None
+            }
+        };
+        let default = match field.get("Default") {
+            None | Some(Value::Null) => continue,
+            Some(Value::String(default)) => default,
+            Some(_) => {
+                problems.push(SchemaProblem::field(&label, "Default must be a string"));
+                continue;
+            }
+        };
+        if choices.is_some_and(|choices| !choices.contains(&default.as_str())) {
+            problems.push(SchemaProblem::field(
+                &label,
+                format!("Default \"{}\" is not one of the Choices", default),
+            ));
+        }
+        let valid = match kind.as_deref() {
+            Some("number") => canonical_number(default).is_some(),
+            Some("bool" | "boolean") => parse_bool(default).is_some(),
+            _ => true,
+        };
+        if !valid {
+            problems.push(SchemaProblem::field(
+                &label,
+                format!(
+                    "Default \"{}\" is not a {}",
+                    default,
+                    kind.unwrap_or_default()
+                ),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_lint_schema_reports_every_problem() {
+        let schema = json!({
+            "Name": "deploy",
+            "Fields": [
+                {"Name": "env", "Type": "string", "Order": 1, "Default": "qa",
+                 "Choices": ["staging", "production"]},
+                {"Name": "env", "Type": "String", "Order": 1},
+                {"Name": "count", "Type": "int"},
+                {"Name": "dry", "Type": "bool", "Order": 3, "Default": "maybe"},
+            ]
+        });
+        let problems = lint_schema(&schema);
+        let problems: Vec<(Option<&str>, &str)> = problems
+            .iter()
+            .map(|problem| (problem.field.as_deref(), problem.message.as_str()))
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                (Some("env"), "Default \"qa\" is not one of the Choices"),
+                (Some("env"), "Duplicate field name"),
+                (Some("env"), "Order 1 is also used by env"),
+                (Some("count"), "Missing Order"),
+                (
+                    Some("count"),
+                    "Unknown Type \"int\" (expected string, number, bool, or path)"
+                ),
+                (Some("dry"), "Default \"maybe\" is not a bool"),
+            ]
+        );
+
+        let valid = json!({"Name": "ok", "Fields": [{"Name": "a", "Type": "path", "Order": 1}]});
+        assert!(lint_schema(&valid).is_empty());
+        assert_eq!(
+            lint_schema(&json!({"Fields": {}})),
+            vec![
+                SchemaProblem::schema("Missing Name"),
+                SchemaProblem::schema("Fields is not a list")
+            ]
+        );
+    }
+}
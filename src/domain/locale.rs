@@ -0,0 +1,101 @@
+/// Words read as yes and no, by language. Matching ignores case.
+const BOOL_WORDS: &[(&str, &[&str], &[&str])] = &[
+    (
+        "en",
+        &["true", "t", "yes", "y", "1"],
+        &["false", "f", "no", "n", "0"],
+    ),
+    ("de", &["ja", "j", "wahr"], &["nein", "falsch"]),
+    ("es", &["sí", "si", "s", "verdadero"], &["falso"]),
+    ("fr", &["oui", "o", "vrai"], &["non", "faux"]),
+    ("it", &["sì", "vero"], &["falso"]),
+    ("nl", &["ja", "waar"], &["nee", "onwaar"]),
+    ("pt", &["sim", "verdadeiro"], &["não", "nao", "falso"]),
+    ("ja", &["はい", "真"], &["いいえ", "偽"]),
+];
+
+/// Reads a yes/no answer in any of the known languages.
+pub(crate) fn parse_bool(input: &str) -> Option<bool> {
+    let input = input.trim().to_lowercase();
+    BOOL_WORDS.iter().find_map(|(_, yes, no)| {
+        if yes.contains(&input.as_str()) {
+            Some(true)
+        } else if no.contains(&input.as_str()) {
+            Some(false)
+        } else {
+            None
+        }
+    })
+}
+
+/// Rewrites a number typed with either decimal separator into the `1234.5`
+/// form scripts expect.
+///
+/// A separator that appears once and is the last one in the number is the
+/// decimal point, so `3,14`, `1.234,5`, and `1,234.5` all parse; a separator
+/// repeated or followed by the other one groups thousands and needs three
+/// digits per group. A lone comma is always a decimal point: `1,234` is
+/// `1.234`.
+pub(crate) fn canonical_number(input: &str) -> Option<String> {
+    if input.parse::<f64>().is_ok() {
+        return Some(input.to_string());
+    }
+    let last = input.rfind([',', '.'])?;
+    let last_separator = input[last..].chars().next()?;
+    let (integer, fraction) = if input.matches(last_separator).count() == 1 {
+        (&input[..last], Some(&input[last + 1..]))
+    } else {
+        (input, None)
+    };
+
+    let mut groups = integer.split([',', '.']);
+    let mut digits = groups.next()?.to_string();
+    for group in groups {
+        if group.len() != 3 || !group.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+        digits.push_str(group);
+    }
+    let canonical = match fraction {
+        Some(fraction) => format!("{}.{}", digits, fraction),
+        None => digits,
+    };
+    canonical.parse::<f64>().ok().map(|_| canonical)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bool_languages() {
+        for yes in ["Yes", "ja", "oui", "Sí", "sim", "はい"] {
+            assert_eq!(parse_bool(yes), Some(true), "{}", yes);
+        }
+        for no in ["NO", "nein", "non", "não", "nee", "いいえ"] {
+            assert_eq!(parse_bool(no), Some(false), "{}", no);
+        }
+        assert_eq!(parse_bool("vielleicht"), None);
+    }
+
+    #[test]
+    fn test_canonical_number() {
+        let cases = [
+            ("42", Some("42")),
+            ("-3.14", Some("-3.14")),
+            ("3,14", Some("3.14")),
+            ("-0,5", Some("-0.5")),
+            ("1.234,5", Some("1234.5")),
+            ("1,234.5", Some("1234.5")),
+            ("1.234.567", Some("1234567")),
+            ("1,234", Some("1.234")),
+            ("1.2.3", None),
+            ("1,2,3.4", None),
+            ("3,14,", None),
+            ("abc", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(canonical_number(input).as_deref(), expected, "{}", input);
+        }
+    }
+}
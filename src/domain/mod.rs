@@ -1,9 +1,22 @@
 //! Domain layer - core types and validation logic.
 
+mod lint;
+mod locale;
 mod parsing;
+mod paths;
+mod pipeline;
+mod prompt;
 mod schema;
+mod terraform;
 mod validation;
 
-pub use parsing::{extract_schema_block, parse_schema};
-pub use schema::{Field, Schema};
-pub use validation::normalize_input;
+pub use lint::{lint_schema, SchemaProblem};
+pub use parsing::{extract_schema_block, parse_outputs, parse_schema};
+pub use paths::PathStyle;
+pub use pipeline::{expand_references, parse_pipeline, Pipeline, Stage, StageOutputs};
+pub use prompt::{parse_prompt, PromptKind, ScriptPrompt};
+pub use schema::{Field, Hooks, OutputField, RunLimits, Schema};
+pub use terraform::{parse_plan_summary, phase_env, strip_ansi, PlanSummary, PLAN_FILE_NAME};
+pub use validation::{
+    build_args, field_arg, inputs_from_args, missing_env, normalize_input, schema_env,
+};
@@ -1,4 +1,5 @@
 use serde::Deserialize;
+use std::collections::BTreeMap;
 
 use crate::error::SchemaError;
 
@@ -52,6 +53,25 @@ pub fn extract_schema_block(contents: &str, prefixes: &[&str]) -> Result<String,
     Err(SchemaError::BlockNotFound)
 }
 
+/// Parses `OMAKURE_OUTPUT name=value` lines emitted by a script on stdout.
+pub fn parse_outputs(stdout: &str) -> BTreeMap<String, String> {
+    let mut outputs = BTreeMap::new();
+    for line in stdout.lines() {
+        let Some(rest) = line.trim().strip_prefix("OMAKURE_OUTPUT ") else {
+            continue;
+        };
+        let Some((name, value)) = rest.split_once('=') else {
+            continue;
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            continue;
+        }
+        outputs.insert(name.to_string(), value.trim().to_string());
+    }
+    outputs
+}
+
 fn strip_comment_prefix<'a>(line: &'a str, prefixes: &[&str]) -> Option<&'a str> {
     let trimmed = line.trim_start();
     for prefix in prefixes {
@@ -101,6 +121,93 @@ Some output after"#;
         assert!(schema.fields.is_empty());
     }
 
+    #[test]
+    fn test_parse_schema_with_owner() {
+        let output = r##"{
+  "Name": "dns_update",
+  "Owner": "platform-team",
+  "Contact": "#platform-oncall",
+  "Fields": []
+}"##;
+        let schema = parse_schema(output).unwrap();
+        assert_eq!(schema.owner.as_deref(), Some("platform-team"));
+        assert_eq!(
+            schema.owner_label().as_deref(),
+            Some("platform-team (#platform-oncall)")
+        );
+
+        let schema = parse_schema(&make_schema_json()).unwrap();
+        assert_eq!(schema.owner_label(), None);
+    }
+
+    #[test]
+    fn test_parse_schema_workflow_dispatch() {
+        let output = r#"{
+  "Type": "workflow_dispatch",
+  "Name": "deploy",
+  "Workflow": { "Repo": "acme/app", "Workflow": "deploy.yml" },
+  "Fields": []
+}"#;
+        let schema = parse_schema(output).unwrap();
+        assert_eq!(schema.kind.as_deref(), Some("workflow_dispatch"));
+        let workflow = schema.workflow.unwrap();
+        assert_eq!(workflow.repo, "acme/app");
+        assert_eq!(workflow.workflow, "deploy.yml");
+        assert_eq!(workflow.git_ref, None);
+    }
+
+    #[test]
+    fn test_parse_schema_expected_duration() {
+        let output = r#"{
+  "Name": "backup",
+  "ExpectedDurationSeconds": 60,
+  "Fields": []
+}"#;
+        let schema = parse_schema(output).unwrap();
+        assert_eq!(schema.expected_duration_seconds, Some(60));
+        assert!(!schema.is_overdue(60_000, 1));
+        assert!(schema.is_overdue(60_001, 1));
+        assert!(!schema.is_overdue(90_000, 2));
+
+        let schema = parse_schema(&make_schema_json()).unwrap();
+        assert!(!schema.is_overdue(u64::MAX, 1));
+    }
+
+    #[test]
+    fn test_parse_schema_success_criteria() {
+        let output = r#"{
+  "Name": "lint",
+  "SuccessCodes": [0, 2],
+  "FailRegex": "(?i)fatal:",
+  "Fields": []
+}"#;
+        let schema = parse_schema(output).unwrap();
+        assert!(schema.run_succeeded(Some(2), "2 warnings", ""));
+        assert!(!schema.run_succeeded(Some(1), "", ""));
+        assert!(!schema.run_succeeded(Some(0), "", "FATAL: no config"));
+        assert!(!schema.run_succeeded(None, "", ""));
+
+        let schema = parse_schema(&make_schema_json()).unwrap();
+        assert!(schema.run_succeeded(Some(0), "", ""));
+        assert!(!schema.run_succeeded(Some(2), "", ""));
+    }
+
+    #[test]
+    fn test_parse_schema_retry() {
+        let output = r#"{
+  "Name": "deploy",
+  "Retry": { "Attempts": 3, "DelaySeconds": 5, "OnExitCodes": [75] },
+  "Fields": []
+}"#;
+        let retry = parse_schema(output).unwrap().retry.unwrap();
+        assert_eq!(retry.delay().as_secs(), 5);
+        assert!(retry.should_retry(1, Some(75)));
+        assert!(retry.should_retry(2, Some(75)));
+        assert!(!retry.should_retry(3, Some(75)));
+        assert!(!retry.should_retry(1, Some(1)));
+        assert!(!retry.should_retry(1, None));
+    }
+
     #[test]
     fn test_parse_schema_with_fields() {
         let output = r#"{
@@ -175,4 +282,16 @@ Some output after"#;
         let result = extract_schema_block(contents, &["#"]);
         assert!(matches!(result.unwrap_err(), SchemaError::BlockNotFound));
     }
+
+    #[test]
+    fn test_parse_outputs() {
+        let stdout = "working...\nOMAKURE_OUTPUT rg_id=/subscriptions/1/rg\nOMAKURE_OUTPUT count = 3\nOMAKURE_OUTPUT broken\ndone";
+        let outputs = parse_outputs(stdout);
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(
+            outputs.get("rg_id").map(String::as_str),
+            Some("/subscriptions/1/rg")
+        );
+        assert_eq!(outputs.get("count").map(String::as_str), Some("3"));
+    }
 }
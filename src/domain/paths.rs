@@ -0,0 +1,148 @@
+/// How the target runtime expects filesystem paths to be written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathStyle {
+    /// Native Unix paths, passed through untouched.
+    Unix,
+    /// Windows paths such as `C:\dir\file`.
+    Windows,
+    /// Git Bash / MSYS paths such as `/c/dir/file`.
+    Msys,
+    /// WSL paths such as `/mnt/c/dir/file`.
+    Wsl,
+}
+
+/// Rewrites `value` into the given path style.
+///
+/// Drive-letter paths in any of the supported forms are translated; other
+/// paths only get their separators converted. A UNC path such as
+/// `\\server\share` keeps its double leading separator.
+pub fn convert_path(value: &str, style: PathStyle) -> String {
+    if style == PathStyle::Unix {
+        return value.to_string();
+    }
+
+    let unc = value.starts_with(['/', '\\']) && value[1..].starts_with(['/', '\\']);
+    let (drive, rest) = match split_drive(value).filter(|_| !unc) {
+        Some((drive, rest)) => (Some(drive), rest),
+        None => (None, value),
+    };
+    let segments: Vec<&str> = rest
+        .split(['/', '\\'])
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let absolute = drive.is_some() || rest.starts_with(['/', '\\']);
+
+    match (style, drive) {
+        (PathStyle::Windows, Some(drive)) => {
+            format!("{}:\\{}", drive.to_ascii_uppercase(), segments.join("\\"))
+        }
+        (PathStyle::Windows, None) => {
+            let joined = segments.join("\\");
+            if unc {
+                format!("\\\\{}", joined)
+            } else if absolute {
+                format!("\\{}", joined)
+            } else {
+                joined
+            }
+        }
+        (PathStyle::Msys, Some(drive)) => {
+            join_unix(&format!("/{}", drive.to_ascii_lowercase()), &segments)
+        }
+        (PathStyle::Wsl, Some(drive)) => {
+            join_unix(&format!("/mnt/{}", drive.to_ascii_lowercase()), &segments)
+        }
+        _ => {
+            let joined = segments.join("/");
+            if unc {
+                format!("//{}", joined)
+            } else if absolute {
+                format!("/{}", joined)
+            } else {
+                joined
+            }
+        }
+    }
+}
+
+/// Splits a drive letter off `C:\x`, `C:/x`, `/c/x`, or `/mnt/c/x`.
+fn split_drive(value: &str) -> Option<(char, &str)> {
+    let bytes = value.as_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        let rest = &value[2..];
+        if rest.is_empty() || rest.starts_with(['/', '\\']) {
+            return Some((bytes[0] as char, rest));
+        }
+        return None;
+    }
+
+    let unix = value
+        .strip_prefix("/mnt/")
+        .or_else(|| value.strip_prefix('/'))?;
+    let mut chars = unix.chars();
+    let drive = chars.next().filter(char::is_ascii_alphabetic)?;
+    let rest = chars.as_str();
+    if rest.is_empty() || rest.starts_with('/') {
+        Some((drive, rest))
+    } else {
+        None
+    }
+}
+
+fn join_unix(root: &str, segments: &[&str]) -> String {
+    if segments.is_empty() {
+        root.to_string()
+    } else {
+        format!("{}/{}", root, segments.join("/"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_path_to_windows() {
+        assert_eq!(
+            convert_path("/c/Users/me/file.txt", PathStyle::Windows),
+            "C:\\Users\\me\\file.txt"
+        );
+        assert_eq!(convert_path("/mnt/d/data", PathStyle::Windows), "D:\\data");
+        assert_eq!(
+            convert_path("dir/sub/file", PathStyle::Windows),
+            "dir\\sub\\file"
+        );
+        assert_eq!(
+            convert_path("//server/share/dir", PathStyle::Windows),
+            "\\\\server\\share\\dir"
+        );
+        assert_eq!(
+            convert_path("\\\\server\\share", PathStyle::Windows),
+            "\\\\server\\share"
+        );
+    }
+
+    #[test]
+    fn test_convert_path_to_bash_styles() {
+        assert_eq!(
+            convert_path("C:\\Users\\me\\file.txt", PathStyle::Msys),
+            "/c/Users/me/file.txt"
+        );
+        assert_eq!(convert_path("D:/data", PathStyle::Wsl), "/mnt/d/data");
+        assert_eq!(convert_path("dir\\sub", PathStyle::Msys), "dir/sub");
+        assert_eq!(convert_path("/mnt/c/x", PathStyle::Msys), "/c/x");
+        assert_eq!(
+            convert_path("\\\\server\\share\\file", PathStyle::Msys),
+            "//server/share/file"
+        );
+    }
+
+    #[test]
+    fn test_convert_path_leaves_unix_untouched() {
+        assert_eq!(
+            convert_path("C:\\odd\\name", PathStyle::Unix),
+            "C:\\odd\\name"
+        );
+        assert_eq!(convert_path("/home/me", PathStyle::Unix), "/home/me");
+    }
+}
@@ -0,0 +1,205 @@
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// Scripts run one after another, each able to take the outputs of the
+/// stages before it. Read from `.omaken/pipelines/<name>.toml`:
+///
+/// ```toml
+/// description = "Resource group, then the app in it"
+///
+/// [[stages]]
+/// script = "azure/create-rg"
+/// fields = { location = "westeurope" }
+///
+/// [[stages]]
+/// script = "azure/deploy-app"
+/// fields = { group = "${create-rg.rg_name}" }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pipeline {
+    /// File name without `.toml`, or the script a `DependsOn` chain ends with.
+    #[serde(skip)]
+    pub name: String,
+    pub description: Option<String>,
+    pub stages: Vec<Stage>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Stage {
+    /// What later stages call this one in references; the script's file
+    /// name without its extension when unset.
+    pub id: Option<String>,
+    /// Workspace-relative script path, with or without its extension.
+    pub script: String,
+    /// Field values by field name. `${stage.output}` is replaced with an
+    /// output of an earlier stage.
+    #[serde(default)]
+    pub fields: BTreeMap<String, String>,
+}
+
+impl Stage {
+    pub fn new(script: String) -> Self {
+        Self {
+            id: None,
+            script,
+            fields: BTreeMap::new(),
+        }
+    }
+
+    pub fn id(&self) -> String {
+        if let Some(id) = &self.id {
+            return id.clone();
+        }
+        let path = Path::new(&self.script);
+        path.file_stem()
+            .or(path.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.script.clone())
+    }
+}
+
+/// Outputs of the stages run so far, by stage id.
+pub type StageOutputs = BTreeMap<String, BTreeMap<String, String>>;
+
+/// Reads a pipeline file and checks its stages refer to each other correctly.
+pub fn parse_pipeline(name: &str, contents: &str) -> Result<Pipeline, String> {
+    let mut pipeline: Pipeline = toml::from_str(contents).map_err(|err| err.to_string())?;
+    pipeline.name = name.to_string();
+    pipeline.validate()?;
+    Ok(pipeline)
+}
+
+impl Pipeline {
+    /// Stage ids must be unique, and references may only name earlier stages.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.stages.is_empty() {
+            return Err("The pipeline has no stages".to_string());
+        }
+        let mut seen = HashSet::new();
+        for stage in &self.stages {
+            let id = stage.id();
+            for (field, value) in &stage.fields {
+                for (other, _) in references(value)? {
+                    if !seen.contains(other) {
+                        return Err(format!(
+                            "Stage {}: field {} refers to '{}', which is not an earlier stage",
+                            id, field, other
+                        ));
+                    }
+                }
+            }
+            if !seen.insert(id.clone()) {
+                return Err(format!(
+                    "Two stages are called '{}'; set an id on one of them",
+                    id
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `value` with each `${stage.output}` replaced by that output.
+pub fn expand_references(value: &str, outputs: &StageOutputs) -> Result<String, String> {
+    let mut expanded = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or_else(|| format!("Unclosed reference in '{}'", value))?;
+        let (stage, output) = reference(&rest[start + 2..end])?;
+        let resolved = outputs
+            .get(stage)
+            .and_then(|outputs| outputs.get(output))
+            .ok_or_else(|| format!("Stage {} has no output {}", stage, output))?;
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(resolved);
+        rest = &rest[end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// `${stage.output}` references in `value`, as `(stage, output)` pairs.
+fn references(value: &str) -> Result<Vec<(&str, &str)>, String> {
+    let mut found = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or_else(|| format!("Unclosed reference in '{}'", value))?;
+        found.push(reference(&rest[start + 2..end])?);
+        rest = &rest[end + 1..];
+    }
+    Ok(found)
+}
+
+/// Splits `stage.output` at its last dot, so stage ids may contain dots.
+fn reference(inner: &str) -> Result<(&str, &str), String> {
+    match inner.trim().rsplit_once('.') {
+        Some((stage, output)) if !stage.is_empty() && !output.is_empty() => Ok((stage, output)),
+        _ => Err(format!(
+            "Invalid reference '${{{}}}': use ${{stage.output}}",
+            inner
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pipeline_and_expand_references() {
+        let pipeline = parse_pipeline(
+            "release",
+            r#"
+            [[stages]]
+            script = "azure/create-rg.bash"
+
+            [[stages]]
+            id = "app"
+            script = "azure/deploy-app"
+            fields = { group = "${create-rg.rg_name}", slot = "staging" }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(pipeline.name, "release");
+        let ids: Vec<String> = pipeline.stages.iter().map(Stage::id).collect();
+        assert_eq!(ids, vec!["create-rg", "app"]);
+
+        let mut outputs = StageOutputs::new();
+        outputs.insert(
+            "create-rg".to_string(),
+            BTreeMap::from([("rg_name".to_string(), "rg-prod".to_string())]),
+        );
+        assert_eq!(
+            expand_references("${create-rg.rg_name}/apps", &outputs).unwrap(),
+            "rg-prod/apps"
+        );
+        assert!(expand_references("${create-rg.location}", &outputs).is_err());
+
+        let forward = r#"
+            [[stages]]
+            script = "a.bash"
+            fields = { name = "${b.name}" }
+
+            [[stages]]
+            script = "b.bash"
+            "#;
+        assert!(parse_pipeline("forward", forward).is_err());
+        let duplicate = r#"
+            [[stages]]
+            script = "a.bash"
+
+            [[stages]]
+            script = "other/a.py"
+            "#;
+        assert!(parse_pipeline("duplicate", duplicate).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+use serde::Deserialize;
+
+/// Marker of a stdout line asking the user something mid-run, e.g.
+/// `OMAKURE_PROMPT {"Type": "confirm", "Message": "Delete 3 VMs?"}`. The
+/// answer comes back on the script's stdin as one line.
+pub const PROMPT_MARKER: &str = "OMAKURE_PROMPT";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptKind {
+    /// Answered with `yes` or `no`.
+    Confirm,
+    /// Answered with the text typed.
+    #[default]
+    Value,
+}
+
+/// A question a running script asks with an `OMAKURE_PROMPT` line.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ScriptPrompt {
+    #[serde(rename = "Type", default)]
+    pub kind: PromptKind,
+    pub message: String,
+    /// Answer given when the prompt is dismissed.
+    pub default: Option<String>,
+    /// Hide what is typed, for passwords and tokens.
+    #[serde(default)]
+    pub secret: bool,
+}
+
+impl ScriptPrompt {
+    /// The answer line for what the user typed: `yes` or `no` for a
+    /// confirmation, the text for a value, and `fallback` when left empty.
+    pub fn answer(&self, input: &str) -> String {
+        let input = input.trim();
+        match self.kind {
+            _ if input.is_empty() => self.fallback(),
+            PromptKind::Confirm if is_yes(input) => "yes".to_string(),
+            PromptKind::Confirm => "no".to_string(),
+            PromptKind::Value => input.to_string(),
+        }
+    }
+
+    /// The answer to a dismissed prompt: its `Default`, else `no` for a
+    /// confirmation and an empty line for a value.
+    pub fn fallback(&self) -> String {
+        match (&self.default, self.kind) {
+            (Some(default), PromptKind::Confirm) if is_yes(default) => "yes".to_string(),
+            (_, PromptKind::Confirm) => "no".to_string(),
+            (default, PromptKind::Value) => default.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// The prompt on a stdout `line`; `None` when the line is not a prompt, and
+/// an error when its JSON does not parse.
+pub fn parse_prompt(line: &str) -> Option<Result<ScriptPrompt, String>> {
+    let rest = line.trim().strip_prefix(PROMPT_MARKER)?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    Some(serde_json::from_str(rest.trim()).map_err(|err| err.to_string()))
+}
+
+fn is_yes(value: &str) -> bool {
+    matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "yes" | "y" | "true"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_prompt() {
+        let prompt = parse_prompt(
+            r#"OMAKURE_PROMPT {"Type": "confirm", "Message": "Delete 3 VMs?", "Default": "Yes"}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(prompt.kind, PromptKind::Confirm);
+        assert_eq!(prompt.message, "Delete 3 VMs?");
+        assert_eq!(prompt.fallback(), "yes");
+        assert_eq!(prompt.answer(" n "), "no");
+        assert_eq!(prompt.answer(""), "yes");
+
+        let prompt = parse_prompt(r#"OMAKURE_PROMPT {"Message": "Tag?", "Secret": true}"#)
+            .unwrap()
+            .unwrap();
+        assert_eq!(prompt.kind, PromptKind::Value);
+        assert!(prompt.secret);
+        assert_eq!(prompt.fallback(), "");
+        assert_eq!(prompt.answer("v1.2 "), "v1.2");
+
+        assert!(parse_prompt("OMAKURE_PROMPT not json").unwrap().is_err());
+        assert!(parse_prompt("OMAKURE_PROMPTS {}").is_none());
+        assert!(parse_prompt("echo OMAKURE_PROMPT").is_none());
+    }
+}
@@ -1,19 +1,219 @@
-use serde::Deserialize;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
 
 /// Schema definition for a script.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "PascalCase")]
 pub struct Schema {
+    /// Action type for script-free entries, e.g. `workflow_dispatch`.
+    #[serde(rename = "Type")]
+    pub kind: Option<String>,
     pub name: String,
     pub description: Option<String>,
     pub tags: Option<Vec<String>>,
+    /// Person or team responsible for the script.
+    pub owner: Option<String>,
+    /// Where to reach the owner, e.g. an email or a chat channel.
+    pub contact: Option<String>,
+    /// How long a normal run takes; longer runs are flagged as overdue.
+    pub expected_duration_seconds: Option<u64>,
+    /// Runs of scripts sharing a group wait for each other.
+    pub concurrency_group: Option<String>,
     pub fields: Vec<Field>,
     pub outputs: Option<Vec<OutputField>>,
     pub queue: Option<QueueSpec>,
+    pub limits: Option<RunLimits>,
+    /// `local` or `kubernetes`, overriding the workspace `[runner] backend`.
+    pub backend: Option<String>,
+    /// Offer the inventory host picker and run once per selected host over SSH.
+    pub targets: Option<bool>,
+    /// Credential checks run before the form opens, e.g. `["aws"]`.
+    pub preflight: Option<Vec<String>>,
+    /// Files or globs on the host, fetched into the run's artifacts folder after an SSH run.
+    pub remote_artifacts: Option<Vec<String>>,
+    /// Target of a `workflow_dispatch` action.
+    pub workflow: Option<WorkflowTarget>,
+    /// Variables set for the script process, by name.
+    pub env: Option<BTreeMap<String, EnvVar>>,
+    /// `false` to run without the variables of the active env file.
+    pub active_env: Option<bool>,
+    /// Variables the script needs; runs are refused while any is unset.
+    pub requires_env: Option<Vec<String>>,
+    /// Tools the script calls, e.g. `az` or `kubectl>=1.27`; local runs are
+    /// refused while any is missing or too old.
+    pub requires: Option<Vec<String>>,
+    /// Container image the script runs in, so it brings its own toolchain.
+    pub runtime: Option<ContainerRuntime>,
+    /// Scripts, by workspace-relative path, run first when this one is run
+    /// as a pipeline; their outputs fill fields of the same name.
+    pub depends_on: Option<Vec<String>>,
+    /// Exit codes that count as success, e.g. `[0, 2]` for a CLI that exits
+    /// with 2 on warnings; only `0` when unset.
+    pub success_codes: Option<Vec<i32>>,
+    /// A run whose stdout or stderr matches this regular expression fails,
+    /// whatever its exit code.
+    pub fail_regex: Option<String>,
+    /// Runs again after a failure, for errors that tend to go away, such as
+    /// throttling.
+    pub retry: Option<RetryPolicy>,
+    /// Shell commands run before and after the script, after the workspace's.
+    pub hooks: Option<Hooks>,
+    /// `true` when the script asks questions with `OMAKURE_PROMPT`; only then
+    /// is its stdin kept open for the answers.
+    pub prompts: Option<bool>,
+}
+
+/// The `Hooks` of a script, or the `[hooks]` table of `omakure.toml`: shell
+/// command lines run around every run, from the script's folder.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Hooks {
+    /// Runs first; when it fails, the script does not run.
+    pub before: Option<String>,
+    /// Runs last, whether or not the script succeeded.
+    pub after: Option<String>,
+}
+
+/// The `Retry` of a script.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct RetryPolicy {
+    /// Runs in all, the first one included.
+    pub attempts: u32,
+    /// Seconds to wait before each retry; none when unset.
+    pub delay_seconds: Option<u64>,
+    /// Exit codes worth another attempt; any failure when unset.
+    pub on_exit_codes: Option<Vec<i32>>,
+}
+
+impl RetryPolicy {
+    /// Whether attempt `attempt`, counted from 1, failed in a way worth
+    /// running again. A run stopped by a signal or a timeout is not retried.
+    pub fn should_retry(&self, attempt: u32, exit_code: Option<i32>) -> bool {
+        let Some(code) = exit_code else {
+            return false;
+        };
+        attempt < self.attempts
+            && self
+                .on_exit_codes
+                .as_ref()
+                .is_none_or(|codes| codes.contains(&code))
+    }
+
+    pub fn delay(&self) -> Duration {
+        Duration::from_secs(self.delay_seconds.unwrap_or(0))
+    }
+}
+
+/// The `Runtime` of a script: a Docker or Podman container.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContainerRuntime {
+    pub image: String,
+    /// `docker` or `podman`; whichever is installed when unset.
+    pub engine: Option<String>,
+}
+
+/// A variable a script declares in its `Env` map.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct EnvVar {
+    /// Used when the variable is not already set and `FromField` gives no value.
+    pub default: Option<String>,
+    /// Name of the field whose value the variable takes.
+    pub from_field: Option<String>,
+}
+
+/// GitHub Actions workflow triggered by a `workflow_dispatch` action.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct WorkflowTarget {
+    /// `owner/name` of the repository.
+    pub repo: String,
+    /// Workflow file name (e.g. `deploy.yml`) or numeric id.
+    pub workflow: String,
+    /// Branch or tag to run on; defaults to `main`.
+    #[serde(rename = "Ref")]
+    pub git_ref: Option<String>,
+    /// Seconds between status checks; defaults to 10.
+    pub poll_seconds: Option<u64>,
+    /// Minutes to wait for the run to complete before failing; defaults to 60.
+    pub timeout_minutes: Option<u64>,
+}
+
+impl Schema {
+    /// Tagged `terraform` or `opentofu`: runs plan first and applies only once approved.
+    pub fn is_terraform(&self) -> bool {
+        self.tags.iter().flatten().any(|tag| {
+            super::terraform::TERRAFORM_TAGS
+                .iter()
+                .any(|name| tag.eq_ignore_ascii_case(name))
+        })
+    }
+
+    /// `Owner (Contact)`, or whichever of the two is set.
+    pub fn owner_label(&self) -> Option<String> {
+        let owner = self
+            .owner
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty());
+        let contact = self
+            .contact
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty());
+        match (owner, contact) {
+            (Some(owner), Some(contact)) => Some(format!("{} ({})", owner, contact)),
+            (Some(value), None) | (None, Some(value)) => Some(value.to_string()),
+            (None, None) => None,
+        }
+    }
+
+    /// The concurrency group, ignoring blank values.
+    pub fn concurrency_group(&self) -> Option<&str> {
+        self.concurrency_group
+            .as_deref()
+            .map(str::trim)
+            .filter(|group| !group.is_empty())
+    }
+
+    /// Whether a run that exited with `exit_code` and printed `stdout` and
+    /// `stderr` succeeded, by `SuccessCodes` and `FailRegex`. A run without
+    /// an exit code was stopped and never succeeds; a `FailRegex` that does
+    /// not compile is ignored (`omakure validate` reports it).
+    pub fn run_succeeded(&self, exit_code: Option<i32>, stdout: &str, stderr: &str) -> bool {
+        let Some(code) = exit_code else {
+            return false;
+        };
+        let accepted = match &self.success_codes {
+            Some(codes) => codes.contains(&code),
+            None => code == 0,
+        };
+        if !accepted {
+            return false;
+        }
+        match self.fail_regex.as_deref().map(Regex::new) {
+            Some(Ok(pattern)) => !pattern.is_match(stdout) && !pattern.is_match(stderr),
+            _ => true,
+        }
+    }
+
+    /// Whether a run of `elapsed_ms` exceeded `factor` times the expected duration.
+    pub fn is_overdue(&self, elapsed_ms: u64, factor: u64) -> bool {
+        match self.expected_duration_seconds {
+            Some(expected) if expected > 0 => {
+                elapsed_ms > expected.saturating_mul(1000).saturating_mul(factor)
+            }
+            _ => false,
+        }
+    }
 }
 
 /// Script input field definition.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "PascalCase")]
 pub struct Field {
     pub name: String,
@@ -28,16 +228,58 @@ pub struct Field {
 }
 
 /// Script output field definition.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "PascalCase")]
 pub struct OutputField {
     pub name: String,
     #[serde(rename = "Type")]
     pub kind: String,
+    /// For `file` outputs: a path or glob, relative to the script's folder,
+    /// of files copied into the run's artifacts folder.
+    pub path: Option<String>,
+}
+
+impl OutputField {
+    /// The path pattern of a `file` output.
+    pub fn file_pattern(&self) -> Option<&str> {
+        self.path
+            .as_deref()
+            .filter(|_| self.kind.eq_ignore_ascii_case("file"))
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+    }
+}
+
+/// Process priority and resource limits applied to a script run.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct RunLimits {
+    /// Scheduling niceness, from -20 (highest) to 19 (lowest).
+    pub nice: Option<i32>,
+    /// `idle`, or a best-effort level from `0` (highest) to `7` (lowest).
+    pub io_priority: Option<String>,
+    pub max_memory_mb: Option<u64>,
+    pub max_open_files: Option<u64>,
+}
+
+impl RunLimits {
+    /// Returns these limits with unset values taken from `base`.
+    pub fn or(self, base: &RunLimits) -> RunLimits {
+        RunLimits {
+            nice: self.nice.or(base.nice),
+            io_priority: self.io_priority.or_else(|| base.io_priority.clone()),
+            max_memory_mb: self.max_memory_mb.or(base.max_memory_mb),
+            max_open_files: self.max_open_files.or(base.max_open_files),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == RunLimits::default()
+    }
 }
 
 /// Optional queue specification for batch execution.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "PascalCase")]
 pub struct QueueSpec {
     pub matrix: Option<MatrixSpec>,
@@ -45,14 +287,14 @@ pub struct QueueSpec {
 }
 
 /// Matrix specification for batch execution.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "PascalCase")]
 pub struct MatrixSpec {
     pub values: Vec<MatrixValue>,
 }
 
 /// Matrix value.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "PascalCase")]
 pub struct MatrixValue {
     pub name: String,
@@ -60,7 +302,7 @@ pub struct MatrixValue {
 }
 
 /// Queue case entry.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "PascalCase")]
 pub struct QueueCase {
     pub name: Option<String>,
@@ -68,7 +310,7 @@ pub struct QueueCase {
 }
 
 /// Queue case value.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "PascalCase")]
 pub struct CaseValue {
     pub name: String,
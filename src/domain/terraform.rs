@@ -0,0 +1,125 @@
+use std::path::Path;
+
+/// Tags that turn on the plan-then-apply run mode.
+pub const TERRAFORM_TAGS: [&str; 2] = ["terraform", "opentofu"];
+/// Env var telling a Terraform script which phase to run: `plan` or `apply`.
+pub const PHASE_ENV: &str = "OMAKURE_TF_PHASE";
+/// Env var holding the plan file the plan phase writes and the apply phase applies.
+pub const PLAN_FILE_ENV: &str = "OMAKURE_TF_PLAN";
+
+/// Name of the plan file inside the plan run's artifacts folder.
+pub const PLAN_FILE_NAME: &str = "tfplan";
+
+/// Environment for one phase of a Terraform run.
+pub fn phase_env(phase: &str, plan_file: &Path) -> Vec<(String, String)> {
+    vec![
+        (PHASE_ENV.to_string(), phase.to_string()),
+        (
+            PLAN_FILE_ENV.to_string(),
+            plan_file.to_string_lossy().to_string(),
+        ),
+    ]
+}
+
+/// Resource counts from the `Plan:` line of `terraform plan` (or `tofu plan`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlanSummary {
+    pub add: u64,
+    pub change: u64,
+    pub destroy: u64,
+}
+
+impl PlanSummary {
+    pub fn has_changes(&self) -> bool {
+        self.add + self.change + self.destroy > 0
+    }
+}
+
+/// Reads the change counts from plan output, colored or not.
+///
+/// `No changes.` counts as an empty plan; `None` means the output has neither
+/// line, e.g. because the plan failed.
+pub fn parse_plan_summary(output: &str) -> Option<PlanSummary> {
+    let plain = strip_ansi(output);
+    for line in plain.lines().map(str::trim) {
+        if line.starts_with("No changes.") {
+            return Some(PlanSummary::default());
+        }
+        let Some(rest) = line.strip_prefix("Plan:") else {
+            continue;
+        };
+        let mut summary = PlanSummary::default();
+        // "1 to add, 2 to change, 3 to destroy." (imports and forgets may follow)
+        for part in rest.trim_end_matches('.').split(',') {
+            let mut words = part.split_whitespace();
+            let (Some(count), Some("to"), Some(action)) =
+                (words.next(), words.next(), words.next())
+            else {
+                continue;
+            };
+            let Ok(count) = count.parse() else {
+                continue;
+            };
+            match action {
+                "add" => summary.add = count,
+                "change" => summary.change = count,
+                "destroy" => summary.destroy = count,
+                _ => {}
+            }
+        }
+        return Some(summary);
+    }
+    None
+}
+
+/// Drops ANSI escape sequences such as the colors Terraform prints by default.
+pub fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != '\x1b' {
+            plain.push(ch);
+            continue;
+        }
+        if chars.peek() == Some(&'[') {
+            chars.next();
+            // Parameters run until the final byte in `@`..=`~`.
+            for next in chars.by_ref() {
+                if ('@'..='~').contains(&next) {
+                    break;
+                }
+            }
+        }
+    }
+    plain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plan_summary() {
+        let output = "Terraform will perform the following actions:\n\
+                      \x1b[1mPlan:\x1b[0m 2 to add, 1 to change, 0 to destroy.\n";
+        assert_eq!(
+            parse_plan_summary(output),
+            Some(PlanSummary {
+                add: 2,
+                change: 1,
+                destroy: 0,
+            })
+        );
+
+        let output = "Plan: 1 to import, 0 to add, 0 to change, 3 to destroy.";
+        let summary = parse_plan_summary(output).unwrap();
+        assert_eq!(summary.destroy, 3);
+        assert!(summary.has_changes());
+
+        let output = "\x1b[32mNo changes.\x1b[0m Your infrastructure matches the configuration.";
+        assert_eq!(parse_plan_summary(output), Some(PlanSummary::default()));
+        assert!(!PlanSummary::default().has_changes());
+
+        assert_eq!(parse_plan_summary("Error: Invalid provider"), None);
+    }
+}
@@ -1,8 +1,15 @@
+use std::fmt;
+
 use crate::error::SchemaError;
 
-use super::schema::Field;
+use super::locale::{canonical_number, parse_bool};
+use super::paths::{convert_path, PathStyle};
+use super::schema::{Field, Schema};
 
 /// Normalizes and validates a field input value.
+///
+/// Numbers and booleans may be typed in other locales' forms (`3,14`, `ja`,
+/// `はい`); they come back as `3.14` and `true`/`false`.
 pub fn normalize_input(field: &Field, input: &str) -> Result<Option<String>, SchemaError> {
     let trimmed = input.trim();
     let required = field.required.unwrap_or(false);
@@ -30,13 +37,11 @@ pub fn normalize_input(field: &Field, input: &str) -> Result<Option<String>, Sch
 
     let kind = field.kind.to_lowercase();
     match kind.as_str() {
-        "string" => Ok(Some(raw_value)),
-        "number" => {
-            if raw_value.parse::<f64>().is_err() {
-                return Err(SchemaError::InvalidNumber);
-            }
-            Ok(Some(raw_value))
-        }
+        "string" | "path" => Ok(Some(raw_value)),
+        "number" => match canonical_number(&raw_value) {
+            Some(value) => Ok(Some(value)),
+            None => Err(SchemaError::InvalidNumber),
+        },
         "bool" | "boolean" => match parse_bool(&raw_value) {
             Some(value) => Ok(Some(value.to_string())),
             None => Err(SchemaError::InvalidBoolean),
@@ -45,12 +50,127 @@ pub fn normalize_input(field: &Field, input: &str) -> Result<Option<String>, Sch
     }
 }
 
-fn parse_bool(input: &str) -> Option<bool> {
-    match input.trim().to_lowercase().as_str() {
-        "true" | "t" | "yes" | "y" | "1" => Some(true),
-        "false" | "f" | "no" | "n" | "0" => Some(false),
-        _ => None,
+/// A field that failed validation while building script arguments.
+#[derive(Debug)]
+pub struct FieldValidationError {
+    pub index: usize,
+    pub name: String,
+    pub error: SchemaError,
+}
+
+impl fmt::Display for FieldValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.error)
+    }
+}
+
+impl std::error::Error for FieldValidationError {}
+
+/// The flag a field is passed with: its `Arg`, or `--<name>`.
+pub fn field_arg(field: &Field) -> String {
+    field
+        .arg
+        .clone()
+        .unwrap_or_else(|| format!("--{}", field.name))
+}
+
+/// Normalizes every field input and builds the script argument list.
+///
+/// `inputs` is matched to `fields` by index; missing inputs count as empty.
+/// Values of `path` fields are rewritten into `path_style`.
+pub fn build_args(
+    fields: &[Field],
+    inputs: &[String],
+    path_style: PathStyle,
+) -> Result<Vec<String>, FieldValidationError> {
+    let mut args = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        let input = inputs.get(index).map(String::as_str).unwrap_or("");
+        match normalize_input(field, input) {
+            Ok(Some(value)) => {
+                args.push(field_arg(field));
+                if field.kind.eq_ignore_ascii_case("path") {
+                    args.push(convert_path(&value, path_style));
+                } else {
+                    args.push(value);
+                }
+            }
+            Ok(None) => {}
+            Err(error) => {
+                return Err(FieldValidationError {
+                    index,
+                    name: field.name.clone(),
+                    error,
+                })
+            }
+        }
     }
+    Ok(args)
+}
+
+/// Recovers form inputs from an argument list produced by `build_args`.
+///
+/// Fields whose flag is absent get an empty input.
+pub fn inputs_from_args(fields: &[Field], args: &[String]) -> Vec<String> {
+    fields
+        .iter()
+        .map(|field| {
+            let flag = field_arg(field);
+            args.iter()
+                .position(|arg| *arg == flag)
+                .and_then(|index| args.get(index + 1))
+                .cloned()
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Variables the schema's `Env` sets for a run with `args`.
+///
+/// A `FromField` variable takes that field's value from `args`; otherwise,
+/// or when the field was left empty, the `Default` applies unless `is_set`
+/// says the variable already has a value.
+pub fn schema_env(
+    schema: &Schema,
+    args: &[String],
+    is_set: impl Fn(&str) -> bool,
+) -> Vec<(String, String)> {
+    let Some(env) = &schema.env else {
+        return Vec::new();
+    };
+    env.iter()
+        .filter_map(|(name, var)| {
+            let from_field = var.from_field.as_deref().and_then(|field_name| {
+                let field = schema
+                    .fields
+                    .iter()
+                    .find(|field| field.name == field_name)?;
+                let flag = field_arg(field);
+                let index = args.iter().position(|arg| *arg == flag)?;
+                args.get(index + 1).cloned()
+            });
+            let value = match from_field {
+                Some(value) => value,
+                None if is_set(name) => return None,
+                None => var.default.clone()?,
+            };
+            Some((name.clone(), value))
+        })
+        .collect()
+}
+
+/// Names in the schema's `RequiresEnv` that neither `is_set` nor the
+/// schema's own `Env` give a value for a run with `args`.
+pub fn missing_env(schema: &Schema, args: &[String], is_set: impl Fn(&str) -> bool) -> Vec<String> {
+    let Some(required) = &schema.requires_env else {
+        return Vec::new();
+    };
+    let from_schema = schema_env(schema, args, &is_set);
+    required
+        .iter()
+        .filter(|name| !is_set(name) && !from_schema.iter().any(|(key, _)| key == *name))
+        .cloned()
+        .collect()
 }
 
 #[cfg(test)]
@@ -109,6 +229,24 @@ mod tests {
         assert_eq!(result, Some("3.14".to_string()));
     }
 
+    #[test]
+    fn test_normalize_input_locale_variants() {
+        let count = make_field("count", "number", false);
+        assert_eq!(
+            normalize_input(&count, "1.234,5").unwrap(),
+            Some("1234.5".to_string())
+        );
+        let flag = make_field("flag", "bool", false);
+        assert_eq!(
+            normalize_input(&flag, "Nein").unwrap(),
+            Some("false".to_string())
+        );
+        assert_eq!(
+            normalize_input(&flag, "はい").unwrap(),
+            Some("true".to_string())
+        );
+    }
+
     #[test]
     fn test_normalize_input_number_invalid() {
         let field = make_field("count", "number", false);
@@ -176,4 +314,107 @@ mod tests {
             SchemaError::InvalidChoice { .. }
         ));
     }
+
+    #[test]
+    fn test_build_args_uses_arg_or_name() {
+        let mut target = make_field("target", "string", true);
+        target.arg = Some("-t".to_string());
+        let force = make_field("force", "bool", false);
+        let note = make_field("note", "string", false);
+        let args = build_args(
+            &[target, force, note],
+            &["prod".to_string(), "yes".to_string()],
+            PathStyle::Unix,
+        )
+        .unwrap();
+        assert_eq!(args, vec!["-t", "prod", "--force", "true"]);
+    }
+
+    #[test]
+    fn test_build_args_reports_failing_field() {
+        let first = make_field("first", "string", false);
+        let second = make_field("second", "number", true);
+        let err = build_args(&[first, second], &[], PathStyle::Unix).unwrap_err();
+        assert_eq!(err.index, 1);
+        assert_eq!(err.to_string(), "second: Value required");
+    }
+
+    #[test]
+    fn test_inputs_from_args_round_trips() {
+        let mut target = make_field("target", "string", true);
+        target.arg = Some("-t".to_string());
+        let force = make_field("force", "bool", false);
+        let note = make_field("note", "string", false);
+        let args = vec![
+            "-t".to_string(),
+            "prod".to_string(),
+            "--force".to_string(),
+            "true".to_string(),
+        ];
+        assert_eq!(
+            inputs_from_args(&[target, force, note], &args),
+            vec!["prod", "true", ""]
+        );
+    }
+
+    #[test]
+    fn test_schema_env_reads_fields_and_defaults() {
+        let mut schema = crate::domain::parse_schema(
+            r#"{
+                "Name": "deploy",
+                "Fields": [{ "Name": "target", "Type": "string", "Order": 1, "Arg": "-t" }],
+                "Env": {
+                    "TARGET": { "FromField": "target", "Default": "dev" },
+                    "LOG_LEVEL": { "Default": "info" },
+                    "REGION": { "Default": "eu-west-1" },
+                    "TOKEN": {}
+                }
+            }"#,
+        )
+        .unwrap();
+        let is_set = |name: &str| name == "REGION";
+        let args = vec!["-t".to_string(), "prod".to_string()];
+        assert_eq!(
+            schema_env(&schema, &args, is_set),
+            vec![
+                ("LOG_LEVEL".to_string(), "info".to_string()),
+                ("TARGET".to_string(), "prod".to_string()),
+            ]
+        );
+        assert_eq!(
+            schema_env(&schema, &[], is_set),
+            vec![
+                ("LOG_LEVEL".to_string(), "info".to_string()),
+                ("TARGET".to_string(), "dev".to_string()),
+            ]
+        );
+        schema.env = None;
+        assert!(schema_env(&schema, &args, is_set).is_empty());
+    }
+
+    #[test]
+    fn test_missing_env_skips_set_and_schema_variables() {
+        let schema = crate::domain::parse_schema(
+            r#"{
+                "Name": "deploy",
+                "Fields": [],
+                "Env": { "REGION": { "Default": "eu-west-1" } },
+                "RequiresEnv": ["SUBSCRIPTION_ID", "REGION", "API_TOKEN"]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            missing_env(&schema, &[], |name| name == "API_TOKEN"),
+            vec!["SUBSCRIPTION_ID".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_build_args_converts_path_fields() {
+        let file = make_field("file", "path", true);
+        let name = make_field("name", "string", true);
+        let inputs = ["C:\\data\\in.csv".to_string(), "a\\b".to_string()];
+        let args = build_args(&[file, name], &inputs, PathStyle::Msys).unwrap();
+        assert_eq!(args, vec!["--file", "/c/data/in.csv", "--name", "a\\b"]);
+    }
 }
@@ -0,0 +1,153 @@
+use crate::app_dirs::AppDirs;
+use crate::util::write_private;
+use age::secrecy::ExposeSecret;
+use age::x25519::Identity;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Extension added to an env file once it is encrypted, e.g. `prod.conf.age`.
+pub const ENCRYPTED_EXTENSION: &str = "age";
+/// age identity decrypting env files, in the global config folder.
+const IDENTITY_FILE: &str = "env.key";
+
+pub fn is_encrypted(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(ENCRYPTED_EXTENSION))
+}
+
+pub fn identity_path() -> Option<PathBuf> {
+    AppDirs::resolve().map(|dirs| dirs.config.join(IDENTITY_FILE))
+}
+
+/// Contents of an env file, decrypted when it is an `.age` file.
+pub fn read_env_file(path: &Path) -> io::Result<String> {
+    if !is_encrypted(path) {
+        return fs::read_to_string(path);
+    }
+    let identity = load_identity()?.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "{} is encrypted, but there is no key at {}",
+                path.display(),
+                identity_path().unwrap_or_default().display()
+            ),
+        )
+    })?;
+    decrypt(&identity, &fs::read(path)?)
+}
+
+/// Encrypts the env file at `path` into `<path>.age` and removes the plain file.
+pub fn encrypt_file(path: &Path) -> io::Result<PathBuf> {
+    let identity = load_or_create_identity()?;
+    let encrypted = with_extension_added(path);
+    fs::write(&encrypted, encrypt(&identity, &fs::read_to_string(path)?)?)?;
+    fs::remove_file(path)?;
+    Ok(encrypted)
+}
+
+/// Writes the plain contents of an `.age` env file next to it and removes it.
+pub fn decrypt_file(path: &Path) -> io::Result<PathBuf> {
+    let plain = path.with_extension("");
+    write_private(&plain, read_env_file(path)?.as_bytes())?;
+    fs::remove_file(path)?;
+    Ok(plain)
+}
+
+fn encrypt(identity: &Identity, contents: &str) -> io::Result<String> {
+    age::encrypt_and_armor(&identity.to_public(), contents.as_bytes())
+        .map_err(|err| io::Error::other(err.to_string()))
+}
+
+fn decrypt(identity: &Identity, data: &[u8]) -> io::Result<String> {
+    let plain = age::decrypt(identity, data).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Decrypting env file failed: {}", err),
+        )
+    })?;
+    String::from_utf8(plain).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// The identity, or `None` when none was created yet. Any age identity file
+/// works, so an existing key can be copied in.
+pub fn load_identity() -> io::Result<Option<Identity>> {
+    let path = identity_path().ok_or_else(no_home)?;
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    contents
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .and_then(|line| line.parse::<Identity>().ok())
+        .map(Some)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} holds no age identity", path.display()),
+            )
+        })
+}
+
+/// Loads the identity, creating one readable only by the user on first use.
+fn load_or_create_identity() -> io::Result<Identity> {
+    if let Some(identity) = load_identity()? {
+        return Ok(identity);
+    }
+    let path = identity_path().ok_or_else(no_home)?;
+    let identity = Identity::generate();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let contents = format!(
+        "# public key: {}\n{}\n",
+        identity.to_public(),
+        identity.to_string().expose_secret()
+    );
+    write_private(&path, contents.as_bytes())?;
+    Ok(identity)
+}
+
+fn with_extension_added(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(ENCRYPTED_EXTENSION);
+    PathBuf::from(name)
+}
+
+fn no_home() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        "No home directory for the env file key",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_round_trips() {
+        let identity = Identity::generate();
+        let contents = "REGION=eu-1\nAPI_TOKEN=abc\n";
+        let encrypted = encrypt(&identity, contents).unwrap();
+        assert!(encrypted.starts_with("-----BEGIN AGE ENCRYPTED FILE-----"));
+        assert!(!encrypted.contains("abc"));
+        assert_eq!(decrypt(&identity, encrypted.as_bytes()).unwrap(), contents);
+        assert!(decrypt(&Identity::generate(), encrypted.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_encrypted_names() {
+        assert!(is_encrypted(Path::new("envs/prod.conf.age")));
+        assert!(!is_encrypted(Path::new("envs/prod.conf")));
+        assert_eq!(
+            with_extension_added(Path::new("envs/prod.conf")),
+            PathBuf::from("envs/prod.conf.age")
+        );
+    }
+}
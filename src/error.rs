@@ -35,6 +35,9 @@ pub enum SchemaError {
     #[error("Invalid JSON in schema: {0}")]
     InvalidJson(#[from] serde_json::Error),
 
+    #[error("Invalid TOML in schema: {0}")]
+    InvalidToml(String),
+
     #[error("Schema JSON object not found in output")]
     JsonNotFound,
 
@@ -62,6 +65,12 @@ pub enum ScriptError {
 
     #[error("{name} found, but check failed: {message}")]
     DependencyCheckFailed { name: String, message: String },
+
+    #[error("Invalid run limit: {0}")]
+    InvalidLimit(String),
+
+    #[error("Missing tools required by the script: {0}")]
+    RequirementsNotMet(String),
 }
 
 /// Errors related to environment configuration.
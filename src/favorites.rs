@@ -0,0 +1,54 @@
+use crate::workspace::Workspace;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Pinned scripts, relative to the workspace root, in the order they were pinned.
+pub fn load(workspace: &Workspace) -> Vec<PathBuf> {
+    fs::read(workspace.favorites_path())
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(workspace: &Workspace, favorites: &[PathBuf]) -> io::Result<()> {
+    let path = workspace.favorites_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let contents = serde_json::to_vec_pretty(favorites).map_err(io::Error::other)?;
+    fs::write(path, contents)
+}
+
+/// Pins `script` or unpins it if already pinned; returns whether it is now pinned.
+pub fn toggle(favorites: &mut Vec<PathBuf>, script: &Path) -> bool {
+    if let Some(index) = favorites.iter().position(|path| path == script) {
+        favorites.remove(index);
+        false
+    } else {
+        favorites.push(script.to_path_buf());
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_and_persist() {
+        let root = std::env::temp_dir().join(format!("omakure-favorites-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let workspace = Workspace::new(root.clone());
+        assert!(load(&workspace).is_empty());
+
+        let mut favorites = Vec::new();
+        assert!(toggle(&mut favorites, Path::new("dns/update.bash")));
+        assert!(toggle(&mut favorites, Path::new("backup.sh")));
+        assert!(!toggle(&mut favorites, Path::new("dns/update.bash")));
+        save(&workspace, &favorites).unwrap();
+
+        assert_eq!(load(&workspace), vec![PathBuf::from("backup.sh")]);
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,234 @@
+use crate::concurrency;
+use crate::history::{self, HistoryEntry};
+use crate::util::read_dir_or_empty;
+use crate::workspace::Workspace;
+use rusqlite::Connection;
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Artifacts folders touched this recently may belong to a run still going,
+/// whose history entry is only written when it ends.
+const ACTIVE_RUN_GRACE: Duration = Duration::from_secs(24 * 60 * 60);
+/// Temp folders omakure creates as `<prefix><pid>`, removed when it exits.
+const TEMP_DIR_PREFIXES: [&str; 5] = [
+    "omakure-update-",
+    "omakure-sync-",
+    "omakure-template-",
+    "omakure-tutorial-",
+    "omakure-flavor-",
+];
+
+/// Something `omakure gc` can reclaim.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Garbage {
+    pub path: PathBuf,
+    pub kind: GarbageKind,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GarbageKind {
+    /// Run folder no history entry points to.
+    Artifacts,
+    /// Queue ticket of an exited process, or an empty queue group.
+    Ticket,
+    /// Temp folder of an update, template, tutorial, or flavor install that did
+    /// not clean up.
+    TempDir,
+    /// `-wal` or `-shm` file of a database that no longer exists.
+    DatabaseLeftover,
+    /// Write-ahead log of a live database, shrunk by a checkpoint.
+    Wal,
+}
+
+impl GarbageKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            GarbageKind::Artifacts => "orphaned artifacts",
+            GarbageKind::Ticket => "stale queue ticket",
+            GarbageKind::TempDir => "temp folder",
+            GarbageKind::DatabaseLeftover => "database leftover",
+            GarbageKind::Wal => "write-ahead log",
+        }
+    }
+}
+
+/// Everything that can go, given the workspace's history `entries`.
+pub fn collect(workspace: &Workspace, entries: &[HistoryEntry]) -> io::Result<Vec<Garbage>> {
+    let mut garbage = Vec::new();
+    let referenced: HashSet<PathBuf> = entries
+        .iter()
+        .map(|entry| history::run_artifacts_dir(workspace, entry))
+        .collect();
+    let now = SystemTime::now();
+    for entry in read_dir_or_empty(&workspace.artifacts_dir())? {
+        let path = entry.path();
+        let recent = now
+            .duration_since(newest_modified(&path))
+            .is_ok_and(|age| age < ACTIVE_RUN_GRACE);
+        if path.is_dir() && !referenced.contains(&path) && !recent {
+            garbage.push(item(path, GarbageKind::Artifacts));
+        }
+    }
+    for path in concurrency::stale_entries(workspace)? {
+        garbage.push(item(path, GarbageKind::Ticket));
+    }
+    for entry in read_dir_or_empty(&env::temp_dir())? {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let pid = TEMP_DIR_PREFIXES
+            .iter()
+            .find_map(|prefix| name.strip_prefix(prefix)?.parse::<u32>().ok());
+        if pid.is_some_and(|pid| !concurrency::process_alive(pid)) {
+            garbage.push(item(entry.path(), GarbageKind::TempDir));
+        }
+    }
+    for db in [workspace.history_db_path(), workspace.search_db_path()] {
+        for suffix in ["-wal", "-shm"] {
+            let path = sidecar(&db, suffix);
+            if !path.is_file() {
+                continue;
+            }
+            if !db.exists() {
+                garbage.push(item(path, GarbageKind::DatabaseLeftover));
+            } else if suffix == "-wal" && size_of(&path) > 0 {
+                garbage.push(item(path, GarbageKind::Wal));
+            }
+        }
+    }
+    Ok(garbage)
+}
+
+/// Removes `garbage`, or checkpoints its database for a write-ahead log.
+/// Returns the bytes reclaimed.
+pub fn reclaim(garbage: &Garbage) -> Result<u64, String> {
+    let removed = match garbage.kind {
+        GarbageKind::Wal => checkpoint(&garbage.path),
+        _ if garbage.path.is_dir() => {
+            fs::remove_dir_all(&garbage.path).map_err(|err| err.to_string())
+        }
+        _ => fs::remove_file(&garbage.path).map_err(|err| err.to_string()),
+    };
+    removed
+        .map(|()| garbage.bytes.saturating_sub(size_of(&garbage.path)))
+        .map_err(|err| format!("{}: {}", garbage.path.display(), err))
+}
+
+/// Copies the write-ahead log at `wal` into its database and truncates it.
+fn checkpoint(wal: &Path) -> Result<(), String> {
+    let name = wal.to_string_lossy();
+    let db = PathBuf::from(name.trim_end_matches("-wal"));
+    let conn = Connection::open(&db).map_err(|err| err.to_string())?;
+    let busy: i64 = conn
+        .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| row.get(0))
+        .map_err(|err| err.to_string())?;
+    if busy != 0 {
+        return Err("database in use, try again later".to_string());
+    }
+    Ok(())
+}
+
+fn item(path: PathBuf, kind: GarbageKind) -> Garbage {
+    let bytes = size_of(&path);
+    Garbage { path, kind, bytes }
+}
+
+/// Size of a file, or of everything in a folder.
+fn size_of(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    read_dir_or_empty(path)
+        .unwrap_or_default()
+        .iter()
+        .map(|entry| size_of(&entry.path()))
+        .sum()
+}
+
+/// Latest modification of a folder or the files directly in it.
+fn newest_modified(path: &Path) -> SystemTime {
+    let modified = |path: &Path| {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .unwrap_or(SystemTime::UNIX_EPOCH)
+    };
+    read_dir_or_empty(path)
+        .unwrap_or_default()
+        .iter()
+        .map(|entry| modified(&entry.path()))
+        .fold(modified(path), SystemTime::max)
+}
+
+fn sidecar(db: &Path, suffix: &str) -> PathBuf {
+    let mut path = db.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_finds_orphans_but_not_referenced_or_recent_runs() {
+        let root = std::env::temp_dir().join(format!("omakure-gc-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let workspace = Workspace::isolated(root.clone());
+        let artifacts = workspace.artifacts_dir();
+        for name in ["100-kept_bash", "200-orphan_bash", "300-running_bash"] {
+            fs::create_dir_all(artifacts.join(name)).unwrap();
+            fs::write(artifacts.join(name).join("stdout.log"), "output").unwrap();
+        }
+        let old = SystemTime::now() - ACTIVE_RUN_GRACE * 2;
+        for name in ["100-kept_bash", "200-orphan_bash"] {
+            for path in [
+                artifacts.join(name),
+                artifacts.join(name).join("stdout.log"),
+            ] {
+                fs::File::open(&path)
+                    .and_then(|file| file.set_modified(old))
+                    .unwrap();
+            }
+        }
+        let kept = HistoryEntry {
+            artifacts: Some(PathBuf::from(".history/artifacts/100-kept_bash")),
+            ..history::error_entry(&workspace, Path::new("kept.bash"), &[], String::new())
+        };
+        fs::write(sidecar(&workspace.history_db_path(), "-shm"), "x").unwrap();
+
+        let garbage = collect(&workspace, &[kept]).unwrap();
+        let found: Vec<_> = garbage
+            .iter()
+            .filter(|item| item.path.starts_with(&root))
+            .map(|item| {
+                (
+                    item.path.strip_prefix(&root).unwrap().to_path_buf(),
+                    item.kind,
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (
+                    PathBuf::from(".history/artifacts/200-orphan_bash"),
+                    GarbageKind::Artifacts
+                ),
+                (
+                    PathBuf::from(".history/history.sqlite-shm"),
+                    GarbageKind::DatabaseLeftover
+                ),
+            ]
+        );
+        assert_eq!(garbage[0].bytes, 6);
+        assert_eq!(reclaim(&garbage[0]), Ok(6));
+        assert!(!artifacts.join("200-orphan_bash").exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -1,10 +1,31 @@
-use crate::ports::ScriptRunOutput;
+use crate::config;
+use crate::error::AppResult;
+use crate::ports::{HookRun, HookStage, ResourceUsage, ScriptRunOutput};
+use crate::receipt;
+use crate::search_index;
+use crate::status::{FAIL_SYMBOL, OK_SYMBOL, WARN_SYMBOL};
 use crate::workspace::Workspace;
+use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// `PRAGMA user_version` once the legacy per-run JSON files were imported.
+const SCHEMA_VERSION: i64 = 1;
+/// Where imported JSON files are moved, relative to the history folder.
+const LEGACY_JSON_DIR: &str = "json-backup";
+/// Longest gap between two runs for the second to count as a follow-up.
+const FOLLOW_UP_WINDOW_MS: i64 = 30 * 60 * 1000;
+/// Times a follow-up must have happened before it is suggested.
+const MIN_FOLLOW_UPS: usize = 2;
+/// Latest runs of each script checked for flaky results.
+const FLAKY_WINDOW: usize = 20;
+/// Status flips between runs with the same args that make a script flaky,
+/// e.g. passed, failed, passed.
+const FLAKY_FLIPS: usize = 2;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryEntry {
@@ -16,6 +37,50 @@ pub struct HistoryEntry {
     pub stdout: String,
     pub stderr: String,
     pub error: Option<String>,
+    #[serde(default)]
+    pub usage: Option<ResourceUsage>,
+    /// Wall-clock run time; `None` when the script never started.
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    /// Per-run folder, relative to the workspace root.
+    #[serde(default)]
+    pub artifacts: Option<PathBuf>,
+    /// Members of a grouped entry, e.g. one run per environment; empty otherwise.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub runs: Vec<GroupRun>,
+    /// Ad-hoc shell command run from the TUI `:` prompt; `script` then reads `:<command>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Names of the variables set with `omakure run -e`; their values are not kept.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env_overrides: Vec<String>,
+    /// Failed attempts run again under the script's `Retry` before this one.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub retries: u32,
+    /// `Hooks` commands run around the script, with their output.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hooks: Vec<HookRun>,
+    /// Files of `file` outputs copied into `artifacts`, relative to it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<PathBuf>,
+}
+
+/// One run inside a grouped history entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupRun {
+    /// What the run varied on, such as the env file name.
+    pub label: String,
+    pub args: Vec<String>,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    /// The label names an inventory host the run went to over SSH.
+    #[serde(default)]
+    pub host: bool,
 }
 
 pub fn success_entry(
@@ -33,6 +98,15 @@ pub fn success_entry(
         stdout: output.stdout,
         stderr: output.stderr,
         error: None,
+        usage: output.usage,
+        duration_ms: Some(output.duration_ms),
+        artifacts: output.log_dir.map(|dir| script_path(workspace, &dir)),
+        runs: Vec::new(),
+        command: None,
+        env_overrides: Vec::new(),
+        retries: output.retries,
+        hooks: output.hooks,
+        files: output.files,
     }
 }
 
@@ -51,85 +125,870 @@ pub fn error_entry(
         stdout: String::new(),
         stderr: String::new(),
         error: Some(message),
+        usage: None,
+        duration_ms: None,
+        artifacts: None,
+        runs: Vec::new(),
+        command: None,
+        env_overrides: Vec::new(),
+        retries: 0,
+        hooks: Vec::new(),
+        files: Vec::new(),
     }
 }
 
-pub fn record_entry(workspace: &Workspace, entry: &HistoryEntry) -> io::Result<PathBuf> {
-    let data = serde_json::to_vec_pretty(entry).map_err(io::Error::other)?;
-    let file_name = history_file_name(entry);
-    let path = workspace.history_dir().join(file_name);
-    fs::write(&path, data)?;
-    Ok(path)
+pub fn group_run(label: &str, args: &[String], result: AppResult<ScriptRunOutput>) -> GroupRun {
+    let (success, exit_code, stdout, stderr, error, duration_ms) = match result {
+        Ok(output) => (
+            output.success,
+            output.exit_code,
+            output.stdout,
+            output.stderr,
+            None,
+            Some(output.duration_ms),
+        ),
+        Err(err) => (
+            false,
+            None,
+            String::new(),
+            String::new(),
+            Some(err.to_string()),
+            None,
+        ),
+    };
+    GroupRun {
+        label: label.to_string(),
+        args: args.to_vec(),
+        success,
+        exit_code,
+        stdout,
+        stderr,
+        error,
+        duration_ms,
+        host: false,
+    }
 }
 
-pub fn load_entries(workspace: &Workspace) -> io::Result<Vec<HistoryEntry>> {
-    let mut entries = Vec::new();
-    let dir_entries = match fs::read_dir(workspace.history_dir()) {
-        Ok(entries) => entries,
-        Err(err) => {
-            if err.kind() == io::ErrorKind::NotFound {
-                return Ok(entries);
-            }
-            return Err(err);
-        }
+/// Entry for an ad-hoc shell command, recorded like a script run.
+pub fn shell_entry(
+    workspace: &Workspace,
+    command: &str,
+    result: AppResult<ScriptRunOutput>,
+) -> HistoryEntry {
+    let label = shell_label(command);
+    let entry = match result {
+        Ok(output) => success_entry(workspace, &label, &[], output),
+        Err(err) => error_entry(workspace, &label, &[], err.to_string()),
     };
+    HistoryEntry {
+        command: Some(command.to_string()),
+        ..entry
+    }
+}
 
-    for entry in dir_entries {
-        let entry = entry?;
-        let path = entry.path();
-        if !path.is_file() {
-            continue;
+/// Stand-in script path for a shell command, as shown in history.
+pub fn shell_label(command: &str) -> PathBuf {
+    PathBuf::from(format!(":{}", command))
+}
+
+/// Entry for a group of runs of `script`; it succeeds only if every run did.
+///
+/// `dir` is the group's folder from `new_run_dir`, kept only if a run logged into it.
+pub fn group_entry(
+    workspace: &Workspace,
+    script: &Path,
+    runs: Vec<GroupRun>,
+    dir: &Path,
+) -> HistoryEntry {
+    let _ = fs::remove_dir(dir);
+    HistoryEntry {
+        timestamp: timestamp_ms(),
+        script: script_path(workspace, script),
+        args: Vec::new(),
+        success: runs.iter().all(|run| run.success),
+        exit_code: None,
+        stdout: String::new(),
+        stderr: String::new(),
+        error: None,
+        usage: None,
+        duration_ms: group_duration(&runs),
+        artifacts: dir.is_dir().then(|| script_path(workspace, dir)),
+        runs,
+        command: None,
+        env_overrides: Vec::new(),
+        retries: 0,
+        hooks: Vec::new(),
+        files: Vec::new(),
+    }
+}
+
+/// Replaces the runs of `entry` that share a label with `runs` and recomputes
+/// its status, keeping the entry's timestamp.
+pub fn merge_group_runs(
+    workspace: &Workspace,
+    entry: &HistoryEntry,
+    runs: Vec<GroupRun>,
+    dir: &Path,
+) -> HistoryEntry {
+    let _ = fs::remove_dir(dir);
+    let mut merged = entry.clone();
+    for run in runs {
+        match merged.runs.iter_mut().find(|old| old.label == run.label) {
+            Some(old) => *old = run,
+            None => merged.runs.push(run),
         }
-        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+    }
+    merged.success = merged.runs.iter().all(|run| run.success);
+    merged.duration_ms = group_duration(&merged.runs);
+    merged.artifacts = dir.is_dir().then(|| script_path(workspace, dir));
+    merged
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+/// Total time of the runs in a group, which run one after another.
+fn group_duration(runs: &[GroupRun]) -> Option<u64> {
+    runs.iter()
+        .filter_map(|run| run.duration_ms)
+        .reduce(|a, b| a + b)
+}
+
+/// Log folder of the run labelled `label` inside a group folder.
+pub fn group_run_dir(dir: &Path, label: &str) -> PathBuf {
+    dir.join(safe_slug(label))
+}
+
+/// The script most often run right after `script`, from newest-first `entries`.
+///
+/// Ties go to the follow-up seen most recently; shell commands are skipped.
+pub fn suggest_next(entries: &[HistoryEntry], script: &Path) -> Option<PathBuf> {
+    let mut counts: Vec<(&Path, usize)> = Vec::new();
+    for pair in entries.windows(2) {
+        let (next, previous) = (&pair[0], &pair[1]);
+        if previous.script != script
+            || next.script == script
+            || next.command.is_some()
+            || next.timestamp - previous.timestamp > FOLLOW_UP_WINDOW_MS
+        {
             continue;
         }
-        let data = match fs::read(&path) {
-            Ok(data) => data,
-            Err(_) => continue,
+        match counts.iter_mut().find(|(path, _)| *path == next.script) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((&next.script, 1)),
+        }
+    }
+    counts
+        .into_iter()
+        .filter(|(_, count)| *count >= MIN_FOLLOW_UPS)
+        .reduce(|best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        })
+        .map(|(path, _)| path.to_path_buf())
+}
+
+/// The newest run of each of the last `limit` distinct scripts, newest first.
+///
+/// Shell commands from the `:` prompt are skipped.
+pub fn recent_scripts(entries: &[HistoryEntry], limit: usize) -> Vec<&HistoryEntry> {
+    let mut recent: Vec<&HistoryEntry> = Vec::new();
+    for entry in entries.iter().filter(|entry| entry.command.is_none()) {
+        if recent.len() == limit {
+            break;
+        }
+        if !recent.iter().any(|seen| seen.script == entry.script) {
+            recent.push(entry);
+        }
+    }
+    recent
+}
+
+/// Scripts whose recent runs with identical args keep flipping between
+/// success and failure, as their history paths.
+///
+/// Only single runs that started count: grouped runs, shell commands, and
+/// runs refused before the script ran are skipped.
+pub fn flaky_scripts(entries: &[HistoryEntry]) -> HashSet<PathBuf> {
+    let mut runs: HashMap<&Path, Vec<&HistoryEntry>> = HashMap::new();
+    for entry in entries
+        .iter()
+        .filter(|entry| entry.command.is_none() && entry.runs.is_empty() && entry.error.is_none())
+    {
+        let script_runs = runs.entry(&entry.script).or_default();
+        if script_runs.len() < FLAKY_WINDOW {
+            script_runs.push(entry);
+        }
+    }
+    runs.into_iter()
+        .filter(|(_, script_runs)| {
+            let mut last_status: HashMap<&[String], (bool, usize)> = HashMap::new();
+            // Newest first, so walk back to count flips in run order.
+            for entry in script_runs.iter().rev() {
+                let (status, flips) = last_status.entry(&entry.args).or_insert((entry.success, 0));
+                if *status != entry.success {
+                    *status = entry.success;
+                    *flips += 1;
+                }
+            }
+            last_status.values().any(|(_, flips)| *flips >= FLAKY_FLIPS)
+        })
+        .map(|(script, _)| script.to_path_buf())
+        .collect()
+}
+
+/// Run counts and timings of one script, as `omakure stats` lists them.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptStats {
+    pub script: PathBuf,
+    pub runs: usize,
+    pub failed: usize,
+    pub average_duration_ms: Option<u64>,
+    pub last_run: i64,
+    pub flaky: bool,
+}
+
+/// Stats for every script in `entries`, most runs first. Shell commands are
+/// skipped; a grouped entry counts once and fails if any of its runs did.
+pub fn script_stats(entries: &[HistoryEntry]) -> Vec<ScriptStats> {
+    let flaky = flaky_scripts(entries);
+    let mut stats: Vec<ScriptStats> = Vec::new();
+    let mut durations: HashMap<PathBuf, Vec<u64>> = HashMap::new();
+    for entry in entries.iter().filter(|entry| entry.command.is_none()) {
+        let index = match stats.iter().position(|stat| stat.script == entry.script) {
+            Some(index) => index,
+            None => {
+                stats.push(ScriptStats {
+                    script: entry.script.clone(),
+                    runs: 0,
+                    failed: 0,
+                    average_duration_ms: None,
+                    last_run: entry.timestamp,
+                    flaky: flaky.contains(&entry.script),
+                });
+                stats.len() - 1
+            }
         };
-        let parsed: HistoryEntry = match serde_json::from_slice(&data) {
-            Ok(entry) => entry,
-            Err(_) => continue,
+        let stat = &mut stats[index];
+        stat.runs += 1;
+        if !entry.success {
+            stat.failed += 1;
+        }
+        stat.last_run = stat.last_run.max(entry.timestamp);
+        if let Some(duration_ms) = entry.duration_ms {
+            durations
+                .entry(entry.script.clone())
+                .or_default()
+                .push(duration_ms);
+        }
+    }
+    for stat in &mut stats {
+        stat.average_duration_ms = durations
+            .get(&stat.script)
+            .filter(|values| !values.is_empty())
+            .map(|values| values.iter().sum::<u64>() / values.len() as u64);
+    }
+    stats.sort_by(|a, b| b.runs.cmp(&a.runs).then_with(|| a.script.cmp(&b.script)));
+    stats
+}
+
+pub fn record_entry(workspace: &Workspace, entry: &HistoryEntry) -> io::Result<()> {
+    let conn = open_db(workspace)?;
+    insert_entry(&conn, entry)?;
+    // Trimming only; the entry just recorded is already stored.
+    let _ = prune_entries(
+        &conn,
+        &config::load_workspace_config(workspace.config_path()),
+    );
+    if entry.command.is_none() {
+        // Search ranking only; a busy or broken index must not lose the entry.
+        let _ =
+            search_index::record_run(&workspace.search_db_path(), &entry.script, entry.timestamp);
+    }
+    write_receipt(workspace, entry);
+    Ok(())
+}
+
+/// Drops runs past the `[history]` `retention_days` and `max_entries`.
+/// Their artifacts folders are left for `omakure gc`.
+fn prune_entries(conn: &Connection, config: &config::WorkspaceConfig) -> io::Result<()> {
+    if let Some(retention) = config.retention() {
+        let cutoff = timestamp_ms().saturating_sub(retention.as_millis() as i64);
+        conn.execute(
+            "DELETE FROM history_entries WHERE timestamp < ?",
+            params![cutoff],
+        )
+        .map_err(db_error)?;
+    }
+    if let Some(max_entries) = config.max_entries() {
+        conn.execute(
+            "DELETE FROM history_entries WHERE id NOT IN \
+             (SELECT id FROM history_entries ORDER BY timestamp DESC, id DESC LIMIT ?)",
+            params![max_entries as i64],
+        )
+        .map_err(db_error)?;
+    }
+    Ok(())
+}
+
+/// Signs the run's receipt when the workspace asks for them.
+fn write_receipt(workspace: &Workspace, entry: &HistoryEntry) {
+    if config::load_workspace_config(workspace.config_path()).receipts() {
+        let _ = receipt::write(workspace, entry);
+    }
+}
+
+/// Fresh per-run folder for a run of `script` starting now.
+pub fn new_run_dir(workspace: &Workspace, script: &Path) -> PathBuf {
+    let slug = safe_slug(&script_path(workspace, script).to_string_lossy());
+    workspace
+        .artifacts_dir()
+        .join(format!("{}-{}", timestamp_ms(), slug))
+}
+
+/// Per-run folder for files produced by or about a run.
+pub fn run_artifacts_dir(workspace: &Workspace, entry: &HistoryEntry) -> PathBuf {
+    if let Some(dir) = &entry.artifacts {
+        return workspace.root().join(dir);
+    }
+    let slug = safe_slug(&entry.script.to_string_lossy());
+    workspace
+        .artifacts_dir()
+        .join(format!("{}-{}", entry.timestamp, slug))
+}
+
+/// Like `format_output`, but with truncated streams read back from their logs.
+/// With a `limit`, only the first and last `limit / 2` bytes of each log are
+/// read, with a marker where the rest was left out.
+///
+/// Returns `None` when the entry has no output logs.
+pub fn load_full_output(
+    workspace: &Workspace,
+    entry: &HistoryEntry,
+    limit: Option<u64>,
+) -> io::Result<Option<String>> {
+    let dir = run_artifacts_dir(workspace, entry);
+    if !entry.runs.is_empty() {
+        let mut found = false;
+        let mut runs = Vec::new();
+        for run in &entry.runs {
+            let run_dir = group_run_dir(&dir, &run.label);
+            let stdout = read_log(&run_dir.join("stdout.log"), limit)?;
+            let stderr = read_log(&run_dir.join("stderr.log"), limit)?;
+            found |= stdout.is_some() || stderr.is_some();
+            runs.push(GroupRun {
+                stdout: stdout.unwrap_or_else(|| run.stdout.clone()),
+                stderr: stderr.unwrap_or_else(|| run.stderr.clone()),
+                ..run.clone()
+            });
+        }
+        if !found {
+            return Ok(None);
+        }
+        let full = HistoryEntry {
+            runs,
+            ..entry.clone()
         };
-        entries.push(parsed);
+        return Ok(Some(format_output(&full)));
+    }
+    let stdout = read_log(&dir.join("stdout.log"), limit)?;
+    let stderr = read_log(&dir.join("stderr.log"), limit)?;
+    if stdout.is_none() && stderr.is_none() {
+        return Ok(None);
     }
+    let full = HistoryEntry {
+        stdout: stdout.unwrap_or_else(|| entry.stdout.clone()),
+        stderr: stderr.unwrap_or_else(|| entry.stderr.clone()),
+        ..entry.clone()
+    };
+    Ok(Some(format_output(&full)))
+}
+
+/// Appends a timestamped line to the run's `notes.md`.
+pub fn append_note(workspace: &Workspace, entry: &HistoryEntry, note: &str) -> io::Result<PathBuf> {
+    use std::io::Write;
+
+    let dir = run_artifacts_dir(workspace, entry);
+    fs::create_dir_all(&dir)?;
+    let path = dir.join("notes.md");
+    let now = timestamp_ms();
+    let stamp = format_iso8601(now, TimeZone::Local.offset_at(now));
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+    writeln!(file, "- {} {}", stamp, note.trim())?;
+    Ok(path)
+}
 
-    entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+fn read_log(path: &Path, limit: Option<u64>) -> io::Result<Option<String>> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = match fs::File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    let len = file.metadata()?.len();
+    let Some(limit) = limit.filter(|limit| len > *limit) else {
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+        return Ok(Some(String::from_utf8_lossy(&data).into_owned()));
+    };
+    let mut head = vec![0; (limit / 2) as usize];
+    file.read_exact(&mut head)?;
+    let tail_len = limit - head.len() as u64;
+    file.seek(SeekFrom::End(-(tail_len as i64)))?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail)?;
+    let mut text = String::from_utf8_lossy(&head).into_owned();
+    text.push_str(&format!(
+        "\n[... {} bytes not loaded; full output in {} ...]\n",
+        len - limit,
+        path.display()
+    ));
+    text.push_str(&String::from_utf8_lossy(&tail));
+    Ok(Some(text))
+}
+
+pub fn load_entries(workspace: &Workspace) -> io::Result<Vec<HistoryEntry>> {
+    let conn = open_db(workspace)?;
+    let mut stmt = conn
+        .prepare("SELECT entry FROM history_entries ORDER BY timestamp DESC, id DESC")
+        .map_err(db_error)?;
+    let rows = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(db_error)?;
+
+    let mut entries = Vec::new();
+    for row in rows {
+        let data = row.map_err(db_error)?;
+        if let Ok(entry) = serde_json::from_str::<HistoryEntry>(&data) {
+            entries.push(entry);
+        }
+    }
     Ok(entries)
 }
 
+/// Overwrites the stored entry with the same timestamp and script.
+pub fn update_entry(workspace: &Workspace, entry: &HistoryEntry) -> io::Result<()> {
+    let conn = open_db(workspace)?;
+    let data = serde_json::to_string(entry).map_err(io::Error::other)?;
+    conn.execute(
+        "UPDATE history_entries SET success = ?, entry = ? WHERE timestamp = ? AND script = ?",
+        params![
+            entry.success,
+            data,
+            entry.timestamp,
+            entry.script.to_string_lossy()
+        ],
+    )
+    .map_err(db_error)?;
+    write_receipt(workspace, entry);
+    Ok(())
+}
+
+/// Opens `history.sqlite`, importing legacy JSON files on first use.
+fn open_db(workspace: &Workspace) -> io::Result<Connection> {
+    fs::create_dir_all(workspace.history_dir())?;
+    let mut conn = Connection::open(workspace.history_db_path()).map_err(db_error)?;
+    conn.busy_timeout(Duration::from_millis(500))
+        .map_err(db_error)?;
+    let _journal_mode: String = conn
+        .query_row("PRAGMA journal_mode = WAL", [], |row| row.get(0))
+        .map_err(db_error)?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS history_entries (\
+            id INTEGER PRIMARY KEY AUTOINCREMENT,\
+            timestamp INTEGER NOT NULL,\
+            script TEXT NOT NULL,\
+            success INTEGER NOT NULL,\
+            entry TEXT NOT NULL\
+        );\
+        CREATE INDEX IF NOT EXISTS idx_history_timestamp ON history_entries(timestamp);\
+        CREATE INDEX IF NOT EXISTS idx_history_script ON history_entries(script);",
+    )
+    .map_err(db_error)?;
+
+    let version: i64 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(db_error)?;
+    if version < SCHEMA_VERSION {
+        migrate_json_entries(workspace, &mut conn)?;
+    }
+    Ok(conn)
+}
+
+fn insert_entry(conn: &Connection, entry: &HistoryEntry) -> io::Result<()> {
+    let data = serde_json::to_string(entry).map_err(io::Error::other)?;
+    conn.execute(
+        "INSERT INTO history_entries (timestamp, script, success, entry) VALUES (?, ?, ?, ?)",
+        params![
+            entry.timestamp,
+            entry.script.to_string_lossy(),
+            entry.success,
+            data
+        ],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
+
+/// Imports `.history/*.json` from before the SQLite store, then moves the files
+/// into `.history/json-backup/` so they are not imported twice.
+fn migrate_json_entries(workspace: &Workspace, conn: &mut Connection) -> io::Result<()> {
+    let mut imported = Vec::new();
+    let tx = conn.transaction().map_err(db_error)?;
+    if let Ok(dir_entries) = fs::read_dir(workspace.history_dir()) {
+        for entry in dir_entries.flatten() {
+            let path = entry.path();
+            if !path.is_file() || path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let Ok(data) = fs::read(&path) else {
+                continue;
+            };
+            let Ok(parsed) = serde_json::from_slice::<HistoryEntry>(&data) else {
+                continue;
+            };
+            insert_entry(&tx, &parsed)?;
+            imported.push(path);
+        }
+    }
+    tx.pragma_update(None, "user_version", SCHEMA_VERSION)
+        .map_err(db_error)?;
+    tx.commit().map_err(db_error)?;
+
+    if !imported.is_empty() {
+        let backup_dir = workspace.history_dir().join(LEGACY_JSON_DIR);
+        fs::create_dir_all(&backup_dir)?;
+        for path in imported {
+            if let Some(name) = path.file_name() {
+                let _ = fs::rename(&path, backup_dir.join(name));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn db_error(err: rusqlite::Error) -> io::Error {
+    io::Error::other(format!("History db failed: {}", err))
+}
+
 pub fn format_output(entry: &HistoryEntry) -> String {
-    if let Some(error) = &entry.error {
+    if !entry.runs.is_empty() {
+        return entry
+            .runs
+            .iter()
+            .map(|run| format!("== {} ==\n{}", run_heading(run), format_run_output(run)))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+    }
+    let output = format_streams(&entry.stdout, &entry.stderr, entry.error.as_deref());
+    if entry.hooks.is_empty() {
+        return output;
+    }
+    let hooks = |stage: HookStage| {
+        entry
+            .hooks
+            .iter()
+            .filter(move |hook| hook.stage == stage)
+            .map(format_hook)
+    };
+    hooks(HookStage::Before)
+        .chain(Some(output).filter(|output| !output.is_empty()))
+        .chain(hooks(HookStage::After))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// A hook's command and output, headed like the runs of a group.
+fn format_hook(hook: &HookRun) -> String {
+    let symbol = if hook.success { OK_SYMBOL } else { FAIL_SYMBOL };
+    let code = match (hook.success, hook.exit_code) {
+        (false, Some(code)) => format!(" (exit {})", code),
+        _ => String::new(),
+    };
+    let heading = format!(
+        "== {} {} hook: {}{} ==",
+        symbol,
+        hook.stage.label(),
+        hook.command,
+        code
+    );
+    let output = format_streams(&hook.stdout, &hook.stderr, None);
+    if output.is_empty() {
+        heading
+    } else {
+        format!("{}\n{}", heading, output)
+    }
+}
+
+/// The collected output files of `entry`, comma-separated.
+pub fn format_files(entry: &HistoryEntry) -> String {
+    entry
+        .files
+        .iter()
+        .map(|file| file.to_string_lossy().replace('\\', "/"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Output of a single run in a group, formatted like `format_output`.
+pub fn format_run_output(run: &GroupRun) -> String {
+    format_streams(&run.stdout, &run.stderr, run.error.as_deref())
+}
+
+/// Status symbol, label, and exit code of a run in a group.
+pub fn run_heading(run: &GroupRun) -> String {
+    match (&run.error, run.success, run.exit_code) {
+        (Some(_), _, _) => format!("{} {} (error)", WARN_SYMBOL, run.label),
+        (None, true, _) => format!("{} {}", OK_SYMBOL, run.label),
+        (None, false, Some(code)) => format!("{} {} (exit {})", FAIL_SYMBOL, run.label, code),
+        (None, false, None) => format!("{} {}", FAIL_SYMBOL, run.label),
+    }
+}
+
+fn format_streams(stdout: &str, stderr: &str, error: Option<&str>) -> String {
+    if let Some(error) = error {
         return error.trim().to_string();
     }
     let mut parts = Vec::new();
-    if !entry.stdout.trim().is_empty() {
-        parts.push(format!("STDOUT:\n{}", entry.stdout.trim_end()));
+    if !stdout.trim().is_empty() {
+        parts.push(format!("STDOUT:\n{}", stdout.trim_end()));
     }
-    if !entry.stderr.trim().is_empty() {
-        parts.push(format!("STDERR:\n{}", entry.stderr.trim_end()));
+    if !stderr.trim().is_empty() {
+        parts.push(format!("STDERR:\n{}", stderr.trim_end()));
     }
     parts.join("\n\n")
 }
 
-pub fn format_timestamp(timestamp_ms: i64) -> String {
-    let mut ms = timestamp_ms;
-    if ms < 0 {
-        ms = 0;
+pub fn format_usage(usage: &ResourceUsage) -> String {
+    let mut text = format!(
+        "cpu user {} / sys {}",
+        format_seconds(usage.user_cpu_ms),
+        format_seconds(usage.system_cpu_ms)
+    );
+    if let Some(max_rss_kb) = usage.max_rss_kb {
+        text.push_str(&format!(", max rss {:.1} MB", max_rss_kb as f64 / 1024.0));
     }
-    let seconds = ms / 1000;
-    let days = seconds.div_euclid(86_400);
-    let seconds_of_day = seconds.rem_euclid(86_400);
-    let hour = seconds_of_day / 3_600;
-    let minute = (seconds_of_day % 3_600) / 60;
+    text
+}
 
-    let (year, month, day) = civil_from_days(days);
+/// Compact run time: `850ms`, `12.3s`, `4m 05s`, or `1h 02m`.
+pub fn format_duration(ms: u64) -> String {
+    if ms < 1000 {
+        return format!("{}ms", ms);
+    }
+    let secs = ms / 1000;
+    if secs < 60 {
+        return format!("{}.{}s", secs, (ms % 1000) / 100);
+    }
+    if secs < 3600 {
+        return format!("{}m {:02}s", secs / 60, secs % 60);
+    }
+    format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60)
+}
+
+/// Plain-text report of a session's runs, one line each: start time, status,
+/// duration, command line, and history id (the entry's `timestamp`).
+pub fn session_summary(entries: &[HistoryEntry], time_zone: TimeZone) -> String {
+    let failed = entries.iter().filter(|entry| !entry.success).count();
+    let mut out = format!(
+        "omakure session: {} run{}, {} ok, {} failed\n",
+        entries.len(),
+        if entries.len() == 1 { "" } else { "s" },
+        entries.len() - failed,
+        failed
+    );
+    for entry in entries {
+        let mut line = entry.script.to_string_lossy().to_string();
+        for arg in &entry.args {
+            line.push(' ');
+            line.push_str(arg);
+        }
+        out.push_str(&format!(
+            "  {}  {:<9} {:>7}  {}  [id {}]\n",
+            format_timestamp(entry.timestamp, time_zone.offset_at(entry.timestamp)),
+            status_label(entry),
+            entry.duration_ms.map(format_duration).unwrap_or_default(),
+            line,
+            entry.timestamp
+        ));
+    }
+    out
+}
+
+fn status_label(entry: &HistoryEntry) -> String {
+    match (&entry.error, entry.success, entry.exit_code) {
+        (Some(_), _, _) => format!("{} error", WARN_SYMBOL),
+        (None, true, _) => format!("{} ok", OK_SYMBOL),
+        (None, false, Some(code)) => format!("{} exit {}", FAIL_SYMBOL, code),
+        (None, false, None) => format!("{} failed", FAIL_SYMBOL),
+    }
+}
+
+fn format_seconds(ms: u64) -> String {
+    format!("{}.{:02}s", ms / 1000, (ms % 1000) / 10)
+}
+
+/// Offset from UTC in minutes, positive east of Greenwich.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct UtcOffset(pub i32);
+
+impl UtcOffset {
+    pub const UTC: UtcOffset = UtcOffset(0);
+
+    /// Parses `Z`, `UTC`, `+02:00`, `-0530`, or `+2`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("z") || value.eq_ignore_ascii_case("utc") {
+            return Some(Self::UTC);
+        }
+        let (sign, rest) = match value.as_bytes().first()? {
+            b'+' => (1, &value[1..]),
+            b'-' => (-1, &value[1..]),
+            _ => return None,
+        };
+        let (hours, minutes) = match rest.split_once(':') {
+            Some((hours, minutes)) => (hours, minutes),
+            None if rest.len() == 4 => rest.split_at(2),
+            None => (rest, "0"),
+        };
+        let hours: i32 = hours.parse().ok()?;
+        let minutes: i32 = minutes.parse().ok()?;
+        if hours > 14 || minutes > 59 {
+            return None;
+        }
+        Some(UtcOffset(sign * (hours * 60 + minutes)))
+    }
+}
+
+/// Time zone used to display history timestamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeZone {
+    /// The system zone, honoring `TZ` on Unix.
+    #[default]
+    Local,
+    Fixed(UtcOffset),
+}
+
+impl TimeZone {
+    /// Parses `local` or anything `UtcOffset::parse` accepts.
+    pub fn parse(value: &str) -> Option<Self> {
+        if value.trim().eq_ignore_ascii_case("local") {
+            return Some(TimeZone::Local);
+        }
+        UtcOffset::parse(value).map(TimeZone::Fixed)
+    }
+
+    pub fn offset_at(self, timestamp_ms: i64) -> UtcOffset {
+        match self {
+            TimeZone::Local => local_offset(timestamp_ms),
+            TimeZone::Fixed(offset) => offset,
+        }
+    }
+}
+
+pub fn format_timestamp(timestamp_ms: i64, offset: UtcOffset) -> String {
+    let (year, month, day, hour, minute, _) = civil_time(timestamp_ms, offset);
     format!(
         "{:04}-{:02}-{:02} {:02}:{:02}",
         year, month, day, hour, minute
     )
 }
 
+/// ISO-8601 timestamp with an explicit offset, e.g. `2024-01-15T14:30:00+02:00`.
+pub fn format_iso8601(timestamp_ms: i64, offset: UtcOffset) -> String {
+    let (year, month, day, hour, minute, second) = civil_time(timestamp_ms, offset);
+    let sign = if offset.0 < 0 { '-' } else { '+' };
+    let offset_minutes = offset.0.abs();
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}{:02}:{:02}",
+        year,
+        month,
+        day,
+        hour,
+        minute,
+        second,
+        sign,
+        offset_minutes / 60,
+        offset_minutes % 60
+    )
+}
+
+/// Short relative age such as `3m ago`.
+pub fn format_relative(timestamp_ms: i64, now_ms: i64) -> String {
+    let seconds = (now_ms - timestamp_ms).max(0) / 1000;
+    match seconds {
+        0..=9 => "just now".to_string(),
+        10..=59 => format!("{}s ago", seconds),
+        60..=3_599 => format!("{}m ago", seconds / 60),
+        3_600..=86_399 => format!("{}h ago", seconds / 3_600),
+        86_400..=2_591_999 => format!("{}d ago", seconds / 86_400),
+        2_592_000..=31_535_999 => format!("{}mo ago", seconds / 2_592_000),
+        _ => format!("{}y ago", seconds / 31_536_000),
+    }
+}
+
+/// Year, month, day, hour, minute, and second of `timestamp_ms` at `offset`.
+pub(crate) fn civil_time(timestamp_ms: i64, offset: UtcOffset) -> (i64, i64, i64, i64, i64, i64) {
+    let seconds = timestamp_ms.max(0) / 1000 + i64::from(offset.0) * 60;
+    let days = seconds.div_euclid(86_400);
+    let seconds_of_day = seconds.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+    (
+        year,
+        month,
+        day,
+        seconds_of_day / 3_600,
+        (seconds_of_day % 3_600) / 60,
+        seconds_of_day % 60,
+    )
+}
+
+#[cfg(unix)]
+fn local_offset(timestamp_ms: i64) -> UtcOffset {
+    let seconds = (timestamp_ms.max(0) / 1000) as libc::time_t;
+    let mut tm = std::mem::MaybeUninit::<libc::tm>::zeroed();
+    // SAFETY: localtime_r only writes into the provided struct.
+    let result = unsafe { libc::localtime_r(&seconds, tm.as_mut_ptr()) };
+    if result.is_null() {
+        return UtcOffset::UTC;
+    }
+    // SAFETY: localtime_r succeeded, so the struct is initialized.
+    let tm = unsafe { tm.assume_init() };
+    UtcOffset((tm.tm_gmtoff / 60) as i32)
+}
+
+#[cfg(windows)]
+fn local_offset(_timestamp_ms: i64) -> UtcOffset {
+    use windows_sys::Win32::System::Time::{GetTimeZoneInformation, TIME_ZONE_INFORMATION};
+
+    const TIME_ZONE_ID_DAYLIGHT: u32 = 2;
+    const TIME_ZONE_ID_INVALID: u32 = u32::MAX;
+
+    // SAFETY: the struct is plain data and GetTimeZoneInformation fills it in.
+    let (id, info) = unsafe {
+        let mut info: TIME_ZONE_INFORMATION = std::mem::zeroed();
+        (GetTimeZoneInformation(&mut info), info)
+    };
+    match id {
+        TIME_ZONE_ID_INVALID => UtcOffset::UTC,
+        TIME_ZONE_ID_DAYLIGHT => UtcOffset(-(info.Bias + info.DaylightBias)),
+        _ => UtcOffset(-(info.Bias + info.StandardBias)),
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn local_offset(_timestamp_ms: i64) -> UtcOffset {
+    UtcOffset::UTC
+}
+
 fn civil_from_days(days: i64) -> (i64, i64, i64) {
     let z = days + 719_468;
     let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
@@ -144,12 +1003,8 @@ fn civil_from_days(days: i64) -> (i64, i64, i64) {
     (year, month, day)
 }
 
-fn history_file_name(entry: &HistoryEntry) -> String {
-    let slug = safe_slug(&entry.script.to_string_lossy());
-    format!("{}-{}-{}.json", entry.timestamp, std::process::id(), slug)
-}
-
-fn safe_slug(input: &str) -> String {
+/// Lowercase ASCII letters and digits, with runs of anything else as one `_`.
+pub(crate) fn safe_slug(input: &str) -> String {
     let mut out = String::new();
     let mut prev_underscore = false;
     for ch in input.chars() {
@@ -173,14 +1028,15 @@ fn safe_slug(input: &str) -> String {
     slug
 }
 
-fn script_path(workspace: &Workspace, script: &Path) -> PathBuf {
+/// `script` relative to the workspace root, as history records it.
+pub(crate) fn script_path(workspace: &Workspace, script: &Path) -> PathBuf {
     script
         .strip_prefix(workspace.root())
         .unwrap_or(script)
         .to_path_buf()
 }
 
-fn timestamp_ms() -> i64 {
+pub fn timestamp_ms() -> i64 {
     let duration = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default();
@@ -191,23 +1047,75 @@ fn timestamp_ms() -> i64 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_read_log_keeps_head_and_tail_within_limit() {
+        let dir = std::env::temp_dir().join(format!("omakure-read-log-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("stdout.log");
+        fs::write(&path, "0123456789abcdefghij").unwrap();
+
+        assert_eq!(
+            read_log(&path, Some(100)).unwrap().as_deref(),
+            Some("0123456789abcdefghij")
+        );
+        let text = read_log(&path, Some(8)).unwrap().unwrap();
+        assert!(text.starts_with("0123\n[... 12 bytes not loaded; full output in "));
+        assert!(text.ends_with(" ...]\nghij"));
+        assert_eq!(read_log(&dir.join("missing.log"), None).unwrap(), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn test_format_timestamp() {
         // 2024-01-15 12:30 UTC = 1705321800000 ms
         let ts = 1705321800000_i64;
-        let formatted = format_timestamp(ts);
+        let formatted = format_timestamp(ts, UtcOffset::UTC);
         assert_eq!(formatted, "2024-01-15 12:30");
     }
 
+    #[test]
+    fn test_format_timestamp_with_offset() {
+        let ts = 1705321800000_i64;
+        let offset = UtcOffset::parse("-05:30").unwrap();
+        assert_eq!(format_timestamp(ts, offset), "2024-01-15 07:00");
+        assert_eq!(format_iso8601(ts, offset), "2024-01-15T07:00:00-05:30");
+        assert_eq!(
+            format_iso8601(ts, UtcOffset::UTC),
+            "2024-01-15T12:30:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_time_zone_parse() {
+        assert_eq!(TimeZone::parse("local"), Some(TimeZone::Local));
+        assert_eq!(TimeZone::parse("UTC"), Some(TimeZone::Fixed(UtcOffset(0))));
+        assert_eq!(
+            TimeZone::parse("+0200"),
+            Some(TimeZone::Fixed(UtcOffset(120)))
+        );
+        assert_eq!(TimeZone::parse("+2"), Some(TimeZone::Fixed(UtcOffset(120))));
+        assert_eq!(TimeZone::parse("Europe/Paris"), None);
+    }
+
+    #[test]
+    fn test_format_relative() {
+        let now = 1705321800000_i64;
+        assert_eq!(format_relative(now - 2_000, now), "just now");
+        assert_eq!(format_relative(now - 180_000, now), "3m ago");
+        assert_eq!(format_relative(now - 7_200_000, now), "2h ago");
+        assert_eq!(format_relative(now - 3 * 86_400_000, now), "3d ago");
+    }
+
     #[test]
     fn test_format_timestamp_zero() {
-        let formatted = format_timestamp(0);
+        let formatted = format_timestamp(0, UtcOffset::UTC);
         assert_eq!(formatted, "1970-01-01 00:00");
     }
 
     #[test]
     fn test_format_timestamp_negative() {
-        let formatted = format_timestamp(-1000);
+        let formatted = format_timestamp(-1000, UtcOffset::UTC);
         assert_eq!(formatted, "1970-01-01 00:00");
     }
 
@@ -252,10 +1160,41 @@ mod tests {
             stdout: "output here\n".to_string(),
             stderr: "".to_string(),
             error: None,
+            usage: None,
+            duration_ms: None,
+            artifacts: None,
+            runs: Vec::new(),
+            command: None,
+            env_overrides: Vec::new(),
+            retries: 0,
+            hooks: Vec::new(),
+            files: Vec::new(),
         };
         let output = format_output(&entry);
         assert!(output.contains("STDOUT:"));
         assert!(output.contains("output here"));
+
+        let hook = |stage, command: &str, exit_code| HookRun {
+            stage,
+            command: command.to_string(),
+            success: exit_code == 0,
+            exit_code: Some(exit_code),
+            stdout: format!("{} ran\n", command),
+            stderr: String::new(),
+        };
+        let entry = HistoryEntry {
+            hooks: vec![
+                hook(HookStage::Before, "az account show", 0),
+                hook(HookStage::After, "rm -rf tmp", 1),
+            ],
+            ..entry
+        };
+        let output = format_output(&entry);
+        let before = output.find("before hook: az account show").unwrap();
+        let script = output.find("output here").unwrap();
+        let after = output.find("after hook: rm -rf tmp (exit 1)").unwrap();
+        assert!(before < script && script < after);
+        assert!(output.contains("rm -rf tmp ran"));
     }
 
     #[test]
@@ -269,8 +1208,277 @@ mod tests {
             stdout: "".to_string(),
             stderr: "".to_string(),
             error: Some("Script failed to run".to_string()),
+            usage: None,
+            duration_ms: None,
+            artifacts: None,
+            runs: Vec::new(),
+            command: None,
+            env_overrides: Vec::new(),
+            retries: 0,
+            hooks: Vec::new(),
+            files: Vec::new(),
         };
         let output = format_output(&entry);
         assert_eq!(output, "Script failed to run");
     }
+
+    #[test]
+    fn test_session_summary() {
+        let ok = HistoryEntry {
+            timestamp: 60_000,
+            script: PathBuf::from("deploy.bash"),
+            args: vec!["--env".to_string(), "prod".to_string()],
+            success: true,
+            exit_code: Some(0),
+            stdout: String::new(),
+            stderr: String::new(),
+            error: None,
+            usage: None,
+            duration_ms: Some(12_345),
+            artifacts: None,
+            runs: Vec::new(),
+            command: None,
+            env_overrides: Vec::new(),
+            retries: 0,
+            hooks: Vec::new(),
+            files: Vec::new(),
+        };
+        let failed = HistoryEntry {
+            timestamp: 120_000,
+            success: false,
+            exit_code: Some(3),
+            args: vec![],
+            ..ok.clone()
+        };
+        let summary = session_summary(&[ok, failed], TimeZone::Fixed(UtcOffset::UTC));
+        let lines: Vec<&str> = summary.lines().collect();
+        assert_eq!(lines[0], "omakure session: 2 runs, 1 ok, 1 failed");
+        assert_eq!(
+            lines[1],
+            "  1970-01-01 00:01  ✓ ok        12.3s  deploy.bash --env prod  [id 60000]"
+        );
+        assert_eq!(
+            lines[2],
+            "  1970-01-01 00:02  ✗ exit 3    12.3s  deploy.bash  [id 120000]"
+        );
+    }
+
+    fn fixture_run(label: &str, success: bool, stdout: &str) -> GroupRun {
+        GroupRun {
+            label: label.to_string(),
+            args: vec![],
+            success,
+            exit_code: Some(if success { 0 } else { 2 }),
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+            error: None,
+            duration_ms: None,
+            host: false,
+        }
+    }
+
+    fn fixture_group(runs: Vec<GroupRun>) -> HistoryEntry {
+        HistoryEntry {
+            timestamp: 0,
+            script: PathBuf::from("check.bash"),
+            args: vec![],
+            success: runs.iter().all(|run| run.success),
+            exit_code: None,
+            stdout: String::new(),
+            stderr: String::new(),
+            error: None,
+            usage: None,
+            duration_ms: None,
+            artifacts: None,
+            runs,
+            command: None,
+            env_overrides: Vec::new(),
+            retries: 0,
+            hooks: Vec::new(),
+            files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_suggest_next_picks_most_common_follow_up() {
+        let run = |timestamp: i64, script: &str| HistoryEntry {
+            timestamp,
+            script: PathBuf::from(script),
+            ..fixture_group(Vec::new())
+        };
+        // Newest first: build -> test twice, build -> deploy once plus once too late.
+        let entries = vec![
+            run(10_000_000, "test.bash"),
+            run(9_990_000, "build.bash"),
+            run(9_000_000, "deploy.bash"),
+            run(8_990_000, "build.bash"),
+            run(8_000_000, "test.bash"),
+            run(7_990_000, "build.bash"),
+            run(2_000_000, "deploy.bash"),
+            run(0, "build.bash"),
+        ];
+        assert_eq!(
+            suggest_next(&entries, Path::new("build.bash")),
+            Some(PathBuf::from("test.bash"))
+        );
+        assert_eq!(suggest_next(&entries, Path::new("test.bash")), None);
+    }
+
+    #[test]
+    fn test_flaky_scripts_flip_with_the_same_args() {
+        let run = |timestamp: i64, script: &str, args: &[&str], success: bool| HistoryEntry {
+            timestamp,
+            script: PathBuf::from(script),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            success,
+            ..fixture_group(Vec::new())
+        };
+        // Newest first.
+        let entries = vec![
+            run(6, "sync.bash", &["--env", "prod"], true),
+            run(5, "sync.bash", &["--env", "prod"], false),
+            run(4, "deploy.bash", &["--env", "dev"], true),
+            run(3, "sync.bash", &["--env", "prod"], true),
+            run(2, "deploy.bash", &["--env", "prod"], false),
+            run(1, "deploy.bash", &["--env", "dev"], false),
+        ];
+        assert_eq!(
+            flaky_scripts(&entries),
+            HashSet::from([PathBuf::from("sync.bash")])
+        );
+        let stats = script_stats(&entries);
+        assert_eq!(stats[0].script, PathBuf::from("deploy.bash"));
+        assert_eq!((stats[0].runs, stats[0].failed), (3, 2));
+        assert!(stats[1].flaky && !stats[0].flaky);
+        assert_eq!(stats[1].last_run, 6);
+    }
+
+    #[test]
+    fn test_recent_scripts_are_distinct_and_limited() {
+        let run = |timestamp: i64, script: &str| HistoryEntry {
+            timestamp,
+            script: PathBuf::from(script),
+            ..fixture_group(Vec::new())
+        };
+        let shell = HistoryEntry {
+            command: Some("ls".to_string()),
+            ..run(6, ":ls")
+        };
+        let entries = vec![
+            shell,
+            run(5, "deploy.bash"),
+            run(4, "build.bash"),
+            run(3, "deploy.bash"),
+            run(2, "test.bash"),
+            run(1, "lint.bash"),
+        ];
+        let recent: Vec<(i64, &str)> = recent_scripts(&entries, 3)
+            .into_iter()
+            .map(|entry| (entry.timestamp, entry.script.to_str().unwrap()))
+            .collect();
+        assert_eq!(
+            recent,
+            vec![(5, "deploy.bash"), (4, "build.bash"), (2, "test.bash")]
+        );
+    }
+
+    #[test]
+    fn test_shell_entry_records_command() {
+        let workspace = Workspace::new(PathBuf::from("/ws"));
+        let entry = shell_entry(
+            &workspace,
+            "kubectl get pods",
+            Err(crate::error::ScriptError::UnsupportedType.into()),
+        );
+        assert_eq!(entry.script, PathBuf::from(":kubectl get pods"));
+        assert_eq!(entry.command.as_deref(), Some("kubectl get pods"));
+        assert!(!entry.success);
+    }
+
+    #[test]
+    fn test_format_output_groups_runs() {
+        let entry = fixture_group(vec![
+            fixture_run("dev.conf", true, "v1\n"),
+            fixture_run("prod.conf", false, "v2\n"),
+        ]);
+        assert_eq!(
+            format_output(&entry),
+            "== ✓ dev.conf ==\nSTDOUT:\nv1\n\n== ✗ prod.conf (exit 2) ==\nSTDOUT:\nv2"
+        );
+    }
+
+    #[test]
+    fn test_merge_group_runs_recomputes_status() {
+        let workspace = Workspace::new(PathBuf::from("/nonexistent/ws"));
+        let entry = fixture_group(vec![
+            fixture_run("dev.conf", true, "a"),
+            fixture_run("prod.conf", false, "b"),
+        ]);
+        assert!(!entry.success);
+
+        let merged = merge_group_runs(
+            &workspace,
+            &entry,
+            vec![fixture_run("prod.conf", true, "c")],
+            Path::new("/nonexistent/ws/.history/artifacts/0-check"),
+        );
+        assert!(merged.success);
+        assert_eq!(merged.timestamp, entry.timestamp);
+        let outputs: Vec<&str> = merged.runs.iter().map(|run| run.stdout.as_str()).collect();
+        assert_eq!(outputs, vec!["a", "c"]);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(850), "850ms");
+        assert_eq!(format_duration(12_345), "12.3s");
+        assert_eq!(format_duration(245_000), "4m 05s");
+        assert_eq!(format_duration(3_720_000), "1h 02m");
+    }
+
+    #[test]
+    fn test_format_usage() {
+        let usage = ResourceUsage {
+            user_cpu_ms: 1_230,
+            system_cpu_ms: 45,
+            max_rss_kb: Some(2_048),
+        };
+        assert_eq!(
+            format_usage(&usage),
+            "cpu user 1.23s / sys 0.04s, max rss 2.0 MB"
+        );
+    }
+
+    #[test]
+    fn test_record_and_load_migrates_legacy_json() {
+        let root = std::env::temp_dir().join(format!("omakure-history-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let workspace = Workspace::new(root.clone());
+        fs::create_dir_all(workspace.history_dir()).unwrap();
+        let legacy = error_entry(&workspace, Path::new("old.bash"), &[], "boom".to_string());
+        let legacy = HistoryEntry {
+            timestamp: 1,
+            ..legacy
+        };
+        let legacy_path = workspace.history_dir().join("1-1-old_bash.json");
+        fs::write(&legacy_path, serde_json::to_vec(&legacy).unwrap()).unwrap();
+
+        let newer = error_entry(&workspace, Path::new("new.bash"), &[], "late".to_string());
+        record_entry(&workspace, &newer).unwrap();
+
+        let entries = load_entries(&workspace).unwrap();
+        let scripts: Vec<_> = entries.iter().map(|entry| entry.script.clone()).collect();
+        assert_eq!(
+            scripts,
+            vec![PathBuf::from("new.bash"), PathBuf::from("old.bash")]
+        );
+        assert!(!legacy_path.exists());
+        assert!(workspace
+            .history_dir()
+            .join(LEGACY_JSON_DIR)
+            .join("1-1-old_bash.json")
+            .exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
 }
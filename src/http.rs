@@ -0,0 +1,203 @@
+use crate::app_meta::APP_VERSION;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Attempts per request before a download gives up.
+const ATTEMPTS: u32 = 3;
+const TIMEOUT: Duration = Duration::from_secs(30);
+/// Minimum time between progress redraws.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Client for downloads: proxies come from `HTTPS_PROXY`, `HTTP_PROXY`, or
+/// `ALL_PROXY` (upper or lower case).
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .user_agent(&format!("omakure/{}", APP_VERSION))
+        .timeout_connect(TIMEOUT)
+        .timeout_read(TIMEOUT)
+        .try_proxy_from_env(true)
+        .build()
+}
+
+/// Fetches `url` as text. `file://` URLs are read from disk.
+pub fn get_string(url: &str) -> Result<String, Box<dyn Error>> {
+    if let Some(path) = url.strip_prefix("file://") {
+        return fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read {}: {}", url, err).into());
+    }
+    let agent = agent();
+    with_retries(url, || {
+        let mut body = String::new();
+        get(&agent, url)?
+            .into_reader()
+            .read_to_string(&mut body)
+            .map_err(Failure::retry)?;
+        Ok(body)
+    })
+}
+
+/// Downloads `url` to `dest`, drawing progress on stderr when it is a
+/// terminal. `file://` URLs are copied.
+pub fn download(url: &str, dest: &Path) -> Result<(), Box<dyn Error>> {
+    if let Some(path) = url.strip_prefix("file://") {
+        fs::copy(path, dest).map_err(|err| format!("Failed to read {}: {}", url, err))?;
+        return Ok(());
+    }
+    let agent = agent();
+    let result = with_retries(url, || download_once(&agent, url, dest));
+    if result.is_err() {
+        let _ = fs::remove_file(dest);
+    }
+    result
+}
+
+fn download_once(agent: &ureq::Agent, url: &str, dest: &Path) -> Result<(), Failure> {
+    let response = get(agent, url)?;
+    let total: Option<u64> = response
+        .header("Content-Length")
+        .and_then(|value| value.parse().ok());
+    let name = url.rsplit('/').next().unwrap_or(url);
+    let mut progress = io::stderr()
+        .is_terminal()
+        .then(|| Progress::new(name, total));
+
+    let mut reader = response.into_reader();
+    let mut file = File::create(dest).map_err(Failure::fatal)?;
+    let mut buffer = [0u8; 64 * 1024];
+    let mut received = 0u64;
+    loop {
+        let read = reader.read(&mut buffer).map_err(Failure::retry)?;
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buffer[..read]).map_err(Failure::fatal)?;
+        received += read as u64;
+        if let Some(progress) = progress.as_mut() {
+            progress.update(received);
+        }
+    }
+    if let Some(progress) = progress.as_mut() {
+        progress.finish(received);
+    }
+    if total.is_some_and(|total| total != received) {
+        return Err(Failure::retry("connection closed early"));
+    }
+    Ok(())
+}
+
+/// Why a request failed, and whether trying again could help.
+struct Failure {
+    message: String,
+    retry: bool,
+}
+
+impl Failure {
+    fn retry(err: impl ToString) -> Self {
+        Self {
+            message: err.to_string(),
+            retry: true,
+        }
+    }
+
+    fn fatal(err: impl ToString) -> Self {
+        Self {
+            message: err.to_string(),
+            retry: false,
+        }
+    }
+}
+
+/// GET that treats connection failures, `429`, and `5xx` as worth retrying.
+fn get(agent: &ureq::Agent, url: &str) -> Result<ureq::Response, Failure> {
+    match agent.get(url).call() {
+        Ok(response) => Ok(response),
+        Err(ureq::Error::Status(code, _)) if code == 429 || code >= 500 => {
+            Err(Failure::retry(format!("HTTP {}", code)))
+        }
+        Err(ureq::Error::Status(code, _)) => Err(Failure::fatal(format!("HTTP {}", code))),
+        Err(ureq::Error::Transport(transport)) => Err(Failure::retry(match transport.message() {
+            Some(message) => format!("{}: {}", transport.kind(), message),
+            None => transport.kind().to_string(),
+        })),
+    }
+}
+
+/// Runs `request` up to `ATTEMPTS` times, backing off between tries.
+fn with_retries<T>(
+    url: &str,
+    mut request: impl FnMut() -> Result<T, Failure>,
+) -> Result<T, Box<dyn Error>> {
+    let mut attempt = 1;
+    loop {
+        match request() {
+            Ok(value) => return Ok(value),
+            Err(failure) if failure.retry && attempt < ATTEMPTS => {
+                thread::sleep(backoff(attempt));
+                attempt += 1;
+            }
+            Err(failure) => {
+                return Err(format!("Failed to download {}: {}", url, failure.message).into())
+            }
+        }
+    }
+}
+
+/// 1s, 2s, 4s, ...
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_secs(1 << (attempt - 1).min(5))
+}
+
+struct Progress<'a> {
+    name: &'a str,
+    total: Option<u64>,
+    last_draw: Instant,
+}
+
+impl<'a> Progress<'a> {
+    fn new(name: &'a str, total: Option<u64>) -> Self {
+        Self {
+            name,
+            total,
+            last_draw: Instant::now(),
+        }
+    }
+
+    fn update(&mut self, received: u64) {
+        if self.last_draw.elapsed() >= PROGRESS_INTERVAL {
+            self.draw(received);
+            self.last_draw = Instant::now();
+        }
+    }
+
+    fn finish(&mut self, received: u64) {
+        self.draw(received);
+        eprintln!();
+    }
+
+    fn draw(&self, received: u64) {
+        let line = match self.total {
+            Some(total) if total > 0 => format!(
+                "{} / {} ({}%)",
+                format_size(received),
+                format_size(total),
+                received * 100 / total
+            ),
+            _ => format_size(received),
+        };
+        eprint!("\rDownloading {}: {}\x1b[K", self.name, line);
+        let _ = io::stderr().flush();
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    if bytes as f64 >= MB {
+        format!("{:.1} MB", bytes as f64 / MB)
+    } else {
+        format!("{} KB", bytes.div_ceil(1024))
+    }
+}
@@ -0,0 +1,146 @@
+use crate::ports::SshTarget;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Named hosts and groups read from `.omaken/inventory.toml`.
+#[derive(Debug, Default, Deserialize)]
+pub struct Inventory {
+    #[serde(default)]
+    pub hosts: BTreeMap<String, HostSpec>,
+    /// Group name to the hosts it contains.
+    #[serde(default)]
+    pub groups: BTreeMap<String, Vec<String>>,
+}
+
+/// A host entry: `"user@address"`, or a table with the pieces spelled out.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum HostSpec {
+    Destination(String),
+    Detailed {
+        address: String,
+        user: Option<String>,
+        port: Option<u16>,
+    },
+}
+
+/// Picker labels starting with this select a whole group.
+pub const GROUP_PREFIX: char = '@';
+
+/// Reads the inventory; a missing file is an empty inventory.
+pub fn load(path: &Path) -> Result<Inventory, String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Inventory::default()),
+        Err(err) => return Err(format!("Failed to read {}: {}", path.display(), err)),
+    };
+    toml::from_str(&contents).map_err(|err| format!("Failed to parse {}: {}", path.display(), err))
+}
+
+impl Inventory {
+    /// Picker entries: every group as `@name`, then every host.
+    pub fn choices(&self) -> Vec<String> {
+        self.groups
+            .keys()
+            .map(|name| format!("{}{}", GROUP_PREFIX, name))
+            .chain(self.hosts.keys().cloned())
+            .collect()
+    }
+
+    /// Expands groups and host names into hosts, each once, in selection order.
+    pub fn resolve(&self, selection: &[String]) -> Result<Vec<(String, SshTarget)>, String> {
+        let mut names: Vec<&str> = Vec::new();
+        for item in selection {
+            let members: Vec<&str> = match item.strip_prefix(GROUP_PREFIX) {
+                Some(group) => self
+                    .groups
+                    .get(group)
+                    .ok_or_else(|| format!("Unknown group: {}", group))?
+                    .iter()
+                    .map(String::as_str)
+                    .collect(),
+                None => vec![item.as_str()],
+            };
+            for name in members {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+            .into_iter()
+            .map(|name| {
+                self.host(name)
+                    .map(|target| (name.to_string(), target))
+                    .ok_or_else(|| format!("Unknown host: {}", name))
+            })
+            .collect()
+    }
+
+    pub fn host(&self, name: &str) -> Option<SshTarget> {
+        Some(match self.hosts.get(name)? {
+            HostSpec::Destination(destination) => SshTarget {
+                destination: destination.clone(),
+                port: None,
+            },
+            HostSpec::Detailed {
+                address,
+                user,
+                port,
+            } => SshTarget {
+                destination: match user {
+                    Some(user) => format!("{}@{}", user, address),
+                    None => address.clone(),
+                },
+                port: *port,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INVENTORY: &str = r#"
+[hosts]
+web-1 = "deploy@10.0.0.11"
+web-2 = "deploy@10.0.0.12"
+db-1 = { address = "10.0.0.21", user = "admin", port = 2222 }
+
+[groups]
+web = ["web-1", "web-2"]
+all = ["web-1", "web-2", "db-1"]
+broken = ["web-9"]
+"#;
+
+    #[test]
+    fn test_resolve_expands_groups_once() {
+        let inventory: Inventory = toml::from_str(INVENTORY).unwrap();
+        assert_eq!(
+            inventory.choices(),
+            vec!["@all", "@broken", "@web", "db-1", "web-1", "web-2"]
+        );
+
+        let hosts = inventory
+            .resolve(&["web-2".to_string(), "@all".to_string()])
+            .unwrap();
+        let names: Vec<&str> = hosts.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["web-2", "web-1", "db-1"]);
+        assert_eq!(
+            hosts[2].1,
+            SshTarget {
+                destination: "admin@10.0.0.21".to_string(),
+                port: Some(2222),
+            }
+        );
+
+        assert_eq!(
+            inventory.resolve(&["@broken".to_string()]).unwrap_err(),
+            "Unknown host: web-9"
+        );
+        assert!(inventory.resolve(&["@nope".to_string()]).is_err());
+    }
+}
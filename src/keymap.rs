@@ -0,0 +1,282 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// TUI actions whose keys can be changed under `[keys]` in `omakure.toml`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Leave omakure from the script list; closes other screens.
+    Quit,
+    Search,
+    History,
+    Refresh,
+    /// Open the selected entry, or run the filled-in form.
+    Run,
+    /// Close the current screen, or go up a folder in the script list.
+    Back,
+    /// List the keys of the current screen.
+    Help,
+}
+
+const ACTIONS: [(Action, &str, &[&str]); 7] = [
+    (Action::Quit, "quit", &["q"]),
+    (Action::Search, "search", &["ctrl+s"]),
+    (Action::History, "history", &["h", "H"]),
+    (Action::Refresh, "refresh", &["r", "R", "f5"]),
+    (Action::Run, "run", &["enter"]),
+    (Action::Back, "back", &["esc"]),
+    (Action::Help, "help", &["?", "f1"]),
+];
+
+/// One key, or a list of keys, bound to an action.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    fn chords(&self) -> Vec<&str> {
+        match self {
+            KeyList::One(chord) => vec![chord.as_str()],
+            KeyList::Many(chords) => chords.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// A key with the Ctrl and Alt modifiers it needs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Chord {
+    /// Parses `ctrl+s`, `alt+e`, `f5`, `esc`, `H`, ...
+    ///
+    /// Modifier and key names ignore case; a single character keeps it, so `h`
+    /// and `H` are different keys.
+    pub fn parse(chord: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = chord.trim();
+        while let Some((modifier, tail)) = rest.split_once('+').filter(|(_, tail)| !tail.is_empty())
+        {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                _ => {
+                    return Err(format!(
+                        "unknown modifier \"{}\" in \"{}\"",
+                        modifier, chord
+                    ))
+                }
+            };
+            rest = tail;
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(ch), None) => KeyCode::Char(ch),
+            _ => named_key(&rest.to_ascii_lowercase())
+                .ok_or_else(|| format!("unknown key \"{}\" in \"{}\"", rest, chord))?,
+        };
+        Ok(Self { code, modifiers })
+    }
+
+    /// Whether `key` is this chord; Shift only counts through the character.
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let pressed = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        if pressed != self.modifiers {
+            return false;
+        }
+        match (self.code, key.code) {
+            // Terminals disagree on the case of letters typed with Ctrl or Alt.
+            (KeyCode::Char(want), KeyCode::Char(got)) if !self.modifiers.is_empty() => {
+                want.eq_ignore_ascii_case(&got)
+            }
+            (want, got) => want == got,
+        }
+    }
+
+    /// How footers show the chord, e.g. `Ctrl+S` or `Esc`.
+    pub fn label(&self) -> String {
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("Ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("Alt+");
+        }
+        match self.code {
+            KeyCode::Char(' ') => label.push_str("Space"),
+            KeyCode::Char(ch) if !self.modifiers.is_empty() => label.push(ch.to_ascii_uppercase()),
+            KeyCode::Char(ch) => label.push(ch),
+            KeyCode::F(number) => label.push_str(&format!("F{}", number)),
+            KeyCode::Esc => label.push_str("Esc"),
+            KeyCode::PageUp => label.push_str("PgUp"),
+            KeyCode::PageDown => label.push_str("PgDn"),
+            code => label.push_str(&format!("{:?}", code)),
+        }
+        label
+    }
+}
+
+fn named_key(name: &str) -> Option<KeyCode> {
+    Some(match name {
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "space" => KeyCode::Char(' '),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" | "pgup" => KeyCode::PageUp,
+        "pagedown" | "pgdn" => KeyCode::PageDown,
+        _ => {
+            let number: u8 = name.strip_prefix('f')?.parse().ok()?;
+            return (1..=12).contains(&number).then_some(KeyCode::F(number));
+        }
+    })
+}
+
+/// Keys for each action: the defaults, with `[keys]` entries replacing them.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<Chord>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            bindings: ACTIONS
+                .iter()
+                .map(|(action, _, chords)| {
+                    let chords = chords
+                        .iter()
+                        .map(|chord| Chord::parse(chord).expect("default chords parse"))
+                        .collect();
+                    (*action, chords)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Applies the `[keys]` table; entries with errors keep their defaults and
+    /// are reported, one message each.
+    pub fn from_config(keys: &BTreeMap<String, KeyList>) -> (Self, Vec<String>) {
+        let mut keymap = Self::default();
+        let mut errors = Vec::new();
+        for (name, list) in keys {
+            let Some((action, ..)) = ACTIONS.iter().find(|(_, known, _)| known == name) else {
+                errors.push(format!(
+                    "unknown action \"{}\" (expected one of: {})",
+                    name,
+                    ACTIONS.map(|(_, known, _)| known).join(", ")
+                ));
+                continue;
+            };
+            let chords: Result<Vec<Chord>, String> =
+                list.chords().into_iter().map(Chord::parse).collect();
+            match chords {
+                Ok(chords) if chords.is_empty() => {
+                    errors.push(format!("{}: no keys given", name));
+                }
+                Ok(chords) => keymap.set(*action, chords),
+                Err(err) => errors.push(format!("{}: {}", name, err)),
+            }
+        }
+        (keymap, errors)
+    }
+
+    pub fn matches(&self, action: Action, key: &KeyEvent) -> bool {
+        self.chords(action).iter().any(|chord| chord.matches(key))
+    }
+
+    /// The first key bound to `action`, for footers.
+    pub fn label(&self, action: Action) -> String {
+        self.chords(action)
+            .first()
+            .map(Chord::label)
+            .unwrap_or_default()
+    }
+
+    /// Every key bound to `action`.
+    pub fn labels(&self, action: Action) -> Vec<String> {
+        self.chords(action).iter().map(Chord::label).collect()
+    }
+
+    fn chords(&self, action: Action) -> &[Chord] {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == action)
+            .map(|(_, chords)| chords.as_slice())
+            .unwrap_or_default()
+    }
+
+    fn set(&mut self, action: Action, chords: Vec<Chord>) {
+        if let Some((_, bound)) = self.bindings.iter_mut().find(|(bound, _)| *bound == action) {
+            *bound = chords;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_keymap_from_config() {
+        let keys: BTreeMap<String, KeyList> = toml::from_str(
+            r#"
+quit = ["x", "ctrl+q"]
+search = "/"
+back = "alt+bogus"
+launch = "l"
+"#,
+        )
+        .unwrap();
+        let (keymap, errors) = Keymap::from_config(&keys);
+
+        assert!(keymap.matches(Action::Quit, &key(KeyCode::Char('x'), KeyModifiers::NONE)));
+        assert!(keymap.matches(
+            Action::Quit,
+            &key(KeyCode::Char('Q'), KeyModifiers::CONTROL)
+        ));
+        assert!(!keymap.matches(Action::Quit, &key(KeyCode::Char('q'), KeyModifiers::NONE)));
+        assert!(!keymap.matches(Action::Quit, &key(KeyCode::Char('x'), KeyModifiers::ALT)));
+        assert_eq!(keymap.label(Action::Quit), "x");
+        assert_eq!(keymap.label(Action::Search), "/");
+
+        // Defaults stay for actions that are not configured or are invalid.
+        assert!(keymap.matches(Action::Back, &key(KeyCode::Esc, KeyModifiers::NONE)));
+        assert!(keymap.matches(
+            Action::History,
+            &key(KeyCode::Char('H'), KeyModifiers::SHIFT)
+        ));
+        assert!(keymap.matches(Action::Refresh, &key(KeyCode::F(5), KeyModifiers::NONE)));
+        assert_eq!(Keymap::default().label(Action::Search), "Ctrl+S");
+        assert_eq!(keymap.labels(Action::Refresh), vec!["r", "R", "F5"]);
+
+        assert_eq!(
+            errors,
+            vec![
+                "back: unknown key \"bogus\" in \"alt+bogus\"".to_string(),
+                "unknown action \"launch\" (expected one of: quit, search, history, refresh, run, back, help)"
+                    .to_string(),
+            ]
+        );
+        assert_eq!(Chord::parse("ctrl++").unwrap().label(), "Ctrl++");
+    }
+}
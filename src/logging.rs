@@ -0,0 +1,200 @@
+use crate::history::{format_iso8601, timestamp_ms, UtcOffset};
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Variable holding the filter: a default level and `target=level` pairs,
+/// e.g. `debug` or `warn,run=debug,schema=off`.
+pub const FILTER_VAR: &str = "OMAKURE_LOG";
+/// Filter used when `FILTER_VAR` is unset.
+const DEFAULT_FILTER: &str = "info";
+/// Size the log may reach before it is moved to `omakure.log.1`.
+const MAX_BYTES: u64 = 1024 * 1024;
+/// Rotated files kept, `omakure.log.1` being the newest.
+const KEEP: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl Level {
+    fn name(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+        }
+    }
+
+    /// `None` inside `Some` is `off`.
+    fn parse(value: &str) -> Option<Option<Level>> {
+        match value.trim().to_lowercase().as_str() {
+            "off" => Some(None),
+            "error" => Some(Some(Level::Error)),
+            "warn" | "warning" => Some(Some(Level::Warn)),
+            "info" => Some(Some(Level::Info)),
+            "debug" | "trace" => Some(Some(Level::Debug)),
+            _ => None,
+        }
+    }
+}
+
+/// The most detailed level logged, overall and per target; `None` is off.
+#[derive(Debug, PartialEq, Eq)]
+struct Filter {
+    default: Option<Level>,
+    targets: Vec<(String, Option<Level>)>,
+}
+
+impl Filter {
+    /// Unknown levels are skipped, so a typo does not silence the log.
+    fn parse(spec: &str) -> Self {
+        let mut filter = Filter {
+            default: Some(Level::Info),
+            targets: Vec::new(),
+        };
+        for item in spec.split(',').filter(|item| !item.trim().is_empty()) {
+            match item.split_once('=') {
+                Some((target, level)) => {
+                    if let Some(level) = Level::parse(level) {
+                        filter.targets.push((target.trim().to_string(), level));
+                    }
+                }
+                None => {
+                    if let Some(level) = Level::parse(item) {
+                        filter.default = level;
+                    }
+                }
+            }
+        }
+        filter
+    }
+
+    fn enabled(&self, level: Level, target: &str) -> bool {
+        let max = self
+            .targets
+            .iter()
+            .rev()
+            .find(|(name, _)| name == target)
+            .map_or(self.default, |(_, level)| *level);
+        max.is_some_and(|max| level <= max)
+    }
+}
+
+struct Logger {
+    path: PathBuf,
+    filter: Filter,
+}
+
+static LOGGER: Mutex<Option<Logger>> = Mutex::new(None);
+
+/// Logs to `path` from now on, with the filter from `OMAKURE_LOG`. Until
+/// this is called, nothing is logged.
+pub fn init(path: PathBuf) {
+    let spec = std::env::var(FILTER_VAR).unwrap_or_else(|_| DEFAULT_FILTER.to_string());
+    if let Ok(mut logger) = LOGGER.lock() {
+        *logger = Some(Logger {
+            path,
+            filter: Filter::parse(&spec),
+        });
+    }
+}
+
+pub fn error(target: &str, message: &str, fields: Value) {
+    log(Level::Error, target, message, fields);
+}
+
+pub fn warn(target: &str, message: &str, fields: Value) {
+    log(Level::Warn, target, message, fields);
+}
+
+pub fn info(target: &str, message: &str, fields: Value) {
+    log(Level::Info, target, message, fields);
+}
+
+pub fn debug(target: &str, message: &str, fields: Value) {
+    log(Level::Debug, target, message, fields);
+}
+
+/// Appends one JSON line: time, level, target, message, then the entries of
+/// `fields`. Failing to write is ignored; the log must never break a run.
+fn log(level: Level, target: &str, message: &str, fields: Value) {
+    let Ok(logger) = LOGGER.lock() else {
+        return;
+    };
+    let Some(logger) = logger.as_ref() else {
+        return;
+    };
+    if !logger.filter.enabled(level, target) {
+        return;
+    }
+    let mut line = format!(
+        "{{\"time\":{},\"level\":{},\"target\":{},\"message\":{}",
+        Value::from(format_iso8601(timestamp_ms(), UtcOffset::UTC)),
+        Value::from(level.name()),
+        Value::from(target),
+        Value::from(message)
+    );
+    if let Value::Object(fields) = fields {
+        for (key, value) in fields {
+            line.push_str(&format!(",{}:{}", Value::from(key), value));
+        }
+    }
+    line.push('}');
+    let _ = write_line(&logger.path, &line);
+}
+
+fn write_line(path: &Path, line: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::metadata(path).is_ok_and(|metadata| metadata.len() >= MAX_BYTES) {
+        rotate(path)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// Shifts `path.1` to `path.2` and so on, dropping the oldest, then moves
+/// `path` to `path.1`.
+fn rotate(path: &Path) -> std::io::Result<()> {
+    let numbered = |index: usize| {
+        let mut name = path.as_os_str().to_owned();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    };
+    let _ = fs::remove_file(numbered(KEEP));
+    for index in (1..KEEP).rev() {
+        let _ = fs::rename(numbered(index), numbered(index + 1));
+    }
+    fs::rename(path, numbered(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter() {
+        let filter = Filter::parse("warn, run=debug,schema=off,index=loud");
+        assert!(filter.enabled(Level::Warn, "update"));
+        assert!(!filter.enabled(Level::Info, "update"));
+        assert!(filter.enabled(Level::Debug, "run"));
+        assert!(!filter.enabled(Level::Error, "schema"));
+        // The unknown level leaves `index` on the default.
+        assert!(filter.enabled(Level::Error, "index"));
+        assert!(!filter.enabled(Level::Info, "index"));
+
+        let default = Filter::parse(DEFAULT_FILTER);
+        assert!(default.enabled(Level::Info, "run"));
+        assert!(!default.enabled(Level::Debug, "run"));
+        assert!(!Filter::parse("off").enabled(Level::Error, "run"));
+    }
+}
@@ -1,14 +1,75 @@
-use mlua::{Lua, Table, Value};
+use mlua::{Lua, LuaOptions, StdLib, Table, Value};
 use std::fs;
+use std::io::{self, Read};
 use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Seconds `omakure.exec` gives a command when the widget sets no timeout.
+const DEFAULT_EXEC_TIMEOUT: f64 = 5.0;
+/// Longest timeout a widget may set.
+const MAX_EXEC_TIMEOUT: f64 = 30.0;
+/// Output of a command kept for the widget; the rest is dropped.
+const MAX_EXEC_OUTPUT: u64 = 64 * 1024;
+/// Variables every command gets, when set; the rest of the environment is
+/// cleared unless `ExecPolicy::env` names it.
+const BASE_ENV: &[&str] = &[
+    "PATH",
+    "HOME",
+    "USER",
+    "LOGNAME",
+    "LANG",
+    "LC_ALL",
+    "TMPDIR",
+    "SYSTEMROOT",
+    "USERPROFILE",
+    "APPDATA",
+    "LOCALAPPDATA",
+    "TEMP",
+    "TMP",
+];
+
+/// What `omakure.exec` may run, from the `[widgets]` table of `omakure.toml`.
+#[derive(Debug, Clone, Default)]
+pub struct ExecPolicy {
+    /// Programs a widget may start, as it names them; none by default.
+    pub commands: Vec<String>,
+    /// Variables passed on besides `BASE_ENV`.
+    pub env: Vec<String>,
+}
 
 #[derive(Debug, Clone)]
 pub struct WidgetData {
     pub title: String,
-    pub lines: Vec<String>,
+    pub lines: Vec<WidgetLine>,
+    /// Scripts the widget offers to run, shown numbered from 1.
+    pub actions: Vec<WidgetAction>,
+}
+
+/// A script a widget offers to run with fixed arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WidgetAction {
+    pub label: String,
+    /// Workspace-relative script path, with or without its extension.
+    pub script: String,
+    pub args: Vec<String>,
+}
+
+/// A line of the widget: a plain string, or a table with `text` and
+/// optional `fg`, `bg` (color names or `#rrggbb`), and `bold`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WidgetLine {
+    pub text: String,
+    pub fg: Option<String>,
+    pub bg: Option<String>,
+    pub bold: bool,
 }
 
-pub fn load_widget(dir: &Path) -> Result<Option<WidgetData>, String> {
+/// Runs `dir/index.lua` without the `os` and `io` libraries, so
+/// `omakure.exec` under `policy` is the only way it can start a process.
+pub fn load_widget(dir: &Path, policy: &ExecPolicy) -> Result<Option<WidgetData>, String> {
     let script_path = dir.join("index.lua");
     if !script_path.is_file() {
         return Ok(None);
@@ -16,7 +77,12 @@ pub fn load_widget(dir: &Path) -> Result<Option<WidgetData>, String> {
 
     let script = fs::read_to_string(&script_path)
         .map_err(|err| format!("Failed to read {}: {}", script_path.display(), err))?;
-    let lua = Lua::new();
+    let lua = Lua::new_with(
+        StdLib::ALL_SAFE ^ StdLib::OS ^ StdLib::IO,
+        LuaOptions::default(),
+    )
+    .map_err(|err| format!("Lua error: {}", err))?;
+    register_helpers(&lua, dir, policy).map_err(|err| format!("Lua error: {}", err))?;
     let value = lua
         .load(&script)
         .set_name(script_path.to_string_lossy().as_ref())
@@ -39,12 +105,142 @@ pub fn load_widget(dir: &Path) -> Result<Option<WidgetData>, String> {
     let lines_table: Option<Table> = globals.get("lines").map_err(|err| err.to_string())?;
     if let (Some(title), Some(lines_table)) = (title, lines_table) {
         let lines = read_lines_table(lines_table)?;
-        return Ok(Some(WidgetData { title, lines }));
+        let actions = read_actions(globals.get("actions").map_err(|err| err.to_string())?)?;
+        return Ok(Some(WidgetData {
+            title,
+            lines,
+            actions,
+        }));
     }
 
     Err("Lua widget must return a table with `title` and `lines`".to_string())
 }
 
+/// Adds the `omakure` table: `omakure.exec(command, timeout)` runs
+/// `command`, an argument list or a line split on whitespace, in the widget's
+/// folder and returns its stdout, or `nil` and an error when it is not
+/// allowed, fails, or outlives `timeout` seconds.
+fn register_helpers(lua: &Lua, dir: &Path, policy: &ExecPolicy) -> mlua::Result<()> {
+    let dir = dir.to_path_buf();
+    let policy = policy.clone();
+    let exec = lua.create_function(move |_, (command, timeout): (Value, Option<f64>)| {
+        let argv: Vec<String> = match command {
+            Value::String(line) => Ok(line
+                .to_str()?
+                .split_whitespace()
+                .map(str::to_string)
+                .collect()),
+            Value::Table(args) => args.sequence_values::<String>().collect(),
+            other => Err(mlua::Error::RuntimeError(format!(
+                "omakure.exec takes a list of arguments or a string, not {}",
+                other.type_name()
+            ))),
+        }?;
+        Ok(match exec(&dir, &policy, &argv, timeout) {
+            Ok(stdout) => (Some(stdout), None),
+            Err(err) => (None, Some(err)),
+        })
+    })?;
+    let omakure = lua.create_table()?;
+    omakure.set("exec", exec)?;
+    lua.globals().set("omakure", omakure)
+}
+
+/// Runs `argv` directly, without a shell, with no stdin and only the
+/// variables `policy` allows, killing it once `timeout` seconds have passed.
+/// The error is the last line of stderr, or how the command ended.
+fn exec(
+    dir: &Path,
+    policy: &ExecPolicy,
+    argv: &[String],
+    timeout: Option<f64>,
+) -> Result<String, String> {
+    let Some((program, args)) = argv.split_first() else {
+        return Err("omakure.exec needs a command".to_string());
+    };
+    if !policy.commands.iter().any(|allowed| allowed == program) {
+        return Err(format!(
+            "{} is not in the commands of [widgets] in omakure.toml",
+            program
+        ));
+    }
+    let line = argv.join(" ");
+    let timeout = timeout
+        .filter(|seconds| *seconds > 0.0)
+        .unwrap_or(DEFAULT_EXEC_TIMEOUT)
+        .min(MAX_EXEC_TIMEOUT);
+    let mut command = Command::new(program);
+    command.args(args).current_dir(dir).env_clear();
+    for name in BASE_ENV
+        .iter()
+        .copied()
+        .chain(policy.env.iter().map(String::as_str))
+    {
+        if let Some(value) = std::env::var_os(name) {
+            command.env(name, value);
+        }
+    }
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("{}: {}", line, err))?;
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed().as_secs_f64() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("{}: timed out after {}s", line, timeout));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(20)),
+            Err(err) => return Err(format!("{}: {}", line, err)),
+        }
+    };
+    // A background process the command left may hold the pipes open.
+    let collect = |output: Receiver<Vec<u8>>| {
+        let bytes = output
+            .recv_timeout(Duration::from_millis(200))
+            .unwrap_or_default();
+        String::from_utf8_lossy(&bytes).trim_end().to_string()
+    };
+    if status.success() {
+        return Ok(collect(stdout));
+    }
+    let stderr = collect(stderr);
+    Err(stderr
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| match status.code() {
+            Some(code) => format!("{}: exited with code {}", line, code),
+            None => format!("{}: terminated by a signal", line),
+        }))
+}
+
+/// Reads `reader` to its end on a thread, keeping the first
+/// `MAX_EXEC_OUTPUT` bytes so a chatty command never blocks on a full pipe.
+fn read_in_background(reader: Option<impl Read + Send + 'static>) -> Receiver<Vec<u8>> {
+    let (sender, receiver) = mpsc::channel();
+    if let Some(mut reader) = reader {
+        thread::spawn(move || {
+            let mut bytes = Vec::new();
+            let _ = reader
+                .by_ref()
+                .take(MAX_EXEC_OUTPUT)
+                .read_to_end(&mut bytes);
+            let _ = io::copy(&mut reader, &mut io::sink());
+            let _ = sender.send(bytes);
+        });
+    }
+    receiver
+}
+
 fn read_widget_table(table: Table) -> Result<WidgetData, String> {
     let title: String = table
         .get("title")
@@ -53,14 +249,126 @@ fn read_widget_table(table: Table) -> Result<WidgetData, String> {
         .get("lines")
         .map_err(|_| "Lua widget missing `lines`".to_string())?;
     let lines = read_lines_table(lines_table)?;
-    Ok(WidgetData { title, lines })
+    let actions = read_actions(table.get("actions").map_err(|err| err.to_string())?)?;
+    Ok(WidgetData {
+        title,
+        lines,
+        actions,
+    })
 }
 
-fn read_lines_table(table: Table) -> Result<Vec<String>, String> {
+fn read_lines_table(table: Table) -> Result<Vec<WidgetLine>, String> {
     let mut lines = Vec::new();
-    for pair in table.sequence_values::<String>() {
-        let line = pair.map_err(|err| err.to_string())?;
-        lines.push(line);
+    for (index, value) in table.sequence_values::<Value>().enumerate() {
+        let value = value.map_err(|err| err.to_string())?;
+        lines.push(read_line(value).map_err(|err| format!("Line {}: {}", index + 1, err))?);
     }
     Ok(lines)
 }
+
+fn read_actions(table: Option<Table>) -> Result<Vec<WidgetAction>, String> {
+    let Some(table) = table else {
+        return Ok(Vec::new());
+    };
+    let mut actions = Vec::new();
+    for (index, action) in table.sequence_values::<Table>().enumerate() {
+        let action = action.map_err(|err| format!("Action {}: {}", index + 1, err))?;
+        let field = |name: &str| {
+            action
+                .get::<_, String>(name)
+                .map_err(|_| format!("Action {} needs a `{}` string", index + 1, name))
+        };
+        let args: Option<Vec<String>> = action
+            .get("args")
+            .map_err(|err| format!("Action {}: `args`: {}", index + 1, err))?;
+        actions.push(WidgetAction {
+            label: field("label")?,
+            script: field("script")?,
+            args: args.unwrap_or_default(),
+        });
+    }
+    Ok(actions)
+}
+
+fn read_line(value: Value) -> Result<WidgetLine, String> {
+    match value {
+        Value::String(text) => Ok(WidgetLine {
+            text: text.to_str().map_err(|err| err.to_string())?.to_string(),
+            ..WidgetLine::default()
+        }),
+        Value::Integer(number) => Ok(WidgetLine {
+            text: number.to_string(),
+            ..WidgetLine::default()
+        }),
+        Value::Number(number) => Ok(WidgetLine {
+            text: number.to_string(),
+            ..WidgetLine::default()
+        }),
+        Value::Table(table) => Ok(WidgetLine {
+            text: table
+                .get("text")
+                .map_err(|_| "a styled line needs a `text` string".to_string())?,
+            fg: table.get("fg").map_err(|err| err.to_string())?,
+            bg: table.get("bg").map_err(|err| err.to_string())?,
+            bold: table
+                .get::<_, Option<bool>>("bold")
+                .map_err(|err| err.to_string())?
+                .unwrap_or(false),
+        }),
+        other => Err(format!(
+            "expected a string or a table, got {}",
+            other.type_name()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_widget_exec_policy() {
+        let dir = std::env::temp_dir().join(format!("omakure-widget-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let widget = |lua: &str, policy: &ExecPolicy| {
+            fs::write(dir.join("index.lua"), lua).unwrap();
+            load_widget(&dir, policy)
+        };
+        let policy = ExecPolicy {
+            commands: vec!["echo".to_string()],
+            env: Vec::new(),
+        };
+
+        for lua in ["os.execute('true')", "io.popen('true')"] {
+            assert!(widget(lua, &policy).is_err(), "{} ran", lua);
+        }
+        let lines = |lua: &str| {
+            let widget = widget(lua, &policy).unwrap().unwrap();
+            widget
+                .lines
+                .into_iter()
+                .map(|line| line.text)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            lines(
+                r#"local _, err = omakure.exec({ "rm", "-rf", "x" })
+                return { title = "t", lines = { err } }"#
+            ),
+            vec!["rm is not in the commands of [widgets] in omakure.toml"]
+        );
+        if cfg!(unix) {
+            // No shell: `;` and `$HOME` reach echo as they are.
+            assert_eq!(
+                lines(
+                    r#"return { title = "t", lines = {
+                        omakure.exec({ "echo", "a;", "$HOME" }),
+                        omakure.exec("echo b  c"),
+                    } }"#
+                ),
+                vec!["a; $HOME", "b c"]
+            );
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
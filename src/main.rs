@@ -1,27 +1,59 @@
 mod adapters;
+mod app_dirs;
 mod app_meta;
+mod archive;
+mod artifacts;
 mod cli;
+mod codegen;
+mod concurrency;
+mod config;
+mod cron;
+mod digest;
 mod domain;
+mod env_crypto;
 mod error;
+mod favorites;
+mod gc;
 mod history;
+mod http;
+mod inventory;
+mod keymap;
+mod logging;
 mod lua_widget;
+mod notify;
+mod omaken_catalog;
+mod omaken_lock;
+mod omaken_manifest;
+mod openapi;
+mod pipelines;
 mod ports;
+mod preflight;
+mod receipt;
+mod requirements;
 mod runtime;
+mod script_sync;
 mod search_index;
+mod snapshot;
+mod status;
+mod syntax;
 mod theme_config;
+mod tutorial;
 mod use_cases;
 mod util;
 mod workspace;
+mod workspace_profiles;
+mod workspace_template;
 
+use adapters::environments::FsEnvironmentRepository;
 use adapters::script_runner::MultiScriptRunner;
 use adapters::tui;
 use adapters::workspace_repository::FsWorkspaceRepository;
 use clap::Parser;
-use cli::args::{Cli, Commands, Shell};
+use cli::args::{Cli, Commands, CompleteCli};
 use std::env;
 use std::error::Error;
-use std::path::PathBuf;
-use use_cases::ScriptService;
+use std::path::{Path, PathBuf};
+use use_cases::{EnvironmentService, ScriptService};
 use workspace::Workspace;
 
 fn scripts_dir_for(name: &str) -> PathBuf {
@@ -132,6 +164,10 @@ fn scripts_dir() -> PathBuf {
         return PathBuf::from(dir);
     }
 
+    if let Some(dir) = workspace_profiles::load().default_dir() {
+        return dir;
+    }
+
     if cfg!(debug_assertions) {
         let dev_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("scripts");
         if dev_dir.is_dir() {
@@ -157,54 +193,131 @@ fn scripts_dir() -> PathBuf {
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
+    if env::args().nth(1).as_deref() == Some("__complete") {
+        let cli = CompleteCli::parse_from(env::args().skip(1));
+        let scripts_dir = cli.scripts_dir.unwrap_or_else(scripts_dir);
+        register_custom_extensions(&scripts_dir);
+        return cli::completion::complete(scripts_dir, cli.command);
+    }
     let cli = Cli::parse();
-    let scripts_dir = cli.scripts_dir.unwrap_or_else(scripts_dir);
+    let scripts_dir = match (cli.scripts_dir, cli.workspace) {
+        (Some(dir), _) => dir,
+        (None, Some(name)) => workspace_profiles::load()
+            .resolve(&name)
+            .unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }),
+        (None, None) => scripts_dir(),
+    };
+    register_custom_extensions(&scripts_dir);
+    logging::init(Workspace::new(scripts_dir.clone()).log_path());
 
     match cli.command {
         Some(Commands::Update(args)) => cli::update::run(scripts_dir, args)?,
+        Some(Commands::SyncScripts(args)) => cli::sync_scripts::run(scripts_dir, args)?,
         Some(Commands::Uninstall(args)) => cli::uninstall::run(scripts_dir, args)?,
-        Some(Commands::Doctor) => cli::doctor::run(scripts_dir)?,
+        Some(Commands::Doctor(args)) => cli::doctor::run(scripts_dir, args)?,
         Some(Commands::List) => cli::omaken::run_list(scripts_dir)?,
         Some(Commands::Install(args)) => cli::omaken::run_install(scripts_dir, args)?,
-        Some(Commands::Scripts) => cli::list::run(scripts_dir)?,
+        Some(Commands::Browse(args)) => cli::browse::run(scripts_dir, args)?,
+        Some(Commands::Scripts(args)) => cli::list::run(scripts_dir, args)?,
         Some(Commands::Run(args)) => cli::run::run(scripts_dir, args)?,
+        Some(Commands::RunAll(args)) => cli::run_all::run(scripts_dir, args)?,
+        Some(Commands::Pipeline(args)) => cli::pipeline::run(scripts_dir, args)?,
         Some(Commands::Init(args)) => cli::init::run(scripts_dir, args)?,
+        Some(Commands::InitWorkspace(args)) => cli::init_workspace::run(scripts_dir, args)?,
+        Some(Commands::Workspaces(args)) => cli::workspaces::run(scripts_dir, args)?,
         Some(Commands::Config) => cli::config::run(scripts_dir)?,
         Some(Commands::Theme(args)) => cli::theme::run(scripts_dir, args)?,
-        Some(Commands::Completion(args)) => generate_completions(args.shell),
-        None => run_tui(scripts_dir)?,
+        Some(Commands::History(args)) => cli::history::run(scripts_dir, args)?,
+        Some(Commands::Envs(args)) => cli::envs::run(scripts_dir, args)?,
+        Some(Commands::Report(args)) => cli::report::run(scripts_dir, args)?,
+        Some(Commands::Stats(args)) => cli::stats::run(scripts_dir, args)?,
+        Some(Commands::Digest(args)) => cli::digest::run(scripts_dir, args)?,
+        Some(Commands::Docs(args)) => cli::docs::run(scripts_dir, args)?,
+        Some(Commands::Schema(args)) => cli::schema::run(scripts_dir, args)?,
+        Some(Commands::Codegen(args)) => cli::codegen::run(scripts_dir, args)?,
+        Some(Commands::VerifyReceipt(args)) => cli::verify_receipt::run(scripts_dir, args)?,
+        Some(Commands::Snapshot(args)) => cli::snapshot::run(scripts_dir, args)?,
+        Some(Commands::Gc(args)) => cli::gc::run(scripts_dir, args)?,
+        Some(Commands::Validate(args)) => cli::validate::run(scripts_dir, args)?,
+        Some(Commands::Dispatch(args)) => cli::dispatch::run(args)?,
+        Some(Commands::KubeRun(args)) => cli::kubernetes::run(args)?,
+        Some(Commands::SshRun(args)) => cli::ssh::run(args)?,
+        Some(Commands::Completion(args)) => cli::completion::run(args)?,
+        Some(Commands::Tutorial) => {
+            let (sandbox, _guard) = cli::tutorial::prepare()?;
+            run_tui(sandbox, true)?
+        }
+        None => run_tui(scripts_dir, false)?,
     }
 
     Ok(())
 }
 
-fn run_tui(scripts_dir: PathBuf) -> Result<(), Box<dyn Error>> {
-    let workspace = Workspace::new(scripts_dir.clone());
-    workspace.ensure_layout()?;
-
-    let repo = Box::new(FsWorkspaceRepository::new(scripts_dir));
-    let runner = Box::new(MultiScriptRunner::new());
-    let service = ScriptService::new(repo, runner);
-
-    let mut terminal = tui::setup_terminal()?;
-    let app_result = tui::run_app(&mut terminal, &service, workspace);
-    tui::restore_terminal(&mut terminal)?;
-    app_result?;
-
-    Ok(())
+/// Lets the runtime recognize the scripts mapped in the workspace's
+/// `[runner.extensions]`.
+fn register_custom_extensions(scripts_dir: &Path) {
+    let workspace = Workspace::new(scripts_dir.to_path_buf());
+    let config = config::load_workspace_config(workspace.config_path());
+    runtime::set_custom_extensions(config.custom_extensions());
 }
 
-fn generate_completions(shell: Shell) {
-    use clap::CommandFactory;
-    use clap_complete::{generate, Shell as ClapShell};
+fn run_tui(mut scripts_dir: PathBuf, mut tutorial: bool) -> Result<(), Box<dyn Error>> {
+    let mut runs = Vec::new();
+    let mut terminal = None;
+    // The workspace switcher ends a session; the next one starts in the new workspace.
+    let config = loop {
+        let workspace = Workspace::new(scripts_dir.clone());
+        if let Err(err) = workspace.ensure_layout() {
+            if let Some(mut terminal) = terminal {
+                tui::restore_terminal(&mut terminal)?;
+            }
+            return Err(err.into());
+        }
+        logging::init(workspace.log_path());
+
+        let repo = Box::new(FsWorkspaceRepository::new(scripts_dir.clone()));
+        let runner = Box::new(MultiScriptRunner::new());
+        let config = config::load_workspace_config(workspace.config_path());
+        runtime::set_custom_extensions(config.custom_extensions());
+        let mut service = ScriptService::new(repo, runner)
+            .with_run_defaults(config.run_options())
+            .with_hooks(config.hooks());
+        let (prompts, prompt) = tui::prompt_channel();
+        service = service.with_prompts(prompts);
+        if config.pass_active_env() {
+            let environment = FsEnvironmentRepository::new(workspace.envs_dir());
+            service = service.with_environment(EnvironmentService::new(Box::new(environment)));
+        }
 
-    let mut cmd = Cli::command();
-    let shell = match shell {
-        Shell::Bash => ClapShell::Bash,
-        Shell::Zsh => ClapShell::Zsh,
-        Shell::Fish => ClapShell::Fish,
-        Shell::Pwsh => ClapShell::PowerShell,
+        let terminal = match &mut terminal {
+            Some(terminal) => terminal,
+            None => terminal.insert(tui::setup_terminal()?),
+        };
+        let session = match tui::run_app(terminal, &service, workspace, tutorial, prompt) {
+            Ok(session) => session,
+            Err(err) => {
+                tui::restore_terminal(terminal)?;
+                return Err(err);
+            }
+        };
+        runs.extend(session.runs);
+        match session.switch_to {
+            Some(dir) => {
+                scripts_dir = dir;
+                tutorial = false;
+            }
+            None => break config,
+        }
     };
+    if let Some(mut terminal) = terminal {
+        tui::restore_terminal(&mut terminal)?;
+    }
+    if config.exit_summary() && !runs.is_empty() {
+        print!("{}", history::session_summary(&runs, config.time_zone()));
+    }
 
-    generate(shell, &mut cmd, "omakure", &mut std::io::stdout());
+    Ok(())
 }
@@ -0,0 +1,96 @@
+use crate::config::NotificationsConfig;
+use crate::runtime::shell_command;
+use serde_json::json;
+use std::env;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Title of a message, set for the `[notifications] command`.
+pub const TITLE_VAR: &str = "OMAKURE_NOTIFY_TITLE";
+
+/// Whether `[notifications]` names any channel.
+pub fn has_channels(config: &NotificationsConfig) -> bool {
+    !config.webhooks.is_empty() || config.command.is_some()
+}
+
+/// Sends `title` and `body` to every configured channel, trying all of them
+/// before reporting the ones that failed.
+pub fn send(config: &NotificationsConfig, title: &str, body: &str) -> Result<(), String> {
+    let mut errors = Vec::new();
+    for webhook in &config.webhooks {
+        if let Err(err) = post_webhook(webhook, title, body) {
+            errors.push(format!("webhook {}: {}", webhook_label(webhook), err));
+        }
+    }
+    if let Some(line) = &config.command {
+        if let Err(err) = run_command(line, title, body) {
+            errors.push(format!("command: {}", err));
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Notification failed: {}", errors.join("; ")))
+    }
+}
+
+fn post_webhook(webhook: &str, title: &str, body: &str) -> Result<(), String> {
+    let url = match webhook.strip_prefix('$') {
+        Some(name) => env::var(name).map_err(|_| format!("{} is not set", name))?,
+        None => webhook.to_string(),
+    };
+    let payload = json!({ "text": format!("*{}*\n```\n{}```", title, body) });
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--config", "-"])
+        .args(["--header", "Content-Type: application/json"])
+        .arg("--data-binary")
+        .arg(payload.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("curl: {}", err))?;
+    // The URL goes through stdin; webhook URLs are secrets.
+    if let Some(mut stdin) = child.stdin.take() {
+        writeln!(stdin, "url = \"{}\"", url.replace('"', "%22")).map_err(|err| err.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|err| err.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+fn run_command(line: &str, title: &str, body: &str) -> Result<(), String> {
+    let mut child = shell_command(line)
+        .env(TITLE_VAR, title)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| err.to_string())?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A command that ignores its input closes stdin early; that is fine.
+        let _ = stdin.write_all(body.as_bytes());
+    }
+    let output = child.wait_with_output().map_err(|err| err.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "exited with {}: {}",
+            output.status.code().unwrap_or(-1),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// A webhook as it may be shown: the variable name, or the URL's host.
+fn webhook_label(webhook: &str) -> &str {
+    if webhook.starts_with('$') {
+        return webhook;
+    }
+    let rest = webhook.split_once("://").map_or(webhook, |(_, rest)| rest);
+    rest.split('/').next().unwrap_or(rest)
+}
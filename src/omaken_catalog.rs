@@ -0,0 +1,104 @@
+use serde::Deserialize;
+
+/// Catalog read when `[omaken] catalog` is not set.
+pub const DEFAULT_CATALOG_URL: &str =
+    "https://raw.githubusercontent.com/This-Is-NPC/omakure/main/omaken-catalog.json";
+
+/// Community flavors listed by `omakure browse`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+pub struct Catalog {
+    #[serde(default)]
+    pub flavors: Vec<CatalogEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct CatalogEntry {
+    pub name: String,
+    /// Anything `omakure install` takes: a git URL, an archive, or a folder.
+    pub url: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Tag or branch to install instead of the default branch.
+    #[serde(rename = "ref")]
+    pub reference: Option<String>,
+}
+
+pub fn parse(contents: &str) -> Result<Catalog, String> {
+    serde_json::from_str(contents).map_err(|err| format!("Invalid catalog: {}", err))
+}
+
+impl Catalog {
+    /// The entry at a 1-based `selector` index, or named `selector`
+    /// (ignoring case).
+    pub fn find(&self, selector: &str) -> Option<&CatalogEntry> {
+        match selector.parse::<usize>() {
+            Ok(index) => index
+                .checked_sub(1)
+                .and_then(|index| self.flavors.get(index)),
+            Err(_) => self
+                .flavors
+                .iter()
+                .find(|entry| entry.name.eq_ignore_ascii_case(selector)),
+        }
+    }
+
+    /// Positions of the entries tagged `tag`, or of all of them.
+    pub fn matching(&self, tag: Option<&str>) -> Vec<usize> {
+        (0..self.flavors.len())
+            .filter(|index| {
+                tag.is_none_or(|tag| {
+                    self.flavors[*index]
+                        .tags
+                        .iter()
+                        .any(|entry_tag| entry_tag.eq_ignore_ascii_case(tag))
+                })
+            })
+            .collect()
+    }
+}
+
+impl CatalogEntry {
+    /// `description [tag, tag]`, or whichever of the two is set.
+    pub fn summary(&self) -> String {
+        let mut text = self.description.clone().unwrap_or_default();
+        if !self.tags.is_empty() {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(&format!("[{}]", self.tags.join(", ")));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_find() {
+        let catalog = parse(
+            r#"{"flavors": [
+                {"name": "azure", "url": "https://example.com/azure.git",
+                 "description": "Resource group reports", "tags": ["cloud", "azure"]},
+                {"name": "k8s", "url": "https://example.com/k8s.git", "ref": "v2",
+                 "tags": ["Cloud"]},
+                {"name": "notes", "url": "./flavors/notes"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(catalog.find("2").unwrap().reference.as_deref(), Some("v2"));
+        assert_eq!(catalog.find("AZURE").unwrap().name, "azure");
+        assert!(catalog.find("0").is_none());
+        assert!(catalog.find("4").is_none());
+        assert_eq!(catalog.matching(Some("cloud")), vec![0, 1]);
+        assert_eq!(catalog.matching(None).len(), 3);
+        assert_eq!(
+            catalog.flavors[0].summary(),
+            "Resource group reports [cloud, azure]"
+        );
+        assert_eq!(catalog.flavors[2].summary(), "");
+        assert!(parse("{\"flavors\": [{}]}").is_err());
+    }
+}
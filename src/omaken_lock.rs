@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const HEADER: &str =
+    "# Written by `omakure install`; `omakure install --locked` reproduces it.\n\n";
+
+/// Installed flavors pinned to the commits they resolved to, read from
+/// `omaken.lock` in the workspace root.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    #[serde(default, rename = "flavor")]
+    pub flavors: Vec<LockedFlavor>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedFlavor {
+    /// Folder under `.omaken/`.
+    pub name: String,
+    #[serde(default, skip_serializing_if = "FlavorSource::is_git")]
+    pub source: FlavorSource,
+    /// Git or archive URL, archive file, or folder the flavor came from.
+    pub url: String,
+    /// Tag or branch passed with `--ref`, kept for reference only.
+    #[serde(default, rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    /// Commit a git flavor is pinned to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Digest an archive must match to be installed again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+/// How a flavor was installed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FlavorSource {
+    #[default]
+    Git,
+    /// A `.tar.gz`, `.tgz`, `.tar`, or `.zip`, downloaded or local.
+    Archive,
+    /// A local folder, copied.
+    Path,
+}
+
+impl FlavorSource {
+    fn is_git(&self) -> bool {
+        *self == FlavorSource::Git
+    }
+}
+
+impl Lockfile {
+    /// Records `flavor`, replacing any entry with the same name; entries stay
+    /// sorted by name so the file diffs cleanly.
+    pub fn pin(&mut self, flavor: LockedFlavor) {
+        self.flavors.retain(|locked| locked.name != flavor.name);
+        self.flavors.push(flavor);
+        self.flavors.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LockedFlavor> {
+        self.flavors.iter().find(|flavor| flavor.name == name)
+    }
+}
+
+/// Reads the lockfile; a missing file pins nothing.
+pub fn load(path: &Path) -> Result<Lockfile, String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Lockfile::default()),
+        Err(err) => return Err(format!("Failed to read {}: {}", path.display(), err)),
+    };
+    toml::from_str(&contents).map_err(|err| format!("Failed to parse {}: {}", path.display(), err))
+}
+
+pub fn save(path: &Path, lockfile: &Lockfile) -> io::Result<()> {
+    let contents = toml::to_string(lockfile).map_err(io::Error::other)?;
+    fs::write(path, format!("{}{}", HEADER, contents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flavor(name: &str, commit: &str) -> LockedFlavor {
+        LockedFlavor {
+            name: name.to_string(),
+            source: FlavorSource::Git,
+            url: format!("https://example.com/{}.git", name),
+            reference: None,
+            commit: Some(commit.to_string()),
+            sha256: None,
+        }
+    }
+
+    #[test]
+    fn test_pin_and_round_trip() {
+        let mut lockfile = Lockfile::default();
+        lockfile.pin(flavor("k8s", "aaa"));
+        lockfile.pin(LockedFlavor {
+            reference: Some("v1.2".to_string()),
+            ..flavor("azure", "bbb")
+        });
+        lockfile.pin(flavor("k8s", "ccc"));
+        lockfile.pin(LockedFlavor {
+            source: FlavorSource::Archive,
+            url: "https://example.com/tools.tar.gz".to_string(),
+            commit: None,
+            sha256: Some("ddd".to_string()),
+            ..flavor("tools", "")
+        });
+        let names: Vec<&str> = lockfile.flavors.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["azure", "k8s", "tools"]);
+        assert_eq!(
+            lockfile.get("k8s").and_then(|f| f.commit.as_deref()),
+            Some("ccc")
+        );
+
+        let path = std::env::temp_dir().join(format!("omaken-{}.lock", std::process::id()));
+        save(&path, &lockfile).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("[[flavor]]\nname = \"azure\""));
+        assert!(contents.contains("ref = \"v1.2\""));
+        assert!(contents.contains("source = \"archive\""));
+        assert_eq!(contents.matches("source =").count(), 1);
+        assert_eq!(load(&path).unwrap(), lockfile);
+        let _ = fs::remove_file(&path);
+        assert_eq!(load(&path).unwrap(), Lockfile::default());
+    }
+}
@@ -0,0 +1,99 @@
+use serde::Deserialize;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Manifest file at the root of a flavor repository.
+pub const MANIFEST_FILE: &str = "omaken.toml";
+
+/// What a flavor says about itself in its `omaken.toml`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+pub struct FlavorManifest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// Commands the flavor's scripts need in PATH, e.g. `az` or `python`.
+    #[serde(default)]
+    pub runtimes: Vec<String>,
+    pub min_omakure_version: Option<String>,
+}
+
+/// Reads the manifest of the flavor in `flavor_dir`; `None` without one.
+pub fn load(flavor_dir: &Path) -> Result<Option<FlavorManifest>, String> {
+    let path = flavor_dir.join(MANIFEST_FILE);
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(format!("Failed to read {}: {}", path.display(), err)),
+    };
+    toml::from_str(&contents)
+        .map(Some)
+        .map_err(|err| format!("Failed to parse {}: {}", path.display(), err))
+}
+
+impl FlavorManifest {
+    /// Fails when `current` is older than `min_omakure_version`.
+    pub fn check_version(&self, current: &str) -> Result<(), String> {
+        let Some(minimum) = &self.min_omakure_version else {
+            return Ok(());
+        };
+        let required = parse_version(minimum)
+            .ok_or_else(|| format!("Invalid min_omakure_version: {}", minimum))?;
+        match parse_version(current) {
+            Some(current_parts) if current_parts < required => Err(format!(
+                "Requires omakure {} or newer (this is {}); run omakure update",
+                minimum, current
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// `Name: description`, whichever of the two is set.
+    pub fn summary(&self) -> Option<String> {
+        match (&self.name, &self.description) {
+            (Some(name), Some(description)) => Some(format!("{}: {}", name, description)),
+            (Some(text), None) | (None, Some(text)) => Some(text.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// `1.2.3` or `v1.2` as numbers padded to three parts; a pre-release suffix
+/// (`-rc.1`) is ignored.
+pub(crate) fn parse_version(text: &str) -> Option<Vec<u64>> {
+    let core = text.trim().trim_start_matches('v');
+    let core = core.split(['-', '+']).next().unwrap_or(core);
+    let mut parts = core
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+    parts.resize(parts.len().max(3), 0);
+    Some(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_version() {
+        let manifest: FlavorManifest = toml::from_str(
+            "name = \"Azure\"\nruntimes = [\"az\", \"jq\"]\nmin_omakure_version = \"0.6\"\n",
+        )
+        .unwrap();
+        assert_eq!(manifest.runtimes, vec!["az", "jq"]);
+        assert_eq!(manifest.summary().as_deref(), Some("Azure"));
+        assert!(manifest.check_version("0.6.0").is_ok());
+        assert!(manifest.check_version("v0.10.1-rc.1").is_ok());
+        assert!(manifest
+            .check_version("0.5.9")
+            .unwrap_err()
+            .contains("Requires omakure 0.6 or newer"));
+
+        let invalid = FlavorManifest {
+            min_omakure_version: Some("latest".to_string()),
+            ..FlavorManifest::default()
+        };
+        assert!(invalid.check_version("1.0.0").is_err());
+        assert!(FlavorManifest::default().check_version("0.1.0").is_ok());
+    }
+}
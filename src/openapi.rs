@@ -0,0 +1,293 @@
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "patch", "head", "options", "trace",
+];
+
+/// Where an operation parameter goes in the request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamLocation {
+    Path,
+    Query,
+    Header,
+    /// Top-level property of a JSON request body.
+    Body,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Param {
+    pub name: String,
+    pub location: ParamLocation,
+    /// omakure field type: `string`, `number`, or `bool`.
+    pub kind: String,
+    pub required: bool,
+    pub default: Option<String>,
+    pub choices: Option<Vec<String>>,
+    pub description: Option<String>,
+}
+
+/// An OpenAPI operation flattened into the pieces a script needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operation {
+    pub id: String,
+    pub method: String,
+    pub path: String,
+    pub summary: Option<String>,
+    pub server: Option<String>,
+    pub params: Vec<Param>,
+}
+
+/// Reads a spec file; YAML is a superset of JSON, so both parse the same way.
+pub fn load_spec(path: &Path) -> Result<Value, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+    serde_yaml::from_str(&contents)
+        .map_err(|err| format!("Failed to parse {}: {}", path.display(), err))
+}
+
+/// Finds the operation with `operation_id` and collects its parameters.
+///
+/// Path-level parameters are merged with the operation's own, local `$ref`s
+/// are resolved, and a JSON request body contributes its top-level properties.
+pub fn find_operation(spec: &Value, operation_id: &str) -> Result<Operation, String> {
+    let paths = spec
+        .get("paths")
+        .and_then(Value::as_object)
+        .ok_or("Spec has no paths")?;
+    for (path, item) in paths {
+        let item = resolve(spec, item);
+        for method in METHODS {
+            let Some(operation) = item.get(method).map(|op| resolve(spec, op)) else {
+                continue;
+            };
+            if operation.get("operationId").and_then(Value::as_str) != Some(operation_id) {
+                continue;
+            }
+            return Ok(Operation {
+                id: operation_id.to_string(),
+                method: method.to_ascii_uppercase(),
+                path: path.clone(),
+                summary: text(operation, "summary").or_else(|| text(operation, "description")),
+                server: spec
+                    .pointer("/servers/0/url")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                params: collect_params(spec, item, operation),
+            });
+        }
+    }
+    Err(format!("Operation not found: {}", operation_id))
+}
+
+fn collect_params(spec: &Value, item: &Value, operation: &Value) -> Vec<Param> {
+    let mut params: Vec<Param> = Vec::new();
+    let declared = [item, operation]
+        .into_iter()
+        .filter_map(|value| value.get("parameters").and_then(Value::as_array))
+        .flatten();
+    for parameter in declared {
+        let Some(param) = parse_parameter(spec, resolve(spec, parameter)) else {
+            continue;
+        };
+        // Operation parameters override path-level ones with the same name and location.
+        params
+            .retain(|existing| existing.name != param.name || existing.location != param.location);
+        params.push(param);
+    }
+    if let Some(schema) = operation
+        .pointer("/requestBody")
+        .map(|body| resolve(spec, body))
+        .and_then(|body| body.pointer("/content/application~1json/schema"))
+    {
+        params.extend(body_params(spec, resolve(spec, schema)));
+    }
+    params
+}
+
+fn parse_parameter(spec: &Value, parameter: &Value) -> Option<Param> {
+    let location = match parameter.get("in").and_then(Value::as_str)? {
+        "path" => ParamLocation::Path,
+        "query" => ParamLocation::Query,
+        "header" => ParamLocation::Header,
+        _ => return None,
+    };
+    let schema = parameter
+        .get("schema")
+        .map(|schema| resolve(spec, schema))
+        .unwrap_or(&Value::Null);
+    let mut param = schema_param(parameter.get("name")?.as_str()?, location, schema);
+    param.required = location == ParamLocation::Path
+        || parameter
+            .get("required")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+    if let Some(description) = text(parameter, "description") {
+        param.description = Some(description);
+    }
+    Some(param)
+}
+
+fn body_params(spec: &Value, schema: &Value) -> Vec<Param> {
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    properties
+        .iter()
+        .map(|(name, property)| {
+            let mut param = schema_param(name, ParamLocation::Body, resolve(spec, property));
+            param.required = required.contains(&name.as_str());
+            param
+        })
+        .collect()
+}
+
+fn schema_param(name: &str, location: ParamLocation, schema: &Value) -> Param {
+    let kind = match schema.get("type").and_then(Value::as_str) {
+        Some("integer") | Some("number") => "number",
+        Some("boolean") => "bool",
+        _ => "string",
+    };
+    Param {
+        name: name.to_string(),
+        location,
+        kind: kind.to_string(),
+        required: false,
+        default: schema.get("default").and_then(scalar),
+        choices: schema
+            .get("enum")
+            .and_then(Value::as_array)
+            .map(|values| values.iter().filter_map(scalar).collect()),
+        description: text(schema, "description"),
+    }
+}
+
+/// Follows a local `#/...` reference; anything else is returned unchanged.
+fn resolve<'a>(spec: &'a Value, value: &'a Value) -> &'a Value {
+    let mut current = value;
+    // Bounded so a self-referencing spec cannot loop forever.
+    for _ in 0..16 {
+        let Some(reference) = current.get("$ref").and_then(Value::as_str) else {
+            break;
+        };
+        match reference
+            .strip_prefix('#')
+            .and_then(|pointer| spec.pointer(pointer))
+        {
+            Some(target) => current = target,
+            None => break,
+        }
+    }
+    current
+}
+
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.clone()),
+        Value::Number(value) => Some(value.to_string()),
+        Value::Bool(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+fn text(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r##"
+openapi: 3.0.0
+servers:
+  - url: https://api.example.com/v1
+paths:
+  /orgs/{org}/users:
+    parameters:
+      - name: org
+        in: path
+        schema: { type: string }
+    post:
+      operationId: createUser
+      summary: Create a user
+      parameters:
+        - $ref: "#/components/parameters/DryRun"
+        - name: X-Request-Id
+          in: header
+          schema: { type: string }
+      requestBody:
+        content:
+          application/json:
+            schema:
+              $ref: "#/components/schemas/NewUser"
+components:
+  parameters:
+    DryRun:
+      name: dry_run
+      in: query
+      schema: { type: boolean, default: false }
+  schemas:
+    NewUser:
+      type: object
+      required: [email]
+      properties:
+        email: { type: string }
+        role: { type: string, enum: [admin, member] }
+        age: { type: integer }
+"##;
+
+    #[test]
+    fn test_find_operation_collects_params() {
+        let spec: Value = serde_yaml::from_str(SPEC).unwrap();
+        let operation = find_operation(&spec, "createUser").unwrap();
+        assert_eq!(operation.method, "POST");
+        assert_eq!(operation.path, "/orgs/{org}/users");
+        assert_eq!(operation.summary.as_deref(), Some("Create a user"));
+        assert_eq!(
+            operation.server.as_deref(),
+            Some("https://api.example.com/v1")
+        );
+
+        let summary: Vec<(&str, ParamLocation, &str, bool)> = operation
+            .params
+            .iter()
+            .map(|param| {
+                (
+                    param.name.as_str(),
+                    param.location,
+                    param.kind.as_str(),
+                    param.required,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("org", ParamLocation::Path, "string", true),
+                ("dry_run", ParamLocation::Query, "bool", false),
+                ("X-Request-Id", ParamLocation::Header, "string", false),
+                ("age", ParamLocation::Body, "number", false),
+                ("email", ParamLocation::Body, "string", true),
+                ("role", ParamLocation::Body, "string", false),
+            ]
+        );
+        assert_eq!(operation.params[1].default.as_deref(), Some("false"));
+        assert_eq!(
+            operation.params[5].choices,
+            Some(vec!["admin".to_string(), "member".to_string()])
+        );
+
+        assert!(find_operation(&spec, "deleteUser").is_err());
+    }
+}
@@ -0,0 +1,45 @@
+use crate::domain::{parse_pipeline, Pipeline};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The pipeline files in `dir`, by name. A file that does not parse is
+/// listed with its error, so it can be reported instead of hidden.
+pub fn list(dir: &Path) -> Vec<(String, Result<Pipeline, String>)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "toml") {
+                path.file_stem()
+                    .map(|name| name.to_string_lossy().into_owned())
+            } else {
+                None
+            }
+        })
+        .collect();
+    names.sort();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let pipeline = load(dir, &name).transpose()?;
+            Some((name, pipeline))
+        })
+        .collect()
+}
+
+/// Reads `<dir>/<name>.toml`; `None` when there is no such file.
+pub fn load(dir: &Path, name: &str) -> Result<Option<Pipeline>, String> {
+    let path = dir.join(format!("{}.toml", name));
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(format!("Failed to read {}: {}", path.display(), err)),
+    };
+    parse_pipeline(name, &contents)
+        .map(Some)
+        .map_err(|err| format!("{}: {}", path.display(), err))
+}
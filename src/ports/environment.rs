@@ -17,9 +17,13 @@ pub struct EnvFile {
 
 pub type EnvPreview = Vec<(String, String)>;
 
-pub trait EnvironmentRepository {
+/// Unmasked `KEY=value` pairs of an env file, in file order.
+pub type EnvVars = Vec<(String, String)>;
+
+pub trait EnvironmentRepository: Send + Sync {
     fn list_env_files(&self) -> AppResult<Vec<EnvFile>>;
     fn load_environment_config(&self) -> AppResult<EnvironmentConfig>;
     fn set_active_env(&self, name: Option<&str>) -> AppResult<()>;
     fn load_env_preview(&self, path: &Path) -> AppResult<EnvPreview>;
+    fn load_env_vars(&self, name: &str) -> AppResult<EnvVars>;
 }
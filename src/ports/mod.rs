@@ -1,11 +1,15 @@
 mod environment;
 
-use crate::domain::Schema;
+use crate::domain::{RunLimits, Schema, ScriptPrompt};
 use crate::error::AppResult;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
-pub use environment::{EnvFile, EnvPreview, EnvironmentConfig, EnvironmentRepository};
+pub use environment::{EnvFile, EnvPreview, EnvVars, EnvironmentConfig, EnvironmentRepository};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WorkspaceEntryKind {
@@ -19,7 +23,7 @@ pub struct WorkspaceEntry {
     pub kind: WorkspaceEntryKind,
 }
 
-pub trait ScriptRepository {
+pub trait ScriptRepository: Send + Sync {
     fn list_entries(&self, dir: &Path) -> io::Result<Vec<WorkspaceEntry>>;
     fn list_scripts_recursive(&self) -> io::Result<Vec<PathBuf>>;
     fn read_schema(&self, script: &Path) -> AppResult<Schema>;
@@ -31,8 +35,161 @@ pub struct ScriptRunOutput {
     pub stderr: String,
     pub exit_code: Option<i32>,
     pub success: bool,
+    pub usage: Option<ResourceUsage>,
+    /// Wall-clock time from spawn to exit.
+    pub duration_ms: u64,
+    /// Folder the run's output logs were written to, if any.
+    pub log_dir: Option<PathBuf>,
+    /// Failed attempts run again under the schema's `Retry` before this one.
+    pub retries: u32,
+    /// `Hooks` commands run around the script, in the order they ran.
+    pub hooks: Vec<HookRun>,
+    /// Files of `file` outputs copied into `log_dir`, relative to it.
+    pub files: Vec<PathBuf>,
 }
 
-pub trait ScriptRunner {
-    fn run(&self, script: &Path, args: &[String]) -> AppResult<ScriptRunOutput>;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookStage {
+    Before,
+    After,
+}
+
+impl HookStage {
+    pub fn label(self) -> &'static str {
+        match self {
+            HookStage::Before => "before",
+            HookStage::After => "after",
+        }
+    }
+}
+
+/// One `Hooks` command run before or after a script.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookRun {
+    pub stage: HookStage,
+    pub command: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// CPU time and peak memory consumed by a script process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    pub user_cpu_ms: u64,
+    pub system_cpu_ms: u64,
+    pub max_rss_kb: Option<u64>,
+}
+
+/// Per-run settings handed to a runner alongside the script arguments.
+#[derive(Debug, Clone, Default)]
+pub struct RunOptions {
+    pub limits: RunLimits,
+    /// Bytes of stdout and of stderr kept in memory; the rest only goes to the logs.
+    pub max_output_bytes: Option<usize>,
+    /// Folder receiving `stdout.log` and `stderr.log` for truncated streams.
+    pub log_dir: Option<PathBuf>,
+    /// Extra environment variables set for the script process.
+    pub env: Vec<(String, String)>,
+    pub backend: Backend,
+    /// Where and how `Backend::Kubernetes` runs the script.
+    pub kubernetes: KubernetesOptions,
+    /// Host to run the script on over SSH instead of locally.
+    pub ssh: Option<SshTarget>,
+    /// Container to run the script in instead of on this machine.
+    pub container: Option<ContainerOptions>,
+    /// Paths fetched from the SSH host into `log_dir` once the script finishes.
+    pub remote_artifacts: Vec<String>,
+    /// Tools from the schema's `Requires`, checked before a local run.
+    pub requires: Vec<String>,
+    /// Kill the script process once it has run this long.
+    pub timeout: Option<Duration>,
+    pub interpreters: Interpreters,
+    /// Answers the script's `OMAKURE_PROMPT` lines; without it the script's
+    /// stdin is closed.
+    pub prompts: Option<Prompts>,
+}
+
+/// Answers the questions a running script asks with `OMAKURE_PROMPT`.
+pub trait PromptHandler: Send + Sync {
+    /// Blocks until the user answers; `None` when they dismiss the prompt.
+    fn ask(&self, prompt: &ScriptPrompt) -> Option<String>;
+}
+
+/// A shared `PromptHandler`, as `RunOptions` carries it.
+#[derive(Clone)]
+pub struct Prompts(pub Arc<dyn PromptHandler>);
+
+impl fmt::Debug for Prompts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Prompts")
+    }
+}
+
+/// Where a script runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backend {
+    #[default]
+    Local,
+    /// As a Kubernetes Job, with the script mounted from a ConfigMap.
+    Kubernetes,
+}
+
+impl Backend {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "local" => Some(Backend::Local),
+            "kubernetes" | "k8s" => Some(Backend::Kubernetes),
+            _ => None,
+        }
+    }
+}
+
+/// The `[kubernetes]` settings; unset values use kubectl's current context
+/// and namespace, and an image picked from the script type.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KubernetesOptions {
+    pub context: Option<String>,
+    pub namespace: Option<String>,
+    pub image: Option<String>,
+}
+
+/// A script's `Runtime`: the image it runs in, and the engine running it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerOptions {
+    pub image: String,
+    /// `docker` or `podman`; `None` picks whichever is installed.
+    pub engine: Option<String>,
+}
+
+/// Programs replacing the default `bash`, PowerShell, and Python for local
+/// runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Interpreters {
+    pub bash: Option<String>,
+    pub powershell: Option<String>,
+    pub python: Option<String>,
+}
+
+/// An inventory host, as passed to `ssh`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshTarget {
+    /// `user@address`, or just the address.
+    pub destination: String,
+    pub port: Option<u16>,
+}
+
+pub trait ScriptRunner: Send + Sync {
+    fn run(
+        &self,
+        script: &Path,
+        args: &[String],
+        options: &RunOptions,
+    ) -> AppResult<ScriptRunOutput>;
+
+    /// Runs an ad-hoc shell command line from `dir`.
+    fn run_shell(&self, line: &str, dir: &Path, options: &RunOptions)
+        -> AppResult<ScriptRunOutput>;
 }
@@ -0,0 +1,219 @@
+use crate::config::PreflightConfig;
+use crate::domain::Schema;
+use crate::ports::Interpreters;
+use crate::runtime::{command_for_script, shell_command};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// A check that works without configuration.
+struct Builtin {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    check: &'static [&'static str],
+    login: &'static [&'static str],
+}
+
+const BUILTIN: [Builtin; 3] = [
+    Builtin {
+        name: "aws",
+        aliases: &[],
+        check: &["aws", "sts", "get-caller-identity", "--output", "json"],
+        login: &["aws", "sso", "login"],
+    },
+    Builtin {
+        name: "azure",
+        aliases: &["az"],
+        // `az account show` still succeeds once the token expired; fetching a token does not.
+        check: &["az", "account", "get-access-token", "--output", "none"],
+        login: &["az", "login"],
+    },
+    Builtin {
+        name: "gcloud",
+        aliases: &["gcp"],
+        check: &["gcloud", "auth", "print-access-token", "--quiet"],
+        login: &["gcloud", "auth", "login"],
+    },
+];
+
+/// How to run a check or a login.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Invocation {
+    Program(Vec<String>),
+    /// Command line for the platform shell, from `omakure.toml`.
+    Shell(String),
+    /// Workspace script, from `omakure.toml`.
+    Script(PathBuf),
+}
+
+impl Invocation {
+    pub fn command(&self) -> Result<Command, String> {
+        match self {
+            Invocation::Program(argv) => {
+                let mut command = Command::new(&argv[0]);
+                command.args(&argv[1..]);
+                Ok(command)
+            }
+            Invocation::Shell(line) => Ok(shell_command(line)),
+            Invocation::Script(script) => command_for_script(script, &Interpreters::default())
+                .map_err(|err| format!("{}: {}", script.display(), err)),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Invocation::Program(argv) => argv.join(" "),
+            Invocation::Shell(line) => line.clone(),
+            Invocation::Script(script) => script.display().to_string(),
+        }
+    }
+}
+
+/// A credential check run before a script's form opens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: String,
+    pub command: Invocation,
+    /// What to run when the check fails, if anything.
+    pub login: Option<Invocation>,
+}
+
+/// Checks a script asks for through `Preflight`, or through tags naming a check.
+///
+/// Built-in checks are `aws`, `azure`, and `gcloud`; `[preflight.<name>]`
+/// tables add checks or replace the command or login of a built-in one.
+/// Tags that name no check are ordinary tags, but an unknown `Preflight`
+/// name is an error.
+pub fn checks_for(
+    schema: &Schema,
+    config: &BTreeMap<String, PreflightConfig>,
+    root: &Path,
+) -> Result<Vec<Check>, String> {
+    let declared = schema.preflight.iter().flatten().map(|name| (name, true));
+    let tagged = schema.tags.iter().flatten().map(|name| (name, false));
+    let mut checks: Vec<Check> = Vec::new();
+    for (name, explicit) in declared.chain(tagged) {
+        let Some(check) = resolve(name, config, root) else {
+            if explicit {
+                return Err(format!("Unknown preflight check: {}", name));
+            }
+            continue;
+        };
+        if !checks.iter().any(|existing| existing.name == check.name) {
+            checks.push(check);
+        }
+    }
+    Ok(checks)
+}
+
+fn resolve(name: &str, config: &BTreeMap<String, PreflightConfig>, root: &Path) -> Option<Check> {
+    let key = name.trim().to_ascii_lowercase();
+    let builtin = BUILTIN
+        .iter()
+        .find(|builtin| builtin.name == key || builtin.aliases.contains(&key.as_str()));
+    let name = builtin
+        .map(|builtin| builtin.name.to_string())
+        .unwrap_or(key);
+    let custom = config.get(&name);
+    let command = match (custom.and_then(|custom| custom.command.as_ref()), builtin) {
+        (Some(line), _) => Invocation::Shell(line.clone()),
+        (None, Some(builtin)) => Invocation::Program(to_strings(builtin.check)),
+        (None, None) => return None,
+    };
+    let login = match custom.and_then(|custom| custom.login.as_ref()) {
+        Some(script) => Some(Invocation::Script(root.join(script))),
+        None => builtin.map(|builtin| Invocation::Program(to_strings(builtin.login))),
+    };
+    Some(Check {
+        name,
+        command,
+        login,
+    })
+}
+
+/// Runs a check quietly; the error is the last line it printed to stderr.
+///
+/// `env` is the active environment, which often picks the profile or account.
+pub fn run(check: &Check, dir: &Path, env: &[(String, String)]) -> Result<(), String> {
+    let output = check
+        .command
+        .command()?
+        .current_dir(dir)
+        .envs(env.iter().map(|(key, value)| (key, value)))
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|err| format!("{}: {}", check.command.label(), err))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(stderr
+        .lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .unwrap_or("check failed")
+        .to_string())
+}
+
+fn to_strings(parts: &[&str]) -> Vec<String> {
+    parts.iter().map(|part| part.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema(preflight: Option<Vec<&str>>, tags: Vec<&str>) -> Schema {
+        let mut schema = crate::domain::parse_schema(r#"{"Name":"deploy","Fields":[]}"#).unwrap();
+        schema.preflight = preflight.map(|names| to_strings(&names));
+        schema.tags = Some(to_strings(&tags));
+        schema
+    }
+
+    #[test]
+    fn test_checks_for_merges_declared_tags_and_config() {
+        let mut config = BTreeMap::new();
+        config.insert(
+            "aws".to_string(),
+            PreflightConfig {
+                command: None,
+                login: Some("auth/aws-login.bash".to_string()),
+            },
+        );
+        config.insert(
+            "vault".to_string(),
+            PreflightConfig {
+                command: Some("vault token lookup".to_string()),
+                login: None,
+            },
+        );
+        let root = Path::new("/ws");
+
+        let checks = checks_for(
+            &schema(Some(vec!["vault", "az"]), vec!["aws", "azure", "infra"]),
+            &config,
+            root,
+        )
+        .unwrap();
+        let names: Vec<&str> = checks.iter().map(|check| check.name.as_str()).collect();
+        assert_eq!(names, vec!["vault", "azure", "aws"]);
+        assert_eq!(
+            checks[0].command,
+            Invocation::Shell("vault token lookup".to_string())
+        );
+        assert_eq!(checks[0].login, None);
+        assert_eq!(
+            checks[1].login,
+            Some(Invocation::Program(to_strings(&["az", "login"])))
+        );
+        assert_eq!(
+            checks[2].login,
+            Some(Invocation::Script(PathBuf::from("/ws/auth/aws-login.bash")))
+        );
+
+        assert_eq!(
+            checks_for(&schema(Some(vec!["nope"]), vec![]), &config, root).unwrap_err(),
+            "Unknown preflight check: nope"
+        );
+    }
+}
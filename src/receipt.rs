@@ -0,0 +1,272 @@
+use crate::app_dirs::AppDirs;
+use crate::history::{self, HistoryEntry};
+use crate::util::write_private;
+use crate::workspace::Workspace;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Receipt file name inside a run's artifacts folder.
+pub const RECEIPT_FILE: &str = "receipt.json";
+/// Signing key, in the global config folder.
+const KEY_FILE: &str = "receipt.key";
+const RECEIPT_VERSION: u32 = 1;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Signed evidence of one run: what ran, how it ended, and digests of the
+/// arguments and output, so the receipt can be shared without leaking either.
+///
+/// The signature is an HMAC under a secret key, so only holders of that key can
+/// check it, and each of them could also have forged it. It proves a receipt
+/// came from someone with the key, not from a given user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Receipt {
+    pub version: u32,
+    /// Script path relative to the workspace, or `:<command>` for shell commands.
+    pub script: String,
+    pub args_sha256: String,
+    /// `ok`, `failed`, or `error` when the script never started.
+    pub status: String,
+    pub exit_code: Option<i32>,
+    /// End of the run in Unix milliseconds; also its history id.
+    pub timestamp: i64,
+    pub duration_ms: Option<u64>,
+    /// Digest of the full output as `history::format_output` shows it.
+    pub output_sha256: String,
+    /// First bytes of the key's digest, telling which key signed the receipt.
+    pub key_id: String,
+    /// HMAC-SHA256 of the receipt with an empty signature, in hex.
+    #[serde(default)]
+    pub signature: String,
+}
+
+impl Receipt {
+    /// Unsigned receipt for `entry`; `output` is its full, untruncated output.
+    pub fn new(entry: &HistoryEntry, output: &str) -> Self {
+        Self {
+            version: RECEIPT_VERSION,
+            script: entry.script.to_string_lossy().to_string(),
+            args_sha256: args_digest(&entry.args),
+            status: status(entry).to_string(),
+            exit_code: entry.exit_code,
+            timestamp: entry.timestamp,
+            duration_ms: entry.duration_ms,
+            output_sha256: sha256_hex(output.as_bytes()),
+            key_id: String::new(),
+            signature: String::new(),
+        }
+    }
+
+    pub fn sign(&mut self, key: &[u8]) {
+        self.key_id = key_id(key);
+        self.signature = hex(&self.mac(key).finalize().into_bytes());
+    }
+
+    /// Whether the signature matches the contents under `key`.
+    pub fn verify(&self, key: &[u8]) -> bool {
+        let Some(signature) = unhex(&self.signature) else {
+            return false;
+        };
+        self.mac(key).verify_slice(&signature).is_ok()
+    }
+
+    /// Differences from the receipt `entry` would get, as `field` names.
+    pub fn mismatches(&self, entry: &HistoryEntry, output: &str) -> Vec<&'static str> {
+        let expected = Receipt::new(entry, output);
+        let mut fields = Vec::new();
+        if self.script != expected.script {
+            fields.push("script");
+        }
+        if self.args_sha256 != expected.args_sha256 {
+            fields.push("args");
+        }
+        if self.status != expected.status || self.exit_code != expected.exit_code {
+            fields.push("status");
+        }
+        if self.duration_ms != expected.duration_ms {
+            fields.push("duration");
+        }
+        if self.output_sha256 != expected.output_sha256 {
+            fields.push("output");
+        }
+        fields
+    }
+
+    fn mac(&self, key: &[u8]) -> HmacSha256 {
+        let unsigned = Receipt {
+            signature: String::new(),
+            ..self.clone()
+        };
+        let payload = serde_json::to_vec(&unsigned).unwrap_or_default();
+        let mut mac = HmacSha256::new_from_slice(key).expect("HMAC takes keys of any length");
+        mac.update(&payload);
+        mac
+    }
+}
+
+/// Signs a receipt for `entry` and writes it into the run's artifacts folder.
+pub fn write(workspace: &Workspace, entry: &HistoryEntry) -> io::Result<PathBuf> {
+    let key = load_or_create_key()?;
+    let output = full_output(workspace, entry);
+    let mut receipt = Receipt::new(entry, &output);
+    receipt.sign(&key);
+    let dir = history::run_artifacts_dir(workspace, entry);
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(RECEIPT_FILE);
+    let data = serde_json::to_string_pretty(&receipt).map_err(io::Error::other)?;
+    fs::write(&path, data + "\n")?;
+    Ok(path)
+}
+
+pub fn read(path: &Path) -> Result<Receipt, String> {
+    let data = fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    serde_json::from_str(&data).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+/// Output the receipt digests: the kept logs when output was truncated.
+pub fn full_output(workspace: &Workspace, entry: &HistoryEntry) -> String {
+    history::load_full_output(workspace, entry, None)
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| history::format_output(entry))
+}
+
+pub fn key_path() -> Option<PathBuf> {
+    AppDirs::resolve().map(|dirs| dirs.config.join(KEY_FILE))
+}
+
+/// The signing key, or `None` when none was created yet.
+pub fn load_key() -> io::Result<Option<Vec<u8>>> {
+    let path = key_path().ok_or_else(no_home)?;
+    match fs::read_to_string(&path) {
+        Ok(data) => unhex(data.trim()).map(Some).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} is not a hex key", path.display()),
+            )
+        }),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Loads the signing key, creating a random one readable only by the user
+/// on first use.
+fn load_or_create_key() -> io::Result<Vec<u8>> {
+    if let Some(key) = load_key()? {
+        return Ok(key);
+    }
+    let path = key_path().ok_or_else(no_home)?;
+    let mut key = vec![0u8; 32];
+    getrandom::getrandom(&mut key).map_err(|err| io::Error::other(err.to_string()))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_private(&path, (hex(&key) + "\n").as_bytes())?;
+    Ok(key)
+}
+
+fn no_home() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        "No home directory for the receipt key",
+    )
+}
+
+fn status(entry: &HistoryEntry) -> &'static str {
+    match (&entry.error, entry.success) {
+        (Some(_), _) => "error",
+        (None, true) => "ok",
+        (None, false) => "failed",
+    }
+}
+
+/// Digest of the arguments, NUL-separated so `a b` and `a`, `b` differ.
+fn args_digest(args: &[String]) -> String {
+    let mut hasher = Sha256::new();
+    for arg in args {
+        hasher.update(arg.as_bytes());
+        hasher.update([0]);
+    }
+    hex(&hasher.finalize())
+}
+
+pub fn key_id(key: &[u8]) -> String {
+    sha256_hex(key)[..16].to_string()
+}
+
+pub(crate) fn sha256_hex(data: &[u8]) -> String {
+    hex(&Sha256::digest(data))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn unhex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(text.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> HistoryEntry {
+        HistoryEntry {
+            timestamp: 1_700_000_000_000,
+            script: PathBuf::from("deploy.bash"),
+            args: vec!["--env".to_string(), "prod".to_string()],
+            success: true,
+            exit_code: Some(0),
+            stdout: "done\n".to_string(),
+            stderr: String::new(),
+            error: None,
+            usage: None,
+            duration_ms: Some(1200),
+            artifacts: None,
+            runs: Vec::new(),
+            command: None,
+            env_overrides: Vec::new(),
+            retries: 0,
+            hooks: Vec::new(),
+            files: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_receipt_signature_and_mismatches() {
+        let entry = entry();
+        let mut receipt = Receipt::new(&entry, "STDOUT:\ndone");
+        receipt.sign(b"secret");
+        assert_eq!(receipt.key_id, key_id(b"secret"));
+        assert!(receipt.verify(b"secret"));
+        assert!(!receipt.verify(b"other"));
+
+        let json = serde_json::to_string(&receipt).unwrap();
+        let parsed: Receipt = serde_json::from_str(&json).unwrap();
+        assert!(parsed.verify(b"secret"));
+
+        let mut tampered = parsed.clone();
+        tampered.status = "failed".to_string();
+        assert!(!tampered.verify(b"secret"));
+
+        assert!(receipt.mismatches(&entry, "STDOUT:\ndone").is_empty());
+        let rerun = HistoryEntry {
+            args: vec!["--env prod".to_string()],
+            ..entry
+        };
+        assert_eq!(receipt.mismatches(&rerun, "other"), vec!["args", "output"]);
+        assert_eq!(unhex(&hex(&[0, 15, 255])), Some(vec![0, 15, 255]));
+        assert_eq!(unhex("abc"), None);
+    }
+}
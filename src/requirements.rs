@@ -0,0 +1,103 @@
+use crate::adapters::system_checks::ensure_runtime_installed;
+use crate::omaken_manifest::parse_version;
+use std::process::Command;
+
+/// Arguments tried, in order, to get a tool to print its version.
+const VERSION_ARGS: [&[&str]; 3] = [&["--version"], &["version", "--client"], &["version"]];
+
+/// A tool a script lists in `Requires`: `az`, or `kubectl>=1.27`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Requirement {
+    pub name: String,
+    pub min_version: Option<String>,
+}
+
+pub fn parse(spec: &str) -> Requirement {
+    match spec.split_once(">=") {
+        Some((name, version)) => Requirement {
+            name: name.trim().to_string(),
+            min_version: Some(version.trim().to_string()),
+        },
+        None => Requirement {
+            name: spec.trim().to_string(),
+            min_version: None,
+        },
+    }
+}
+
+/// The first dotted version number in a tool's `--version` output, such as
+/// `1.28.2` in `Client Version: v1.28.2`.
+pub fn version_in(output: &str) -> Option<&str> {
+    output
+        .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .map(|word| word.trim_matches('.'))
+        .find(|word| word.contains('.') && parse_version(word).is_some())
+}
+
+/// Whether the tool `spec` names is installed, and new enough.
+pub fn check(spec: &str) -> Result<(), String> {
+    let requirement = parse(spec);
+    ensure_runtime_installed(&requirement.name).map_err(|err| err.to_string())?;
+    let Some(minimum) = &requirement.min_version else {
+        return Ok(());
+    };
+    let required =
+        parse_version(minimum).ok_or_else(|| format!("Invalid version in Requires: {}", spec))?;
+    let installed = VERSION_ARGS
+        .iter()
+        .filter_map(|args| Command::new(&requirement.name).args(*args).output().ok())
+        .find_map(|output| {
+            let text = format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            version_in(&text).map(str::to_string)
+        })
+        .ok_or_else(|| {
+            format!(
+                "Could not tell which {} version is installed",
+                requirement.name
+            )
+        })?;
+    match parse_version(&installed) {
+        Some(parts) if parts < required => Err(format!(
+            "{} {} is installed; {} or newer is required",
+            requirement.name, installed, minimum
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Problems with the tools in `requires`, one message per tool.
+pub fn missing(requires: &[String]) -> Vec<String> {
+    requires
+        .iter()
+        .filter_map(|spec| check(spec).err())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_find_versions() {
+        assert_eq!(
+            parse("kubectl >= 1.27"),
+            Requirement {
+                name: "kubectl".to_string(),
+                min_version: Some("1.27".to_string()),
+            }
+        );
+        assert_eq!(parse("az").min_version, None);
+        assert_eq!(version_in("Client Version: v1.28.2\n"), Some("1.28.2"));
+        assert_eq!(version_in("azure-cli  2.53.0 *"), Some("2.53.0"));
+        assert_eq!(version_in("jq-1.7.1"), Some("1.7.1"));
+        assert_eq!(
+            version_in("GNU bash, version 5.2.15(1)-release"),
+            Some("5.2.15")
+        );
+        assert_eq!(version_in("no version here 42"), None);
+    }
+}
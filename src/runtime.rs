@@ -1,48 +1,413 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::{OnceLock, RwLock};
 
+use crate::domain::PathStyle;
 use crate::error::ScriptError;
+use crate::ports::{Interpreters, KubernetesOptions, SshTarget};
+use crate::util::is_executable_file;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ScriptKind {
     Bash,
     PowerShell,
     Python,
+    /// GitHub Actions `workflow_dispatch` action, described by a `.workflow.json` file.
+    Workflow,
+    /// A binary or other executable file, run directly with its sidecar schema's fields.
+    Executable,
+    /// A Makefile target, listed as `Makefile#target` and run with `make`.
+    Make,
+    /// A justfile recipe, listed as `justfile#recipe` and run with `just`.
+    Just,
+    /// A script with an extension mapped to a command line in `[runner.extensions]`.
+    Custom,
 }
 
-pub fn script_kind(path: &Path) -> Option<ScriptKind> {
+/// Extensions omakure runs itself; `[runner.extensions]` cannot remap them.
+const BUILTIN_EXTENSIONS: [&str; 5] = ["bash", "sh", "ps1", "py", "workflow.json"];
+
+/// An extension from `[runner.extensions]` and the command line running its
+/// scripts, e.g. `ts` with `deno run`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomExtension {
+    /// Lowercase, without the leading dot.
+    pub extension: String,
+    /// Program followed by its arguments; the script path comes after them.
+    pub command: Vec<String>,
+    /// Prefix of the lines holding the schema block, e.g. `//`.
+    pub comment: String,
+}
+
+static CUSTOM_EXTENSIONS: RwLock<Vec<CustomExtension>> = RwLock::new(Vec::new());
+
+/// Replaces the extensions of the workspace in use. Built-in extensions and
+/// empty command lines are skipped.
+pub fn set_custom_extensions(extensions: Vec<CustomExtension>) {
+    let extensions = extensions
+        .into_iter()
+        .filter(|custom| !custom.command.is_empty() && !is_builtin_extension(&custom.extension))
+        .collect();
+    if let Ok(mut registry) = CUSTOM_EXTENSIONS.write() {
+        *registry = extensions;
+    }
+}
+
+pub fn is_builtin_extension(extension: &str) -> bool {
+    BUILTIN_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+}
+
+/// The `[runner.extensions]` entry running `path`, if one matches its extension.
+pub fn custom_extension(path: &Path) -> Option<CustomExtension> {
     let ext = path.extension()?.to_str()?.to_ascii_lowercase();
-    match ext.as_str() {
-        "bash" | "sh" => Some(ScriptKind::Bash),
-        "ps1" => Some(ScriptKind::PowerShell),
-        "py" => Some(ScriptKind::Python),
+    CUSTOM_EXTENSIONS
+        .read()
+        .ok()?
+        .iter()
+        .find(|custom| custom.extension == ext)
+        .cloned()
+}
+
+/// Separates a Makefile or justfile from one of its targets in a script path,
+/// e.g. `ops/Makefile#deploy`.
+pub const TASK_SEPARATOR: char = '#';
+
+/// Hidden subcommand the runner calls to dispatch a workflow action.
+pub const DISPATCH_SUBCOMMAND: &str = "dispatch";
+
+pub fn script_kind(path: &Path) -> Option<ScriptKind> {
+    if let Some((file, _)) = task_target(path) {
+        return task_file_kind(&file);
+    }
+    if is_workflow_file(path) {
+        return Some(ScriptKind::Workflow);
+    }
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+    match ext.as_deref() {
+        Some("bash" | "sh") => Some(ScriptKind::Bash),
+        Some("ps1") => Some(ScriptKind::PowerShell),
+        Some("py") => Some(ScriptKind::Python),
+        _ if custom_extension(path).is_some() => Some(ScriptKind::Custom),
+        _ if is_wrapped_executable(path, ext.as_deref()) => Some(ScriptKind::Executable),
+        _ => None,
+    }
+}
+
+fn is_workflow_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.to_ascii_lowercase().ends_with(".workflow.json"))
+}
+
+/// Kind of the targets a Makefile or justfile holds, or `None` for any other file.
+pub fn task_file_kind(path: &Path) -> Option<ScriptKind> {
+    match path.file_name()?.to_str()? {
+        "Makefile" | "makefile" | "GNUmakefile" => Some(ScriptKind::Make),
+        "justfile" | "Justfile" | ".justfile" => Some(ScriptKind::Just),
         _ => None,
     }
 }
 
-pub fn script_extensions() -> &'static [&'static str] {
-    &["bash", "sh", "ps1", "py"]
+/// Splits `ops/Makefile#deploy` into the task file and the target name.
+pub fn task_target(path: &Path) -> Option<(PathBuf, &str)> {
+    let name = path.file_name()?.to_str()?;
+    let (file, target) = name.split_once(TASK_SEPARATOR)?;
+    let file = path.with_file_name(file);
+    (task_file_kind(&file).is_some() && !target.is_empty()).then_some((file, target))
 }
 
-pub fn command_for_script(script: &Path) -> Result<Command, ScriptError> {
+/// File on disk behind a script: the Makefile or justfile for a target, the
+/// script itself otherwise.
+pub fn source_file(script: &Path) -> PathBuf {
+    task_target(script)
+        .map(|(file, _)| file)
+        .unwrap_or_else(|| script.to_path_buf())
+}
+
+/// Executables only count as scripts once a sidecar schema describes them;
+/// plenty of files carry an execute bit without being meant for omakure.
+fn is_wrapped_executable(path: &Path, ext: Option<&str>) -> bool {
+    (ext == Some("exe") || is_executable_file(path))
+        && sidecar_schema_paths(path)
+            .iter()
+            .any(|sidecar| sidecar.is_file())
+}
+
+/// Schema files that may sit next to a script, in the order they are tried:
+/// `deploy.omakure.json`, then `deploy.omakure.toml` for `deploy.bash`.
+/// Targets use the task file's name, e.g. `Makefile.deploy.omakure.json` for
+/// `Makefile#deploy`.
+///
+/// A sidecar is read instead of the script's embedded schema block, which
+/// gives a schema to scripts that cannot be edited, such as vendored tools,
+/// shims around binaries, or scripts published by other tools.
+pub fn sidecar_schema_paths(script: &Path) -> Vec<PathBuf> {
+    if is_workflow_file(script) {
+        return Vec::new();
+    }
+    let stem = match task_target(script) {
+        Some((file, target)) => match file.file_name().and_then(|name| name.to_str()) {
+            Some(name) => format!("{}.{}", name, target),
+            None => return Vec::new(),
+        },
+        None => match script.file_stem().and_then(|stem| stem.to_str()) {
+            Some(stem) => stem.to_string(),
+            None => return Vec::new(),
+        },
+    };
+    ["json", "toml"]
+        .iter()
+        .map(|ext| script.with_file_name(format!("{}.omakure.{}", stem, ext)))
+        .collect()
+}
+
+/// Extensions of the scripts omakure lists: the built-in ones, then those
+/// from `[runner.extensions]`.
+pub fn script_extensions() -> Vec<String> {
+    let mut extensions: Vec<String> = BUILTIN_EXTENSIONS
+        .iter()
+        .map(|ext| ext.to_string())
+        .collect();
+    if let Ok(registry) = CUSTOM_EXTENSIONS.read() {
+        extensions.extend(registry.iter().map(|custom| custom.extension.clone()));
+    }
+    extensions
+}
+
+/// The command running `script`, with `interpreters` in place of the
+/// default programs.
+pub fn command_for_script(
+    script: &Path,
+    interpreters: &Interpreters,
+) -> Result<Command, ScriptError> {
     let kind = script_kind(script).ok_or(ScriptError::UnsupportedType)?;
     let mut command = match kind {
-        ScriptKind::Bash => Command::new("bash"),
-        ScriptKind::PowerShell => Command::new(powershell_program()),
-        ScriptKind::Python => Command::new(python_program()),
+        ScriptKind::Bash => Command::new(interpreters.bash.as_deref().unwrap_or("bash")),
+        ScriptKind::PowerShell => Command::new(
+            interpreters
+                .powershell
+                .as_deref()
+                .unwrap_or(powershell_program()),
+        ),
+        ScriptKind::Python => {
+            Command::new(interpreters.python.as_deref().unwrap_or(python_program()))
+        }
+        ScriptKind::Workflow => {
+            Command::new(std::env::current_exe().unwrap_or_else(|_| PathBuf::from("omakure")))
+        }
+        ScriptKind::Executable => Command::new(script),
+        ScriptKind::Make => Command::new("make"),
+        ScriptKind::Just => Command::new("just"),
+        ScriptKind::Custom => {
+            let custom = custom_extension(script).ok_or(ScriptError::UnsupportedType)?;
+            let mut command = Command::new(&custom.command[0]);
+            command.args(&custom.command[1..]);
+            command
+        }
     };
 
     match kind {
-        ScriptKind::Bash | ScriptKind::Python => {
+        ScriptKind::Bash | ScriptKind::Python | ScriptKind::Custom => {
             command.arg(script);
         }
         ScriptKind::PowerShell => {
             command.arg("-NoProfile").arg("-File").arg(script);
         }
+        ScriptKind::Workflow => {
+            command.arg(DISPATCH_SUBCOMMAND).arg(script);
+        }
+        ScriptKind::Executable => {}
+        ScriptKind::Make | ScriptKind::Just => {
+            let (file, target) = task_target(script).ok_or(ScriptError::UnsupportedType)?;
+            let dir = file.parent().unwrap_or(Path::new("."));
+            if kind == ScriptKind::Make {
+                command
+                    .arg("--no-print-directory")
+                    .arg("-C")
+                    .arg(dir)
+                    .arg("-f")
+                    .arg(&file);
+            } else {
+                command
+                    .arg("--justfile")
+                    .arg(&file)
+                    .arg("--working-directory")
+                    .arg(dir);
+            }
+            command.arg(target);
+        }
     }
 
     Ok(command)
 }
 
+/// Rewrites the `--flag value` pairs built from a form into what `script`
+/// takes: `flag=value` variables for make, positional recipe parameters in
+/// the recipe's order for just, and the pairs unchanged for everything else.
+pub fn script_args(script: &Path, kind: ScriptKind, args: &[String]) -> Vec<String> {
+    match kind {
+        ScriptKind::Make => args
+            .chunks(2)
+            .map(|pair| match pair {
+                [flag, value] => format!("{}={}", flag.trim_start_matches('-'), value),
+                _ => pair[0].clone(),
+            })
+            .collect(),
+        ScriptKind::Just => crate::adapters::task_files::recipe_args(script, args),
+        _ => args.to_vec(),
+    }
+}
+
+/// Hidden subcommand the runner calls to run a script as a Kubernetes Job.
+pub const KUBE_RUN_SUBCOMMAND: &str = "kube-run";
+
+/// Command running `script` on the Kubernetes backend through `omakure kube-run`.
+///
+/// Only the names of `env` are passed on the command line; `kube-run` reads the
+/// values from its own environment, where the runner sets them.
+pub fn kubernetes_command(
+    script: &Path,
+    args: &[String],
+    kubernetes: &KubernetesOptions,
+    env: &[(String, String)],
+) -> Result<Command, ScriptError> {
+    match script_kind(script) {
+        Some(ScriptKind::Bash | ScriptKind::Python | ScriptKind::PowerShell) => {}
+        _ => return Err(ScriptError::UnsupportedType),
+    }
+    let mut command =
+        Command::new(std::env::current_exe().unwrap_or_else(|_| PathBuf::from("omakure")));
+    command.arg(KUBE_RUN_SUBCOMMAND);
+    for (flag, value) in [
+        ("--context", &kubernetes.context),
+        ("--namespace", &kubernetes.namespace),
+        ("--image", &kubernetes.image),
+    ] {
+        if let Some(value) = value {
+            command.arg(flag).arg(value);
+        }
+    }
+    for (name, _) in env {
+        command.arg("--env").arg(name);
+    }
+    command.arg(script).arg("--").args(args);
+    Ok(command)
+}
+
+/// Hidden subcommand the runner calls to run a script on an inventory host.
+pub const SSH_RUN_SUBCOMMAND: &str = "ssh-run";
+
+/// Command running `script` on `target` through `omakure ssh-run`.
+///
+/// Like `kubernetes_command`, only the names of `env` go on the command line.
+/// `remote_artifacts` are fetched into `artifacts_dir` after the run.
+pub fn ssh_command(
+    script: &Path,
+    args: &[String],
+    target: &SshTarget,
+    env: &[(String, String)],
+    remote_artifacts: &[String],
+    artifacts_dir: Option<&Path>,
+) -> Result<Command, ScriptError> {
+    match script_kind(script) {
+        Some(ScriptKind::Bash | ScriptKind::Python) => {}
+        _ => return Err(ScriptError::UnsupportedType),
+    }
+    let mut command =
+        Command::new(std::env::current_exe().unwrap_or_else(|_| PathBuf::from("omakure")));
+    command
+        .arg(SSH_RUN_SUBCOMMAND)
+        .arg("--destination")
+        .arg(&target.destination);
+    if let Some(port) = target.port {
+        command.arg("--port").arg(port.to_string());
+    }
+    for (name, _) in env {
+        command.arg("--env").arg(name);
+    }
+    if let Some(dir) = artifacts_dir.filter(|_| !remote_artifacts.is_empty()) {
+        command.arg("--artifacts-dir").arg(dir);
+        for path in remote_artifacts {
+            command.arg("--collect").arg(path);
+        }
+    }
+    command.arg(script).arg("--").args(args);
+    Ok(command)
+}
+
+/// Runs an ad-hoc command line through the platform shell.
+pub fn shell_command(line: &str) -> Command {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new(powershell_program());
+        command.arg("-NoProfile").arg("-Command");
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c");
+        command
+    };
+    command.arg(line);
+    command
+}
+
+/// Interpreter and full argument vector used to run `script` with `args`,
+/// rewritten by `script_args` the way a local run passes them.
+pub fn resolved_command(
+    script: &Path,
+    args: &[String],
+    interpreters: &Interpreters,
+) -> Result<Vec<String>, ScriptError> {
+    let command = command_for_script(script, interpreters)?;
+    let mut parts = vec![command.get_program().to_string_lossy().into_owned()];
+    parts.extend(
+        command
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned()),
+    );
+    match script_kind(script) {
+        Some(kind) => parts.extend(script_args(script, kind, args)),
+        None => parts.extend(args.iter().cloned()),
+    }
+    Ok(parts)
+}
+
+/// Path style expected by the interpreter that runs `script`.
+pub fn path_style(script: &Path) -> PathStyle {
+    if !cfg!(windows) {
+        return PathStyle::Unix;
+    }
+    match script_kind(script) {
+        Some(ScriptKind::Bash) => windows_bash_path_style(),
+        _ => PathStyle::Windows,
+    }
+}
+
+/// Tells WSL's `bash.exe` (under System32) apart from Git Bash / MSYS.
+fn windows_bash_path_style() -> PathStyle {
+    static STYLE: OnceLock<PathStyle> = OnceLock::new();
+    *STYLE.get_or_init(|| {
+        let is_wsl = Command::new("where")
+            .arg("bash")
+            .output()
+            .ok()
+            .and_then(|output| {
+                String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .next()
+                    .map(|line| line.to_ascii_lowercase().contains("\\system32\\"))
+            })
+            .unwrap_or(false);
+        if is_wsl {
+            PathStyle::Wsl
+        } else {
+            PathStyle::Msys
+        }
+    })
+}
+
 pub fn powershell_program() -> &'static str {
     if cfg!(windows) {
         "powershell"
@@ -0,0 +1,198 @@
+use crate::receipt::sha256_hex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Lines either side compared by `line_diff`; longer files are shown whole.
+const MAX_DIFF_LINES: usize = 2000;
+
+/// Digests of the upstream scripts `omakure update` last synced, by path
+/// relative to the workspace. This is the base a local edit is judged
+/// against.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SyncBase {
+    files: BTreeMap<String, String>,
+}
+
+impl SyncBase {
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.files.get(path).map(String::as_str)
+    }
+
+    /// Records `contents` as the upstream version of `path`.
+    pub fn record(&mut self, path: &str, contents: &[u8]) {
+        self.files.insert(path.to_string(), sha256_hex(contents));
+    }
+}
+
+/// Reads the sync base; a missing file is an empty base.
+pub fn load(path: &Path) -> Result<SyncBase, String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(SyncBase::default()),
+        Err(err) => return Err(format!("Failed to read {}: {}", path.display(), err)),
+    };
+    serde_json::from_str(&contents)
+        .map_err(|err| format!("Failed to parse {}: {}", path.display(), err))
+}
+
+pub fn save(path: &Path, base: &SyncBase) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+    }
+    let contents = serde_json::to_string_pretty(base).map_err(|err| err.to_string())?;
+    fs::write(path, contents).map_err(|err| format!("Failed to write {}: {}", path.display(), err))
+}
+
+/// What syncing one upstream script does to the workspace copy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncAction {
+    /// Not in the workspace yet.
+    Copy,
+    UpToDate,
+    /// Upstream changed and the local copy was not edited.
+    Update,
+    /// The local copy was edited and upstream did not change.
+    KeepLocal,
+    /// Both changed, or there is no base to tell which did.
+    Conflict,
+}
+
+/// Three-way comparison of the `local` copy, the `upstream` version, and
+/// the digest of the `base` last synced.
+pub fn classify(local: Option<&[u8]>, upstream: &[u8], base: Option<&str>) -> SyncAction {
+    let Some(local) = local else {
+        return SyncAction::Copy;
+    };
+    if local == upstream {
+        return SyncAction::UpToDate;
+    }
+    match base {
+        Some(base) if sha256_hex(local) == base => SyncAction::Update,
+        Some(base) if sha256_hex(upstream) == base => SyncAction::KeepLocal,
+        _ => SyncAction::Conflict,
+    }
+}
+
+/// How the user settled a conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    KeepLocal,
+    TakeUpstream,
+    /// Move the local copy to `backup_path` and take upstream.
+    BackupAndTake,
+}
+
+/// A script changed both locally and upstream.
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    /// Relative to the workspace.
+    pub path: PathBuf,
+    pub local: String,
+    pub upstream: String,
+}
+
+/// `deploy.bash.bak`, or `deploy.bash.bak.2` and up when that is taken.
+pub fn backup_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut candidate = path.with_file_name(format!("{}.bak", name));
+    let mut counter = 2;
+    while candidate.exists() {
+        candidate = path.with_file_name(format!("{}.bak.{}", name, counter));
+        counter += 1;
+    }
+    candidate
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Line diff from `old` to `new` by longest common subsequence.
+pub fn line_diff<'a>(old: &'a str, new: &'a str) -> Vec<DiffLine<'a>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    if old.len() > MAX_DIFF_LINES || new.len() > MAX_DIFF_LINES {
+        return old
+            .into_iter()
+            .map(DiffLine::Removed)
+            .chain(new.into_iter().map(DiffLine::Added))
+            .collect();
+    }
+    // lengths[i][j]: common lines of old[i..] and new[j..].
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::new();
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            diff.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().copied().map(DiffLine::Removed));
+    diff.extend(new[j..].iter().copied().map(DiffLine::Added));
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_against_base() {
+        let base = sha256_hex(b"v1");
+        assert_eq!(classify(None, b"v2", Some(&base)), SyncAction::Copy);
+        assert_eq!(classify(Some(b"v2"), b"v2", None), SyncAction::UpToDate);
+        assert_eq!(
+            classify(Some(b"v1"), b"v2", Some(&base)),
+            SyncAction::Update
+        );
+        assert_eq!(
+            classify(Some(b"mine"), b"v1", Some(&base)),
+            SyncAction::KeepLocal
+        );
+        assert_eq!(
+            classify(Some(b"mine"), b"v2", Some(&base)),
+            SyncAction::Conflict
+        );
+        assert_eq!(classify(Some(b"mine"), b"v2", None), SyncAction::Conflict);
+    }
+
+    #[test]
+    fn test_line_diff() {
+        assert_eq!(
+            line_diff("a\nb\nc\n", "a\nc\nd\n"),
+            vec![
+                DiffLine::Same("a"),
+                DiffLine::Removed("b"),
+                DiffLine::Same("c"),
+                DiffLine::Added("d"),
+            ]
+        );
+    }
+}
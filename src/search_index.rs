@@ -1,17 +1,46 @@
 use crate::adapters::workspace_repository::FsWorkspaceRepository;
+use crate::domain::field_arg;
+use crate::logging;
 use crate::ports::ScriptRepository;
-use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use crate::runtime::{sidecar_schema_paths, source_file};
+use crate::util::fuzzy_score;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Transaction};
+use serde_json::json;
+use std::collections::{HashMap, VecDeque};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+/// `PRAGMA user_version` of the current index layout.
+const INDEX_VERSION: i64 = 5;
+/// How often the background thread looks for added, changed, or removed scripts.
+const RESCAN_INTERVAL: Duration = Duration::from_secs(5);
+/// BM25 weights for the `script_search` columns, in declaration order:
+/// path, display name, description, tags, fields, ownership.
+const COLUMN_WEIGHTS: &str = "4.0, 10.0, 2.0, 3.0, 1.0, 1.0";
+/// Extra score for tokens found in the display name or the script path.
+const NAME_BONUS: i64 = 20;
+const PATH_BONUS: i64 = 10;
+/// Boost per natural-log unit of runs: 20 runs lift a script by about 75%.
+const FREQUENCY_WEIGHT: f64 = 0.25;
+/// Boost for a script run just now, halving every `RECENCY_HALF_LIFE_DAYS`.
+const RECENCY_WEIGHT: f64 = 0.5;
+const RECENCY_HALF_LIFE_DAYS: f64 = 7.0;
+const DAY_MS: f64 = 86_400_000.0;
+/// Scripts whose details stay in memory between selection moves.
+const DETAILS_CACHE_SIZE: usize = 64;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SearchStatus {
     Idle,
     Indexing,
-    Ready { script_count: usize },
+    Ready {
+        script_count: usize,
+        /// Bumped whenever indexed content changes, so open results refresh.
+        revision: u64,
+    },
     Error(String),
 }
 
@@ -30,6 +59,8 @@ pub struct SearchField {
     pub prompt: Option<String>,
     pub kind: String,
     pub required: bool,
+    /// Flag the field is passed with, e.g. `--subscription`.
+    pub arg: String,
 }
 
 #[derive(Debug, Clone)]
@@ -37,6 +68,8 @@ pub struct SearchDetails {
     pub display_name: String,
     pub description: Option<String>,
     pub tags: Vec<String>,
+    pub owner: Option<String>,
+    pub contact: Option<String>,
     pub fields: Vec<SearchField>,
     pub schema_error: Option<String>,
 }
@@ -45,6 +78,48 @@ pub struct SearchDetails {
 pub struct SearchIndex {
     db_path: PathBuf,
     status: Arc<Mutex<SearchStatus>>,
+    details: Arc<Mutex<DetailsCache>>,
+}
+
+/// Recently loaded details, least recently used first; cleared whenever the
+/// index revision changes.
+#[derive(Default)]
+struct DetailsCache {
+    revision: u64,
+    entries: VecDeque<(PathBuf, Option<SearchDetails>)>,
+}
+
+impl DetailsCache {
+    fn get(&mut self, revision: u64, script_path: &Path) -> Option<Option<SearchDetails>> {
+        if self.revision != revision {
+            self.entries.clear();
+            self.revision = revision;
+        }
+        let index = self
+            .entries
+            .iter()
+            .position(|(path, _)| path == script_path)?;
+        let entry = self.entries.remove(index)?;
+        let details = entry.1.clone();
+        self.entries.push_back(entry);
+        Some(details)
+    }
+
+    fn contains(&self, revision: u64, script_path: &Path) -> bool {
+        self.revision == revision && self.entries.iter().any(|(path, _)| path == script_path)
+    }
+
+    /// Stores `details`, unless they were read before the index last changed.
+    fn insert(&mut self, revision: u64, script_path: &Path, details: Option<SearchDetails>) {
+        if self.revision != revision {
+            return;
+        }
+        self.entries.retain(|(path, _)| path != script_path);
+        if self.entries.len() >= DETAILS_CACHE_SIZE {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((script_path.to_path_buf(), details));
+    }
 }
 
 impl SearchIndex {
@@ -52,6 +127,7 @@ impl SearchIndex {
         Self {
             db_path,
             status: Arc::new(Mutex::new(SearchStatus::Idle)),
+            details: Arc::new(Mutex::new(DetailsCache::default())),
         }
     }
 
@@ -64,87 +140,145 @@ impl SearchIndex {
             ))
     }
 
-    pub fn start_background_rebuild(&self, root: PathBuf) {
-        let status = self.status.clone();
+    /// Indexes the workspace on a background thread, then keeps rescanning it
+    /// so added, edited, and removed scripts show up without a full rebuild.
+    ///
+    /// The thread stops once every clone of this index has been dropped.
+    ///
+    /// Newly indexed scripts take their run counts from `history_db`.
+    pub fn start_background_rebuild(&self, root: PathBuf, history_db: PathBuf) {
+        let status = Arc::downgrade(&self.status);
         let db_path = self.db_path.clone();
         thread::spawn(move || {
-            let _ = update_status(&status, SearchStatus::Indexing);
-            match rebuild_index(&db_path, &root) {
-                Ok(count) => {
-                    let _ = update_status(
-                        &status,
-                        SearchStatus::Ready {
-                            script_count: count,
-                        },
-                    );
-                }
-                Err(err) => {
-                    let _ = update_status(&status, SearchStatus::Error(err));
+            let mut revision = 0;
+            // Publish the next result even if nothing changed: first scan, or after an error.
+            let mut stale = true;
+            if let Some(status) = status.upgrade() {
+                let _ = update_status(&status, SearchStatus::Indexing);
+            }
+            while let Some(status) = status.upgrade() {
+                match update_index(&db_path, &root, &history_db) {
+                    Ok(update) if stale || update.changed > 0 => {
+                        logging::info(
+                            "index",
+                            "Search index updated",
+                            json!({ "scripts": update.script_count, "changed": update.changed }),
+                        );
+                        revision += 1;
+                        stale = false;
+                        let _ = update_status(
+                            &status,
+                            SearchStatus::Ready {
+                                script_count: update.script_count,
+                                revision,
+                            },
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        logging::error(
+                            "index",
+                            "Search index update failed",
+                            json!({ "error": err }),
+                        );
+                        stale = true;
+                        let _ = update_status(&status, SearchStatus::Error(err));
+                    }
                 }
+                drop(status);
+                thread::sleep(RESCAN_INTERVAL);
             }
         });
     }
 
+    /// Scripts matching every query token, best match first.
+    ///
+    /// Tokens are FTS5 prefix queries ranked by BM25, with hits in the display
+    /// name weighing most. When nothing matches, tokens are retried as fuzzy
+    /// subsequences (`rgcl` finds `rg-cleanup`). Either way, scripts run often
+    /// or lately rank higher, and an empty query lists them first.
+    ///
+    /// `field:<name>` and `arg:<flag>` keep only scripts with a field whose
+    /// name or flag starts with the value, so `arg:--subscription` finds every
+    /// script taking `--subscription`.
     pub fn query(&self, query: &str) -> Result<Vec<SearchResult>, String> {
         let conn = open_connection(&self.db_path)?;
         init_db(&conn)?;
 
-        let tokens = split_query(query);
-        let mut sql = String::from(
-            "SELECT script_path, display_name, description, tags, schema_error \
-             FROM script_index",
-        );
-        if !tokens.is_empty() {
-            sql.push_str(" WHERE ");
-            for (idx, _) in tokens.iter().enumerate() {
-                if idx > 0 {
-                    sql.push_str(" AND ");
-                }
-                sql.push_str("search_blob LIKE ? ESCAPE '\\'");
-            }
+        let query = parse_query(query);
+        if query.tokens.is_empty() {
+            return fuzzy_query(&conn, &query);
         }
-        sql.push_str(" ORDER BY display_name COLLATE NOCASE, script_path COLLATE NOCASE");
+        let results = match fts_expression(&query.tokens) {
+            Some(expression) => fts_query(&conn, &expression, &query.filters)?,
+            None => Vec::new(),
+        };
+        if results.is_empty() {
+            return fuzzy_query(&conn, &query);
+        }
+        Ok(results)
+    }
 
-        let mut stmt = conn
-            .prepare(&sql)
-            .map_err(|err| format!("Search prepare failed: {}", err))?;
+    /// Details of one indexed script, from memory when it was loaded lately.
+    pub fn load_details(&self, script_path: &Path) -> Result<Option<SearchDetails>, String> {
+        let revision = self.revision();
+        if let Some(details) = self
+            .details
+            .lock()
+            .ok()
+            .and_then(|mut cache| cache.get(revision, script_path))
+        {
+            return Ok(details);
+        }
+        let details = self.query_details(script_path)?;
+        if let Ok(mut cache) = self.details.lock() {
+            cache.insert(revision, script_path, details.clone());
+        }
+        Ok(details)
+    }
 
-        let params: Vec<String> = tokens
-            .iter()
-            .map(|token| format!("%{}%", escape_like(token)))
-            .collect();
-        let rows = stmt
-            .query_map(params_from_iter(params), |row| {
-                let script_path: String = row.get(0)?;
-                let display_name: String = row.get(1)?;
-                let description: Option<String> = row.get(2)?;
-                let tags_raw: Option<String> = row.get(3)?;
-                let schema_error: Option<String> = row.get(4)?;
-                Ok(SearchResult {
-                    script_path: PathBuf::from(script_path),
-                    display_name,
-                    description,
-                    tags: parse_tags(tags_raw),
-                    schema_error,
-                })
-            })
-            .map_err(|err| format!("Search query failed: {}", err))?;
+    /// Loads details of `script_paths` into memory on a background thread, so
+    /// selecting them next does not wait on the database.
+    pub fn prefetch_details(&self, script_paths: Vec<PathBuf>) {
+        let revision = self.revision();
+        let missing: Vec<PathBuf> = match self.details.lock() {
+            Ok(cache) => script_paths
+                .into_iter()
+                .filter(|path| !cache.contains(revision, path))
+                .collect(),
+            Err(_) => return,
+        };
+        if missing.is_empty() {
+            return;
+        }
+        let index = self.clone();
+        thread::spawn(move || {
+            for path in missing {
+                if let Ok(details) = index.query_details(&path) {
+                    if let Ok(mut cache) = index.details.lock() {
+                        cache.insert(revision, &path, details);
+                    }
+                }
+            }
+        });
+    }
 
-        let mut results = Vec::new();
-        for row in rows {
-            results.push(row.map_err(|err| format!("Search row failed: {}", err))?);
+    /// The revision cached details belong to; 0 until the first scan is ready.
+    fn revision(&self) -> u64 {
+        match self.status() {
+            SearchStatus::Ready { revision, .. } => revision,
+            _ => 0,
         }
-        Ok(results)
     }
 
-    pub fn load_details(&self, script_path: &Path) -> Result<Option<SearchDetails>, String> {
+    fn query_details(&self, script_path: &Path) -> Result<Option<SearchDetails>, String> {
         let conn = open_connection(&self.db_path)?;
         init_db(&conn)?;
         let script_path = script_path.to_string_lossy().to_string();
 
         let mut stmt = conn
             .prepare(
-                "SELECT display_name, description, tags, schema_error \
+                "SELECT display_name, description, tags, schema_error, owner, contact \
                  FROM script_index WHERE script_path = ?",
             )
             .map_err(|err| format!("Search detail prepare failed: {}", err))?;
@@ -155,19 +289,28 @@ impl SearchIndex {
                 let description: Option<String> = row.get(1)?;
                 let tags_raw: Option<String> = row.get(2)?;
                 let schema_error: Option<String> = row.get(3)?;
-                Ok((display_name, description, tags_raw, schema_error))
+                let owner: Option<String> = row.get(4)?;
+                let contact: Option<String> = row.get(5)?;
+                Ok((
+                    display_name,
+                    description,
+                    tags_raw,
+                    schema_error,
+                    owner,
+                    contact,
+                ))
             })
             .optional()
             .map_err(|err| format!("Search detail query failed: {}", err))?;
 
-        let (display_name, description, tags_raw, schema_error) = match base {
+        let (display_name, description, tags_raw, schema_error, owner, contact) = match base {
             Some(base) => base,
             None => return Ok(None),
         };
 
         let mut field_stmt = conn
             .prepare(
-                "SELECT name, prompt, kind, required \
+                "SELECT name, prompt, kind, required, arg \
                  FROM script_fields WHERE script_path = ? \
                  ORDER BY field_order",
             )
@@ -180,6 +323,7 @@ impl SearchIndex {
                     prompt: row.get(1)?,
                     kind: row.get(2)?,
                     required: row.get::<_, i64>(3)? != 0,
+                    arg: row.get(4)?,
                 })
             })
             .map_err(|err| format!("Search fields query failed: {}", err))?;
@@ -193,13 +337,167 @@ impl SearchIndex {
             display_name,
             description,
             tags: parse_tags(tags_raw),
+            owner,
+            contact,
             fields,
             schema_error,
         }))
     }
 }
 
-fn rebuild_index(db_path: &Path, root: &Path) -> Result<usize, String> {
+/// Result columns selected by both query paths, usage last.
+const RESULT_COLUMNS: &str = "i.script_path, i.display_name, i.description, i.tags, \
+     i.schema_error, i.runs_count, i.last_run_at";
+
+fn fts_query(
+    conn: &Connection,
+    expression: &str,
+    filters: &[FieldFilter],
+) -> Result<Vec<SearchResult>, String> {
+    let (conditions, patterns) = filter_conditions(filters, 2);
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {}, bm25(script_search, {}) FROM script_search s \
+             JOIN script_index i ON i.script_path = s.script_path \
+             WHERE script_search MATCH ?1{} \
+             ORDER BY bm25(script_search, {}), i.display_name COLLATE NOCASE",
+            RESULT_COLUMNS,
+            COLUMN_WEIGHTS,
+            conditions
+                .iter()
+                .map(|condition| format!(" AND {}", condition))
+                .collect::<String>(),
+            COLUMN_WEIGHTS
+        ))
+        .map_err(|err| format!("Search prepare failed: {}", err))?;
+    let query_params = std::iter::once(expression.to_string()).chain(patterns);
+    let rows = stmt
+        .query_map(params_from_iter(query_params), |row| {
+            // BM25 is negative, more so for better matches.
+            let relevance = -row.get::<_, f64>(7)?;
+            Ok((read_result(row)?, relevance, read_usage(row)?))
+        })
+        .map_err(|err| format!("Search query failed: {}", err))?;
+    let mut ranked = Vec::new();
+    for row in rows {
+        ranked.push(row.map_err(|err| format!("Search row failed: {}", err))?);
+    }
+    Ok(rank_by_usage(ranked))
+}
+
+/// Scans every script passing the filters, keeping those where each token
+/// is a fuzzy match.
+fn fuzzy_query(conn: &Connection, query: &Query) -> Result<Vec<SearchResult>, String> {
+    let (conditions, patterns) = filter_conditions(&query.filters, 1);
+    let filter = if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    };
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {}, s.description, s.tags, s.fields, s.ownership FROM script_search s \
+             JOIN script_index i ON i.script_path = s.script_path{} \
+             ORDER BY i.display_name COLLATE NOCASE, i.script_path COLLATE NOCASE",
+            RESULT_COLUMNS, filter
+        ))
+        .map_err(|err| format!("Search prepare failed: {}", err))?;
+    let rows = stmt
+        .query_map(params_from_iter(patterns), |row| {
+            let text: Vec<String> = (7..11)
+                .map(|index| row.get::<_, String>(index))
+                .collect::<Result<_, _>>()?;
+            Ok((
+                read_result(row)?,
+                read_usage(row)?,
+                text.join(" ").to_lowercase(),
+            ))
+        })
+        .map_err(|err| format!("Search query failed: {}", err))?;
+
+    let mut scored = Vec::new();
+    for row in rows {
+        let (result, usage, text) = row.map_err(|err| format!("Search row failed: {}", err))?;
+        if let Some(score) = match_score(&query.tokens, &result, &text) {
+            // An empty query matches everything with 0, leaving only usage to rank by.
+            scored.push((result, score.max(1) as f64, usage));
+        }
+    }
+    Ok(rank_by_usage(scored))
+}
+
+/// How often and how recently a script ran.
+#[derive(Debug, Clone, Copy)]
+struct Usage {
+    runs_count: i64,
+    last_run_at: Option<i64>,
+}
+
+impl Usage {
+    /// Factor of at least 1 lifting scripts that run often or ran lately.
+    fn boost(self, now_ms: i64) -> f64 {
+        let frequency = (self.runs_count.max(0) as f64).ln_1p() * FREQUENCY_WEIGHT;
+        let recency = self.last_run_at.map_or(0.0, |last_run_at| {
+            let days = (now_ms - last_run_at).max(0) as f64 / DAY_MS;
+            RECENCY_WEIGHT * 0.5_f64.powf(days / RECENCY_HALF_LIFE_DAYS)
+        });
+        1.0 + frequency + recency
+    }
+}
+
+/// Orders by relevance times usage boost.
+///
+/// Stable, so equal ranks keep the order from SQL.
+fn rank_by_usage(mut ranked: Vec<(SearchResult, f64, Usage)>) -> Vec<SearchResult> {
+    let now_ms = timestamp_ms();
+    let rank = |(_, relevance, usage): &(SearchResult, f64, Usage)| relevance * usage.boost(now_ms);
+    ranked.sort_by(|a, b| rank(b).total_cmp(&rank(a)));
+    ranked.into_iter().map(|(result, ..)| result).collect()
+}
+
+fn read_usage(row: &rusqlite::Row) -> rusqlite::Result<Usage> {
+    Ok(Usage {
+        runs_count: row.get(5)?,
+        last_run_at: row.get(6)?,
+    })
+}
+
+/// Counts a run of `script` (relative to the workspace root) recorded at `at`.
+///
+/// Does nothing before the TUI built the index; indexing then counts the run
+/// from history.
+pub fn record_run(db_path: &Path, script: &Path, at: i64) -> Result<(), String> {
+    if !db_path.exists() {
+        return Ok(());
+    }
+    let conn = open_connection(db_path)?;
+    init_db(&conn)?;
+    conn.execute(
+        "UPDATE script_index SET runs_count = runs_count + 1, \
+         last_run_at = MAX(COALESCE(last_run_at, 0), ?1) WHERE script_path = ?2",
+        params![at, script.to_string_lossy()],
+    )
+    .map_err(|err| format!("Record run failed: {}", err))?;
+    Ok(())
+}
+
+fn read_result(row: &rusqlite::Row) -> rusqlite::Result<SearchResult> {
+    let script_path: String = row.get(0)?;
+    let tags_raw: Option<String> = row.get(3)?;
+    Ok(SearchResult {
+        script_path: PathBuf::from(script_path),
+        display_name: row.get(1)?,
+        description: row.get(2)?,
+        tags: parse_tags(tags_raw),
+        schema_error: row.get(4)?,
+    })
+}
+
+/// Brings the index in line with the workspace.
+///
+/// Only scripts that are new or whose modification time changed are read
+/// again; scripts that are gone are dropped.
+fn update_index(db_path: &Path, root: &Path, history_db: &Path) -> Result<IndexUpdate, String> {
     let repo = FsWorkspaceRepository::new(root.to_path_buf());
     let scripts = repo
         .list_scripts_recursive()
@@ -209,102 +507,234 @@ fn rebuild_index(db_path: &Path, root: &Path) -> Result<usize, String> {
     init_db(&conn)?;
     conn.execute("PRAGMA foreign_keys = ON", [])
         .map_err(|err| format!("Enable foreign keys failed: {}", err))?;
+    let mut indexed = indexed_mtimes(&conn)?;
+    // Attaching a missing file would create it.
+    let history = history_db.exists()
+        && conn
+            .execute(
+                "ATTACH DATABASE ? AS history",
+                [history_db.to_string_lossy()],
+            )
+            .is_ok();
 
     let tx = conn
         .transaction()
         .map_err(|err| format!("Begin transaction failed: {}", err))?;
-    tx.execute("DELETE FROM script_fields", [])
-        .map_err(|err| format!("Clear fields failed: {}", err))?;
-    tx.execute("DELETE FROM script_index", [])
-        .map_err(|err| format!("Clear scripts failed: {}", err))?;
-
+    let mut changed = 0;
     for script in &scripts {
-        let relative = script.strip_prefix(root).unwrap_or(script);
-        let relative_str = relative.to_string_lossy().to_string();
-        let file_name = script
-            .file_name()
-            .and_then(|name| name.to_str())
-            .unwrap_or("script");
-
-        let mut schema_error = None;
-        let mut display_name = file_name.to_string();
-        let mut description: Option<String> = None;
-        let mut tags: Vec<String> = Vec::new();
-        let mut fields: Vec<SearchField> = Vec::new();
-
-        match repo.read_schema(script) {
-            Ok(schema) => {
-                display_name = schema.name.clone();
-                description = schema.description.clone();
-                tags = schema.tags.clone().unwrap_or_default();
-                fields = schema
-                    .fields
-                    .iter()
-                    .map(|field| SearchField {
-                        name: field.name.clone(),
-                        prompt: field.prompt.clone(),
-                        kind: field.kind.clone(),
-                        required: field.required.unwrap_or(false),
-                    })
-                    .collect();
-            }
-            Err(err) => {
-                schema_error = Some(err.to_string());
-            }
+        let relative_str = script
+            .strip_prefix(root)
+            .unwrap_or(script)
+            .to_string_lossy()
+            .to_string();
+        // A published sidecar schema can change without touching its script.
+        let mtime_ms = sidecar_schema_paths(script)
+            .iter()
+            .map(|sidecar| modified_ms(sidecar))
+            .fold(modified_ms(&source_file(script)), i64::max);
+        let previous = indexed.remove(&relative_str);
+        if previous == Some(mtime_ms) {
+            continue;
+        }
+        remove_script_text(&tx, &relative_str)?;
+        index_script(&tx, &repo, script, &relative_str, mtime_ms)?;
+        if previous.is_none() && history {
+            count_past_runs(&tx, &relative_str)?;
         }
+        changed += 1;
+    }
+    // Whatever was not seen on disk has been removed or renamed.
+    for relative_str in indexed.keys() {
+        remove_script(&tx, relative_str)?;
+        changed += 1;
+    }
 
-        let search_blob = build_search_blob(
-            &relative_str,
-            &display_name,
-            description.as_deref(),
-            &tags,
-            &fields,
-        );
+    tx.commit()
+        .map_err(|err| format!("Commit search index failed: {}", err))?;
+    Ok(IndexUpdate {
+        script_count: scripts.len(),
+        changed,
+    })
+}
 
-        let tags_raw = if tags.is_empty() {
-            None
-        } else {
-            Some(tags.join(","))
-        };
-        let indexed_at = timestamp_ms();
+struct IndexUpdate {
+    script_count: usize,
+    changed: usize,
+}
+
+fn indexed_mtimes(conn: &Connection) -> Result<HashMap<String, i64>, String> {
+    let mut stmt = conn
+        .prepare("SELECT script_path, mtime_ms FROM script_index")
+        .map_err(|err| format!("Read indexed scripts failed: {}", err))?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|err| format!("Read indexed scripts failed: {}", err))?;
+    rows.map(|row| row.map_err(|err| format!("Read indexed scripts failed: {}", err)))
+        .collect()
+}
+
+/// Modification time in milliseconds, or 0 when the platform has none.
+fn modified_ms(path: &Path) -> i64 {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+fn remove_script(tx: &Transaction, relative_str: &str) -> Result<(), String> {
+    remove_script_text(tx, relative_str)?;
+    tx.execute(
+        "DELETE FROM script_index WHERE script_path = ?",
+        [relative_str],
+    )
+    .map_err(|err| format!("Remove script failed: {}", err))?;
+    Ok(())
+}
+
+/// Drops the rows rebuilt from the schema, keeping the script's usage.
+fn remove_script_text(tx: &Transaction, relative_str: &str) -> Result<(), String> {
+    for table in ["script_fields", "script_search"] {
+        tx.execute(
+            &format!("DELETE FROM {} WHERE script_path = ?", table),
+            [relative_str],
+        )
+        .map_err(|err| format!("Remove script failed: {}", err))?;
+    }
+    Ok(())
+}
+
+/// Seeds a newly indexed script's usage from the attached history database.
+fn count_past_runs(tx: &Transaction, relative_str: &str) -> Result<(), String> {
+    tx.execute(
+        "UPDATE script_index SET (runs_count, last_run_at) = \
+         (SELECT COUNT(*), MAX(timestamp) FROM history.history_entries WHERE script = ?1) \
+         WHERE script_path = ?1",
+        [relative_str],
+    )
+    .map_err(|err| format!("Count past runs failed: {}", err))?;
+    Ok(())
+}
+
+/// Reads `script`'s schema and inserts its rows.
+fn index_script(
+    tx: &Transaction,
+    repo: &FsWorkspaceRepository,
+    script: &Path,
+    relative_str: &str,
+    mtime_ms: i64,
+) -> Result<(), String> {
+    let file_name = script
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("script");
+
+    let mut schema_error = None;
+    let mut display_name = file_name.to_string();
+    let mut description: Option<String> = None;
+    let mut tags: Vec<String> = Vec::new();
+    let mut owner: Option<String> = None;
+    let mut contact: Option<String> = None;
+    let mut fields: Vec<SearchField> = Vec::new();
+
+    match repo.read_schema(script) {
+        Ok(schema) => {
+            display_name = schema.name.clone();
+            description = schema.description.clone();
+            tags = schema.tags.clone().unwrap_or_default();
+            owner = schema.owner.clone();
+            contact = schema.contact.clone();
+            fields = schema
+                .fields
+                .iter()
+                .map(|field| SearchField {
+                    name: field.name.clone(),
+                    prompt: field.prompt.clone(),
+                    kind: field.kind.clone(),
+                    required: field.required.unwrap_or(false),
+                    arg: field_arg(field),
+                })
+                .collect();
+        }
+        Err(err) => {
+            schema_error = Some(err.to_string());
+        }
+    }
+
+    let field_text = fields
+        .iter()
+        .flat_map(|field| [Some(field.name.as_str()), field.prompt.as_deref()])
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let ownership = [owner.as_deref(), contact.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let tags_raw = if tags.is_empty() {
+        None
+    } else {
+        Some(tags.join(","))
+    };
+    let indexed_at = timestamp_ms();
+
+    tx.execute(
+        "INSERT INTO script_index \
+         (script_path, display_name, description, tags, schema_error, indexed_at, owner, contact, mtime_ms) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?) \
+         ON CONFLICT(script_path) DO UPDATE SET display_name = excluded.display_name, \
+         description = excluded.description, tags = excluded.tags, \
+         schema_error = excluded.schema_error, indexed_at = excluded.indexed_at, \
+         owner = excluded.owner, contact = excluded.contact, mtime_ms = excluded.mtime_ms",
+        params![
+            relative_str,
+            display_name,
+            description,
+            tags_raw,
+            schema_error,
+            indexed_at,
+            owner,
+            contact,
+            mtime_ms
+        ],
+    )
+    .map_err(|err| format!("Insert script failed: {}", err))?;
+    tx.execute(
+        "INSERT INTO script_search \
+         (script_path, display_name, description, tags, fields, ownership) \
+         VALUES (?, ?, ?, ?, ?, ?)",
+        params![
+            relative_str,
+            display_name,
+            description.as_deref().unwrap_or_default(),
+            tags.join(" "),
+            field_text,
+            ownership
+        ],
+    )
+    .map_err(|err| format!("Insert search text failed: {}", err))?;
 
+    for (order, field) in fields.iter().enumerate() {
         tx.execute(
-            "INSERT OR REPLACE INTO script_index \
-             (script_path, display_name, description, tags, search_blob, schema_error, indexed_at) \
+            "INSERT INTO script_fields \
+             (script_path, field_order, name, prompt, kind, required, arg) \
              VALUES (?, ?, ?, ?, ?, ?, ?)",
             params![
-                relative_str.as_str(),
-                display_name,
-                description,
-                tags_raw,
-                search_blob,
-                schema_error,
-                indexed_at
+                relative_str,
+                order as i64,
+                &field.name,
+                field.prompt.clone(),
+                &field.kind,
+                if field.required { 1 } else { 0 },
+                &field.arg
             ],
         )
-        .map_err(|err| format!("Insert script failed: {}", err))?;
-
-        for (order, field) in fields.iter().enumerate() {
-            tx.execute(
-                "INSERT INTO script_fields \
-                 (script_path, field_order, name, prompt, kind, required) \
-                 VALUES (?, ?, ?, ?, ?, ?)",
-                params![
-                    &relative_str,
-                    order as i64,
-                    &field.name,
-                    field.prompt.clone(),
-                    &field.kind,
-                    if field.required { 1 } else { 0 }
-                ],
-            )
-            .map_err(|err| format!("Insert field failed: {}", err))?;
-        }
+        .map_err(|err| format!("Insert field failed: {}", err))?;
     }
-
-    tx.commit()
-        .map_err(|err| format!("Commit search index failed: {}", err))?;
-    Ok(scripts.len())
+    Ok(())
 }
 
 fn open_connection(db_path: &Path) -> Result<Connection, String> {
@@ -323,15 +753,33 @@ fn open_connection(db_path: &Path) -> Result<Connection, String> {
 }
 
 fn init_db(conn: &Connection) -> Result<(), String> {
+    // The index is a cache, so an outdated layout is dropped and rebuilt.
+    let version: i64 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|err| format!("Read search db version failed: {}", err))?;
+    if version < INDEX_VERSION {
+        conn.execute_batch(&format!(
+            "DROP TABLE IF EXISTS script_fields;\
+             DROP TABLE IF EXISTS script_index;\
+             DROP TABLE IF EXISTS script_search;\
+             PRAGMA user_version = {};",
+            INDEX_VERSION
+        ))
+        .map_err(|err| format!("Reset search db failed: {}", err))?;
+    }
     conn.execute_batch(
         "CREATE TABLE IF NOT EXISTS script_index (\
             script_path TEXT PRIMARY KEY,\
             display_name TEXT NOT NULL,\
             description TEXT,\
             tags TEXT,\
-            search_blob TEXT NOT NULL,\
             schema_error TEXT,\
-            indexed_at INTEGER NOT NULL\
+            indexed_at INTEGER NOT NULL,\
+            owner TEXT,\
+            contact TEXT,\
+            mtime_ms INTEGER NOT NULL,\
+            runs_count INTEGER NOT NULL DEFAULT 0,\
+            last_run_at INTEGER\
         );\
         CREATE TABLE IF NOT EXISTS script_fields (\
             script_path TEXT NOT NULL,\
@@ -340,53 +788,121 @@ fn init_db(conn: &Connection) -> Result<(), String> {
             prompt TEXT,\
             kind TEXT,\
             required INTEGER NOT NULL,\
+            arg TEXT NOT NULL,\
             FOREIGN KEY(script_path) REFERENCES script_index(script_path) ON DELETE CASCADE\
         );\
-        CREATE INDEX IF NOT EXISTS idx_script_search ON script_index(search_blob);\
-        CREATE INDEX IF NOT EXISTS idx_script_fields ON script_fields(script_path);",
+        CREATE INDEX IF NOT EXISTS idx_script_fields ON script_fields(script_path);\
+        CREATE VIRTUAL TABLE IF NOT EXISTS script_search USING fts5(\
+            script_path,\
+            display_name,\
+            description,\
+            tags,\
+            fields,\
+            ownership,\
+            tokenize = 'unicode61 remove_diacritics 2',\
+            prefix = '2 3'\
+        );",
     )
     .map_err(|err| format!("Init search db failed: {}", err))
 }
 
-fn build_search_blob(
-    script_path: &str,
-    display_name: &str,
-    description: Option<&str>,
-    tags: &[String],
-    fields: &[SearchField],
-) -> String {
-    let mut parts = Vec::new();
-    parts.push(script_path.to_string());
-    parts.push(display_name.to_string());
-    if let Some(description) = description {
-        parts.push(description.to_string());
-    }
-    for tag in tags {
-        parts.push(tag.clone());
-    }
-    for field in fields {
-        parts.push(field.name.clone());
-        if let Some(prompt) = &field.prompt {
-            parts.push(prompt.clone());
+/// Sum of each token's best fuzzy score, or `None` if any token misses.
+fn match_score(tokens: &[String], result: &SearchResult, text: &str) -> Option<i64> {
+    let path = result.script_path.to_string_lossy();
+    tokens.iter().try_fold(0, |total, token| {
+        let best = [
+            fuzzy_score(token, &result.display_name).map(|score| score + NAME_BONUS),
+            fuzzy_score(token, &path).map(|score| score + PATH_BONUS),
+            fuzzy_score(token, text),
+        ]
+        .into_iter()
+        .flatten()
+        .max()?;
+        Some(total + best)
+    })
+}
+
+/// `"token"*` for each token, ANDed; quoting keeps FTS5 syntax characters literal.
+///
+/// `None` when a token has nothing FTS5 would index, leaving it to the fuzzy scan.
+fn fts_expression(tokens: &[String]) -> Option<String> {
+    let terms: Vec<String> = tokens
+        .iter()
+        .filter(|token| token.chars().any(char::is_alphanumeric))
+        .map(|token| format!("\"{}\"*", token.replace('"', "\"\"")))
+        .collect();
+    (terms.len() == tokens.len()).then(|| terms.join(" "))
+}
+
+/// A search query: free-text tokens plus `field:` and `arg:` filters.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Query {
+    tokens: Vec<String>,
+    filters: Vec<FieldFilter>,
+}
+
+/// Keeps scripts with a field whose name or flag starts with the value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FieldFilter {
+    Name(String),
+    /// Compared without leading dashes, so `arg:sub` matches `--subscription`.
+    Arg(String),
+}
+
+/// Lowercases and splits the query on whitespace. A filter with nothing after
+/// its colon, as while it is still being typed, is dropped.
+fn parse_query(query: &str) -> Query {
+    let mut parsed = Query::default();
+    for token in query.split_whitespace().map(str::to_lowercase) {
+        if let Some(name) = token.strip_prefix("field:") {
+            if !name.is_empty() {
+                parsed.filters.push(FieldFilter::Name(name.to_string()));
+            }
+        } else if let Some(flag) = token.strip_prefix("arg:") {
+            let flag = flag.trim_start_matches('-');
+            if !flag.is_empty() {
+                parsed.filters.push(FieldFilter::Arg(flag.to_string()));
+            }
+        } else {
+            parsed.tokens.push(token);
         }
-        parts.push(field.kind.clone());
     }
-    parts.join(" ").to_lowercase()
+    parsed
 }
 
-fn split_query(query: &str) -> Vec<String> {
-    query
-        .split_whitespace()
-        .filter(|token| !token.is_empty())
-        .map(|token| token.to_lowercase())
-        .collect()
+/// One `EXISTS` condition per filter, with its `LIKE` pattern bound to the
+/// parameters numbered from `first`.
+fn filter_conditions(filters: &[FieldFilter], first: usize) -> (Vec<String>, Vec<String>) {
+    filters
+        .iter()
+        .enumerate()
+        .map(|(index, filter)| {
+            let (column, value) = match filter {
+                FieldFilter::Name(name) => ("f.name", name),
+                FieldFilter::Arg(flag) => ("ltrim(f.arg, '-')", flag),
+            };
+            let condition = format!(
+                "EXISTS (SELECT 1 FROM script_fields f \
+                 WHERE f.script_path = i.script_path AND {} LIKE ?{} ESCAPE '\\')",
+                column,
+                first + index
+            );
+            (condition, like_prefix(value))
+        })
+        .unzip()
 }
 
-fn escape_like(input: &str) -> String {
-    input
-        .replace('\\', "\\\\")
-        .replace('%', "\\%")
-        .replace('_', "\\_")
+/// `LIKE` pattern matching values that start with `text`.
+fn like_prefix(text: &str) -> String {
+    let mut pattern = String::with_capacity(text.len() + 1);
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
 }
 
 fn parse_tags(tags_raw: Option<String>) -> Vec<String> {
@@ -416,3 +932,58 @@ fn timestamp_ms() -> i64 {
         .unwrap_or_default();
     duration.as_millis() as i64
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn details(name: &str) -> Option<SearchDetails> {
+        Some(SearchDetails {
+            display_name: name.to_string(),
+            description: None,
+            tags: Vec::new(),
+            owner: None,
+            contact: None,
+            fields: Vec::new(),
+            schema_error: None,
+        })
+    }
+
+    #[test]
+    fn test_parse_query_separates_field_filters() {
+        let query = parse_query("Rotate field:Sub_ID arg:--subscription arg:-- field: kv");
+        assert_eq!(query.tokens, vec!["rotate", "kv"]);
+        assert_eq!(
+            query.filters,
+            vec![
+                FieldFilter::Name("sub_id".to_string()),
+                FieldFilter::Arg("subscription".to_string()),
+            ]
+        );
+        assert_eq!(like_prefix("sub_id"), "sub\\_id%");
+        let (conditions, patterns) = filter_conditions(&query.filters, 2);
+        assert!(conditions[0].contains("f.name LIKE ?2"));
+        assert!(conditions[1].contains("ltrim(f.arg, '-') LIKE ?3"));
+        assert_eq!(patterns, vec!["sub\\_id%", "subscription%"]);
+    }
+
+    #[test]
+    fn test_details_cache_evicts_least_recent_and_resets_on_revision() {
+        let mut cache = DetailsCache::default();
+        for index in 0..DETAILS_CACHE_SIZE {
+            let path = PathBuf::from(format!("{}.bash", index));
+            cache.insert(0, &path, details(&index.to_string()));
+        }
+        let first = Path::new("0.bash");
+        assert!(cache.get(0, first).is_some());
+        cache.insert(0, Path::new("new.bash"), None);
+        assert!(cache.contains(0, first));
+        assert!(!cache.contains(0, Path::new("1.bash")));
+        assert!(matches!(cache.get(0, Path::new("new.bash")), Some(None)));
+
+        cache.insert(1, Path::new("stale.bash"), details("stale"));
+        assert!(!cache.contains(1, Path::new("stale.bash")));
+        assert!(cache.get(1, first).is_none());
+        assert!(!cache.contains(1, Path::new("new.bash")));
+    }
+}
@@ -0,0 +1,155 @@
+use crate::adapters::workspace_repository::FsWorkspaceRepository;
+use crate::ports::ScriptRepository;
+use crate::receipt::sha256_hex;
+use crate::runtime::source_file;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Digests of every script in a workspace, to tell later what changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub created_at: i64,
+    /// Script paths relative to the workspace root, with their digests.
+    pub scripts: BTreeMap<String, ScriptDigest>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptDigest {
+    /// SHA-256 of the script file; for a Makefile target, of the Makefile.
+    pub sha256: String,
+    /// SHA-256 of the text its schema is read from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_sha256: Option<String>,
+}
+
+/// A difference between a snapshot and the workspace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Drift {
+    Added(String),
+    Removed(String),
+    /// `schema` is set when the schema changed too.
+    Modified {
+        path: String,
+        schema: bool,
+    },
+}
+
+/// Hashes every script under `root`. Scripts that cannot be read are left
+/// out, so they show up as removed.
+pub fn take(root: &Path, created_at: i64) -> io::Result<Snapshot> {
+    let repo = FsWorkspaceRepository::new(root.to_path_buf());
+    let mut scripts = BTreeMap::new();
+    for script in repo.list_scripts_recursive()? {
+        let Ok(contents) = fs::read(source_file(&script)) else {
+            continue;
+        };
+        let relative = script.strip_prefix(root).unwrap_or(&script);
+        scripts.insert(
+            relative.to_string_lossy().replace('\\', "/"),
+            ScriptDigest {
+                sha256: sha256_hex(&contents),
+                schema_sha256: repo
+                    .schema_source(&script)
+                    .map(|source| sha256_hex(source.as_bytes())),
+            },
+        );
+    }
+    Ok(Snapshot {
+        created_at,
+        scripts,
+    })
+}
+
+pub fn read(path: &Path) -> Result<Snapshot, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+    serde_json::from_str(&contents)
+        .map_err(|err| format!("Failed to parse {}: {}", path.display(), err))
+}
+
+pub fn write(path: &Path, snapshot: &Snapshot) -> io::Result<()> {
+    let mut contents = serde_json::to_string_pretty(snapshot).map_err(io::Error::other)?;
+    contents.push('\n');
+    fs::write(path, contents)
+}
+
+impl Snapshot {
+    /// What changed from this snapshot to `current`, sorted by path.
+    pub fn drift(&self, current: &Snapshot) -> Vec<Drift> {
+        let mut drift = Vec::new();
+        for (path, digest) in &current.scripts {
+            match self.scripts.get(path) {
+                None => drift.push(Drift::Added(path.clone())),
+                Some(previous) if previous != digest => drift.push(Drift::Modified {
+                    path: path.clone(),
+                    schema: previous.schema_sha256 != digest.schema_sha256,
+                }),
+                Some(_) => {}
+            }
+        }
+        for path in self.scripts.keys() {
+            if !current.scripts.contains_key(path) {
+                drift.push(Drift::Removed(path.clone()));
+            }
+        }
+        drift.sort_by(|a, b| a.path().cmp(b.path()));
+        drift
+    }
+}
+
+impl Drift {
+    pub fn path(&self) -> &str {
+        match self {
+            Drift::Added(path) | Drift::Removed(path) | Drift::Modified { path, .. } => path,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_and_drift() {
+        let root = std::env::temp_dir().join(format!("omakure-snapshot-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("dns")).unwrap();
+        let schema = "# OMAKURE_SCHEMA_START\n# {\"Name\":\"update\"}\n# OMAKURE_SCHEMA_END\n";
+        fs::write(root.join("dns/update.bash"), format!("{}echo v1\n", schema)).unwrap();
+        fs::write(root.join("backup.sh"), "echo backup\n").unwrap();
+        fs::write(root.join("old.bash"), "echo old\n").unwrap();
+        let before = take(&root, 1).unwrap();
+        assert!(before.scripts["dns/update.bash"].schema_sha256.is_some());
+        assert_eq!(before.scripts["backup.sh"].schema_sha256, None);
+
+        fs::write(root.join("dns/update.bash"), format!("{}echo v2\n", schema)).unwrap();
+        fs::write(
+            root.join("backup.sh"),
+            format!("{}echo backup\n", schema.replace("update", "backup")),
+        )
+        .unwrap();
+        fs::remove_file(root.join("old.bash")).unwrap();
+        fs::write(root.join("new.py"), "print('new')\n").unwrap();
+        let after = take(&root, 2).unwrap();
+        assert_eq!(
+            before.drift(&after),
+            vec![
+                Drift::Modified {
+                    path: "backup.sh".to_string(),
+                    schema: true,
+                },
+                Drift::Modified {
+                    path: "dns/update.bash".to_string(),
+                    schema: false,
+                },
+                Drift::Added("new.py".to_string()),
+                Drift::Removed("old.bash".to_string()),
+            ]
+        );
+        assert!(after.drift(&after).is_empty());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,5 @@
+//! Status symbols shared by the CLI and the TUI, so outcomes never rely on color alone.
+
+pub const OK_SYMBOL: &str = "✓";
+pub const FAIL_SYMBOL: &str = "✗";
+pub const WARN_SYMBOL: &str = "!";
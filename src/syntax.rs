@@ -0,0 +1,193 @@
+use crate::runtime::ScriptKind;
+
+/// What a piece of a source line is, for coloring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Plain,
+    Keyword,
+    String,
+    Variable,
+    Comment,
+}
+
+const BASH_KEYWORDS: &[&str] = &[
+    "case", "do", "done", "elif", "else", "esac", "exit", "export", "fi", "for", "function", "if",
+    "in", "local", "readonly", "return", "set", "shift", "then", "until", "while",
+];
+const POWERSHELL_KEYWORDS: &[&str] = &[
+    "begin", "break", "catch", "continue", "default", "else", "elseif", "end", "exit", "finally",
+    "foreach", "function", "if", "in", "param", "process", "return", "switch", "throw", "try",
+    "while",
+];
+const PYTHON_KEYWORDS: &[&str] = &[
+    "and", "as", "assert", "break", "class", "continue", "def", "elif", "else", "except", "False",
+    "finally", "for", "from", "if", "import", "in", "is", "lambda", "None", "not", "or", "pass",
+    "raise", "return", "True", "try", "while", "with", "yield",
+];
+const JSON_KEYWORDS: &[&str] = &["false", "null", "true"];
+
+/// Splits one line of a script into colored pieces.
+///
+/// Each line is read on its own, so strings and comments spanning lines
+/// (heredocs, triple quotes, `<# #>`) only color their first line.
+pub fn highlight_line(line: &str, kind: ScriptKind) -> Vec<(TokenKind, &str)> {
+    let (keywords, comments, variables) = match kind {
+        ScriptKind::Bash => (BASH_KEYWORDS, true, true),
+        ScriptKind::PowerShell => (POWERSHELL_KEYWORDS, true, true),
+        ScriptKind::Python => (PYTHON_KEYWORDS, true, false),
+        ScriptKind::Workflow => (JSON_KEYWORDS, false, false),
+        ScriptKind::Executable | ScriptKind::Custom => return vec![(TokenKind::Plain, line)],
+        ScriptKind::Make | ScriptKind::Just => (&[] as &[&str], true, true),
+    };
+    let bytes = line.as_bytes();
+    let mut tokens: Vec<(TokenKind, &str)> = Vec::new();
+    let mut start = 0;
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        let after_space = index == 0 || bytes[index - 1].is_ascii_whitespace();
+        let (kind, end) = if comments && byte == b'#' && after_space {
+            (TokenKind::Comment, bytes.len())
+        } else if byte == b'"' || byte == b'\'' {
+            (TokenKind::String, string_end(bytes, index))
+        } else if variables && byte == b'$' && index + 1 < bytes.len() {
+            (TokenKind::Variable, variable_end(bytes, index))
+        } else if is_word(byte) && (index == 0 || !is_word(bytes[index - 1])) {
+            let end = (index..bytes.len())
+                .find(|&at| !is_word(bytes[at]))
+                .unwrap_or(bytes.len());
+            let word = &line[index..end];
+            let keyword = match kind {
+                ScriptKind::PowerShell => keywords
+                    .iter()
+                    .any(|keyword| keyword.eq_ignore_ascii_case(word)),
+                _ => keywords.contains(&word),
+            };
+            if keyword {
+                (TokenKind::Keyword, end)
+            } else {
+                index = end;
+                continue;
+            }
+        } else {
+            index += 1;
+            continue;
+        };
+        if start < index {
+            tokens.push((TokenKind::Plain, &line[start..index]));
+        }
+        tokens.push((kind, &line[index..end]));
+        start = end;
+        index = end;
+    }
+    if start < bytes.len() {
+        tokens.push((TokenKind::Plain, &line[start..]));
+    }
+    tokens
+}
+
+fn is_word(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+/// End of the string opened at `start`, past its closing quote; an unclosed
+/// string runs to the end of the line.
+fn string_end(bytes: &[u8], start: usize) -> usize {
+    let quote = bytes[start];
+    let mut index = start + 1;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' if quote == b'"' => index += 2,
+            byte if byte == quote => return index + 1,
+            _ => index += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// End of `$name`, `${...}`, or a special parameter such as `$1` or `$@`.
+fn variable_end(bytes: &[u8], start: usize) -> usize {
+    let next = start + 1;
+    if bytes[next] == b'{' {
+        return bytes[next..]
+            .iter()
+            .position(|&byte| byte == b'}')
+            .map(|offset| next + offset + 1)
+            .unwrap_or(bytes.len());
+    }
+    let end = (next..bytes.len())
+        .find(|&at| !(is_word(bytes[at]) || bytes[at] == b':'))
+        .unwrap_or(bytes.len());
+    if end > next {
+        end
+    } else if b"@*#?$!-".contains(&bytes[next]) {
+        next + 1
+    } else {
+        start + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_highlight_line() {
+        assert_eq!(
+            highlight_line(
+                "if [ \"$1\" = 'a#b' ]; then echo ${HOME}-x # note",
+                ScriptKind::Bash
+            ),
+            vec![
+                (TokenKind::Keyword, "if"),
+                (TokenKind::Plain, " [ "),
+                (TokenKind::String, "\"$1\""),
+                (TokenKind::Plain, " = "),
+                (TokenKind::String, "'a#b'"),
+                (TokenKind::Plain, " ]; "),
+                (TokenKind::Keyword, "then"),
+                (TokenKind::Plain, " echo "),
+                (TokenKind::Variable, "${HOME}"),
+                (TokenKind::Plain, "-x "),
+                (TokenKind::Comment, "# note"),
+            ]
+        );
+        assert_eq!(
+            highlight_line("echo ${#list[@]} $#", ScriptKind::Bash),
+            vec![
+                (TokenKind::Plain, "echo "),
+                (TokenKind::Variable, "${#list[@]}"),
+                (TokenKind::Plain, " "),
+                (TokenKind::Variable, "$#"),
+            ]
+        );
+        assert_eq!(
+            highlight_line("Param($Env:Name) # x", ScriptKind::PowerShell),
+            vec![
+                (TokenKind::Keyword, "Param"),
+                (TokenKind::Plain, "("),
+                (TokenKind::Variable, "$Env:Name"),
+                (TokenKind::Plain, ") "),
+                (TokenKind::Comment, "# x"),
+            ]
+        );
+        assert_eq!(
+            highlight_line("print(\"a\\\"b\", x_if)", ScriptKind::Python),
+            vec![
+                (TokenKind::Plain, "print("),
+                (TokenKind::String, "\"a\\\"b\""),
+                (TokenKind::Plain, ", x_if)"),
+            ]
+        );
+        assert_eq!(
+            highlight_line("  \"ref\": true,", ScriptKind::Workflow),
+            vec![
+                (TokenKind::Plain, "  "),
+                (TokenKind::String, "\"ref\""),
+                (TokenKind::Plain, ": "),
+                (TokenKind::Keyword, "true"),
+                (TokenKind::Plain, ","),
+            ]
+        );
+    }
+}
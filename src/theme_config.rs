@@ -1,4 +1,5 @@
 use crate::adapters::tui::theme::{theme_file_path, BUILTIN_THEMES};
+use crate::app_dirs::AppDirs;
 use serde::Deserialize;
 use std::error::Error;
 use std::fs;
@@ -21,25 +22,29 @@ struct ThemeConfig {
     name: Option<String>,
 }
 
+/// Where the global config and themes live, without creating anything.
+pub(crate) fn theme_layout() -> Option<ThemeLayout> {
+    let config_dir = AppDirs::resolve()?.config;
+    Some(ThemeLayout {
+        themes_dir: config_dir.join("themes"),
+        config_path: config_dir.join("config.toml"),
+        config_dir,
+    })
+}
+
 pub(crate) fn ensure_theme_layout() -> Result<ThemeLayout, Box<dyn Error>> {
-    let Some(config_dir) = config_dir() else {
+    let Some(layout) = theme_layout() else {
         return Err("Unable to resolve config directory".into());
     };
-    let themes_dir = config_dir.join("themes");
-    let config_path = config_dir.join("config.toml");
 
-    fs::create_dir_all(&themes_dir)?;
-    ensure_builtin_themes(&themes_dir)?;
+    fs::create_dir_all(&layout.themes_dir)?;
+    ensure_builtin_themes(&layout.themes_dir)?;
 
-    if !config_path.exists() {
-        write_global_theme(&config_path, "default")?;
+    if !layout.config_path.exists() {
+        write_global_theme(&layout.config_path, "default")?;
     }
 
-    Ok(ThemeLayout {
-        config_dir,
-        themes_dir,
-        config_path,
-    })
+    Ok(layout)
 }
 
 pub(crate) fn load_theme_name(path: &Path) -> Option<String> {
@@ -88,7 +93,3 @@ fn ensure_builtin_themes(themes_dir: &Path) -> Result<(), Box<dyn Error>> {
     }
     Ok(())
 }
-
-fn config_dir() -> Option<PathBuf> {
-    dirs::config_dir().map(|dir| dir.join("omakure"))
-}
@@ -0,0 +1,156 @@
+use crate::util::set_executable_permissions;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// Script the form step asks the user to run.
+pub const GREET_SCRIPT: &str = "greet.bash";
+
+const GREET: &str = r#"#!/usr/bin/env bash
+set -euo pipefail
+
+# OMAKURE_SCHEMA_START
+# {
+#   "Name": "greet",
+#   "Description": "Says hello. Try it with and without an active env file.",
+#   "Tags": ["tutorial"],
+#   "Fields": [
+#     { "Name": "name", "Prompt": "Who to greet", "Type": "string", "Order": 1, "Required": true, "Arg": "--name" },
+#     { "Name": "region", "Prompt": "Region", "Type": "string", "Order": 2, "Default": "local", "Arg": "--region" }
+#   ]
+# }
+# OMAKURE_SCHEMA_END
+
+NAME=""
+REGION="local"
+while [[ $# -gt 0 ]]; do
+  case "$1" in
+    --name) NAME="${2:-}"; shift 2 ;;
+    --region) REGION="${2:-}"; shift 2 ;;
+    *) echo "Unknown arg: $1" >&2; exit 1 ;;
+  esac
+done
+
+echo "Hello, ${NAME}, from ${REGION}!"
+"#;
+
+const DISK_USAGE: &str = r#"#!/usr/bin/env bash
+set -euo pipefail
+
+# OMAKURE_SCHEMA_START
+# {
+#   "Name": "disk-usage",
+#   "Description": "Shows free space where the workspace lives.",
+#   "Tags": ["tutorial"],
+#   "Fields": []
+# }
+# OMAKURE_SCHEMA_END
+
+df -h .
+"#;
+
+/// Files of the sandbox workspace, relative to its root.
+const SANDBOX_FILES: &[(&str, &str)] = &[
+    (GREET_SCRIPT, GREET),
+    ("ops/disk-usage.bash", DISK_USAGE),
+    (".omaken/envs/dev.conf", "REGION=eu-dev-1\n"),
+    (".omaken/envs/prod.conf", "REGION=eu-prod-1\n"),
+];
+
+/// A task of the tutorial, done once the TUI reaches a given state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    CreateScript,
+    FillForm,
+    ViewHistory,
+    ActivateEnv,
+}
+
+pub const STEPS: &[Step] = &[
+    Step::CreateScript,
+    Step::FillForm,
+    Step::ViewHistory,
+    Step::ActivateEnv,
+];
+
+impl Step {
+    pub fn title(self) -> &'static str {
+        match self {
+            Step::CreateScript => "Create a script",
+            Step::FillForm => "Fill a form",
+            Step::ViewHistory => "View history",
+            Step::ActivateEnv => "Activate an env file",
+        }
+    }
+
+    pub fn instructions(self) -> &'static [&'static str] {
+        match self {
+            Step::CreateScript => &[
+                "Press : to open the command line and run",
+                "  omakure init hello.bash",
+                "then Esc to close its output and r to refresh.",
+            ],
+            Step::FillForm => &[
+                "Select greet.bash and press Enter.",
+                "Type a name, move with Up/Down,",
+                "and press Enter on the last field to run.",
+            ],
+            Step::ViewHistory => &[
+                "Press Esc to leave the result,",
+                "then h to open the run history.",
+            ],
+            Step::ActivateEnv => &[
+                "Press Alt+E, pick dev.conf, and press Enter.",
+                "Its REGION now fills greet.bash's form.",
+            ],
+        }
+    }
+}
+
+/// Writes the sandbox workspace the tutorial runs against.
+pub fn create_sandbox(root: &Path) -> Result<(), Box<dyn Error>> {
+    for (relative, contents) in SANDBOX_FILES {
+        let path = root.join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, contents)?;
+        if path.extension().is_some_and(|ext| ext == "bash") {
+            set_executable_permissions(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether `root` holds a script the user added, i.e. one not seeded.
+pub fn has_new_script(root: &Path) -> bool {
+    let Ok(entries) = fs::read_dir(root) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let name = entry.file_name().to_string_lossy().to_string();
+        crate::runtime::script_kind(&entry.path()).is_some()
+            && !SANDBOX_FILES.iter().any(|(relative, _)| *relative == name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{extract_schema_block, parse_schema};
+
+    #[test]
+    fn test_sandbox_scripts_have_schemas() {
+        let root = std::env::temp_dir().join(format!("omakure-tutorial-{}", std::process::id()));
+        create_sandbox(&root).unwrap();
+        for relative in [GREET_SCRIPT, "ops/disk-usage.bash"] {
+            let contents = fs::read_to_string(root.join(relative)).unwrap();
+            let block = extract_schema_block(&contents, &["#"]).unwrap();
+            assert!(parse_schema(&block).is_ok(), "{}", relative);
+        }
+        assert!(!has_new_script(&root));
+        fs::write(root.join("hello.bash"), "").unwrap();
+        assert!(has_new_script(&root));
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -1,7 +1,7 @@
 use std::path::Path;
 
 use crate::error::AppResult;
-use crate::ports::{EnvFile, EnvPreview, EnvironmentConfig, EnvironmentRepository};
+use crate::ports::{EnvFile, EnvPreview, EnvVars, EnvironmentConfig, EnvironmentRepository};
 
 pub struct EnvironmentService {
     repo: Box<dyn EnvironmentRepository>,
@@ -27,4 +27,8 @@ impl EnvironmentService {
     pub fn load_env_preview(&self, path: &Path) -> AppResult<EnvPreview> {
         self.repo.load_env_preview(path)
     }
+
+    pub fn load_env_vars(&self, name: &str) -> AppResult<EnvVars> {
+        self.repo.load_env_vars(name)
+    }
 }
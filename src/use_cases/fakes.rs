@@ -0,0 +1,155 @@
+//! In-memory ports for the use case tests.
+
+use crate::domain::{parse_schema, Schema};
+use crate::error::{AppResult, EnvironmentError, SchemaError};
+use crate::ports::{
+    EnvFile, EnvPreview, EnvVars, EnvironmentConfig, EnvironmentRepository, RunOptions,
+    ScriptRepository, ScriptRunOutput, ScriptRunner, WorkspaceEntry,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Scripts by path, each with its schema JSON; `None` has no schema.
+#[derive(Default)]
+pub(super) struct FakeRepository {
+    pub(super) scripts: BTreeMap<PathBuf, Option<String>>,
+}
+
+impl FakeRepository {
+    pub(super) fn with(mut self, script: &str, schema: Option<&str>) -> Self {
+        self.scripts
+            .insert(PathBuf::from(script), schema.map(str::to_string));
+        self
+    }
+}
+
+impl ScriptRepository for FakeRepository {
+    fn list_entries(&self, _dir: &Path) -> io::Result<Vec<WorkspaceEntry>> {
+        Ok(Vec::new())
+    }
+
+    fn list_scripts_recursive(&self) -> io::Result<Vec<PathBuf>> {
+        Ok(self.scripts.keys().cloned().collect())
+    }
+
+    fn read_schema(&self, script: &Path) -> AppResult<Schema> {
+        match self.scripts.get(script) {
+            Some(Some(schema)) => Ok(parse_schema(schema)?),
+            _ => Err(SchemaError::BlockNotFound.into()),
+        }
+    }
+}
+
+/// A run the fake runner was asked for.
+#[derive(Debug, Clone)]
+pub(super) struct Run {
+    pub(super) script: PathBuf,
+    pub(super) args: Vec<String>,
+    pub(super) env: Vec<(String, String)>,
+    /// Whether the run could ask prompts, with its stdin kept open.
+    pub(super) prompts: bool,
+}
+
+/// Prints the stdout set for a script and exits with its code, 0 by
+/// default; `runs` records every call.
+#[derive(Default)]
+pub(super) struct FakeRunner {
+    pub(super) results: HashMap<PathBuf, (String, i32)>,
+    pub(super) runs: Arc<Mutex<Vec<Run>>>,
+}
+
+impl FakeRunner {
+    pub(super) fn with(mut self, script: &str, stdout: &str, exit_code: i32) -> Self {
+        self.results
+            .insert(PathBuf::from(script), (stdout.to_string(), exit_code));
+        self
+    }
+}
+
+impl ScriptRunner for FakeRunner {
+    fn run(
+        &self,
+        script: &Path,
+        args: &[String],
+        options: &RunOptions,
+    ) -> AppResult<ScriptRunOutput> {
+        self.runs.lock().unwrap().push(Run {
+            script: script.to_path_buf(),
+            args: args.to_vec(),
+            env: options.env.clone(),
+            prompts: options.prompts.is_some(),
+        });
+        let (stdout, exit_code) = self.results.get(script).cloned().unwrap_or_default();
+        Ok(output(stdout, exit_code))
+    }
+
+    fn run_shell(
+        &self,
+        line: &str,
+        _dir: &Path,
+        options: &RunOptions,
+    ) -> AppResult<ScriptRunOutput> {
+        self.run(Path::new(line), &[], options)
+    }
+}
+
+pub(super) fn output(stdout: String, exit_code: i32) -> ScriptRunOutput {
+    ScriptRunOutput {
+        stdout,
+        stderr: String::new(),
+        exit_code: Some(exit_code),
+        success: exit_code == 0,
+        usage: None,
+        duration_ms: 1,
+        log_dir: None,
+        retries: 0,
+        hooks: Vec::new(),
+        files: Vec::new(),
+    }
+}
+
+/// Env files by name; an `Err` file fails to read with that message.
+#[derive(Default)]
+pub(super) struct FakeEnvironments {
+    pub(super) active: Option<String>,
+    pub(super) files: BTreeMap<String, Result<EnvVars, String>>,
+}
+
+impl EnvironmentRepository for FakeEnvironments {
+    fn list_env_files(&self) -> AppResult<Vec<EnvFile>> {
+        Ok(self
+            .files
+            .keys()
+            .map(|name| EnvFile { name: name.clone() })
+            .collect())
+    }
+
+    fn load_environment_config(&self) -> AppResult<EnvironmentConfig> {
+        Ok(EnvironmentConfig {
+            envs_dir: PathBuf::from("envs"),
+            active: self.active.clone(),
+            defaults: HashMap::new(),
+        })
+    }
+
+    fn set_active_env(&self, _name: Option<&str>) -> AppResult<()> {
+        Ok(())
+    }
+
+    fn load_env_preview(&self, _path: &Path) -> AppResult<EnvPreview> {
+        Ok(Vec::new())
+    }
+
+    fn load_env_vars(&self, name: &str) -> AppResult<EnvVars> {
+        match self.files.get(name) {
+            Some(Ok(vars)) => Ok(vars.clone()),
+            Some(Err(err)) => Err(EnvironmentError::ReadFailed(err.clone()).into()),
+            None => Err(EnvironmentError::NotFound {
+                name: name.to_string(),
+            }
+            .into()),
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use crate::domain::Hooks;
+use crate::error::{AppError, AppResult};
+use crate::logging;
+use crate::ports::{HookRun, HookStage, RunOptions};
+use serde_json::json;
+use std::path::Path;
+
+use super::ScriptService;
+
+/// Path of the script a hook runs around.
+const SCRIPT_VAR: &str = "OMAKURE_SCRIPT";
+/// Exit code of the script, set for `After` hooks; empty when it has none.
+const EXIT_CODE_VAR: &str = "OMAKURE_EXIT_CODE";
+
+impl ScriptService {
+    /// Runs the workspace's `before` hook, then the script's. The first one
+    /// to fail stops the run with an error holding its output.
+    pub(super) fn run_before_hooks(
+        &self,
+        script: &Path,
+        options: &RunOptions,
+    ) -> AppResult<Vec<HookRun>> {
+        let mut runs = Vec::new();
+        for command in self.hook_commands(script, HookStage::Before) {
+            let run = self.run_hook(script, HookStage::Before, &command, options, Vec::new());
+            if !run.success {
+                return Err(AppError::General(hook_failure(&run)));
+            }
+            runs.push(run);
+        }
+        Ok(runs)
+    }
+
+    /// Runs the script's `after` hook, then the workspace's, whatever the
+    /// script's outcome. A failing one does not fail the run.
+    pub(super) fn run_after_hooks(
+        &self,
+        script: &Path,
+        options: &RunOptions,
+        exit_code: Option<i32>,
+    ) -> Vec<HookRun> {
+        let mut commands = self.hook_commands(script, HookStage::After);
+        commands.reverse();
+        let exit_code = exit_code.map(|code| code.to_string()).unwrap_or_default();
+        commands
+            .iter()
+            .map(|command| {
+                let env = vec![(EXIT_CODE_VAR.to_string(), exit_code.clone())];
+                self.run_hook(script, HookStage::After, command, options, env)
+            })
+            .collect()
+    }
+
+    /// Commands of `stage`, the workspace's before the script's.
+    fn hook_commands(&self, script: &Path, stage: HookStage) -> Vec<String> {
+        let schema = self
+            .repo
+            .read_schema(script)
+            .ok()
+            .and_then(|schema| schema.hooks);
+        [Some(&self.hooks), schema.as_ref()]
+            .into_iter()
+            .flatten()
+            .filter_map(|hooks: &Hooks| match stage {
+                HookStage::Before => hooks.before.clone(),
+                HookStage::After => hooks.after.clone(),
+            })
+            .filter(|command| !command.trim().is_empty())
+            .collect()
+    }
+
+    /// Runs `command` on this machine from the script's folder, with the
+    /// run's environment and `extra` on top.
+    fn run_hook(
+        &self,
+        script: &Path,
+        stage: HookStage,
+        command: &str,
+        options: &RunOptions,
+        extra: Vec<(String, String)>,
+    ) -> HookRun {
+        let mut hook_options = self.run_defaults.clone();
+        hook_options.env = options.env.clone();
+        hook_options.env.push((
+            SCRIPT_VAR.to_string(),
+            script.to_string_lossy().into_owned(),
+        ));
+        hook_options.env.extend(extra);
+        let dir = script.parent().unwrap_or(Path::new("."));
+        let run = match self.runner.run_shell(command, dir, &hook_options) {
+            Ok(output) => HookRun {
+                stage,
+                command: command.to_string(),
+                success: output.success,
+                exit_code: output.exit_code,
+                stdout: output.stdout,
+                stderr: output.stderr,
+            },
+            Err(err) => HookRun {
+                stage,
+                command: command.to_string(),
+                success: false,
+                exit_code: None,
+                stdout: String::new(),
+                stderr: err.to_string(),
+            },
+        };
+        let fields = json!({
+            "script": script,
+            "stage": stage.label(),
+            "command": command,
+            "exit_code": run.exit_code,
+        });
+        if run.success {
+            logging::info("hook", "Hook finished", fields);
+        } else {
+            logging::warn("hook", "Hook failed", fields);
+        }
+        run
+    }
+}
+
+/// Error of a run stopped by its `before` hook: the command, then its output.
+fn hook_failure(run: &HookRun) -> String {
+    let mut message = match run.exit_code {
+        Some(code) => format!(
+            "Before hook failed with exit code {}: {}",
+            code, run.command
+        ),
+        None => format!("Before hook failed: {}", run.command),
+    };
+    for stream in [&run.stdout, &run.stderr] {
+        if !stream.trim().is_empty() {
+            message.push('\n');
+            message.push_str(stream.trim_end());
+        }
+    }
+    message
+}
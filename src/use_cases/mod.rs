@@ -1,21 +1,104 @@
 mod environment;
+#[cfg(test)]
+mod fakes;
+mod hooks;
+mod pipeline;
 
-use crate::domain::Schema;
-use crate::error::AppResult;
-use crate::ports::{ScriptRepository, ScriptRunOutput, ScriptRunner, WorkspaceEntry};
+use crate::artifacts;
+use crate::domain::{missing_env, schema_env, Hooks, Schema};
+use crate::error::{AppError, AppResult};
+use crate::logging;
+use crate::ports::{
+    Backend, ContainerOptions, Prompts, RunOptions, ScriptRepository, ScriptRunOutput,
+    ScriptRunner, SshTarget, WorkspaceEntry,
+};
+use serde_json::json;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::thread;
 
 pub struct ScriptService {
     repo: Box<dyn ScriptRepository>,
     runner: Box<dyn ScriptRunner>,
+    run_defaults: RunOptions,
+    environment: Option<EnvironmentService>,
+    hooks: Hooks,
+    prompts: Option<Prompts>,
 }
 
 pub use environment::EnvironmentService;
+pub use pipeline::{PipelineExecutor, StageState, StageStatus};
 
 impl ScriptService {
     pub fn new(repo: Box<dyn ScriptRepository>, runner: Box<dyn ScriptRunner>) -> Self {
-        Self { repo, runner }
+        Self {
+            repo,
+            runner,
+            run_defaults: RunOptions::default(),
+            environment: None,
+            hooks: Hooks::default(),
+            prompts: None,
+        }
+    }
+
+    /// Sets the options used for runs whose schema does not override them.
+    pub fn with_run_defaults(mut self, defaults: RunOptions) -> Self {
+        self.run_defaults = defaults;
+        self
+    }
+
+    /// Passes the active env file of `environment` to script runs.
+    pub fn with_environment(mut self, environment: EnvironmentService) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    /// Runs the workspace `hooks` around every script, outside the script's own.
+    pub fn with_hooks(mut self, hooks: Hooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Lets scripts ask `prompts` questions mid-run with `OMAKURE_PROMPT`.
+    pub fn with_prompts(mut self, prompts: Prompts) -> Self {
+        self.prompts = Some(prompts);
+        self
+    }
+
+    /// Variables of the active env file for a run of `script`; none without
+    /// an environment or an active env file, or for a schema with
+    /// `"ActiveEnv": false`. An active env file that cannot be read, e.g. an
+    /// encrypted one without its key, is an error, so the script never runs
+    /// without the variables it was meant to get.
+    pub fn active_env(&self, script: &Path) -> AppResult<Vec<(String, String)>> {
+        let Some(environment) = &self.environment else {
+            return Ok(Vec::new());
+        };
+        if self
+            .repo
+            .read_schema(script)
+            .is_ok_and(|schema| schema.active_env == Some(false))
+        {
+            return Ok(Vec::new());
+        }
+        match environment.load_environment_config()?.active {
+            Some(name) => environment.load_env_vars(&name),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Variables in the `RequiresEnv` of `script` that a run with `args` and
+    /// `env` would not have.
+    pub fn missing_env(
+        &self,
+        script: &Path,
+        args: &[String],
+        env: &[(String, String)],
+    ) -> Vec<String> {
+        match self.repo.read_schema(script) {
+            Ok(schema) => missing_env(&schema, args, |name| is_set(env, name)),
+            Err(_) => Vec::new(),
+        }
     }
 
     pub fn list_entries(&self, dir: &Path) -> io::Result<Vec<WorkspaceEntry>> {
@@ -23,10 +106,370 @@ impl ScriptService {
     }
 
     pub fn load_schema(&self, script: &Path) -> AppResult<Schema> {
-        self.repo.read_schema(script)
+        let schema = self.repo.read_schema(script);
+        match &schema {
+            Ok(_) => logging::debug("schema", "Schema loaded", json!({ "script": script })),
+            Err(err) => logging::warn(
+                "schema",
+                "Schema load failed",
+                json!({ "script": script, "error": err.to_string() }),
+            ),
+        }
+        schema
+    }
+
+    /// Where `script` runs when not on this machine and no SSH host is
+    /// picked: on the Kubernetes backend, which the runner tries first, or in
+    /// the schema's container image. Dry runs show this instead of a local
+    /// command.
+    pub fn remote_backend(&self, script: &Path) -> Option<String> {
+        let schema = self.repo.read_schema(script).ok();
+        let backend = schema
+            .as_ref()
+            .and_then(|schema| schema.backend.as_deref())
+            .and_then(Backend::parse)
+            .unwrap_or(self.run_defaults.backend);
+        if backend == Backend::Kubernetes {
+            return Some("the Kubernetes backend".to_string());
+        }
+        schema
+            .and_then(|schema| schema.runtime)
+            .map(|runtime| format!("container image {}", runtime.image))
+    }
+
+    /// Runs `script` with the active env file, streaming its output logs
+    /// into `log_dir` when given. `overrides` come after the env file's
+    /// variables and replace any with the same name.
+    pub fn run_script(
+        &self,
+        script: &Path,
+        args: &[String],
+        log_dir: Option<&Path>,
+        overrides: &[(String, String)],
+    ) -> AppResult<ScriptRunOutput> {
+        let mut env = self.active_env(script)?;
+        env.extend(overrides.iter().cloned());
+        self.run_script_with_env(script, args, log_dir, &env, None)
+    }
+
+    /// Like `run_script`, with `env` added to the script's environment and,
+    /// given a `host`, run there over SSH.
+    ///
+    /// Variables from the schema's `Env` come after `env`, so a `FromField`
+    /// value wins over the same variable from an env file.
+    pub fn run_script_with_env(
+        &self,
+        script: &Path,
+        args: &[String],
+        log_dir: Option<&Path>,
+        env: &[(String, String)],
+        host: Option<&SshTarget>,
+    ) -> AppResult<ScriptRunOutput> {
+        logging::info(
+            "run",
+            "Run started",
+            json!({
+                "script": script,
+                "args": args.len(),
+                "host": host.map(|host| host.destination.clone()),
+            }),
+        );
+        let result = self.run_options(script, args, env).and_then(|mut options| {
+            options.log_dir = log_dir.map(Path::to_path_buf);
+            options.ssh = host.cloned();
+            let mut hooks = self.run_before_hooks(script, &options)?;
+            let result = self.run_attempts(script, args, &options);
+            let files = match (&result, log_dir, host) {
+                (Ok(_), Some(dir), None) => self.collect_files(script, dir),
+                _ => Vec::new(),
+            };
+            let exit_code = result.as_ref().ok().and_then(|output| output.exit_code);
+            hooks.extend(self.run_after_hooks(script, &options, exit_code));
+            result.map(|output| ScriptRunOutput {
+                hooks,
+                files,
+                ..output
+            })
+        });
+        log_run_result(&script.to_string_lossy(), &result);
+        result
+    }
+
+    /// Runs an ad-hoc shell command line from `dir` with the workspace run defaults.
+    pub fn run_shell(
+        &self,
+        line: &str,
+        dir: &Path,
+        log_dir: Option<&Path>,
+        env: &[(String, String)],
+    ) -> AppResult<ScriptRunOutput> {
+        let mut options = self.run_defaults.clone();
+        options.log_dir = log_dir.map(Path::to_path_buf);
+        options.env = env.to_vec();
+        logging::info(
+            "run",
+            "Shell command started",
+            json!({ "command": line, "dir": dir }),
+        );
+        let result = self.runner.run_shell(line, dir, &options);
+        // Labelled like its history entry.
+        log_run_result(&format!(":{}", line), &result);
+        result
+    }
+
+    /// Runs `script` until it succeeds or the schema's `Retry` gives up, and
+    /// returns the last attempt. Each attempt logs into the same folder, so
+    /// the logs kept are the last attempt's.
+    fn run_attempts(
+        &self,
+        script: &Path,
+        args: &[String],
+        options: &RunOptions,
+    ) -> AppResult<ScriptRunOutput> {
+        let retry = self
+            .repo
+            .read_schema(script)
+            .ok()
+            .and_then(|schema| schema.retry);
+        let mut attempt = 1;
+        loop {
+            let mut output = self.classify(script, self.runner.run(script, args, options)?);
+            match &retry {
+                Some(retry) if !output.success && retry.should_retry(attempt, output.exit_code) => {
+                    logging::warn(
+                        "run",
+                        "Run attempt failed, retrying",
+                        json!({
+                            "script": script,
+                            "attempt": attempt,
+                            "attempts": retry.attempts,
+                            "exit_code": output.exit_code,
+                            "delay_seconds": retry.delay().as_secs(),
+                        }),
+                    );
+                    thread::sleep(retry.delay());
+                    attempt += 1;
+                }
+                _ => {
+                    output.retries = attempt - 1;
+                    return Ok(output);
+                }
+            }
+        }
+    }
+
+    /// Copies the files of the schema's `file` outputs into `dir`. Runs on
+    /// another host leave their files there; see `RemoteArtifacts`.
+    fn collect_files(&self, script: &Path, dir: &Path) -> Vec<PathBuf> {
+        let outputs = self
+            .repo
+            .read_schema(script)
+            .ok()
+            .and_then(|schema| schema.outputs)
+            .unwrap_or_default();
+        let base = script.parent().unwrap_or(Path::new("."));
+        artifacts::collect(&outputs, base, dir)
     }
 
-    pub fn run_script(&self, script: &Path, args: &[String]) -> AppResult<ScriptRunOutput> {
-        self.runner.run(script, args)
+    /// Applies the schema's `SuccessCodes` and `FailRegex` to `output`.
+    fn classify(&self, script: &Path, mut output: ScriptRunOutput) -> ScriptRunOutput {
+        if let Ok(schema) = self.repo.read_schema(script) {
+            if schema.success_codes.is_some() || schema.fail_regex.is_some() {
+                output.success =
+                    schema.run_succeeded(output.exit_code, &output.stdout, &output.stderr);
+            }
+        }
+        output
+    }
+
+    fn run_options(
+        &self,
+        script: &Path,
+        args: &[String],
+        env: &[(String, String)],
+    ) -> AppResult<RunOptions> {
+        let mut options = self.run_defaults.clone();
+        options.env = env.to_vec();
+        let Ok(schema) = self.repo.read_schema(script) else {
+            return Ok(options);
+        };
+        if schema.prompts == Some(true) {
+            options.prompts = self.prompts.clone();
+        }
+        let missing = missing_env(&schema, args, |name| is_set(env, name));
+        if !missing.is_empty() {
+            return Err(AppError::General(format!(
+                "Missing environment variables required by the script: {}. Set them in an env file (omakure envs use NAME) or the shell.",
+                missing.join(", ")
+            )));
+        }
+        options
+            .env
+            .extend(schema_env(&schema, args, |name| is_set(env, name)));
+        if let Some(limits) = schema.limits {
+            options.limits = limits.or(&options.limits);
+        }
+        options.remote_artifacts = schema.remote_artifacts.unwrap_or_default();
+        options.requires = schema.requires.unwrap_or_default();
+        options.container = schema.runtime.map(|runtime| ContainerOptions {
+            image: runtime.image,
+            engine: runtime.engine,
+        });
+        if let Some(backend) = schema.backend.as_deref() {
+            options.backend = Backend::parse(backend).ok_or_else(|| {
+                AppError::General(format!(
+                    "Unknown Backend '{}': use 'local' or 'kubernetes'",
+                    backend
+                ))
+            })?;
+        }
+        Ok(options)
+    }
+}
+
+fn log_run_result(script: &str, result: &AppResult<ScriptRunOutput>) {
+    match result {
+        Ok(output) => {
+            let fields = json!({
+                "script": script,
+                "exit_code": output.exit_code,
+                "duration_ms": output.duration_ms,
+                "log_dir": output.log_dir,
+                "retries": output.retries,
+            });
+            if output.success {
+                logging::info("run", "Run finished", fields);
+            } else {
+                logging::warn("run", "Run failed", fields);
+            }
+        }
+        Err(err) => logging::error(
+            "run",
+            "Run could not start",
+            json!({ "script": script, "error": err.to_string() }),
+        ),
+    }
+}
+
+/// Whether `name` has a value in `env` or in this process's environment.
+fn is_set(env: &[(String, String)], name: &str) -> bool {
+    env.iter().any(|(key, _)| key == name) || std::env::var_os(name).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fakes::{FakeEnvironments, FakeRepository, FakeRunner};
+    use super::*;
+    use crate::domain::ScriptPrompt;
+    use crate::ports::PromptHandler;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_active_env_fails_the_run_when_unreadable() {
+        let service = |active: &str| {
+            let environments = FakeEnvironments {
+                active: Some(active.to_string()),
+                files: BTreeMap::from([
+                    (
+                        "dev".to_string(),
+                        Ok(vec![("REGION".to_string(), "eu".to_string())]),
+                    ),
+                    (
+                        "prod".to_string(),
+                        Err("encrypted, but env.key is missing".to_string()),
+                    ),
+                ]),
+            };
+            let runner = FakeRunner::default().with("a.bash", "ok", 0);
+            let runs = runner.runs.clone();
+            let service = ScriptService::new(
+                Box::new(FakeRepository::default().with("a.bash", None)),
+                Box::new(runner),
+            )
+            .with_environment(EnvironmentService::new(Box::new(environments)));
+            (service, runs)
+        };
+        let script = Path::new("a.bash");
+
+        let (dev, runs) = service("dev");
+        assert_eq!(
+            dev.active_env(script).unwrap(),
+            vec![("REGION".to_string(), "eu".to_string())]
+        );
+        let output = dev
+            .run_script(script, &["--x".to_string()], None, &[])
+            .unwrap();
+        assert_eq!(output.stdout, "ok");
+        let run = runs.lock().unwrap()[0].clone();
+        assert_eq!(
+            (run.script.as_path(), run.args.as_slice()),
+            (script, &["--x".to_string()][..])
+        );
+        assert!(run.env.contains(&("REGION".to_string(), "eu".to_string())));
+
+        let (prod, runs) = service("prod");
+        let err = prod.run_script(script, &[], None, &[]).unwrap_err();
+        assert!(err.to_string().contains("env.key is missing"));
+        assert!(runs.lock().unwrap().is_empty());
+
+        let (missing, _) = service("gone");
+        assert!(missing.active_env(script).is_err());
+    }
+
+    struct NoAnswers;
+
+    impl PromptHandler for NoAnswers {
+        fn ask(&self, _prompt: &ScriptPrompt) -> Option<String> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_prompts_only_reach_scripts_that_opt_in() {
+        let runner = FakeRunner::default();
+        let runs = runner.runs.clone();
+        let repo = FakeRepository::default()
+            .with(
+                "ask.bash",
+                Some(r#"{"Name":"ask","Prompts":true,"Fields":[]}"#),
+            )
+            .with("quiet.bash", Some(r#"{"Name":"quiet","Fields":[]}"#))
+            .with("plain.bash", None);
+        let service = ScriptService::new(Box::new(repo), Box::new(runner))
+            .with_prompts(Prompts(std::sync::Arc::new(NoAnswers)));
+
+        for script in ["ask.bash", "quiet.bash", "plain.bash"] {
+            service
+                .run_script(Path::new(script), &[], None, &[])
+                .unwrap();
+        }
+        let prompts: Vec<bool> = runs.lock().unwrap().iter().map(|run| run.prompts).collect();
+        assert_eq!(prompts, vec![true, false, false]);
+    }
+
+    #[test]
+    fn test_remote_backend_follows_runner_order() {
+        let repo = FakeRepository::default()
+            .with(
+                "both.bash",
+                Some(r#"{"Name":"both","Backend":"kubernetes","Runtime":{"Image":"alpine"},"Fields":[]}"#),
+            )
+            .with(
+                "boxed.bash",
+                Some(r#"{"Name":"boxed","Runtime":{"Image":"alpine"},"Fields":[]}"#),
+            )
+            .with("plain.bash", None);
+        let service = ScriptService::new(Box::new(repo), Box::new(FakeRunner::default()));
+        let remote = |script: &str| service.remote_backend(Path::new(script));
+
+        assert_eq!(
+            remote("both.bash").as_deref(),
+            Some("the Kubernetes backend")
+        );
+        assert_eq!(
+            remote("boxed.bash").as_deref(),
+            Some("container image alpine")
+        );
+        assert_eq!(remote("plain.bash"), None);
     }
 }
@@ -0,0 +1,524 @@
+use crate::domain::{
+    build_args, expand_references, parse_outputs, PathStyle, Pipeline, Stage, StageOutputs,
+};
+use crate::error::{AppError, AppResult};
+use crate::ports::ScriptRunOutput;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::ScriptService;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StageStatus {
+    Pending,
+    Running,
+    Passed,
+    Failed,
+    /// Not run because an earlier stage failed.
+    Skipped,
+}
+
+impl StageStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            StageStatus::Pending => "pending",
+            StageStatus::Running => "running",
+            StageStatus::Passed => "passed",
+            StageStatus::Failed => "failed",
+            StageStatus::Skipped => "skipped",
+        }
+    }
+}
+
+/// Where one stage of a pipeline run stands.
+#[derive(Debug, Clone)]
+pub struct StageState {
+    pub id: String,
+    pub script: PathBuf,
+    pub status: StageStatus,
+    /// Arguments the stage was started with.
+    pub args: Vec<String>,
+    pub outputs: BTreeMap<String, String>,
+    pub duration_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Runs the stages of a pipeline in order. A field a stage does not set
+/// takes the output of the same name from the latest earlier stage that
+/// has one, then its `Default`.
+///
+/// Callers run each stage themselves between `start_stage` and
+/// `finish_stage`, so they can show progress and record history as the
+/// pipeline goes.
+pub struct PipelineExecutor<'a> {
+    service: &'a ScriptService,
+    path_style: fn(&Path) -> PathStyle,
+    pipeline: Pipeline,
+    stages: Vec<StageState>,
+    outputs: StageOutputs,
+}
+
+impl<'a> PipelineExecutor<'a> {
+    /// Finds the script of every stage under `root` before anything runs.
+    pub fn new(
+        service: &'a ScriptService,
+        root: &Path,
+        pipeline: Pipeline,
+        path_style: fn(&Path) -> PathStyle,
+    ) -> AppResult<Self> {
+        let stages = pipeline
+            .stages
+            .iter()
+            .map(|stage| {
+                Ok(StageState {
+                    id: stage.id(),
+                    script: service.find_script(root, &stage.script)?,
+                    status: StageStatus::Pending,
+                    args: Vec::new(),
+                    outputs: BTreeMap::new(),
+                    duration_ms: None,
+                    error: None,
+                })
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+        Ok(Self {
+            service,
+            path_style,
+            pipeline,
+            stages,
+            outputs: StageOutputs::new(),
+        })
+    }
+
+    pub fn pipeline(&self) -> &Pipeline {
+        &self.pipeline
+    }
+
+    pub fn stages(&self) -> &[StageState] {
+        &self.stages
+    }
+
+    /// The first stage still to run; none once all ran or one failed.
+    pub fn next_stage(&self) -> Option<usize> {
+        if self.failed() {
+            return None;
+        }
+        self.stages
+            .iter()
+            .position(|stage| stage.status == StageStatus::Pending)
+    }
+
+    pub fn failed(&self) -> bool {
+        self.stages
+            .iter()
+            .any(|stage| stage.status == StageStatus::Failed)
+    }
+
+    /// Builds the arguments of stage `index` and marks it running. When the
+    /// fields do not validate, the stage fails without running.
+    pub fn start_stage(&mut self, index: usize) -> Result<Vec<String>, String> {
+        match self.stage_args(index) {
+            Ok(args) => {
+                let state = &mut self.stages[index];
+                state.status = StageStatus::Running;
+                state.args = args.clone();
+                Ok(args)
+            }
+            Err(err) => {
+                self.fail(index, err.clone());
+                Err(err)
+            }
+        }
+    }
+
+    /// Records how stage `index` went; its outputs become available to the
+    /// stages after it.
+    pub fn finish_stage(&mut self, index: usize, result: &AppResult<ScriptRunOutput>) {
+        match result {
+            Ok(output) => {
+                let outputs = parse_outputs(&output.stdout);
+                let state = &mut self.stages[index];
+                state.duration_ms = Some(output.duration_ms);
+                state.outputs = outputs.clone();
+                self.outputs.insert(state.id.clone(), outputs);
+                if output.success {
+                    state.status = StageStatus::Passed;
+                } else {
+                    let error = match output.exit_code {
+                        Some(code) => format!("Exited with code {}", code),
+                        None => "Terminated by a signal".to_string(),
+                    };
+                    self.fail(index, error);
+                }
+            }
+            Err(err) => self.fail(index, err.to_string()),
+        }
+    }
+
+    fn fail(&mut self, index: usize, error: String) {
+        self.stages[index].status = StageStatus::Failed;
+        self.stages[index].error = Some(error);
+        for stage in &mut self.stages[index + 1..] {
+            stage.status = StageStatus::Skipped;
+        }
+    }
+
+    fn stage_args(&self, index: usize) -> Result<Vec<String>, String> {
+        let stage = &self.pipeline.stages[index];
+        let script = &self.stages[index].script;
+        let mut fields = match self.service.load_schema(script) {
+            Ok(schema) => schema.fields,
+            Err(_) if stage.fields.is_empty() => return Ok(Vec::new()),
+            Err(err) => return Err(err.to_string()),
+        };
+        fields.sort_by_key(|field| field.order);
+        if let Some(name) = stage.fields.keys().find(|name| {
+            !fields
+                .iter()
+                .any(|field| field.name.eq_ignore_ascii_case(name))
+        }) {
+            return Err(format!("The script has no field {}", name));
+        }
+        let inputs = fields
+            .iter()
+            .map(|field| match stage_value(stage, &field.name) {
+                Some(value) => expand_references(value, &self.outputs),
+                None => Ok(self.earlier_output(index, &field.name).unwrap_or_default()),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        build_args(&fields, &inputs, (self.path_style)(script)).map_err(|err| err.to_string())
+    }
+
+    /// Output `name` of the latest stage before `index` that produced it.
+    fn earlier_output(&self, index: usize, name: &str) -> Option<String> {
+        self.stages[..index].iter().rev().find_map(|stage| {
+            stage
+                .outputs
+                .iter()
+                .find(|(output, _)| output.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.clone())
+        })
+    }
+}
+
+fn stage_value<'s>(stage: &'s Stage, field: &str) -> Option<&'s str> {
+    stage
+        .fields
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(field))
+        .map(|(_, value)| value.as_str())
+}
+
+impl ScriptService {
+    /// The script under `root` named `name`: its workspace-relative path,
+    /// with or without the extension.
+    pub fn find_script(&self, root: &Path, name: &str) -> AppResult<PathBuf> {
+        let name = name.replace('\\', "/");
+        let name = name.trim_matches('/');
+        let scripts = self.repo.list_scripts_recursive()?;
+        let relative = |script: &PathBuf| {
+            script
+                .strip_prefix(root)
+                .map(|path| path.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_default()
+        };
+        scripts
+            .iter()
+            .find(|script| relative(script) == name)
+            .or_else(|| {
+                scripts.iter().find(|script| {
+                    relative(script)
+                        .strip_prefix(name)
+                        .is_some_and(|rest| rest.starts_with('.') && !rest[1..].contains('/'))
+                })
+            })
+            .cloned()
+            .ok_or_else(|| AppError::General(format!("Script not found: {}", name)))
+    }
+
+    /// Scripts whose schema has a `DependsOn`, in path order.
+    pub fn scripts_with_dependencies(&self) -> AppResult<Vec<PathBuf>> {
+        let mut scripts = self.repo.list_scripts_recursive()?;
+        scripts.sort();
+        scripts.retain(|script| {
+            self.load_schema(script)
+                .is_ok_and(|schema| schema.depends_on.is_some_and(|names| !names.is_empty()))
+        });
+        Ok(scripts)
+    }
+
+    /// A pipeline running the scripts `script` `DependsOn`, theirs first,
+    /// then `script` itself. Each script runs once, however many depend on it.
+    pub fn dependency_pipeline(&self, root: &Path, script: &Path) -> AppResult<Pipeline> {
+        let mut order = Vec::new();
+        self.visit_dependencies(root, script, &mut Vec::new(), &mut order)?;
+        let name = relative_name(root, script);
+        Ok(Pipeline {
+            name,
+            description: self
+                .load_schema(script)
+                .ok()
+                .and_then(|schema| schema.description),
+            stages: order
+                .iter()
+                .map(|script| Stage::new(relative_name(root, script)))
+                .collect(),
+        })
+    }
+
+    fn visit_dependencies(
+        &self,
+        root: &Path,
+        script: &Path,
+        path: &mut Vec<PathBuf>,
+        order: &mut Vec<PathBuf>,
+    ) -> AppResult<()> {
+        if order.iter().any(|done| done == script) {
+            return Ok(());
+        }
+        if path.iter().any(|open| open == script) {
+            let cycle: Vec<String> = path
+                .iter()
+                .chain(std::iter::once(&script.to_path_buf()))
+                .map(|script| relative_name(root, script))
+                .collect();
+            return Err(AppError::General(format!(
+                "DependsOn cycle: {}",
+                cycle.join(" -> ")
+            )));
+        }
+        let depends_on = self
+            .load_schema(script)
+            .ok()
+            .and_then(|schema| schema.depends_on)
+            .unwrap_or_default();
+        path.push(script.to_path_buf());
+        for name in depends_on {
+            let dependency = self.find_script(root, &name)?;
+            self.visit_dependencies(root, &dependency, path, order)?;
+        }
+        path.pop();
+        order.push(script.to_path_buf());
+        Ok(())
+    }
+}
+
+fn relative_name(root: &Path, script: &Path) -> String {
+    script
+        .strip_prefix(root)
+        .unwrap_or(script)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::fakes::{FakeRepository, FakeRunner, Run};
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    const ROOT: &str = "/ws";
+
+    fn schema(fields: &[&str], depends_on: &[&str]) -> String {
+        let fields: Vec<String> = fields
+            .iter()
+            .enumerate()
+            .map(|(order, name)| {
+                format!(
+                    r#"{{"Name":"{}","Type":"string","Order":{}}}"#,
+                    name,
+                    order + 1
+                )
+            })
+            .collect();
+        let depends_on: Vec<String> = depends_on
+            .iter()
+            .map(|name| format!("\"{}\"", name))
+            .collect();
+        format!(
+            r#"{{"Name":"s","Fields":[{}],"DependsOn":[{}]}}"#,
+            fields.join(","),
+            depends_on.join(",")
+        )
+    }
+
+    fn service(repo: FakeRepository, runner: FakeRunner) -> (ScriptService, Arc<Mutex<Vec<Run>>>) {
+        let runs = runner.runs.clone();
+        (ScriptService::new(Box::new(repo), Box::new(runner)), runs)
+    }
+
+    fn pipeline(stages: Vec<Stage>) -> Pipeline {
+        Pipeline {
+            name: "release".to_string(),
+            description: None,
+            stages,
+        }
+    }
+
+    fn stage(script: &str, fields: &[(&str, &str)]) -> Stage {
+        Stage {
+            fields: fields
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            ..Stage::new(script.to_string())
+        }
+    }
+
+    /// Runs every stage the way the CLI and TUI do.
+    fn run_all(service: &ScriptService, executor: &mut PipelineExecutor) {
+        while let Some(index) = executor.next_stage() {
+            let Ok(args) = executor.start_stage(index) else {
+                continue;
+            };
+            let script = executor.stages()[index].script.clone();
+            let result = service.run_script(&script, &args, None, &[]);
+            executor.finish_stage(index, &result);
+        }
+    }
+
+    fn statuses(executor: &PipelineExecutor) -> Vec<StageStatus> {
+        executor.stages().iter().map(|stage| stage.status).collect()
+    }
+
+    #[test]
+    fn test_pipeline_passes_outputs_to_later_stages() {
+        let repo = FakeRepository::default()
+            .with("/ws/create.bash", Some(&schema(&[], &[])))
+            .with(
+                "/ws/deploy.bash",
+                Some(&schema(&["group", "name", "region"], &[])),
+            );
+        let runner = FakeRunner::default().with(
+            "/ws/create.bash",
+            "OMAKURE_OUTPUT group=rg-1\nOMAKURE_OUTPUT region=eu\n",
+            0,
+        );
+        let (service, runs) = service(repo, runner);
+        let stages = vec![
+            stage("create", &[]),
+            stage("deploy", &[("name", "${create.group}-app")]),
+        ];
+        let mut executor =
+            PipelineExecutor::new(&service, Path::new(ROOT), pipeline(stages), |_| {
+                PathStyle::Unix
+            })
+            .unwrap();
+
+        run_all(&service, &mut executor);
+
+        assert_eq!(
+            statuses(&executor),
+            vec![StageStatus::Passed, StageStatus::Passed]
+        );
+        let runs = runs.lock().unwrap();
+        assert_eq!(runs.len(), 2);
+        assert!(runs[0].args.is_empty());
+        assert_eq!(
+            runs[1].args,
+            vec!["--group", "rg-1", "--name", "rg-1-app", "--region", "eu"]
+        );
+        assert_eq!(executor.stages()[0].outputs["group"], "rg-1");
+    }
+
+    #[test]
+    fn test_pipeline_skips_stages_after_a_failure() {
+        let repo = FakeRepository::default()
+            .with("/ws/a.bash", Some(&schema(&[], &[])))
+            .with("/ws/b.bash", Some(&schema(&[], &[])))
+            .with("/ws/c.bash", Some(&schema(&[], &[])));
+        let runner = FakeRunner::default().with("/ws/b.bash", "", 3);
+        let (service, runs) = service(repo, runner);
+        let stages = vec![stage("a", &[]), stage("b", &[]), stage("c", &[])];
+        let mut executor =
+            PipelineExecutor::new(&service, Path::new(ROOT), pipeline(stages), |_| {
+                PathStyle::Unix
+            })
+            .unwrap();
+
+        run_all(&service, &mut executor);
+
+        assert_eq!(
+            statuses(&executor),
+            vec![
+                StageStatus::Passed,
+                StageStatus::Failed,
+                StageStatus::Skipped
+            ]
+        );
+        assert!(executor.failed());
+        assert_eq!(
+            executor.stages()[1].error.as_deref(),
+            Some("Exited with code 3")
+        );
+        assert_eq!(runs.lock().unwrap().len(), 2);
+
+        // A field the script does not have fails the stage before it runs.
+        let stages = vec![stage("a", &[("missing", "x")]), stage("b", &[])];
+        let mut executor =
+            PipelineExecutor::new(&service, Path::new(ROOT), pipeline(stages), |_| {
+                PathStyle::Unix
+            })
+            .unwrap();
+        run_all(&service, &mut executor);
+        assert_eq!(
+            statuses(&executor),
+            vec![StageStatus::Failed, StageStatus::Skipped]
+        );
+        assert_eq!(runs.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_find_script() {
+        let repo = FakeRepository::default()
+            .with("/ws/azure/create-rg.bash", None)
+            .with("/ws/azure/create-rg-old.py", None)
+            .with("/ws/deploy", None);
+        let (service, _) = service(repo, FakeRunner::default());
+        let root = Path::new(ROOT);
+        let find = |name: &str| service.find_script(root, name).ok();
+
+        assert_eq!(
+            find("azure/create-rg"),
+            Some(PathBuf::from("/ws/azure/create-rg.bash"))
+        );
+        assert_eq!(
+            find("azure\\create-rg.bash"),
+            Some(PathBuf::from("/ws/azure/create-rg.bash"))
+        );
+        assert_eq!(find("/deploy/"), Some(PathBuf::from("/ws/deploy")));
+        assert_eq!(find("azure"), None);
+        assert_eq!(find("azure/create"), None);
+    }
+
+    #[test]
+    fn test_dependency_pipeline() {
+        let repo = FakeRepository::default()
+            .with("/ws/app.bash", Some(&schema(&[], &["net", "db"])))
+            .with("/ws/db.bash", Some(&schema(&[], &["net"])))
+            .with("/ws/net.bash", Some(&schema(&[], &[])))
+            .with("/ws/x.bash", Some(&schema(&[], &["y"])))
+            .with("/ws/y.bash", Some(&schema(&[], &["x"])));
+        let (service, _) = service(repo, FakeRunner::default());
+        let root = Path::new(ROOT);
+
+        let pipeline = service
+            .dependency_pipeline(root, Path::new("/ws/app.bash"))
+            .unwrap();
+        assert_eq!(pipeline.name, "app.bash");
+        let scripts: Vec<&str> = pipeline
+            .stages
+            .iter()
+            .map(|stage| stage.script.as_str())
+            .collect();
+        assert_eq!(scripts, vec!["net.bash", "db.bash", "app.bash"]);
+
+        let err = service
+            .dependency_pipeline(root, Path::new("/ws/x.bash"))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "DependsOn cycle: x.bash -> y.bash -> x.bash"
+        );
+    }
+}
@@ -18,11 +18,106 @@ pub fn set_executable_permissions(_path: &Path) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// Whether `path` is a file with an execute bit set (always false on Windows,
+/// where executables are told apart by extension).
+#[cfg(not(windows))]
+pub fn is_executable_file(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(windows)]
+pub fn is_executable_file(_path: &Path) -> bool {
+    false
+}
+
 /// Quote a string for use in PowerShell commands.
 pub fn ps_quote(input: &str) -> String {
     format!("'{}'", input.replace('\'', "''"))
 }
 
+/// Quotes `value` for a bash double-quoted string.
+pub fn bash_quote(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for ch in value.chars() {
+        if matches!(ch, '"' | '\\' | '$' | '`') {
+            quoted.push('\\');
+        }
+        quoted.push(ch);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Join command parts into one line, single-quoting parts that need it.
+pub fn shell_join(parts: &[String]) -> String {
+    parts
+        .iter()
+        .map(|part| {
+            let plain = !part.is_empty()
+                && part
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || "-_./:=@%+,".contains(ch));
+            if plain {
+                part.clone()
+            } else {
+                format!("'{}'", part.replace('\'', "'\\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Standard base64 with padding.
+pub fn base64_encode(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let value = (u32::from(bytes[0]) << 16) | (u32::from(bytes[1]) << 8) | u32::from(bytes[2]);
+        for index in 0..4 {
+            if index <= chunk.len() {
+                out.push(ALPHABET[((value >> (18 - index * 6)) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Copy text to the system clipboard through the terminal (OSC 52).
+pub fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    use std::io::Write;
+
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64_encode(text.as_bytes()))?;
+    stdout.flush()
+}
+
+/// Open a file or folder with the platform's default handler.
+pub fn open_path(path: &Path) -> io::Result<()> {
+    let program = if cfg!(windows) {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    std::process::Command::new(program)
+        .arg(path)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
 /// Read a directory, returning an empty list if missing.
 pub fn read_dir_or_empty(dir: &Path) -> io::Result<Vec<fs::DirEntry>> {
     match fs::read_dir(dir) {
@@ -41,6 +136,70 @@ pub fn read_file_if_exists(path: &Path) -> io::Result<Option<String>> {
     }
 }
 
+/// Create `path` holding `contents`, readable only by the user on Unix. The
+/// mode is set as the file is created, and an existing file is an error.
+pub fn write_private(path: &Path, contents: &[u8]) -> io::Result<()> {
+    use std::io::Write;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(not(windows))]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents)
+}
+
+/// Scores `pattern` as a case-insensitive subsequence of `text`, fzf style.
+///
+/// Returns `None` when a pattern character is missing. Consecutive matches and
+/// matches at word starts score higher; gaps between matches cost a little.
+pub fn fuzzy_score(pattern: &str, text: &str) -> Option<i64> {
+    let pattern: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+    if pattern.is_empty() {
+        return Some(0);
+    }
+    let text: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = text
+        .iter()
+        .map(|ch| ch.to_lowercase().next().unwrap_or(*ch))
+        .collect();
+    (0..lower.len())
+        .filter(|start| lower[*start] == pattern[0])
+        .filter_map(|start| fuzzy_score_from(&pattern, &text, &lower, start))
+        .max()
+}
+
+fn fuzzy_score_from(pattern: &[char], text: &[char], lower: &[char], start: usize) -> Option<i64> {
+    let mut score = 0;
+    let mut matched = 0;
+    let mut previous: Option<usize> = None;
+    for index in start..lower.len() {
+        if matched == pattern.len() {
+            break;
+        }
+        if lower[index] != pattern[matched] {
+            continue;
+        }
+        score += 1;
+        let word_start = index == 0
+            || !text[index - 1].is_alphanumeric()
+            || (text[index].is_uppercase() && text[index - 1].is_lowercase());
+        if word_start {
+            score += 8;
+        }
+        match previous {
+            Some(previous) if previous + 1 == index => score += 12,
+            Some(previous) => score -= (index - previous - 1).min(10) as i64,
+            None => {}
+        }
+        previous = Some(index);
+        matched += 1;
+    }
+    (matched == pattern.len()).then_some(score)
+}
+
 /// RAII guard that removes a temporary directory when dropped.
 pub struct TempDirGuard {
     path: PathBuf,
@@ -58,10 +217,47 @@ impl Drop for TempDirGuard {
     }
 }
 
+/// `512 B`, `1.5 KB`, `12.3 MB`: binary units, one decimal above bytes.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_write_private_creates_only_new_files() {
+        let dir = std::env::temp_dir().join(format!("omakure-private-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("secret.key");
+        let _ = fs::remove_file(&path);
+
+        write_private(&path, b"secret\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "secret\n");
+        #[cfg(not(windows))]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o077, 0);
+        }
+        let err = write_private(&path, b"other\n").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "secret\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_ps_quote_simple() {
         assert_eq!(ps_quote("hello"), "'hello'");
@@ -76,4 +272,51 @@ mod tests {
     fn test_ps_quote_empty() {
         assert_eq!(ps_quote(""), "''");
     }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 * 1024), "5.0 GB");
+    }
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn test_shell_join_quotes_when_needed() {
+        let parts = vec![
+            "bash".to_string(),
+            "/tmp/run.sh".to_string(),
+            "hello world".to_string(),
+            "it's".to_string(),
+            String::new(),
+            "C:\\temp\\n".to_string(),
+        ];
+        assert_eq!(
+            shell_join(&parts),
+            "bash /tmp/run.sh 'hello world' 'it'\\''s' '' 'C:\\temp\\n'"
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_score_ranks_matches() {
+        assert!(fuzzy_score("rgcl", "rg-cleanup").is_some());
+        assert!(fuzzy_score("RGCL", "rg-cleanup").is_some());
+        assert_eq!(fuzzy_score("xyz", "rg-cleanup"), None);
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+
+        let exact = fuzzy_score("clean", "rg-cleanup").unwrap();
+        let scattered = fuzzy_score("clean", "copy-large-entries-and-notify").unwrap();
+        assert!(exact > scattered);
+
+        let word_starts = fuzzy_score("dbb", "db-backup").unwrap();
+        let inside = fuzzy_score("dbb", "oddbubble").unwrap();
+        assert!(word_starts > inside);
+    }
 }
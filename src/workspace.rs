@@ -1,7 +1,11 @@
+use crate::app_dirs::AppDirs;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 
+const SEARCH_DB_NAME: &str = "search-index.sqlite";
+const QUEUE_DIR_NAME: &str = "queue";
+
 pub struct Workspace {
     root: PathBuf,
     omaken_dir: PathBuf,
@@ -9,6 +13,9 @@ pub struct Workspace {
     config_path: PathBuf,
     envs_dir: PathBuf,
     envs_active_path: PathBuf,
+    /// Per-user folders for this workspace's caches and state.
+    cache_dir: PathBuf,
+    state_dir: PathBuf,
 }
 
 impl Workspace {
@@ -18,6 +25,11 @@ impl Workspace {
         let config_path = root.join("omakure.toml");
         let envs_dir = omaken_dir.join("envs");
         let envs_active_path = envs_dir.join("active");
+        let (cache_dir, state_dir) = match AppDirs::resolve() {
+            Some(dirs) => (dirs.workspace_cache(&root), dirs.workspace_state(&root)),
+            // Without a home directory, keep them in the workspace as before.
+            None => (history_dir.clone(), history_dir.clone()),
+        };
         Self {
             root,
             omaken_dir,
@@ -25,6 +37,20 @@ impl Workspace {
             config_path,
             envs_dir,
             envs_active_path,
+            cache_dir,
+            state_dir,
+        }
+    }
+
+    /// A workspace keeping its caches and state inside itself, so tests do not
+    /// touch the user's folders.
+    #[cfg(test)]
+    pub fn isolated(root: PathBuf) -> Self {
+        let workspace = Self::new(root);
+        Self {
+            cache_dir: workspace.history_dir.clone(),
+            state_dir: workspace.history_dir.clone(),
+            ..workspace
         }
     }
 
@@ -40,8 +66,69 @@ impl Workspace {
         &self.history_dir
     }
 
+    /// JSON-lines log of schema loads, runs, index updates, and updates.
+    pub fn log_path(&self) -> PathBuf {
+        self.history_dir.join("omakure.log")
+    }
+
+    pub fn artifacts_dir(&self) -> PathBuf {
+        self.history_dir.join("artifacts")
+    }
+
+    pub fn history_db_path(&self) -> PathBuf {
+        self.history_dir.join("history.sqlite")
+    }
+
+    /// Scripts pinned to the top of the TUI entries list.
+    pub fn favorites_path(&self) -> PathBuf {
+        self.history_dir.join("favorites.json")
+    }
+
+    /// Tickets of runs queued by concurrency group.
+    pub fn queue_dir(&self) -> PathBuf {
+        self.state_dir.join(QUEUE_DIR_NAME)
+    }
+
     pub fn search_db_path(&self) -> PathBuf {
-        self.history_dir.join("search-index.sqlite")
+        self.cache_dir.join(SEARCH_DB_NAME)
+    }
+
+    pub fn cache_dir(&self) -> &Path {
+        &self.cache_dir
+    }
+
+    pub fn state_dir(&self) -> &Path {
+        &self.state_dir
+    }
+
+    /// When the last digest was sent, as milliseconds since the epoch.
+    pub fn digest_state_path(&self) -> PathBuf {
+        self.state_dir.join("digest-last")
+    }
+
+    /// Digests of the scripts `omakure update` last synced from upstream.
+    pub fn sync_base_path(&self) -> PathBuf {
+        self.state_dir.join("sync-base.json")
+    }
+
+    /// Named hosts and groups that scripts with `Targets` can run on.
+    pub fn inventory_path(&self) -> PathBuf {
+        self.omaken_dir.join("inventory.toml")
+    }
+
+    /// Pipeline files run by `omakure pipeline run` and the TUI.
+    pub fn pipelines_dir(&self) -> PathBuf {
+        self.omaken_dir.join("pipelines")
+    }
+
+    /// Commits the installed Omaken flavors are pinned to.
+    pub fn omaken_lock_path(&self) -> PathBuf {
+        self.root.join("omaken.lock")
+    }
+
+    /// Script digests written by `omakure snapshot`.
+    pub fn snapshot_path(&self) -> PathBuf {
+        self.root.join("omakure.snapshot.json")
     }
 
     pub fn config_path(&self) -> &Path {
@@ -56,6 +143,28 @@ impl Workspace {
         &self.envs_active_path
     }
 
+    /// Moves state that older versions kept in `.history/` to the per-user
+    /// folders. The search index is a cache, so it is dropped and rebuilt.
+    fn migrate_legacy_state(&self) -> io::Result<()> {
+        let legacy_queue = self.history_dir.join(QUEUE_DIR_NAME);
+        if legacy_queue.is_dir() && legacy_queue != self.queue_dir() && !self.queue_dir().exists() {
+            fs::create_dir_all(&self.state_dir)?;
+            fs::rename(&legacy_queue, self.queue_dir())?;
+        }
+        let legacy_search_db = self.history_dir.join(SEARCH_DB_NAME);
+        if legacy_search_db != self.search_db_path() {
+            for suffix in ["", "-wal", "-shm"] {
+                let mut path = legacy_search_db.clone().into_os_string();
+                path.push(suffix);
+                match fs::remove_file(path) {
+                    Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn ensure_layout(&self) -> io::Result<()> {
         fs::create_dir_all(&self.root)?;
         fs::create_dir_all(&self.omaken_dir)?;
@@ -64,13 +173,27 @@ impl Workspace {
         if !self.config_path.exists() {
             fs::write(&self.config_path, default_config())?;
         }
+        // Best effort: leftovers only cost a rebuilt index or a stale queue.
+        let _ = self.migrate_legacy_state();
         Ok(())
     }
 }
 
 fn default_config() -> String {
     format!(
-        "# Omakure workspace configuration\n[workspace]\nversion = \"{}\"\n",
+        "# Omakure workspace configuration; `omakure doctor` checks it.\n\
+         [workspace]\n\
+         version = \"{}\"\n\
+         # name = \"ops\"\n\
+         \n\
+         # [runner]\n\
+         # timeout_seconds = 3600\n\
+         \n\
+         # [runner.interpreters]\n\
+         # python = \"python3.12\"\n\
+         \n\
+         # [history]\n\
+         # retention_days = 90\n",
         crate::app_meta::APP_VERSION
     )
 }
@@ -0,0 +1,207 @@
+use crate::theme_config;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The parts of the global `config.toml` naming workspaces:
+///
+/// ```toml
+/// default_workspace = "work"
+///
+/// [workspaces]
+/// work = "~/clients/acme/scripts"
+/// home = "~/Documents/omakure-scripts"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+struct ProfilesFile {
+    default_workspace: Option<String>,
+    #[serde(default)]
+    workspaces: BTreeMap<String, String>,
+}
+
+/// Named workspaces, with `~` expanded in their paths.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct Profiles {
+    /// Used when neither `--workspace`, `--scripts-dir`, nor
+    /// `OMAKURE_SCRIPTS_DIR` picks a workspace.
+    pub default: Option<String>,
+    pub workspaces: BTreeMap<String, PathBuf>,
+}
+
+impl Profiles {
+    pub fn resolve(&self, name: &str) -> Result<PathBuf, String> {
+        if let Some(path) = self.workspaces.get(name) {
+            return Ok(path.clone());
+        }
+        if self.workspaces.is_empty() {
+            return Err(format!(
+                "Unknown workspace '{}'; add one with omakure workspaces add",
+                name
+            ));
+        }
+        let names: Vec<&str> = self.workspaces.keys().map(String::as_str).collect();
+        Err(format!(
+            "Unknown workspace '{}'; known: {}",
+            name,
+            names.join(", ")
+        ))
+    }
+
+    pub fn default_dir(&self) -> Option<PathBuf> {
+        self.default
+            .as_deref()
+            .and_then(|name| self.workspaces.get(name))
+            .cloned()
+    }
+
+    /// The name of the workspace rooted at `dir`, if it has one.
+    pub fn name_of(&self, dir: &Path) -> Option<&str> {
+        self.workspaces
+            .iter()
+            .find(|(_, path)| path.as_path() == dir)
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+/// Named workspaces from the global config; none when it is missing or invalid.
+pub(crate) fn load() -> Profiles {
+    theme_config::theme_layout()
+        .and_then(|layout| read(&layout.config_path).ok())
+        .unwrap_or_default()
+}
+
+pub(crate) fn read(path: &Path) -> Result<Profiles, String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Profiles::default()),
+        Err(err) => return Err(format!("Failed to read {}: {}", path.display(), err)),
+    };
+    parse(&contents).map_err(|err| format!("Failed to parse {}: {}", path.display(), err))
+}
+
+fn parse(contents: &str) -> Result<Profiles, toml::de::Error> {
+    let file: ProfilesFile = toml::from_str(contents)?;
+    Ok(Profiles {
+        default: file.default_workspace,
+        workspaces: file
+            .workspaces
+            .into_iter()
+            .map(|(name, path)| (name, expand_home(&path)))
+            .collect(),
+    })
+}
+
+/// `~` and `~/...` relative to the home directory; other paths as given.
+fn expand_home(path: &str) -> PathBuf {
+    let rest = match path.strip_prefix('~') {
+        Some("") => "",
+        Some(rest) if rest.starts_with(['/', '\\']) => &rest[1..],
+        _ => return PathBuf::from(path),
+    };
+    match dirs::home_dir() {
+        Some(home) => home.join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+/// Adds or replaces the workspace `name`.
+pub(crate) fn add(config_path: &Path, name: &str, dir: &Path) -> Result<(), Box<dyn Error>> {
+    edit(config_path, |table| {
+        let workspaces = table
+            .entry("workspaces".to_string())
+            .or_insert_with(|| toml::Value::Table(toml::value::Table::new()));
+        if !workspaces.is_table() {
+            *workspaces = toml::Value::Table(toml::value::Table::new());
+        }
+        if let Some(workspaces) = workspaces.as_table_mut() {
+            workspaces.insert(
+                name.to_string(),
+                toml::Value::String(dir.to_string_lossy().to_string()),
+            );
+        }
+    })
+}
+
+/// Removes the workspace `name`, and unsets it as the default.
+pub(crate) fn remove(config_path: &Path, name: &str) -> Result<(), Box<dyn Error>> {
+    edit(config_path, |table| {
+        if let Some(workspaces) = table
+            .get_mut("workspaces")
+            .and_then(toml::Value::as_table_mut)
+        {
+            workspaces.remove(name);
+        }
+        if table.get("default_workspace").and_then(toml::Value::as_str) == Some(name) {
+            table.remove("default_workspace");
+        }
+    })
+}
+
+pub(crate) fn set_default(config_path: &Path, name: &str) -> Result<(), Box<dyn Error>> {
+    edit(config_path, |table| {
+        table.insert(
+            "default_workspace".to_string(),
+            toml::Value::String(name.to_string()),
+        );
+    })
+}
+
+/// Rewrites the global config with `change` applied to its top-level table.
+fn edit(
+    config_path: &Path,
+    change: impl FnOnce(&mut toml::value::Table),
+) -> Result<(), Box<dyn Error>> {
+    let mut value = if config_path.exists() {
+        toml::from_str::<toml::Value>(&fs::read_to_string(config_path)?)?
+    } else {
+        toml::Value::Table(toml::value::Table::new())
+    };
+    let table = value
+        .as_table_mut()
+        .ok_or_else(|| "Config root is not a table".to_string())?;
+    change(table);
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(config_path, toml::to_string_pretty(&value)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_resolve() {
+        let profiles = parse(
+            r#"
+            default_workspace = "work"
+
+            [theme]
+            name = "light"
+
+            [workspaces]
+            work = "/srv/acme/scripts"
+            home = "~/scripts"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            profiles.default_dir(),
+            Some(PathBuf::from("/srv/acme/scripts"))
+        );
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(profiles.resolve("home"), Ok(home.join("scripts")));
+        }
+        assert_eq!(
+            profiles.name_of(Path::new("/srv/acme/scripts")),
+            Some("work")
+        );
+        assert_eq!(
+            profiles.resolve("client"),
+            Err("Unknown workspace 'client'; known: home, work".to_string())
+        );
+    }
+}
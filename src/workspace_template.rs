@@ -0,0 +1,171 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Manifest at the root of a template repository; never copied.
+pub const MANIFEST_FILE: &str = "omakure-template.toml";
+/// Variable every template gets: the new workspace's folder name.
+pub const WORKSPACE_VARIABLE: &str = "workspace";
+
+/// `omakure-template.toml`: the variables to ask for, in order.
+#[derive(Debug, Default, Deserialize)]
+pub struct Manifest {
+    pub description: Option<String>,
+    #[serde(default, rename = "variable")]
+    pub variables: Vec<Variable>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Variable {
+    pub name: String,
+    pub prompt: Option<String>,
+    /// Used when nothing is entered; without one, a value is required.
+    pub default: Option<String>,
+}
+
+impl Variable {
+    pub fn label(&self) -> &str {
+        self.prompt.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// Reads the template's manifest; a template without one has no variables.
+pub fn load_manifest(template: &Path) -> Result<Manifest, String> {
+    let path = template.join(MANIFEST_FILE);
+    if !path.is_file() {
+        return Ok(Manifest::default());
+    }
+    let contents = fs::read_to_string(&path).map_err(|err| err.to_string())?;
+    toml::from_str(&contents).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+/// Replaces `{{name}}` (spaces inside the braces allowed) for each variable.
+///
+/// Placeholders naming no variable stay as they are, so Helm charts and
+/// other templating in the scripts survive.
+pub fn render(text: &str, values: &BTreeMap<String, String>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let value = after
+            .find("}}")
+            .and_then(|end| values.get(after[..end].trim()).map(|value| (end, value)));
+        match value {
+            Some((end, value)) => {
+                out.push_str(value);
+                rest = &after[end + 2..];
+            }
+            None => {
+                out.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Copies `template` into `target`, rendering file names and text files.
+///
+/// Skips `.git` and the manifest; binary files are copied as they are.
+/// Returns the files written, relative to `target`.
+pub fn instantiate(
+    template: &Path,
+    target: &Path,
+    values: &BTreeMap<String, String>,
+) -> io::Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    copy_dir(template, target, Path::new(""), values, &mut written)?;
+    written.sort();
+    Ok(written)
+}
+
+fn copy_dir(
+    source: &Path,
+    target: &Path,
+    relative: &Path,
+    values: &BTreeMap<String, String>,
+    written: &mut Vec<PathBuf>,
+) -> io::Result<()> {
+    fs::create_dir_all(target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name == ".git" || (relative.as_os_str().is_empty() && name == MANIFEST_FILE) {
+            continue;
+        }
+        let name = render(&name, values);
+        let to = target.join(&name);
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &to, &relative.join(&name), values, written)?;
+        } else {
+            write_file(&entry.path(), &to, values)?;
+            written.push(relative.join(&name));
+        }
+    }
+    Ok(())
+}
+
+fn write_file(from: &Path, to: &Path, values: &BTreeMap<String, String>) -> io::Result<()> {
+    let bytes = fs::read(from)?;
+    match String::from_utf8(bytes) {
+        Ok(text) => fs::write(to, render(&text, values))?,
+        Err(err) => fs::write(to, err.into_bytes())?,
+    }
+    // Keeps scripts executable.
+    fs::set_permissions(to, fs::metadata(from)?.permissions())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_and_instantiate() {
+        let mut values = BTreeMap::new();
+        values.insert("team".to_string(), "payments".to_string());
+        assert_eq!(
+            render(
+                "name = \"{{team}}\" # {{ team }}, {{ .Values.x }} {{",
+                &values
+            ),
+            "name = \"payments\" # payments, {{ .Values.x }} {{"
+        );
+
+        let root = std::env::temp_dir().join(format!("omakure-template-{}", std::process::id()));
+        let template = root.join("template");
+        let target = root.join("target");
+        fs::create_dir_all(template.join(".git")).unwrap();
+        fs::create_dir_all(template.join("{{team}}")).unwrap();
+        fs::write(template.join(".git/HEAD"), "ref").unwrap();
+        fs::write(
+            template.join(MANIFEST_FILE),
+            "[[variable]]\nname = \"team\"\nprompt = \"Team\"\n",
+        )
+        .unwrap();
+        fs::write(template.join("README.md"), "# {{team}} scripts\n").unwrap();
+        fs::write(template.join("{{team}}/deploy.bash"), "echo {{team}}\n").unwrap();
+
+        let manifest = load_manifest(&template).unwrap();
+        assert_eq!(manifest.variables[0].label(), "Team");
+        let written = instantiate(&template, &target, &values).unwrap();
+        assert_eq!(
+            written,
+            vec![
+                PathBuf::from("README.md"),
+                PathBuf::from("payments").join("deploy.bash"),
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(target.join("payments/deploy.bash")).unwrap(),
+            "echo payments\n"
+        );
+        assert!(!target.join(".git").exists());
+        assert!(!target.join(MANIFEST_FILE).exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+}